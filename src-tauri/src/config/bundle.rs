@@ -0,0 +1,354 @@
+//! Export/import of launcher settings as a single JSON bundle, without
+//! accounts or tokens.
+
+use super::{read_config, write_config, ConfigManager};
+use crate::core::bandwidth;
+use crate::core::http_client;
+use crate::core::instance_manager::{get_all_instances, update_instance};
+use crate::core::minecraft_instance::MinecraftInstance;
+use crate::core::mirrors;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+
+/// Versión del formato del bundle; se incrementa con cambios incompatibles
+const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+/// Fragmentos que delatan secretos en nombres de variables de entorno
+const SECRET_MARKERS: &[&str] = &["TOKEN", "SECRET", "PASSWORD", "PASSWD", "API_KEY", "AUTH"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceMetadata {
+    pub instance_id: String,
+    pub instance_name: String,
+    pub minecraft_version: String,
+    pub forge_version: Option<String>,
+    pub modpack_id: Option<String>,
+    pub memory: Option<u32>,
+    pub wrapper_command: Option<String>,
+    pub env_vars: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsBundle {
+    pub format_version: u32,
+    pub launcher_version: String,
+    pub exported_at: String,
+    pub settings: HashMap<String, Value>,
+    #[serde(default)]
+    pub instances: Vec<InstanceMetadata>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum MergeStrategy {
+    /// Bundle values replace local ones.
+    Overwrite,
+    /// Only settings still at their default value are taken from the bundle.
+    KeepLocal,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsImportReport {
+    pub applied: Vec<String>,
+    pub kept_local: Vec<String>,
+    /// Ajustes rechazados (clave, motivo), p. ej. rutas que no existen aquí
+    pub rejected: Vec<(String, String)>,
+    pub updated_instances: Vec<String>,
+    /// Instancias del bundle que no existen en este equipo
+    pub missing_instances: Vec<InstanceMetadata>,
+}
+
+impl ConfigManager {
+    /// Settings that can leave this machine (everything but `internal` and
+    /// secrets, which are encrypted for this device anyway).
+    fn exportable_values(&self) -> HashMap<String, Value> {
+        self.values
+            .iter()
+            .filter(|(key, _)| {
+                self.schema
+                    .get_config_definition(key)
+                    .map_or(false, |def| def.ui_section != "internal" && !def.secret)
+            })
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+}
+
+fn is_secret_env_var(name: &str) -> bool {
+    let upper = name.to_uppercase();
+    SECRET_MARKERS.iter().any(|marker| upper.contains(marker))
+}
+
+fn without_secrets(env_vars: &Option<HashMap<String, String>>) -> Option<HashMap<String, String>> {
+    env_vars.as_ref().map(|vars| {
+        vars.iter()
+            .filter(|(key, _)| !is_secret_env_var(key))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    })
+}
+
+/// Local instance `metadata` applies to. The `instanceId` only matches on
+/// the machine that exported the bundle, so instances are also matched by
+/// modpack and then by name. `taken` holds instances already matched by an
+/// earlier entry of the bundle.
+fn find_local_instance<'a>(
+    local_instances: &'a [MinecraftInstance],
+    metadata: &InstanceMetadata,
+    taken: &HashSet<String>,
+) -> Option<&'a MinecraftInstance> {
+    let available = || {
+        local_instances
+            .iter()
+            .filter(|instance| !taken.contains(&instance.instanceId))
+    };
+
+    if let Some(instance) = available().find(|i| i.instanceId == metadata.instance_id) {
+        return Some(instance);
+    }
+    if let Some(modpack_id) = &metadata.modpack_id {
+        let same_modpack: Vec<&MinecraftInstance> = available()
+            .filter(|i| i.modpackId.as_ref() == Some(modpack_id))
+            .collect();
+        // Con varias instancias del mismo modpack decide el nombre
+        if let Some(instance) = same_modpack
+            .iter()
+            .find(|i| i.instanceName == metadata.instance_name)
+            .or(same_modpack.first())
+        {
+            return Some(*instance);
+        }
+    }
+    available().find(|i| i.modpackId.is_none() && i.instanceName == metadata.instance_name)
+}
+
+pub fn build_bundle() -> Result<SettingsBundle, String> {
+    let settings = {
+        let config_manager = read_config();
+        let config = config_manager.as_ref().map_err(|e| e.clone())?;
+        config.exportable_values()
+    };
+
+    let instances = get_all_instances()?
+        .into_iter()
+        .map(|instance| InstanceMetadata {
+            env_vars: without_secrets(&instance.envVars),
+            instance_id: instance.instanceId,
+            instance_name: instance.instanceName,
+            minecraft_version: instance.minecraftVersion,
+            forge_version: instance.forgeVersion,
+            modpack_id: instance.modpackId,
+            memory: instance.memory,
+            wrapper_command: instance.wrapperCommand,
+        })
+        .collect();
+
+    Ok(SettingsBundle {
+        format_version: BUNDLE_FORMAT_VERSION,
+        launcher_version: env!("CARGO_PKG_VERSION").to_string(),
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        settings,
+        instances,
+    })
+}
+
+pub fn apply_bundle(
+    bundle: SettingsBundle,
+    strategy: MergeStrategy,
+) -> Result<SettingsImportReport, String> {
+    if bundle.format_version > BUNDLE_FORMAT_VERSION {
+        return Err(format!(
+            "El archivo fue exportado con una versión más reciente del launcher ({}). Actualiza Modpack Store para importarlo.",
+            bundle.launcher_version
+        ));
+    }
+
+    let mut report = SettingsImportReport::default();
+
+    {
+        let mut config_manager = write_config();
+        let config = config_manager.as_mut().map_err(|e| e.clone())?;
+        let defaults = config.schema.get_default_values();
+
+        for (key, value) in bundle.settings {
+            let internal = config
+                .schema
+                .get_config_definition(&key)
+                .map_or(true, |def| def.ui_section == "internal" || def.secret);
+            if internal {
+                report
+                    .rejected
+                    .push((key, "Ajuste desconocido o interno".to_string()));
+                continue;
+            }
+
+            if strategy == MergeStrategy::KeepLocal && config.get(&key) != defaults.get(&key) {
+                report.kept_local.push(key);
+                continue;
+            }
+
+            match config.set(&key, value) {
+                Ok(()) => report.applied.push(key),
+                Err(e) => report.rejected.push((key, e.to_string())),
+            }
+        }
+
+        config.save()?;
+        http_client::reload(config);
+        mirrors::reload(config);
+        bandwidth::reload(config);
+    }
+
+    let local_instances = get_all_instances()?;
+    let mut taken = HashSet::new();
+    for metadata in bundle.instances {
+        let mut instance = match find_local_instance(&local_instances, &metadata, &taken) {
+            Some(instance) => instance.clone(),
+            None => {
+                report.missing_instances.push(metadata);
+                continue;
+            }
+        };
+        taken.insert(instance.instanceId.clone());
+
+        if strategy == MergeStrategy::KeepLocal {
+            // Sólo rellenamos lo que no está configurado localmente
+            instance.memory = instance.memory.or(metadata.memory);
+            instance.wrapperCommand = instance.wrapperCommand.or(metadata.wrapper_command);
+            instance.envVars = instance.envVars.or(metadata.env_vars);
+        } else {
+            instance.instanceName = metadata.instance_name;
+            instance.memory = metadata.memory;
+            instance.wrapperCommand = metadata.wrapper_command;
+            instance.envVars = metadata.env_vars;
+        }
+
+        let instance_id = instance.instanceId.clone();
+        match update_instance(instance) {
+            Ok(()) => report.updated_instances.push(instance_id),
+            Err(e) => log::warn!("[SettingsBundle] Could not update {}: {}", instance_id, e),
+        }
+    }
+
+    log::info!(
+        "[SettingsBundle] Imported {} settings ({} kept, {} rejected), {} instances updated",
+        report.applied.len(),
+        report.kept_local.len(),
+        report.rejected.len(),
+        report.updated_instances.len()
+    );
+
+    Ok(report)
+}
+
+#[tauri::command]
+pub fn export_settings_bundle(path: String) -> Result<(), String> {
+    let bundle = build_bundle()?;
+    let content = serde_json::to_string_pretty(&bundle)
+        .map_err(|e| format!("Error al serializar la configuración: {}", e))?;
+    fs::write(&path, content).map_err(|e| format!("Error al escribir {}: {}", path, e))
+}
+
+#[tauri::command]
+pub fn import_settings_bundle(
+    path: String,
+    strategy: MergeStrategy,
+) -> Result<SettingsImportReport, String> {
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Error al leer {}: {}", path, e))?;
+    let bundle: SettingsBundle = serde_json::from_str(&content)
+        .map_err(|e| format!("El archivo no es una exportación válida: {}", e))?;
+
+    apply_bundle(bundle, strategy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(id: &str, name: &str, modpack_id: Option<&str>) -> MinecraftInstance {
+        let mut instance = MinecraftInstance::new();
+        instance.instanceId = id.to_string();
+        instance.instanceName = name.to_string();
+        instance.modpackId = modpack_id.map(str::to_string);
+        instance
+    }
+
+    fn metadata(id: &str, name: &str, modpack_id: Option<&str>) -> InstanceMetadata {
+        InstanceMetadata {
+            instance_id: id.to_string(),
+            instance_name: name.to_string(),
+            minecraft_version: "1.20.1".to_string(),
+            forge_version: None,
+            modpack_id: modpack_id.map(str::to_string),
+            memory: None,
+            wrapper_command: None,
+            env_vars: None,
+        }
+    }
+
+    fn matched<'a>(
+        local_instances: &'a [MinecraftInstance],
+        metadata: &InstanceMetadata,
+    ) -> Option<&'a str> {
+        find_local_instance(local_instances, metadata, &HashSet::new())
+            .map(|instance| instance.instanceId.as_str())
+    }
+
+    #[test]
+    fn matches_by_modpack_across_machines() {
+        let instances = [
+            local("a", "Vanilla", None),
+            local("b", "Renamed pack", Some("pack-1")),
+        ];
+        let entry = metadata("other-machine", "My pack", Some("pack-1"));
+        assert_eq!(matched(&instances, &entry), Some("b"));
+    }
+
+    #[test]
+    fn prefers_the_name_among_instances_of_the_same_modpack() {
+        let instances = [
+            local("a", "Pack (copy)", Some("pack-1")),
+            local("b", "Pack", Some("pack-1")),
+        ];
+        let entry = metadata("other-machine", "Pack", Some("pack-1"));
+        assert_eq!(matched(&instances, &entry), Some("b"));
+    }
+
+    #[test]
+    fn matches_plain_instances_by_name() {
+        let instances = [
+            local("a", "Survival", Some("pack-1")),
+            local("b", "Survival", None),
+        ];
+        let entry = metadata("other-machine", "Survival", None);
+        assert_eq!(matched(&instances, &entry), Some("b"));
+        assert_eq!(
+            matched(&instances, &metadata("other-machine", "Creative", None)),
+            None
+        );
+    }
+
+    #[test]
+    fn the_same_machine_matches_by_id() {
+        let instances = [
+            local("a", "Pack", Some("pack-1")),
+            local("b", "Pack", Some("pack-1")),
+        ];
+        let entry = metadata("b", "Pack", Some("pack-1"));
+        assert_eq!(matched(&instances, &entry), Some("b"));
+    }
+
+    #[test]
+    fn each_local_instance_is_matched_once() {
+        let instances = [local("a", "Pack", Some("pack-1"))];
+        let entry = metadata("other-machine", "Pack", Some("pack-1"));
+        let taken = HashSet::from(["a".to_string()]);
+        assert!(find_local_instance(&instances, &entry, &taken).is_none());
+    }
+}
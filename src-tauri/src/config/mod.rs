@@ -1,15 +1,23 @@
+pub mod bundle;
 pub mod schema;
+pub mod secrets;
 pub mod validation;
 
+use crate::core::bandwidth;
+use crate::core::http_client;
+use crate::core::minecraft::GameResolution;
+use crate::core::mirrors;
 use once_cell::sync::OnceCell;
-use schema::{ConfigSchema, ConfigValue, ConfigValueType};
+use schema::{ConfigSchema, ConfigValue, ConfigValueType, SettingsSectionLayout};
+use secrets::SECRET_MASK;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::{
     collections::HashMap,
     fs::{self, create_dir_all, read_to_string, write},
     path::{Path, PathBuf},
-    sync::Mutex,
+    sync::{RwLock, RwLockReadGuard, RwLockWriteGuard},
+    time::Duration,
 };
 use validation::{validate_config_value, ValidationError};
 
@@ -58,11 +66,24 @@ impl ConfigManager {
             json_values
         };
 
-        Ok(Self {
+        // Las claves nuevas del esquema toman su valor predeterminado
+        let mut values = extract_values_map(values);
+        for (key, default) in schema.get_default_values() {
+            values.entry(key).or_insert(default);
+        }
+
+        let mut manager = Self {
             config_path,
             schema,
-            values: extract_values_map(values),
-        })
+            values,
+        };
+        // Secretos guardados en claro por versiones anteriores
+        if manager.seal_plain_secrets() {
+            if let Err(e) = manager.save() {
+                log::warn!("[Config] Could not save encrypted secrets: {}", e);
+            }
+        }
+        Ok(manager)
     }
 
     /// Guarda la configuración actual en disco
@@ -84,7 +105,17 @@ impl ConfigManager {
 
         // Validar el valor contra el esquema
         if let Some(config_def) = self.schema.get_config_definition(key) {
+            // La UI devuelve la máscara si el secreto no se modificó
+            if config_def.secret && value_json.as_str() == Some(SECRET_MASK) {
+                return Ok(());
+            }
+
             validate_config_value(key, &value_json, config_def)?;
+            let value_json = if config_def.secret {
+                Self::seal_secret(value_json).map_err(ValidationError::Other)?
+            } else {
+                value_json
+            };
 
             // Si la validación pasa, actualizar el valor
             self.values.insert(key.to_string(), value_json);
@@ -112,9 +143,10 @@ impl ConfigManager {
             .and_then(|v| serde_json::from_value(v.clone()).ok())
     }
 
-    /// Obtiene una representación JSON de toda la configuración
+    /// Obtiene una representación JSON de toda la configuración, con los
+    /// secretos enmascarados
     pub fn get_all_json(&self) -> Value {
-        json!(self.values)
+        json!(self.masked_values())
     }
 
     /// Obtiene el esquema de configuración
@@ -137,6 +169,38 @@ impl ConfigManager {
             .unwrap_or(default)
     }
 
+    /// Carpetas donde hay instancias: la principal primero y después las
+    /// adicionales, sin repetir
+    pub fn get_instance_roots(&self) -> Vec<PathBuf> {
+        let mut roots = vec![self.get_instances_dir()];
+        for extra in self
+            .get_typed::<Vec<String>>("extraInstancesDirs")
+            .unwrap_or_default()
+        {
+            let extra = extra.trim();
+            if extra.is_empty() {
+                continue;
+            }
+            let path = expand_path(extra);
+            if !roots.contains(&path) {
+                roots.push(path);
+            }
+        }
+        roots
+    }
+
+    /// Carpetas de instancias instaladas fuera de las ubicaciones de
+    /// instancias (ver `instance_locations`)
+    pub fn get_instance_locations(&self) -> Vec<PathBuf> {
+        self.get_typed::<Vec<String>>("instanceLocations")
+            .unwrap_or_default()
+            .iter()
+            .map(|location| location.trim())
+            .filter(|location| !location.is_empty())
+            .map(PathBuf::from)
+            .collect()
+    }
+
     /// Obtiene el directorio de Java
     pub fn get_java_dir(&self) -> Option<PathBuf> {
         let default = std::env::var("JAVA_HOME").unwrap_or_else(|_| "java".to_string());
@@ -154,6 +218,28 @@ impl ConfigManager {
             .unwrap_or(true)
     }
 
+    /// Canal del que se descargan las actualizaciones del launcher
+    pub fn get_release_channel(&self) -> String {
+        self.get("releaseChannel")
+            .and_then(Value::as_str)
+            .unwrap_or("canary")
+            .to_string()
+    }
+
+    /// Horas entre comprobaciones de actualizaciones; 0 sólo comprueba al iniciar
+    pub fn get_update_check_interval_hours(&self) -> u64 {
+        self.get("updateCheckIntervalHours")
+            .and_then(Value::as_u64)
+            .unwrap_or(6)
+    }
+
+    /// Borrar al arrancar los runtimes de Java que ninguna instancia usa
+    pub fn get_auto_cleanup_java(&self) -> bool {
+        self.get("autoCleanupJava")
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+    }
+
     /// Comprobar si se debe cerrar el launcher al iniciar Minecraft
     pub fn get_close_on_launch(&self) -> bool {
         self.get("closeOnLaunch")
@@ -161,11 +247,236 @@ impl ConfigManager {
             .unwrap_or(false)
     }
 
+    /// Lanzar con una cuenta offline si la cuenta de la instancia no es válida
+    pub fn get_offline_fallback(&self) -> bool {
+        self.get("offlineFallback")
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+    }
+
+    /// Iniciar Minecraft con prioridad alta (Windows)
+    pub fn get_high_priority_game(&self) -> bool {
+        self.get("highPriorityGame")
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+    }
+
+    /// Memoria global; `None` cuando está en modo automático
     pub fn get_minecraft_memory(&self) -> Option<u32> {
+        if self.is_auto_memory() {
+            return None;
+        }
+
         self.get("memory")
             .and_then(Value::as_u64)
             .map(|v| v as u32)
     }
+
+    pub fn is_auto_memory(&self) -> bool {
+        self.get("autoMemory")
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+    }
+
+    pub fn get_language(&self) -> String {
+        self.get("language")
+            .and_then(Value::as_str)
+            .unwrap_or("es")
+            .to_string()
+    }
+
+    /// Idioma del juego elegido por el usuario; `None` si no hay ninguno
+    pub fn get_game_language(&self) -> Option<String> {
+        self.get("gameLanguage")
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+    }
+
+    /// Número máximo de descargas en paralelo
+    pub fn get_download_concurrency(&self) -> usize {
+        self.get("downloadConcurrency")
+            .and_then(Value::as_u64)
+            .map(|v| v.clamp(1, 32) as usize)
+            .unwrap_or(8)
+    }
+
+    /// Número máximo de tareas pesadas (crear/importar instancias) a la vez
+    pub fn get_max_concurrent_tasks(&self) -> usize {
+        self.get("maxConcurrentTasks")
+            .and_then(Value::as_u64)
+            .map(|v| v.clamp(1, 8) as usize)
+            .unwrap_or(2)
+    }
+
+    /// Ejecutar instaladores y processors de Forge con prioridad baja (Windows)
+    pub fn get_low_priority_background_tasks(&self) -> bool {
+        self.get("lowPriorityBackgroundTasks")
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+    }
+
+    /// Tamaño a partir del cual se descarga por fragmentos (MB); `None` si está desactivado
+    pub fn get_chunked_download_threshold_mb(&self) -> Option<u64> {
+        let enabled = self
+            .get("chunkedDownloads")
+            .and_then(Value::as_bool)
+            .unwrap_or(true);
+        enabled.then(|| {
+            self.get("chunkedDownloadThreshold")
+                .and_then(Value::as_u64)
+                .map(|v| v.max(1))
+                .unwrap_or(32)
+        })
+    }
+
+    /// Conexiones simultáneas por archivo en descargas por fragmentos
+    pub fn get_chunked_download_connections(&self) -> usize {
+        self.get("chunkedDownloadConnections")
+            .and_then(Value::as_u64)
+            .map(|v| v.clamp(2, 16) as usize)
+            .unwrap_or(4)
+    }
+
+    /// Tamaño máximo de la caché de descargas en MB (0 = desactivada)
+    pub fn get_download_cache_size_mb(&self) -> u64 {
+        self.get("downloadCacheSize")
+            .and_then(Value::as_u64)
+            .unwrap_or(2048)
+    }
+
+    /// Límite de descarga en KB/s; `None` si no hay límite
+    pub fn get_bandwidth_limit_kbps(&self) -> Option<u64> {
+        self.get("bandwidthLimit")
+            .and_then(Value::as_u64)
+            .filter(|limit| *limit > 0)
+    }
+
+    /// Mirror de las descargas de Mojang y Forge: official, bmclapi o custom
+    pub fn get_download_mirror(&self) -> String {
+        self.get("downloadMirror")
+            .and_then(Value::as_str)
+            .unwrap_or("official")
+            .to_string()
+    }
+
+    /// Raíz personalizada de un mirror (`mirrorMetaUrl`...); `None` si está vacía
+    pub fn get_mirror_url(&self, key: &str) -> Option<String> {
+        self.get(key)
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+            .map(String::from)
+    }
+
+    /// "system", "manual" o "none"
+    pub fn get_proxy_mode(&self) -> String {
+        self.get("proxyMode")
+            .and_then(Value::as_str)
+            .unwrap_or("system")
+            .to_string()
+    }
+
+    /// URL del proxy manual; `http://` si el host no indica esquema
+    pub fn get_proxy_url(&self) -> Option<String> {
+        let host = self
+            .get("proxyHost")
+            .and_then(Value::as_str)
+            .map(|host| host.trim().trim_end_matches('/'))
+            .filter(|host| !host.is_empty())?;
+        let port = self.get("proxyPort").and_then(Value::as_u64)?;
+
+        Some(if host.contains("://") {
+            format!("{}:{}", host, port)
+        } else {
+            format!("http://{}:{}", host, port)
+        })
+    }
+
+    /// Usuario y contraseña del proxy; `None` si no hay usuario
+    pub fn get_proxy_credentials(&self) -> Option<(String, String)> {
+        let username = self
+            .get("proxyUsername")
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|s| !s.is_empty())?;
+        let password = self.get_secret("proxyPassword").unwrap_or_default();
+        Some((username.to_string(), password))
+    }
+
+    /// Archivo con certificados raíz adicionales
+    pub fn get_ca_bundle_path(&self) -> Option<PathBuf> {
+        self.get("caBundlePath")
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(expand_path)
+    }
+
+    pub fn is_telemetry_enabled(&self) -> bool {
+        self.get("telemetryEnabled")
+            .and_then(Value::as_bool)
+            .unwrap_or(true)
+    }
+
+    /// Tiempo máximo para el instalador de Forge
+    pub fn get_forge_installer_timeout(&self) -> Duration {
+        let seconds = self
+            .get("forgeInstallerTimeout")
+            .and_then(Value::as_u64)
+            .unwrap_or(600);
+        Duration::from_secs(seconds)
+    }
+
+    /// Mover nativos y temporales de la JVM a una carpeta ASCII si el temporal del sistema no lo es
+    pub fn get_relocate_non_ascii_paths(&self) -> bool {
+        self.get("relocateNonAsciiPaths")
+            .and_then(Value::as_bool)
+            .unwrap_or(true)
+    }
+
+    /// Tiempo sin salida del juego antes de avisar de un arranque colgado; `None` si está desactivado
+    pub fn get_startup_watchdog_timeout(&self) -> Option<Duration> {
+        let seconds = self
+            .get("startupWatchdogTimeout")
+            .and_then(Value::as_u64)
+            .unwrap_or(120);
+        (seconds > 0).then(|| Duration::from_secs(seconds))
+    }
+
+    /// Resolución por defecto de la ventana del juego (0 = la del juego)
+    pub fn get_game_resolution(&self) -> GameResolution {
+        let dimension = |key: &str| {
+            self.get(key)
+                .and_then(Value::as_u64)
+                .filter(|value| *value > 0)
+                .map(|value| value as u32)
+        };
+
+        GameResolution {
+            width: dimension("gameWidth"),
+            height: dimension("gameHeight"),
+            fullscreen: self
+                .get("gameFullscreen")
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+        }
+    }
+
+    /// Variables de entorno globales en formato `CLAVE=valor`
+    pub fn get_env_vars(&self) -> Vec<String> {
+        self.get_typed::<Vec<String>>("envVars").unwrap_or_default()
+    }
+
+    /// Comando envoltorio global; `None` si está vacío
+    pub fn get_wrapper_command(&self) -> Option<String> {
+        self.get("wrapperCommand")
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+    }
 }
 
 // Convierte un Value en un HashMap
@@ -198,52 +509,78 @@ fn expand_path(path: &str) -> PathBuf {
 }
 
 // Singleton para acceder globalmente al ConfigManager
-static INSTANCE: OnceCell<Mutex<Result<ConfigManager, String>>> = OnceCell::new();
+static INSTANCE: OnceCell<RwLock<Result<ConfigManager, String>>> = OnceCell::new();
 
-/// Obtiene el singleton de ConfigManager
-pub fn get_config_manager() -> &'static Mutex<Result<ConfigManager, String>> {
-    INSTANCE.get_or_init(|| Mutex::new(ConfigManager::new()))
+/// Obtiene el singleton de ConfigManager. Lanzamientos, descargas y comandos
+/// lo leen a la vez; sólo los cambios toman el lock de escritura.
+fn get_config_manager() -> &'static RwLock<Result<ConfigManager, String>> {
+    INSTANCE.get_or_init(|| RwLock::new(ConfigManager::new()))
+}
+
+/// Shared access to the config. A thread that panicked while holding the
+/// lock doesn't leave the config unusable: the values are still valid.
+///
+/// Must not be held while calling code that takes the lock again.
+pub fn read_config() -> RwLockReadGuard<'static, Result<ConfigManager, String>> {
+    get_config_manager()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+/// Exclusive access to the config, to change and save it.
+pub fn write_config() -> RwLockWriteGuard<'static, Result<ConfigManager, String>> {
+    get_config_manager()
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+/// Runs `f` with the loaded config; fails only if it couldn't be loaded.
+pub fn with_config<T>(f: impl FnOnce(&ConfigManager) -> T) -> Result<T, String> {
+    let guard = read_config();
+    let config = guard.as_ref().map_err(|e| e.clone())?;
+    Ok(f(config))
 }
 
 // Comandos para la API de Tauri
 
 #[tauri::command]
 pub fn get_config() -> Result<Value, String> {
-    match get_config_manager().lock() {
-        Ok(config_result) => match &*config_result {
-            Ok(config) => Ok(config.get_all_json()),
-            Err(e) => Err(e.clone()),
-        },
-        Err(_) => Err("Error al obtener el bloqueo del gestor de configuración".to_string()),
-    }
+    with_config(|config| config.get_all_json())
 }
 
 #[tauri::command]
 pub fn set_config(key: String, value: Value) -> Result<(), String> {
-    match get_config_manager().lock() {
-        Ok(mut config_result) => match &mut *config_result {
-            Ok(config) => match config.set(&key, value) {
-                Ok(_) => {
-                    if let Err(e) = config.save() {
-                        return Err(format!("Error al guardar la configuración: {}", e));
-                    }
-                    Ok(())
+    let mut config_result = write_config();
+    match &mut *config_result {
+        Ok(config) => match config.set(&key, value) {
+            Ok(_) => {
+                if let Err(e) = config.save() {
+                    return Err(format!("Error al guardar la configuración: {}", e));
+                }
+                if http_client::HTTP_CONFIG_KEYS.contains(&key.as_str()) {
+                    http_client::reload(config);
                 }
-                Err(e) => Err(format!("Error de validación: {}", e)),
-            },
-            Err(e) => Err(e.clone()),
+                if mirrors::MIRROR_CONFIG_KEYS.contains(&key.as_str()) {
+                    mirrors::reload(config);
+                }
+                if bandwidth::BANDWIDTH_CONFIG_KEYS.contains(&key.as_str()) {
+                    bandwidth::reload(config);
+                }
+                Ok(())
+            }
+            Err(e) => Err(format!("Error de validación: {}", e)),
         },
-        Err(_) => Err("Error al obtener el bloqueo del gestor de configuración".to_string()),
+        Err(e) => Err(e.clone()),
     }
 }
 
 #[tauri::command]
 pub fn get_schema() -> Result<Value, String> {
-    match get_config_manager().lock() {
-        Ok(config_result) => match &*config_result {
-            Ok(config) => Ok(json!(config.get_schema())),
-            Err(e) => Err(e.clone()),
-        },
-        Err(_) => Err("Error al obtener el bloqueo del gestor de configuración".to_string()),
-    }
+    with_config(|config| json!(config.get_schema()))
+}
+
+/// Secciones y ajustes ordenados, con su valor actual, para generar la UI
+#[tauri::command]
+pub fn get_settings_layout() -> Result<Vec<SettingsSectionLayout>, String> {
+    with_config(|config| config.get_schema().build_layout(&config.masked_values()))
 }
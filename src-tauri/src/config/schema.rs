@@ -33,15 +33,75 @@ pub struct ConfigValue {
     pub choices: Option<Vec<Value>>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub validator: Option<String>,
+    /// Nombre corto para mostrar en la UI
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Unidad de los valores numéricos (MB, s, KB/s...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub step: Option<Value>,
+    /// Texto a mostrar para cada opción de un enum
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub choice_labels: Option<HashMap<String, String>>,
+    /// El ajuste sólo aplica cuando otro ajuste tiene cierto valor
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub depends_on: Option<ConfigDependency>,
+    /// Posición dentro de su sección
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order: Option<u32>,
+    /// Se guarda cifrado y el frontend sólo recibe una máscara
+    #[serde(default)]
+    pub secret: bool,
+}
+
+/// Condición `key == equals` de la que depende un ajuste
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigDependency {
+    pub key: String,
+    pub equals: Value,
+}
+
+/// Agrupación de ajustes en la UI
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigSection {
+    pub id: String,
+    pub label: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub order: u32,
 }
 
 /// Define el esquema completo de configuración
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConfigSchema {
+    #[serde(default)]
+    pub sections: Vec<ConfigSection>,
     #[serde(flatten)]
     pub definitions: HashMap<String, ConfigValue>,
 }
 
+/// Ajuste listo para que la UI genere su control
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingDescriptor {
+    pub key: String,
+    pub definition: ConfigValue,
+    pub value: Value,
+    /// `false` si su dependencia no se cumple (control deshabilitado)
+    pub active: bool,
+}
+
+/// Sección con sus ajustes ordenados
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsSectionLayout {
+    #[serde(flatten)]
+    pub section: ConfigSection,
+    pub settings: Vec<SettingDescriptor>,
+}
+
 impl ConfigSchema {
     /// Carga el esquema desde un archivo YAML incrustado
     pub fn load_from_embedded() -> Result<Self, String> {
@@ -94,6 +154,73 @@ impl ConfigSchema {
         sections.dedup();
         sections
     }
+
+    /// Indica si la dependencia de un ajuste se cumple con los valores actuales
+    pub fn is_setting_active(&self, key: &str, values: &HashMap<String, Value>) -> bool {
+        match self
+            .definitions
+            .get(key)
+            .and_then(|def| def.depends_on.as_ref())
+        {
+            Some(dependency) => {
+                let current = values.get(&dependency.key).cloned().or_else(|| {
+                    self.definitions
+                        .get(&dependency.key)
+                        .map(|def| process_default_value(&def.default))
+                });
+                current.as_ref() == Some(&dependency.equals)
+                    && self.is_setting_active(&dependency.key, values)
+            }
+            None => true,
+        }
+    }
+
+    /// Construye las secciones (sin las internas) con sus ajustes ordenados.
+    /// Las secciones usadas por algún ajuste pero no declaradas se añaden al final.
+    pub fn build_layout(&self, values: &HashMap<String, Value>) -> Vec<SettingsSectionLayout> {
+        let mut sections = self.sections.clone();
+        for id in self.get_ui_sections() {
+            if !sections.iter().any(|s| s.id == id) {
+                sections.push(ConfigSection {
+                    label: id.clone(),
+                    id,
+                    description: None,
+                    order: u32::MAX,
+                });
+            }
+        }
+        sections.sort_by_key(|s| s.order);
+
+        sections
+            .into_iter()
+            .filter(|section| section.id != "internal")
+            .map(|section| {
+                let mut settings = self
+                    .get_definitions_by_section(&section.id)
+                    .into_iter()
+                    .map(|(key, def)| SettingDescriptor {
+                        key: key.clone(),
+                        definition: def.clone(),
+                        value: values
+                            .get(key)
+                            .cloned()
+                            .unwrap_or_else(|| process_default_value(&def.default)),
+                        active: self.is_setting_active(key, values),
+                    })
+                    .collect::<Vec<_>>();
+                settings.sort_by(|a, b| {
+                    a.definition
+                        .order
+                        .unwrap_or(u32::MAX)
+                        .cmp(&b.definition.order.unwrap_or(u32::MAX))
+                        .then_with(|| a.key.cmp(&b.key))
+                });
+
+                SettingsSectionLayout { section, settings }
+            })
+            .filter(|layout| !layout.settings.is_empty())
+            .collect()
+    }
 }
 
 /// Procesa y normaliza valores de configuración, especialmente rutas
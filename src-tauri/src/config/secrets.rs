@@ -0,0 +1,103 @@
+//! Settings marked `secret: true`, stored encrypted and masked in `get_config`.
+
+use super::ConfigManager;
+use crate::core::token_storage;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Prefijo de los valores cifrados en config.json
+const ENCRYPTED_PREFIX: &str = "enc:";
+
+/// Lo que ve el frontend en lugar de un secreto configurado
+pub const SECRET_MASK: &str = "********";
+
+impl ConfigManager {
+    pub(super) fn is_secret(&self, key: &str) -> bool {
+        self.schema
+            .get_config_definition(key)
+            .is_some_and(|def| def.secret)
+    }
+
+    /// Value to store for a secret: encrypted, unless it is empty.
+    pub(super) fn seal_secret(value: Value) -> Result<Value, String> {
+        match value.as_str() {
+            Some(plain) if !plain.is_empty() => Ok(Value::String(format!(
+                "{}{}",
+                ENCRYPTED_PREFIX,
+                token_storage::encrypt(plain)?
+            ))),
+            _ => Ok(value),
+        }
+    }
+
+    /// Encrypts the secrets still stored in clear (saved before the setting
+    /// was marked secret, or edited by hand). Returns whether any changed.
+    pub(super) fn seal_plain_secrets(&mut self) -> bool {
+        let plain: Vec<String> = self
+            .values
+            .iter()
+            .filter(|(key, value)| {
+                self.is_secret(key)
+                    && value
+                        .as_str()
+                        .is_some_and(|value| !value.starts_with(ENCRYPTED_PREFIX))
+            })
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        let mut changed = false;
+        for key in plain {
+            let Some(value) = self.values.get(&key).cloned() else {
+                continue;
+            };
+            match Self::seal_secret(value) {
+                Ok(sealed) => {
+                    changed |= self.values.get(&key) != Some(&sealed);
+                    self.values.insert(key, sealed);
+                }
+                Err(e) => log::warn!("[Config] Could not encrypt '{}': {}", key, e),
+            }
+        }
+        changed
+    }
+
+    /// Decrypted value of secret `key`; `None` when it is empty, isn't a
+    /// secret or can't be decrypted (e.g. a config copied from another
+    /// machine).
+    pub(crate) fn get_secret(&self, key: &str) -> Option<String> {
+        if !self.is_secret(key) {
+            log::warn!("[Config] '{}' is not a secret setting", key);
+            return None;
+        }
+
+        let stored = self.values.get(key).and_then(Value::as_str)?;
+        if stored.is_empty() {
+            return None;
+        }
+        let Some(encrypted) = stored.strip_prefix(ENCRYPTED_PREFIX) else {
+            return Some(stored.to_string());
+        };
+        match token_storage::decrypt(encrypted) {
+            Ok(value) => Some(value),
+            Err(e) => {
+                log::warn!("[Config] Could not decrypt '{}': {}", key, e);
+                None
+            }
+        }
+    }
+
+    /// Every value, with configured secrets replaced by `SECRET_MASK`.
+    pub(super) fn masked_values(&self) -> HashMap<String, Value> {
+        self.values
+            .iter()
+            .map(|(key, value)| {
+                let configured = value.as_str().is_some_and(|value| !value.is_empty());
+                if self.is_secret(key) && configured {
+                    (key.clone(), Value::String(SECRET_MASK.to_string()))
+                } else {
+                    (key.clone(), value.clone())
+                }
+            })
+            .collect()
+    }
+}
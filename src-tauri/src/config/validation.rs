@@ -23,6 +23,9 @@ pub enum ValidationError {
     DirectoryNotExists(String),
     DirectoryNotCreatable(String),
     InvalidValidator(String),
+    InvalidEnvVar(String),
+    InvalidCommandLine(String),
+    InvalidUrl(String),
     Other(String),
 }
 
@@ -61,6 +64,19 @@ impl fmt::Display for ValidationError {
             ValidationError::InvalidValidator(validator) => {
                 write!(f, "Validador desconocido: {}", validator)
             }
+            ValidationError::InvalidEnvVar(entry) => {
+                write!(
+                    f,
+                    "Variable de entorno inválida (se esperaba CLAVE=valor): {}",
+                    entry
+                )
+            }
+            ValidationError::InvalidCommandLine(msg) => {
+                write!(f, "Comando inválido: {}", msg)
+            }
+            ValidationError::InvalidUrl(url) => {
+                write!(f, "URL inválida (se esperaba http:// o https://): {}", url)
+            }
             ValidationError::Other(msg) => {
                 write!(f, "{}", msg)
             }
@@ -123,6 +139,10 @@ pub fn validate_config_value(
         match validator.as_str() {
             "directory_exists" => validate_directory_exists(value)?,
             "directory_exists_or_creatable" => validate_directory_exists_or_creatable(value)?,
+            "env_vars" => validate_env_vars(value)?,
+            "directory_list" => validate_directory_list(value)?,
+            "command_line" => validate_command_line(value)?,
+            "http_url" => validate_http_url(value)?,
             _ => return Err(ValidationError::InvalidValidator(validator.clone())),
         }
     }
@@ -190,6 +210,128 @@ fn validate_directory_exists_or_creatable(value: &Value) -> Result<(), Validatio
     Ok(())
 }
 
+/// Validador para una lista de directorios existentes o que se puedan crear
+fn validate_directory_list(value: &Value) -> Result<(), ValidationError> {
+    if let Value::Array(entries) = value {
+        for entry in entries {
+            match entry {
+                Value::String(path) if !path.trim().is_empty() => {
+                    validate_directory_exists_or_creatable(entry)?;
+                }
+                _ => return Err(ValidationError::DirectoryNotCreatable(entry.to_string())),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Validador para una lista de variables de entorno en formato `CLAVE=valor`
+fn validate_env_vars(value: &Value) -> Result<(), ValidationError> {
+    if let Value::Array(entries) = value {
+        for entry in entries {
+            match entry.as_str() {
+                Some(entry) => {
+                    parse_env_var(entry)?;
+                }
+                None => return Err(ValidationError::InvalidEnvVar(entry.to_string())),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Validador para una línea de comando (p. ej. un wrapper como `gamemoderun`)
+fn validate_command_line(value: &Value) -> Result<(), ValidationError> {
+    if let Value::String(command) = value {
+        split_command_line(command)?;
+    }
+    Ok(())
+}
+
+/// Validador para una URL http(s); vacía significa "sin valor"
+fn validate_http_url(value: &Value) -> Result<(), ValidationError> {
+    let Value::String(raw) = value else {
+        return Ok(());
+    };
+    if raw.trim().is_empty() {
+        return Ok(());
+    }
+    match url::Url::parse(raw.trim()) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") && parsed.has_host() => Ok(()),
+        _ => Err(ValidationError::InvalidUrl(raw.clone())),
+    }
+}
+
+/// Comprueba que el nombre de una variable de entorno sea válido
+pub fn validate_env_var_name(name: &str) -> Result<(), ValidationError> {
+    let mut chars = name.chars();
+    let valid = match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {
+            chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }
+        _ => false,
+    };
+
+    if valid {
+        Ok(())
+    } else {
+        Err(ValidationError::InvalidEnvVar(name.to_string()))
+    }
+}
+
+/// Separa una entrada `CLAVE=valor` en sus partes
+pub fn parse_env_var(entry: &str) -> Result<(String, String), ValidationError> {
+    let (key, value) = entry
+        .split_once('=')
+        .ok_or_else(|| ValidationError::InvalidEnvVar(entry.to_string()))?;
+    let key = key.trim();
+    validate_env_var_name(key).map_err(|_| ValidationError::InvalidEnvVar(entry.to_string()))?;
+
+    Ok((key.to_string(), value.to_string()))
+}
+
+/// Divide una línea de comando respetando comillas simples y dobles.
+/// Una cadena vacía devuelve una lista vacía.
+pub fn split_command_line(command: &str) -> Result<Vec<String>, ValidationError> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    let mut in_token = false;
+
+    for c in command.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => current.push(c),
+            None if c == '"' || c == '\'' => {
+                quote = Some(c);
+                in_token = true;
+            }
+            None if c.is_whitespace() => {
+                if in_token {
+                    parts.push(std::mem::take(&mut current));
+                    in_token = false;
+                }
+            }
+            None => {
+                current.push(c);
+                in_token = true;
+            }
+        }
+    }
+
+    if quote.is_some() {
+        return Err(ValidationError::InvalidCommandLine(format!(
+            "comillas sin cerrar en '{}'",
+            command
+        )));
+    }
+    if in_token {
+        parts.push(current);
+    }
+
+    Ok(parts)
+}
+
 /// Expande una ruta con variables de entorno y ~
 fn expand_path(path: &str) -> std::path::PathBuf {
     let mut result = path.to_string();
@@ -0,0 +1,156 @@
+//! Player head avatars for the accounts list, cached on disk per UUID and size.
+
+use crate::core::http_client;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, RgbaImage};
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+const PROFILE_URL: &str = "https://sessionserver.mojang.com/session/minecraft/profile";
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+/// Las skins cambian poco; una vez al día basta
+const CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+const DEFAULT_SIZE: u32 = 64;
+const MIN_SIZE: u32 = 8;
+const MAX_SIZE: u32 = 512;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountAvatar {
+    pub path: String,
+    /// `data:image/png;base64,...`
+    pub data_url: String,
+}
+
+fn avatars_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| {
+        dir.join("dev.alexitoo.modpackstore")
+            .join("cache")
+            .join("avatars")
+    })
+}
+
+/// UUID without dashes, lowercase; `None` if it isn't one.
+fn normalize_uuid(uuid: &str) -> Option<String> {
+    let uuid: String = uuid.chars().filter(|c| *c != '-').collect();
+    (uuid.len() == 32 && uuid.chars().all(|c| c.is_ascii_hexdigit()))
+        .then(|| uuid.to_ascii_lowercase())
+}
+
+fn is_fresh(path: &Path) -> bool {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|age| age < CACHE_TTL)
+}
+
+/// Skin URL from the `textures` property of the profile.
+fn skin_url(profile: &Value) -> Option<String> {
+    let encoded = profile["properties"]
+        .as_array()?
+        .iter()
+        .find(|property| property["name"] == "textures")?["value"]
+        .as_str()?;
+    let textures: Value = serde_json::from_slice(&BASE64.decode(encoded).ok()?).ok()?;
+    textures["textures"]["SKIN"]["url"]
+        .as_str()
+        .map(str::to_string)
+}
+
+fn fetch_skin(uuid: &str) -> Result<DynamicImage, String> {
+    let client = http_client::blocking_builder()
+        .timeout(FETCH_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let response = client
+        .get(format!("{}/{}", PROFILE_URL, uuid))
+        .send()
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("Error al consultar el perfil: {}", e))?;
+    // Mojang responde 204 para UUIDs sin perfil (p. ej. cuentas offline)
+    if response.status().as_u16() == 204 {
+        return Err("La cuenta no tiene un perfil de Minecraft".to_string());
+    }
+    let profile: Value = response
+        .json()
+        .map_err(|e| format!("Perfil no válido: {}", e))?;
+
+    let url = skin_url(&profile).ok_or_else(|| "La cuenta no tiene skin".to_string())?;
+    let bytes = client
+        .get(&url)
+        .send()
+        .and_then(|response| response.error_for_status())
+        .and_then(|response| response.bytes())
+        .map_err(|e| format!("Error al descargar la skin: {}", e))?;
+
+    image::load_from_memory(&bytes).map_err(|e| format!("Skin no válida: {}", e))
+}
+
+/// Face plus hat layer, `size` x `size`. Works with classic (64x32),
+/// modern (64x64) and HD skins.
+fn render_head(skin: &DynamicImage, size: u32) -> Result<RgbaImage, String> {
+    let skin = skin.to_rgba8();
+    if skin.width() < 64 || skin.height() < 32 || skin.width() % 64 != 0 {
+        return Err(format!(
+            "Tamaño de skin no soportado: {}x{}",
+            skin.width(),
+            skin.height()
+        ));
+    }
+
+    let scale = skin.width() / 64;
+    let face_size = 8 * scale;
+    let mut head = imageops::crop_imm(&skin, 8 * scale, 8 * scale, face_size, face_size).to_image();
+    let hat = imageops::crop_imm(&skin, 40 * scale, 8 * scale, face_size, face_size).to_image();
+    imageops::overlay(&mut head, &hat, 0, 0);
+
+    Ok(imageops::resize(&head, size, size, FilterType::Nearest))
+}
+
+fn render_to_cache(uuid: &str, size: u32, path: &Path) -> Result<(), String> {
+    let head = render_head(&fetch_skin(uuid)?, size)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Error al crear el directorio de avatares: {}", e))?;
+    }
+    head.save_with_format(path, image::ImageFormat::Png)
+        .map_err(|e| format!("Error al guardar el avatar: {}", e))
+}
+
+pub fn account_avatar(uuid: &str, size: Option<u32>) -> Result<AccountAvatar, String> {
+    let uuid = normalize_uuid(uuid).ok_or_else(|| format!("UUID no válido: {}", uuid))?;
+    let size = size.unwrap_or(DEFAULT_SIZE).clamp(MIN_SIZE, MAX_SIZE);
+    let path = avatars_dir()
+        .ok_or_else(|| "No se pudo determinar el directorio de caché".to_string())?
+        .join(format!("{}-{}.png", uuid, size));
+
+    if !is_fresh(&path) {
+        if let Err(e) = render_to_cache(&uuid, size, &path) {
+            if !path.exists() {
+                return Err(e);
+            }
+            log::warn!("[Avatar] Using cached avatar for {}: {}", uuid, e);
+        }
+    }
+
+    let bytes = fs::read(&path).map_err(|e| format!("Error al leer el avatar: {}", e))?;
+    Ok(AccountAvatar {
+        path: path.to_string_lossy().to_string(),
+        data_url: format!("data:image/png;base64,{}", BASE64.encode(bytes)),
+    })
+}
+
+/// Head avatar of the account `uuid`, `size` pixels wide (default 64).
+#[tauri::command]
+pub async fn get_account_avatar(uuid: String, size: Option<u32>) -> Result<AccountAvatar, String> {
+    tokio::task::spawn_blocking(move || account_avatar(&uuid, size))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
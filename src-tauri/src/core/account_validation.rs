@@ -0,0 +1,117 @@
+//! Account checks before launching an instance.
+
+use crate::config::with_config;
+use crate::core::accounts_manager::AccountsManager;
+use crate::core::i18n::{keys, Message};
+use crate::core::minecraft_account::MinecraftAccount;
+use crate::core::minecraft_instance::MinecraftInstance;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde_json::Value;
+
+/// Nombre de la cuenta offline de reserva cuando no se conoce la cuenta original
+const FALLBACK_USERNAME: &str = "Player";
+/// Margen para no lanzar con un token que caduca en plena carga del juego
+const TOKEN_EXPIRY_MARGIN_SECS: i64 = 5 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountIssue {
+    NotSelected,
+    NotFound,
+    EmptyUsername,
+    MissingToken,
+    TokenExpired,
+}
+
+impl AccountIssue {
+    pub fn code(&self) -> &'static str {
+        match self {
+            AccountIssue::NotSelected => "ACCOUNT_NOT_SELECTED",
+            AccountIssue::NotFound => "ACCOUNT_NOT_FOUND",
+            AccountIssue::EmptyUsername => "ACCOUNT_EMPTY_USERNAME",
+            AccountIssue::MissingToken => "ACCOUNT_TOKEN_MISSING",
+            AccountIssue::TokenExpired => "ACCOUNT_TOKEN_EXPIRED",
+        }
+    }
+
+    pub fn message(&self) -> Message {
+        Message::new(match self {
+            AccountIssue::NotSelected => keys::ACCOUNT_NOT_SELECTED,
+            AccountIssue::NotFound => keys::ACCOUNT_NOT_FOUND,
+            AccountIssue::EmptyUsername => keys::ACCOUNT_EMPTY_USERNAME,
+            AccountIssue::MissingToken | AccountIssue::TokenExpired => keys::LAUNCH_INVALID_SESSION,
+        })
+    }
+}
+
+fn is_microsoft_account(account: &MinecraftAccount) -> bool {
+    account.user_type().eq_ignore_ascii_case("microsoft")
+}
+
+/// `exp` claim of a JWT access token; `None` if it isn't a JWT.
+fn token_expiry(token: &str) -> Option<i64> {
+    let payload = token.split('.').nth(1)?;
+    let claims: Value = serde_json::from_slice(&URL_SAFE_NO_PAD.decode(payload).ok()?).ok()?;
+    claims.get("exp")?.as_i64()
+}
+
+pub fn check_account(account: &MinecraftAccount) -> Result<(), AccountIssue> {
+    if account.username().trim().is_empty() {
+        return Err(AccountIssue::EmptyUsername);
+    }
+    if !is_microsoft_account(account) {
+        return Ok(());
+    }
+
+    let token = account
+        .access_token()
+        .filter(|token| !token.is_empty())
+        .ok_or(AccountIssue::MissingToken)?;
+    // Un token opaco no se puede comprobar aquí; lo hará el servidor
+    let expired = token_expiry(token)
+        .is_some_and(|expiry| expiry - TOKEN_EXPIRY_MARGIN_SECS <= chrono::Utc::now().timestamp());
+    if expired {
+        return Err(AccountIssue::TokenExpired);
+    }
+    Ok(())
+}
+
+/// Account bound to `instance`, checked.
+pub fn validate_instance_account(
+    instance: &MinecraftInstance,
+) -> Result<MinecraftAccount, AccountIssue> {
+    let uuid = instance
+        .accountUuid
+        .as_deref()
+        .filter(|uuid| !uuid.is_empty())
+        .ok_or(AccountIssue::NotSelected)?;
+    let account = AccountsManager::new()
+        .get_minecraft_account_by_uuid(uuid)
+        .ok_or(AccountIssue::NotFound)?;
+    check_account(&account)?;
+    Ok(account)
+}
+
+/// Offline account used when the user accepts playing without the bound
+/// one. Keeps the original username when it is known, and the UUID is
+/// derived from the name so worlds keep the same player data.
+pub fn offline_fallback_account(instance: &MinecraftInstance) -> MinecraftAccount {
+    let username = instance
+        .accountUuid
+        .as_deref()
+        .and_then(|uuid| AccountsManager::new().get_minecraft_account_by_uuid(uuid))
+        .map(|account| account.username().trim().to_string())
+        .filter(|username| !username.is_empty())
+        .unwrap_or_else(|| FALLBACK_USERNAME.to_string());
+    let uuid = AccountsManager::get_offline_player_uuid(&username)
+        .unwrap_or_else(|_| uuid::Uuid::nil().to_string());
+
+    MinecraftAccount::new(username, uuid, None, "offline".to_string())
+}
+
+/// `offlineFallback` setting. Must not be called while holding the config lock.
+pub fn offline_fallback_enabled() -> bool {
+    with_config(|config| config.get_offline_fallback())
+        .ok()
+        .unwrap_or(false)
+}
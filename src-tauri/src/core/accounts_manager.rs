@@ -1,16 +1,22 @@
+use crate::core::accounts_store;
+use crate::core::instance_manager::get_all_instances;
 use crate::core::minecraft_account::MinecraftAccount;
+use crate::core::minecraft_instance;
 use dirs::config_dir;
-use serde::{Deserialize, Serialize};
-use serde_json::{self, json};
-use std::fs::{self, File};
-use std::io::{Read, Write};
+use md5::{Digest, Md5};
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use uuid::Uuid;
 
+/// Longitud permitida por Minecraft para los nombres de jugador
+const USERNAME_MIN_LEN: usize = 3;
+const USERNAME_MAX_LEN: usize = 16;
+
 pub struct AccountsManager {
     pub accounts: Vec<MinecraftAccount>,
     accounts_file: PathBuf,
+    // El archivo no se puede reescribir sin perder datos (ver accounts_store)
+    read_only: bool,
 }
 
 impl AccountsManager {
@@ -20,17 +26,14 @@ impl AccountsManager {
             .join("dev.alexitoo.modpackstore")
             .join("accounts.json");
         if !accounts_file.exists() {
-            let default_accounts = json!([]);
-            fs::write(
-                &accounts_file,
-                serde_json::to_string_pretty(&default_accounts).unwrap(),
-            )
-            .expect("Failed to create accounts.json file");
+            accounts_store::write(&accounts_file, &[])
+                .expect("Failed to create accounts.json file");
         }
 
         let mut manager = AccountsManager {
             accounts: Vec::new(),
             accounts_file,
+            read_only: false,
         };
         manager.load();
         manager
@@ -58,6 +61,7 @@ impl AccountsManager {
     }
 
     pub fn add_offline_account(&mut self, username: &str) -> Result<MinecraftAccount, String> {
+        let username = username.trim();
         let uuid = Self::get_offline_player_uuid(username)?;
         let account = MinecraftAccount::new(
             username.to_string(),
@@ -66,7 +70,7 @@ impl AccountsManager {
             "offline".to_string(),
         );
         if self.accounts.iter().any(|a| a.uuid() == uuid) {
-            return Err(format!("Account with UUID {} already exists", uuid));
+            return Err(format!("Ya existe una cuenta offline llamada {}", username));
         }
         self.accounts.push(account.clone());
         self.save();
@@ -102,69 +106,129 @@ impl AccountsManager {
             return;
         }
 
-        match fs::read_to_string(&self.accounts_file) {
-            Ok(contents) => match serde_json::from_str::<Vec<MinecraftAccount>>(&contents) {
-                Ok(loaded_accounts) => {
-                    self.accounts = loaded_accounts;
-                    println!("Accounts loaded successfully: {}", self.accounts.len());
-                }
-                Err(e) => {
-                    eprintln!("Error parsing accounts.json: {}", e);
-                }
-            },
-            Err(e) => {
-                eprintln!("Error reading accounts.json: {}", e);
-            }
-        }
+        let loaded = accounts_store::read(&self.accounts_file);
+        self.accounts = loaded.accounts;
+        self.read_only = loaded.read_only;
+        println!("Accounts loaded successfully: {}", self.accounts.len());
     }
 
     pub fn save(&self) {
-        if let Some(parent) = self.accounts_file.parent() {
-            if !parent.exists() {
-                if let Err(e) = fs::create_dir_all(parent) {
-                    eprintln!("Error creating directory: {}", e);
-                    return;
-                }
-            }
+        if self.read_only {
+            log::warn!("[Accounts] accounts.json is read-only for this version, not saving");
+            return;
         }
-
-        match serde_json::to_string_pretty(&self.accounts) {
-            Ok(json) => {
-                if let Err(e) = fs::write(&self.accounts_file, json) {
-                    eprintln!("Error writing to accounts.json: {}", e);
-                }
-            }
-            Err(e) => {
-                eprintln!("Error serializing accounts: {}", e);
-            }
+        if let Err(e) = accounts_store::write(&self.accounts_file, &self.accounts) {
+            eprintln!("Error writing to accounts.json: {}", e);
         }
     }
 
-    /// Calculates the UUID for an offline player
-    pub fn get_offline_player_uuid(username: &str) -> Result<String, String> {
-        // Validation
+    /// Checks Minecraft's username rules: 3-16 characters, ASCII letters,
+    /// digits and underscores only.
+    pub fn validate_offline_username(username: &str) -> Result<(), String> {
         if username.is_empty() {
-            return Err("Username cannot be null or empty".to_string());
+            return Err("El nombre de usuario no puede estar vacío".to_string());
         }
 
-        if username.len() < 3 || username.len() > 16 {
-            return Err("Username must be between 3 and 16 characters".to_string());
+        let length = username.chars().count();
+        if !(USERNAME_MIN_LEN..=USERNAME_MAX_LEN).contains(&length) {
+            return Err(format!(
+                "El nombre de usuario debe tener entre {} y {} caracteres",
+                USERNAME_MIN_LEN, USERNAME_MAX_LEN
+            ));
         }
 
-        if !username.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        if !username
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
             return Err(
-                "Username can only contain letters (a-z, A-Z), numbers (0-9), and underscores (_)"
+                "El nombre de usuario sólo puede contener letras (a-z, A-Z), números (0-9) y guiones bajos (_)"
                     .to_string(),
             );
         }
+        Ok(())
+    }
 
-        // Create the string to hash
-        let string_to_hash = format!("OfflinePlayer:{}", username);
+    /// Calculates the UUID for an offline player, the same one vanilla
+    /// servers in offline mode assign (`UUID.nameUUIDFromBytes` of
+    /// `OfflinePlayer:<name>`), so the identity survives reinstalls.
+    pub fn get_offline_player_uuid(username: &str) -> Result<String, String> {
+        Self::validate_offline_username(username)?;
+        Ok(Self::offline_uuid(username))
+    }
 
-        // Generate the UUID (Version 3, name-based)
-        let offline_uuid = Uuid::new_v3(&Uuid::NAMESPACE_DNS, string_to_hash.as_bytes());
+    /// MD5 name-based UUID (version 3) without namespace, as Java does it.
+    fn offline_uuid(username: &str) -> String {
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&Md5::digest(format!("OfflinePlayer:{}", username)));
+        bytes[6] = (bytes[6] & 0x0f) | 0x30;
+        bytes[8] = (bytes[8] & 0x3f) | 0x80;
+        Uuid::from_bytes(bytes).to_string()
+    }
 
-        Ok(offline_uuid.to_string())
+    /// UUID that older versions of the launcher gave offline accounts
+    /// (hashed with the DNS namespace, unlike vanilla).
+    fn legacy_offline_uuid(username: &str) -> String {
+        let name = format!("OfflinePlayer:{}", username);
+        Uuid::new_v3(&Uuid::NAMESPACE_DNS, name.as_bytes()).to_string()
+    }
+
+    /// Moves offline accounts created with the old UUID to the vanilla one
+    /// and rebinds the instances that used them. Reads the instances, so it
+    /// must not run while holding the config lock.
+    fn migrate_offline_uuids(&mut self) {
+        let mut remapped = Vec::new();
+        for account in self.accounts.iter_mut() {
+            if account.user_type() != "offline"
+                || account.uuid() != Self::legacy_offline_uuid(account.username())
+            {
+                continue;
+            }
+            let new_uuid = Self::offline_uuid(account.username());
+            remapped.push((account.uuid().to_string(), new_uuid.clone()));
+            account.set_uuid(new_uuid);
+        }
+        if remapped.is_empty() {
+            return;
+        }
+
+        // Si ya existía la cuenta con el UUID nuevo, nos quedamos con una
+        let mut seen = std::collections::HashSet::new();
+        self.accounts
+            .retain(|account| seen.insert(account.uuid().to_string()));
+        self.save();
+        log::info!(
+            "[Accounts] Migrated {} offline account(s) to vanilla offline UUIDs",
+            remapped.len()
+        );
+
+        match get_all_instances() {
+            Ok(instances) => {
+                for instance in instances {
+                    let Some((_, new_uuid)) = remapped
+                        .iter()
+                        .find(|(old, _)| instance.accountUuid.as_deref() == Some(old.as_str()))
+                    else {
+                        continue;
+                    };
+                    let Some(directory) = instance.instanceDirectory.as_deref() else {
+                        continue;
+                    };
+                    let rebound = minecraft_instance::with_locked(Path::new(directory), |stored| {
+                        stored.accountUuid = Some(new_uuid.clone());
+                        Ok(())
+                    });
+                    if let Err(e) = rebound {
+                        log::error!(
+                            "[Accounts] Could not rebind instance {}: {}",
+                            instance.instanceId,
+                            e
+                        );
+                    }
+                }
+            }
+            Err(e) => log::error!("[Accounts] Could not rebind instances: {}", e),
+        }
     }
 }
 
@@ -177,6 +241,14 @@ pub fn get_accounts_manager() -> Arc<Mutex<AccountsManager>> {
     ACCOUNTS_MANAGER.clone()
 }
 
+/// Startup migration of offline account UUIDs (see `migrate_offline_uuids`).
+pub fn migrate_offline_accounts() {
+    match get_accounts_manager().lock() {
+        Ok(mut manager) => manager.migrate_offline_uuids(),
+        Err(_) => log::warn!("[Accounts] Accounts lock poisoned, skipping migration"),
+    }
+}
+
 #[tauri::command]
 pub fn add_offline_account(username: &str) -> Result<MinecraftAccount, String> {
     let accounts_manager = get_accounts_manager();
@@ -202,7 +274,6 @@ pub fn get_all_accounts() -> Result<Vec<MinecraftAccount>, String> {
     Ok(manager.get_all_accounts())
 }
 
-
 #[tauri::command]
 pub fn ensure_account_exists(uuid: &str) -> Result<bool, String> {
     let accounts_manager = get_accounts_manager();
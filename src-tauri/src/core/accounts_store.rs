@@ -0,0 +1,253 @@
+//! Versioned on-disk format of `accounts.json` and its migrations.
+
+use crate::core::minecraft_account::MinecraftAccount;
+use crate::core::token_storage;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const CURRENT_VERSION: u64 = 2;
+
+/// `MIGRATIONS[i]` upgrades a version `i + 1` document to version `i + 2`
+const MIGRATIONS: &[fn(Value) -> Result<Value, String>] = &[migrate_v1_to_v2];
+
+/// Result of reading the store.
+#[derive(Debug, Default)]
+pub struct LoadedAccounts {
+    pub accounts: Vec<MinecraftAccount>,
+    /// No se debe sobrescribir: versión más nueva o migración imposible
+    pub read_only: bool,
+}
+
+fn version_of(document: &Value) -> Result<u64, String> {
+    match document {
+        Value::Array(_) => Ok(1),
+        Value::Object(object) => object
+            .get("version")
+            .and_then(Value::as_u64)
+            .filter(|version| *version >= 1)
+            .ok_or_else(|| "accounts.json has no valid version".to_string()),
+        _ => Err("accounts.json is not an object or array".to_string()),
+    }
+}
+
+fn migrate_v1_to_v2(document: Value) -> Result<Value, String> {
+    let Value::Array(accounts) = document else {
+        return Err("Version 1 accounts.json must be an array".to_string());
+    };
+
+    let accounts = accounts
+        .into_iter()
+        .map(|mut account| {
+            let token = account
+                .get("access_token")
+                .and_then(Value::as_str)
+                .map(str::to_string);
+            if let (Some(token), Some(object)) = (token, account.as_object_mut()) {
+                // Sin clave de equipo el token se queda en claro antes que perderlo
+                match token_storage::encrypt(&token) {
+                    Ok(encrypted) => {
+                        object.insert("access_token".to_string(), Value::Null);
+                        object.insert("access_token_encrypted".to_string(), json!(encrypted));
+                    }
+                    Err(e) => log::warn!("[Accounts] Token left unencrypted: {}", e),
+                }
+            }
+            account
+        })
+        .collect::<Vec<_>>();
+
+    Ok(json!({ "version": 2, "accounts": accounts }))
+}
+
+/// Applies the migrations from `version` up to `CURRENT_VERSION`.
+fn migrate(document: Value, version: u64) -> Result<Value, String> {
+    MIGRATIONS[version as usize - 1..]
+        .iter()
+        .try_fold(document, |document, step| step(document))
+}
+
+/// `<file>.v<version>-<timestamp>.bak` (or `.corrupt-...`) next to `path`.
+fn backup_path(path: &Path, tag: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(
+        ".{}-{}.bak",
+        tag,
+        chrono::Utc::now().format("%Y%m%d%H%M%S")
+    ));
+    path.with_file_name(name)
+}
+
+fn backup(path: &Path, tag: &str) -> Result<PathBuf, String> {
+    let destination = backup_path(path, tag);
+    fs::copy(path, &destination)
+        .map_err(|e| format!("Could not back up {}: {}", path.display(), e))?;
+    Ok(destination)
+}
+
+/// Decodes one current-version record. A token that can't be decrypted (the
+/// file comes from another device) is dropped: the account stays and only
+/// needs a new login.
+fn decode_account(mut record: Value) -> Option<MinecraftAccount> {
+    let encrypted = record
+        .get("access_token_encrypted")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    let object = record.as_object_mut()?;
+    object.remove("access_token_encrypted");
+
+    if let Some(encrypted) = encrypted {
+        let token = match token_storage::decrypt(&encrypted) {
+            Ok(token) => json!(token),
+            Err(e) => {
+                log::warn!("[Accounts] Dropping undecryptable token: {}", e);
+                Value::Null
+            }
+        };
+        object.insert("access_token".to_string(), token);
+    }
+
+    match serde_json::from_value(record) {
+        Ok(account) => Some(account),
+        Err(e) => {
+            log::error!("[Accounts] Skipping invalid account record: {}", e);
+            None
+        }
+    }
+}
+
+fn encode_account(account: &MinecraftAccount) -> Result<Value, String> {
+    let mut record = serde_json::to_value(account).map_err(|e| e.to_string())?;
+    if let Some(token) = account.access_token() {
+        match token_storage::encrypt(token) {
+            Ok(encrypted) => {
+                record["access_token"] = Value::Null;
+                record["access_token_encrypted"] = json!(encrypted);
+            }
+            Err(e) => log::warn!("[Accounts] Token stored unencrypted: {}", e),
+        }
+    }
+    Ok(record)
+}
+
+fn decode_accounts(document: &Value) -> Vec<MinecraftAccount> {
+    document
+        .get("accounts")
+        .and_then(Value::as_array)
+        .map(|records| records.iter().cloned().filter_map(decode_account).collect())
+        .unwrap_or_default()
+}
+
+/// Writes `accounts` in the current format (through a temporary file so a
+/// crash never leaves a truncated store).
+pub fn write(path: &Path, accounts: &[MinecraftAccount]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Error creating directory: {}", e))?;
+    }
+
+    let records = accounts
+        .iter()
+        .map(encode_account)
+        .collect::<Result<Vec<_>, _>>()?;
+    let document = json!({ "version": CURRENT_VERSION, "accounts": records });
+    let contents = serde_json::to_string_pretty(&document).map_err(|e| e.to_string())?;
+
+    let temp = path.with_extension("json.tmp");
+    fs::write(&temp, contents).map_err(|e| format!("Error writing accounts: {}", e))?;
+    fs::rename(&temp, path).map_err(|e| format!("Error writing accounts: {}", e))
+}
+
+/// Reads the store, migrating it to `CURRENT_VERSION` if needed.
+pub fn read(path: &Path) -> LoadedAccounts {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) => {
+            log::error!("[Accounts] Error reading {}: {}", path.display(), e);
+            return LoadedAccounts::default();
+        }
+    };
+
+    let parsed = serde_json::from_str::<Value>(&contents)
+        .map_err(|e| e.to_string())
+        .and_then(|document| version_of(&document).map(|version| (document, version)));
+    let (document, version) = match parsed {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            // Se aparta para que el primer guardado no lo sustituya por una lista vacía
+            match backup(path, "corrupt") {
+                Ok(copy) => log::error!(
+                    "[Accounts] Unreadable accounts file ({}), saved a copy to {}",
+                    e,
+                    copy.display()
+                ),
+                Err(backup_error) => log::error!(
+                    "[Accounts] Unreadable accounts file ({}): {}",
+                    e,
+                    backup_error
+                ),
+            }
+            return LoadedAccounts::default();
+        }
+    };
+
+    if version > CURRENT_VERSION {
+        log::warn!(
+            "[Accounts] accounts.json is version {} (newer than {}), opening read-only",
+            version,
+            CURRENT_VERSION
+        );
+        return LoadedAccounts {
+            accounts: decode_accounts(&document),
+            read_only: true,
+        };
+    }
+
+    if version == CURRENT_VERSION {
+        return LoadedAccounts {
+            accounts: decode_accounts(&document),
+            read_only: false,
+        };
+    }
+
+    let document = match migrate(document, version) {
+        Ok(document) => document,
+        Err(e) => {
+            log::error!(
+                "[Accounts] Migration from version {} failed: {}",
+                version,
+                e
+            );
+            return LoadedAccounts {
+                accounts: Vec::new(),
+                read_only: true,
+            };
+        }
+    };
+    let accounts = decode_accounts(&document);
+
+    // Sin copia de seguridad no se toca el archivo original
+    let copy = match backup(path, &format!("v{}", version)) {
+        Ok(copy) => copy,
+        Err(e) => {
+            log::error!("[Accounts] {}; keeping the old file", e);
+            return LoadedAccounts {
+                accounts,
+                read_only: true,
+            };
+        }
+    };
+    if let Err(e) = write(path, &accounts) {
+        log::error!("[Accounts] Could not write migrated accounts: {}", e);
+    }
+    log::info!(
+        "[Accounts] Migrated accounts.json from version {} to {} (backup: {})",
+        version,
+        CURRENT_VERSION,
+        copy.display()
+    );
+
+    LoadedAccounts {
+        accounts,
+        read_only: false,
+    }
+}
@@ -0,0 +1,112 @@
+//! Client for the Modpack Store API; refreshes the access token on a 401.
+
+use crate::core::auth;
+use crate::core::events::EventEmitter;
+use crate::core::http_client;
+use crate::API_ENDPOINT;
+use serde_json::Value;
+use tauri_plugin_http::reqwest::{Client, Method, RequestBuilder, Response, StatusCode};
+
+pub struct ApiClient {
+    client: Client,
+    app_handle: tauri::AppHandle,
+}
+
+impl ApiClient {
+    pub fn new(app_handle: tauri::AppHandle) -> Self {
+        Self {
+            client: http_client::async_client(),
+            app_handle,
+        }
+    }
+
+    /// Client bound to the global app handle, for code outside commands.
+    pub fn from_global() -> Result<Self, String> {
+        let emitter = EventEmitter::global().ok_or("AppHandle no inicializado")?;
+        Ok(Self::new(emitter.app_handle().clone()))
+    }
+
+    /// Accepts either an API path (`/auth/me`) or an absolute URL, so
+    /// download links returned by the API go through the same client.
+    fn url(path: &str) -> String {
+        if path.starts_with("http://") || path.starts_with("https://") {
+            path.to_string()
+        } else {
+            format!("{}{}", API_ENDPOINT, path)
+        }
+    }
+
+    pub async fn get(&self, path: &str) -> Result<Response, String> {
+        self.request(Method::GET, path, None).await
+    }
+
+    /// GET for public endpoints: the token is sent when there is a session,
+    /// but a logged-out user can call them too.
+    pub async fn get_public(&self, path: &str) -> Result<Response, String> {
+        let has_session = auth::load_tokens_from_store(&self.app_handle)
+            .await
+            .ok()
+            .flatten()
+            .is_some();
+        if has_session {
+            return self.get(path).await;
+        }
+        self.client
+            .get(Self::url(path))
+            .send()
+            .await
+            .map_err(|e| format!("Error al contactar la API: {}", e))
+    }
+
+    pub async fn post(&self, path: &str, body: &Value) -> Result<Response, String> {
+        self.request(Method::POST, path, Some(body)).await
+    }
+
+    pub async fn request(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&Value>,
+    ) -> Result<Response, String> {
+        let url = Self::url(path);
+        self.send_with(|client| {
+            let builder = client.request(method.clone(), &url);
+            match body {
+                Some(body) => builder.json(body),
+                None => builder,
+            }
+        })
+        .await
+    }
+
+    /// Sends the request built by `build` with the current access token,
+    /// refreshing it and retrying once if the API answers 401.
+    pub async fn send_with<F>(&self, build: F) -> Result<Response, String>
+    where
+        F: Fn(&Client) -> RequestBuilder,
+    {
+        let tokens = auth::load_tokens_from_store(&self.app_handle)
+            .await?
+            .ok_or("No hay una sesión iniciada")?;
+
+        let response = build(&self.client)
+            .bearer_auth(&tokens.access_token)
+            .send()
+            .await
+            .map_err(|e| format!("Error al contactar la API: {}", e))?;
+
+        if response.status() != StatusCode::UNAUTHORIZED {
+            return Ok(response);
+        }
+
+        log::info!("[ApiClient] 401 received, refreshing access token");
+        let new_tokens =
+            auth::refresh_session_tokens(&self.app_handle, &tokens.access_token).await?;
+
+        build(&self.client)
+            .bearer_auth(&new_tokens.access_token)
+            .send()
+            .await
+            .map_err(|e| format!("Error al contactar la API: {}", e))
+    }
+}
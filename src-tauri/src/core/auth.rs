@@ -1,12 +1,13 @@
-use crate::GLOBAL_APP_HANDLE;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use sha2::{Digest, Sha256};
 use std::convert::Infallible;
-use std::net::SocketAddr;
+use std::net::TcpListener;
+use std::ops::RangeInclusive;
 use std::sync::Arc;
-use tauri::Emitter;
 use tauri::{Manager, State};
-use tauri_plugin_http::reqwest::Client;
 use tauri_plugin_opener;
 use tauri_plugin_store::StoreExt;
 use tokio::sync::Mutex;
@@ -16,13 +17,18 @@ use hyper::header::HeaderValue;
 use hyper::server::Server;
 use hyper::service::{make_service_fn, service_fn};
 use hyper::{Body, Request, Response, StatusCode as HyperStatusCode};
-use tauri_plugin_http::reqwest::StatusCode;
 
+use crate::core::api_client::ApiClient;
+use crate::core::events::{self, LauncherEvent};
+use crate::core::http_client;
+use crate::core::token_storage;
 use crate::API_ENDPOINT;
 
 // Constantes para el almacenamiento
 const STORAGE_PATH: &str = "auth_store.json";
 const STORAGE_KEY_TOKENS: &str = "auth_tokens";
+const STORAGE_KEY_SESSIONS: &str = "auth_sessions";
+const STORAGE_KEY_ACTIVE_SESSION: &str = "active_session";
 
 // User session structure
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -31,11 +37,22 @@ pub struct UserSession {
     pub extra: serde_json::Value,
 }
 
+// Sesión guardada en el store; los tokens van aparte, en el almacenamiento seguro
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredSession {
+    pub account_id: String,
+    /// Última copia conocida de los datos del usuario, para listar cuentas sin red
+    pub user: UserSession,
+    #[serde(default)]
+    pub active: bool,
+}
+
 // Token response from API
 #[derive(Debug, Serialize, Deserialize, Clone)]
-struct TokenResponse {
-    access_token: String,
-    refresh_token: String,
+pub(crate) struct TokenResponse {
+    pub(crate) access_token: String,
+    pub(crate) refresh_token: String,
 }
 
 // Auth steps for frontend
@@ -54,6 +71,9 @@ pub struct AuthState {
     pub session: Mutex<Option<UserSession>>,
     pub auth_code: Mutex<Option<String>>,
     // Tokens se guardarán en store, no en memoria
+    // Serializa las renovaciones para que varias peticiones con 401 no
+    // gasten el mismo refresh_token a la vez
+    refresh_lock: Mutex<()>,
 }
 
 impl AuthState {
@@ -61,25 +81,73 @@ impl AuthState {
         Self {
             session: Mutex::new(None),
             auth_code: Mutex::new(None),
+            refresh_lock: Mutex::new(()),
         }
     }
 }
 
 // --- Constants ---
 const CLIENT_ID: &str = "943184136976334879";
-const REDIRECT_URI: &str = "http://localhost:1957/callback";
+// Puertos registrados como redirect en Discord; Discord rechaza cualquier
+// otro, así que si están todos ocupados no se puede iniciar sesión
+const CALLBACK_PORTS: RangeInclusive<u16> = 1957..=1966;
 // --- /Constants ---
 
-// Helper para emitir eventos (optimizado para evitar repetición de código)
-fn emit_event<T: Serialize + Clone>(event: &str, payload: Option<T>) -> Result<(), String> {
-    let binding = GLOBAL_APP_HANDLE.lock().unwrap();
-    let app = binding.as_ref().ok_or("AppHandle no inicializado")?;
+// Error estructurado para fallos del flujo OAuth que el frontend debe distinguir
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthErrorEvent {
+    pub code: String,
+    pub message: String,
+}
+
+// Valores aleatorios del flujo: `state` anti-CSRF y `code_verifier` de PKCE
+fn random_token() -> String {
+    format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    )
+}
+
+// code_challenge = BASE64URL(SHA256(code_verifier)), método S256
+fn pkce_challenge(verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+fn query_param(query: &str, name: &str) -> Option<String> {
+    url::form_urlencoded::parse(query.as_bytes())
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.into_owned())
+}
+
+fn redirect_uri(port: u16) -> String {
+    format!("http://localhost:{}/callback", port)
+}
+
+fn encode_query_value(value: &str) -> String {
+    url::form_urlencoded::byte_serialize(value.as_bytes()).collect()
+}
 
-    let main_window = app
-        .get_webview_window("main")
-        .ok_or("Ventana principal no encontrada")?;
+// Reserva el primer puerto libre de los registrados para el callback
+fn bind_callback_listener() -> Result<TcpListener, String> {
+    for port in CALLBACK_PORTS {
+        match TcpListener::bind(("127.0.0.1", port)) {
+            Ok(listener) => return Ok(listener),
+            Err(e) => log::warn!("[Auth] Callback port {} unavailable: {}", port, e),
+        }
+    }
 
-    main_window.emit(event, payload).map_err(|e| e.to_string())
+    Err(format!(
+        "Los puertos {}-{} que usa el inicio de sesión están ocupados. Cierra la aplicación que los usa (u otra ventana del launcher) e inténtalo de nuevo.",
+        CALLBACK_PORTS.start(),
+        CALLBACK_PORTS.end()
+    ))
+}
+
+// Helper para emitir eventos a través del EventEmitter
+fn emit_event(event: LauncherEvent) -> Result<(), String> {
+    events::try_emit(event)
 }
 
 // Success HTML page
@@ -112,75 +180,279 @@ const SUCCESS_HTML: &str = r#"
 struct AppState {
     auth_state: Arc<AuthState>,
     server_tx: Option<tokio::sync::oneshot::Sender<()>>,
+    // `state` enviado a Discord; el callback debe devolver el mismo
+    expected_state: String,
 }
 
-// Helper para guardar tokens en el store (nueva sintaxis)
-async fn save_tokens_to_store(
+// Cada cuenta guarda sus tokens con su propia clave. La clave sin sufijo es
+// la de versiones con una sola sesión (y la de una sesión aún sin registrar).
+fn tokens_key(account_id: Option<&str>) -> String {
+    match account_id {
+        Some(id) => format!("{}:{}", STORAGE_KEY_TOKENS, id),
+        None => STORAGE_KEY_TOKENS.to_string(),
+    }
+}
+
+fn session_account_id(user: &UserSession) -> Option<String> {
+    match user.extra.get("id")? {
+        serde_json::Value::String(id) => Some(id.clone()),
+        serde_json::Value::Number(id) => Some(id.to_string()),
+        _ => None,
+    }
+}
+
+fn active_account_id(app_handle: &tauri::AppHandle) -> Result<Option<String>, String> {
+    let store = app_handle
+        .store(STORAGE_PATH)
+        .map_err(|e| format!("Error al acceder al store: {}", e))?;
+    let active = store
+        .get(STORAGE_KEY_ACTIVE_SESSION)
+        .and_then(|value| value.as_str().map(str::to_string));
+    store.close_resource();
+    Ok(active)
+}
+
+fn set_active_account_id(
     app_handle: &tauri::AppHandle,
-    tokens: &TokenResponse,
+    account_id: Option<&str>,
 ) -> Result<(), String> {
-    // Este sí devuelve Result, así que usamos map_err
-    let store = app_handle.store(STORAGE_PATH).map_err(|e| e.to_string())?;
-
-    // Este NO devuelve Result, así que no uses `?`
-    store.set(STORAGE_KEY_TOKENS.to_string(), json!(tokens));
+    let store = app_handle
+        .store(STORAGE_PATH)
+        .map_err(|e| format!("Error al acceder al store: {}", e))?;
+    match account_id {
+        Some(id) => store.set(STORAGE_KEY_ACTIVE_SESSION.to_string(), json!(id)),
+        None => {
+            store.delete(STORAGE_KEY_ACTIVE_SESSION);
+        }
+    }
+    store
+        .save()
+        .map_err(|e| format!("Error al guardar cambios en el store: {}", e))?;
+    store.close_resource();
+    Ok(())
+}
 
-    // Este sí devuelve Result
-    store.save().map_err(|e| e.to_string())?;
+fn load_stored_sessions(app_handle: &tauri::AppHandle) -> Result<Vec<StoredSession>, String> {
+    let store = app_handle
+        .store(STORAGE_PATH)
+        .map_err(|e| format!("Error al acceder al store: {}", e))?;
+    let sessions = store
+        .get(STORAGE_KEY_SESSIONS)
+        .and_then(|value| serde_json::from_value::<Vec<StoredSession>>(value).ok())
+        .unwrap_or_default();
+    store.close_resource();
+    Ok(sessions)
+}
 
-    // Cierre del recurso, probablemente sin fallo también
+fn save_stored_sessions(
+    app_handle: &tauri::AppHandle,
+    sessions: &[StoredSession],
+) -> Result<(), String> {
+    let store = app_handle
+        .store(STORAGE_PATH)
+        .map_err(|e| format!("Error al acceder al store: {}", e))?;
+    store.set(STORAGE_KEY_SESSIONS.to_string(), json!(sessions));
+    store
+        .save()
+        .map_err(|e| format!("Error al guardar cambios en el store: {}", e))?;
     store.close_resource();
+    Ok(())
+}
+
+// Registra (o actualiza) la sesión del usuario, guarda sus tokens con su
+// clave propia y la marca como activa
+async fn register_session(
+    app_handle: &tauri::AppHandle,
+    user: &UserSession,
+    tokens: &TokenResponse,
+) -> Result<(), String> {
+    let account_id = match session_account_id(user) {
+        Some(id) => id,
+        None => {
+            // Sin identificador no podemos separar cuentas: modo de sesión única
+            log::warn!("[Auth] Session without account id, using the shared token slot");
+            set_active_account_id(app_handle, None)?;
+            return save_tokens_to_store(app_handle, tokens).await;
+        }
+    };
 
+    let serialized = serde_json::to_string(tokens).map_err(|e| e.to_string())?;
+    token_storage::save_secret(
+        app_handle,
+        STORAGE_PATH,
+        &tokens_key(Some(&account_id)),
+        &serialized,
+    )?;
+
+    let mut sessions = load_stored_sessions(app_handle)?;
+    sessions.retain(|session| session.account_id != account_id);
+    sessions.push(StoredSession {
+        account_id: account_id.clone(),
+        user: user.clone(),
+        active: false,
+    });
+    save_stored_sessions(app_handle, &sessions)?;
+    set_active_account_id(app_handle, Some(&account_id))?;
+
+    // Los tokens de la ranura compartida ya pertenecen a esta cuenta
+    token_storage::delete_secret(app_handle, STORAGE_PATH, STORAGE_KEY_TOKENS)?;
     Ok(())
 }
 
-// Helper para cargar tokens desde el store (nueva sintaxis)
-async fn load_tokens_from_store(
+// Helper para guardar tokens de la sesión activa en el almacenamiento seguro
+async fn save_tokens_to_store(
+    app_handle: &tauri::AppHandle,
+    tokens: &TokenResponse,
+) -> Result<(), String> {
+    let key = tokens_key(active_account_id(app_handle)?.as_deref());
+    let serialized = serde_json::to_string(tokens).map_err(|e| e.to_string())?;
+    token_storage::save_secret(app_handle, STORAGE_PATH, &key, &serialized)
+}
+
+// Helper para cargar tokens de la sesión activa desde el almacenamiento seguro
+pub(crate) async fn load_tokens_from_store(
+    app_handle: &tauri::AppHandle,
+) -> Result<Option<TokenResponse>, String> {
+    if let Some(tokens) = migrate_plaintext_tokens(app_handle)? {
+        return Ok(Some(tokens));
+    }
+
+    let key = tokens_key(active_account_id(app_handle)?.as_deref());
+    match token_storage::load_secret(app_handle, STORAGE_PATH, &key)? {
+        Some(serialized) => serde_json::from_str::<TokenResponse>(&serialized)
+            .map(Some)
+            .map_err(|e| format!("Error al deserializar tokens: {}", e)),
+        None => Ok(None),
+    }
+}
+
+// Versiones anteriores guardaban los tokens en texto plano en el store:
+// se mueven al almacenamiento seguro y se borra la copia en claro.
+fn migrate_plaintext_tokens(
     app_handle: &tauri::AppHandle,
 ) -> Result<Option<TokenResponse>, String> {
     let store = app_handle
         .store(STORAGE_PATH)
         .map_err(|e| format!("Error al acceder al store: {}", e))?;
 
-    let result = if store.has(STORAGE_KEY_TOKENS) {
-        let tokens_value = store
-            .get(STORAGE_KEY_TOKENS)
-            .ok_or_else(|| "No se pudieron obtener los tokens del store".to_string())?;
+    let plaintext = store.get(STORAGE_KEY_TOKENS);
+    store.close_resource();
 
-        match serde_json::from_value::<TokenResponse>(tokens_value.clone()) {
-            Ok(tokens) => Ok(Some(tokens)),
-            Err(e) => Err(format!("Error al deserializar tokens: {}", e)),
-        }
-    } else {
-        Ok(None)
+    let tokens_value = match plaintext {
+        Some(value) => value,
+        None => return Ok(None),
     };
 
-    // Opcional: cerrar el recurso después de usarlo
+    let tokens = serde_json::from_value::<TokenResponse>(tokens_value)
+        .map_err(|e| format!("Error al deserializar tokens: {}", e));
+
+    if let Ok(tokens) = &tokens {
+        let serialized = serde_json::to_string(tokens).map_err(|e| e.to_string())?;
+        token_storage::save_secret(app_handle, STORAGE_PATH, STORAGE_KEY_TOKENS, &serialized)?;
+        log::info!("[Auth] Plaintext tokens migrated to secure storage");
+    }
+
+    // Se borre o no la migración, la copia en claro no debe quedarse en disco
+    let store = app_handle
+        .store(STORAGE_PATH)
+        .map_err(|e| format!("Error al acceder al store: {}", e))?;
+    store.delete(STORAGE_KEY_TOKENS);
+    store
+        .save()
+        .map_err(|e| format!("Error al guardar cambios en el store: {}", e))?;
     store.close_resource();
 
-    result
+    tokens.map(Some)
 }
 
-// Helper para eliminar tokens del store (nueva sintaxis)
+// Helper para eliminar los tokens de la sesión activa y sacarla de la lista
 async fn remove_tokens_from_store(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    let active = active_account_id(app_handle)?;
+    token_storage::delete_secret(app_handle, STORAGE_PATH, &tokens_key(active.as_deref()))?;
+
+    if let Some(account_id) = active {
+        let mut sessions = load_stored_sessions(app_handle)?;
+        sessions.retain(|session| session.account_id != account_id);
+        save_stored_sessions(app_handle, &sessions)?;
+        set_active_account_id(app_handle, None)?;
+    }
+
+    // Por si quedaba una copia en texto plano de versiones anteriores
     let store = app_handle
         .store(STORAGE_PATH)
         .map_err(|e| format!("Error al acceder al store: {}", e))?;
 
-    if store.has(STORAGE_KEY_TOKENS) {
-        store.delete(STORAGE_KEY_TOKENS.to_string());
+    if store.delete(STORAGE_KEY_TOKENS) {
+        store
+            .save()
+            .map_err(|e| format!("Error al guardar cambios en el store: {}", e))?;
     }
 
-    store
-        .save()
-        .map_err(|e| format!("Error al guardar cambios en el store: {}", e))?;
-
     // Opcional: cerrar el recurso después de usarlo
     store.close_resource();
 
     Ok(())
 }
 
+// Limpia tokens y sesión cuando el refresh_token ya no es válido
+pub(crate) async fn expire_session(app_handle: &tauri::AppHandle) {
+    let _ = remove_tokens_from_store(app_handle).await;
+
+    let auth_state = app_handle.state::<Arc<AuthState>>();
+    *auth_state.session.lock().await = None;
+
+    let _ = emit_event(LauncherEvent::AuthStatusChanged(None));
+}
+
+/// Renueva los tokens tras un 401 y los persiste. `failed_access_token` es el
+/// token rechazado: si otra petición ya lo renovó, se reutiliza el nuevo.
+pub(crate) async fn refresh_session_tokens(
+    app_handle: &tauri::AppHandle,
+    failed_access_token: &str,
+) -> Result<TokenResponse, String> {
+    let auth_state = app_handle.state::<Arc<AuthState>>();
+    let _refresh_guard = auth_state.refresh_lock.lock().await;
+
+    let current_tokens = load_tokens_from_store(app_handle)
+        .await?
+        .ok_or("No hay tokens para renovar")?;
+    if current_tokens.access_token != failed_access_token {
+        return Ok(current_tokens);
+    }
+
+    let refresh_endpoint = format!("{}/auth/refresh", API_ENDPOINT);
+    let resp = http_client::async_client()
+        .post(&refresh_endpoint)
+        .json(&json!({ "refresh_token": current_tokens.refresh_token }))
+        .send()
+        .await
+        .map_err(|e| format!("Error al llamar API de renovación: {}", e))?;
+
+    if !resp.status().is_success() {
+        let status = resp.status();
+        eprintln!("Error al renovar tokens: {}", status);
+        eprintln!("Cuerpo de error: {}", resp.text().await.unwrap_or_default());
+
+        // Un error del servidor no invalida la sesión; un rechazo sí
+        if !status.is_server_error() {
+            expire_session(app_handle).await;
+        }
+        return Err(format!("Error al renovar tokens: {}", status));
+    }
+
+    let new_tokens = resp
+        .json::<TokenResponse>()
+        .await
+        .map_err(|e| format!("Error al parsear tokens renovados: {}", e))?;
+
+    save_tokens_to_store(app_handle, &new_tokens)
+        .await
+        .map_err(|e| format!("Error al guardar tokens renovados: {}", e))?;
+
+    println!("Tokens renovados con éxito");
+    Ok(new_tokens)
+}
+
 // HTTP server handler for OAuth callback
 async fn handle_callback(
     req: Request<Body>,
@@ -198,14 +470,26 @@ async fn handle_callback(
 
     // Extraer el código de autorización del query string
     let query = uri.query().unwrap_or("");
-    let code = query.split('&').find_map(|pair| {
-        let mut parts = pair.splitn(2, '=');
-        if parts.next() == Some("code") {
-            parts.next().map(|v| v.to_string())
-        } else {
-            None
+    let code = query_param(query, "code");
+    let received_state = query_param(query, "state");
+
+    // Rechazar callbacks que no vienen de nuestra solicitud (CSRF / código inyectado).
+    // El servidor sigue escuchando por si llega el callback legítimo.
+    {
+        let state = app_state_mutex.lock().await;
+        if received_state.as_deref() != Some(state.expected_state.as_str()) {
+            eprintln!("OAuth Callback Error: parámetro state inválido.");
+            let _ = emit_event(LauncherEvent::AuthErrorDetails(AuthErrorEvent {
+                code: "state-mismatch".to_string(),
+                message: "Se rechazó un callback de autenticación no solicitado".to_string(),
+            }));
+            let mut response = Response::new(Body::from(
+                "Error: La solicitud de autenticación no es válida o ha expirado.",
+            ));
+            *response.status_mut() = HyperStatusCode::BAD_REQUEST;
+            return Ok(response);
         }
-    });
+    }
 
     if let Some(code_str) = code {
         // Obtener acceso al estado compartido
@@ -249,171 +533,71 @@ pub async fn init_session(
 ) -> Result<Option<UserSession>, String> {
     // Intentar cargar tokens desde el store
     match load_tokens_from_store(&app_handle).await {
-        Ok(Some(tokens)) => {
-            // Si tenemos tokens guardados, verificar la sesión del usuario
-            println!("Tokens encontrados en store, verificando sesión...");
-
-            let client = Client::new();
-            let session_endpoint = format!("{}/auth/me", API_ENDPOINT);
-
-            match client
-                .get(&session_endpoint)
-                .bearer_auth(&tokens.access_token)
-                .send()
-                .await
-            {
-                Ok(user_resp) => {
-                    if user_resp.status().is_success() {
-                        match user_resp.json::<UserSession>().await {
-                            Ok(user) => {
-                                println!("Sesión recuperada con éxito");
-                                // Guardar la sesión en memoria
-                                let mut session_guard = auth_state.session.lock().await;
-                                *session_guard = Some(user.clone());
-                                drop(session_guard);
-
-                                // Notificar al frontend
-                                let _ = emit_event("auth-status-changed", Some(user.clone()));
-
-                                return Ok(Some(user));
-                            }
-                            Err(e) => {
-                                eprintln!("Error al parsear datos de sesión: {}", e);
-                                // Si hay error de parseo, eliminar tokens
-                                let _ = remove_tokens_from_store(&app_handle).await;
-                            }
-                        }
-                    }
-                    // can't compare tauri_plugin_http::reqwest::StatusCode with hyper::StatusCode
-                    // Replace the problematic section in the init_session function with this code:
-                    else if user_resp.status() == StatusCode::UNAUTHORIZED {
-                        println!("Tokens expirados, intentando renovar...");
-
-                        // Acceder directamente al refresh_token como String
-                        let refresh_token = tokens.refresh_token.clone();
-
-                        let client = Client::new();
-                        let refresh_endpoint = format!("{}/auth/refresh", API_ENDPOINT);
-
-                        match client
-                            .post(&refresh_endpoint)
-                            .json(&json!({ "refresh_token": refresh_token }))
-                            .send()
-                            .await
-                        {
-                            Ok(resp) if resp.status().is_success() => {
-                                match resp.json::<TokenResponse>().await {
-                                    Ok(new_tokens) => {
-                                        // Guardar los nuevos tokens
-                                        if let Err(e) =
-                                            save_tokens_to_store(&app_handle, &new_tokens).await
-                                        {
-                                            eprintln!("Error al guardar tokens renovados: {}", e);
-                                            return Ok(None);
-                                        }
-
-                                        println!("Tokens renovados con éxito");
-
-                                        // Intentar nuevamente obtener la sesión con el nuevo token
-                                        let session_endpoint = format!("{}/auth/me", API_ENDPOINT);
-
-                                        match client
-                                            .get(&session_endpoint)
-                                            .bearer_auth(&new_tokens.access_token)
-                                            .send()
-                                            .await
-                                        {
-                                            Ok(new_user_resp)
-                                                if new_user_resp.status().is_success() =>
-                                            {
-                                                match new_user_resp.json::<UserSession>().await {
-                                                    Ok(user) => {
-                                                        println!("Sesión recuperada con éxito tras renovar tokens");
-                                                        // Guardar la sesión en memoria
-                                                        let mut session_guard =
-                                                            auth_state.session.lock().await;
-                                                        *session_guard = Some(user.clone());
-                                                        drop(session_guard);
-
-                                                        // Notificar al frontend
-                                                        let _ = emit_event(
-                                                            "auth-status-changed",
-                                                            Some(user.clone()),
-                                                        );
-
-                                                        return Ok(Some(user));
-                                                    }
-                                                    Err(e) => {
-                                                        eprintln!("Error al parsear datos de sesión tras renovar: {}", e);
-                                                        let _ =
-                                                            remove_tokens_from_store(&app_handle)
-                                                                .await;
-                                                    }
-                                                }
-                                            }
-                                            Ok(_) => {
-                                                eprintln!("Error al verificar sesión con tokens renovados");
-                                                let _ = remove_tokens_from_store(&app_handle).await;
-                                            }
-                                            Err(e) => {
-                                                eprintln!("Error al contactar API tras renovar tokens: {}", e);
-                                                let _ = remove_tokens_from_store(&app_handle).await;
-                                            }
-                                        }
-                                    }
-                                    Err(e) => {
-                                        eprintln!(
-                                            "Error al parsear respuesta de tokens renovados: {}",
-                                            e
-                                        );
-                                        let _ = remove_tokens_from_store(&app_handle).await;
-                                    }
-                                }
-                            }
-                            Ok(resp) => {
-                                eprintln!("Error al renovar tokens: {}", resp.status());
-                                eprintln!("Cuerpo de error: {}", resp.text().await.unwrap_or_default());
-                                let _ = remove_tokens_from_store(&app_handle).await;
-                            }
-                            Err(e) => {
-                                eprintln!("Error al contactar API para renovación: {}", e);
-                                let _ = remove_tokens_from_store(&app_handle).await;
-                            }
-                        }
-                    } else {
-                        let status_code = user_resp.status();
-                        eprintln!("Error al verificar sesión: {}", status_code);
-
-                        if status_code.is_server_error() {
-                            log::error!("Error del servidor: {}", status_code);
-                            // Don't remove tokens, just log the error
-                           emit_event::<String>(
-                                "auth-error",
-                                Some(format!("Error del servidor: {}", status_code)),
-                            )?;
-                        } else {
-                            // Si no es un error del servidor, eliminar tokens
-                            let _ = remove_tokens_from_store(&app_handle).await;
-                            eprintln!("Tokens inválidos, eliminando...");
-                            let _ = emit_event("auth-status-changed", Option::<UserSession>::None);
-                            return Ok(None);
-                        }
-                        
-                    }
-                }
-                Err(e) => {
-                    eprintln!("Error al contactar API: {}", e);
-                }
-            }
-        }
+        Ok(Some(_)) => println!("Tokens encontrados en store, verificando sesión..."),
         Ok(None) => {
             println!("No hay tokens guardados");
+            return Ok(None);
         }
         Err(e) => {
             eprintln!("Error al cargar tokens: {}", e);
+            return Ok(None);
         }
     }
 
+    // El cliente renueva los tokens y reintenta si la API responde 401
+    let user_resp = match ApiClient::new(app_handle.clone()).get("/auth/me").await {
+        Ok(resp) => resp,
+        Err(e) => {
+            eprintln!("Error al verificar sesión: {}", e);
+            return Ok(None);
+        }
+    };
+
+    let status_code = user_resp.status();
+    if status_code.is_success() {
+        match user_resp.json::<UserSession>().await {
+            Ok(user) => {
+                println!("Sesión recuperada con éxito");
+
+                // Refrescar la copia guardada (y migrar sesiones de versiones
+                // con una única cuenta a su clave propia)
+                if let Ok(Some(tokens)) = load_tokens_from_store(&app_handle).await {
+                    if let Err(e) = register_session(&app_handle, &user, &tokens).await {
+                        eprintln!("Error al registrar la sesión: {}", e);
+                    }
+                }
+
+                // Guardar la sesión en memoria
+                let mut session_guard = auth_state.session.lock().await;
+                *session_guard = Some(user.clone());
+                drop(session_guard);
+
+                // Notificar al frontend
+                let _ = emit_event(LauncherEvent::AuthStatusChanged(Some(user.clone())));
+
+                return Ok(Some(user));
+            }
+            Err(e) => {
+                eprintln!("Error al parsear datos de sesión: {}", e);
+                // Si hay error de parseo, eliminar tokens
+                let _ = remove_tokens_from_store(&app_handle).await;
+            }
+        }
+    } else if status_code.is_server_error() {
+        log::error!("Error del servidor: {}", status_code);
+        // Don't remove tokens, just log the error
+        emit_event(LauncherEvent::AuthError(format!(
+            "Error del servidor: {}",
+            status_code
+        )))?;
+    } else {
+        // Incluye un 401 que persiste tras renovar los tokens
+        eprintln!("Error al verificar sesión: {}", status_code);
+        let _ = remove_tokens_from_store(&app_handle).await;
+        eprintln!("Tokens inválidos, eliminando...");
+        let _ = emit_event(LauncherEvent::AuthStatusChanged(None));
+    }
+
     Ok(None)
 }
 
@@ -430,7 +614,7 @@ pub async fn start_discord_auth(
     app_handle: tauri::AppHandle,
     auth_state: State<'_, Arc<AuthState>>,
 ) -> Result<(), String> {
-    emit_event("auth-step-changed", Some(AuthStep::StartingAuth))?;
+    emit_event(LauncherEvent::AuthStepChanged(AuthStep::StartingAuth))?;
 
     // Limpiar código de autorización previo
     let mut auth_code_guard = auth_state.auth_code.lock().await;
@@ -441,13 +625,32 @@ pub async fn start_discord_auth(
 
     // Crear estado compartido para los manejadores HTTP
     let shared_auth_state = Arc::clone(auth_state.inner());
+    let oauth_state = random_token();
+    let code_verifier = random_token();
     let app_state_mutex = Arc::new(Mutex::new(AppState {
         auth_state: shared_auth_state,
         server_tx: Some(shutdown_tx),
+        expected_state: oauth_state.clone(),
     }));
 
     // Configurar y iniciar el servidor Hyper
-    let addr = SocketAddr::from(([127, 0, 0, 1], 1957));
+    let listener = match bind_callback_listener().and_then(|listener| {
+        listener.set_nonblocking(true).map_err(|e| e.to_string())?;
+        Ok(listener)
+    }) {
+        Ok(listener) => listener,
+        Err(message) => {
+            eprintln!("{}", message);
+            let _ = emit_event(LauncherEvent::AuthErrorDetails(AuthErrorEvent {
+                code: "callback-port-unavailable".to_string(),
+                message: message.clone(),
+            }));
+            let _ = emit_event(LauncherEvent::AuthError(message.clone()));
+            return Err(message);
+        }
+    };
+    let addr = listener.local_addr().map_err(|e| e.to_string())?;
+    let callback_uri = redirect_uri(addr.port());
 
     let app_state_mutex_clone = app_state_mutex.clone();
     let make_svc = make_service_fn(move |_conn| {
@@ -459,7 +662,8 @@ pub async fn start_discord_auth(
         }
     });
 
-    let server = Server::bind(&addr)
+    let server = Server::from_tcp(listener)
+        .map_err(|e| format!("Error al iniciar el servidor de callback: {}", e))?
         .serve(make_svc)
         .with_graceful_shutdown(async {
             shutdown_rx.await.ok();
@@ -471,14 +675,20 @@ pub async fn start_discord_auth(
         println!("Servidor de callback escuchando en http://{}", addr);
         if let Err(e) = server.await {
             eprintln!("Error del servidor: {}", e);
-            let _ = emit_event::<String>("auth-error", Some(format!("Error del servidor: {}", e)));
+            let _ = emit_event(LauncherEvent::AuthError(format!(
+                "Error del servidor: {}",
+                e
+            )));
         }
     });
 
     // Abrir URL de autenticación de Discord en el navegador
     let discord_url = format!(
-        "https://discord.com/api/oauth2/authorize?client_id={}&response_type=code&scope=identify%20email%20guilds&redirect_uri={}",
-        CLIENT_ID, REDIRECT_URI
+        "https://discord.com/api/oauth2/authorize?client_id={}&response_type=code&scope=identify%20email%20guilds&redirect_uri={}&state={}&code_challenge={}&code_challenge_method=S256",
+        CLIENT_ID,
+        encode_query_value(&callback_uri),
+        oauth_state,
+        pkce_challenge(&code_verifier)
     );
 
     println!("Abriendo URL de autenticación: {}", discord_url);
@@ -487,11 +697,12 @@ pub async fn start_discord_auth(
         "Error al abrir URL de autenticación".to_string()
     })?;
 
-    emit_event("auth-step-changed", Some(AuthStep::WaitingCallback))?;
+    emit_event(LauncherEvent::AuthStepChanged(AuthStep::WaitingCallback))?;
 
     // Clonar los handles necesarios para la tarea de polling
     let auth_state_clone = Arc::clone(auth_state.inner());
     let app_handle_clone = app_handle.clone();
+    let callback_uri_clone = callback_uri.clone();
 
     // Tarea para esperar el código de autorización y procesarlo
     tokio::spawn(async move {
@@ -506,7 +717,7 @@ pub async fn start_discord_auth(
 
             if let Some(code) = code_option {
                 println!("Código de autenticación recibido. Procesando...");
-                let _ = emit_event("auth-step-changed", Some(AuthStep::ProcessingCallback));
+                let _ = emit_event(LauncherEvent::AuthStepChanged(AuthStep::ProcessingCallback));
 
                 // Enfocar la ventana principal
                 if let Some(main_window) = app_handle_clone.get_webview_window("main") {
@@ -516,12 +727,23 @@ pub async fn start_discord_auth(
                 }
 
                 // Intercambiar código por tokens
-                let client = Client::new();
-                let token_endpoint =
-                    format!("{}/auth/discord/callback?code={}", API_ENDPOINT, code);
+                let client = http_client::async_client();
+                // El backend necesita el mismo redirect_uri para canjear el código
+                // y el code_verifier para completar PKCE; van en el cuerpo para
+                // que no acaben en ningún log de URLs
+                let token_endpoint = format!("{}/auth/discord/callback", API_ENDPOINT);
                 println!("Solicitando tokens desde: {}", token_endpoint);
 
-                match client.get(&token_endpoint).send().await {
+                match client
+                    .post(&token_endpoint)
+                    .json(&json!({
+                        "code": code,
+                        "redirect_uri": callback_uri_clone,
+                        "code_verifier": code_verifier,
+                    }))
+                    .send()
+                    .await
+                {
                     Ok(resp) => {
                         if !resp.status().is_success() {
                             let status = resp.status();
@@ -529,7 +751,7 @@ pub async fn start_discord_auth(
                                 "No se pudo leer el cuerpo del error".to_string()
                             });
                             eprintln!("Error de API de tokens: {} - {}", status, error_body);
-                            let _ = emit_event::<String>("auth-error", Some(error_body));
+                            let _ = emit_event(LauncherEvent::AuthError(error_body));
                             return;
                         }
 
@@ -537,19 +759,10 @@ pub async fn start_discord_auth(
                             Ok(tokens) => {
                                 println!("Tokens recibidos correctamente.");
 
-                                // Guardar tokens en el store
-                                if let Err(e) =
-                                    save_tokens_to_store(&app_handle_clone, &tokens).await
-                                {
-                                    eprintln!("Error al guardar tokens: {}", e);
-                                    // Continuar a pesar del error para intentar completar el flujo
-                                }
-
                                 // Solicitar sesión de usuario
-                                let _ = emit_event(
-                                    "auth-step-changed",
-                                    Some(AuthStep::RequestingSession),
-                                );
+                                let _ = emit_event(LauncherEvent::AuthStepChanged(
+                                    AuthStep::RequestingSession,
+                                ));
                                 let session_endpoint = format!("{}/auth/me", API_ENDPOINT);
                                 println!(
                                     "Solicitando sesión de usuario desde: {}",
@@ -574,13 +787,10 @@ pub async fn start_discord_auth(
                                                 "Error de API de sesión: {} - {}",
                                                 status, error_body
                                             );
-                                            let _ = emit_event::<String>(
-                                                "auth-error",
-                                                Some(format!(
-                                                    "Error de API de sesión: {} - {}",
-                                                    status, error_body
-                                                )),
-                                            );
+                                            let _ = emit_event(LauncherEvent::AuthError(format!(
+                                                "Error de API de sesión: {} - {}",
+                                                status, error_body
+                                            )));
                                             return;
                                         }
 
@@ -591,6 +801,18 @@ pub async fn start_discord_auth(
                                                     user.extra
                                                 );
 
+                                                // Guardar tokens con la clave de esta cuenta
+                                                if let Err(e) = register_session(
+                                                    &app_handle_clone,
+                                                    &user,
+                                                    &tokens,
+                                                )
+                                                .await
+                                                {
+                                                    eprintln!("Error al guardar tokens: {}", e);
+                                                    // Continuar a pesar del error para intentar completar el flujo
+                                                }
+
                                                 // Guardar sesión
                                                 {
                                                     let mut session_guard =
@@ -599,8 +821,9 @@ pub async fn start_discord_auth(
                                                 }
 
                                                 // Notificar éxito con datos de usuario
-                                                let _ =
-                                                    emit_event("auth-status-changed", Some(user));
+                                                let _ = emit_event(
+                                                    LauncherEvent::AuthStatusChanged(Some(user)),
+                                                );
                                                 return;
                                             }
                                             Err(e) => {
@@ -608,40 +831,39 @@ pub async fn start_discord_auth(
                                                     "Error al parsear sesión de usuario: {}",
                                                     e
                                                 );
-                                                let _ = emit_event::<String>(
-                                                    "auth-error",
-                                                    Some(format!("Error al parsear sesión: {}", e)),
-                                                );
+                                                let _ = emit_event(LauncherEvent::AuthError(
+                                                    format!("Error al parsear sesión: {}", e),
+                                                ));
                                                 return;
                                             }
                                         }
                                     }
                                     Err(e) => {
                                         eprintln!("Error al solicitar sesión de usuario: {}", e);
-                                        let _ = emit_event::<String>(
-                                            "auth-error",
-                                            Some(format!("Error al solicitar sesión: {}", e)),
-                                        );
+                                        let _ = emit_event(LauncherEvent::AuthError(format!(
+                                            "Error al solicitar sesión: {}",
+                                            e
+                                        )));
                                         return;
                                     }
                                 }
                             }
                             Err(e) => {
                                 eprintln!("Error al parsear respuesta de tokens: {}", e);
-                                let _ = emit_event::<String>(
-                                    "auth-error",
-                                    Some(format!("Error al parsear tokens: {}", e)),
-                                );
+                                let _ = emit_event(LauncherEvent::AuthError(format!(
+                                    "Error al parsear tokens: {}",
+                                    e
+                                )));
                                 return;
                             }
                         }
                     }
                     Err(e) => {
                         eprintln!("Error al llamar API de tokens: {}", e);
-                        let _ = emit_event::<String>(
-                            "auth-error",
-                            Some(format!("Error al llamar API de tokens: {}", e)),
-                        );
+                        let _ = emit_event(LauncherEvent::AuthError(format!(
+                            "Error al llamar API de tokens: {}",
+                            e
+                        )));
                         return;
                     }
                 }
@@ -662,7 +884,9 @@ pub async fn start_discord_auth(
             "Autenticación expiró después de {} segundos.",
             MAX_WAIT_SECS
         );
-        let _ = emit_event::<String>("auth-error", Some("Timeout de autenticación".to_string()));
+        let _ = emit_event(LauncherEvent::AuthError(
+            "Timeout de autenticación".to_string(),
+        ));
 
         // Asegurar que el servidor se apague si hay timeout antes del callback
         let mut state = app_state_mutex.lock().await;
@@ -715,7 +939,7 @@ pub async fn logout(
         let logout_endpoint = format!("{}/logout", API_ENDPOINT);
         println!("Llamando logout del backend: {}", logout_endpoint);
 
-        match Client::new()
+        match http_client::async_client()
             .post(&logout_endpoint)
             .bearer_auth(&tokens.access_token)
             .send()
@@ -737,17 +961,14 @@ pub async fn logout(
     }
 
     // Notificar al frontend
-    emit_event("auth-status-changed", Option::<UserSession>::None)?;
+    emit_event(LauncherEvent::AuthStatusChanged(None))?;
     println!("Logout completo.");
     Ok(())
 }
 
 // Opcional: función para verificar la validez de los tokens y renovarlos si es necesario
 #[tauri::command]
-pub async fn refresh_tokens(
-    app_handle: tauri::AppHandle,
-    auth_state: State<'_, Arc<AuthState>>,
-) -> Result<bool, String> {
+pub async fn refresh_tokens(app_handle: tauri::AppHandle) -> Result<bool, String> {
     // Cargar tokens del store
     let current_tokens = match load_tokens_from_store(&app_handle).await {
         Ok(Some(tokens)) => tokens,
@@ -755,48 +976,70 @@ pub async fn refresh_tokens(
         Err(e) => return Err(e),
     };
 
-    // Lógica para renovar tokens (depende de tu API)
-    let client = Client::new();
-    let refresh_endpoint = format!("{}/auth/refresh", API_ENDPOINT);
+    refresh_session_tokens(&app_handle, &current_tokens.access_token).await?;
+    Ok(true)
+}
 
-    match client
-        .post(&refresh_endpoint)
-        .json(&json!({
-            "refresh_token": current_tokens.refresh_token
-        }))
-        .send()
-        .await
-    {
-        Ok(resp) => {
-            if resp.status().is_success() {
-                match resp.json::<TokenResponse>().await {
-                    Ok(new_tokens) => {
-                        // Guardar nuevos tokens
-                        if let Err(e) = save_tokens_to_store(&app_handle, &new_tokens).await {
-                            return Err(format!("Error al guardar tokens renovados: {}", e));
-                        }
+#[tauri::command]
+pub async fn list_sessions(app_handle: tauri::AppHandle) -> Result<Vec<StoredSession>, String> {
+    let active = active_account_id(&app_handle)?;
+    let mut sessions = load_stored_sessions(&app_handle)?;
+    for session in &mut sessions {
+        session.active = active.as_deref() == Some(session.account_id.as_str());
+    }
+    Ok(sessions)
+}
 
-                        println!("Tokens renovados exitosamente");
-                        Ok(true)
-                    }
-                    Err(e) => Err(format!("Error al parsear tokens renovados: {}", e)),
-                }
-            } else {
-                // Si hay error en la renovación, limpiar tokens
-                let _ = remove_tokens_from_store(&app_handle).await;
+#[tauri::command]
+pub async fn switch_session(
+    app_handle: tauri::AppHandle,
+    auth_state: State<'_, Arc<AuthState>>,
+    account_id: String,
+) -> Result<UserSession, String> {
+    let sessions = load_stored_sessions(&app_handle)?;
+    if !sessions
+        .iter()
+        .any(|session| session.account_id == account_id)
+    {
+        return Err("No existe una sesión guardada para esa cuenta".to_string());
+    }
 
-                // Limpiar sesión
-                let mut session_guard = auth_state.session.lock().await;
-                *session_guard = None;
+    let previous = active_account_id(&app_handle)?;
+    set_active_account_id(&app_handle, Some(&account_id))?;
 
-                // Notificar cambio de estado al frontend
-                let _ = emit_event("auth-status-changed", Option::<UserSession>::None);
+    let result = match ApiClient::new(app_handle.clone()).get("/auth/me").await {
+        Ok(resp) if resp.status().is_success() => resp
+            .json::<UserSession>()
+            .await
+            .map_err(|e| format!("Error al parsear datos de sesión: {}", e)),
+        Ok(resp) => Err(format!("La sesión ya no es válida: {}", resp.status())),
+        Err(e) => Err(e),
+    };
 
-                Err(format!("Error al renovar tokens: {}", resp.status()))
-            }
+    let user = match result {
+        Ok(user) => user,
+        Err(e) => {
+            // Volver a la cuenta anterior; si la nueva expiró ya se eliminó de la lista
+            set_active_account_id(&app_handle, previous.as_deref())?;
+            let previous_user = previous.as_ref().and_then(|id| {
+                sessions
+                    .iter()
+                    .find(|session| &session.account_id == id)
+                    .map(|session| session.user.clone())
+            });
+            *auth_state.session.lock().await = previous_user.clone();
+            let _ = emit_event(LauncherEvent::AuthStatusChanged(previous_user));
+            return Err(e);
         }
-        Err(e) => Err(format!("Error al llamar API de renovación: {}", e)),
+    };
+
+    if let Ok(Some(tokens)) = load_tokens_from_store(&app_handle).await {
+        register_session(&app_handle, &user, &tokens).await?;
     }
+
+    *auth_state.session.lock().await = Some(user.clone());
+    emit_event(LauncherEvent::AuthStatusChanged(Some(user.clone())))?;
+    Ok(user)
 }
 
 // Función para registrar el estado de autenticación en main.rs
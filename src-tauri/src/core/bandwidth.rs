@@ -0,0 +1,76 @@
+//! Global download speed limit (`bandwidthLimit`).
+
+use crate::config::{with_config, ConfigManager};
+use once_cell::sync::Lazy;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Ajustes que obligan a recalcular el límite
+pub const BANDWIDTH_CONFIG_KEYS: &[&str] = &["bandwidthLimit"];
+
+/// Bytes por segundo; 0 = sin límite
+static LIMIT: AtomicU64 = AtomicU64::new(0);
+
+/// Bytes que aún se pueden escribir sin esperar; negativo si hay deuda
+struct Bucket {
+    available: f64,
+    refilled_at: Instant,
+}
+
+static BUCKET: Lazy<Mutex<Bucket>> = Lazy::new(|| {
+    Mutex::new(Bucket {
+        available: 0.0,
+        refilled_at: Instant::now(),
+    })
+});
+
+/// Rebuilds the snapshot from `config`.
+pub fn reload(config: &ConfigManager) {
+    let limit_kbps = config.get_bandwidth_limit_kbps();
+    match limit_kbps {
+        Some(limit) => log::info!("[Bandwidth] Download limit: {} KB/s", limit),
+        None => log::info!("[Bandwidth] No download limit"),
+    }
+    LIMIT.store(
+        limit_kbps.map_or(0, |limit| limit * 1024),
+        Ordering::Relaxed,
+    );
+}
+
+/// Loads the snapshot at startup.
+pub fn init() {
+    if let Err(e) = with_config(reload) {
+        log::warn!("[Bandwidth] Config unavailable, downloads unlimited: {}", e);
+    }
+}
+
+/// Accounts for `bytes` just downloaded, sleeping as long as the downloads
+/// are ahead of the limit.
+pub fn throttle(bytes: usize) {
+    let limit = LIMIT.load(Ordering::Relaxed);
+    if limit == 0 || bytes == 0 {
+        return;
+    }
+    let limit = limit as f64;
+
+    let wait = {
+        let mut bucket = BUCKET.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        // Como mucho un segundo de ráfaga acumulada
+        let refill = now.duration_since(bucket.refilled_at).as_secs_f64() * limit;
+        bucket.available = (bucket.available + refill).min(limit);
+        bucket.refilled_at = now;
+        bucket.available -= bytes as f64;
+        if bucket.available < 0.0 {
+            Duration::from_secs_f64(-bucket.available / limit)
+        } else {
+            Duration::ZERO
+        }
+    };
+
+    if !wait.is_zero() {
+        thread::sleep(wait);
+    }
+}
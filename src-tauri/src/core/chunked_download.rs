@@ -0,0 +1,232 @@
+//! Parallel ranged downloads for large files.
+
+use crate::config::{with_config, ConfigManager};
+use crate::core::download_cache::{self, HashAlgo};
+use crate::core::transfer_stats::{TrackedWriter, TransferTracker};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::thread;
+use tauri_plugin_http::reqwest::blocking::{Client, Response};
+use tauri_plugin_http::reqwest::header::{ACCEPT_RANGES, RANGE};
+use tauri_plugin_http::reqwest::StatusCode;
+
+/// Reintentos por fragmento antes de abandonar la descarga
+const CHUNK_RETRIES: u32 = 3;
+/// Por debajo de este tamaño no compensa abrir otra conexión
+const MIN_CHUNK_BYTES: u64 = 4 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy)]
+pub struct ChunkSettings {
+    /// `None` cuando las descargas por fragmentos están desactivadas
+    pub threshold_bytes: Option<u64>,
+    pub connections: usize,
+}
+
+impl ChunkSettings {
+    pub fn from_config(config: &ConfigManager) -> Self {
+        Self {
+            threshold_bytes: config
+                .get_chunked_download_threshold_mb()
+                .map(|mb| mb * 1024 * 1024),
+            connections: config.get_chunked_download_connections(),
+        }
+    }
+
+    /// Reads the settings, falling back to single-stream downloads if the
+    /// config is unavailable. Must not be called while holding its lock.
+    pub fn load() -> Self {
+        with_config(Self::from_config).ok().unwrap_or(Self {
+            threshold_bytes: None,
+            connections: 1,
+        })
+    }
+
+    /// Total size when `response` is worth splitting.
+    pub fn split_length(&self, response: &Response) -> Option<u64> {
+        let threshold = self.threshold_bytes?;
+        let accepts_ranges = response
+            .headers()
+            .get(ACCEPT_RANGES)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.eq_ignore_ascii_case("bytes"));
+
+        response
+            .content_length()
+            .filter(|length| accepts_ranges && self.connections > 1 && *length >= threshold)
+    }
+}
+
+/// `(start, end)` inclusive byte ranges covering `total`.
+fn ranges(total: u64, connections: usize) -> Vec<(u64, u64)> {
+    let count = (total / MIN_CHUNK_BYTES).clamp(1, connections as u64);
+    let size = total.div_ceil(count);
+    (0..count)
+        .map(|index| index * size)
+        .take_while(|start| *start < total)
+        .map(|start| (start, (start + size).min(total) - 1))
+        .collect()
+}
+
+fn fetch_range(
+    client: &Client,
+    url: &str,
+    part: &Path,
+    start: u64,
+    end: u64,
+    tracker: Option<&TransferTracker>,
+) -> Result<(), String> {
+    let response = client
+        .get(url)
+        .header(RANGE, format!("bytes={}-{}", start, end))
+        .send()
+        .map_err(|e| format!("Download error: {}", e))?;
+    if response.status() != StatusCode::PARTIAL_CONTENT {
+        return Err(format!(
+            "Range request answered with status {}",
+            response.status()
+        ));
+    }
+
+    let file = OpenOptions::new()
+        .write(true)
+        .open(part)
+        .map_err(|e| format!("Error opening file: {}", e))?;
+    (&file)
+        .seek(SeekFrom::Start(start))
+        .map_err(|e| format!("Error writing file: {}", e))?;
+
+    let expected = end - start + 1;
+    let mut writer = TrackedWriter::new(&file, tracker);
+    let written = io::copy(&mut response.take(expected), &mut writer)
+        .map_err(|e| format!("Error writing file: {}", e))?;
+    if written != expected {
+        return Err(format!(
+            "Incomplete range {}-{}: {} of {} bytes",
+            start, end, written, expected
+        ));
+    }
+    Ok(())
+}
+
+fn fetch_range_with_retries(
+    client: &Client,
+    url: &str,
+    part: &Path,
+    start: u64,
+    end: u64,
+    tracker: Option<&TransferTracker>,
+) -> Result<(), String> {
+    let mut last_error = String::new();
+    for attempt in 1..=CHUNK_RETRIES {
+        match fetch_range(client, url, part, start, end, tracker) {
+            Ok(()) => return Ok(()),
+            Err(e) => {
+                log::warn!(
+                    "[ChunkedDownload] Range {}-{} of {} failed (attempt {}/{}): {}",
+                    start,
+                    end,
+                    url,
+                    attempt,
+                    CHUNK_RETRIES,
+                    e
+                );
+                last_error = e;
+            }
+        }
+    }
+    Err(last_error)
+}
+
+fn verify_hash(path: &Path, expected: &str) -> Result<(), String> {
+    let Some(algo) = HashAlgo::for_hash(expected) else {
+        return Ok(());
+    };
+    let actual = download_cache::file_hash(path, algo)
+        .ok_or_else(|| format!("Error reading {}", path.display()))?;
+    if !actual.eq_ignore_ascii_case(expected) {
+        return Err(format!(
+            "Hash mismatch for {}: expected {}, got {}",
+            path.display(),
+            expected,
+            actual
+        ));
+    }
+    Ok(())
+}
+
+fn part_path(destination: &Path) -> PathBuf {
+    let mut name = destination.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    destination.with_file_name(name)
+}
+
+fn download_to_part(
+    client: &Client,
+    url: &str,
+    part: &Path,
+    total: u64,
+    connections: usize,
+    tracker: Option<&TransferTracker>,
+) -> Result<(), String> {
+    let file = fs::File::create(part).map_err(|e| format!("Error creating file: {}", e))?;
+    file.set_len(total)
+        .map_err(|e| format!("Error allocating file: {}", e))?;
+    drop(file);
+
+    let ranges = ranges(total, connections);
+    log::info!(
+        "[ChunkedDownload] {} ({} bytes) in {} ranges",
+        url,
+        total,
+        ranges.len()
+    );
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = ranges
+            .iter()
+            .map(|&(start, end)| {
+                scope
+                    .spawn(move || fetch_range_with_retries(client, url, part, start, end, tracker))
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|_| Err("Download thread panicked".to_string()))
+            })
+            .collect::<Vec<_>>()
+    })
+    .into_iter()
+    .collect()
+}
+
+/// Downloads `total` bytes of `url` with up to `connections` parallel range
+/// requests and moves the result to `destination` once verified. Every range
+/// reports its bytes to `tracker`.
+pub fn download(
+    client: &Client,
+    url: &str,
+    destination: &Path,
+    total: u64,
+    connections: usize,
+    expected_hash: Option<&str>,
+    tracker: Option<&TransferTracker>,
+) -> Result<(), String> {
+    let part = part_path(destination);
+    let result = download_to_part(client, url, &part, total, connections, tracker)
+        .and_then(|_| match expected_hash {
+            Some(expected) => verify_hash(&part, expected),
+            None => Ok(()),
+        })
+        .and_then(|_| {
+            fs::rename(&part, destination).map_err(|e| format!("Error moving file: {}", e))
+        });
+
+    if result.is_err() {
+        let _ = fs::remove_file(&part);
+    }
+    result
+}
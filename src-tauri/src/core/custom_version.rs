@@ -0,0 +1,301 @@
+//! Custom version JSONs ("advanced" instances).
+
+use crate::config::read_config;
+use crate::core::download_cache::{self, HashAlgo};
+use crate::core::http_client;
+use crate::core::inflight_downloads;
+use crate::core::instance_manager::{get_instance_by_id, update_instance_by_id};
+use crate::core::minecraft::manifest::InheritanceChain;
+use crate::core::minecraft::paths::{self, MinecraftPaths};
+use crate::core::minecraft::{MavenCoordinate, RuleEvaluator};
+use crate::core::minecraft_instance::MinecraftInstance;
+use crate::core::mirrors;
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use tauri_plugin_http::reqwest;
+
+/// Repositorio de las librerías que no declaran `url`
+const DEFAULT_LIBRARY_REPO: &str = "https://libraries.minecraft.net/";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomVersionInfo {
+    pub instance_id: String,
+    pub version_id: String,
+    pub inherits_from: Vec<String>,
+    pub main_class: String,
+    pub libraries: usize,
+    pub downloaded_libraries: usize,
+    /// Se copió un jar propio a `versions/<id>/`
+    pub has_jar: bool,
+}
+
+struct LibraryDownload {
+    name: String,
+    target: PathBuf,
+    url: String,
+    sha1: Option<String>,
+}
+
+fn read_json(path: &Path) -> Result<Value, String> {
+    let data = fs::read_to_string(path)
+        .map_err(|e| format!("No se pudo leer {}: {}", path.display(), e))?;
+    serde_json::from_str(&data).map_err(|e| format!("JSON de versión inválido: {}", e))
+}
+
+/// Checks the fields the launcher relies on and returns the version id.
+fn validate(manifest: &Value) -> Result<String, String> {
+    let id = manifest
+        .get("id")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .ok_or_else(|| "El JSON de versión no tiene id".to_string())?;
+    // El id acaba siendo el nombre de la carpeta en versions/
+    if id.contains(['/', '\\']) || id == "." || id == ".." {
+        return Err(format!("Id de versión no válido: {}", id));
+    }
+
+    if let Some(libraries) = manifest.get("libraries") {
+        let libraries = libraries
+            .as_array()
+            .ok_or_else(|| "`libraries` debe ser una lista".to_string())?;
+        for library in libraries {
+            let name = library.get("name").and_then(Value::as_str).unwrap_or("");
+            let has_path = library
+                .pointer("/downloads/artifact/path")
+                .and_then(Value::as_str)
+                .is_some();
+            if !has_path && MavenCoordinate::parse(name).is_none() {
+                return Err(format!("Librería no válida en el JSON: {:?}", name));
+            }
+        }
+    }
+
+    Ok(id.to_string())
+}
+
+/// Libraries of the JSON that apply on this machine, with where they go and
+/// where they come from. Entries with an empty URL are skipped (the loader
+/// generates them).
+fn library_downloads(manifest: &Value, libraries_dir: &Path) -> Vec<LibraryDownload> {
+    let Some(libraries) = manifest.get("libraries").and_then(Value::as_array) else {
+        return Vec::new();
+    };
+
+    libraries
+        .iter()
+        .filter(|library| RuleEvaluator::applies(library, None))
+        .filter_map(|library| {
+            let name = library
+                .get("name")
+                .and_then(Value::as_str)
+                .unwrap_or("")
+                .to_string();
+            let artifact = library.pointer("/downloads/artifact");
+            let artifact_path = artifact
+                .and_then(|artifact| artifact.get("path"))
+                .and_then(Value::as_str);
+
+            let (relative, url) = match artifact_path {
+                Some(path) => {
+                    let url = artifact
+                        .and_then(|artifact| artifact.get("url"))
+                        .and_then(Value::as_str)
+                        .unwrap_or("")
+                        .to_string();
+                    (path.to_string(), url)
+                }
+                None => {
+                    let coordinate = MavenCoordinate::parse(&name)?;
+                    let repo = library
+                        .get("url")
+                        .and_then(Value::as_str)
+                        .unwrap_or(DEFAULT_LIBRARY_REPO);
+                    let repo = repo.trim_end_matches('/');
+                    let relative = coordinate.url_path();
+                    let url = format!("{}/{}", repo, relative);
+                    (relative, url)
+                }
+            };
+            if url.is_empty() {
+                return None;
+            }
+
+            Some(LibraryDownload {
+                target: paths::join_relative(libraries_dir, &relative),
+                sha1: artifact
+                    .and_then(|artifact| artifact.get("sha1"))
+                    .and_then(Value::as_str)
+                    .map(str::to_lowercase),
+                name,
+                url,
+            })
+        })
+        .collect()
+}
+
+/// Downloads one library unless it is already there. Returns whether it had
+/// to be fetched.
+fn download_library(
+    client: &reqwest::blocking::Client,
+    library: &LibraryDownload,
+) -> Result<bool, String> {
+    let matches = |path: &Path| match &library.sha1 {
+        Some(sha1) => {
+            download_cache::file_hash(path, HashAlgo::Sha1).as_deref() == Some(sha1.as_str())
+        }
+        None => path.is_file(),
+    };
+    if matches(&library.target) {
+        return Ok(false);
+    }
+    if let Some(sha1) = &library.sha1 {
+        if download_cache::restore(sha1, &library.target) && matches(&library.target) {
+            return Ok(true);
+        }
+    }
+
+    log::info!(
+        "[CustomVersion] Downloading {} from {}",
+        library.name,
+        library.url
+    );
+    inflight_downloads::download(&library.target, library.sha1.as_deref(), |temp| {
+        let mut response = mirrors::get(client, &library.url)
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| format!("Error al descargar {}: {}", library.name, e))?;
+        let mut file = fs::File::create(temp)
+            .map_err(|e| format!("Error al crear {}: {}", temp.display(), e))?;
+        io::copy(&mut response, &mut file)
+            .map_err(|e| format!("Error al descargar {}: {}", library.name, e))?;
+        drop(file);
+
+        if !matches(temp) {
+            return Err(format!(
+                "La librería {} descargada no coincide con su sha1",
+                library.name
+            ));
+        }
+        Ok(())
+    })?;
+    if let Some(sha1) = &library.sha1 {
+        download_cache::store(&library.target, sha1);
+    }
+    Ok(true)
+}
+
+fn instance_paths(instance: &MinecraftInstance) -> Result<MinecraftPaths, String> {
+    let config_manager = read_config();
+    let config = config_manager.as_ref().map_err(|e| e.clone())?;
+    MinecraftPaths::new(instance, config)
+        .ok_or_else(|| "No se pudieron resolver las rutas de la instancia".to_string())
+}
+
+fn import(
+    instance_id: &str,
+    json_path: &Path,
+    jar_path: Option<&Path>,
+) -> Result<CustomVersionInfo, String> {
+    let instance = get_instance_by_id(instance_id.to_string())?
+        .ok_or_else(|| format!("Instance with ID {} not found", instance_id))?;
+    let paths = instance_paths(&instance)?;
+
+    let manifest = read_json(json_path)?;
+    let version_id = validate(&manifest)?;
+    if manifest.get("inheritsFrom").is_none() && jar_path.is_none() {
+        return Err(
+            "Una versión que no hereda de otra necesita su propio jar de cliente".to_string(),
+        );
+    }
+
+    // La cadena de herencia tiene que resolverse antes de tocar nada
+    let chain = InheritanceChain::resolve(paths.game_dir(), &version_id, manifest.clone())?;
+    let inherits_from = chain.ids();
+    let merged = chain.merge_with_report().0;
+    let main_class = merged
+        .get("mainClass")
+        .and_then(Value::as_str)
+        .filter(|main_class| !main_class.is_empty())
+        .ok_or_else(|| "La versión no define mainClass".to_string())?
+        .to_string();
+
+    let downloads = library_downloads(&manifest, &paths.libraries_dir());
+    let client = http_client::blocking_client();
+    let mut downloaded_libraries = 0;
+    for library in &downloads {
+        if download_library(&client, library)? {
+            downloaded_libraries += 1;
+        }
+    }
+
+    let version_file = paths::version_file(paths.game_dir(), &version_id, "json");
+    if let Some(parent) = version_file.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Error al crear directorio: {}", e))?;
+    }
+    if json_path != version_file {
+        fs::copy(json_path, &version_file)
+            .map_err(|e| format!("Error al copiar el JSON de versión: {}", e))?;
+    }
+    if let Some(jar_path) = jar_path {
+        let jar_file = paths::version_file(paths.game_dir(), &version_id, "jar");
+        if jar_path != jar_file {
+            fs::copy(jar_path, &jar_file)
+                .map_err(|e| format!("Error al copiar el jar de la versión: {}", e))?;
+        }
+    }
+
+    log::info!(
+        "[CustomVersion] Instance {} now launches {} ({} libraries, {} downloaded)",
+        instance.instanceName,
+        version_id,
+        downloads.len(),
+        downloaded_libraries
+    );
+    update_instance_by_id(instance_id, |instance| {
+        instance.customVersion = Some(version_id.clone());
+        Ok(())
+    })?;
+
+    Ok(CustomVersionInfo {
+        instance_id: instance.instanceId,
+        version_id,
+        inherits_from,
+        main_class,
+        libraries: downloads.len(),
+        downloaded_libraries,
+        has_jar: jar_path.is_some(),
+    })
+}
+
+/// Makes the instance launch the version JSON at `json_path` (plus its
+/// client jar at `jar_path`, if it has one).
+#[tauri::command]
+pub async fn import_custom_version(
+    instance_id: String,
+    json_path: String,
+    jar_path: Option<String>,
+) -> Result<CustomVersionInfo, String> {
+    tokio::task::spawn_blocking(move || {
+        import(
+            &instance_id,
+            Path::new(&json_path),
+            jar_path.as_deref().map(Path::new),
+        )
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Goes back to launching the instance's own version. The files in
+/// `versions/` are kept.
+#[tauri::command]
+pub fn clear_custom_version(instance_id: String) -> Result<MinecraftInstance, String> {
+    update_instance_by_id(&instance_id, |instance| {
+        instance.customVersion = None;
+        Ok(instance.clone())
+    })
+}
@@ -0,0 +1,273 @@
+//! Content-addressed cache for downloaded libraries and modpack files.
+
+use crate::config::with_config;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// Bytes que ocupa la caché. Se calcula recorriendo el directorio la
+/// primera vez; después cada `store` suma lo suyo, así que el directorio
+/// sólo se vuelve a recorrer cuando hay que liberar espacio
+static CACHED_BYTES: Lazy<Mutex<Option<u64>>> = Lazy::new(|| Mutex::new(None));
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HashAlgo {
+    Sha1,
+    Sha256,
+}
+
+impl HashAlgo {
+    /// Deduces the algorithm from the length of a hex digest.
+    pub fn for_hash(hash: &str) -> Option<Self> {
+        if !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        match hash.len() {
+            40 => Some(HashAlgo::Sha1),
+            64 => Some(HashAlgo::Sha256),
+            _ => None,
+        }
+    }
+
+    fn dir_name(self) -> &'static str {
+        match self {
+            HashAlgo::Sha1 => "sha1",
+            HashAlgo::Sha256 => "sha256",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheClearReport {
+    pub removed_files: u64,
+    pub reclaimed_bytes: u64,
+}
+
+fn cache_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| {
+        dir.join("dev.alexitoo.modpackstore")
+            .join("cache")
+            .join("downloads")
+    })
+}
+
+/// Tamaño máximo en bytes; 0 desactiva la caché
+fn max_cache_bytes() -> u64 {
+    with_config(|config| config.get_download_cache_size_mb())
+        .ok()
+        .unwrap_or(0)
+        * 1024
+        * 1024
+}
+
+fn entry_path(hash: &str) -> Option<PathBuf> {
+    let algo = HashAlgo::for_hash(hash)?;
+    let hash = hash.to_lowercase();
+    Some(
+        cache_dir()?
+            .join(algo.dir_name())
+            .join(&hash[..2])
+            .join(hash),
+    )
+}
+
+pub fn file_hash(path: &Path, algo: HashAlgo) -> Option<String> {
+    let mut file = fs::File::open(path).ok()?;
+    let digest = match algo {
+        HashAlgo::Sha1 => {
+            let mut hasher = Sha1::new();
+            io::copy(&mut file, &mut hasher).ok()?;
+            format!("{:x}", hasher.finalize())
+        }
+        HashAlgo::Sha256 => {
+            let mut hasher = Sha256::new();
+            io::copy(&mut file, &mut hasher).ok()?;
+            format!("{:x}", hasher.finalize())
+        }
+    };
+    Some(digest)
+}
+
+fn touch(path: &Path) {
+    if let Ok(file) = fs::File::options().write(true).open(path) {
+        let _ = file.set_modified(SystemTime::now());
+    }
+}
+
+/// Whether a file with `hash` can be restored without downloading it.
+pub fn contains(hash: &str) -> bool {
+    max_cache_bytes() > 0 && entry_path(hash).is_some_and(|path| path.exists())
+}
+
+/// Copies the cached file with `hash` to `destination`. Returns false on a
+/// miss (or when the cache is disabled).
+pub fn restore(hash: &str, destination: &Path) -> bool {
+    if max_cache_bytes() == 0 {
+        return false;
+    }
+    let Some(cached) = entry_path(hash).filter(|path| path.exists()) else {
+        return false;
+    };
+
+    if let Some(parent) = destination.parent() {
+        if fs::create_dir_all(parent).is_err() {
+            return false;
+        }
+    }
+    match fs::copy(&cached, destination) {
+        Ok(_) => {
+            touch(&cached);
+            log::debug!("[DownloadCache] Hit for {}", hash);
+            true
+        }
+        Err(e) => {
+            log::warn!("[DownloadCache] Could not restore {}: {}", hash, e);
+            false
+        }
+    }
+}
+
+/// Adds a downloaded file to the cache if its content matches `hash`.
+pub fn store(path: &Path, hash: &str) {
+    let max_bytes = max_cache_bytes();
+    if max_bytes == 0 {
+        return;
+    }
+    let (Some(algo), Some(entry)) = (HashAlgo::for_hash(hash), entry_path(hash)) else {
+        return;
+    };
+    if entry.exists() {
+        touch(&entry);
+        return;
+    }
+
+    // Un archivo corrupto no debe acabar compartido con otras instancias
+    if file_hash(path, algo).as_deref() != Some(hash.to_lowercase().as_str()) {
+        log::warn!(
+            "[DownloadCache] {} does not match {}, not caching",
+            path.display(),
+            hash
+        );
+        return;
+    }
+
+    if let Some(parent) = entry.parent() {
+        if let Err(e) = fs::create_dir_all(parent) {
+            log::warn!("[DownloadCache] Could not create cache dir: {}", e);
+            return;
+        }
+    }
+    // Copia a un temporal y renombra para no dejar entradas a medias
+    let partial = entry.with_extension("part");
+    if let Err(e) = fs::copy(path, &partial).and_then(|_| fs::rename(&partial, &entry)) {
+        log::warn!("[DownloadCache] Could not cache {}: {}", hash, e);
+        let _ = fs::remove_file(&partial);
+        return;
+    }
+
+    let size = fs::metadata(&entry).map(|m| m.len()).unwrap_or(0);
+    account(size, max_bytes);
+}
+
+/// Adds `bytes` to the running size and evicts only when the total goes
+/// over `max_bytes`.
+fn account(bytes: u64, max_bytes: u64) {
+    let mut cached = CACHED_BYTES.lock().unwrap_or_else(|e| e.into_inner());
+    let total = match *cached {
+        Some(total) => total + bytes,
+        // El recorrido ya incluye el archivo recién guardado
+        None => cached_files().iter().map(|(_, size, _)| size).sum(),
+    };
+    *cached = Some(if total > max_bytes {
+        evict(max_bytes)
+    } else {
+        total
+    });
+}
+
+fn cached_files() -> Vec<(PathBuf, u64, SystemTime)> {
+    let mut files = Vec::new();
+    let Some(root) = cache_dir() else {
+        return files;
+    };
+
+    let mut pending = vec![root];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                pending.push(entry.path());
+            } else {
+                let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                files.push((entry.path(), metadata.len(), modified));
+            }
+        }
+    }
+    files
+}
+
+/// Removes the least recently used entries until the cache fits. Returns
+/// the bytes left.
+fn evict(max_bytes: u64) -> u64 {
+    let mut files = cached_files();
+    let mut total: u64 = files.iter().map(|(_, size, _)| size).sum();
+    if total <= max_bytes {
+        return total;
+    }
+
+    files.sort_by_key(|(_, _, modified)| *modified);
+    for (path, size, _) in files {
+        if total <= max_bytes {
+            break;
+        }
+        if fs::remove_file(&path).is_ok() {
+            total -= size;
+        }
+    }
+    total
+}
+
+/// Files and bytes currently held by the cache.
+pub fn usage() -> (u64, u64) {
+    let files = cached_files();
+    let bytes = files.iter().map(|(_, size, _)| size).sum();
+    (files.len() as u64, bytes)
+}
+
+/// Empties the download cache. Instances keep their own copies.
+#[tauri::command]
+pub fn clear_download_cache() -> Result<CacheClearReport, String> {
+    let mut report = CacheClearReport::default();
+    let mut cached = CACHED_BYTES.lock().unwrap_or_else(|e| e.into_inner());
+
+    for (path, size, _) in cached_files() {
+        match fs::remove_file(&path) {
+            Ok(()) => {
+                report.removed_files += 1;
+                report.reclaimed_bytes += size;
+            }
+            Err(e) => log::warn!("[DownloadCache] Could not remove {}: {}", path.display(), e),
+        }
+    }
+    // Lo que no se pudo borrar se vuelve a contar en el próximo `store`
+    *cached = None;
+
+    log::info!(
+        "[DownloadCache] Cleared {} files ({} bytes)",
+        report.removed_files,
+        report.reclaimed_bytes
+    );
+    Ok(report)
+}
@@ -0,0 +1,132 @@
+//! Game ownership check for Microsoft accounts before launching.
+
+use crate::core::http_client;
+use crate::core::i18n::{keys, Message};
+use crate::core::minecraft_account::MinecraftAccount;
+use serde_json::Value;
+use std::time::Duration;
+
+pub const MINECRAFT_PROFILE_URL: &str = "https://api.minecraftservices.com/minecraft/profile";
+pub const ACCOUNT_OWNS_MINECRAFT_URL: &str =
+    "https://api.minecraftservices.com/entitlements/license";
+
+const CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntitlementError {
+    NoGameOwnership,
+    ProfileNotCreated,
+    /// The stored token was rejected (expired or revoked)
+    InvalidSession,
+}
+
+impl EntitlementError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            EntitlementError::NoGameOwnership => "NO_GAME_OWNERSHIP",
+            EntitlementError::ProfileNotCreated => "PROFILE_NOT_CREATED",
+            EntitlementError::InvalidSession => "INVALID_SESSION",
+        }
+    }
+
+    pub fn message(&self) -> Message {
+        Message::new(match self {
+            EntitlementError::NoGameOwnership => keys::LAUNCH_NO_GAME_OWNERSHIP,
+            EntitlementError::ProfileNotCreated => keys::LAUNCH_PROFILE_NOT_CREATED,
+            EntitlementError::InvalidSession => keys::LAUNCH_INVALID_SESSION,
+        })
+    }
+}
+
+/// Whether the `entitlements/license` response includes a Java Edition
+/// license that is not a trial.
+pub fn has_java_license(license_data: &Value) -> bool {
+    license_data
+        .get("items")
+        .and_then(Value::as_array)
+        .map(|items| {
+            items.iter().any(|item| {
+                let name = item.get("name").and_then(Value::as_str).unwrap_or("");
+                let source = item.get("source").and_then(Value::as_str).unwrap_or("");
+
+                (name == "product_minecraft" || name == "game_minecraft") && source != "TRIAL"
+            })
+        })
+        .unwrap_or(false)
+}
+
+fn is_microsoft_account(account: &MinecraftAccount) -> bool {
+    account.user_type().eq_ignore_ascii_case("microsoft")
+}
+
+/// Checks license and profile of a Microsoft account. Offline accounts pass
+/// untouched, and so does any network failure: the game may still start
+/// with a cached session, so only a definite answer blocks the launch.
+pub fn verify_account(account: &MinecraftAccount) -> Result<(), EntitlementError> {
+    if !is_microsoft_account(account) {
+        return Ok(());
+    }
+    let Some(access_token) = account.access_token() else {
+        return Err(EntitlementError::InvalidSession);
+    };
+
+    let client = match http_client::blocking_builder()
+        .timeout(CHECK_TIMEOUT)
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => {
+            log::warn!("[Entitlement] Could not build HTTP client: {}", e);
+            return Ok(());
+        }
+    };
+
+    let license = match client
+        .get(ACCOUNT_OWNS_MINECRAFT_URL)
+        .query(&[("requestId", uuid::Uuid::new_v4().to_string())])
+        .bearer_auth(access_token)
+        .send()
+    {
+        Ok(response) => response,
+        Err(e) => {
+            log::warn!("[Entitlement] License check skipped: {}", e);
+            return Ok(());
+        }
+    };
+
+    match license.status().as_u16() {
+        401 | 403 => return Err(EntitlementError::InvalidSession),
+        status if !(200..300).contains(&status) => {
+            log::warn!("[Entitlement] License check returned HTTP {}", status);
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    match license.json::<Value>() {
+        Ok(data) if !has_java_license(&data) => return Err(EntitlementError::NoGameOwnership),
+        Ok(_) => {}
+        Err(e) => {
+            log::warn!("[Entitlement] Invalid license response: {}", e);
+            return Ok(());
+        }
+    }
+
+    match client
+        .get(MINECRAFT_PROFILE_URL)
+        .bearer_auth(access_token)
+        .send()
+    {
+        Ok(response) if response.status().as_u16() == 404 => {
+            Err(EntitlementError::ProfileNotCreated)
+        }
+        Ok(response) if matches!(response.status().as_u16(), 401 | 403) => {
+            Err(EntitlementError::InvalidSession)
+        }
+        Ok(_) => Ok(()),
+        Err(e) => {
+            log::warn!("[Entitlement] Profile check skipped: {}", e);
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,355 @@
+//! Typed launcher events and the emitter that delivers them to the frontend.
+
+use crate::core::auth::{AuthErrorEvent, AuthStep, UserSession};
+use crate::core::i18n::Message;
+use crate::core::instance_state::InstanceStateInfo;
+use crate::core::launch_queue::{LaunchDequeued, LaunchRequest};
+use crate::core::launcher_updater::LauncherUpdateInfo;
+use crate::core::microsoft_auth::AuthProgressEvent;
+use crate::core::minecraft::heap_limits::HeapAdjustment;
+use crate::core::minecraft_account::MinecraftAccount;
+use crate::core::network_monitor::NetworkStatus;
+use crate::core::prelaunch_appearance::hot_reload::AppearanceUpdate;
+use crate::core::prelaunch_appearance::PendingNotice;
+use crate::core::startup_watchdog::LaunchStalled;
+use crate::core::tasks_manager::TaskInfo;
+use crate::core::transfer_stats::TransferSummary;
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+
+/// Event names shared with the frontend.
+pub mod names {
+    pub const APP_READY: &str = "app-ready";
+    pub const LAUNCHER_UPDATE_READY: &str = "launcher-update-ready";
+
+    pub const INSTANCE_BOOTSTRAP_START: &str = "instance-bootstrap-start";
+    pub const INSTANCE_DOWNLOADING_ASSETS: &str = "instance-downloading-assets";
+    pub const INSTANCE_DOWNLOADING_CLIENT: &str = "instance-downloading-client";
+    pub const INSTANCE_DOWNLOADING_FORGE: &str = "instance-downloading-forge";
+    pub const INSTANCE_DOWNLOADING_FORGE_INSTALLER: &str = "instance-downloading-forge-installer";
+    pub const INSTANCE_DOWNLOADING_FORGE_LIBRARIES: &str = "instance-downloading-forge-libraries";
+    pub const INSTANCE_DOWNLOADING_JSON: &str = "instance-downloading-json";
+    pub const INSTANCE_DOWNLOADING_LIBRARIES: &str = "instance-downloading-libraries";
+    pub const INSTANCE_DOWNLOADING_MANIFEST: &str = "instance-downloading-manifest";
+    pub const INSTANCE_DOWNLOADING_MODPACK_ASSETS: &str = "instance-downloading-modpack-assets";
+    pub const INSTANCE_DOWNLOADING_NATIVE_LIBRARY: &str = "instance-downloading-native-library";
+    pub const INSTANCE_EXTRACTING_NATIVE_LIBRARY: &str = "instance-extracting-native-library";
+    pub const INSTANCE_EXTRACTING_NATIVES: &str = "instance-extracting-natives";
+    pub const INSTANCE_FINISH_ASSETS_DOWNLOAD: &str = "instance-finish-assets-download";
+    pub const INSTANCE_FORGE_VANILLA_SETUP: &str = "instance-forge-vanilla-setup";
+    pub const INSTANCE_INSTALLING_FORGE: &str = "instance-installing-forge";
+    pub const INSTANCE_VERIFYING_COMPLETE: &str = "instance-verifying-complete";
+    pub const INSTANCE_VERIFYING_LIBRARIES: &str = "instance-verifying-libraries";
+    pub const INSTANCE_VERIFYING_VANILLA: &str = "instance-verifying-vanilla";
+    pub const INSTANCE_LAUNCH_START: &str = "instance-launch-start";
+    pub const INSTANCE_LAUNCHED: &str = "instance-launched";
+    pub const INSTANCE_EXITED: &str = "instance-exited";
+    pub const INSTANCE_ERROR: &str = "instance-error";
+    pub const INSTANCE_ACCOUNT_INVALID: &str = "instance-account-invalid";
+    pub const INSTANCE_STATE_CHANGED: &str = "instance-state-changed";
+    pub const INSTANCE_LAUNCH_QUEUED: &str = "instance-launch-queued";
+    pub const INSTANCE_LAUNCH_DEQUEUED: &str = "instance-launch-dequeued";
+    pub const INSTANCE_LAUNCH_STALLED: &str = "instance-launch-stalled";
+    pub const INSTANCE_MEMORY_ADJUSTED: &str = "instance-memory-adjusted";
+    pub const NOTICE_REQUIRED: &str = "notice-required";
+    pub const PRELAUNCH_APPEARANCE_UPDATED: &str = "prelaunch-appearance-updated";
+
+    pub const TASK_CREATED: &str = "task-created";
+    pub const TASK_UPDATED: &str = "task-updated";
+    pub const TASK_REMOVED: &str = "task-removed";
+    pub const TRANSFER_STATS: &str = "transfer-stats";
+
+    pub const NETWORK_STATUS_CHANGED: &str = "network-status-changed";
+
+    pub const AUTH_STATUS_CHANGED: &str = "auth-status-changed";
+    pub const AUTH_STEP_CHANGED: &str = "auth-step-changed";
+    pub const AUTH_ERROR: &str = "auth-error";
+    pub const AUTH_ERROR_DETAILS: &str = "auth-error-details";
+    pub const SESSION_EXPIRED: &str = "session-expired";
+    pub const SESSION_RESTORED: &str = "session-restored";
+
+    pub const MICROSOFT_AUTH_PROGRESS: &str = "microsoft-auth-progress";
+    pub const MICROSOFT_AUTH_SUCCESS: &str = "microsoft-auth-success";
+    pub const MICROSOFT_AUTH_ERROR: &str = "microsoft-auth-error";
+    pub const MICROSOFT_AUTH_ACCOUNT_SAVED: &str = "microsoft-auth-account-saved";
+}
+
+/// Serialized as its payload only; the event name comes from `name()`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum LauncherEvent {
+    AppReady,
+    /// A launcher update was downloaded and verified; restart to install it.
+    LauncherUpdateReady(LauncherUpdateInfo),
+    /// Progress or status of an instance (bootstrap, launch, modpack sync).
+    InstanceStatus {
+        #[serde(skip)]
+        event: &'static str,
+        id: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+        message: String,
+        /// Clave de traducción de `message` (ver `i18n::keys`)
+        #[serde(rename = "messageKey", skip_serializing_if = "Option::is_none")]
+        message_key: Option<String>,
+        #[serde(rename = "messageParams", skip_serializing_if = "Option::is_none")]
+        message_params: Option<Value>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        data: Option<Value>,
+    },
+    /// An instance moved to another lifecycle state (see `instance_state`).
+    InstanceStateChanged(InstanceStateInfo),
+    /// Play was pressed while the instance is still being set up.
+    InstanceLaunchQueued(LaunchRequest),
+    /// The queued launch started, or was dropped (see `reason`).
+    InstanceLaunchDequeued(LaunchDequeued),
+    /// The game produced no output for a while before opening its window.
+    InstanceLaunchStalled(LaunchStalled),
+    /// The configured heap didn't fit the machine or the JVM and was lowered.
+    InstanceMemoryAdjusted(HeapAdjustment),
+    NoticeRequired(PendingNotice),
+    /// `prelaunch_appearance.json` changed while hot reload was watching it.
+    PrelaunchAppearanceUpdated(AppearanceUpdate),
+    TaskCreated(TaskInfo),
+    TaskUpdated(TaskInfo),
+    TaskRemoved(String),
+    /// Aggregated in-flight downloads, sent every second while any is active.
+    TransferStats(TransferSummary),
+    NetworkStatusChanged(NetworkStatus),
+    AuthStatusChanged(Option<UserSession>),
+    AuthStepChanged(AuthStep),
+    AuthError(String),
+    AuthErrorDetails(AuthErrorEvent),
+    SessionExpired,
+    SessionRestored(UserSession),
+    MicrosoftAuthProgress(AuthProgressEvent),
+    /// `None` once Microsoft accepts the login, then the saved account.
+    MicrosoftAuthSuccess(Option<MinecraftAccount>),
+    MicrosoftAuthError(String),
+    MicrosoftAuthAccountSaved(MinecraftAccount),
+}
+
+impl LauncherEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LauncherEvent::AppReady => names::APP_READY,
+            LauncherEvent::LauncherUpdateReady(_) => names::LAUNCHER_UPDATE_READY,
+            LauncherEvent::InstanceStatus { event, .. } => event,
+            LauncherEvent::InstanceStateChanged(_) => names::INSTANCE_STATE_CHANGED,
+            LauncherEvent::InstanceLaunchQueued(_) => names::INSTANCE_LAUNCH_QUEUED,
+            LauncherEvent::InstanceLaunchDequeued(_) => names::INSTANCE_LAUNCH_DEQUEUED,
+            LauncherEvent::InstanceLaunchStalled(_) => names::INSTANCE_LAUNCH_STALLED,
+            LauncherEvent::InstanceMemoryAdjusted(_) => names::INSTANCE_MEMORY_ADJUSTED,
+            LauncherEvent::NoticeRequired(_) => names::NOTICE_REQUIRED,
+            LauncherEvent::PrelaunchAppearanceUpdated(_) => names::PRELAUNCH_APPEARANCE_UPDATED,
+            LauncherEvent::TaskCreated(_) => names::TASK_CREATED,
+            LauncherEvent::TaskUpdated(_) => names::TASK_UPDATED,
+            LauncherEvent::TaskRemoved(_) => names::TASK_REMOVED,
+            LauncherEvent::TransferStats(_) => names::TRANSFER_STATS,
+            LauncherEvent::NetworkStatusChanged(_) => names::NETWORK_STATUS_CHANGED,
+            LauncherEvent::AuthStatusChanged(_) => names::AUTH_STATUS_CHANGED,
+            LauncherEvent::AuthStepChanged(_) => names::AUTH_STEP_CHANGED,
+            LauncherEvent::AuthError(_) => names::AUTH_ERROR,
+            LauncherEvent::AuthErrorDetails(_) => names::AUTH_ERROR_DETAILS,
+            LauncherEvent::SessionExpired => names::SESSION_EXPIRED,
+            LauncherEvent::SessionRestored(_) => names::SESSION_RESTORED,
+            LauncherEvent::MicrosoftAuthProgress(_) => names::MICROSOFT_AUTH_PROGRESS,
+            LauncherEvent::MicrosoftAuthSuccess(_) => names::MICROSOFT_AUTH_SUCCESS,
+            LauncherEvent::MicrosoftAuthError(_) => names::MICROSOFT_AUTH_ERROR,
+            LauncherEvent::MicrosoftAuthAccountSaved(_) => names::MICROSOFT_AUTH_ACCOUNT_SAVED,
+        }
+    }
+
+    /// Instance status event with the usual `{ id, name, message }` payload,
+    /// plus `messageKey`/`messageParams` when the message has a key.
+    pub fn instance(
+        event: &'static str,
+        id: impl Into<String>,
+        name: Option<String>,
+        message: impl Into<Message>,
+        data: Option<Value>,
+    ) -> Self {
+        let message = message.into();
+        LauncherEvent::InstanceStatus {
+            event,
+            id: id.into(),
+            name,
+            message_params: message.params_value(),
+            message_key: message.key,
+            message: message.text,
+            data,
+        }
+    }
+}
+
+/// Como mucho un evento por clave en este intervalo (10 por segundo)
+const THROTTLE_INTERVAL: Duration = Duration::from_millis(100);
+/// Las claves sin eventos durante este tiempo se olvidan
+const THROTTLE_IDLE: Duration = Duration::from_secs(30);
+
+struct ThrottleSlot {
+    last_sent: Instant,
+    /// Último evento recibido dentro del intervalo; sustituye a los anteriores
+    pending: Option<LauncherEvent>,
+}
+
+/// Delivers `LauncherEvent`s to the frontend.
+///
+/// Lives in Tauri managed state and is handed to the code that emits the
+/// most (task managers, launchers), so progress never waits on a lock. The
+/// global copy is only a fallback for code outside those paths.
+///
+/// Progress goes through `send_throttled`: per key (a task, an instance
+/// step) at most one event every `THROTTLE_INTERVAL` reaches the webview,
+/// and the latest one of each interval is delivered when it ends.
+#[derive(Clone)]
+pub struct EventEmitter {
+    app_handle: tauri::AppHandle,
+    throttle: Arc<Mutex<HashMap<String, ThrottleSlot>>>,
+}
+
+static EVENT_EMITTER: OnceCell<EventEmitter> = OnceCell::new();
+
+impl EventEmitter {
+    /// Creates the emitter and registers it as managed state. Call once
+    /// from the app setup.
+    pub fn init(app_handle: tauri::AppHandle) -> EventEmitter {
+        let emitter = EventEmitter {
+            app_handle: app_handle.clone(),
+            throttle: Arc::new(Mutex::new(HashMap::new())),
+        };
+        if EVENT_EMITTER.set(emitter.clone()).is_err() {
+            log::warn!("[Events] Emitter already initialized");
+        }
+        app_handle.manage(emitter.clone());
+
+        // Entrega los eventos retenidos cuando vence su intervalo
+        let flusher = emitter.clone();
+        let spawned = thread::Builder::new()
+            .name("event-throttle".to_string())
+            .spawn(move || loop {
+                thread::sleep(THROTTLE_INTERVAL);
+                flusher.flush_due();
+            });
+        if let Err(e) = spawned {
+            log::error!("[Events] Could not start the throttle thread: {}", e);
+        }
+        emitter
+    }
+
+    pub fn global() -> Option<&'static EventEmitter> {
+        EVENT_EMITTER.get()
+    }
+
+    pub fn app_handle(&self) -> &tauri::AppHandle {
+        &self.app_handle
+    }
+
+    pub fn emit(&self, event: LauncherEvent) -> Result<(), String> {
+        self.app_handle
+            .emit(event.name(), &event)
+            .map_err(|e| format!("Error al emitir '{}': {}", event.name(), e))
+    }
+
+    /// Like `emit`, logging (not failing) on error.
+    pub fn send(&self, event: LauncherEvent) {
+        if let Err(e) = self.emit(event) {
+            log::warn!("[Events] {}", e);
+        }
+    }
+
+    // Se emite con el lock tomado para que un evento retenido nunca llegue
+    // después de uno más reciente de la misma clave
+    fn slots(&self) -> MutexGuard<'_, HashMap<String, ThrottleSlot>> {
+        self.throttle.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Sends `event` unless another one with the same `key` went out less
+    /// than `THROTTLE_INTERVAL` ago; then it replaces the pending one and is
+    /// sent when the interval ends.
+    pub fn send_throttled(&self, key: &str, event: LauncherEvent) {
+        let now = Instant::now();
+        let mut slots = self.slots();
+        match slots.get_mut(key) {
+            Some(slot) if now.duration_since(slot.last_sent) < THROTTLE_INTERVAL => {
+                slot.pending = Some(event);
+            }
+            _ => {
+                slots.insert(
+                    key.to_string(),
+                    ThrottleSlot {
+                        last_sent: now,
+                        pending: None,
+                    },
+                );
+                self.send(event);
+            }
+        }
+    }
+
+    /// Sends `event` right away and drops whatever was pending for `key`
+    /// (a finished task must not be followed by stale progress).
+    pub fn send_final(&self, key: &str, event: LauncherEvent) {
+        let mut slots = self.slots();
+        slots.remove(key);
+        self.send(event);
+    }
+
+    fn flush_due(&self) {
+        let now = Instant::now();
+        let mut slots = self.slots();
+        slots.retain(|_, slot| {
+            slot.pending.is_some() || now.duration_since(slot.last_sent) < THROTTLE_IDLE
+        });
+        for slot in slots.values_mut() {
+            if now.duration_since(slot.last_sent) < THROTTLE_INTERVAL {
+                continue;
+            }
+            if let Some(event) = slot.pending.take() {
+                slot.last_sent = now;
+                self.send(event);
+            }
+        }
+    }
+}
+
+/// Emits through the global emitter, logging (not failing) if it is missing.
+pub fn emit(event: LauncherEvent) {
+    if let Err(e) = try_emit(event) {
+        log::warn!("[Events] {}", e);
+    }
+}
+
+/// `EventEmitter::send_throttled` on the global emitter.
+pub fn emit_throttled(key: &str, event: LauncherEvent) {
+    match EventEmitter::global() {
+        Some(emitter) => emitter.send_throttled(key, event),
+        None => log::warn!(
+            "[Events] Emitter not initialized, dropping '{}'",
+            event.name()
+        ),
+    }
+}
+
+/// `EventEmitter::send_final` on the global emitter.
+pub fn emit_final(key: &str, event: LauncherEvent) {
+    match EventEmitter::global() {
+        Some(emitter) => emitter.send_final(key, event),
+        None => log::warn!(
+            "[Events] Emitter not initialized, dropping '{}'",
+            event.name()
+        ),
+    }
+}
+
+pub fn try_emit(event: LauncherEvent) -> Result<(), String> {
+    EventEmitter::global()
+        .ok_or_else(|| format!("Eventos no inicializados al emitir '{}'", event.name()))?
+        .emit(event)
+}
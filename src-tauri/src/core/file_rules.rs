@@ -0,0 +1,200 @@
+//! Player `exclude`/`preserve` rules for the files of a modpack instance.
+
+use crate::core::instance_manager::{get_instance_by_id, update_instance_by_id};
+use crate::core::minecraft_instance::MinecraftInstance;
+use crate::core::models::ModpackManifest;
+use serde::{Deserialize, Serialize};
+
+const MAX_PATTERN_LENGTH: usize = 256;
+const MAX_RULES: usize = 200;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FileRules {
+    /// Archivos del modpack que no se instalan
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// Archivos que el modpack nunca sobrescribe ni borra
+    #[serde(default)]
+    pub preserve: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum FileRuleKind {
+    Exclude,
+    Preserve,
+}
+
+impl FileRules {
+    pub fn for_instance(instance: &MinecraftInstance) -> Self {
+        instance.fileRules.clone().unwrap_or_default()
+    }
+
+    /// The player's rules plus the paths the modpack itself protects.
+    pub fn for_manifest(instance: &MinecraftInstance, manifest: &ModpackManifest) -> Self {
+        let mut rules = Self::for_instance(instance);
+        rules
+            .preserve
+            .extend(manifest.protected_paths.iter().cloned());
+        rules
+    }
+
+    pub fn is_excluded(&self, path: &str) -> bool {
+        matches_any(&self.exclude, path)
+    }
+
+    pub fn is_preserved(&self, path: &str) -> bool {
+        matches_any(&self.preserve, path)
+    }
+
+    fn patterns_mut(&mut self, kind: FileRuleKind) -> &mut Vec<String> {
+        match kind {
+            FileRuleKind::Exclude => &mut self.exclude,
+            FileRuleKind::Preserve => &mut self.preserve,
+        }
+    }
+}
+
+fn matches_any(patterns: &[String], path: &str) -> bool {
+    let path = path.replace('\\', "/");
+    patterns.iter().any(|pattern| glob_match(pattern, &path))
+}
+
+/// Glob relative to the `minecraft` folder: `*` and `?` stay within a
+/// segment, `**` spans any number of them.
+fn glob_match(pattern: &str, path: &str) -> bool {
+    // "config/mymod/" equivale a "config/mymod/**"
+    let mut pattern: Vec<&str> = pattern.split('/').collect();
+    if let Some(last) = pattern.last_mut().filter(|last| last.is_empty()) {
+        *last = "**";
+    }
+    let pattern: Vec<&str> = pattern
+        .into_iter()
+        .filter(|segment| !segment.is_empty() && *segment != ".")
+        .collect();
+    let path: Vec<&str> = path
+        .split('/')
+        .filter(|segment| !segment.is_empty() && *segment != ".")
+        .collect();
+    match_segments(&pattern, &path)
+}
+
+fn match_segments(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| match_segments(rest, &path[skip..])),
+        Some((segment, rest)) => path.split_first().is_some_and(|(name, path_rest)| {
+            match_segment(segment, name) && match_segments(rest, path_rest)
+        }),
+    }
+}
+
+/// `*` and `?` within one segment. Case-insensitive, since the files may
+/// live on a case-insensitive file system.
+fn match_segment(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // Último `*` visto y la posición del nombre desde la que se reintenta
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p].eq_ignore_ascii_case(&name[n])) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, n));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            n = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+fn validate_pattern(pattern: &str) -> Result<String, String> {
+    let pattern = pattern.trim().replace('\\', "/");
+    if pattern.is_empty() {
+        return Err("El patrón no puede estar vacío".to_string());
+    }
+    if pattern.chars().count() > MAX_PATTERN_LENGTH {
+        return Err(format!(
+            "El patrón no puede superar los {} caracteres",
+            MAX_PATTERN_LENGTH
+        ));
+    }
+    if pattern.starts_with('/') || pattern.contains(':') || pattern.split('/').any(|s| s == "..") {
+        return Err(format!(
+            "El patrón debe ser relativo a la carpeta de la instancia: {}",
+            pattern
+        ));
+    }
+    Ok(pattern)
+}
+
+fn load_instance(instance_id: String) -> Result<MinecraftInstance, String> {
+    get_instance_by_id(instance_id.clone())?
+        .ok_or_else(|| format!("Instance with ID {} not found", instance_id))
+}
+
+fn update_rules(
+    instance_id: String,
+    change: impl FnOnce(&mut FileRules) -> Result<(), String>,
+) -> Result<FileRules, String> {
+    update_instance_by_id(&instance_id, |instance| {
+        let mut rules = FileRules::for_instance(instance);
+        change(&mut rules)?;
+
+        instance.fileRules = Some(rules.clone());
+        Ok(rules)
+    })
+}
+
+#[tauri::command]
+pub fn get_instance_file_rules(instance_id: String) -> Result<FileRules, String> {
+    Ok(FileRules::for_instance(&load_instance(instance_id)?))
+}
+
+/// Adds an `exclude` or `preserve` pattern. Takes effect on the next
+/// validation or update; nothing is deleted now.
+#[tauri::command]
+pub fn add_instance_file_rule(
+    instance_id: String,
+    kind: FileRuleKind,
+    pattern: String,
+) -> Result<FileRules, String> {
+    let pattern = validate_pattern(&pattern)?;
+    update_rules(instance_id, |rules| {
+        if rules.exclude.len() + rules.preserve.len() >= MAX_RULES {
+            return Err(format!("No se pueden añadir más de {} reglas", MAX_RULES));
+        }
+        let patterns = rules.patterns_mut(kind);
+        if !patterns.contains(&pattern) {
+            patterns.push(pattern);
+        }
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub fn remove_instance_file_rule(
+    instance_id: String,
+    kind: FileRuleKind,
+    pattern: String,
+) -> Result<FileRules, String> {
+    let pattern = pattern.trim().replace('\\', "/");
+    update_rules(instance_id, |rules| {
+        let patterns = rules.patterns_mut(kind);
+        let before = patterns.len();
+        patterns.retain(|existing| existing != &pattern);
+        if patterns.len() == before {
+            return Err(format!("La regla no existe: {}", pattern));
+        }
+        Ok(())
+    })
+}
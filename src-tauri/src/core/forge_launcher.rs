@@ -1,7 +1,6 @@
 use serde_json::Value;
 use std::collections::{HashMap, HashSet};
 use std::fs::create_dir_all;
-use std::fs::File;
 use std::io::Read;
 use std::io::Write;
 use std::{
@@ -9,19 +8,19 @@ use std::{
     path::{Path, PathBuf},
     process::{Child, Command, Stdio},
 };
-use zip::ZipArchive;
 
-use crate::config::get_config_manager;
+use crate::config::read_config;
 use crate::core::accounts_manager::AccountsManager;
+use crate::core::minecraft::manifest::version_type;
+use crate::core::minecraft::paths;
+use crate::core::minecraft::{MavenCoordinate, RuleEvaluator};
+use crate::core::zip_extract::ZipExtractor;
 use crate::core::{minecraft_account::MinecraftAccount, minecraft_instance::MinecraftInstance};
 use crate::interfaces::game_launcher::GameLauncher;
-use uuid::Uuid;
 
 // Import VanillaLauncher for inheritance
 use super::vanilla_launcher::VanillaLauncher;
 
-const CREATE_NO_WINDOW: u32 = 0x08000000;
-
 pub struct ForgeLoader {
     instance: MinecraftInstance,
     vanilla_launcher: VanillaLauncher, // Composition with VanillaLauncher for reusing functionality
@@ -64,24 +63,11 @@ impl ForgeLoader {
         // Añadir el jar cliente de Vanilla (comentado, pero disponible si se necesita posteriormente)
         // classpath.push(vanilla_client_jar.to_string_lossy().to_string());
 
-        // Función auxiliar para extraer identificador único de biblioteca con grupo, artefacto y versión
-        fn parse_library_info(lib_name: &str) -> Option<(String, String, String)> {
-            let parts: Vec<&str> = lib_name.split(':').collect();
-            if parts.len() >= 3 {
-                let group_id = parts[0].to_string();
-                let artifact_id = parts[1].to_string();
-                let version = parts[2].split('@').next().unwrap_or(parts[2]).to_string();
-                return Some((group_id, artifact_id, version));
-            }
-            None
-        }
-
         // Función auxiliar para extraer identificador único de biblioteca (artifact_id:version)
         fn get_library_id(lib: &Value) -> Option<(String, String, String)> {
-            if let Some(name) = lib.get("name").and_then(|name| name.as_str()) {
-                return parse_library_info(name);
-            }
-            None
+            let name = lib.get("name").and_then(|name| name.as_str())?;
+            MavenCoordinate::parse(name)
+                .map(|coordinate| (coordinate.group, coordinate.artifact, coordinate.version))
         }
 
         // Función auxiliar para procesar biblioteca y añadirla a la classpath
@@ -92,17 +78,7 @@ impl ForgeLoader {
             launcher: &ForgeLoader,
         ) -> Option<String> {
             // Comprobar si esta biblioteca tiene reglas que podrían excluirla
-            let should_include = lib
-                .get("rules")
-                .and_then(|rules| rules.as_array())
-                .map(|rules_arr| {
-                    rules_arr
-                        .iter()
-                        .any(|rule| launcher.vanilla_launcher.should_apply_rule(rule, None))
-                })
-                .unwrap_or(true); // Por defecto incluir si no hay reglas
-
-            if !should_include {
+            if !RuleEvaluator::applies(lib, None) {
                 return None;
             }
 
@@ -113,8 +89,7 @@ impl ForgeLoader {
                 .and_then(|artifact| artifact.get("path"))
                 .and_then(|path| path.as_str())
             {
-                let lib_path =
-                    libraries_dir.join(path.replace('/', &std::path::MAIN_SEPARATOR.to_string()));
+                let lib_path = paths::join_relative(&libraries_dir, path);
                 if lib_path.exists() {
                     classpath.push(lib_path.to_string_lossy().to_string());
                     return Some(lib_path.to_string_lossy().to_string());
@@ -124,30 +99,9 @@ impl ForgeLoader {
             }
             // Formato antiguo - construir la ruta a partir del nombre
             else if let Some(name) = lib.get("name").and_then(|name| name.as_str()) {
-                // Parse Maven coordinates
-                if let Some((group_id, artifact_id, version_raw)) = parse_library_info(name) {
-                    let group_path = group_id.replace('.', &std::path::MAIN_SEPARATOR.to_string());
-
-                    // Manejar el formato personalizado de Forge con el símbolo @
-                    let (version, classifier) = if version_raw.contains('@') {
-                        let v_parts: Vec<&str> = version_raw.split('@').collect();
-                        (v_parts[0], Some(v_parts[1]))
-                    } else {
-                        (&version_raw[..], None)
-                    };
-
-                    // Construir el nombre de archivo con clasificador opcional
-                    let filename = if let Some(classifier) = classifier {
-                        format!("{}-{}-{}.jar", artifact_id, version, classifier)
-                    } else {
-                        format!("{}-{}.jar", artifact_id, version)
-                    };
-
-                    let lib_path = libraries_dir
-                        .join(group_path)
-                        .join(&artifact_id)
-                        .join(version)
-                        .join(&filename);
+                // Parse Maven coordinates (clasificador y `@ext` incluidos)
+                if let Some(coordinate) = MavenCoordinate::parse(name) {
+                    let lib_path = paths::join_relative(&libraries_dir, &coordinate.url_path());
 
                     if lib_path.exists() {
                         classpath.push(lib_path.to_string_lossy().to_string());
@@ -265,31 +219,7 @@ impl ForgeLoader {
             };
 
             // Skip if library doesn't apply to current OS
-            let should_apply = lib
-                .get("rules")
-                .and_then(|rules| rules.as_array())
-                .map(|rules_arr| {
-                    rules_arr.iter().all(|rule| {
-                        let action = rule
-                            .get("action")
-                            .and_then(|a| a.as_str())
-                            .unwrap_or("allow");
-                        let os_name = rule
-                            .get("os")
-                            .and_then(|os| os.get("name"))
-                            .and_then(|n| n.as_str());
-
-                        match (action, os_name) {
-                            ("allow", None) => true,
-                            ("allow", Some(name)) => name == current_os,
-                            ("disallow", Some(name)) => name != current_os,
-                            _ => true,
-                        }
-                    })
-                })
-                .unwrap_or(true);
-
-            if !should_apply {
+            if !RuleEvaluator::applies(lib, None) {
                 return Ok(());
             }
 
@@ -314,8 +244,7 @@ impl ForgeLoader {
                     let classifier_key = format!("natives-{}", current_os);
                     if let Some(native_info) = classifiers.get(&classifier_key) {
                         if let Some(path) = native_info.get("path").and_then(|p| p.as_str()) {
-                            let jar_path = libraries_dir
-                                .join(path.replace('/', &std::path::MAIN_SEPARATOR.to_string()));
+                            let jar_path = paths::join_relative(&libraries_dir, path);
                             if jar_path.exists() {
                                 Some(jar_path)
                             } else {
@@ -334,9 +263,7 @@ impl ForgeLoader {
             } else {
                 // Fallback to legacy format
                 if let Some(name) = lib.get("name").and_then(|n| n.as_str()) {
-                    if let Some((group_id, artifact_id, version)) = Self::parse_library_info(name) {
-                        let group_path =
-                            group_id.replace('.', &std::path::MAIN_SEPARATOR.to_string());
+                    if let Some(coordinate) = MavenCoordinate::parse(name) {
                         let classifier = match current_os {
                             "windows" => "natives-windows",
                             "osx" => "natives-osx",
@@ -344,12 +271,12 @@ impl ForgeLoader {
                             _ => return Ok(()),
                         };
 
-                        let native_jar = format!("{}-{}-{}.jar", artifact_id, version, classifier);
-                        let jar_path = libraries_dir
-                            .join(group_path)
-                            .join(&artifact_id)
-                            .join(version)
-                            .join(&native_jar);
+                        let native = MavenCoordinate {
+                            classifier: Some(classifier.to_string()),
+                            extension: "jar".to_string(),
+                            ..coordinate
+                        };
+                        let jar_path = paths::join_relative(&libraries_dir, &native.url_path());
 
                         if jar_path.exists() {
                             Some(jar_path)
@@ -369,56 +296,34 @@ impl ForgeLoader {
             if let Some(jar_path) = native_path {
                 println!("Extracting natives from: {}", jar_path.display());
 
-                let file = File::open(&jar_path)?;
-                let mut archive = ZipArchive::new(file)?;
-
-                for i in 0..archive.len() {
-                    let mut file = archive.by_index(i)?;
-                    let outpath = match file.enclosed_name() {
-                        Some(path) => path.to_owned(),
-                        None => continue,
-                    };
-
-                    // Skip directories and unwanted files
-                    let file_name = outpath.file_name().unwrap().to_string_lossy();
-                    if file_name.contains("META-INF")
-                        || file_name.contains("MANIFEST.MF")
-                        || file_name.ends_with(".git")
-                        || file_name.ends_with(".sha1")
-                        || file_name.ends_with(".md5")
-                    {
-                        continue;
-                    }
-
-                    // Only extract DLL, SO, and DYLIB files
-                    let is_native_file = file_name.ends_with(".dll")
-                        || file_name.ends_with(".so")
-                        || file_name.ends_with(".dylib")
-                        || file_name.ends_with(".jnilib");
+                ZipExtractor::new(&jar_path, natives_dir)
+                    .map_entries(|outpath| {
+                        // Skip directories and unwanted files
+                        let file_name = outpath.file_name()?.to_string_lossy().to_string();
+                        if file_name.contains("META-INF")
+                            || file_name.contains("MANIFEST.MF")
+                            || file_name.ends_with(".git")
+                            || file_name.ends_with(".sha1")
+                            || file_name.ends_with(".md5")
+                        {
+                            return None;
+                        }
 
-                    if is_native_file {
-                        // Construct output path
-                        let mut outfile = natives_dir.join(file_name.as_ref());
+                        // Only extract DLL, SO, and DYLIB files
+                        let is_native_file = file_name.ends_with(".dll")
+                            || file_name.ends_with(".so")
+                            || file_name.ends_with(".dylib")
+                            || file_name.ends_with(".jnilib");
 
                         // Skip if already extracted
-                        if extracted_files.contains(&file_name.to_string()) {
-                            continue;
+                        if !is_native_file || !extracted_files.insert(file_name.clone()) {
+                            return None;
                         }
 
                         println!("  Extracting: {}", file_name);
-
-                        if let Some(p) = outfile.parent() {
-                            if !p.exists() {
-                                fs::create_dir_all(p)?;
-                            }
-                        }
-
-                        let mut outfile_writer = File::create(&outfile)?;
-                        std::io::copy(&mut file, &mut outfile_writer)?;
-
-                        extracted_files.insert(file_name.to_string());
-                    }
-                }
+                        Some(PathBuf::from(file_name))
+                    })
+                    .extract()?;
             }
 
             Ok(())
@@ -455,18 +360,6 @@ impl ForgeLoader {
         Ok(())
     }
 
-    // Helper function to parse library info
-    fn parse_library_info(lib_name: &str) -> Option<(String, String, String)> {
-        let parts: Vec<&str> = lib_name.split(':').collect();
-        if parts.len() >= 3 {
-            let group_id = parts[0].to_string();
-            let artifact_id = parts[1].to_string();
-            let version = parts[2].split('@').next().unwrap_or(parts[2]).to_string();
-            return Some((group_id, artifact_id, version));
-        }
-        None
-    }
-
     // Process Forge-specific JVM arguments
     fn process_forge_jvm_arguments(
         &self,
@@ -581,7 +474,10 @@ impl ForgeLoader {
             }
             .to_string(),
         );
-        placeholders.insert("version_type".to_string(), "release".to_string());
+        placeholders.insert(
+            "version_type".to_string(),
+            version_type(vanilla_manifest_json).to_string(),
+        );
         placeholders.insert(
             "natives_directory".to_string(),
             natives_dir.to_string_lossy().to_string(),
@@ -693,13 +589,15 @@ impl ForgeLoader {
 
 impl GameLauncher for ForgeLoader {
     fn launch(&self) -> Option<Child> {
-        let config_lock = get_config_manager()
-            .lock()
-            .expect("Failed to lock config manager mutex");
+        let config_lock = read_config();
 
-        let config = config_lock
-            .as_ref()
-            .expect("Config manager failed to initialize");
+        let config = match config_lock.as_ref() {
+            Ok(config) => config,
+            Err(e) => {
+                println!("Config manager failed to initialize: {}", e);
+                return None;
+            }
+        };
 
         let mc_memory = config.get_minecraft_memory().unwrap_or(2048); // Default to 2GB if not set
         println!("Minecraft memory: {}MB", mc_memory);
@@ -734,13 +632,10 @@ impl GameLauncher for ForgeLoader {
         let account = match accounts_manager.get_minecraft_account_by_uuid(account_uuid) {
             Some(acct) => acct,
             None => {
+                // Sin caer en una cuenta offline aleatoria: InstanceLauncher
+                // valida la cuenta y decide si se juega sin conexión
                 println!("Account not found for UUID: {}", account_uuid);
-                MinecraftAccount::new(
-                    "offline".to_string(),
-                    Uuid::new_v4().to_string(),
-                    None,
-                    "offline".to_string(),
-                )
+                return None;
             }
         };
 
@@ -748,7 +643,7 @@ impl GameLauncher for ForgeLoader {
 
         // Get game directory
         let game_dir = match &self.instance.instanceDirectory {
-            Some(dir) => PathBuf::from(dir).join("minecraft"),
+            Some(dir) => paths::normalize(&paths::native_path(dir).join("minecraft")),
             None => PathBuf::from("default_path").join("minecraft"),
         };
 
@@ -764,18 +659,12 @@ impl GameLauncher for ForgeLoader {
         });
 
         // Forge version folder and manifest pattern: mcVersion-forge-forgeVersion
-        let version_dir = game_dir
-            .join("versions")
-            .join(format!("{}-forge-{}", minecraft_version, forge_version));
-        let forge_client_jar =
-            version_dir.join(format!("{}-forge-{}.jar", minecraft_version, forge_version));
-        let natives_dir = game_dir.join("natives").join(&minecraft_version); // Uses the same natives directory as vanilla
-        let libraries_dir = game_dir.join("libraries");
-        let assets_dir = game_dir.join("assets");
-        let forge_manifest_file = version_dir.join(format!(
-            "{}-forge-{}.json",
-            minecraft_version, forge_version
-        ));
+        let forge_version_id = format!("{}-forge-{}", minecraft_version, forge_version);
+        let forge_client_jar = paths::version_file(&game_dir, &forge_version_id, "jar");
+        let natives_dir = paths::extracted_natives_dir(&game_dir, &minecraft_version); // Uses the same natives directory as vanilla
+        let libraries_dir = paths::libraries_dir(&game_dir);
+        let assets_dir = paths::assets_dir(&game_dir);
+        let forge_manifest_file = paths::version_file(&game_dir, &forge_version_id, "json");
 
         // Read and parse the Forge JSON manifest
         let forge_manifest_data = match fs::read_to_string(&forge_manifest_file) {
@@ -809,9 +698,8 @@ impl GameLauncher for ForgeLoader {
         );
 
         // Set up vanilla-related paths
-        let vanilla_version_dir = game_dir.join("versions").join(&vanilla_version);
-        let vanilla_client_jar = vanilla_version_dir.join(format!("{}.jar", vanilla_version));
-        let vanilla_manifest_file = vanilla_version_dir.join(format!("{}.json", vanilla_version));
+        let vanilla_client_jar = paths::version_file(&game_dir, &vanilla_version, "jar");
+        let vanilla_manifest_file = paths::version_file(&game_dir, &vanilla_version, "json");
 
         // Read and parse the vanilla JSON manifest
         let vanilla_manifest_data = match fs::read_to_string(&vanilla_manifest_file) {
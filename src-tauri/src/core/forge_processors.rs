@@ -0,0 +1,542 @@
+// src-tauri/src/core/forge_processors.rs
+//! Native ("headless") Forge installation: the installer's processors run
+//! here instead of through its CLI.
+
+use crate::core::i18n::{keys, Message};
+use crate::core::inflight_downloads;
+use crate::core::minecraft::paths;
+use crate::core::minecraft::MavenCoordinate;
+use crate::core::mirrors;
+use crate::core::subprocess;
+use crate::core::zip_extract::ZipExtractor;
+use serde_json::Value;
+use sha1::{Digest, Sha1};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tauri_plugin_http::reqwest;
+
+const FORGE_MAVEN_URL: &str = "https://maven.minecraftforge.net/";
+
+pub struct HeadlessForgeInstaller<'a> {
+    client: &'a reqwest::blocking::Client,
+    installer_path: PathBuf,
+    minecraft_dir: PathBuf,
+    minecraft_version: String,
+    java_path: PathBuf,
+}
+
+impl<'a> HeadlessForgeInstaller<'a> {
+    pub fn new(
+        client: &'a reqwest::blocking::Client,
+        installer_path: &Path,
+        minecraft_dir: &Path,
+        minecraft_version: &str,
+        java_path: &Path,
+    ) -> Self {
+        Self {
+            client,
+            installer_path: installer_path.to_path_buf(),
+            minecraft_dir: minecraft_dir.to_path_buf(),
+            minecraft_version: minecraft_version.to_string(),
+            java_path: java_path.to_path_buf(),
+        }
+    }
+
+    /// Whether the installer uses the processor based format we can handle.
+    pub fn is_supported(installer_path: &Path) -> bool {
+        match read_installer_json(installer_path, "install_profile.json") {
+            Ok(profile) => {
+                profile.get("spec").and_then(Value::as_u64).unwrap_or(0) >= 1
+                    && profile.get("processors").is_some()
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Whether the installer uses the legacy `install` / `versionInfo` format.
+    pub fn is_legacy(installer_path: &Path) -> bool {
+        match read_installer_json(installer_path, "install_profile.json") {
+            Ok(profile) => profile.get("versionInfo").is_some() && profile.get("install").is_some(),
+            Err(_) => false,
+        }
+    }
+
+    /// Installs a legacy Forge profile as `version_id`: writes `versionInfo`
+    /// as the version JSON and extracts the universal jar to its maven path.
+    /// Libraries are downloaded afterwards from the written JSON.
+    pub fn install_legacy(&self, version_id: &str) -> Result<String, String> {
+        let profile = read_installer_json(&self.installer_path, "install_profile.json")?;
+        let install = profile
+            .get("install")
+            .ok_or_else(|| "install_profile.json sin sección install".to_string())?;
+        let mut version_json = profile
+            .get("versionInfo")
+            .cloned()
+            .ok_or_else(|| "install_profile.json sin versionInfo".to_string())?;
+
+        // Usamos nuestro propio id ({mc}-forge-{forge}); los instaladores
+        // antiguos usan ids como "1.7.10-Forge10.13.4.1614-1.7.10"
+        version_json["id"] = Value::String(version_id.to_string());
+        if version_json.get("inheritsFrom").is_none() {
+            version_json["inheritsFrom"] = Value::String(self.minecraft_version.clone());
+        }
+
+        let version_dir = paths::version_dir(&self.minecraft_dir, version_id);
+        fs::create_dir_all(&version_dir)
+            .map_err(|e| format!("Error al crear directorio de versión Forge: {}", e))?;
+        fs::write(
+            paths::version_file(&self.minecraft_dir, version_id, "json"),
+            serde_json::to_string_pretty(&version_json).unwrap_or_default(),
+        )
+        .map_err(|e| format!("Error al escribir version.json de Forge: {}", e))?;
+
+        // El jar universal va en libraries/ bajo la coordenada de install.path
+        let coord = install
+            .get("path")
+            .and_then(Value::as_str)
+            .ok_or_else(|| "install_profile.json sin install.path".to_string())?;
+        let file_path = install
+            .get("filePath")
+            .and_then(Value::as_str)
+            .ok_or_else(|| "install_profile.json sin install.filePath".to_string())?;
+        let target = self.library_path(coord)?;
+        extract_installer_entry(&self.installer_path, file_path, &target)?;
+
+        log::info!(
+            "[Forge Processors] Legacy Forge {} installed ({})",
+            version_id,
+            target.display()
+        );
+        Ok(version_id.to_string())
+    }
+
+    fn libraries_dir(&self) -> PathBuf {
+        paths::libraries_dir(&self.minecraft_dir)
+    }
+
+    /// Runs the full installation and returns the installed version id
+    /// (e.g. `1.20.1-forge-47.2.0`). `on_progress` receives 0.0 - 1.0.
+    pub fn install<F: Fn(f32, Message)>(&self, on_progress: F) -> Result<String, String> {
+        let profile = read_installer_json(&self.installer_path, "install_profile.json")?;
+
+        // 1. version.json del perfil de Forge
+        let json_entry = profile
+            .get("json")
+            .and_then(Value::as_str)
+            .unwrap_or("/version.json")
+            .trim_start_matches('/');
+        let version_json = read_installer_json(&self.installer_path, json_entry)?;
+        let version_id = version_json
+            .get("id")
+            .and_then(Value::as_str)
+            .ok_or_else(|| "El version.json de Forge no tiene id".to_string())?
+            .to_string();
+
+        let version_dir = paths::version_dir(&self.minecraft_dir, &version_id);
+        fs::create_dir_all(&version_dir)
+            .map_err(|e| format!("Error al crear directorio de versión Forge: {}", e))?;
+        fs::write(
+            paths::version_file(&self.minecraft_dir, &version_id, "json"),
+            serde_json::to_string_pretty(&version_json).unwrap_or_default(),
+        )
+        .map_err(|e| format!("Error al escribir version.json de Forge: {}", e))?;
+
+        on_progress(0.05, Message::new(keys::FORGE_EXTRACTING_ARTIFACTS));
+
+        // 2. Artefactos incluidos en el instalador (maven/...)
+        self.extract_embedded_maven()?;
+
+        // 3. Librerías que necesitan los processors y el propio perfil
+        let mut libraries: Vec<&Value> = Vec::new();
+        for source in [&profile, &version_json] {
+            if let Some(list) = source.get("libraries").and_then(Value::as_array) {
+                libraries.extend(list.iter());
+            }
+        }
+
+        let total_libraries = libraries.len().max(1);
+        for (index, library) in libraries.iter().enumerate() {
+            self.download_library(library)?;
+            on_progress(
+                0.05 + 0.4 * ((index + 1) as f32 / total_libraries as f32),
+                Message::new(keys::FORGE_LIBRARIES_PROGRESS).progress(index + 1, libraries.len()),
+            );
+        }
+
+        // 4. Processors
+        let temp_dir = self.minecraft_dir.join(".forge-install-tmp");
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(&temp_dir)
+            .map_err(|e| format!("Error al crear directorio temporal: {}", e))?;
+
+        let result = self.run_processors(&profile, &temp_dir, &on_progress);
+        let _ = fs::remove_dir_all(&temp_dir);
+        result?;
+
+        on_progress(1.0, Message::new(keys::FORGE_INSTALLED));
+        Ok(version_id)
+    }
+
+    fn run_processors<F: Fn(f32, Message)>(
+        &self,
+        profile: &Value,
+        temp_dir: &Path,
+        on_progress: &F,
+    ) -> Result<(), String> {
+        let data = self.build_data_map(profile, temp_dir)?;
+
+        let processors: Vec<&Value> = profile
+            .get("processors")
+            .and_then(Value::as_array)
+            .map(|list| {
+                list.iter()
+                    .filter(|p| {
+                        p.get("sides")
+                            .and_then(Value::as_array)
+                            .map(|sides| sides.iter().any(|s| s == "client"))
+                            .unwrap_or(true)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let total = processors.len().max(1);
+        for (index, processor) in processors.iter().enumerate() {
+            on_progress(
+                0.45 + 0.5 * (index as f32 / total as f32),
+                Message::new(keys::FORGE_PROCESSORS_PROGRESS).progress(index + 1, processors.len()),
+            );
+            self.run_processor(processor, &data)?;
+        }
+
+        Ok(())
+    }
+
+    /// Builds the `{KEY}` substitution map from the profile `data` section
+    /// plus the built-in keys the installer provides.
+    fn build_data_map(
+        &self,
+        profile: &Value,
+        temp_dir: &Path,
+    ) -> Result<HashMap<String, String>, String> {
+        let mut data = HashMap::new();
+
+        if let Some(entries) = profile.get("data").and_then(Value::as_object) {
+            for (key, value) in entries {
+                let raw = match value.get("client").and_then(Value::as_str) {
+                    Some(raw) => raw,
+                    None => continue,
+                };
+                data.insert(key.clone(), self.resolve_data_value(raw, temp_dir)?);
+            }
+        }
+
+        let client_jar = paths::version_file(&self.minecraft_dir, &self.minecraft_version, "jar");
+
+        data.insert("SIDE".into(), "client".into());
+        data.insert("MINECRAFT_JAR".into(), path_string(&client_jar));
+        data.insert("MINECRAFT_VERSION".into(), self.minecraft_version.clone());
+        data.insert("ROOT".into(), path_string(&self.minecraft_dir));
+        data.insert("INSTALLER".into(), path_string(&self.installer_path));
+        data.insert("LIBRARY_DIR".into(), path_string(&self.libraries_dir()));
+
+        Ok(data)
+    }
+
+    /// `[coord]` -> library path, `'literal'` -> literal, `/path` -> file
+    /// extracted from the installer.
+    fn resolve_data_value(&self, raw: &str, temp_dir: &Path) -> Result<String, String> {
+        if let Some(coord) = raw.strip_prefix('[').and_then(|r| r.strip_suffix(']')) {
+            return self.library_path(coord).map(|p| path_string(&p));
+        }
+        if let Some(literal) = raw.strip_prefix('\'').and_then(|r| r.strip_suffix('\'')) {
+            return Ok(literal.to_string());
+        }
+        if let Some(entry) = raw.strip_prefix('/') {
+            let target = temp_dir.join(entry);
+            extract_installer_entry(&self.installer_path, entry, &target)?;
+            return Ok(path_string(&target));
+        }
+        Ok(raw.to_string())
+    }
+
+    fn resolve_arg(&self, arg: &str, data: &HashMap<String, String>) -> Result<String, String> {
+        if let Some(coord) = arg.strip_prefix('[').and_then(|r| r.strip_suffix(']')) {
+            return self.library_path(coord).map(|p| path_string(&p));
+        }
+        if let Some(literal) = arg.strip_prefix('\'').and_then(|r| r.strip_suffix('\'')) {
+            return Ok(literal.to_string());
+        }
+
+        let mut result = arg.to_string();
+        for (key, value) in data {
+            result = result.replace(&format!("{{{}}}", key), value);
+        }
+        Ok(result)
+    }
+
+    fn run_processor(
+        &self,
+        processor: &Value,
+        data: &HashMap<String, String>,
+    ) -> Result<(), String> {
+        let jar_coord = processor
+            .get("jar")
+            .and_then(Value::as_str)
+            .ok_or_else(|| "Processor sin jar".to_string())?;
+        let jar_path = self.library_path(jar_coord)?;
+
+        // Si las salidas ya existen con el hash esperado no hace falta repetirlo
+        let outputs = self.resolve_outputs(processor, data)?;
+        if !outputs.is_empty()
+            && outputs
+                .iter()
+                .all(|(path, sha)| file_sha1(Path::new(path)).as_deref() == Some(sha.as_str()))
+        {
+            log::info!(
+                "[Forge Processors] Skipping {} (outputs up to date)",
+                jar_coord
+            );
+            return Ok(());
+        }
+
+        let main_class = read_main_class(&jar_path)?;
+
+        let mut classpath = vec![jar_path];
+        if let Some(entries) = processor.get("classpath").and_then(Value::as_array) {
+            for entry in entries.iter().filter_map(Value::as_str) {
+                classpath.push(self.library_path(entry)?);
+            }
+        }
+        let separator = if cfg!(windows) { ";" } else { ":" };
+        let classpath = classpath
+            .iter()
+            .map(|p| path_string(p))
+            .collect::<Vec<_>>()
+            .join(separator);
+
+        let mut args = Vec::new();
+        if let Some(entries) = processor.get("args").and_then(Value::as_array) {
+            for arg in entries.iter().filter_map(Value::as_str) {
+                args.push(self.resolve_arg(arg, data)?);
+            }
+        }
+
+        log::info!(
+            "[Forge Processors] Running {} ({}) with args {:?}",
+            jar_coord,
+            main_class,
+            args
+        );
+
+        let output = subprocess::background_process(
+            Command::new(&self.java_path)
+                .arg("-cp")
+                .arg(&classpath)
+                .arg(&main_class)
+                .args(&args)
+                .current_dir(&self.minecraft_dir),
+        )
+        .output()
+        .map_err(|e| format!("Error al ejecutar processor {}: {}", jar_coord, e))?;
+
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            log::info!("[Forge Processors] {}", line);
+        }
+
+        if !output.status.success() {
+            return Err(format!(
+                "El processor {} falló: {}",
+                jar_coord,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        for (path, expected) in &outputs {
+            let actual = file_sha1(Path::new(path));
+            if actual.as_deref() != Some(expected.as_str()) {
+                let _ = fs::remove_file(path);
+                return Err(format!(
+                    "Salida inválida del processor {}: {} (sha1 esperado {}, obtenido {:?})",
+                    jar_coord, path, expected, actual
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn resolve_outputs(
+        &self,
+        processor: &Value,
+        data: &HashMap<String, String>,
+    ) -> Result<Vec<(String, String)>, String> {
+        let mut outputs = Vec::new();
+        if let Some(entries) = processor.get("outputs").and_then(Value::as_object) {
+            for (key, value) in entries {
+                let path = self.resolve_arg(key, data)?;
+                let sha = self.resolve_arg(value.as_str().unwrap_or_default(), data)?;
+                outputs.push((path, sha));
+            }
+        }
+        Ok(outputs)
+    }
+
+    fn library_path(&self, coord: &str) -> Result<PathBuf, String> {
+        MavenCoordinate::parse(coord)
+            .map(|coordinate| self.libraries_dir().join(coordinate.relative_path()))
+            .ok_or_else(|| format!("Coordenada Maven inválida: {}", coord))
+    }
+
+    /// Copies every `maven/...` entry of the installer into the libraries dir.
+    fn extract_embedded_maven(&self) -> Result<(), String> {
+        ZipExtractor::new(&self.installer_path, &self.libraries_dir())
+            .map_entries(|name| name.strip_prefix("maven").ok().map(Path::to_path_buf))
+            .extract()
+            .map_err(|e| format!("Error al extraer librerías del instalador de Forge: {}", e))?;
+
+        Ok(())
+    }
+
+    fn download_library(&self, library: &Value) -> Result<(), String> {
+        let name = library.get("name").and_then(Value::as_str).unwrap_or("");
+        let artifact = library.get("downloads").and_then(|d| d.get("artifact"));
+
+        let relative = artifact
+            .and_then(|a| a.get("path"))
+            .and_then(Value::as_str)
+            .map(PathBuf::from)
+            .or_else(|| MavenCoordinate::parse(name).map(|coordinate| coordinate.relative_path()));
+        let relative = match relative {
+            Some(relative) => relative,
+            None => return Ok(()),
+        };
+        let target = paths::join_relative(&self.libraries_dir(), &relative.to_string_lossy());
+        let expected_sha = artifact.and_then(|a| a.get("sha1")).and_then(Value::as_str);
+
+        if target.exists() {
+            match expected_sha {
+                Some(sha) if file_sha1(&target).as_deref() != Some(sha) => {
+                    log::warn!("[Forge Processors] Hash mismatch, re-downloading {}", name);
+                }
+                _ => return Ok(()),
+            }
+        }
+
+        let url = artifact
+            .and_then(|a| a.get("url"))
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| {
+                format!(
+                    "{}{}",
+                    FORGE_MAVEN_URL,
+                    relative.to_string_lossy().replace('\\', "/")
+                )
+            });
+
+        // URL vacía: el artefacto lo generan los processors o viene en el instalador
+        if url.is_empty() {
+            return Ok(());
+        }
+
+        inflight_downloads::download(&target, expected_sha, |temp| {
+            let mut response = mirrors::get(&self.client, &url)
+                .map_err(|e| format!("Error al descargar {}: {}", name, e))?;
+            if !response.status().is_success() {
+                return Err(format!(
+                    "Error al descargar {}: estado {}",
+                    name,
+                    response.status()
+                ));
+            }
+            let mut file = fs::File::create(temp)
+                .map_err(|e| format!("Error al crear {}: {}", temp.display(), e))?;
+            response
+                .copy_to(&mut file)
+                .map_err(|e| format!("Error al escribir {}: {}", temp.display(), e))?;
+
+            match expected_sha {
+                Some(sha) if file_sha1(temp).as_deref() != Some(sha) => {
+                    Err(format!("Hash inválido para la librería {}", name))
+                }
+                _ => Ok(()),
+            }
+        })?;
+
+        Ok(())
+    }
+}
+
+fn path_string(path: &Path) -> String {
+    path.to_string_lossy().to_string()
+}
+
+pub(crate) fn file_sha1(path: &Path) -> Option<String> {
+    let mut file = fs::File::open(path).ok()?;
+    let mut hasher = Sha1::new();
+    io::copy(&mut file, &mut hasher).ok()?;
+    Some(format!("{:x}", hasher.finalize()))
+}
+
+fn read_installer_json(installer_path: &Path, entry: &str) -> Result<Value, String> {
+    let file = fs::File::open(installer_path)
+        .map_err(|e| format!("Error al abrir instalador de Forge: {}", e))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("Instalador de Forge inválido: {}", e))?;
+    let mut file = archive
+        .by_name(entry)
+        .map_err(|_| format!("{} no encontrado en el instalador", entry))?;
+
+    let mut content = String::new();
+    file.read_to_string(&mut content)
+        .map_err(|e| format!("Error al leer {}: {}", entry, e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Error al parsear {}: {}", entry, e))
+}
+
+fn extract_installer_entry(
+    installer_path: &Path,
+    entry: &str,
+    target: &Path,
+) -> Result<(), String> {
+    let file = fs::File::open(installer_path)
+        .map_err(|e| format!("Error al abrir instalador de Forge: {}", e))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("Instalador de Forge inválido: {}", e))?;
+    let mut source = archive
+        .by_name(entry)
+        .map_err(|_| format!("{} no encontrado en el instalador", entry))?;
+
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Error al crear directorio: {}", e))?;
+    }
+    let mut out =
+        fs::File::create(target).map_err(|e| format!("Error al extraer {}: {}", entry, e))?;
+    io::copy(&mut source, &mut out).map_err(|e| format!("Error al extraer {}: {}", entry, e))?;
+    Ok(())
+}
+
+/// Reads `Main-Class` from a jar's manifest.
+fn read_main_class(jar_path: &Path) -> Result<String, String> {
+    let file = fs::File::open(jar_path)
+        .map_err(|e| format!("No se encontró el processor {}: {}", jar_path.display(), e))?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| format!("Jar inválido {}: {}", jar_path.display(), e))?;
+    let mut manifest = archive
+        .by_name("META-INF/MANIFEST.MF")
+        .map_err(|_| format!("{} no tiene MANIFEST.MF", jar_path.display()))?;
+
+    let mut content = String::new();
+    manifest
+        .read_to_string(&mut content)
+        .map_err(|e| format!("Error al leer MANIFEST.MF: {}", e))?;
+
+    content
+        .lines()
+        .find_map(|line| line.strip_prefix("Main-Class:"))
+        .map(|class| class.trim().to_string())
+        .ok_or_else(|| format!("{} no declara Main-Class", jar_path.display()))
+}
@@ -0,0 +1,145 @@
+//! Game language (`lang` in `options.txt`) handling.
+
+use crate::config::with_config;
+use crate::core::minecraft::maven_version::MavenVersion;
+use crate::core::minecraft::options;
+use crate::core::minecraft_instance::MinecraftInstance;
+use crate::core::models::ModpackManifest;
+use std::path::Path;
+
+/// Idioma de la interfaz si la configuración no se puede leer
+const DEFAULT_UI_LANGUAGE: &str = "es";
+
+fn ui_language() -> String {
+    with_config(|config| config.get_language())
+        .ok()
+        .unwrap_or_else(|| DEFAULT_UI_LANGUAGE.to_string())
+}
+
+fn global_game_language() -> Option<String> {
+    with_config(|config| config.get_game_language())
+        .ok()
+        .flatten()
+}
+
+/// Accepts `es`, `es_es`, `es-ES`... (letters and one separator).
+pub fn validate_language_code(code: &str) -> Result<(), String> {
+    let valid = code
+        .split(['_', '-'])
+        .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphabetic()))
+        && code.matches(['_', '-']).count() <= 1;
+
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Código de idioma no válido: {}", code))
+    }
+}
+
+/// Region Minecraft uses for a bare language, where it is not the language
+/// code itself (`ja` is `ja_jp`, not `ja_ja`).
+const DEFAULT_REGIONS: &[(&str, &str)] = &[
+    ("af", "za"),
+    ("ar", "sa"),
+    ("be", "by"),
+    ("ca", "es"),
+    ("cs", "cz"),
+    ("cy", "gb"),
+    ("da", "dk"),
+    ("el", "gr"),
+    ("en", "us"),
+    ("et", "ee"),
+    ("eu", "es"),
+    ("fa", "ir"),
+    ("fil", "ph"),
+    ("ga", "ie"),
+    ("gl", "es"),
+    ("he", "il"),
+    ("hi", "in"),
+    ("ja", "jp"),
+    ("kk", "kz"),
+    ("ko", "kr"),
+    ("ms", "my"),
+    ("nb", "no"),
+    ("nn", "no"),
+    ("pt", "br"),
+    ("sl", "si"),
+    ("sr", "sp"),
+    ("sv", "se"),
+    ("uk", "ua"),
+    ("vi", "vn"),
+    ("zh", "cn"),
+];
+
+/// Minecraft locale for `code`: `xx_yy` since 1.11, `xx_YY` before.
+/// A bare language is expanded to its main region: `es` to `es_es`, `ja`
+/// to `ja_jp` (see `DEFAULT_REGIONS`).
+fn minecraft_locale(code: &str, minecraft_version: &str) -> String {
+    let code = code.trim().replace('-', "_").to_lowercase();
+    let (language, region) = match code.split_once('_') {
+        Some((language, region)) => (language.to_string(), region.to_string()),
+        None => {
+            let region = DEFAULT_REGIONS
+                .iter()
+                .find(|(language, _)| *language == code)
+                .map(|(_, region)| region.to_string())
+                .unwrap_or_else(|| code.clone());
+            (code, region)
+        }
+    };
+
+    if MavenVersion::parse(minecraft_version) < MavenVersion::parse("1.11") {
+        format!("{}_{}", language, region.to_uppercase())
+    } else {
+        format!("{}_{}", language, region)
+    }
+}
+
+/// `defaultLanguage` suggested by the modpack manifest, if any.
+fn modpack_default_language(instance_dir: &Path) -> Option<String> {
+    ModpackManifest::read(instance_dir)
+        .ok()??
+        .default_language
+        .filter(|code| validate_language_code(code).is_ok())
+}
+
+/// Writes the language to `options.txt` before launching `instance`.
+pub fn apply_game_language(instance: &MinecraftInstance) -> Result<(), String> {
+    let game_dir = Path::new(&instance.minecraftPath);
+    let is_new_install = options::read_option(game_dir, "lang").is_none();
+
+    let explicit = instance
+        .gameLanguage
+        .clone()
+        .filter(|code| !code.trim().is_empty())
+        .or_else(global_game_language);
+
+    let code = match explicit {
+        Some(code) => code,
+        None if is_new_install => instance
+            .instanceDirectory
+            .as_deref()
+            .and_then(|dir| modpack_default_language(Path::new(dir)))
+            .unwrap_or_else(ui_language),
+        None => return Ok(()),
+    };
+
+    let locale = minecraft_locale(&code, &instance.minecraftVersion);
+    if options::read_option(game_dir, "lang").as_deref() == Some(locale.as_str()) {
+        return Ok(());
+    }
+
+    log::info!(
+        "[Language] Setting lang:{} for instance {}",
+        locale,
+        instance.instanceId
+    );
+    options::set_option(game_dir, "lang", &locale)
+        .map_err(|e| format!("Error al escribir options.txt: {}", e))
+}
+
+/// Language of the launcher UI (`language` setting).
+#[tauri::command]
+pub fn get_ui_locale() -> String {
+    ui_language()
+}
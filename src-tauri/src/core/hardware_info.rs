@@ -0,0 +1,360 @@
+//! GPU and hardware detection.
+
+use crate::core::minecraft::maven_version::MavenVersion;
+use crate::core::subprocess;
+use crate::core::system_resources::SystemMemory;
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use sysinfo::System;
+
+static GPUS: OnceCell<Vec<GpuInfo>> = OnceCell::new();
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum GpuVendor {
+    Nvidia,
+    Amd,
+    Intel,
+    Apple,
+    /// Adaptadores sin aceleración (llvmpipe, "Microsoft Basic Display Adapter", VMs)
+    Software,
+    Unknown,
+}
+
+impl GpuVendor {
+    fn from_name(name: &str) -> Self {
+        let name = name.to_lowercase();
+        if [
+            "llvmpipe",
+            "softpipe",
+            "basic display",
+            "basic render",
+            "vmware svga",
+            "virtualbox",
+        ]
+        .iter()
+        .any(|software| name.contains(software))
+        {
+            GpuVendor::Software
+        } else if name.contains("nvidia") {
+            GpuVendor::Nvidia
+        } else if name.contains("amd") || name.contains("radeon") || name.contains("advanced micro")
+        {
+            GpuVendor::Amd
+        } else if name.contains("intel") {
+            GpuVendor::Intel
+        } else if name.contains("apple") {
+            GpuVendor::Apple
+        } else {
+            GpuVendor::Unknown
+        }
+    }
+
+    fn from_pci_id(id: &str) -> Self {
+        match id.trim().trim_start_matches("0x") {
+            "10de" => GpuVendor::Nvidia,
+            "1002" | "1022" => GpuVendor::Amd,
+            "8086" => GpuVendor::Intel,
+            "15ad" | "80ee" | "1234" => GpuVendor::Software,
+            _ => GpuVendor::Unknown,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GpuInfo {
+    pub vendor: GpuVendor,
+    pub model: String,
+    pub driver_version: Option<String>,
+}
+
+impl GpuInfo {
+    fn new(model: &str, vendor_hint: &str, driver_version: Option<String>) -> Self {
+        let vendor = match GpuVendor::from_name(model) {
+            GpuVendor::Unknown => GpuVendor::from_name(vendor_hint),
+            vendor => vendor,
+        };
+        Self {
+            vendor,
+            model: model.trim().to_string(),
+            driver_version: driver_version.filter(|version| !version.trim().is_empty()),
+        }
+    }
+
+    fn is_discrete(&self) -> bool {
+        matches!(self.vendor, GpuVendor::Nvidia | GpuVendor::Amd)
+    }
+
+    /// Intel GMA and "HD Graphics" iGPUs (before UHD/Iris Xe/Arc).
+    fn is_legacy_intel(&self) -> bool {
+        let model = self.model.to_lowercase();
+        self.vendor == GpuVendor::Intel
+            && (model.contains("gma") || (model.contains("hd graphics") && !model.contains("uhd")))
+    }
+
+    /// Sandy Bridge and older only expose OpenGL 3.1, below what 1.17+ needs.
+    fn lacks_opengl_32(&self) -> bool {
+        let model = self.model.to_lowercase();
+        self.vendor == GpuVendor::Intel
+            && (model.contains("gma")
+                || ["hd graphics 2000", "hd graphics 3000"]
+                    .iter()
+                    .any(|old| model.contains(old))
+                || model.ends_with("hd graphics"))
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HardwareInfo {
+    pub os: String,
+    pub arch: String,
+    pub cpu: Option<String>,
+    pub memory: SystemMemory,
+    pub gpus: Vec<GpuInfo>,
+}
+
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = subprocess::hide_console(Command::new(program).args(args))
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[cfg(target_os = "windows")]
+fn detect_platform_gpus() -> Vec<GpuInfo> {
+    use serde_json::Value;
+
+    let Some(output) = command_output(
+        "powershell",
+        &[
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            "Get-CimInstance Win32_VideoController | Select-Object Name,AdapterCompatibility,DriverVersion | ConvertTo-Json",
+        ],
+    ) else {
+        return Vec::new();
+    };
+
+    // ConvertTo-Json devuelve un objeto si sólo hay un adaptador
+    let adapters = match serde_json::from_str::<Value>(&output) {
+        Ok(Value::Array(adapters)) => adapters,
+        Ok(adapter @ Value::Object(_)) => vec![adapter],
+        _ => return Vec::new(),
+    };
+
+    adapters
+        .iter()
+        .filter_map(|adapter| {
+            let model = adapter.get("Name").and_then(Value::as_str)?;
+            let vendor = adapter
+                .get("AdapterCompatibility")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            let driver = adapter
+                .get("DriverVersion")
+                .and_then(Value::as_str)
+                .map(str::to_string);
+            Some(GpuInfo::new(model, vendor, driver))
+        })
+        .collect()
+}
+
+#[cfg(target_os = "macos")]
+fn detect_platform_gpus() -> Vec<GpuInfo> {
+    use serde_json::Value;
+
+    let Some(output) = command_output("system_profiler", &["SPDisplaysDataType", "-json"]) else {
+        return Vec::new();
+    };
+    let Ok(report) = serde_json::from_str::<Value>(&output) else {
+        return Vec::new();
+    };
+
+    report
+        .get("SPDisplaysDataType")
+        .and_then(Value::as_array)
+        .map(|adapters| {
+            adapters
+                .iter()
+                .filter_map(|adapter| {
+                    let model = adapter.get("sppci_model").and_then(Value::as_str)?;
+                    let vendor = adapter
+                        .get("spdisplays_vendor")
+                        .and_then(Value::as_str)
+                        .unwrap_or_default();
+                    // macOS no expone una versión de driver separada del sistema
+                    Some(GpuInfo::new(model, vendor, None))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn detect_platform_gpus() -> Vec<GpuInfo> {
+    let Ok(cards) = fs::read_dir("/sys/class/drm") else {
+        return Vec::new();
+    };
+
+    let mut gpus = Vec::new();
+    for card in cards.flatten() {
+        let name = card.file_name().to_string_lossy().to_string();
+        // card0-HDMI-A-1 y similares son conectores, no adaptadores
+        if !name.starts_with("card") || name.contains('-') {
+            continue;
+        }
+
+        let device = card.path().join("device");
+        let read = |file: &str| {
+            fs::read_to_string(device.join(file))
+                .ok()
+                .map(|value| value.trim().to_string())
+        };
+        let vendor_id = read("vendor").unwrap_or_default();
+        let device_id = read("device").unwrap_or_default();
+        let driver = fs::read_link(device.join("driver"))
+            .ok()
+            .and_then(|link| link.file_name().map(|n| n.to_string_lossy().to_string()));
+
+        let pci_id = format!(
+            "{}:{}",
+            vendor_id.trim_start_matches("0x"),
+            device_id.trim_start_matches("0x")
+        );
+        let model = command_output("lspci", &["-vmm", "-d", &pci_id])
+            .and_then(|output| {
+                output.lines().find_map(|line| {
+                    line.strip_prefix("Device:")
+                        .map(str::trim)
+                        .map(str::to_string)
+                })
+            })
+            .or_else(|| driver.clone())
+            .unwrap_or_else(|| pci_id.clone());
+
+        // Sólo los módulos fuera del kernel (nvidia) publican su versión
+        let driver_version = driver.as_deref().and_then(|driver| {
+            fs::read_to_string(Path::new("/sys/module").join(driver).join("version"))
+                .ok()
+                .map(|version| version.trim().to_string())
+        });
+
+        let mut gpu = GpuInfo::new(&model, "", driver_version);
+        if gpu.vendor == GpuVendor::Unknown {
+            gpu.vendor = GpuVendor::from_pci_id(&vendor_id);
+        }
+        gpus.push(gpu);
+    }
+    gpus
+}
+
+/// Graphics adapters of this machine; detected on first use.
+pub fn gpus() -> &'static [GpuInfo] {
+    GPUS.get_or_init(|| {
+        let gpus = detect_platform_gpus();
+        for gpu in &gpus {
+            log::info!(
+                "[Hardware] GPU: {} ({:?}, driver {})",
+                gpu.model,
+                gpu.vendor,
+                gpu.driver_version.as_deref().unwrap_or("unknown")
+            );
+        }
+        if gpus.is_empty() {
+            log::warn!("[Hardware] No GPU could be detected");
+        }
+        gpus
+    })
+}
+
+/// Detects the GPUs in the background so startup is not delayed.
+pub fn spawn_detection() {
+    std::thread::spawn(|| {
+        gpus();
+    });
+}
+
+pub fn detect() -> HardwareInfo {
+    let mut system = System::new();
+    system.refresh_cpu();
+
+    HardwareInfo {
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        cpu: system
+            .cpus()
+            .first()
+            .map(|cpu| cpu.brand().trim().to_string()),
+        memory: SystemMemory::detect(),
+        gpus: gpus().to_vec(),
+    }
+}
+
+fn has_shaderpacks(game_dir: &Path) -> bool {
+    fs::read_dir(game_dir.join("shaderpacks"))
+        .map(|mut entries| entries.next().is_some())
+        .unwrap_or(false)
+}
+
+/// Warnings for GPU and Minecraft version combinations known to go wrong.
+/// Systems with a discrete GPU skip the integrated GPU rules, since the
+/// game normally runs on the discrete one.
+pub fn gpu_advisories(minecraft_version: &str, game_dir: &Path) -> Vec<String> {
+    let gpus = gpus();
+    let mut advisories = Vec::new();
+    let modern = MavenVersion::parse(minecraft_version) >= MavenVersion::parse("1.17");
+
+    if gpus.iter().all(|gpu| gpu.vendor == GpuVendor::Software) && !gpus.is_empty() {
+        advisories.push(
+            "No hay drivers de vídeo instalados (renderizado por software); instala los drivers de tu tarjeta gráfica".to_string(),
+        );
+    }
+
+    if gpus.iter().any(GpuInfo::is_discrete) {
+        return advisories;
+    }
+
+    for gpu in gpus.iter().filter(|gpu| gpu.vendor == GpuVendor::Intel) {
+        if modern && gpu.lacks_opengl_32() {
+            advisories.push(format!(
+                "{} no soporta OpenGL 3.2, necesario desde Minecraft 1.17",
+                gpu.model
+            ));
+        } else if gpu.is_legacy_intel() && has_shaderpacks(game_dir) {
+            advisories.push(format!(
+                "{} es una gráfica integrada antigua; los shaders incluidos pueden hacer el juego injugable o cerrarlo",
+                gpu.model
+            ));
+        }
+
+        // Los drivers DCH de Intel (2019 en adelante) empiezan en 26.x
+        let driver_major = gpu
+            .driver_version
+            .as_deref()
+            .and_then(|version| version.split('.').next()?.parse::<u32>().ok());
+        if cfg!(windows) && modern && driver_major.is_some_and(|major| major < 26) {
+            advisories.push(format!(
+                "El driver de {} ({}) es antiguo; actualízalo desde la web de Intel",
+                gpu.model,
+                gpu.driver_version.as_deref().unwrap_or_default()
+            ));
+        }
+    }
+
+    advisories
+}
+
+/// Hardware summary for the diagnostics view.
+#[tauri::command]
+pub fn get_hardware_info() -> HardwareInfo {
+    detect()
+}
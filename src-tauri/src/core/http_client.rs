@@ -0,0 +1,149 @@
+//! Shared HTTP client builders, so the proxy and CA settings apply everywhere.
+
+use crate::config::{with_config, ConfigManager};
+use once_cell::sync::Lazy;
+use std::fs;
+use std::path::Path;
+use std::sync::RwLock;
+use tauri_plugin_http::reqwest;
+
+/// Ajustes que obligan a reconstruir la configuración de red
+pub const HTTP_CONFIG_KEYS: &[&str] = &[
+    "proxyMode",
+    "proxyHost",
+    "proxyPort",
+    "proxyUsername",
+    "proxyPassword",
+    "caBundlePath",
+];
+
+#[derive(Debug, Clone, Default)]
+enum ProxySetting {
+    /// Variables de entorno y proxy del sistema (comportamiento de reqwest)
+    #[default]
+    System,
+    /// Conexión directa, ignorando el proxy del sistema
+    Direct,
+    Manual(reqwest::Proxy),
+}
+
+#[derive(Debug, Clone, Default)]
+struct HttpSettings {
+    proxy: ProxySetting,
+    certificates: Vec<reqwest::Certificate>,
+}
+
+static SETTINGS: Lazy<RwLock<HttpSettings>> = Lazy::new(|| RwLock::new(HttpSettings::default()));
+
+fn manual_proxy(config: &ConfigManager) -> Result<reqwest::Proxy, String> {
+    let url = config
+        .get_proxy_url()
+        .ok_or_else(|| "El proxy manual necesita un host y un puerto".to_string())?;
+    let proxy =
+        reqwest::Proxy::all(&url).map_err(|e| format!("Proxy no válido ({}): {}", url, e))?;
+
+    Ok(match config.get_proxy_credentials() {
+        Some((username, password)) => proxy.basic_auth(&username, &password),
+        None => proxy,
+    })
+}
+
+/// Reads a PEM bundle (one or more certificates) or a single DER file.
+fn load_certificates(path: &Path) -> Result<Vec<reqwest::Certificate>, String> {
+    let bytes = fs::read(path)
+        .map_err(|e| format!("Error al leer los certificados {}: {}", path.display(), e))?;
+
+    match reqwest::Certificate::from_pem_bundle(&bytes) {
+        Ok(certificates) if !certificates.is_empty() => Ok(certificates),
+        _ => reqwest::Certificate::from_der(&bytes)
+            .map(|certificate| vec![certificate])
+            .map_err(|e| format!("Certificados no válidos en {}: {}", path.display(), e)),
+    }
+}
+
+/// Rebuilds the snapshot from `config`. Invalid settings are logged and
+/// skipped so a typo never leaves the launcher without network.
+pub fn reload(config: &ConfigManager) {
+    let proxy = match config.get_proxy_mode().as_str() {
+        "none" => ProxySetting::Direct,
+        "manual" => match manual_proxy(config) {
+            Ok(proxy) => ProxySetting::Manual(proxy),
+            Err(e) => {
+                log::error!("[HTTP] {}; using system proxy", e);
+                ProxySetting::System
+            }
+        },
+        _ => ProxySetting::System,
+    };
+
+    let certificates = match config.get_ca_bundle_path() {
+        Some(path) => load_certificates(&path).unwrap_or_else(|e| {
+            log::error!("[HTTP] {}", e);
+            Vec::new()
+        }),
+        None => Vec::new(),
+    };
+
+    log::info!(
+        "[HTTP] Network settings: proxy {}, {} extra CA certificate(s)",
+        config.get_proxy_mode(),
+        certificates.len()
+    );
+    *SETTINGS.write().unwrap_or_else(|e| e.into_inner()) = HttpSettings {
+        proxy,
+        certificates,
+    };
+}
+
+/// Loads the snapshot at startup.
+pub fn init() {
+    if let Err(e) = with_config(reload) {
+        log::warn!("[HTTP] Config unavailable, using defaults: {}", e);
+    }
+}
+
+fn settings() -> HttpSettings {
+    SETTINGS.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Applies the snapshot to an async or blocking builder (same method names).
+macro_rules! configure {
+    ($builder:expr) => {{
+        let settings = settings();
+        let mut builder = $builder;
+        match settings.proxy {
+            ProxySetting::System => {}
+            ProxySetting::Direct => builder = builder.no_proxy(),
+            ProxySetting::Manual(proxy) => builder = builder.proxy(proxy),
+        }
+        for certificate in settings.certificates {
+            builder = builder.add_root_certificate(certificate);
+        }
+        builder
+    }};
+}
+
+pub fn async_builder() -> reqwest::ClientBuilder {
+    configure!(reqwest::Client::builder())
+}
+
+pub fn blocking_builder() -> reqwest::blocking::ClientBuilder {
+    configure!(reqwest::blocking::Client::builder())
+}
+
+/// Async client with the network settings; falls back to reqwest's
+/// defaults if the settings can't be applied.
+pub fn async_client() -> reqwest::Client {
+    async_builder().build().unwrap_or_else(|e| {
+        log::error!("[HTTP] Could not build HTTP client: {}", e);
+        reqwest::Client::new()
+    })
+}
+
+/// Blocking counterpart of `async_client`.
+pub fn blocking_client() -> reqwest::blocking::Client {
+    blocking_builder().build().unwrap_or_else(|e| {
+        log::error!("[HTTP] Could not build HTTP client: {}", e);
+        reqwest::blocking::Client::new()
+    })
+}
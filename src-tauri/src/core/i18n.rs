@@ -0,0 +1,556 @@
+//! Message keys for backend-generated text.
+
+use crate::config::with_config;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Message keys shared with the frontend.
+pub mod keys {
+    pub const TASK_WAITING: &str = "task.waiting";
+    pub const TASK_RESUMING: &str = "task.resuming";
+    pub const TASK_QUEUED: &str = "task.queued";
+    pub const TASK_INTERRUPTED: &str = "task.interrupted";
+    pub const TASK_WAITING_NETWORK: &str = "task.waitingNetwork";
+
+    pub const INSTANCE_CREATING_METADATA: &str = "instance.creatingMetadata";
+    pub const INSTANCE_CREATED: &str = "instance.created";
+    pub const INSTANCE_BOOTSTRAP_FAILED: &str = "instance.bootstrapFailed";
+    pub const INSTANCE_IMPORT_COPYING: &str = "instance.importCopying";
+    pub const INSTANCE_IMPORT_FAILED: &str = "instance.importFailed";
+
+    pub const BOOTSTRAP_VANILLA_START: &str = "bootstrap.vanillaStart";
+    pub const BOOTSTRAP_VANILLA_DONE: &str = "bootstrap.vanillaDone";
+    pub const BOOTSTRAP_MANIFEST: &str = "bootstrap.manifest";
+    pub const BOOTSTRAP_VERSION_JSON: &str = "bootstrap.versionJson";
+    pub const BOOTSTRAP_CLIENT: &str = "bootstrap.client";
+    pub const BOOTSTRAP_JAVA: &str = "bootstrap.java";
+    pub const BOOTSTRAP_LIBRARIES: &str = "bootstrap.libraries";
+    pub const BOOTSTRAP_LIBRARIES_PROGRESS: &str = "bootstrap.librariesProgress";
+    pub const BOOTSTRAP_ASSETS: &str = "bootstrap.assets";
+    pub const BOOTSTRAP_ASSETS_PROGRESS: &str = "bootstrap.assetsProgress";
+    pub const BOOTSTRAP_ASSETS_LEGACY: &str = "bootstrap.assetsLegacy";
+    pub const BOOTSTRAP_ASSETS_DONE: &str = "bootstrap.assetsDone";
+    pub const BOOTSTRAP_NATIVES: &str = "bootstrap.natives";
+    pub const BOOTSTRAP_NATIVE_DOWNLOADING: &str = "bootstrap.nativeDownloading";
+    pub const BOOTSTRAP_NATIVE_EXTRACTING: &str = "bootstrap.nativeExtracting";
+    pub const BOOTSTRAP_FINISHING: &str = "bootstrap.finishing";
+
+    pub const FORGE_START: &str = "forge.start";
+    pub const FORGE_VANILLA_SETUP: &str = "forge.vanillaSetup";
+    pub const FORGE_CONFIGURING: &str = "forge.configuring";
+    pub const FORGE_DOWNLOADING: &str = "forge.downloading";
+    pub const FORGE_INSTALLER_DOWNLOADING: &str = "forge.installerDownloading";
+    pub const FORGE_INSTALLER_RUNNING: &str = "forge.installerRunning";
+    pub const FORGE_INSTALLER_EXTRACTING: &str = "forge.installerExtracting";
+    pub const FORGE_INSTALLER_DONE: &str = "forge.installerDone";
+    pub const FORGE_CHECKING_CLIENT: &str = "forge.checkingClient";
+    pub const FORGE_EXTRACTING_ARTIFACTS: &str = "forge.extractingArtifacts";
+    pub const FORGE_LIBRARIES: &str = "forge.libraries";
+    pub const FORGE_LIBRARIES_PROGRESS: &str = "forge.librariesProgress";
+    pub const FORGE_PREPARING_PROCESSORS: &str = "forge.preparingProcessors";
+    pub const FORGE_PROCESSORS: &str = "forge.processors";
+    pub const FORGE_PROCESSORS_PROGRESS: &str = "forge.processorsProgress";
+    pub const FORGE_REGISTERING_PROFILE: &str = "forge.registeringProfile";
+    pub const FORGE_PROFILE: &str = "forge.profile";
+    pub const FORGE_INSTALLED: &str = "forge.installed";
+    pub const FORGE_INSTALL_COMPLETED: &str = "forge.installCompleted";
+    pub const FORGE_DONE: &str = "forge.done";
+
+    pub const VERIFY_VANILLA_START: &str = "verify.vanillaStart";
+    pub const VERIFY_VANILLA_DONE: &str = "verify.vanillaDone";
+    pub const VERIFY_LIBRARIES: &str = "verify.libraries";
+    pub const VERIFY_LIBRARIES_PROGRESS: &str = "verify.librariesProgress";
+    pub const VERIFY_LIBRARY_HASHES: &str = "verify.libraryHashes";
+
+    pub const MODPACK_VALIDATING: &str = "modpack.validating";
+    pub const MODPACK_VALIDATING_PROGRESS: &str = "modpack.validatingProgress";
+    pub const MODPACK_VALIDATED: &str = "modpack.validated";
+    pub const MODPACK_UPDATING: &str = "modpack.updating";
+    pub const MODPACK_UPDATED: &str = "modpack.updated";
+
+    pub const LAUNCH_PREPARING: &str = "launch.preparing";
+    pub const LAUNCH_CHECKING_ASSETS: &str = "launch.checkingAssets";
+    pub const LAUNCH_RUNNING: &str = "launch.running";
+    pub const LAUNCH_EXITED: &str = "launch.exited";
+    pub const LAUNCH_PROCESS_ENDED: &str = "launch.processEnded";
+    pub const LAUNCH_START_FAILED: &str = "launch.startFailed";
+    pub const LAUNCH_HOOK_TIMEOUT: &str = "launch.hookTimeout";
+    pub const LAUNCH_HOOK_FAILED: &str = "launch.hookFailed";
+    pub const LAUNCH_NO_GAME_OWNERSHIP: &str = "launch.noGameOwnership";
+    pub const LAUNCH_PROFILE_NOT_CREATED: &str = "launch.profileNotCreated";
+    pub const LAUNCH_INVALID_SESSION: &str = "launch.invalidSession";
+    pub const LAUNCH_MISSING_LIBRARIES: &str = "launch.missingLibraries";
+
+    pub const ACCOUNT_NOT_SELECTED: &str = "account.notSelected";
+    pub const ACCOUNT_NOT_FOUND: &str = "account.notFound";
+    pub const ACCOUNT_EMPTY_USERNAME: &str = "account.emptyUsername";
+    pub const ACCOUNT_OFFLINE_FALLBACK: &str = "account.offlineFallback";
+}
+
+/// Idioma que se usa cuando falta una traducción
+const FALLBACK_LANGUAGE: &str = "es";
+
+/// `(key, es, en)`
+const CATALOG: &[(&str, &str, &str)] = &[
+    (keys::TASK_WAITING, "En espera...", "Waiting..."),
+    (keys::TASK_RESUMING, "Reanudando...", "Resuming..."),
+    (
+        keys::TASK_QUEUED,
+        "En cola (posición {position})",
+        "Queued (position {position})",
+    ),
+    (
+        keys::TASK_INTERRUPTED,
+        "Interrumpida al cerrar el launcher",
+        "Interrupted when the launcher was closed",
+    ),
+    (
+        keys::TASK_WAITING_NETWORK,
+        "Sin conexión, se reanudará al volver la red",
+        "Offline, will resume when the connection is back",
+    ),
+    (
+        keys::INSTANCE_CREATING_METADATA,
+        "Creando metadatos",
+        "Creating metadata",
+    ),
+    (
+        keys::INSTANCE_CREATED,
+        "Instancia {instanceName} creada",
+        "Instance {instanceName} created",
+    ),
+    (
+        keys::INSTANCE_BOOTSTRAP_FAILED,
+        "Error en bootstrap: {error}",
+        "Installation failed: {error}",
+    ),
+    (
+        keys::INSTANCE_IMPORT_COPYING,
+        "Copiando archivos de la instancia",
+        "Copying instance files",
+    ),
+    (
+        keys::INSTANCE_IMPORT_FAILED,
+        "Error al importar los archivos: {error}",
+        "Could not import the files: {error}",
+    ),
+    (
+        keys::BOOTSTRAP_VANILLA_START,
+        "Iniciando bootstrap de instancia Vanilla",
+        "Starting Vanilla instance setup",
+    ),
+    (
+        keys::BOOTSTRAP_VANILLA_DONE,
+        "Bootstrap de instancia Vanilla {minecraftVersion} completado",
+        "Vanilla {minecraftVersion} instance setup completed",
+    ),
+    (
+        keys::BOOTSTRAP_MANIFEST,
+        "Descargando manifiesto de versión",
+        "Downloading version manifest",
+    ),
+    (
+        keys::BOOTSTRAP_VERSION_JSON,
+        "Descargando JSON de versión: {minecraftVersion}",
+        "Downloading version JSON: {minecraftVersion}",
+    ),
+    (
+        keys::BOOTSTRAP_CLIENT,
+        "Descargando cliente: {minecraftVersion}",
+        "Downloading client: {minecraftVersion}",
+    ),
+    (keys::BOOTSTRAP_JAVA, "Instalando Java", "Installing Java"),
+    (
+        keys::BOOTSTRAP_LIBRARIES,
+        "Descargando librerías",
+        "Downloading libraries",
+    ),
+    (
+        keys::BOOTSTRAP_LIBRARIES_PROGRESS,
+        "Descargando librerías: {done}/{total} ({percent}%)",
+        "Downloading libraries: {done}/{total} ({percent}%)",
+    ),
+    (keys::BOOTSTRAP_ASSETS, "Validando assets", "Validating assets"),
+    (
+        keys::BOOTSTRAP_ASSETS_PROGRESS,
+        "Validando assets: {done}/{total} ({percent}%)",
+        "Validating assets: {done}/{total} ({percent}%)",
+    ),
+    (
+        keys::BOOTSTRAP_ASSETS_LEGACY,
+        "Preparando assets para versiones antiguas",
+        "Preparing assets for old versions",
+    ),
+    (
+        keys::BOOTSTRAP_ASSETS_DONE,
+        "Validación de assets completada para {instanceName}",
+        "Asset validation completed for {instanceName}",
+    ),
+    (
+        keys::BOOTSTRAP_NATIVES,
+        "Extrayendo bibliotecas nativas",
+        "Extracting native libraries",
+    ),
+    (
+        keys::BOOTSTRAP_NATIVE_DOWNLOADING,
+        "Descargando biblioteca nativa: {path}",
+        "Downloading native library: {path}",
+    ),
+    (
+        keys::BOOTSTRAP_NATIVE_EXTRACTING,
+        "Extrayendo biblioteca nativa: {path}",
+        "Extracting native library: {path}",
+    ),
+    (
+        keys::BOOTSTRAP_FINISHING,
+        "Finalizando configuración",
+        "Finishing setup",
+    ),
+    (
+        keys::FORGE_START,
+        "Iniciando bootstrap de instancia Forge",
+        "Starting Forge instance setup",
+    ),
+    (
+        keys::FORGE_VANILLA_SETUP,
+        "Configurando base Vanilla",
+        "Setting up the Vanilla base",
+    ),
+    (keys::FORGE_CONFIGURING, "Configurando Forge", "Configuring Forge"),
+    (
+        keys::FORGE_DOWNLOADING,
+        "Descargando Forge {forgeVersion} para Minecraft {minecraftVersion}",
+        "Downloading Forge {forgeVersion} for Minecraft {minecraftVersion}",
+    ),
+    (
+        keys::FORGE_INSTALLER_DOWNLOADING,
+        "Descargando instalador de Forge",
+        "Downloading Forge installer",
+    ),
+    (
+        keys::FORGE_INSTALLER_RUNNING,
+        "Ejecutando instalador de Forge",
+        "Running Forge installer",
+    ),
+    (
+        keys::FORGE_INSTALLER_EXTRACTING,
+        "Extrayendo archivos del instalador",
+        "Extracting installer files",
+    ),
+    (
+        keys::FORGE_INSTALLER_DONE,
+        "Instalador de Forge completado",
+        "Forge installer finished",
+    ),
+    (
+        keys::FORGE_CHECKING_CLIENT,
+        "Verificando cliente de Minecraft",
+        "Checking Minecraft client",
+    ),
+    (
+        keys::FORGE_EXTRACTING_ARTIFACTS,
+        "Extrayendo artefactos de Forge",
+        "Extracting Forge artifacts",
+    ),
+    (
+        keys::FORGE_LIBRARIES,
+        "Descargando librerías de Forge",
+        "Downloading Forge libraries",
+    ),
+    (
+        keys::FORGE_LIBRARIES_PROGRESS,
+        "Descargando librerías de Forge: {done}/{total} ({percent}%)",
+        "Downloading Forge libraries: {done}/{total} ({percent}%)",
+    ),
+    (
+        keys::FORGE_PREPARING_PROCESSORS,
+        "Preparando processors de Forge",
+        "Preparing Forge processors",
+    ),
+    (
+        keys::FORGE_PROCESSORS,
+        "Ejecutando processors de Forge",
+        "Running Forge processors",
+    ),
+    (
+        keys::FORGE_PROCESSORS_PROGRESS,
+        "Ejecutando processors de Forge ({done}/{total})",
+        "Running Forge processors ({done}/{total})",
+    ),
+    (
+        keys::FORGE_REGISTERING_PROFILE,
+        "Registrando perfil de Forge",
+        "Registering Forge profile",
+    ),
+    (
+        keys::FORGE_PROFILE,
+        "Configurando perfil de Forge",
+        "Setting up Forge profile",
+    ),
+    (keys::FORGE_INSTALLED, "Forge instalado", "Forge installed"),
+    (
+        keys::FORGE_INSTALL_COMPLETED,
+        "Instalación completada: Forge {forgeVersion} para Minecraft {minecraftVersion}",
+        "Installation completed: Forge {forgeVersion} for Minecraft {minecraftVersion}",
+    ),
+    (
+        keys::FORGE_DONE,
+        "Bootstrap de instancia Forge {forgeVersion} para Minecraft {minecraftVersion} completado",
+        "Forge {forgeVersion} for Minecraft {minecraftVersion} instance setup completed",
+    ),
+    (
+        keys::VERIFY_VANILLA_START,
+        "Verificando integridad de la instancia Vanilla",
+        "Verifying Vanilla instance integrity",
+    ),
+    (
+        keys::VERIFY_VANILLA_DONE,
+        "Verificación de la instancia Vanilla completada",
+        "Vanilla instance verification completed",
+    ),
+    (
+        keys::VERIFY_LIBRARIES,
+        "Verificando librerías",
+        "Verifying libraries",
+    ),
+    (
+        keys::VERIFY_LIBRARIES_PROGRESS,
+        "Verificando librerías: {done}/{total} ({percent}%)",
+        "Verifying libraries: {done}/{total} ({percent}%)",
+    ),
+    (
+        keys::VERIFY_LIBRARY_HASHES,
+        "Comprobando hashes de librerías: {done}/{total}",
+        "Checking library hashes: {done}/{total}",
+    ),
+    (
+        keys::MODPACK_VALIDATING,
+        "Validando archivos del modpack",
+        "Validating modpack files",
+    ),
+    (
+        keys::MODPACK_VALIDATING_PROGRESS,
+        "Validando archivos del modpack: {done}/{total}",
+        "Validating modpack files: {done}/{total}",
+    ),
+    (
+        keys::MODPACK_VALIDATED,
+        "Validación de archivos del modpack completada",
+        "Modpack file validation completed",
+    ),
+    (
+        keys::MODPACK_UPDATING,
+        "Iniciando actualización del modpack...",
+        "Starting modpack update...",
+    ),
+    (
+        keys::MODPACK_UPDATED,
+        "Actualización del modpack completada",
+        "Modpack update completed",
+    ),
+    (
+        keys::LAUNCH_PREPARING,
+        "Preparando lanzamiento...",
+        "Preparing launch...",
+    ),
+    (
+        keys::LAUNCH_CHECKING_ASSETS,
+        "Verificando/Descargando assets...",
+        "Checking/downloading assets...",
+    ),
+    (
+        keys::LAUNCH_RUNNING,
+        "Minecraft se está ejecutando.",
+        "Minecraft is running.",
+    ),
+    (
+        keys::LAUNCH_EXITED,
+        "La instancia '{instanceName}' se ha cerrado ({exitCode})",
+        "Minecraft instance '{instanceName}' exited ({exitCode})",
+    ),
+    (
+        keys::LAUNCH_PROCESS_ENDED,
+        "El proceso de Minecraft terminó de forma inesperada.",
+        "Minecraft process ended unexpectedly.",
+    ),
+    (
+        keys::LAUNCH_START_FAILED,
+        "Error al iniciar el proceso de Minecraft: No se pudo iniciar el proceso",
+        "Could not start the Minecraft process",
+    ),
+    (
+        keys::LAUNCH_HOOK_TIMEOUT,
+        "El hook pre-launch superó el tiempo máximo",
+        "The pre-launch hook timed out",
+    ),
+    (
+        keys::LAUNCH_HOOK_FAILED,
+        "El hook pre-launch terminó con código {exitCode}",
+        "The pre-launch hook exited with code {exitCode}",
+    ),
+    (
+        keys::LAUNCH_NO_GAME_OWNERSHIP,
+        "Esta cuenta de Microsoft no tiene una licencia válida de Minecraft Java Edition.",
+        "This Microsoft account does not own Minecraft Java Edition.",
+    ),
+    (
+        keys::LAUNCH_PROFILE_NOT_CREATED,
+        "Tu cuenta tiene Minecraft Java Edition pero aún no has creado un perfil. Abre el Launcher oficial una vez para crearlo.",
+        "Your account owns Minecraft Java Edition but has no profile yet. Open the official launcher once to create it.",
+    ),
+    (
+        keys::LAUNCH_INVALID_SESSION,
+        "La sesión de tu cuenta de Microsoft ha caducado. Vuelve a iniciar sesión.",
+        "Your Microsoft account session has expired. Please sign in again.",
+    ),
+    (
+        keys::LAUNCH_MISSING_LIBRARIES,
+        "Faltan {count} librerías y no se pudieron descargar: {libraries}",
+        "{count} libraries are missing and could not be downloaded: {libraries}",
+    ),
+    (
+        keys::ACCOUNT_NOT_SELECTED,
+        "Esta instancia no tiene ninguna cuenta asignada. Elige una cuenta para jugar.",
+        "This instance has no account assigned. Choose an account to play.",
+    ),
+    (
+        keys::ACCOUNT_NOT_FOUND,
+        "La cuenta asignada a esta instancia ya no existe. Elige otra cuenta.",
+        "The account assigned to this instance no longer exists. Choose another account.",
+    ),
+    (
+        keys::ACCOUNT_EMPTY_USERNAME,
+        "La cuenta asignada no tiene nombre de usuario.",
+        "The assigned account has no username.",
+    ),
+    (
+        keys::ACCOUNT_OFFLINE_FALLBACK,
+        "Jugando sin conexión como {username}",
+        "Playing offline as {username}",
+    ),
+];
+
+fn translation(entry: &(&str, &'static str, &'static str), language: &str) -> &'static str {
+    match language {
+        "en" => entry.2,
+        _ => entry.1,
+    }
+}
+
+fn template(key: &str, language: &str) -> Option<&'static str> {
+    CATALOG
+        .iter()
+        .find(|entry| entry.0 == key)
+        .map(|entry| translation(entry, language))
+}
+
+fn render(template: &str, params: &Map<String, Value>) -> String {
+    params
+        .iter()
+        .fold(template.to_string(), |text, (name, value)| {
+            let value = match value {
+                Value::String(value) => value.clone(),
+                Value::Null => String::new(),
+                other => other.to_string(),
+            };
+            text.replace(&format!("{{{}}}", name), &value)
+        })
+}
+
+/// A user-facing message: its key and parameters for the frontend, plus the
+/// text already rendered in Spanish for logs and older frontends.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Message {
+    pub key: Option<String>,
+    pub params: Map<String, Value>,
+    pub text: String,
+}
+
+impl Message {
+    pub fn new(key: &str) -> Self {
+        let text = template(key, FALLBACK_LANGUAGE).unwrap_or(key).to_string();
+        Self {
+            key: Some(key.to_string()),
+            params: Map::new(),
+            text,
+        }
+    }
+
+    pub fn param(mut self, name: &str, value: impl Into<Value>) -> Self {
+        self.params.insert(name.to_string(), value.into());
+        if let Some(template) = self
+            .key
+            .as_deref()
+            .and_then(|key| template(key, FALLBACK_LANGUAGE))
+        {
+            self.text = render(template, &self.params);
+        }
+        self
+    }
+
+    /// `done`, `total` and `percent` (one decimal) for progress templates.
+    pub fn progress(self, done: usize, total: usize) -> Self {
+        let percent = done as f64 * 100.0 / total.max(1) as f64;
+        self.param("done", done)
+            .param("total", total)
+            .param("percent", format!("{:.1}", percent))
+    }
+
+    /// Params as a JSON object, `None` when there are none.
+    pub fn params_value(&self) -> Option<Value> {
+        (!self.params.is_empty()).then(|| Value::Object(self.params.clone()))
+    }
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+/// Plain text without a key (e.g. an error coming from a `Result`).
+impl From<&str> for Message {
+    fn from(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            ..Self::default()
+        }
+    }
+}
+
+impl From<String> for Message {
+    fn from(text: String) -> Self {
+        Self {
+            text,
+            ..Self::default()
+        }
+    }
+}
+
+impl From<&String> for Message {
+    fn from(text: &String) -> Self {
+        Self::from(text.as_str())
+    }
+}
+
+/// Every template for `language` (defaults to the interface language),
+/// falling back to Spanish for unknown languages.
+#[tauri::command]
+pub fn get_message_catalog(language: Option<String>) -> HashMap<String, String> {
+    let language = language.unwrap_or_else(|| {
+        with_config(|config| config.get_language())
+            .ok()
+            .unwrap_or_else(|| FALLBACK_LANGUAGE.to_string())
+    });
+
+    CATALOG
+        .iter()
+        .map(|entry| {
+            (
+                entry.0.to_string(),
+                translation(entry, &language).to_string(),
+            )
+        })
+        .collect()
+}
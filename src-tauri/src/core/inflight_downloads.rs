@@ -0,0 +1,158 @@
+//! Downloads shared between concurrent tasks, written atomically.
+
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+
+/// How the destination file was obtained.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DownloadOutcome {
+    Downloaded,
+    /// Otra tarea lo estaba descargando y se reutilizó su archivo
+    Shared,
+}
+
+#[derive(Default)]
+struct InFlight {
+    /// Destino final del que descarga, o su error
+    result: Mutex<Option<Result<PathBuf, String>>>,
+    done: Condvar,
+}
+
+/// Entrada registrada por quien descarga. Al soltarse publica un error si no
+/// llegó a publicar resultado (p. ej. por un pánico) y quita la entrada, así
+/// quien espera nunca se queda bloqueado.
+struct Leader {
+    key: String,
+    entry: Arc<InFlight>,
+}
+
+impl Leader {
+    fn publish(&self, result: Result<PathBuf, String>) {
+        *self.entry.result.lock().unwrap_or_else(|e| e.into_inner()) = Some(result);
+    }
+}
+
+impl Drop for Leader {
+    fn drop(&mut self) {
+        let mut result = self.entry.result.lock().unwrap_or_else(|e| e.into_inner());
+        if result.is_none() {
+            *result = Some(Err("Download interrupted".to_string()));
+        }
+        drop(result);
+        // Se publica antes de quitar la entrada: quien ya la tiene no se queda esperando
+        self.entry.done.notify_all();
+        in_flight().remove(&self.key);
+    }
+}
+
+static IN_FLIGHT: Lazy<Mutex<HashMap<String, Arc<InFlight>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Distingue los temporales de descargas simultáneas al mismo destino
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+fn in_flight() -> MutexGuard<'static, HashMap<String, Arc<InFlight>>> {
+    IN_FLIGHT.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn key(destination: &Path, hash: Option<&str>) -> String {
+    match hash {
+        Some(hash) => format!("hash:{}", hash.to_ascii_lowercase()),
+        None => format!("path:{}", destination.display()),
+    }
+}
+
+/// Temporary file next to `destination`, unique to this download.
+pub fn temp_path(destination: &Path) -> PathBuf {
+    let mut name = destination.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(
+        ".{}-{}.tmp",
+        std::process::id(),
+        TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    destination.with_file_name(name)
+}
+
+/// Runs `write` on a temporary file and moves it to `destination` only if
+/// it succeeds.
+pub fn write_atomically(
+    destination: &Path,
+    write: impl FnOnce(&Path) -> Result<(), String>,
+) -> Result<(), String> {
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Error creating directory: {}", e))?;
+    }
+    let temp = temp_path(destination);
+    let result = write(&temp).and_then(|_| {
+        fs::rename(&temp, destination)
+            .map_err(|e| format!("Error moving {} into place: {}", destination.display(), e))
+    });
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result
+}
+
+/// Copies the file another task downloaded to `destination`.
+fn reuse(source: &Path, destination: &Path) -> Result<(), String> {
+    if source == destination {
+        return Ok(());
+    }
+    write_atomically(destination, |temp| {
+        fs::copy(source, temp)
+            .map(|_| ())
+            .map_err(|e| format!("Error copying {}: {}", source.display(), e))
+    })
+}
+
+/// Downloads to `destination` with `download`, which receives the temporary
+/// path to write to. If the same file (same `hash`, or same destination) is
+/// already being downloaded, waits for that download instead and reuses
+/// its result.
+pub fn download(
+    destination: &Path,
+    hash: Option<&str>,
+    download: impl FnOnce(&Path) -> Result<(), String>,
+) -> Result<DownloadOutcome, String> {
+    let key = key(destination, hash);
+    let (entry, is_leader) = {
+        let mut in_flight = in_flight();
+        match in_flight.get(&key) {
+            Some(entry) => (Arc::clone(entry), false),
+            None => {
+                let entry = Arc::new(InFlight::default());
+                in_flight.insert(key.clone(), Arc::clone(&entry));
+                (entry, true)
+            }
+        }
+    };
+
+    if !is_leader {
+        log::debug!(
+            "[Downloads] Waiting for the in-flight download of {}",
+            destination.display()
+        );
+        let mut result = entry.result.lock().unwrap_or_else(|e| e.into_inner());
+        while result.is_none() {
+            result = entry.done.wait(result).unwrap_or_else(|e| e.into_inner());
+        }
+        let shared = result.clone();
+        drop(result);
+        return match shared {
+            Some(Ok(source)) => reuse(&source, destination).map(|_| DownloadOutcome::Shared),
+            Some(Err(e)) => Err(e),
+            None => Err("Download interrupted".to_string()),
+        };
+    }
+
+    let leader = Leader { key, entry };
+    let result = write_atomically(destination, download);
+    leader.publish(result.clone().map(|_| destination.to_path_buf()));
+    drop(leader);
+
+    result.map(|_| DownloadOutcome::Downloaded)
+}
@@ -1,23 +1,125 @@
 // src-tauri/src/instance_bootstrap.rs
-use crate::config::get_config_manager;
+use crate::config::read_config;
+use crate::core::chunked_download::{self, ChunkSettings};
+use crate::core::download_cache;
+use crate::core::events::{names, EventEmitter, LauncherEvent};
+use crate::core::file_rules::FileRules;
+use crate::core::forge_processors::{file_sha1, HeadlessForgeInstaller};
+use crate::core::http_client;
+use crate::core::i18n::{keys, Message};
+use crate::core::inflight_downloads::{self, DownloadOutcome};
 use crate::core::instance_manager::get_instance_by_id;
+use crate::core::instance_readme;
 use crate::core::java_manager::JavaManager;
-use crate::core::minecraft_instance::MinecraftInstance;
-use crate::core::tasks_manager::{TaskStatus, TasksManager};
-use crate::GLOBAL_APP_HANDLE;
+use crate::core::launcher_profiles;
+use crate::core::minecraft::legacy;
+use crate::core::minecraft::manifest::version_type;
+use crate::core::minecraft::paths;
+use crate::core::minecraft::{MavenCoordinate, RuleEvaluator};
+use crate::core::minecraft_instance::{self, MinecraftInstance};
+use crate::core::mirrors;
+use crate::core::mod_blocklist;
+use crate::core::models::{ManifestFile, ModpackManifest};
+use crate::core::network_monitor;
+use crate::core::optional_groups;
+use crate::core::parallel_hash::{self, HashJob, HashOutcome};
+use crate::core::subprocess;
+use crate::core::tasks_manager::{self, TaskStatus, TasksManager};
+use crate::core::transfer_stats::{TrackedWriter, TransferTracker};
+use crate::core::zip_extract::ZipExtractor;
+use rayon::prelude::*;
 use serde_json::{json, Value};
 use std::fs;
-use std::io::{self, Result as IoResult};
+use std::io::{self, BufRead, BufReader, Result as IoResult};
 use std::path::{Path, PathBuf};
-use std::process::Command;
-use std::sync::{Arc, Mutex};
-use tauri::Emitter;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::State;
 use tauri_plugin_http::reqwest;
 
+/// Result of a single Forge installer run.
+enum InstallerExit {
+    Success,
+    Failed(String),
+    TimedOut,
+}
+
+/// Turns Forge installer output lines into a 0.0 - 1.0 progress value.
+///
+/// Downloads cover the first half; once "Building Processors" shows up every
+/// processor (`MainClass:` / `Splitting` lines) advances the second half.
+struct ForgeInstallerProgress {
+    total_processors: usize,
+    processors_done: usize,
+    downloads: usize,
+    fraction: f32,
+}
+
+impl ForgeInstallerProgress {
+    fn new(total_processors: usize) -> Self {
+        Self {
+            total_processors,
+            processors_done: 0,
+            downloads: 0,
+            fraction: 0.0,
+        }
+    }
+
+    /// Returns the new progress and a user message when the line moves it forward.
+    fn feed(&mut self, line: &str) -> Option<(f32, Message)> {
+        let line = line.trim();
+
+        let (fraction, message) = if line.starts_with("Extracting") {
+            (0.05, Message::new(keys::FORGE_INSTALLER_EXTRACTING))
+        } else if line.starts_with("Considering minecraft client jar") {
+            (0.1, Message::new(keys::FORGE_CHECKING_CLIENT))
+        } else if line.starts_with("Downloading library")
+            || line.starts_with("Downloading libraries")
+        {
+            // No sabemos cuántas librerías hay; avanzamos de forma asintótica hasta 45%
+            self.downloads += 1;
+            let fraction = 0.1 + 0.35 * (1.0 - 1.0 / (1.0 + self.downloads as f32 / 10.0));
+            (fraction, Message::new(keys::FORGE_LIBRARIES))
+        } else if line.starts_with("Building Processors") {
+            (0.5, Message::new(keys::FORGE_PREPARING_PROCESSORS))
+        } else if line.starts_with("MainClass:") || line.starts_with("Splitting") {
+            self.processors_done += 1;
+            let total = self.total_processors.max(self.processors_done);
+            let fraction = 0.5 + 0.45 * (self.processors_done as f32 / total as f32);
+            let message = if self.total_processors > 0 {
+                Message::new(keys::FORGE_PROCESSORS_PROGRESS)
+                    .progress(self.processors_done.min(total), total)
+            } else {
+                Message::new(keys::FORGE_PROCESSORS)
+            };
+            (fraction, message)
+        } else if line.starts_with("Injecting profile") {
+            (0.97, Message::new(keys::FORGE_REGISTERING_PROFILE))
+        } else if line.starts_with("Successfully installed") {
+            (1.0, Message::new(keys::FORGE_INSTALLER_DONE))
+        } else {
+            return None;
+        };
+
+        if fraction <= self.fraction {
+            return None;
+        }
+        self.fraction = fraction;
+        Some((fraction, message))
+    }
+}
+
 pub struct InstanceBootstrap {
     client: reqwest::blocking::Client,
     // Cache para metadatos de versiones
     version_manifest_cache: Option<(Value, u64)>, // (datos, timestamp)
+    // Estadísticas de descarga de la tarea en curso
+    transfer: Mutex<Option<Arc<TransferTracker>>>,
+    // Emisor de los eventos de esta instalación
+    events: EventEmitter,
 }
 
 impl InstanceBootstrap {
@@ -25,11 +127,72 @@ impl InstanceBootstrap {
         "https://launchermeta.mojang.com/mc/game/version_manifest.json";
     const FORGE_API_BASE_URL: &'static str = "https://mc-versions-api.net/api/forge";
     const CACHE_EXPIRY_MS: u64 = 3600000; // 1 hora
-
-    pub fn new() -> Self {
+    /// Subtareas de una instalación de Forge y su peso en el progreso total
+    const FORGE_STEPS: [(&'static str, f32); 4] = [
+        ("Minecraft vanilla", 45.0),
+        ("Instalador de Forge", 30.0),
+        ("Librerías de Forge", 15.0),
+        ("Assets", 10.0),
+    ];
+
+    /// `events` comes from Tauri managed state (`State<EventEmitter>`).
+    pub fn new(events: EventEmitter) -> Self {
         Self {
-            client: reqwest::blocking::Client::new(),
+            client: http_client::blocking_client(),
             version_manifest_cache: None,
+            transfer: Mutex::new(None),
+            events,
+        }
+    }
+
+    /// Reports the downloads of this bootstrap in the task `task_id`.
+    /// Without a task the current tracker is kept, so nested steps (Forge
+    /// bootstrapping vanilla) add to the same numbers.
+    fn track_transfers(
+        &self,
+        task_id: Option<&str>,
+        task_manager: Option<&Arc<Mutex<TasksManager>>>,
+    ) {
+        if let (Some(task_id), Some(task_manager)) = (task_id, task_manager) {
+            *self.transfer.lock().unwrap_or_else(|e| e.into_inner()) =
+                Some(TransferTracker::new(task_id, task_manager));
+        }
+    }
+
+    /// Runs `op` on a pool with as many threads as `downloadConcurrency`,
+    /// so the parallel downloads inside it respect the setting.
+    fn with_download_pool<R: Send>(op: impl FnOnce() -> R + Send) -> R {
+        let threads = {
+            let config_lock = read_config();
+            config_lock
+                .as_ref()
+                .map(|config| config.get_download_concurrency())
+                .unwrap_or(8)
+        };
+        match rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .thread_name(|index| format!("download-{}", index))
+            .build()
+        {
+            Ok(pool) => pool.install(op),
+            Err(e) => {
+                log::warn!("Could not create the download pool: {}", e);
+                op()
+            }
+        }
+    }
+
+    fn transfer_tracker(&self) -> Option<Arc<TransferTracker>> {
+        self.transfer
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    // Un archivo recuperado de la caché ya no cuenta para el total esperado
+    fn skip_transfer(&self, path: &Path) {
+        if let Some(tracker) = self.transfer_tracker() {
+            tracker.skip_file(fs::metadata(path).map(|m| m.len()).unwrap_or(0));
         }
     }
 
@@ -42,34 +205,24 @@ impl InstanceBootstrap {
     ///
     /// * `event_name` - The name of the event (e.g., "instance-launch-start").
     /// * `message` - A descriptive message for the frontend.
-    fn emit_status(instance: &MinecraftInstance, event_name: &str, message: &str) {
+    fn emit_status(
+        &self,
+        instance: &MinecraftInstance,
+        event_name: &'static str,
+        message: impl Into<Message>,
+    ) {
+        let message = message.into();
         println!(
             "[Instance: {}] Emitting Event: {} - Message: {}",
             instance.instanceId, event_name, message
         );
-        if let Ok(guard) = GLOBAL_APP_HANDLE.lock() {
-            if let Some(app_handle) = guard.as_ref() {
-                let payload = serde_json::json!({
-                    "id": instance.instanceId,
-                    "name": instance.instanceName,
-                    "message": message
-                });
-                // Use emit to notify the specific window listening for this event
-                if let Err(e) = app_handle.emit(event_name, payload) {
-                    log::info!("[Bootstrap] Error emitting event '{}': {}", event_name, e);
-                }
-            } else {
-                log::info!(
-                    "[Bootstrap] Error: GLOBAL_APP_HANDLE is None when trying to emit '{}'.",
-                    event_name
-                );
-            }
-        } else {
-            eprintln!(
-                "[Bootstrap] Error: Failed to lock GLOBAL_APP_HANDLE when trying to emit '{}'.",
-                event_name
-            );
-        }
+        self.events.send(LauncherEvent::instance(
+            event_name,
+            instance.instanceId.clone(),
+            Some(instance.instanceName.clone()),
+            message,
+            None,
+        ));
     }
 
     // Implementación del método extract_natives
@@ -79,7 +232,11 @@ impl InstanceBootstrap {
         libraries_dir: &Path,
         natives_dir: &Path,
         instance: &MinecraftInstance,
+        task_id: Option<&str>,
+        task_manager: Option<&Arc<Mutex<TasksManager>>>,
     ) -> Result<(), String> {
+        let cancel = task_id.map(tasks_manager::cancellation_token);
+
         // Obtener el sistema operativo actual
         let os = std::env::consts::OS;
         let os_name = match os {
@@ -103,6 +260,13 @@ impl InstanceBootstrap {
             .as_array()
             .ok_or_else(|| "No se encontraron bibliotecas en el manifiesto".to_string())?;
 
+        // Cada jar de nativos ocupa su parte del tramo de progreso
+        let native_count = libraries
+            .iter()
+            .filter(|library| library["natives"].get(os_name).is_some())
+            .count();
+        let mut native_index = 0;
+
         for library in libraries {
             // Verificar si la biblioteca tiene nativos
             if let Some(natives) = library.get("natives") {
@@ -110,6 +274,9 @@ impl InstanceBootstrap {
 
                 // Si hay nativos para este sistema operativo
                 if let Some(os_natives_value) = os_natives {
+                    let span_from = 75.0 + 15.0 * native_index as f32 / native_count as f32;
+                    let span_to = 75.0 + 15.0 * (native_index + 1) as f32 / native_count as f32;
+                    native_index += 1;
                     // Obtener información sobre la biblioteca
                     let library_info = library["downloads"]["classifiers"]
                         .get(
@@ -130,7 +297,7 @@ impl InstanceBootstrap {
                         .as_str()
                         .ok_or_else(|| "No se encontró la ruta del archivo nativo".to_string())?;
 
-                    let library_path = libraries_dir.join(path);
+                    let library_path = paths::join_relative(&libraries_dir, path);
 
                     // Si el archivo no existe, descargarlo
                     if !library_path.exists() {
@@ -145,15 +312,19 @@ impl InstanceBootstrap {
                             })?;
                         }
 
-                        Self::emit_status(
+                        self.emit_status(
                             instance,
-                            "instance-downloading-native-library",
-                            &format!("Descargando biblioteca nativa: {}", path),
+                            names::INSTANCE_DOWNLOADING_NATIVE_LIBRARY,
+                            Message::new(keys::BOOTSTRAP_NATIVE_DOWNLOADING).param("path", path),
                         );
 
                         // Descargar el archivo JAR
-                        self.download_file(url, &library_path)
-                            .map_err(|e| format!("Error descargando biblioteca nativa: {}", e))?;
+                        self.download_file_cached(
+                            url,
+                            &library_path,
+                            library_info["sha1"].as_str(),
+                        )
+                        .map_err(|e| format!("Error descargando biblioteca nativa: {}", e))?;
                     }
 
                     // Verificar si hay reglas de extracción (exclude)
@@ -174,57 +345,44 @@ impl InstanceBootstrap {
                         };
 
                     // Extraer el archivo JAR al directorio de nativos
-                    Self::emit_status(
+                    self.emit_status(
                         instance,
-                        "instance-extracting-native-library",
-                        &format!("Extrayendo biblioteca nativa: {}", path),
+                        names::INSTANCE_EXTRACTING_NATIVE_LIBRARY,
+                        Message::new(keys::BOOTSTRAP_NATIVE_EXTRACTING).param("path", path),
                     );
 
-                    // Abrir el archivo JAR
-                    let file = fs::File::open(&library_path)
-                        .map_err(|e| format!("Error abriendo archivo JAR: {}", e))?;
-
-                    let reader = std::io::BufReader::new(file);
-                    let mut archive = zip::ZipArchive::new(reader)
-                        .map_err(|e| format!("Error leyendo archivo ZIP: {}", e))?;
-
-                    // Extraer cada entrada que no esté excluida
-                    for i in 0..archive.len() {
-                        let mut file = archive
-                            .by_index(i)
-                            .map_err(|e| format!("Error obteniendo entrada ZIP: {}", e))?;
-
-                        let file_name = file.name().to_string();
-
-                        // Verificar si el archivo está excluido
-                        let should_extract = !exclude_patterns.iter().any(|pattern| {
-                            if pattern.ends_with("*") {
-                                let prefix = &pattern[0..pattern.len() - 1];
-                                file_name.starts_with(prefix)
-                            } else {
-                                file_name == *pattern
-                            }
-                        });
-
-                        if should_extract && !file.is_dir() {
-                            // Crear la ruta de destino
-                            let output_path = natives_dir.join(file_name);
-
-                            // Crear directorios padres si no existen
-                            if let Some(parent) = output_path.parent() {
-                                fs::create_dir_all(parent).map_err(|e| {
-                                    format!("Error creando directorio para archivo nativo: {}", e)
-                                })?;
-                            }
-
-                            // Extraer el archivo
-                            let mut output_file = fs::File::create(&output_path)
-                                .map_err(|e| format!("Error creando archivo nativo: {}", e))?;
-
-                            std::io::copy(&mut file, &mut output_file)
-                                .map_err(|e| format!("Error escribiendo archivo nativo: {}", e))?;
-                        }
+                    let mut extractor = ZipExtractor::new(&library_path, natives_dir);
+                    if let Some(cancel) = &cancel {
+                        extractor = extractor.cancellation(cancel.clone());
+                    }
+                    if let (Some(task_id), Some(task_manager)) = (task_id, task_manager) {
+                        // Del 75% (nativas) al 90% (cierre) de bootstrap_vanilla
+                        extractor = extractor
+                            .report_to(
+                                task_id,
+                                task_manager,
+                                Message::new(keys::BOOTSTRAP_NATIVE_EXTRACTING).param("path", path),
+                                Some(json!({
+                                    "instanceName": instance.instanceName.clone(),
+                                    "instanceId": instance.instanceId.clone()
+                                })),
+                            )
+                            .progress_span(span_from, span_to);
                     }
+                    extractor
+                        .map_entries(|name| {
+                            let file_name = name.to_string_lossy().replace('\\', "/");
+                            // Verificar si el archivo está excluido
+                            let excluded = exclude_patterns.iter().any(|pattern| {
+                                match pattern.strip_suffix('*') {
+                                    Some(prefix) => file_name.starts_with(prefix),
+                                    None => file_name == *pattern,
+                                }
+                            });
+                            (!excluded).then(|| name.to_path_buf())
+                        })
+                        .extract()
+                        .map_err(|e| format!("Error extrayendo biblioteca nativa: {}", e))?;
                 }
             }
         }
@@ -246,7 +404,7 @@ impl InstanceBootstrap {
         // Obtener la ruta de la instancia
         let instance_dir = Path::new(instance.instanceDirectory.as_deref().unwrap_or(""));
         let minecraft_folder = instance_dir.join("minecraft");
-        let assets_dir = minecraft_folder.join("assets");
+        let assets_dir = paths::assets_dir(&minecraft_folder);
         let assets_indexes_dir = assets_dir.join("indexes");
         let assets_objects_dir = assets_dir.join("objects");
 
@@ -294,8 +452,16 @@ impl InstanceBootstrap {
 
         let assets_index_file = assets_indexes_dir.join(format!("{}.json", assets_index_id));
 
+        // Las snapshots reutilizan el id del índice con otro contenido, así
+        // que un índice ya descargado sólo vale si coincide su sha1
+        let index_is_stale = assets_index_file.exists()
+            && asset_index_node
+                .get("sha1")
+                .and_then(|v| v.as_str())
+                .is_some_and(|expected| file_sha1(&assets_index_file).as_deref() != Some(expected));
+
         // Descargar o validar el índice de assets
-        if !assets_index_file.exists() {
+        if !assets_index_file.exists() || index_is_stale {
             log::info!(
                 "Descargando índice de assets para la versión {}",
                 instance.minecraftVersion
@@ -330,15 +496,26 @@ impl InstanceBootstrap {
             })?;
 
         let total_assets = objects.len();
-        let mut processed_assets = 0;
-        let mut missing_assets = 0;
 
         log::info!("Validando {} assets...", total_assets);
 
-        // Procesar cada asset
-        for (asset_name, asset_info) in objects {
-            processed_assets += 1;
+        if let Some(tracker) = self.transfer_tracker() {
+            let (count, bytes) = objects
+                .values()
+                .filter_map(|info| {
+                    let hash = info.get("hash")?.as_str()?;
+                    let path = assets_objects_dir.join(hash.get(0..2)?).join(hash);
+                    (!path.exists()).then(|| info.get("size").and_then(|v| v.as_u64()))
+                })
+                .fold((0, Some(0u64)), |(count, total), size| {
+                    (count + 1, total.zip(size).map(|(total, size)| total + size))
+                });
+            tracker.expect(count, bytes);
+        }
 
+        // Comprobar qué assets faltan
+        let mut pending = Vec::new();
+        for (asset_name, asset_info) in objects {
             let hash = asset_info
                 .get("hash")
                 .and_then(|v| v.as_str())
@@ -349,47 +526,61 @@ impl InstanceBootstrap {
                     )
                 })?;
 
-            let hash_prefix = &hash[0..2];
-            let asset_file = assets_objects_dir.join(hash_prefix).join(hash);
-
-            // Informar progreso
-
-            log::info!(
-                "Validando assets: {}/{} ({:.1}%)",
-                processed_assets,
-                total_assets,
-                (processed_assets as f64 * 100.0 / total_assets as f64)
-            );
-            Self::emit_status(
-                instance,
-                "instance-downloading-assets",
-                &format!(
-                    "Validando assets: {}/{} ({:.1}%)",
-                    processed_assets,
-                    total_assets,
-                    (processed_assets as f64 * 100.0 / total_assets as f64)
-                ),
-            );
-
+            let asset_file = assets_objects_dir.join(&hash[0..2]).join(hash);
             if !asset_file.exists() {
-                missing_assets += 1;
-                let asset_url = format!(
-                    "https://resources.download.minecraft.net/{}/{}",
-                    hash_prefix, hash
-                );
-                let target_dir = assets_objects_dir.join(hash_prefix);
+                pending.push((asset_name, hash, asset_file));
+            }
+        }
+        let missing_assets = pending.len();
+        let processed_assets = AtomicUsize::new(total_assets - missing_assets);
+        self.emit_status(
+            instance,
+            names::INSTANCE_DOWNLOADING_ASSETS,
+            Message::new(keys::BOOTSTRAP_ASSETS_PROGRESS)
+                .progress(total_assets - missing_assets, total_assets),
+        );
 
-                if !target_dir.exists() {
-                    fs::create_dir_all(&target_dir)?;
-                }
+        // Descargar los que faltan en paralelo
+        Self::with_download_pool(|| {
+            pending
+                .par_iter()
+                .try_for_each(|(asset_name, hash, asset_file)| -> IoResult<()> {
+                    let hash_prefix = &hash[0..2];
+                    let asset_url = format!(
+                        "https://resources.download.minecraft.net/{}/{}",
+                        hash_prefix, hash
+                    );
+                    fs::create_dir_all(assets_objects_dir.join(hash_prefix))?;
+
+                    // Por hash: dos versiones con el mismo asset comparten la descarga
+                    self.download_file_verified(&asset_url, asset_file, Some(*hash))
+                        .map_err(|e| {
+                            io::Error::new(
+                                io::ErrorKind::Other,
+                                format!("Error al descargar asset {}: {}", asset_name, e),
+                            )
+                        })?;
 
-                self.download_file(&asset_url, &asset_file).map_err(|e| {
-                    io::Error::new(
-                        io::ErrorKind::Other,
-                        format!("Error al descargar asset {}: {}", asset_name, e),
-                    )
-                })?;
-            }
+                    // Informar progreso
+                    let processed = processed_assets.fetch_add(1, Ordering::Relaxed) + 1;
+                    log::info!(
+                        "Validando assets: {}/{} ({:.1}%)",
+                        processed,
+                        total_assets,
+                        (processed as f64 * 100.0 / total_assets as f64)
+                    );
+                    self.emit_status(
+                        instance,
+                        names::INSTANCE_DOWNLOADING_ASSETS,
+                        Message::new(keys::BOOTSTRAP_ASSETS_PROGRESS)
+                            .progress(processed, total_assets),
+                    );
+                    Ok(())
+                })
+        })?;
+
+        if let Some(tracker) = self.transfer_tracker() {
+            tracker.flush();
         }
 
         if missing_assets > 0 {
@@ -398,16 +589,40 @@ impl InstanceBootstrap {
             log::info!("Todos los assets están validados.");
         }
 
+        // Índices antiguos (legacy / pre-1.6) marcan `virtual` o
+        // `map_to_resources`: el juego lee los archivos por nombre
+        let is_virtual = assets_index_root
+            .get("virtual")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let map_to_resources = assets_index_root
+            .get("map_to_resources")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        if is_virtual || map_to_resources {
+            self.emit_status(
+                instance,
+                names::INSTANCE_DOWNLOADING_ASSETS,
+                Message::new(keys::BOOTSTRAP_ASSETS_LEGACY),
+            );
+            let written =
+                legacy::materialize_legacy_assets(&assets_dir, &minecraft_folder, assets_index_id)?;
+            log::info!(
+                "Assets legacy preparados para {} ({} archivos copiados)",
+                assets_index_id,
+                written
+            );
+        }
+
         log::info!("Asset revalidation completed");
 
         // Emitir evento de finalización
-        Self::emit_status(
+        self.emit_status(
             instance,
-            "instance-finish-assets-download",
-            &format!(
-                "Validación de assets completada para {}",
-                instance.instanceName
-            ),
+            names::INSTANCE_FINISH_ASSETS_DOWNLOAD,
+            Message::new(keys::BOOTSTRAP_ASSETS_DONE)
+                .param("instanceName", instance.instanceName.clone()),
         );
         Ok(())
     }
@@ -434,9 +649,7 @@ impl InstanceBootstrap {
             .ok_or_else(|| "Invalid version info format".to_string())?;
 
         // Descargar detalles de la versión
-        self.client
-            .get(version_url)
-            .send()
+        mirrors::get(&self.client, version_url)
             .map_err(|e| format!("Error fetching version details: {}", e))?
             .json::<Value>()
             .map_err(|e| format!("Error parsing version details: {}", e))
@@ -444,6 +657,92 @@ impl InstanceBootstrap {
 
     // Método para descargar archivos
     fn download_file(&self, url: &str, destination: &Path) -> Result<(), String> {
+        self.download_file_verified(url, destination, None)
+    }
+
+    /// Descarga un archivo a un temporal que se renombra al terminar; si otra
+    /// tarea ya está descargando el mismo archivo se espera y se reutiliza
+    fn download_file_verified(
+        &self,
+        url: &str,
+        destination: &Path,
+        hash: Option<&str>,
+    ) -> Result<(), String> {
+        let outcome = inflight_downloads::download(destination, hash, |temp| {
+            self.download_mirrored(url, temp, hash)
+        })?;
+        if outcome == DownloadOutcome::Shared {
+            self.skip_transfer(destination);
+        }
+        Ok(())
+    }
+
+    /// Sin conexión espera a que vuelva; cancelar la tarea deja de esperar
+    fn wait_for_network(&self) -> Result<(), String> {
+        if network_monitor::is_online() {
+            return Ok(());
+        }
+        log::warn!("Offline, downloads paused until the connection returns");
+        let cancel = self
+            .transfer_tracker()
+            .map(|tracker| tasks_manager::cancellation_token(tracker.task_id()));
+        if network_monitor::wait_until_online(cancel.as_ref()) {
+            Ok(())
+        } else {
+            Err("Operación cancelada".to_string())
+        }
+    }
+
+    /// Descarga `url`; si la conexión se pierde a medias, la descarga se
+    /// pausa hasta que vuelve y entonces se repite
+    fn download_mirrored(
+        &self,
+        url: &str,
+        destination: &Path,
+        hash: Option<&str>,
+    ) -> Result<(), String> {
+        loop {
+            self.wait_for_network()?;
+            match self.download_mirrored_once(url, destination, hash) {
+                Err(e) if !network_monitor::is_online() => {
+                    log::warn!("Connection lost while downloading {}: {}", url, e);
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Descarga un archivo desde su mirror, si hay uno configurado; si el
+    /// mirror falla se vuelve a intentar desde el host oficial
+    fn download_mirrored_once(
+        &self,
+        url: &str,
+        destination: &Path,
+        hash: Option<&str>,
+    ) -> Result<(), String> {
+        let mirrored = mirrors::rewrite(url);
+        if mirrored == url {
+            return self.download_from(url, destination, hash);
+        }
+        self.download_from(&mirrored, destination, hash)
+            .or_else(|e| {
+                log::warn!(
+                    "[Mirrors] {} failed ({}), using the official host",
+                    mirrored,
+                    e
+                );
+                self.download_from(url, destination, hash)
+            })
+    }
+
+    /// Descarga un archivo; los grandes se bajan por fragmentos en paralelo y
+    /// en ese caso se comprueba `hash` antes de darlos por buenos
+    fn download_from(
+        &self,
+        url: &str,
+        destination: &Path,
+        hash: Option<&str>,
+    ) -> Result<(), String> {
         // Asegurarse de que el directorio padre existe
         if let Some(parent) = destination.parent() {
             fs::create_dir_all(parent).map_err(|e| format!("Error creating directory: {}", e))?;
@@ -462,13 +761,80 @@ impl InstanceBootstrap {
             ));
         }
 
-        let mut file =
+        let tracker = self.transfer_tracker();
+        if let Some(tracker) = &tracker {
+            let name = destination
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy();
+            tracker.start_file(&name, response.content_length());
+        }
+
+        let chunk_settings = ChunkSettings::load();
+        if let Some(total) = chunk_settings.split_length(&response) {
+            drop(response);
+            match chunked_download::download(
+                &self.client,
+                url,
+                destination,
+                total,
+                chunk_settings.connections,
+                hash,
+                tracker.as_deref(),
+            ) {
+                Ok(()) => {
+                    if let Some(tracker) = &tracker {
+                        tracker.finish_file();
+                    }
+                    return Ok(());
+                }
+                Err(e) => {
+                    log::warn!(
+                        "Chunked download of {} failed, retrying as a single stream: {}",
+                        url,
+                        e
+                    );
+                    response = self
+                        .client
+                        .get(url)
+                        .send()
+                        .and_then(|response| response.error_for_status())
+                        .map_err(|e| format!("Download error: {}", e))?;
+                }
+            }
+        }
+
+        let file =
             fs::File::create(destination).map_err(|e| format!("Error creating file: {}", e))?;
 
         response
-            .copy_to(&mut file)
+            .copy_to(&mut TrackedWriter::new(file, tracker.as_deref()))
             .map_err(|e| format!("Error writing file: {}", e))?;
 
+        if let Some(tracker) = &tracker {
+            tracker.finish_file();
+        }
+        Ok(())
+    }
+
+    // Descarga consultando antes la caché compartida cuando se conoce el hash
+    fn download_file_cached(
+        &self,
+        url: &str,
+        destination: &Path,
+        hash: Option<&str>,
+    ) -> Result<(), String> {
+        let Some(hash) = hash else {
+            return self.download_file(url, destination);
+        };
+
+        if download_cache::restore(hash, destination) {
+            self.skip_transfer(destination);
+            return Ok(());
+        }
+
+        self.download_file_verified(url, destination, Some(hash))?;
+        download_cache::store(destination, hash);
         Ok(())
     }
 
@@ -487,11 +853,8 @@ impl InstanceBootstrap {
         }
 
         // Obtener nuevo manifiesto
-        let manifest = self
-            .client
-            .get(Self::MOJANG_VERSION_MANIFEST_URL)
-            .send()?
-            .json::<Value>()?;
+        let manifest =
+            mirrors::get(&self.client, Self::MOJANG_VERSION_MANIFEST_URL)?.json::<Value>()?;
 
         // Actualizar caché
         self.version_manifest_cache = Some((manifest.clone(), current_time));
@@ -509,11 +872,25 @@ impl InstanceBootstrap {
         task_id: Option<String>,
         task_manager: Option<Arc<Mutex<TasksManager>>>,
     ) -> Result<(), String> {
+        self.bootstrap_vanilla(instance, task_id, task_manager, true)
+    }
+
+    /// Vanilla bootstrap; `with_assets` is false when the caller validates
+    /// the assets as a step of its own (Forge).
+    fn bootstrap_vanilla(
+        &mut self,
+        instance: &MinecraftInstance,
+        task_id: Option<String>,
+        task_manager: Option<Arc<Mutex<TasksManager>>>,
+        with_assets: bool,
+    ) -> Result<(), String> {
+        self.track_transfers(task_id.as_deref(), task_manager.as_ref());
+
         // Emit start event
-        Self::emit_status(
+        self.emit_status(
             instance,
-            "instance-bootstrap-start",
-            "Iniciando bootstrap de instancia Vanilla",
+            names::INSTANCE_BOOTSTRAP_START,
+            Message::new(keys::BOOTSTRAP_VANILLA_START),
         );
 
         // Update task status if task_id exists
@@ -523,7 +900,7 @@ impl InstanceBootstrap {
                     task_id,
                     TaskStatus::Running,
                     5.0,
-                    "Iniciando bootstrap de instancia Vanilla",
+                    Message::new(keys::BOOTSTRAP_VANILLA_START),
                     Some(serde_json::json!({
                         "instanceName": instance.instanceName.clone(),
                         "instanceId": instance.instanceId.clone()
@@ -542,13 +919,11 @@ impl InstanceBootstrap {
         }
 
         // Create required subdirectories
-        let versions_dir = minecraft_dir.join("versions");
-        let libraries_dir = minecraft_dir.join("libraries");
-        let assets_dir = minecraft_dir.join("assets");
-        let version_dir = versions_dir.join(&instance.minecraftVersion);
-        let natives_dir = minecraft_dir
-            .join("natives")
-            .join(&instance.minecraftVersion);
+        let versions_dir = paths::versions_dir(&minecraft_dir);
+        let libraries_dir = paths::libraries_dir(&minecraft_dir);
+        let assets_dir = paths::assets_dir(&minecraft_dir);
+        let version_dir = paths::version_dir(&minecraft_dir, &instance.minecraftVersion);
+        let natives_dir = paths::extracted_natives_dir(&minecraft_dir, &instance.minecraftVersion);
 
         for dir in [
             &versions_dir,
@@ -570,7 +945,7 @@ impl InstanceBootstrap {
                     task_id,
                     TaskStatus::Running,
                     15.0,
-                    "Descargando manifiesto de versión",
+                    Message::new(keys::BOOTSTRAP_MANIFEST),
                     Some(serde_json::json!({
                         "instanceName": instance.instanceName.clone(),
                         "instanceId": instance.instanceId.clone()
@@ -580,17 +955,18 @@ impl InstanceBootstrap {
         }
 
         // Get version details
-        Self::emit_status(
+        self.emit_status(
             instance,
-            "instance-downloading-manifest",
-            "Descargando manifiesto de versión",
+            names::INSTANCE_DOWNLOADING_MANIFEST,
+            Message::new(keys::BOOTSTRAP_MANIFEST),
         );
         let version_details = self
             .get_version_details(&instance.minecraftVersion)
             .map_err(|e| format!("Error fetching version details: {}", e))?;
 
         // Download version JSON
-        let version_json_path = version_dir.join(format!("{}.json", instance.minecraftVersion));
+        let version_json_path =
+            paths::version_file(&minecraft_dir, &instance.minecraftVersion, "json");
         if !version_json_path.exists() {
             let version_manifest = self
                 .get_version_manifest()
@@ -621,7 +997,8 @@ impl InstanceBootstrap {
                         task_id,
                         TaskStatus::Running,
                         25.0,
-                        &format!("Descargando JSON de versión: {}", instance.minecraftVersion),
+                        Message::new(keys::BOOTSTRAP_VERSION_JSON)
+                            .param("minecraftVersion", instance.minecraftVersion.clone()),
                         Some(serde_json::json!({
                             "instanceName": instance.instanceName.clone(),
                             "instanceId": instance.instanceId.clone()
@@ -630,10 +1007,11 @@ impl InstanceBootstrap {
                 }
             }
 
-            Self::emit_status(
+            self.emit_status(
                 instance,
-                "instance-downloading-json",
-                &format!("Descargando JSON de versión: {}", instance.minecraftVersion),
+                names::INSTANCE_DOWNLOADING_JSON,
+                Message::new(keys::BOOTSTRAP_VERSION_JSON)
+                    .param("minecraftVersion", instance.minecraftVersion.clone()),
             );
 
             self.download_file(version_url, &version_json_path)
@@ -641,7 +1019,8 @@ impl InstanceBootstrap {
         }
 
         // Download client jar
-        let client_jar_path = version_dir.join(format!("{}.jar", instance.minecraftVersion));
+        let client_jar_path =
+            paths::version_file(&minecraft_dir, &instance.minecraftVersion, "jar");
         if !client_jar_path.exists() {
             let client_url = version_details["downloads"]["client"]["url"]
                 .as_str()
@@ -654,7 +1033,8 @@ impl InstanceBootstrap {
                         task_id,
                         TaskStatus::Running,
                         35.0,
-                        &format!("Descargando cliente: {}", instance.minecraftVersion),
+                        Message::new(keys::BOOTSTRAP_CLIENT)
+                            .param("minecraftVersion", instance.minecraftVersion.clone()),
                         Some(serde_json::json!({
                             "instanceName": instance.instanceName.clone(),
                             "instanceId": instance.instanceId.clone()
@@ -663,14 +1043,19 @@ impl InstanceBootstrap {
                 }
             }
 
-            Self::emit_status(
+            self.emit_status(
                 instance,
-                "instance-downloading-client",
-                &format!("Descargando cliente: {}", instance.minecraftVersion),
+                names::INSTANCE_DOWNLOADING_CLIENT,
+                Message::new(keys::BOOTSTRAP_CLIENT)
+                    .param("minecraftVersion", instance.minecraftVersion.clone()),
             );
 
-            self.download_file(client_url, &client_jar_path)
-                .map_err(|e| format!("Error downloading client jar: {}", e))?;
+            self.download_file_cached(
+                client_url,
+                &client_jar_path,
+                version_details["downloads"]["client"]["sha1"].as_str(),
+            )
+            .map_err(|e| format!("Error downloading client jar: {}", e))?;
         }
 
         // Update task status - 45%
@@ -680,7 +1065,7 @@ impl InstanceBootstrap {
                     task_id,
                     TaskStatus::Running,
                     45.0,
-                    "Descargando librerías",
+                    Message::new(keys::BOOTSTRAP_LIBRARIES),
                     Some(serde_json::json!({
                         "instanceName": instance.instanceName.clone(),
                         "instanceId": instance.instanceId.clone()
@@ -692,26 +1077,20 @@ impl InstanceBootstrap {
         /*
         "javaVersion": {"majorVersion": 21},
         */
-        // Check if correct Java version is installed for this instance
-        let java_version = version_details["javaVersion"]
-            .as_object()
-            .ok_or_else(|| "Java version not found in version details".to_string())?;
-
-        println!("");
-        println!("");
-        println!("");
-
-        println!("Java Version Details: {:?}", java_version);
-        println!("");
-        println!("");
-        println!("");
-
-        // As string
-        let java_major_version = java_version
-            .get("majorVersion")
-            .and_then(|v| v.as_u64()) // Lo tomás como número primero
-            .map(|v| v.to_string()) // Luego lo convertís a String
-            .ok_or_else(|| "8".to_string())?; // Valor por defecto si falla
+        // Check if correct Java version is installed for this instance.
+        // Snapshots antiguas y versiones old_alpha/old_beta no declaran
+        // `javaVersion`: todas funcionan con Java 8
+        let java_major_version = version_details["javaVersion"]["majorVersion"]
+            .as_u64()
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "8".to_string());
+
+        log::info!(
+            "Java {} required for {} ({})",
+            java_major_version,
+            instance.minecraftVersion,
+            version_type(&version_details)
+        );
 
         println!("Java Major Version: {}", java_major_version);
 
@@ -739,7 +1118,7 @@ impl InstanceBootstrap {
                         task_id,
                         TaskStatus::Running,
                         50.0,
-                        "Instalando Java",
+                        Message::new(keys::BOOTSTRAP_JAVA),
                         Some(serde_json::json!({
                             "instanceName": instance.instanceName.clone(),
                             "instanceId": instance.instanceId.clone()
@@ -748,39 +1127,59 @@ impl InstanceBootstrap {
                 }
             }
 
-            let mut instance_to_modify = instance.clone();
-            instance_to_modify.set_java_path(java_path);
+            let java_path = java_path.to_string_lossy().to_string();
+            if let Err(e) = minecraft_instance::with_locked(instance_dir, |stored| {
+                stored.javaMajorVersion = Some(java_major_version.clone());
+                stored.javaPath = Some(java_path);
+                Ok(())
+            }) {
+                log::warn!("Failed to save Java path for instance: {}", e);
+            }
+        } else if instance.javaMajorVersion.as_deref() != Some(java_major_version.as_str()) {
+            // Registrar qué runtime usa la instancia para no borrarlo al limpiar
+            if let Err(e) = minecraft_instance::with_locked(instance_dir, |stored| {
+                stored.javaMajorVersion = Some(java_major_version.clone());
+                Ok(())
+            }) {
+                log::warn!("Failed to save Java version for instance: {}", e);
+            }
         }
 
         // Download and validate libraries
-        Self::emit_status(
+        self.emit_status(
             instance,
-            "instance-downloading-libraries",
-            "Descargando librerías",
+            names::INSTANCE_DOWNLOADING_LIBRARIES,
+            Message::new(keys::BOOTSTRAP_LIBRARIES),
         );
         self.download_libraries(&version_details, &libraries_dir, instance)
             .map_err(|e| format!("Error downloading libraries: {}", e))?;
 
-        // Update task status - 60%
-        if let (Some(task_id), Some(task_manager)) = (&task_id, &task_manager) {
-            if let Ok(mut tm) = task_manager.lock() {
-                tm.update_task(
-                    task_id,
-                    TaskStatus::Running,
-                    60.0,
-                    "Validando assets",
-                    Some(serde_json::json!({
-                        "instanceName": instance.instanceName.clone(),
-                        "instanceId": instance.instanceId.clone()
-                    })),
-                );
+        if with_assets {
+            // Update task status - 60%
+            if let (Some(task_id), Some(task_manager)) = (&task_id, &task_manager) {
+                if let Ok(mut tm) = task_manager.lock() {
+                    tm.update_task(
+                        task_id,
+                        TaskStatus::Running,
+                        60.0,
+                        Message::new(keys::BOOTSTRAP_ASSETS),
+                        Some(serde_json::json!({
+                            "instanceName": instance.instanceName.clone(),
+                            "instanceId": instance.instanceId.clone()
+                        })),
+                    );
+                }
             }
-        }
 
-        // Validate assets
-        Self::emit_status(instance, "instance-downloading-assets", "Validando assets");
-        self.revalidate_assets(instance)
-            .map_err(|e| format!("Error validating assets: {}", e))?;
+            // Validate assets
+            self.emit_status(
+                instance,
+                names::INSTANCE_DOWNLOADING_ASSETS,
+                Message::new(keys::BOOTSTRAP_ASSETS),
+            );
+            self.revalidate_assets(instance)
+                .map_err(|e| format!("Error validating assets: {}", e))?;
+        }
 
         // Create launcher profiles.json if it doesn't exist
         let launcher_profiles_path = minecraft_dir.join("launcher_profiles.json");
@@ -809,7 +1208,7 @@ impl InstanceBootstrap {
                     task_id,
                     TaskStatus::Running,
                     75.0,
-                    "Extrayendo bibliotecas nativas",
+                    Message::new(keys::BOOTSTRAP_NATIVES),
                     Some(serde_json::json!({
                         "instanceName": instance.instanceName.clone(),
                         "instanceId": instance.instanceId.clone()
@@ -818,16 +1217,21 @@ impl InstanceBootstrap {
             }
         }
 
-        Self::emit_status(
+        self.emit_status(
             instance,
-            "instance-extracting-natives",
-            "Extrayendo bibliotecas nativas",
+            names::INSTANCE_EXTRACTING_NATIVES,
+            Message::new(keys::BOOTSTRAP_NATIVES),
         );
 
         // Extraer bibliotecas nativas
-        if let Err(e) =
-            self.extract_natives(&version_details, &libraries_dir, &natives_dir, instance)
-        {
+        if let Err(e) = self.extract_natives(
+            &version_details,
+            &libraries_dir,
+            &natives_dir,
+            instance,
+            task_id.as_deref(),
+            task_manager.as_ref(),
+        ) {
             log::error!("Error extrayendo bibliotecas nativas: {}", e);
             // No devolver error aquí, ya que es opcional
         }
@@ -839,7 +1243,7 @@ impl InstanceBootstrap {
                     task_id,
                     TaskStatus::Running,
                     90.0,
-                    "Finalizando configuración",
+                    Message::new(keys::BOOTSTRAP_FINISHING),
                     Some(serde_json::json!({
                         "instanceName": instance.instanceName.clone(),
                         "instanceId": instance.instanceId.clone()
@@ -853,13 +1257,11 @@ impl InstanceBootstrap {
         // de finalización, así que lo hará la función que llame al proceso
         // de bootstrap.
 
-        Self::emit_status(
+        self.emit_status(
             instance,
             "vanilla-instance-bootstrapped",
-            &format!(
-                "Bootstrap de instancia Vanilla {} completado",
-                instance.minecraftVersion
-            ),
+            Message::new(keys::BOOTSTRAP_VANILLA_DONE)
+                .param("minecraftVersion", instance.minecraftVersion.clone()),
         );
 
         Ok(())
@@ -879,46 +1281,21 @@ impl InstanceBootstrap {
         let total_libraries = libraries.len();
         let mut downloaded_libraries = 0;
 
-        Self::emit_status(
+        self.emit_status(
             instance,
-            "instance-downloading-forge-libraries",
-            &format!(
-                "Descargando librerías de Forge: 0/{} (0.0%)",
-                total_libraries
-            ),
+            names::INSTANCE_DOWNLOADING_FORGE_LIBRARIES,
+            Message::new(keys::FORGE_LIBRARIES_PROGRESS).progress(0, total_libraries),
         );
 
         for library in libraries {
             // Verificar reglas de exclusión/inclusión para esta librería
-            if let Some(rules) = library.get("rules") {
-                let mut allowed = false;
-
-                for rule in rules.as_array().unwrap_or(&Vec::new()) {
-                    let action = rule["action"].as_str().unwrap_or("disallow");
-
-                    // Manejar reglas específicas de SO
-                    if let Some(os) = rule.get("os") {
-                        let os_name = os["name"].as_str().unwrap_or("");
-                        let current_os = if cfg!(target_os = "windows") {
-                            "windows"
-                        } else if cfg!(target_os = "macos") {
-                            "osx"
-                        } else {
-                            "linux"
-                        };
-
-                        if os_name == current_os {
-                            allowed = action == "allow";
-                        }
-                    } else {
-                        // Sin SO especificado, aplicar a todos
-                        allowed = action == "allow";
-                    }
-                }
+            if !RuleEvaluator::applies(library, None) {
+                continue; // Saltar esta librería
+            }
 
-                if !allowed {
-                    continue; // Saltar esta librería
-                }
+            // Perfiles legacy: librerías sólo de servidor
+            if library.get("clientreq").and_then(Value::as_bool) == Some(false) {
+                continue;
             }
 
             // Manejo de librerías con formato Maven (común en Forge)
@@ -935,7 +1312,7 @@ impl InstanceBootstrap {
                         .as_str()
                         .ok_or_else(|| "URL de artefacto no encontrada".to_string())?;
 
-                    let target_path = libraries_dir.join(path);
+                    let target_path = paths::join_relative(&libraries_dir, path);
 
                     // Crear directorios padre si es necesario
                     if let Some(parent) = target_path.parent() {
@@ -945,7 +1322,7 @@ impl InstanceBootstrap {
 
                     // Descargar si el archivo no existe
                     if !target_path.exists() {
-                        self.download_file(url, &target_path)
+                        self.download_file_cached(url, &target_path, artifact["sha1"].as_str())
                             .map_err(|e| format!("Error al descargar librería: {}", e))?;
                     }
                 }
@@ -968,7 +1345,7 @@ impl InstanceBootstrap {
                             .as_str()
                             .ok_or_else(|| "Ruta de librería nativa no encontrada".to_string())?;
 
-                        let target_path = libraries_dir.join(path);
+                        let target_path = paths::join_relative(&libraries_dir, path);
 
                         // Crear directorios padre si es necesario
                         if let Some(parent) = target_path.parent() {
@@ -978,40 +1355,20 @@ impl InstanceBootstrap {
 
                         // Descargar si el archivo no existe
                         if !target_path.exists() {
-                            self.download_file(url, &target_path).map_err(|e| {
-                                format!("Error al descargar librería nativa: {}", e)
-                            })?;
+                            self.download_file_cached(url, &target_path, native["sha1"].as_str())
+                                .map_err(|e| {
+                                    format!("Error al descargar librería nativa: {}", e)
+                                })?;
                         }
                     }
                 }
             }
             // Para librerías sin información de descarga directa, usar formato Maven
             else if !name.is_empty() {
-                // Parsear el nombre en formato Maven: groupId:artifactId:version[:classifier]
-                let parts: Vec<&str> = name.split(':').collect();
-                if parts.len() >= 3 {
-                    let group_id = parts[0];
-                    let artifact_id = parts[1];
-                    let version = parts[2];
-                    let classifier = if parts.len() > 3 {
-                        Some(parts[3])
-                    } else {
-                        None
-                    };
-
-                    // Convertir la especificación de grupo en path
-                    let group_path = group_id.replace('.', "/");
-
-                    // Construir la ruta al archivo JAR
-                    let jar_name = if let Some(classifier) = classifier {
-                        format!("{}-{}-{}.jar", artifact_id, version, classifier)
-                    } else {
-                        format!("{}-{}.jar", artifact_id, version)
-                    };
-
-                    let relative_path =
-                        format!("{}/{}/{}/{}", group_path, artifact_id, version, jar_name);
-                    let target_path = libraries_dir.join(&relative_path);
+                // Parsear el nombre en formato Maven: groupId:artifactId:version[:classifier][@ext]
+                if let Some(coordinate) = MavenCoordinate::parse(name) {
+                    let relative_path = coordinate.url_path();
+                    let target_path = paths::join_relative(&libraries_dir, &relative_path);
 
                     // Crear directorios padre si es necesario
                     if let Some(parent) = target_path.parent() {
@@ -1021,9 +1378,14 @@ impl InstanceBootstrap {
 
                     // Construir la URL para la descarga
                     // Probar primero con el repositorio de Forge
-                    let repo_url = library["url"]
-                        .as_str()
-                        .unwrap_or("https://maven.minecraftforge.net/");
+                    // Los perfiles antiguos apuntan al maven de Forge retirado
+                    let repo_url = match library["url"].as_str() {
+                        Some(url) if url.contains("files.minecraftforge.net/maven") => {
+                            "https://maven.minecraftforge.net/"
+                        }
+                        Some(url) => url,
+                        None => "https://maven.minecraftforge.net/",
+                    };
                     let download_url = format!("{}{}", repo_url, relative_path);
 
                     // Descargar si el archivo no existe
@@ -1047,14 +1409,11 @@ impl InstanceBootstrap {
 
             // Actualizar progreso cada 5 librerías o en la última
             if downloaded_libraries % 5 == 0 || downloaded_libraries == total_libraries {
-                let progress = (downloaded_libraries as f32 / total_libraries as f32) * 100.0;
-                Self::emit_status(
+                self.emit_status(
                     instance,
-                    "instance-downloading-forge-libraries",
-                    &format!(
-                        "Descargando librerías de Forge: {}/{} ({:.1}%)",
-                        downloaded_libraries, total_libraries, progress
-                    ),
+                    names::INSTANCE_DOWNLOADING_FORGE_LIBRARIES,
+                    Message::new(keys::FORGE_LIBRARIES_PROGRESS)
+                        .progress(downloaded_libraries, total_libraries),
                 );
             }
         }
@@ -1077,35 +1436,8 @@ impl InstanceBootstrap {
 
         for library in libraries {
             // Check if we should skip this library based on rules
-            if let Some(rules) = library.get("rules") {
-                let mut allowed = false;
-
-                for rule in rules.as_array().unwrap_or(&Vec::new()) {
-                    let action = rule["action"].as_str().unwrap_or("disallow");
-
-                    // Handle OS-specific rules
-                    if let Some(os) = rule.get("os") {
-                        let os_name = os["name"].as_str().unwrap_or("");
-                        let current_os = if cfg!(target_os = "windows") {
-                            "windows"
-                        } else if cfg!(target_os = "macos") {
-                            "osx"
-                        } else {
-                            "linux"
-                        };
-
-                        if os_name == current_os {
-                            allowed = action == "allow";
-                        }
-                    } else {
-                        // No OS specified, apply to all
-                        allowed = action == "allow";
-                    }
-                }
-
-                if !allowed {
-                    continue; // Skip this library
-                }
+            if !RuleEvaluator::applies(library, None) {
+                continue; // Skip this library
             }
 
             // Get library info
@@ -1122,7 +1454,7 @@ impl InstanceBootstrap {
                     .as_str()
                     .ok_or_else(|| "Library artifact URL not found".to_string())?;
 
-                let target_path = libraries_dir.join(path);
+                let target_path = paths::join_relative(&libraries_dir, path);
 
                 // Create parent directories if needed
                 if let Some(parent) = target_path.parent() {
@@ -1132,7 +1464,7 @@ impl InstanceBootstrap {
 
                 // Download if file doesn't exist
                 if !target_path.exists() {
-                    self.download_file(url, &target_path)
+                    self.download_file_cached(url, &target_path, artifact["sha1"].as_str())
                         .map_err(|e| format!("Error downloading library: {}", e))?;
                 }
             }
@@ -1155,7 +1487,7 @@ impl InstanceBootstrap {
                         .as_str()
                         .ok_or_else(|| "Native library path not found".to_string())?;
 
-                    let target_path = libraries_dir.join(path);
+                    let target_path = paths::join_relative(&libraries_dir, path);
 
                     // Create parent directories if needed
                     if let Some(parent) = target_path.parent() {
@@ -1165,7 +1497,7 @@ impl InstanceBootstrap {
 
                     // Download if file doesn't exist
                     if !target_path.exists() {
-                        self.download_file(url, &target_path)
+                        self.download_file_cached(url, &target_path, native["sha1"].as_str())
                             .map_err(|e| format!("Error downloading native library: {}", e))?;
                     }
                 }
@@ -1175,14 +1507,11 @@ impl InstanceBootstrap {
 
             // Update progress every 5 libraries or on last library
             if downloaded_libraries % 5 == 0 || downloaded_libraries == total_libraries {
-                let progress = (downloaded_libraries as f32 / total_libraries as f32) * 100.0;
-                Self::emit_status(
+                self.emit_status(
                     instance,
-                    "instance-downloading-libraries",
-                    &format!(
-                        "Descargando librerías: {}/{} ({:.1}%)",
-                        downloaded_libraries, total_libraries, progress
-                    ),
+                    names::INSTANCE_DOWNLOADING_LIBRARIES,
+                    Message::new(keys::BOOTSTRAP_LIBRARIES_PROGRESS)
+                        .progress(downloaded_libraries, total_libraries),
                 );
             }
         }
@@ -1196,98 +1525,79 @@ impl InstanceBootstrap {
         task_id: Option<String>,
         task_manager: Option<Arc<Mutex<TasksManager>>>,
     ) -> Result<(), String> {
+        self.track_transfers(task_id.as_deref(), task_manager.as_ref());
+
         // Verificar que tengamos información de Forge
         if instance.forgeVersion.is_none() || instance.forgeVersion.as_ref().unwrap().is_empty() {
             return Err("No se especificó versión de Forge".to_string());
         }
 
         // Emit start event
-        Self::emit_status(
+        self.emit_status(
             instance,
-            "instance-bootstrap-start",
-            "Iniciando bootstrap de instancia Forge",
+            names::INSTANCE_BOOTSTRAP_START,
+            Message::new(keys::FORGE_START),
         );
 
-        // Update task status if task_id exists
-        if let (Some(task_id), Some(task_manager)) = (&task_id, &task_manager) {
-            if let Ok(mut tm) = task_manager.lock() {
-                tm.update_task(
-                    task_id,
-                    TaskStatus::Running,
-                    5.0,
-                    "Iniciando bootstrap de instancia Forge",
-                    Some(serde_json::json!({
-                        "instanceName": instance.instanceName.clone(),
-                        "instanceId": instance.instanceId.clone()
-                    })),
-                );
-            }
-        }
+        // Cada paso es una subtarea y el progreso de la tarea sale de ellas
+        let [vanilla_task, installer_task, libraries_task, assets_task] =
+            Self::FORGE_STEPS.map(|(label, weight)| match (&task_id, &task_manager) {
+                (Some(task_id), Some(task_manager)) => task_manager
+                    .lock()
+                    .ok()
+                    .map(|tm| tm.add_subtask(task_id, label, weight)),
+                _ => None,
+            });
+        let task_manager = task_manager.as_ref();
 
-        // Primero, realizar bootstrap de la instancia Vanilla
-        Self::emit_status(
+        Self::report_step(
             instance,
-            "instance-forge-vanilla-setup",
-            "Configurando base Vanilla",
+            task_id.as_deref(),
+            task_manager,
+            TaskStatus::Running,
+            0.0,
+            Message::new(keys::FORGE_START),
         );
 
-        // Update task status - 10%
-        if let (Some(task_id), Some(task_manager)) = (&task_id, &task_manager) {
-            if let Ok(mut tm) = task_manager.lock() {
-                tm.update_task(
-                    task_id,
-                    TaskStatus::Running,
-                    10.0,
-                    "Configurando base Vanilla",
-                    Some(serde_json::json!({
-                        "instanceName": instance.instanceName.clone(),
-                        "instanceId": instance.instanceId.clone()
-                    })),
-                );
-            }
-        }
+        // Primero, realizar bootstrap de la instancia Vanilla
+        self.emit_status(
+            instance,
+            names::INSTANCE_FORGE_VANILLA_SETUP,
+            Message::new(keys::FORGE_VANILLA_SETUP),
+        );
 
-        // Bootstrap Vanilla primero
-        self.bootstrap_vanilla_instance(instance, None, None)
+        // Los assets se validan al final, como paso propio
+        self.bootstrap_vanilla(instance, vanilla_task.clone(), task_manager.cloned(), false)
             .map_err(|e| format!("Error en bootstrap Vanilla: {}", e))?;
-
-        // Update task status - 60%
-        if let (Some(task_id), Some(task_manager)) = (&task_id, &task_manager) {
-            if let Ok(mut tm) = task_manager.lock() {
-                tm.update_task(
-                    task_id,
-                    TaskStatus::Running,
-                    60.0,
-                    "Configurando Forge",
-                    Some(serde_json::json!({
-                        "instanceName": instance.instanceName.clone(),
-                        "instanceId": instance.instanceId.clone()
-                    })),
-                );
-            }
-        }
+        Self::report_step(
+            instance,
+            vanilla_task.as_deref(),
+            task_manager,
+            TaskStatus::Completed,
+            100.0,
+            Message::new(keys::BOOTSTRAP_VANILLA_DONE)
+                .param("minecraftVersion", instance.minecraftVersion.clone()),
+        );
 
         // Obtener rutas de directorios
         let instance_dir = Path::new(instance.instanceDirectory.as_deref().unwrap_or(""));
         let minecraft_dir = instance_dir.join("minecraft");
-        let versions_dir = minecraft_dir.join("versions");
-        let libraries_dir = minecraft_dir.join("libraries");
+        let libraries_dir = paths::libraries_dir(&minecraft_dir);
 
         // Obtener detalles de Forge
         let forge_version = instance.forgeVersion.as_ref().unwrap();
 
-        Self::emit_status(
+        self.emit_status(
             instance,
-            "instance-downloading-forge",
-            &format!(
-                "Descargando Forge {} para Minecraft {}",
-                forge_version, instance.minecraftVersion
-            ),
+            names::INSTANCE_DOWNLOADING_FORGE,
+            Message::new(keys::FORGE_DOWNLOADING)
+                .param("forgeVersion", forge_version.clone())
+                .param("minecraftVersion", instance.minecraftVersion.clone()),
         );
 
         // Crear directorio específico para la versión de Forge
         let forge_version_name = format!("{}-forge-{}", instance.minecraftVersion, forge_version);
-        let forge_version_dir = versions_dir.join(&forge_version_name);
+        let forge_version_dir = paths::version_dir(&minecraft_dir, &forge_version_name);
 
         if !forge_version_dir.exists() {
             fs::create_dir_all(&forge_version_dir)
@@ -1302,61 +1612,113 @@ impl InstanceBootstrap {
         let forge_installer_path = minecraft_dir.join("forge-installer.jar");
 
         // Descargar instalador Forge
-        Self::emit_status(
+        self.track_transfers(installer_task.as_deref(), task_manager);
+        self.emit_status(
             instance,
-            "instance-downloading-forge-installer",
-            "Descargando instalador de Forge",
+            names::INSTANCE_DOWNLOADING_FORGE_INSTALLER,
+            Message::new(keys::FORGE_INSTALLER_DOWNLOADING),
+        );
+        Self::report_step(
+            instance,
+            installer_task.as_deref(),
+            task_manager,
+            TaskStatus::Running,
+            0.0,
+            Message::new(keys::FORGE_INSTALLER_DOWNLOADING),
         );
         self.download_file(&forge_installer_url, &forge_installer_path)
             .map_err(|e| format!("Error al descargar instalador Forge: {}", e))?;
 
-        // Update task status - 70%
-        if let (Some(task_id), Some(task_manager)) = (&task_id, &task_manager) {
-            if let Ok(mut tm) = task_manager.lock() {
-                tm.update_task(
-                    task_id,
-                    TaskStatus::Running,
-                    70.0,
-                    "Ejecutando instalador de Forge",
-                    Some(serde_json::json!({
-                        "instanceName": instance.instanceName.clone(),
-                        "instanceId": instance.instanceId.clone()
-                    })),
-                );
-            }
-        }
-
         // Ejecutar instalador en modo silencioso
-        Self::emit_status(
+        self.emit_status(
             instance,
-            "instance-installing-forge",
-            "Ejecutando instalador de Forge",
+            names::INSTANCE_INSTALLING_FORGE,
+            Message::new(keys::FORGE_INSTALLER_RUNNING),
         );
 
-        // Preparar argumentos para instalar Forge
-        let forge_install_result = self.run_forge_installer(
-            &forge_installer_path,
-            &minecraft_dir,
-            &instance.minecraftVersion,
-            forge_version,
-            instance,
-        )?;
+        // Instaladores modernos: ejecutamos los processors nosotros mismos y
+        // sólo recurrimos al CLI del instalador si algo falla
+        let mut installed_headless = false;
+        if HeadlessForgeInstaller::is_supported(&forge_installer_path) {
+            let java_path = PathBuf::from(self.find_java_path()?);
+            let headless = HeadlessForgeInstaller::new(
+                &self.client,
+                &forge_installer_path,
+                &minecraft_dir,
+                &instance.minecraftVersion,
+                &java_path,
+            );
 
-        // Update task status - 85%
-        if let (Some(task_id), Some(task_manager)) = (&task_id, &task_manager) {
-            if let Ok(mut tm) = task_manager.lock() {
-                tm.update_task(
-                    task_id,
-                    TaskStatus::Running,
-                    85.0,
-                    "Configurando perfil de Forge",
-                    Some(serde_json::json!({
-                        "instanceName": instance.instanceName.clone(),
-                        "instanceId": instance.instanceId.clone()
-                    })),
-                );
+            match headless.install(|fraction, message| {
+                self.report_forge_install_progress(
+                    instance,
+                    installer_task.as_deref(),
+                    task_manager,
+                    fraction,
+                    message,
+                )
+            }) {
+                Ok(version_id) => {
+                    log::info!("[Forge] Instalación headless completada: {}", version_id);
+                    installed_headless = true;
+                }
+                Err(e) => {
+                    log::warn!(
+                        "[Forge] Instalación headless fallida, usando el instalador: {}",
+                        e
+                    );
+                }
             }
+        } else if HeadlessForgeInstaller::is_legacy(&forge_installer_path) {
+            // Instaladores antiguos (1.7.10 - 1.12.2): no admiten --installClient
+            let java_path = PathBuf::from(self.find_java_path()?);
+            let legacy = HeadlessForgeInstaller::new(
+                &self.client,
+                &forge_installer_path,
+                &minecraft_dir,
+                &instance.minecraftVersion,
+                &java_path,
+            );
+
+            match legacy.install_legacy(&forge_version_name) {
+                Ok(_) => installed_headless = true,
+                Err(e) => {
+                    log::warn!(
+                        "[Forge] Instalación legacy fallida, usando el instalador: {}",
+                        e
+                    );
+                }
+            }
+        }
+
+        if !installed_headless {
+            self.run_forge_installer(
+                &forge_installer_path,
+                &minecraft_dir,
+                &instance.minecraftVersion,
+                forge_version,
+                instance,
+                installer_task.as_deref(),
+                task_manager,
+            )?;
         }
+        Self::report_step(
+            instance,
+            installer_task.as_deref(),
+            task_manager,
+            TaskStatus::Completed,
+            100.0,
+            Message::new(keys::FORGE_INSTALLER_DONE),
+        );
+
+        Self::report_step(
+            instance,
+            task_id.as_deref(),
+            task_manager,
+            TaskStatus::Running,
+            0.0,
+            Message::new(keys::FORGE_PROFILE),
+        );
 
         // Crear/actualizar perfil de Forge en launcher_profiles.json
         let launcher_profiles_path = minecraft_dir.join("launcher_profiles.json");
@@ -1367,31 +1729,25 @@ impl InstanceBootstrap {
         )?;
 
         // Descargar librerías de Forge
-        Self::emit_status(
+        self.track_transfers(libraries_task.as_deref(), task_manager);
+        self.emit_status(
             instance,
-            "instance-downloading-forge-libraries",
-            "Descargando librerías de Forge",
+            names::INSTANCE_DOWNLOADING_FORGE_LIBRARIES,
+            Message::new(keys::FORGE_LIBRARIES),
+        );
+        Self::report_step(
+            instance,
+            libraries_task.as_deref(),
+            task_manager,
+            TaskStatus::Running,
+            0.0,
+            Message::new(keys::FORGE_LIBRARIES),
         );
-
-        if let (Some(task_id), Some(task_manager)) = (&task_id, &task_manager) {
-            if let Ok(mut tm) = task_manager.lock() {
-                tm.update_task(
-                    task_id,
-                    TaskStatus::Running,
-                    90.0,
-                    "Descargando librerías de Forge",
-                    Some(serde_json::json!({
-                        "instanceName": instance.instanceName.clone(),
-                        "instanceId": instance.instanceId.clone()
-                    })),
-                );
-            }
-        }
 
         // Descargar librerías de Forge
         // Leer el archivo de versión para obtener los detalles de las librerías
         let forge_version_json_path =
-            forge_version_dir.join(format!("{}.json", forge_version_name));
+            paths::version_file(&minecraft_dir, &forge_version_name, "json");
 
         if forge_version_json_path.exists() {
             let version_json = fs::read_to_string(&forge_version_json_path)
@@ -1408,22 +1764,50 @@ impl InstanceBootstrap {
                 forge_version_json_path.display()
             ));
         }
+        Self::report_step(
+            instance,
+            libraries_task.as_deref(),
+            task_manager,
+            TaskStatus::Completed,
+            100.0,
+            Message::new(keys::FORGE_LIBRARIES),
+        );
 
-        // Update task status - 95%
-        if let (Some(task_id), Some(task_manager)) = (&task_id, &task_manager) {
-            if let Ok(mut tm) = task_manager.lock() {
-                tm.update_task(
-                    task_id,
-                    TaskStatus::Running,
-                    95.0,
-                    "Configurando Forge",
-                    Some(serde_json::json!({
-                        "instanceName": instance.instanceName.clone(),
-                        "instanceId": instance.instanceId.clone()
-                    })),
-                );
-            }
-        }
+        // Validar assets
+        self.track_transfers(assets_task.as_deref(), task_manager);
+        self.emit_status(
+            instance,
+            names::INSTANCE_DOWNLOADING_ASSETS,
+            Message::new(keys::BOOTSTRAP_ASSETS),
+        );
+        Self::report_step(
+            instance,
+            assets_task.as_deref(),
+            task_manager,
+            TaskStatus::Running,
+            0.0,
+            Message::new(keys::BOOTSTRAP_ASSETS),
+        );
+        self.revalidate_assets(instance)
+            .map_err(|e| format!("Error validating assets: {}", e))?;
+        Self::report_step(
+            instance,
+            assets_task.as_deref(),
+            task_manager,
+            TaskStatus::Completed,
+            100.0,
+            Message::new(keys::BOOTSTRAP_ASSETS_DONE)
+                .param("instanceName", instance.instanceName.clone()),
+        );
+
+        Self::report_step(
+            instance,
+            task_id.as_deref(),
+            task_manager,
+            TaskStatus::Running,
+            0.0,
+            Message::new(keys::FORGE_CONFIGURING),
+        );
 
         // Limpiar instalador Forge para ahorrar espacio
         if forge_installer_path.exists() {
@@ -1435,32 +1819,23 @@ impl InstanceBootstrap {
             }
         }
 
-        // Update task status - 100%
-        if let (Some(task_id), Some(task_manager)) = (&task_id, &task_manager) {
-            if let Ok(mut tm) = task_manager.lock() {
-                tm.update_task(
-                    task_id,
-                    TaskStatus::Completed,
-                    100.0,
-                    &format!(
-                        "Instalación completada: Forge {} para Minecraft {}",
-                        forge_version, instance.minecraftVersion
-                    ),
-                    Some(serde_json::json!({
-                        "instanceName": instance.instanceName.clone(),
-                        "instanceId": instance.instanceId.clone()
-                    })),
-                );
-            }
-        }
+        Self::report_step(
+            instance,
+            task_id.as_deref(),
+            task_manager,
+            TaskStatus::Completed,
+            100.0,
+            Message::new(keys::FORGE_INSTALL_COMPLETED)
+                .param("forgeVersion", forge_version.clone())
+                .param("minecraftVersion", instance.minecraftVersion.clone()),
+        );
 
-        Self::emit_status(
+        self.emit_status(
             instance,
             "forge-instance-bootstrapped",
-            &format!(
-                "Bootstrap de instancia Forge {} para Minecraft {} completado",
-                forge_version, instance.minecraftVersion
-            ),
+            Message::new(keys::FORGE_DONE)
+                .param("forgeVersion", forge_version.clone())
+                .param("minecraftVersion", instance.minecraftVersion.clone()),
         );
 
         Ok(())
@@ -1473,6 +1848,11 @@ impl InstanceBootstrap {
     ) -> Result<String, String> {
         let base = "https://maven.minecraftforge.net/net/minecraftforge/forge";
 
+        // Algunos modpacks guardan la versión completa ("1.12.2-14.23.5.2859")
+        let forge_version = forge_version
+            .strip_prefix(&format!("{}-", minecraft_version))
+            .unwrap_or(forge_version);
+
         let mc_compact = format!("mc{}", minecraft_version.replace('.', ""));
 
         let mut attempts = vec![
@@ -1500,12 +1880,16 @@ impl InstanceBootstrap {
                     format!("forge-{forge_version}-universal.jar"),
                 ],
             ),
-            // Legacy style with full forge version
+            // Legacy branch suffix: 1.7.10-10.13.4.1614-1.7.10
             (
-                forge_version.to_string(),
+                format!("{minecraft_version}-{forge_version}-{minecraft_version}"),
                 vec![
-                    format!("forge-{forge_version}-installer.jar"),
-                    format!("forge-{forge_version}-universal.jar"),
+                    format!(
+                        "forge-{minecraft_version}-{forge_version}-{minecraft_version}-installer.jar"
+                    ),
+                    format!(
+                        "forge-{minecraft_version}-{forge_version}-{minecraft_version}-universal.jar"
+                    ),
                 ],
             ),
             // 🧠 Caso especial: -mcXYZ
@@ -1518,18 +1902,23 @@ impl InstanceBootstrap {
             ),
         ];
 
+        let exists = |url: &str| {
+            log::info!("[Forge] Probando URL: {}", url);
+            self.client
+                .head(url)
+                .send()
+                .map_or(false, |r| r.status().is_success())
+        };
+
         for (folder, files) in attempts.drain(..) {
             for file in files {
                 let url = format!("{}/{}/{}", base, folder, file);
 
-                log::info!("[Forge] Probando URL: {}", url);
-
-                if self
-                    .client
-                    .head(&url)
-                    .send()
-                    .map_or(false, |r| r.status().is_success())
-                {
+                // Primero el mirror configurado, si lo hay; después el host
+                // oficial. Se devuelve la URL oficial: la descarga ya pasa
+                // por el mirror y vuelve al host oficial si falla
+                let mirrored = mirrors::rewrite(&url);
+                if (mirrored != url && exists(&mirrored)) || exists(&url) {
                     return Ok(url);
                 }
             }
@@ -1554,10 +1943,20 @@ impl InstanceBootstrap {
         minecraft_version: &str,
         forge_version: &str,
         instance: &MinecraftInstance,
+        task_id: Option<&str>,
+        task_manager: Option<&Arc<Mutex<TasksManager>>>,
     ) -> Result<(), String> {
         // Determinar la ruta de Java
         let java_path = self.find_java_path()?;
 
+        let timeout = {
+            let config_lock = read_config();
+            config_lock
+                .as_ref()
+                .map(|config| config.get_forge_installer_timeout())
+                .unwrap_or(Duration::from_secs(600))
+        };
+
         // Crear archivo temporal para parámetros de instalación
         let install_profile = minecraft_dir.join("forge-install-profile.json");
         let install_profile_content = json!({
@@ -1571,6 +1970,9 @@ impl InstanceBootstrap {
         fs::write(&install_profile, install_profile_content.to_string())
             .map_err(|e| format!("Error al crear archivo de perfil de instalación: {}", e))?;
 
+        // Número de processors del instalador para poder calcular el progreso real
+        let total_processors = Self::count_installer_processors(installer_path);
+
         // Lista de opciones de instalación para probar secuencialmente
         let install_options = ["--installClient", "--installDir", "--installServer"];
 
@@ -1585,32 +1987,53 @@ impl InstanceBootstrap {
                 .arg("-jar")
                 .arg(installer_path)
                 .arg(option)
-                .current_dir(minecraft_dir);
+                .current_dir(minecraft_dir)
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped());
+            subprocess::background_process(&mut install_cmd);
 
             // Ejecutar instalador con la opción actual
             log::info!("Ejecutando instalador Forge con comando: {:?}", install_cmd);
 
-            match install_cmd.output() {
-                Ok(output) => {
-                    if output.status.success() {
-                        success = true;
-                        log::info!(
-                            "Instalación de Forge completada con éxito usando {}",
-                            option
-                        );
-                        break;
-                    } else {
-                        let error_msg = String::from_utf8_lossy(&output.stderr);
-                        log::warn!(
-                            "Fallo en instalación de Forge con {}: {}",
-                            option,
-                            error_msg
-                        );
-                        last_error = format!(
-                            "Error en instalación de Forge con {}: {}",
-                            option, error_msg
-                        );
-                    }
+            let mut progress = ForgeInstallerProgress::new(total_processors);
+            let on_progress = |fraction: f32, message: Message| {
+                self.report_forge_install_progress(
+                    instance,
+                    task_id,
+                    task_manager,
+                    fraction,
+                    message,
+                )
+            };
+
+            match Self::run_installer_process(install_cmd, timeout, &mut progress, on_progress) {
+                Ok(InstallerExit::Success) => {
+                    success = true;
+                    log::info!(
+                        "Instalación de Forge completada con éxito usando {}",
+                        option
+                    );
+                    break;
+                }
+                Ok(InstallerExit::Failed(error_msg)) => {
+                    log::warn!(
+                        "Fallo en instalación de Forge con {}: {}",
+                        option,
+                        error_msg
+                    );
+                    last_error = format!(
+                        "Error en instalación de Forge con {}: {}",
+                        option, error_msg
+                    );
+                }
+                Ok(InstallerExit::TimedOut) => {
+                    // Si se agota el tiempo no tiene sentido probar otra opción
+                    last_error = format!(
+                        "El instalador de Forge superó el tiempo máximo de {} segundos",
+                        timeout.as_secs()
+                    );
+                    log::error!("{}", last_error);
+                    break;
                 }
                 Err(e) => {
                     log::warn!(
@@ -1646,14 +2069,190 @@ impl InstanceBootstrap {
         }
     }
 
+    /// Reports Forge install progress (0.0 - 1.0) on the installer sub-task.
+    fn report_forge_install_progress(
+        &self,
+        instance: &MinecraftInstance,
+        task_id: Option<&str>,
+        task_manager: Option<&Arc<Mutex<TasksManager>>>,
+        fraction: f32,
+        message: Message,
+    ) {
+        self.emit_status(instance, names::INSTANCE_INSTALLING_FORGE, message.clone());
+        Self::report_step(
+            instance,
+            task_id,
+            task_manager,
+            TaskStatus::Running,
+            fraction * 100.0,
+            message,
+        );
+    }
+
+    /// Updates `task_id` (a task or one of its sub-tasks), if there is one.
+    fn report_step(
+        instance: &MinecraftInstance,
+        task_id: Option<&str>,
+        task_manager: Option<&Arc<Mutex<TasksManager>>>,
+        status: TaskStatus,
+        progress: f32,
+        message: Message,
+    ) {
+        if let (Some(task_id), Some(task_manager)) = (task_id, task_manager) {
+            if let Ok(tm) = task_manager.lock() {
+                tm.update_task(
+                    task_id,
+                    status,
+                    progress,
+                    message,
+                    Some(serde_json::json!({
+                        "instanceName": instance.instanceName.clone(),
+                        "instanceId": instance.instanceId.clone()
+                    })),
+                );
+            }
+        }
+    }
+
+    /// Spawns the Forge installer, feeding every output line to `progress`
+    /// and killing the process if it runs longer than `timeout`.
+    fn run_installer_process<F: Fn(f32, Message)>(
+        mut command: Command,
+        timeout: Duration,
+        progress: &mut ForgeInstallerProgress,
+        on_progress: F,
+    ) -> io::Result<InstallerExit> {
+        let mut child = command.spawn()?;
+        let (tx, rx) = mpsc::channel::<String>();
+
+        let mut readers = Vec::new();
+        if let Some(stdout) = child.stdout.take() {
+            let tx = tx.clone();
+            readers.push(thread::spawn(move || {
+                for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                    let _ = tx.send(line);
+                }
+            }));
+        }
+        // stderr se guarda aparte para devolverlo como error
+        let stderr_lines = Arc::new(Mutex::new(Vec::new()));
+        if let Some(stderr) = child.stderr.take() {
+            let stderr_lines = Arc::clone(&stderr_lines);
+            readers.push(thread::spawn(move || {
+                for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                    log::warn!("[Forge Installer] {}", line);
+                    if let Ok(mut lines) = stderr_lines.lock() {
+                        lines.push(line);
+                    }
+                }
+            }));
+        }
+        drop(tx);
+
+        let started = Instant::now();
+        let status = loop {
+            // El plazo se comprueba en cada vuelta: un instalador que no deja
+            // de escribir también debe poder agotarlo
+            match rx.recv_timeout(Duration::from_millis(200)) {
+                Ok(line) => {
+                    log::info!("[Forge Installer] {}", line);
+                    if let Some((fraction, message)) = progress.feed(&line) {
+                        on_progress(fraction, message);
+                    }
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+                // Ya no hay salida pendiente; esperamos sin bloquear la CPU
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    thread::sleep(Duration::from_millis(100))
+                }
+            }
+
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+
+            if started.elapsed() >= timeout {
+                log::error!(
+                    "[Forge Installer] Timeout after {}s, terminating installer",
+                    timeout.as_secs()
+                );
+                let _ = child.kill();
+                let _ = child.wait();
+                for reader in readers {
+                    let _ = reader.join();
+                }
+                return Ok(InstallerExit::TimedOut);
+            }
+        };
+
+        for reader in readers {
+            let _ = reader.join();
+        }
+        // Procesar las líneas que quedaron en el canal
+        for line in rx.try_iter() {
+            log::info!("[Forge Installer] {}", line);
+            if let Some((fraction, message)) = progress.feed(&line) {
+                on_progress(fraction, message);
+            }
+        }
+
+        if status.success() {
+            Ok(InstallerExit::Success)
+        } else {
+            let stderr = stderr_lines
+                .lock()
+                .map(|lines| lines.join("\n"))
+                .unwrap_or_default();
+            Ok(InstallerExit::Failed(if stderr.is_empty() {
+                format!("código de salida {:?}", status.code())
+            } else {
+                stderr
+            }))
+        }
+    }
+
+    /// Counts the client-side processors declared in the installer's
+    /// `install_profile.json` (0 for legacy installers).
+    fn count_installer_processors(installer_path: &Path) -> usize {
+        let file = match fs::File::open(installer_path) {
+            Ok(file) => file,
+            Err(_) => return 0,
+        };
+        let mut archive = match zip::ZipArchive::new(file) {
+            Ok(archive) => archive,
+            Err(_) => return 0,
+        };
+        let mut entry = match archive.by_name("install_profile.json") {
+            Ok(entry) => entry,
+            Err(_) => return 0,
+        };
+
+        let mut content = String::new();
+        if io::Read::read_to_string(&mut entry, &mut content).is_err() {
+            return 0;
+        }
+
+        serde_json::from_str::<Value>(&content)
+            .ok()
+            .and_then(|profile| profile.get("processors").and_then(Value::as_array).cloned())
+            .map(|processors| {
+                processors
+                    .iter()
+                    .filter(|p| {
+                        p.get("sides")
+                            .and_then(Value::as_array)
+                            .map(|sides| sides.iter().any(|s| s == "client"))
+                            .unwrap_or(true)
+                    })
+                    .count()
+            })
+            .unwrap_or(0)
+    }
+
     fn find_java_path(&self) -> Result<String, String> {
-        let config_lock = get_config_manager()
-            .lock()
-            .expect("Failed to lock config manager mutex");
+        let config_lock = read_config();
 
-        let config = config_lock
-            .as_ref()
-            .expect("Config manager failed to initialize");
+        let config = config_lock.as_ref().map_err(|e| e.clone())?;
 
         let java_path = config
             .get_java_dir()
@@ -1676,20 +2275,16 @@ impl InstanceBootstrap {
         version_id: &str,
         instance_name: &str,
     ) -> Result<(), String> {
-        // Leer archivo de perfiles actual
-        let profiles_content = match fs::read_to_string(profiles_path) {
-            Ok(content) => content,
-            Err(e) if e.kind() == io::ErrorKind::NotFound => {
-                // Si no existe, crear uno básico
-                "{ \"profiles\": {}, \"settings\": {}, \"version\": 3 }".to_string()
-            }
-            Err(e) => return Err(format!("Error al leer archivo de perfiles: {}", e)),
+        // Leer archivo de perfiles actual; uno dañado se repara antes
+        let game_dir = profiles_path.parent().unwrap_or(Path::new(""));
+        let mut profiles_json = if profiles_path.exists() {
+            launcher_profiles::load(game_dir)
+                .ok_or_else(|| "No se pudo reparar el archivo de perfiles".to_string())?
+        } else {
+            // Si no existe, crear uno básico
+            json!({ "profiles": {}, "settings": {}, "version": 3 })
         };
 
-        // Parsear JSON
-        let mut profiles_json: Value = serde_json::from_str(&profiles_content)
-            .map_err(|e| format!("Error al parsear archivo de perfiles: {}", e))?;
-
         // Crear o actualizar perfil de Forge
         let profile_id = format!("forge-{}", version_id);
         let profiles = profiles_json["profiles"]
@@ -1728,13 +2323,15 @@ impl InstanceBootstrap {
         task_id: Option<String>,
         task_manager: Option<Arc<Mutex<TasksManager>>>,
     ) -> Result<(), String> {
+        self.track_transfers(task_id.as_deref(), task_manager.as_ref());
+
         // Verificar integridad de la instancia Vanilla
         let instance = instance.ok_or_else(|| "Instance is not provided".to_string())?;
 
-        Self::emit_status(
+        self.emit_status(
             instance,
-            "instance-verifying-vanilla",
-            "Verificando integridad de la instancia Vanilla",
+            names::INSTANCE_VERIFYING_VANILLA,
+            Message::new(keys::VERIFY_VANILLA_START),
         );
 
         // Update task status if task_id exists
@@ -1744,7 +2341,7 @@ impl InstanceBootstrap {
                     task_id,
                     TaskStatus::Running,
                     5.0,
-                    "Verificando integridad de la instancia Vanilla",
+                    Message::new(keys::VERIFY_VANILLA_START),
                     Some(serde_json::json!({
                         "instanceName": instance.instanceName.clone(),
                         "instanceId": instance.instanceId.clone()
@@ -1758,21 +2355,14 @@ impl InstanceBootstrap {
 
         let instance_dir = Path::new(instance.instanceDirectory.as_deref().unwrap_or(""));
         let minecraft_dir = instance_dir.join("minecraft");
-        let versions_dir = minecraft_dir.join("versions");
-        let natives_dir = minecraft_dir
-            .join("natives")
-            .join(&instance.minecraftVersion);
-        let instance_version_dir = versions_dir.join(&instance.minecraftVersion);
+        let natives_dir = paths::extracted_natives_dir(&minecraft_dir, &instance.minecraftVersion);
         let instance_version_json_path =
-            instance_version_dir.join(format!("{}.json", instance.minecraftVersion));
-        let libraries_dir = minecraft_dir.join("libraries");
+            paths::version_file(&minecraft_dir, &instance.minecraftVersion, "json");
+        let libraries_dir = paths::libraries_dir(&minecraft_dir);
 
         // Get the version manifest
         let version_manifest_url = "https://launchermeta.mojang.com/mc/game/version_manifest.json";
-        let version_manifest: Value = self
-            .client
-            .get(version_manifest_url)
-            .send()
+        let version_manifest: Value = mirrors::get(&self.client, version_manifest_url)
             .map_err(|e| format!("Error al obtener el manifiesto de versiones: {}", e))?
             .json()
             .map_err(|e| format!("Error al parsear el manifiesto de versiones: {}", e))?;
@@ -1787,10 +2377,7 @@ impl InstanceBootstrap {
         let version_url = version_info["url"]
             .as_str()
             .ok_or_else(|| "No se encontró la URL de la versión".to_string())?;
-        let version_details: Value = self
-            .client
-            .get(version_url)
-            .send()
+        let version_details: Value = mirrors::get(&self.client, version_url)
             .map_err(|e| format!("Error al obtener los detalles de la versión: {}", e))?
             .json()
             .map_err(|e| format!("Error al parsear los detalles de la versión: {}", e))?;
@@ -1801,37 +2388,13 @@ impl InstanceBootstrap {
         })?;
         let total_libraries = libraries.len();
         let mut downloaded_libraries = 0;
+        // Las librerías ya presentes se comprueban después, en paralelo
+        let mut hash_jobs = Vec::new();
+        let mut hash_urls = Vec::new();
         for library in libraries {
             // Check if we should skip this library based on rules
-            if let Some(rules) = library.get("rules") {
-                let mut allowed = false;
-
-                for rule in rules.as_array().unwrap_or(&Vec::new()) {
-                    let action = rule["action"].as_str().unwrap_or("disallow");
-
-                    // Handle OS-specific rules
-                    if let Some(os) = rule.get("os") {
-                        let os_name = os["name"].as_str().unwrap_or("");
-                        let current_os = if cfg!(target_os = "windows") {
-                            "windows"
-                        } else if cfg!(target_os = "macos") {
-                            "osx"
-                        } else {
-                            "linux"
-                        };
-
-                        if os_name == current_os {
-                            allowed = action == "allow";
-                        }
-                    } else {
-                        // No OS specified, apply to all
-                        allowed = action == "allow";
-                    }
-                }
-
-                if !allowed {
-                    continue; // Skip this library
-                }
+            if !RuleEvaluator::applies(library, None) {
+                continue; // Skip this library
             }
 
             // Get library info
@@ -1848,7 +2411,7 @@ impl InstanceBootstrap {
                     .as_str()
                     .ok_or_else(|| "Library artifact URL not found".to_string())?;
 
-                let target_path = libraries_dir.join(path);
+                let target_path = paths::join_relative(&libraries_dir, path);
 
                 // Create parent directories if needed
                 if let Some(parent) = target_path.parent() {
@@ -1858,8 +2421,14 @@ impl InstanceBootstrap {
 
                 // Download if file doesn't exist
                 if !target_path.exists() {
-                    self.download_file(url, &target_path)
+                    self.download_file_cached(url, &target_path, artifact["sha1"].as_str())
                         .map_err(|e| format!("Error downloading library: {}", e))?;
+                } else if let Some(sha1) = artifact["sha1"].as_str() {
+                    hash_jobs.push(HashJob {
+                        path: target_path,
+                        expected: sha1.to_string(),
+                    });
+                    hash_urls.push(url.to_string());
                 }
             }
 
@@ -1868,13 +2437,11 @@ impl InstanceBootstrap {
             // Update progress every 5 libraries or on last library
             if downloaded_libraries % 5 == 0 || downloaded_libraries == total_libraries {
                 let progress = (downloaded_libraries as f32 / total_libraries as f32) * 100.0;
-                Self::emit_status(
+                self.emit_status(
                     instance,
-                    "instance-verifying-libraries",
-                    &format!(
-                        "Verificando librerías: {}/{} ({:.1}%)",
-                        downloaded_libraries, total_libraries, progress
-                    ),
+                    names::INSTANCE_VERIFYING_LIBRARIES,
+                    Message::new(keys::VERIFY_LIBRARIES_PROGRESS)
+                        .progress(downloaded_libraries, total_libraries),
                 );
                 // Update task status if task_id exists
                 if let (Some(task_id), Some(task_manager)) = (&task_id, &task_manager) {
@@ -1883,7 +2450,7 @@ impl InstanceBootstrap {
                             task_id,
                             TaskStatus::Running,
                             progress,
-                            "Verificando librerías",
+                            Message::new(keys::VERIFY_LIBRARIES),
                             Some(serde_json::json!({
                                 "instanceName": instance.instanceName.clone(),
                                 "instanceId": instance.instanceId.clone()
@@ -1894,19 +2461,88 @@ impl InstanceBootstrap {
             }
         }
 
-        // Extraer bibliotecas nativas
-        if let Err(e) =
-            self.extract_natives(&version_details, &libraries_dir, &natives_dir, instance)
+        // El jar del cliente y los assets ya descargados entran en la misma
+        // tanda de hashes que las librerías
+        let client = &version_details["downloads"]["client"];
+        let client_jar_path =
+            paths::version_file(&minecraft_dir, &instance.minecraftVersion, "jar");
+        if let (Some(sha1), Some(url)) = (client["sha1"].as_str(), client["url"].as_str()) {
+            if client_jar_path.exists() {
+                hash_jobs.push(HashJob {
+                    path: client_jar_path,
+                    expected: sha1.to_string(),
+                });
+                hash_urls.push(url.to_string());
+            }
+        }
+
+        let assets_dir = paths::assets_dir(&minecraft_dir);
+        let assets_index = version_details["assetIndex"]["id"]
+            .as_str()
+            .map(|id| assets_dir.join("indexes").join(format!("{}.json", id)))
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str::<Value>(&content).ok());
+        if let Some(objects) = assets_index
+            .as_ref()
+            .and_then(|index| index["objects"].as_object())
         {
+            for hash in objects.values().filter_map(|info| info["hash"].as_str()) {
+                let Some(prefix) = hash.get(0..2) else {
+                    continue;
+                };
+                let asset_file = assets_dir.join("objects").join(prefix).join(hash);
+                if asset_file.exists() {
+                    hash_jobs.push(HashJob {
+                        path: asset_file,
+                        expected: hash.to_string(),
+                    });
+                    hash_urls.push(format!(
+                        "https://resources.download.minecraft.net/{}/{}",
+                        prefix, hash
+                    ));
+                }
+            }
+        }
+
+        let cancel = task_id.as_deref().map(tasks_manager::cancellation_token);
+        let outcomes = parallel_hash::verify_files(&hash_jobs, cancel.as_ref(), |done, total| {
+            self.emit_status(
+                instance,
+                names::INSTANCE_VERIFYING_LIBRARIES,
+                Message::new(keys::VERIFY_LIBRARY_HASHES).progress(done, total),
+            );
+        })?;
+
+        for ((job, url), outcome) in hash_jobs.iter().zip(hash_urls).zip(outcomes) {
+            if outcome != HashOutcome::Mismatch {
+                continue;
+            }
+            log::warn!("Corrupted file, downloading again: {}", job.path.display());
+            fs::remove_file(&job.path)
+                .map_err(|e| format!("Error removing corrupted file: {}", e))?;
+            self.download_file_cached(&url, &job.path, Some(&job.expected))
+                .map_err(|e| format!("Error downloading file: {}", e))?;
+        }
+
+        // Extraer bibliotecas nativas
+        if let Err(e) = self.extract_natives(
+            &version_details,
+            &libraries_dir,
+            &natives_dir,
+            instance,
+            task_id.as_deref(),
+            // Las bibliotecas ya dejaron la tarea al 100%
+            None,
+        ) {
             log::error!("Error extrayendo bibliotecas nativas: {}", e);
             // No devolver error aquí, ya que es opcional
         }
 
         // Emit end event
-        Self::emit_status(
+        self.emit_status(
             instance,
-            "instance-verifying-complete",
-            "Verificación de la instancia Vanilla completada",
+            names::INSTANCE_VERIFYING_COMPLETE,
+            Message::new(keys::VERIFY_VANILLA_DONE),
         );
         // Update task status if task_id exists
         if let (Some(task_id), Some(task_manager)) = (&task_id, &task_manager) {
@@ -1915,7 +2551,7 @@ impl InstanceBootstrap {
                     task_id,
                     TaskStatus::Completed,
                     100.0,
-                    "Verificación de la instancia Vanilla completada",
+                    Message::new(keys::VERIFY_VANILLA_DONE),
                     Some(serde_json::json!({
                         "instanceName": instance.instanceName.clone(),
                         "instanceId": instance.instanceId.clone()
@@ -1927,6 +2563,44 @@ impl InstanceBootstrap {
         Ok(())
     }
 
+    /// Downloads what a dedicated server for `instance` needs into
+    /// `server_dir`: the Forge installer or the vanilla server jar. Returns
+    /// the downloaded file name.
+    pub fn download_server_files(
+        &mut self,
+        instance: &MinecraftInstance,
+        server_dir: &Path,
+    ) -> Result<String, String> {
+        let (url, file_name) = match &instance.forgeVersion {
+            Some(forge_version) => {
+                let url =
+                    self.get_forge_installer_url(&instance.minecraftVersion, forge_version)?;
+                let file_name = url
+                    .rsplit('/')
+                    .next()
+                    .unwrap_or("forge-installer.jar")
+                    .to_string();
+                (url, file_name)
+            }
+            None => {
+                let version_details = self.get_version_details(&instance.minecraftVersion)?;
+                let url = version_details["downloads"]["server"]["url"]
+                    .as_str()
+                    .ok_or_else(|| {
+                        format!(
+                            "Minecraft {} no tiene servidor dedicado",
+                            instance.minecraftVersion
+                        )
+                    })?
+                    .to_string();
+                (url, "server.jar".to_string())
+            }
+        };
+
+        self.download_file(&url, &server_dir.join(&file_name))?;
+        Ok(file_name)
+    }
+
     /// Validates modpack assets against the manifest
     pub fn validate_modpack_assets(
         &self,
@@ -1934,135 +2608,275 @@ impl InstanceBootstrap {
         task_id: Option<String>,
         task_manager: Option<Arc<Mutex<TasksManager>>>,
     ) -> Result<(), String> {
+        // Descripción del modpack para consultarla sin conexión; al actualizar
+        // la refresca `update_modpack_instance` con la versión nueva
+        instance_readme::ensure_cached(instance);
+        self.sync_modpack_files(instance, task_id, task_manager)
+            .map(|_| ())
+    }
+
+    /// Brings the files of the modpack in line with its manifest, restoring
+    /// the missing or modified ones. Returns how many could not be restored.
+    pub fn sync_modpack_files(
+        &self,
+        instance: &MinecraftInstance,
+        task_id: Option<String>,
+        task_manager: Option<Arc<Mutex<TasksManager>>>,
+    ) -> Result<usize, String> {
+        self.track_transfers(task_id.as_deref(), task_manager.as_ref());
+
         log::info!("Validating modpack assets for: {}", instance.instanceName);
 
         // Emit event to update frontend status
-        if let Ok(guard) = GLOBAL_APP_HANDLE.lock() {
-            if let Some(app_handle) = guard.as_ref() {
-                let _ = app_handle.emit("instance-downloading-modpack-assets", serde_json::json!({
-                    "id": instance.instanceId,
-                    "message": "Validando archivos del modpack..."
-                }));
-            }
-        }
+        self.events.send(LauncherEvent::instance(
+            names::INSTANCE_DOWNLOADING_MODPACK_ASSETS,
+            instance.instanceId.clone(),
+            None,
+            Message::new(keys::MODPACK_VALIDATING),
+            None,
+        ));
 
         // Get instance directory
         let instance_dir = Path::new(instance.instanceDirectory.as_deref().unwrap_or(""));
-        let modpack_manifest_path = instance_dir.join("modpack_manifest.json");
-
         // Check if modpack manifest exists
-        if !modpack_manifest_path.exists() {
+        let Some(manifest) = ModpackManifest::read(instance_dir)? else {
             log::info!("No modpack manifest found, skipping validation");
-            return Ok(());
+            return Ok(0);
+        };
+        // Los grupos opcionales no elegidos no se instalan
+        let selection =
+            optional_groups::reconcile(instance.optionalGroups.as_ref(), &manifest.optional_groups);
+        if instance.optionalGroups.as_ref() != Some(&selection) {
+            if let Err(e) = minecraft_instance::with_locked(instance_dir, |stored| {
+                stored.optionalGroups = Some(selection.clone());
+                Ok(())
+            }) {
+                log::warn!("Failed to save optional group selection: {}", e);
+            }
         }
-
-        // Read and parse the modpack manifest
-        let manifest_content = std::fs::read_to_string(&modpack_manifest_path)
-            .map_err(|e| format!("Failed to read modpack manifest: {}", e))?;
-
-        let manifest: serde_json::Value = serde_json::from_str(&manifest_content)
-            .map_err(|e| format!("Failed to parse modpack manifest: {}", e))?;
-
-        // Get the files array from manifest
-        let files = manifest
-            .get("files")
-            .and_then(|f| f.as_array())
-            .ok_or_else(|| "No files array found in modpack manifest".to_string())?;
+        optional_groups::remove_unselected_files(instance, &manifest, &selection);
+        // Lo que el jugador excluye no se instala
+        let rules = FileRules::for_manifest(instance, &manifest);
+        let files: Vec<&ManifestFile> = manifest
+            .files
+            .iter()
+            .filter(|entry| optional_groups::is_file_selected(entry, &selection))
+            .filter(|entry| !rules.is_excluded(&entry.path))
+            .collect();
 
         let total_files = files.len();
-        let mut processed_files = 0;
-        let mut missing_files = 0;
+        let minecraft_dir = instance_dir.join("minecraft");
+        let cancel = task_id.as_deref().map(tasks_manager::cancellation_token);
 
         log::info!("Validating {} modpack files...", total_files);
 
-        // Process each file in the manifest
+        // Archivos con malware conocido: no se instalan y los que ya estén
+        // en disco pasan a cuarentena
+        let blocklist = mod_blocklist::blocklist();
+        let mut blocked_files = Vec::new();
+
+        // Existencia y tamaño son baratos; sólo se hashean los que los pasan
+        let mut invalid = Vec::new();
+        let mut hash_jobs = Vec::new();
+        let mut hashed_entries = Vec::new();
+        let mut installed = Vec::new();
         for file_entry in files {
-            processed_files += 1;
+            let file_path = file_entry.path.as_str();
+            let expected_hash = file_entry.hash.as_deref();
+            let expected_size = file_entry.size;
+            let full_file_path = paths::join_relative(&minecraft_dir, file_path);
+
+            if let Some(blocked) = expected_hash.and_then(|hash| blocklist.lookup(hash)) {
+                log::error!("Blocked modpack file {} ({:?})", file_path, blocked.reason);
+                if full_file_path.exists() {
+                    if let Err(e) =
+                        mod_blocklist::quarantine_file(&instance.instanceId, &full_file_path)
+                    {
+                        log::error!("{}", e);
+                    }
+                }
+                blocked_files.push(file_path.to_string());
+                continue;
+            }
+            installed.push((file_path, full_file_path.clone()));
 
-            let file_path = file_entry
-                .get("path")
-                .and_then(|p| p.as_str())
-                .ok_or_else(|| "File path not found in manifest entry".to_string())?;
+            // Los archivos que el jugador conserva no se sobrescriben
+            if rules.is_preserved(file_path) && full_file_path.exists() {
+                continue;
+            }
 
-            let expected_hash = file_entry
-                .get("hash")
-                .and_then(|h| h.as_str());
+            let Ok(metadata) = fs::metadata(&full_file_path) else {
+                log::warn!("Missing modpack file: {}", file_path);
+                invalid.push(file_entry);
+                continue;
+            };
 
-            let expected_size = file_entry
-                .get("size")
-                .and_then(|s| s.as_u64());
+            if let Some(expected_size) = expected_size {
+                if metadata.len() != expected_size {
+                    log::warn!(
+                        "Size mismatch for file: {} (expected: {}, actual: {})",
+                        file_path,
+                        expected_size,
+                        metadata.len()
+                    );
+                    invalid.push(file_entry);
+                    continue;
+                }
+            }
 
-            let full_file_path = instance_dir.join("minecraft").join(file_path);
+            if let Some(expected_hash) = expected_hash {
+                hash_jobs.push(HashJob {
+                    path: full_file_path,
+                    expected: expected_hash.to_string(),
+                });
+                hashed_entries.push(file_entry);
+            }
+        }
 
-            // Update progress
-            let progress = (processed_files as f32 / total_files as f32) * 100.0;
-            log::info!(
-                "Validating modpack files: {}/{} ({:.1}%)",
-                processed_files,
-                total_files,
-                progress
+        // Un evento por archivo saturaría el webview: se agrupan por instancia
+        let progress_key = format!(
+            "{}:{}",
+            names::INSTANCE_DOWNLOADING_MODPACK_ASSETS,
+            instance.instanceId
+        );
+        let outcomes = parallel_hash::verify_files(&hash_jobs, cancel.as_ref(), |done, total| {
+            let progress = (done as f32 / total as f32) * 100.0;
+            self.events.send_throttled(
+                &progress_key,
+                LauncherEvent::instance(
+                    names::INSTANCE_DOWNLOADING_MODPACK_ASSETS,
+                    instance.instanceId.clone(),
+                    None,
+                    Message::new(keys::MODPACK_VALIDATING_PROGRESS).progress(done, total),
+                    None,
+                ),
             );
-
-            // Emit progress update
-            if let Ok(guard) = GLOBAL_APP_HANDLE.lock() {
-                if let Some(app_handle) = guard.as_ref() {
-                    let _ = app_handle.emit("instance-downloading-modpack-assets", serde_json::json!({
-                        "id": instance.instanceId,
-                        "message": format!("Validando archivos del modpack: {}/{}", processed_files, total_files)
-                    }));
+            if let (Some(task_id), Some(task_manager)) = (&task_id, &task_manager) {
+                if let Ok(tm) = task_manager.lock() {
+                    tm.update_task(
+                        task_id,
+                        TaskStatus::Running,
+                        progress,
+                        Message::new(keys::MODPACK_VALIDATING),
+                        Some(serde_json::json!({
+                            "instanceName": instance.instanceName.clone(),
+                            "instanceId": instance.instanceId.clone()
+                        })),
+                    );
                 }
             }
+        })?;
 
-            // Check if file exists
-            if !full_file_path.exists() {
-                log::warn!("Missing modpack file: {}", file_path);
-                missing_files += 1;
-                // TODO: Download missing file
-                continue;
+        for (file_entry, outcome) in hashed_entries.into_iter().zip(outcomes) {
+            match outcome {
+                HashOutcome::Valid => {}
+                HashOutcome::Unsupported => {
+                    log::warn!("Unsupported hash for modpack file: {}", file_entry.path)
+                }
+                HashOutcome::Missing | HashOutcome::Mismatch => {
+                    log::warn!("Hash mismatch for file: {}", file_entry.path);
+                    invalid.push(file_entry);
+                }
             }
+        }
 
-            // Validate file size if provided
-            if let Some(expected_size) = expected_size {
-                if let Ok(metadata) = std::fs::metadata(&full_file_path) {
-                    if metadata.len() != expected_size {
-                        log::warn!("Size mismatch for file: {} (expected: {}, actual: {})", 
-                                  file_path, expected_size, metadata.len());
-                        missing_files += 1;
-                        // TODO: Re-download file with size mismatch
-                        continue;
+        // Recuperar los archivos que faltan o no coinciden
+        if let Some(tracker) = self.transfer_tracker() {
+            let bytes = invalid.iter().map(|entry| entry.size).sum::<Option<u64>>();
+            tracker.expect(invalid.len(), bytes);
+        }
+        let missing_files = AtomicUsize::new(0);
+        Self::with_download_pool(|| {
+            invalid.par_iter().try_for_each(|file_entry| {
+                if cancel.as_ref().is_some_and(|token| token.is_cancelled()) {
+                    return Err("Operación cancelada".to_string());
+                }
+
+                let file_path = file_entry.path.as_str();
+                let full_file_path = paths::join_relative(&minecraft_dir, file_path);
+                let expected_hash = file_entry.hash.as_deref();
+                let url = file_entry.url.as_deref();
+
+                if full_file_path.exists() {
+                    let _ = fs::remove_file(&full_file_path);
+                }
+                let restored = match (expected_hash, url) {
+                    (Some(hash), _) if download_cache::restore(hash, &full_file_path) => {
+                        self.skip_transfer(&full_file_path);
+                        true
                     }
+                    (hash, Some(url)) => self
+                        .download_file_cached(url, &full_file_path, hash)
+                        .map_err(|e| log::warn!("Failed to download {}: {}", file_path, e))
+                        .is_ok(),
+                    _ => false,
+                };
+
+                if !restored {
+                    missing_files.fetch_add(1, Ordering::Relaxed);
                 }
-            }
+                Ok(())
+            })
+        })?;
+        let missing_files = missing_files.into_inner();
+        if let Some(tracker) = self.transfer_tracker() {
+            tracker.flush();
+        }
 
-            // Validate file hash if provided
-            if let Some(expected_hash) = expected_hash {
-                // TODO: Implement hash validation
-                // For now, we'll assume hash validation passes
+        // El hash declarado lo pone quien publica el manifiesto: la lista se
+        // compara con el contenido real de cada archivo instalado
+        if cancel.as_ref().is_some_and(|token| token.is_cancelled()) {
+            return Err("Operación cancelada".to_string());
+        }
+        installed.retain(|(_, full_file_path)| full_file_path.exists());
+        let installed_paths: Vec<PathBuf> =
+            installed.iter().map(|(_, path)| path.clone()).collect();
+        let matches = blocklist.check_files(&installed_paths);
+        for ((file_path, full_file_path), blocked) in installed.into_iter().zip(matches) {
+            let Some(blocked) = blocked else {
+                continue;
+            };
+            log::error!("Blocked modpack file {} ({:?})", file_path, blocked.reason);
+            if let Err(e) = mod_blocklist::quarantine_file(&instance.instanceId, &full_file_path) {
+                log::error!("{}", e);
             }
+            blocked_files.push(file_path.to_string());
         }
 
         if missing_files > 0 {
             log::warn!("Found {} missing or invalid modpack files", missing_files);
-            // TODO: Download missing/invalid files
         }
 
         // Emit completion event
-        if let Ok(guard) = GLOBAL_APP_HANDLE.lock() {
-            if let Some(app_handle) = guard.as_ref() {
-                let _ = app_handle.emit("instance-finish-assets-download", serde_json::json!({
-                    "id": instance.instanceId,
-                    "message": "Validación de archivos del modpack completada"
-                }));
-            }
-        }
+        self.events.send_final(
+            &progress_key,
+            LauncherEvent::instance(
+                names::INSTANCE_FINISH_ASSETS_DOWNLOAD,
+                instance.instanceId.clone(),
+                None,
+                Message::new(keys::MODPACK_VALIDATED),
+                None,
+            ),
+        );
 
         log::info!("Modpack asset validation completed for: {}", instance.instanceName);
-        Ok(())
+
+        if !blocked_files.is_empty() {
+            return Err(format!(
+                "Se bloquearon {} archivo(s) del modpack por contener malware conocido: {}",
+                blocked_files.len(),
+                blocked_files.join(", ")
+            ));
+        }
+        Ok(missing_files)
     }
 }
 
 #[tauri::command]
-pub fn check_vanilla_integrity(instance_id: String) -> Result<(), String> {
+pub fn check_vanilla_integrity(
+    events: State<'_, EventEmitter>,
+    instance_id: String,
+) -> Result<(), String> {
     // Obtener la instancia de Minecraft
     let instance = get_instance_by_id(instance_id)
         .map_err(|e| format!("Error al obtener la instancia: {}", e))?;
@@ -2071,7 +2885,7 @@ pub fn check_vanilla_integrity(instance_id: String) -> Result<(), String> {
         return Err("No se encontró la instancia".to_string());
     }
 
-    let bootstrapper = InstanceBootstrap::new();
+    let bootstrapper = InstanceBootstrap::new(events.inner().clone());
     // Verificar que la instancia sea válida
 
     // Verificar la integridad de la instancia
@@ -2083,7 +2897,10 @@ pub fn check_vanilla_integrity(instance_id: String) -> Result<(), String> {
 }
 
 #[tauri::command]
-pub fn validate_modpack_assets(instance_id: String) -> Result<(), String> {
+pub fn validate_modpack_assets(
+    events: State<'_, EventEmitter>,
+    instance_id: String,
+) -> Result<(), String> {
     // Get the instance
     let instance = get_instance_by_id(instance_id)
         .map_err(|e| format!("Error al obtener la instancia: {}", e))?;
@@ -2092,7 +2909,7 @@ pub fn validate_modpack_assets(instance_id: String) -> Result<(), String> {
         return Err("No se encontró la instancia".to_string());
     }
 
-    let mut bootstrapper = InstanceBootstrap::new();
+    let mut bootstrapper = InstanceBootstrap::new(events.inner().clone());
     
     // Validate modpack assets
     bootstrapper
@@ -0,0 +1,221 @@
+//! Disk usage of an instance by category, for the storage screen.
+
+use crate::core::instance_manager::get_instance_by_id;
+use crate::core::minecraft::paths;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+const CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Carpetas de `minecraft/` con categoría propia; el resto cuenta como `other`
+const CATEGORIES: &[(&str, &[&str])] = &[
+    ("mods", &["mods"]),
+    ("saves", &["saves"]),
+    ("resourcepacks", &["resourcepacks", "texturepacks"]),
+    ("shaderpacks", &["shaderpacks"]),
+    ("screenshots", &["screenshots"]),
+    ("logs", &["logs", "crash-reports"]),
+    ("config", &["config"]),
+    ("libraries", &["libraries"]),
+    ("assets", &["assets"]),
+    ("versions", &["versions"]),
+];
+
+struct CachedDir {
+    modified: Option<SystemTime>,
+    scanned_at: Instant,
+    file_bytes: u64,
+    file_count: u64,
+    subdirs: Vec<PathBuf>,
+}
+
+static CACHE: Lazy<Mutex<HashMap<PathBuf, CachedDir>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskUsageCategory {
+    pub category: &'static str,
+    pub bytes: u64,
+    pub files: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceDiskUsage {
+    pub instance_id: String,
+    pub total_bytes: u64,
+    pub total_files: u64,
+    /// De mayor a menor; las categorías vacías no aparecen
+    pub categories: Vec<DiskUsageCategory>,
+    /// Carpetas que hubo que leer de nuevo en esta llamada
+    pub rescanned_dirs: usize,
+}
+
+#[derive(Default)]
+struct Totals {
+    bytes: u64,
+    files: u64,
+}
+
+struct Scanner<'a> {
+    cache: &'a mut HashMap<PathBuf, CachedDir>,
+    rescanned: usize,
+}
+
+impl Scanner<'_> {
+    /// Bytes and files under `dir`, reading only the folders that changed.
+    fn measure(&mut self, dir: &Path) -> Totals {
+        let modified = fs::symlink_metadata(dir)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        let fresh = self.cache.get(dir).is_some_and(|cached| {
+            cached.modified.is_some()
+                && cached.modified == modified
+                && cached.scanned_at.elapsed() < CACHE_TTL
+        });
+        if !fresh {
+            let scanned = scan_dir(dir, modified);
+            self.cache.insert(dir.to_path_buf(), scanned);
+            self.rescanned += 1;
+        }
+
+        let (mut totals, subdirs) = match self.cache.get(dir) {
+            Some(cached) => (
+                Totals {
+                    bytes: cached.file_bytes,
+                    files: cached.file_count,
+                },
+                cached.subdirs.clone(),
+            ),
+            None => return Totals::default(),
+        };
+        for subdir in subdirs {
+            let sub = self.measure(&subdir);
+            totals.bytes += sub.bytes;
+            totals.files += sub.files;
+        }
+        totals
+    }
+}
+
+fn scan_dir(dir: &Path, modified: Option<SystemTime>) -> CachedDir {
+    let mut cached = CachedDir {
+        modified,
+        scanned_at: Instant::now(),
+        file_bytes: 0,
+        file_count: 0,
+        subdirs: Vec::new(),
+    };
+    let Ok(entries) = fs::read_dir(dir) else {
+        return cached;
+    };
+
+    for entry in entries.flatten() {
+        // `DirEntry::metadata` no sigue enlaces simbólicos
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.is_dir() {
+            cached.subdirs.push(entry.path());
+        } else if metadata.is_file() {
+            cached.file_bytes += metadata.len();
+            cached.file_count += 1;
+        }
+    }
+    cached
+}
+
+fn category_of(name: &str) -> &'static str {
+    CATEGORIES
+        .iter()
+        .find(|(_, folders)| {
+            folders
+                .iter()
+                .any(|folder| folder.eq_ignore_ascii_case(name))
+        })
+        .map(|(category, _)| *category)
+        .unwrap_or("other")
+}
+
+fn disk_usage(instance_id: &str) -> Result<InstanceDiskUsage, String> {
+    let instance = get_instance_by_id(instance_id.to_string())?
+        .ok_or_else(|| format!("Instance with ID {} not found", instance_id))?;
+    let instance_dir = instance
+        .instanceDirectory
+        .as_deref()
+        .map(paths::native_path)
+        .ok_or_else(|| "La instancia no tiene directorio".to_string())?;
+    let game_dir = paths::native_path(&instance.minecraftPath);
+
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    let mut scanner = Scanner {
+        cache: &mut cache,
+        rescanned: 0,
+    };
+    let mut categories: HashMap<&'static str, DiskUsageCategory> = HashMap::new();
+    let mut add = |category: &'static str, totals: Totals| {
+        let entry = categories.entry(category).or_insert(DiskUsageCategory {
+            category,
+            ..Default::default()
+        });
+        entry.bytes += totals.bytes;
+        entry.files += totals.files;
+    };
+
+    // Archivos sueltos de la instancia (instance.json, iconos...) y de `minecraft/`
+    for dir in [&instance_dir, &game_dir] {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            let path = entry.path();
+            if metadata.is_file() {
+                add(
+                    "other",
+                    Totals {
+                        bytes: metadata.len(),
+                        files: 1,
+                    },
+                );
+            } else if metadata.is_dir() && path != game_dir {
+                let name = entry.file_name().to_string_lossy().to_string();
+                let category = if dir == &game_dir {
+                    category_of(&name)
+                } else {
+                    "other"
+                };
+                add(category, scanner.measure(&path));
+            }
+        }
+    }
+
+    let mut categories: Vec<DiskUsageCategory> = categories
+        .into_values()
+        .filter(|category| category.bytes > 0 || category.files > 0)
+        .collect();
+    categories.sort_by(|a, b| b.bytes.cmp(&a.bytes));
+
+    Ok(InstanceDiskUsage {
+        instance_id: instance_id.to_string(),
+        total_bytes: categories.iter().map(|category| category.bytes).sum(),
+        total_files: categories.iter().map(|category| category.files).sum(),
+        categories,
+        rescanned_dirs: scanner.rescanned,
+    })
+}
+
+/// Total size of the instance and its breakdown by category.
+#[tauri::command]
+pub async fn get_instance_disk_usage(instance_id: String) -> Result<InstanceDiskUsage, String> {
+    tokio::task::spawn_blocking(move || disk_usage(&instance_id))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,315 @@
+//! Custom icon and banner images, stored as PNG in `<instance>/images`.
+
+use crate::core::events::EventEmitter;
+use crate::core::http_client;
+use crate::core::instance_manager::{get_instance_by_id, update_instance_by_id};
+use crate::core::minecraft_instance::MinecraftInstance;
+use image::imageops::FilterType;
+use once_cell::sync::Lazy;
+use sha1::{Digest, Sha1};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::Manager;
+
+const IMAGES_DIR: &str = "images";
+/// Imágenes más grandes que esto se rechazan antes de decodificarlas
+const MAX_SOURCE_BYTES: usize = 20 * 1024 * 1024;
+
+/// Imágenes remotas que ya se están guardando (o fallaron) en esta sesión
+static CACHING: Lazy<Mutex<HashSet<PathBuf>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImageKind {
+    Icon,
+    Banner,
+}
+
+impl ImageKind {
+    fn parse(kind: &str) -> Result<Self, String> {
+        match kind {
+            "icon" => Ok(ImageKind::Icon),
+            "banner" => Ok(ImageKind::Banner),
+            other => Err(format!("Tipo de imagen desconocido: {}", other)),
+        }
+    }
+
+    fn file_prefix(self) -> &'static str {
+        match self {
+            ImageKind::Icon => "icon",
+            ImageKind::Banner => "banner",
+        }
+    }
+
+    /// Tamaño máximo (ancho, alto); se conserva la proporción
+    fn max_size(self) -> (u32, u32) {
+        match self {
+            ImageKind::Icon => (256, 256),
+            ImageKind::Banner => (1280, 720),
+        }
+    }
+}
+
+/// Same encoding as the frontend's `convertFileSrc` (`encodeURIComponent`).
+fn encode_path_component(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z'
+            | b'a'..=b'z'
+            | b'0'..=b'9'
+            | b'-'
+            | b'_'
+            | b'.'
+            | b'!'
+            | b'~'
+            | b'*'
+            | b'\''
+            | b'('
+            | b')' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// Asset protocol URL for a local file, allowing it in the asset scope.
+pub fn asset_url(path: &Path) -> String {
+    if let Some(emitter) = EventEmitter::global() {
+        if let Err(e) = emitter.app_handle().asset_protocol_scope().allow_file(path) {
+            log::warn!("[Images] Could not allow {}: {}", path.display(), e);
+        }
+    }
+
+    let encoded = encode_path_component(&path.to_string_lossy());
+    if cfg!(windows) {
+        format!("http://asset.localhost/{}", encoded)
+    } else {
+        format!("asset://localhost/{}", encoded)
+    }
+}
+
+fn image_path(instance: &MinecraftInstance, file: &str) -> Option<PathBuf> {
+    let directory = instance.instanceDirectory.as_ref()?;
+    Some(Path::new(directory).join(file))
+}
+
+fn is_remote(source: &str) -> bool {
+    source.starts_with("http://") || source.starts_with("https://")
+}
+
+/// Fills `iconAssetUrl`/`bannerAssetUrl` from the stored custom images or,
+/// without one, from the cached copy of a remote `iconUrl`/`bannerUrl`.
+pub fn resolve_image_urls(instance: &mut MinecraftInstance) {
+    let resolve = |file: Option<&str>| {
+        file.and_then(|file| image_path(instance, file))
+            .filter(|path| path.exists())
+            .map(|path| asset_url(&path))
+    };
+
+    let icon = resolve(instance.iconFile.as_deref())
+        .or_else(|| cached_remote_image(instance, ImageKind::Icon, instance.iconUrl.as_deref()));
+    let banner = resolve(instance.bannerFile.as_deref()).or_else(|| {
+        cached_remote_image(instance, ImageKind::Banner, instance.bannerUrl.as_deref())
+    });
+    instance.iconAssetUrl = icon;
+    instance.bannerAssetUrl = banner;
+}
+
+/// Asset URL of the cached copy of a remote image. If it is not cached yet
+/// it is downloaded in the background and `None` is returned, so the
+/// frontend keeps using the remote URL until the next listing.
+fn cached_remote_image(
+    instance: &MinecraftInstance,
+    kind: ImageKind,
+    url: Option<&str>,
+) -> Option<String> {
+    let url = url.filter(|url| is_remote(url))?;
+    // El nombre sale de la URL: si el modpack cambia la imagen, se baja otra
+    let digest = format!("{:x}", Sha1::digest(url.as_bytes()));
+    let file = format!(
+        "{}/{}-remote-{}.png",
+        IMAGES_DIR,
+        kind.file_prefix(),
+        &digest[..16]
+    );
+    let path = image_path(instance, &file)?;
+    if path.exists() {
+        return Some(asset_url(&path));
+    }
+
+    if CACHING
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(path.clone())
+    {
+        let url = url.to_string();
+        tauri::async_runtime::spawn(async move {
+            let result = match download_image(&url).await {
+                Ok(bytes) => tokio::task::spawn_blocking(move || save_png(&path, kind, &bytes))
+                    .await
+                    .map_err(|e| format!("Task join error: {}", e))
+                    .and_then(|saved| saved),
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                log::warn!("[Images] Could not cache {}: {}", url, e);
+            }
+        });
+    }
+    None
+}
+
+/// Downloads an image, giving up as soon as it is known to be larger than
+/// `MAX_SOURCE_BYTES` instead of after receiving all of it.
+async fn download_image(url: &str) -> Result<Vec<u8>, String> {
+    let mut response = http_client::async_client()
+        .get(url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("Error al descargar la imagen: {}", e))?;
+
+    if response
+        .content_length()
+        .is_some_and(|length| length > MAX_SOURCE_BYTES as u64)
+    {
+        return Err("La imagen es demasiado grande".to_string());
+    }
+
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Error al descargar la imagen: {}", e))?
+    {
+        // Content-Length puede faltar o mentir
+        if bytes.len() + chunk.len() > MAX_SOURCE_BYTES {
+            return Err("La imagen es demasiado grande".to_string());
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes)
+}
+
+async fn read_source(source: &str) -> Result<Vec<u8>, String> {
+    if is_remote(source) {
+        return download_image(source).await;
+    }
+
+    let size = fs::metadata(source)
+        .map_err(|e| format!("No se pudo leer la imagen: {}", e))?
+        .len();
+    if size > MAX_SOURCE_BYTES as u64 {
+        return Err("La imagen es demasiado grande".to_string());
+    }
+    fs::read(source).map_err(|e| format!("No se pudo leer la imagen: {}", e))
+}
+
+/// Decodes the image, shrinks it to the size of `kind` and writes it as PNG
+/// at `path`.
+fn save_png(path: &Path, kind: ImageKind, bytes: &[u8]) -> Result<(), String> {
+    let image = image::load_from_memory(bytes)
+        .map_err(|e| format!("Formato de imagen no válido: {}", e))?;
+
+    let (max_width, max_height) = kind.max_size();
+    let image = if image.width() > max_width || image.height() > max_height {
+        image.resize(max_width, max_height, FilterType::Lanczos3)
+    } else {
+        image
+    };
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Error al crear el directorio de imágenes: {}", e))?;
+    }
+    image
+        .save_with_format(path, image::ImageFormat::Png)
+        .map_err(|e| format!("Error al guardar la imagen: {}", e))
+}
+
+/// Stores the image in the instance's images directory. Returns the file
+/// name relative to the instance directory.
+fn store_image(instance_dir: &Path, kind: ImageKind, bytes: &[u8]) -> Result<String, String> {
+    // Un nombre nuevo en cada cambio evita que el webview muestre la
+    // versión anterior desde su caché
+    let file_name = format!(
+        "{}-{}.png",
+        kind.file_prefix(),
+        chrono::Utc::now().timestamp_millis()
+    );
+    save_png(&instance_dir.join(IMAGES_DIR).join(&file_name), kind, bytes)?;
+
+    Ok(format!("{}/{}", IMAGES_DIR, file_name))
+}
+
+fn remove_image_file(instance: &MinecraftInstance, file: Option<&str>) {
+    if let Some(path) = file.and_then(|file| image_path(instance, file)) {
+        if path.exists() {
+            if let Err(e) = fs::remove_file(&path) {
+                log::warn!("[Images] Could not remove {}: {}", path.display(), e);
+            }
+        }
+    }
+}
+
+fn load_instance(instance_id: String) -> Result<MinecraftInstance, String> {
+    get_instance_by_id(instance_id.clone())?
+        .ok_or_else(|| format!("Instance with ID {} not found", instance_id))
+}
+
+/// Sets the icon or banner (`kind`) from a local path or an http(s) URL.
+/// Returns the instance with its image URLs resolved.
+#[tauri::command]
+pub async fn set_instance_image(
+    instance_id: String,
+    kind: String,
+    source: String,
+) -> Result<MinecraftInstance, String> {
+    let kind = ImageKind::parse(&kind)?;
+    let instance = load_instance(instance_id.clone())?;
+    let instance_dir = PathBuf::from(
+        instance
+            .instanceDirectory
+            .clone()
+            .ok_or_else(|| "La instancia no tiene directorio".to_string())?,
+    );
+
+    let bytes = read_source(&source).await?;
+    let file = tokio::task::spawn_blocking(move || store_image(&instance_dir, kind, &bytes))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))??;
+
+    let mut instance = update_instance_by_id(&instance_id, |instance| {
+        let previous = match kind {
+            ImageKind::Icon => instance.iconFile.replace(file),
+            ImageKind::Banner => instance.bannerFile.replace(file),
+        };
+        remove_image_file(instance, previous.as_deref());
+        Ok(instance.clone())
+    })?;
+
+    resolve_image_urls(&mut instance);
+    Ok(instance)
+}
+
+/// Removes the custom icon or banner, going back to `iconUrl`/`bannerUrl`.
+#[tauri::command]
+pub fn clear_instance_image(
+    instance_id: String,
+    kind: String,
+) -> Result<MinecraftInstance, String> {
+    let kind = ImageKind::parse(&kind)?;
+    let mut instance = update_instance_by_id(&instance_id, |instance| {
+        let previous = match kind {
+            ImageKind::Icon => instance.iconFile.take(),
+            ImageKind::Banner => instance.bannerFile.take(),
+        };
+        remove_image_file(instance, previous.as_deref());
+        Ok(instance.clone())
+    })?;
+
+    resolve_image_urls(&mut instance);
+    Ok(instance)
+}
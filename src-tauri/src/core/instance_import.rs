@@ -0,0 +1,631 @@
+// src-tauri/src/core/instance_import.rs
+//! Importing instances from other launchers (MultiMC/Prism, the official
+//! launcher and GDLauncher) as Modpack Store instances.
+
+use crate::core::events::EventEmitter;
+use crate::core::i18n::{keys, Message};
+use crate::core::instance_locations;
+use crate::core::instance_manager::{resolve_instance_parent, run_instance_bootstrap};
+use crate::core::minecraft_instance::MinecraftInstance;
+use crate::core::tasks_manager::{self, TaskKind, TaskPriority, TaskStatus, TasksManager};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+/// Carpetas que no merece la pena copiar (se regeneran al lanzar)
+const SKIPPED_ENTRIES: &[&str] = &[
+    "logs",
+    "crash-reports",
+    "natives",
+    "webcache",
+    "webcache2",
+    ".fabric",
+    "launcher_log.txt",
+    "launcher_accounts.json",
+    "launcher_msa_credentials.json",
+    "launcher_msa_credentials.bin",
+];
+
+/// Carpetas raíz iguales para todos los perfiles: se enlazan en vez de copiarse
+const SHARED_ENTRIES: &[&str] = &["assets", "libraries"];
+
+/// Carpetas raíz que el bootstrap vuelve a generar para la versión de la instancia
+const REBUILT_ENTRIES: &[&str] = &["versions"];
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ExternalLauncher {
+    MultiMc,
+    Prism,
+    Official,
+    GdLauncher,
+}
+
+/// How the game directory is brought into Modpack Store.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportMode {
+    /// Copy the files; the original instance is left untouched.
+    Copy,
+    /// Symlink the original game directory (both launchers share the files).
+    Link,
+}
+
+/// An instance found in another launcher.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalInstance {
+    pub launcher: ExternalLauncher,
+    pub name: String,
+    /// Instance folder (MultiMC/Prism/GDLauncher) or `.minecraft` (official)
+    pub source_path: String,
+    pub game_dir: String,
+    pub minecraft_version: String,
+    pub forge_version: Option<String>,
+    pub memory: Option<u32>,
+    /// Loader we can't run (Fabric, Quilt, NeoForge...), if any
+    pub unsupported_loader: Option<String>,
+}
+
+/// Default instance roots for every supported launcher on this system.
+fn default_roots() -> Vec<(ExternalLauncher, PathBuf)> {
+    let mut roots = Vec::new();
+
+    if let Some(data_dir) = dirs::data_dir() {
+        roots.push((
+            ExternalLauncher::Prism,
+            data_dir.join("PrismLauncher").join("instances"),
+        ));
+        roots.push((
+            ExternalLauncher::Prism,
+            data_dir.join("PolyMC").join("instances"),
+        ));
+        roots.push((
+            ExternalLauncher::MultiMc,
+            data_dir.join("multimc").join("instances"),
+        ));
+        roots.push((
+            ExternalLauncher::GdLauncher,
+            data_dir.join("gdlauncher_next").join("instances"),
+        ));
+    }
+
+    let official = if cfg!(target_os = "windows") {
+        dirs::data_dir().map(|dir| dir.join(".minecraft"))
+    } else if cfg!(target_os = "macos") {
+        dirs::data_dir().map(|dir| dir.join("minecraft"))
+    } else {
+        dirs::home_dir().map(|dir| dir.join(".minecraft"))
+    };
+    if let Some(official) = official {
+        roots.push((ExternalLauncher::Official, official));
+    }
+
+    roots
+}
+
+/// Scans the default locations of other launchers.
+pub fn detect_instances() -> Vec<ExternalInstance> {
+    let mut instances = Vec::new();
+
+    for (launcher, root) in default_roots() {
+        if !root.is_dir() {
+            continue;
+        }
+
+        match launcher {
+            ExternalLauncher::Official => match read_official_profiles(&root) {
+                Ok(found) => instances.extend(found),
+                Err(e) => log::warn!("[Import] {}: {}", root.display(), e),
+            },
+            _ => {
+                let entries = match fs::read_dir(&root) {
+                    Ok(entries) => entries,
+                    Err(_) => continue,
+                };
+                for entry in entries.flatten() {
+                    let path = entry.path();
+                    if !path.is_dir() {
+                        continue;
+                    }
+                    match read_instance_folder(launcher, &path) {
+                        Ok(instance) => instances.push(instance),
+                        Err(e) => log::debug!("[Import] Skipping {}: {}", path.display(), e),
+                    }
+                }
+            }
+        }
+    }
+
+    log::info!("[Import] Found {} external instances", instances.len());
+    instances
+}
+
+/// Reads a user-selected folder: a MultiMC/Prism or GDLauncher instance, or
+/// an official `.minecraft` directory (one entry per profile).
+pub fn inspect_path(path: &Path) -> Result<Vec<ExternalInstance>, String> {
+    if path.join("launcher_profiles.json").exists() {
+        return read_official_profiles(path);
+    }
+
+    let launcher = if path.join("mmc-pack.json").exists() {
+        // MultiMC y Prism comparten formato
+        if path.to_string_lossy().to_lowercase().contains("multimc") {
+            ExternalLauncher::MultiMc
+        } else {
+            ExternalLauncher::Prism
+        }
+    } else if path.join("config.json").exists() {
+        ExternalLauncher::GdLauncher
+    } else {
+        return Err("No se reconoce el formato de la instancia".to_string());
+    };
+
+    read_instance_folder(launcher, path).map(|instance| vec![instance])
+}
+
+fn read_instance_folder(
+    launcher: ExternalLauncher,
+    path: &Path,
+) -> Result<ExternalInstance, String> {
+    match launcher {
+        ExternalLauncher::MultiMc | ExternalLauncher::Prism => read_mmc_instance(launcher, path),
+        ExternalLauncher::GdLauncher => read_gdlauncher_instance(path),
+        ExternalLauncher::Official => {
+            Err("El launcher oficial no usa carpetas de instancia".to_string())
+        }
+    }
+}
+
+/// MultiMC / Prism: `instance.cfg` (INI) + `mmc-pack.json`.
+fn read_mmc_instance(launcher: ExternalLauncher, path: &Path) -> Result<ExternalInstance, String> {
+    let pack: Value = read_json(&path.join("mmc-pack.json"))?;
+    let cfg = fs::read_to_string(path.join("instance.cfg"))
+        .map(|content| parse_ini(&content))
+        .unwrap_or_default();
+
+    let mut minecraft_version = String::new();
+    let mut forge_version = None;
+    let mut unsupported_loader = None;
+
+    for component in pack
+        .get("components")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let uid = component.get("uid").and_then(Value::as_str).unwrap_or("");
+        let version = component
+            .get("version")
+            .and_then(Value::as_str)
+            .unwrap_or("")
+            .to_string();
+
+        match uid {
+            "net.minecraft" => minecraft_version = version,
+            "net.minecraftforge" => forge_version = Some(version),
+            "net.fabricmc.fabric-loader" => unsupported_loader = Some("Fabric".to_string()),
+            "org.quiltmc.quilt-loader" => unsupported_loader = Some("Quilt".to_string()),
+            "net.neoforged" => unsupported_loader = Some("NeoForge".to_string()),
+            _ => {}
+        }
+    }
+
+    let game_dir = [".minecraft", "minecraft"]
+        .iter()
+        .map(|dir| path.join(dir))
+        .find(|dir| dir.is_dir())
+        .unwrap_or_else(|| path.join(".minecraft"));
+
+    let memory = match cfg.get("OverrideMemory").map(String::as_str) {
+        Some("true") => cfg.get("MaxMemAlloc").and_then(|v| v.parse().ok()),
+        _ => None,
+    };
+
+    Ok(ExternalInstance {
+        launcher,
+        name: cfg
+            .get("name")
+            .cloned()
+            .unwrap_or_else(|| folder_name(path)),
+        source_path: path.to_string_lossy().to_string(),
+        game_dir: game_dir.to_string_lossy().to_string(),
+        minecraft_version,
+        forge_version,
+        memory,
+        unsupported_loader,
+    })
+}
+
+/// GDLauncher (legacy "next"): `config.json` with a `loader` object.
+fn read_gdlauncher_instance(path: &Path) -> Result<ExternalInstance, String> {
+    let config: Value = read_json(&path.join("config.json"))?;
+    let loader = config
+        .get("loader")
+        .ok_or_else(|| "config.json sin información del loader".to_string())?;
+
+    let minecraft_version = loader
+        .get("mcVersion")
+        .and_then(Value::as_str)
+        .unwrap_or("")
+        .to_string();
+    let loader_type = loader
+        .get("loaderType")
+        .and_then(Value::as_str)
+        .unwrap_or("vanilla");
+    let loader_version = loader.get("loaderVersion").and_then(Value::as_str);
+
+    let (forge_version, unsupported_loader) = match loader_type {
+        "vanilla" => (None, None),
+        "forge" => (
+            loader_version.map(|v| strip_minecraft_prefix(v, &minecraft_version)),
+            None,
+        ),
+        other => (None, Some(other.to_string())),
+    };
+
+    Ok(ExternalInstance {
+        launcher: ExternalLauncher::GdLauncher,
+        name: folder_name(path),
+        source_path: path.to_string_lossy().to_string(),
+        game_dir: path.to_string_lossy().to_string(),
+        minecraft_version,
+        forge_version,
+        memory: None,
+        unsupported_loader,
+    })
+}
+
+/// Official launcher: one entry per profile in `launcher_profiles.json`.
+fn read_official_profiles(minecraft_dir: &Path) -> Result<Vec<ExternalInstance>, String> {
+    let profiles_json: Value = read_json(&minecraft_dir.join("launcher_profiles.json"))?;
+    let profiles = profiles_json
+        .get("profiles")
+        .and_then(Value::as_object)
+        .ok_or_else(|| "launcher_profiles.json sin perfiles".to_string())?;
+
+    let mut instances = Vec::new();
+    for (id, profile) in profiles {
+        // "latest-release"/"latest-snapshot" no fijan versión
+        let version_id = match profile.get("lastVersionId").and_then(Value::as_str) {
+            Some(version_id) if !version_id.starts_with("latest-") => version_id,
+            _ => continue,
+        };
+
+        let (minecraft_version, forge_version, unsupported_loader) =
+            parse_official_version_id(version_id);
+
+        let game_dir = profile
+            .get("gameDir")
+            .and_then(Value::as_str)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| minecraft_dir.to_path_buf());
+
+        let memory = profile
+            .get("javaArgs")
+            .and_then(Value::as_str)
+            .and_then(parse_xmx_mb);
+
+        let name = profile
+            .get("name")
+            .and_then(Value::as_str)
+            .filter(|name| !name.is_empty())
+            .map(str::to_string)
+            .unwrap_or_else(|| id.clone());
+
+        instances.push(ExternalInstance {
+            launcher: ExternalLauncher::Official,
+            name,
+            source_path: minecraft_dir.to_string_lossy().to_string(),
+            game_dir: game_dir.to_string_lossy().to_string(),
+            minecraft_version,
+            forge_version,
+            memory,
+            unsupported_loader,
+        });
+    }
+
+    Ok(instances)
+}
+
+/// Splits official version ids into (minecraft, forge, unsupported loader):
+/// `1.20.1-forge-47.2.0`, `1.12.2-forge1.12.2-14.23.5.2847`,
+/// `1.7.10-Forge10.13.4.1614-1.7.10`, `fabric-loader-0.15.0-1.20.1`...
+fn parse_official_version_id(version_id: &str) -> (String, Option<String>, Option<String>) {
+    let lower = version_id.to_lowercase();
+    for (marker, loader) in [
+        ("neoforge", "NeoForge"),
+        ("fabric", "Fabric"),
+        ("quilt", "Quilt"),
+        ("optifine", "OptiFine"),
+    ] {
+        if lower.contains(marker) {
+            return (String::new(), None, Some(loader.to_string()));
+        }
+    }
+
+    match lower.find("-forge") {
+        Some(index) => {
+            let minecraft_version = version_id[..index].to_string();
+            let rest = version_id[index + "-forge".len()..].trim_start_matches('-');
+            let rest = strip_minecraft_prefix(rest, &minecraft_version);
+            // Formato legacy: la versión de Minecraft también va al final
+            let forge_version = rest
+                .strip_suffix(&format!("-{}", minecraft_version))
+                .unwrap_or(rest.as_str())
+                .to_string();
+            (minecraft_version, Some(forge_version), None)
+        }
+        None => (version_id.to_string(), None, None),
+    }
+}
+
+fn strip_minecraft_prefix(version: &str, minecraft_version: &str) -> String {
+    version
+        .strip_prefix(&format!("{}-", minecraft_version))
+        .unwrap_or(version)
+        .to_string()
+}
+
+/// `-Xmx4G` / `-Xmx4096M` -> MB
+fn parse_xmx_mb(java_args: &str) -> Option<u32> {
+    let value = java_args
+        .split_whitespace()
+        .find_map(|arg| arg.strip_prefix("-Xmx"))?;
+    let (number, unit) = value.split_at(value.find(|c: char| !c.is_ascii_digit())?);
+    let number: u32 = number.parse().ok()?;
+
+    match unit.to_ascii_lowercase().as_str() {
+        "g" => Some(number * 1024),
+        "m" => Some(number),
+        _ => None,
+    }
+}
+
+fn parse_ini(content: &str) -> HashMap<String, String> {
+    content
+        .lines()
+        .filter(|line| !line.starts_with('[') && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect()
+}
+
+fn read_json(path: &Path) -> Result<Value, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("No se pudo leer {}: {}", path.display(), e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("JSON inválido en {}: {}", path.display(), e))
+}
+
+fn folder_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "Instancia importada".to_string())
+}
+
+/// Copies the profile content of `source` into `target`, skipping logs,
+/// natives and launcher secrets. Assets and libraries are hard linked when
+/// the filesystem allows it, and versions are left for the bootstrap.
+/// Returns the number of files copied or linked.
+fn copy_game_dir(source: &Path, target: &Path) -> io::Result<usize> {
+    fs::create_dir_all(target)?;
+    let mut copied = 0;
+
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let name_str = name.to_string_lossy();
+        if SKIPPED_ENTRIES.contains(&name_str.as_ref())
+            || REBUILT_ENTRIES.contains(&name_str.as_ref())
+        {
+            continue;
+        }
+        let shared = SHARED_ENTRIES.contains(&name_str.as_ref());
+        copied += copy_tree(&entry.path(), &target.join(&name), shared)?;
+    }
+
+    Ok(copied)
+}
+
+fn copy_tree(source: &Path, target: &Path, shared: bool) -> io::Result<usize> {
+    if !source.is_dir() {
+        // Otro volumen no admite enlaces duros: entonces se copia
+        if !shared || fs::hard_link(source, target).is_err() {
+            fs::copy(source, target)?;
+        }
+        return Ok(1);
+    }
+
+    fs::create_dir_all(target)?;
+    let mut copied = 0;
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        if SKIPPED_ENTRIES.contains(&name.to_string_lossy().as_ref()) {
+            continue;
+        }
+        copied += copy_tree(&entry.path(), &target.join(&name), shared)?;
+    }
+
+    Ok(copied)
+}
+
+fn link_game_dir(source: &Path, target: &Path) -> io::Result<()> {
+    #[cfg(windows)]
+    {
+        std::os::windows::fs::symlink_dir(source, target)
+    }
+    #[cfg(not(windows))]
+    {
+        std::os::unix::fs::symlink(source, target)
+    }
+}
+
+/// Picks a folder name that doesn't collide with an existing instance.
+fn unique_instance_dir(instances_dir: &Path, name: &str) -> PathBuf {
+    let sanitized: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c => c,
+        })
+        .collect();
+    let sanitized = sanitized.trim().trim_end_matches('.').to_string();
+    let base = if sanitized.is_empty() {
+        "Instancia importada".to_string()
+    } else {
+        sanitized
+    };
+
+    let mut candidate = instances_dir.join(&base);
+    let mut counter = 2;
+    while candidate.exists() {
+        candidate = instances_dir.join(format!("{} ({})", base, counter));
+        counter += 1;
+    }
+    candidate
+}
+
+#[tauri::command]
+pub fn detect_external_instances() -> Vec<ExternalInstance> {
+    detect_instances()
+}
+
+#[tauri::command]
+pub fn inspect_external_instance(path: String) -> Result<Vec<ExternalInstance>, String> {
+    inspect_path(Path::new(&path))
+}
+
+/// Creates a Modpack Store instance from an external one. Files are copied
+/// (or linked) in the background and then the regular bootstrap downloads
+/// whatever is missing (client jar, libraries, Forge...).
+#[tauri::command]
+pub async fn import_external_instance(
+    events: State<'_, EventEmitter>,
+    external: ExternalInstance,
+    mode: ImportMode,
+    instance_name: Option<String>,
+    root: Option<String>,
+    location: Option<String>,
+) -> Result<String, String> {
+    if let Some(loader) = &external.unsupported_loader {
+        return Err(format!("El loader {} no está soportado todavía", loader));
+    }
+    if external.minecraft_version.is_empty() {
+        return Err("No se pudo determinar la versión de Minecraft".to_string());
+    }
+
+    let source_game_dir = PathBuf::from(&external.game_dir);
+    if !source_game_dir.is_dir() {
+        return Err(format!(
+            "No se encontró el directorio del juego: {}",
+            source_game_dir.display()
+        ));
+    }
+
+    let instances_dir = resolve_instance_parent(root.as_deref(), location.as_deref())?;
+
+    let mut instance = MinecraftInstance::new();
+    instance.instanceId = uuid::Uuid::new_v4().to_string();
+    instance.instanceName = instance_name
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or_else(|| external.name.clone());
+    instance.minecraftVersion = external.minecraft_version.clone();
+    instance.forgeVersion = external.forge_version.clone();
+    instance.memory = external.memory;
+    instance.bannerUrl = Some(
+        if instance.forgeVersion.is_some() {
+            "/images/default_instances/default_forge.webp"
+        } else {
+            "/images/default_instances/default_vanilla.webp"
+        }
+        .to_string(),
+    );
+
+    let instance_dir = unique_instance_dir(&instances_dir, &instance.instanceName);
+    fs::create_dir_all(&instance_dir)
+        .map_err(|e| format!("Failed to create instance directory: {}", e))?;
+    let minecraft_dir = instance_dir.join("minecraft");
+    instance.instanceDirectory = Some(instance_dir.to_string_lossy().to_string());
+    instance.minecraftPath = minecraft_dir.to_string_lossy().to_string();
+    instance_locations::register(&instance_dir)?;
+
+    instance
+        .save()
+        .map_err(|e| format!("Failed to save instance: {}", e))?;
+
+    let task_manager = Arc::new(Mutex::new(TasksManager::new(events.inner().clone())));
+    let task_id = task_manager.lock().unwrap().add_instance_task(
+        &format!("Importando instancia {}", instance.instanceName),
+        TaskKind::InstanceImport,
+        &instance.instanceId,
+        Some(serde_json::json!({
+            "instanceName": instance.instanceName.clone(),
+            "instanceId": instance.instanceId.clone()
+        })),
+    );
+
+    let instance_clone = instance.clone();
+    let events = events.inner().clone();
+    std::thread::spawn(move || {
+        // Copiar no necesita conexión; las descargas del bootstrap esperan a
+        // que vuelva si hace falta
+        let _slot =
+            tasks_manager::wait_for_slot(&task_id, TaskPriority::User, false, &task_manager);
+
+        let data = serde_json::json!({
+            "instanceName": instance_clone.instanceName.clone(),
+            "instanceId": instance_clone.instanceId.clone()
+        });
+
+        if let Ok(tm) = task_manager.lock() {
+            tm.update_task(
+                &task_id,
+                TaskStatus::Running,
+                5.0,
+                Message::new(keys::INSTANCE_IMPORT_COPYING),
+                Some(data.clone()),
+            );
+        }
+
+        let result = match mode {
+            ImportMode::Copy => copy_game_dir(&source_game_dir, &minecraft_dir).map(|copied| {
+                log::info!(
+                    "[Import] Copied {} files from {}",
+                    copied,
+                    source_game_dir.display()
+                );
+            }),
+            ImportMode::Link => link_game_dir(&source_game_dir, &minecraft_dir),
+        };
+
+        if let Err(e) = result {
+            log::error!("[Import] Failed to import game directory: {}", e);
+            if let Ok(tm) = task_manager.lock() {
+                tm.update_task(
+                    &task_id,
+                    TaskStatus::Failed,
+                    0.0,
+                    Message::new(keys::INSTANCE_IMPORT_FAILED).param("error", e.to_string()),
+                    Some(data),
+                );
+            }
+            return;
+        }
+
+        // Con los archivos copiados, retomar la tarea es repetir el bootstrap
+        if let Ok(tm) = task_manager.lock() {
+            tm.set_task_kind(&task_id, TaskKind::InstanceBootstrap);
+        }
+
+        run_instance_bootstrap(events, &instance_clone, &task_id, &task_manager);
+    });
+
+    Ok(instance.instanceId)
+}
@@ -9,28 +9,42 @@ use std::path::{Path, PathBuf};
 use std::process::{Child, Command, ExitStatus};
 use std::sync::{Arc, Mutex};
 use std::thread; // Crucial for asynchronous operations // For thread-safe shared state
+use std::time::SystemTime;
 
 // --- Crate Imports ---
 // Core components
+use crate::config; // Paths of the instance (launcher config)
+use crate::core::account_validation::{self, AccountIssue}; // Account checks before launch
+use crate::core::accounts_manager::AccountsManager; // Looks up the instance account
+use crate::core::entitlement::{self, EntitlementError}; // Game ownership check
+use crate::core::events::{names, EventEmitter, LauncherEvent}; // Events sent to the frontend
 use crate::core::forge_launcher::ForgeLoader; // Forge launch logic
+use crate::core::game_language; // Writes the game language into options.txt
+use crate::core::i18n::{keys, Message}; // Translatable status messages
 use crate::core::instance_bootstrap::InstanceBootstrap;
-use crate::core::minecraft::MinecraftLauncher; // Minecraft launcher logic
+use crate::core::instance_state::{self, InstanceState}; // Lifecycle state shown by the frontend
+use crate::core::jvm_crash; // Parses hs_err_pid*.log after a JVM crash
+use crate::core::launch_hooks::{self, HookPoint};
+use crate::core::minecraft::{
+    classpath, LaunchOptions, ManifestParser, MinecraftLauncher, MinecraftPaths,
+}; // Minecraft launcher logic
 use crate::core::minecraft_account::MinecraftAccount; // If needed for validation
 use crate::core::minecraft_instance::MinecraftInstance; // Instance definition
 use crate::core::network_utilities; // Network utilities for checking internet connection
+use crate::core::process_output::OutputCapture; // Drains the game's stdout/stderr
+use crate::core::process_registry::{self, LaunchGuard}; // Per-instance launch locks
+use crate::core::session_logs::SessionLog; // Writes each session's output to disk
+use crate::core::startup_watchdog; // Warns when the game hangs before its window opens
+use crate::core::telemetry::{self, TelemetryEvent}; // Anonymous launch outcomes
 use crate::core::vanilla_launcher::VanillaLauncher; // Vanilla launch logic
 use crate::interfaces::game_launcher::GameLauncher; // Generic launch trait/logic // Asset revalidation logic
 
 // Utilities & Managers (adjust paths if needed)
-use crate::utils::config_manager::get_config_manager; // Access configuration
-                                                      // use crate::core::tasks_manager::{TasksManager, TaskStatus, TaskInfo}; // Keep if used elsewhere
-
-// Global App Handle (or use Tauri Managed State)
-use crate::GLOBAL_APP_HANDLE; // Accessing the globally stored AppHandle
+// use crate::core::tasks_manager::{TasksManager, TaskStatus, TaskInfo}; // Keep if used elsewhere
 
 // --- External Crates ---
 use serde_json::Value; // For JSON manipulation, especially in validation/payloads
-use tauri::{Emitter, Manager}; // For emitting events to the frontend
+use tauri::Manager;
 
 //-----------------------------------------------------------------------------
 // Struct Definition
@@ -40,6 +54,8 @@ use tauri::{Emitter, Manager}; // For emitting events to the frontend
 /// Holds the instance configuration and provides methods to launch it.
 pub struct InstanceLauncher {
     instance: MinecraftInstance, // The configuration of the instance to launch
+    options: LaunchOptions,      // Options for this launch only (demo...)
+    events: EventEmitter,        // Managed-state emitter for this launch's events
 }
 
 #[derive(Debug)]
@@ -71,6 +87,15 @@ impl From<i32> for OfficialExitCode {
     }
 }
 
+/// stderr lines used to guess a `PossibleErrorCode`; tracked for the whole
+/// session since only the tail of the output is kept.
+const ERROR_SIGNATURES: &[&str] = &[
+    "UnsupportedClassVersionError",
+    "Could not find or load main class",
+    "Exception in thread",
+    "OutOfMemoryError",
+];
+
 #[derive(Debug)]
 enum PossibleErrorCode {
     IncompatibleJavaVersion,
@@ -78,6 +103,7 @@ enum PossibleErrorCode {
     CorruptedMod,
     OutOfMemory,
     TerminatedByUser,
+    JvmCrash,
     UnknownError,
 }
 
@@ -90,6 +116,7 @@ impl PossibleErrorCode {
             PossibleErrorCode::UnknownError => "UNKNOWN_ERROR",
             PossibleErrorCode::OutOfMemory => "OUT_OF_MEMORY",
             PossibleErrorCode::TerminatedByUser => "TERMINATED_BY_USER",
+            PossibleErrorCode::JvmCrash => "JVM_CRASH",
         }
     }
 }
@@ -105,14 +132,24 @@ impl InstanceLauncher {
     ///
     /// * `instance` - The `MinecraftInstance` struct containing all necessary details.
     ///              This struct must implement `Clone`.
-    pub fn new(instance: MinecraftInstance) -> Self {
-        Self { instance }
+    pub fn new(instance: MinecraftInstance, events: EventEmitter) -> Self {
+        Self {
+            instance,
+            options: LaunchOptions::default(),
+            events,
+        }
+    }
+
+    /// Sets the per-launch options (e.g. demo mode).
+    pub fn with_options(mut self, options: LaunchOptions) -> Self {
+        self.options = options;
+        self
     }
 
     // --- Helper Methods for Event Emission ---
 
     /// Emits a status update event to the frontend.
-    /// Uses the global `AppHandle` to send events to all windows.
+    /// Uses the injected `EventEmitter` to send events to all windows.
     ///
     /// # Arguments
     ///
@@ -121,38 +158,24 @@ impl InstanceLauncher {
     /// * `data` - Optional additional data to send with the event.
     ///   This can be a JSON object or any other serializable type.
 
-    fn emit_status(&self, event_name: &str, message: &str, data: Option<Value>) {
+    fn emit_status(
+        &self,
+        event_name: &'static str,
+        message: impl Into<Message>,
+        data: Option<Value>,
+    ) {
+        let message = message.into();
         println!(
             "[Instance: {}] Emitting Event: {} - Message: {}",
             self.instance.instanceId, event_name, message
         );
-        if let Ok(guard) = GLOBAL_APP_HANDLE.lock() {
-            if let Some(app_handle) = guard.as_ref() {
-                let payload = serde_json::json!({
-                    "id": self.instance.instanceId,
-                    "name": self.instance.instanceName, // Ensure instanceName is populated
-                    "message": message,
-                    "data": data.unwrap_or(serde_json::json!({})) // Use empty JSON if no data provided
-                });
-                // Use emit to notify the specific window listening for this event
-                if let Err(e) = app_handle.emit(event_name, payload) {
-                    eprintln!(
-                        "[Instance: {}] Failed to emit event '{}': {}",
-                        self.instance.instanceId, event_name, e
-                    );
-                }
-            } else {
-                eprintln!(
-                    "[Instance: {}] Error: GLOBAL_APP_HANDLE is None when trying to emit '{}'.",
-                    self.instance.instanceId, event_name
-                );
-            }
-        } else {
-            eprintln!(
-                "[Instance: {}] Error: Could not lock GLOBAL_APP_HANDLE mutex for '{}'.",
-                self.instance.instanceId, event_name
-            );
-        }
+        self.events.send(LauncherEvent::instance(
+            event_name,
+            self.instance.instanceId.clone(),
+            Some(self.instance.instanceName.clone()), // Ensure instanceName is populated
+            message,
+            Some(data.unwrap_or(serde_json::json!({}))), // Use empty JSON if no data provided
+        ));
     }
 
     /// Emits a specific "instance-error" event.
@@ -161,12 +184,18 @@ impl InstanceLauncher {
     /// # Arguments
     ///
     /// * `error_message` - The error description to send to the frontend.
-    fn emit_error(&self, error_message: &str, data: Option<Value>) {
+    fn emit_error(&self, error_message: impl Into<Message>, data: Option<Value>) {
+        let error_message = error_message.into();
         println!(
             "[Instance: {}] Emitting Error Event: {}",
             self.instance.instanceId, error_message
         );
-        self.emit_status("instance-error", error_message, data);
+        instance_state::transition(
+            &self.instance.instanceId,
+            InstanceState::Failed,
+            Some(error_message.to_string()),
+        );
+        self.emit_status(names::INSTANCE_ERROR, error_message, data);
     }
 
     // --- Process Monitoring ---
@@ -177,35 +206,65 @@ impl InstanceLauncher {
     /// # Arguments
     ///
     /// * `instance` - A clone of the `MinecraftInstance` data for context in the thread.
+    /// * `events` - The emitter of the launch, moved into the monitor thread.
     /// * `child` - The `std::process::Child` representing the running Minecraft game.
-    fn monitor_process(instance: MinecraftInstance, mut child: Child) {
+    /// * `session_log` - The files the game writes its output to, if any.
+    /// * `options` - The options of this launch, kept in case it must be restarted.
+    fn monitor_process(
+        instance: MinecraftInstance,
+        events: EventEmitter,
+        mut child: Child,
+        session_log: Option<Arc<SessionLog>>,
+        options: LaunchOptions,
+    ) {
         let instance_id = instance.instanceId.clone();
         let instance_name = instance.instanceName.clone();
-        let emitter_launcher = InstanceLauncher::new(instance);
+        let emitter_launcher = InstanceLauncher::new(instance, events);
 
         // Ejecutamos en un hilo para no bloquear
         thread::spawn(move || {
             log::info!("[Monitor: {}] Started monitoring process.", instance_id);
+            let started_at = SystemTime::now();
+
+            // El juego escribe en los archivos de sesión y se siguen desde ahí;
+            // si no se pudieron abrir, la salida viene por pipes. Sólo se
+            // guarda el final en memoria.
+            let label = format!("Minecraft:{}", instance_id);
+            let capture = match &session_log {
+                Some(session_log) if child.stdout.is_none() => OutputCapture::follow_session(
+                    session_log,
+                    &label,
+                    startup_watchdog::READY_MARKERS,
+                    ERROR_SIGNATURES,
+                ),
+                _ => OutputCapture::attach(
+                    &mut child,
+                    &label,
+                    startup_watchdog::READY_MARKERS,
+                    ERROR_SIGNATURES,
+                ),
+            };
+            startup_watchdog::spawn(instance_id.clone(), child.id(), options, capture.watch());
+
+            match child.wait() {
+                Ok(status) => {
+                    let output = capture.finish();
+                    let exit_code = status.code().unwrap_or(-1);
+                    if let Some(session_log) = &session_log {
+                        session_log.finish(status.code());
+                    }
+                    let stdout = output.stdout.text();
+                    let stderr = output.stderr.text();
+                    let seen = |pattern| output.stderr.has_seen(pattern);
 
-            // Espera a que termine y captura stdout, stderr, status
-            match child.wait_with_output() {
-                Ok(output) => {
-                    let exit_code = output.status.code().unwrap_or(-1);
-                    let stdout = String::from_utf8_lossy(&output.stdout);
-                    let stderr = String::from_utf8_lossy(&output.stderr);
-
-                    // Loguear todo el output en el backend
-                    log::info!("[Minecraft:{} stdout]\n{}", instance_id, stdout);
-                    log::error!("[Minecraft:{} stderr]\n{}", instance_id, stderr);
-
-                    // Detectar un PossibleErrorCode según el contenido de stderr
-                    let detected = if stderr.contains("UnsupportedClassVersionError") {
+                    // Detectar un PossibleErrorCode según lo que pasó por stderr
+                    let detected = if seen("UnsupportedClassVersionError") {
                         PossibleErrorCode::IncompatibleJavaVersion
-                    } else if stderr.contains("Could not find or load main class") {
+                    } else if seen("Could not find or load main class") {
                         PossibleErrorCode::MissingLibraries
-                    } else if stderr.contains("Exception in thread") && stderr.contains("mod") {
+                    } else if seen("Exception in thread") && stderr.contains("mod") {
                         PossibleErrorCode::CorruptedMod
-                    } else if stderr.contains("OutOfMemoryError") {
+                    } else if seen("OutOfMemoryError") {
                         PossibleErrorCode::OutOfMemory
                     } else if exit_code == 143 {
                         PossibleErrorCode::TerminatedByUser
@@ -213,17 +272,32 @@ impl InstanceLauncher {
                         PossibleErrorCode::UnknownError
                     };
 
+                    // Si la JVM misma se cayó, deja un hs_err_pid*.log en el directorio del juego
+                    let crash_report = if exit_code != 0 {
+                        jvm_crash::collect_since(
+                            Path::new(&emitter_launcher.instance.minecraftPath),
+                            started_at,
+                        )
+                    } else {
+                        None
+                    };
+                    let detected = match detected {
+                        PossibleErrorCode::UnknownError if crash_report.is_some() => {
+                            PossibleErrorCode::JvmCrash
+                        }
+                        detected => detected,
+                    };
+
                     // Mapear el exit_code al enum oficial
                     let official: OfficialExitCode = exit_code.into();
 
                     // Construir y emitir el evento con TODO el detalle
-                    let message = format!(
-                        "Minecraft instance '{}' exited ({:?})",
-                        instance_name, official
-                    );
+                    let message = Message::new(keys::LAUNCH_EXITED)
+                        .param("instanceName", instance_name.clone())
+                        .param("exitCode", format!("{:?}", official));
                     emitter_launcher.emit_status(
-                        "instance-exited",
-                        &message,
+                        names::INSTANCE_EXITED,
+                        message,
                         Some(json!({
                             "instanceName":     instance_name,
                             "exitCode":         exit_code,
@@ -231,8 +305,38 @@ impl InstanceLauncher {
                             "detectedError":    format!("{:?}", detected),
                             "stdout":           stdout.trim_end(),
                             "stderr":           stderr.trim_end(),
+                            "jvmCrash":         crash_report,
                         })),
                     );
+
+                    let success = matches!(
+                        official,
+                        OfficialExitCode::Success | OfficialExitCode::TerminatedByUser
+                    );
+                    if success {
+                        instance_state::transition(&instance_id, InstanceState::Ready, None);
+                    } else {
+                        instance_state::transition(
+                            &instance_id,
+                            InstanceState::Failed,
+                            Some(format!("{:?}", detected)),
+                        );
+                    }
+                    telemetry::record(TelemetryEvent::LaunchResult {
+                        minecraft_version: emitter_launcher.instance.minecraftVersion.clone(),
+                        modpack_id: emitter_launcher.instance.modpackId.clone(),
+                        success,
+                        exit_code: Some(exit_code),
+                        error_code: (!success).then(|| format!("{:?}", detected)),
+                    });
+
+                    if let Err(e) = launch_hooks::run_hook(
+                        &emitter_launcher.instance,
+                        HookPoint::PostExit,
+                        &[("MODPACKSTORE_EXIT_CODE", exit_code.to_string())],
+                    ) {
+                        log::error!("[Monitor: {}] {}", instance_id, e);
+                    }
                 }
                 Err(err) => {
                     // Error al esperar el proceso
@@ -243,8 +347,8 @@ impl InstanceLauncher {
                     log::error!("[Monitor: {}] {}", instance_id, error_msg);
                     emitter_launcher.emit_error(&error_msg, None);
                     emitter_launcher.emit_status(
-                        "instance-exited",
-                        "Minecraft process ended unexpectedly.",
+                        names::INSTANCE_EXITED,
+                        Message::new(keys::LAUNCH_PROCESS_ENDED),
                         Some(json!({
                             "instanceName":     instance_name,
                             "possibleErrorCode":"PROCESS_ERROR",
@@ -254,6 +358,13 @@ impl InstanceLauncher {
                 }
             }
 
+            // Liberar la instancia y su copia de nativos
+            process_registry::mark_exited(&instance_id);
+            MinecraftPaths::cleanup_natives(
+                &instance_id,
+                &emitter_launcher.instance.minecraftVersion,
+            );
+
             log::info!("[Monitor: {}] Finished monitoring.", instance_id);
         });
     }
@@ -266,8 +377,8 @@ impl InstanceLauncher {
             self.instance.instanceName
         );
         self.emit_status(
-            "instance-downloading-assets",
-            "Verificando/Descargando assets...",
+            names::INSTANCE_DOWNLOADING_ASSETS,
+            Message::new(keys::LAUNCH_CHECKING_ASSETS),
             None,
         );
 
@@ -292,7 +403,7 @@ impl InstanceLauncher {
 
         // Call revalidate_assets from InstanceBootstrap (We pass MinecraftInstance to it)
 
-        let mut instance_bootstrap = InstanceBootstrap::new();
+        let mut instance_bootstrap = InstanceBootstrap::new(self.events.clone());
         let result = instance_bootstrap.revalidate_assets(&mut self.instance)?;
 
         // Validate modpack assets if this is a modpack instance
@@ -324,13 +435,64 @@ impl InstanceLauncher {
         Ok(())
     }
 
+    /// Makes sure every jar on the classpath exists, downloading the missing
+    /// ones. Launching without them ends in a `ClassNotFoundException`, so a
+    /// library that can't be restored stops the launch with `MISSING_LIBRARIES`.
+    fn ensure_classpath(&self) -> IoResult<()> {
+        let paths = {
+            let config_manager = config::read_config();
+            let config = config_manager
+                .as_ref()
+                .map_err(|e| IoError::new(IoErrorKind::Other, e.clone()))?;
+            MinecraftPaths::new(&self.instance, config)
+        };
+        // Sin rutas o manifiesto el fallo lo informa el propio lanzamiento
+        let Some(paths) = paths else {
+            return Ok(());
+        };
+        let Some(manifest) = ManifestParser::new(&paths).load_merged_manifest() else {
+            return Ok(());
+        };
+
+        let missing = match classpath::repair_missing_libraries(&manifest, &paths) {
+            Ok(_) => return Ok(()),
+            Err(missing) => missing,
+        };
+
+        let error_code = PossibleErrorCode::MissingLibraries.as_str();
+        let names: Vec<&str> = missing
+            .iter()
+            .map(|library| library.name.as_str())
+            .collect();
+        self.emit_error(
+            Message::new(keys::LAUNCH_MISSING_LIBRARIES)
+                .param("count", missing.len())
+                .param("libraries", names.join(", ")),
+            Some(json!({ "errorCode": error_code, "missingLibraries": missing })),
+        );
+        telemetry::record(TelemetryEvent::LaunchResult {
+            minecraft_version: self.instance.minecraftVersion.clone(),
+            modpack_id: self.instance.modpackId.clone(),
+            success: false,
+            exit_code: None,
+            error_code: Some(error_code.to_string()),
+        });
+        Err(IoError::new(
+            IoErrorKind::NotFound,
+            format!("Missing libraries: {}", names.join(", ")),
+        ))
+    }
+
     // --- Internal Synchronous Launch Logic ---
 
     /// Contains the core, sequential steps for launching the instance.
     /// This method is intended to be run within a dedicated thread.
     /// It handles validation, asset checks, and the actual game launch command.
     /// Errors encountered stop the process and emit an "instance-error" event.
-    fn perform_launch_steps(&mut self) {
+    ///
+    /// The `launch_guard` keeps the instance locked; it is released when this
+    /// method returns without a running process.
+    fn perform_launch_steps(&mut self, launch_guard: LaunchGuard) {
         // Clear the console for better readability
         println!("\x1B[2J\x1B[1;1H"); // Uncomment if you want to clear the console
         println!("Performing launch steps...");
@@ -341,12 +503,35 @@ impl InstanceLauncher {
         );
 
         // Note: Initial "instance-launch-start" event is emitted by this function.
-        self.emit_status("instance-launch-start", "Preparando lanzamiento...", None);
+        instance_state::transition(&self.instance.instanceId, InstanceState::Launching, None);
+        self.emit_status(
+            names::INSTANCE_LAUNCH_START,
+            Message::new(keys::LAUNCH_PREPARING),
+            None,
+        );
         println!(
             "[Launch Thread: {}] Starting launch steps.",
             self.instance.instanceId
         );
 
+        // 1. Cuenta de la instancia: sin ella no descargamos nada
+        if let Err(issue) = self.check_account() {
+            log::warn!(
+                "[Launch Thread: {}] Account check failed: {}",
+                self.instance.instanceId,
+                issue.code()
+            );
+            self.emit_status(
+                names::INSTANCE_ACCOUNT_INVALID,
+                issue.message(),
+                Some(json!({
+                    "reasonCode": issue.code(),
+                    "accountUuid": self.instance.accountUuid,
+                })),
+            );
+            return;
+        }
+
         // 2. Revalidate Assets
         if let Err(e) = self.revalidate_assets() {
             let err_msg = format!("Error en revalidación de assets: {}", e);
@@ -359,11 +544,72 @@ impl InstanceLauncher {
             self.instance.instanceId
         );
 
-        // 3. Use the new MinecraftLauncher because it handles launch type, etc
+        // Librerías del classpath que falten en disco: se reintenta su descarga
+        if let Err(e) = self.ensure_classpath() {
+            log::error!("[Launch Thread: {}] {}", self.instance.instanceId, e);
+            return;
+        }
+
+        // Idioma del juego; si no se puede escribir se lanza igualmente
+        if let Err(e) = game_language::apply_game_language(&self.instance) {
+            log::warn!("[Launch Thread: {}] {}", self.instance.instanceId, e);
+        }
+
+        // 3. Pre-launch hook: si falla o agota el tiempo no lanzamos
+        match launch_hooks::run_hook(&self.instance, HookPoint::PreLaunch, &[]) {
+            Ok(Some(outcome)) if !outcome.success() => {
+                let err_msg = if outcome.timed_out {
+                    Message::new(keys::LAUNCH_HOOK_TIMEOUT)
+                } else {
+                    Message::new(keys::LAUNCH_HOOK_FAILED).param("exitCode", outcome.exit_code)
+                };
+                self.emit_error(err_msg, None);
+                return;
+            }
+            Ok(_) => {}
+            Err(e) => {
+                self.emit_error(&e, None);
+                return;
+            }
+        }
+
+        // Cuentas de Microsoft: licencia y perfil antes de lanzar (la demo y
+        // el modo sin conexión no lo necesitan)
+        if !self.options.demo && self.options.offline_account.is_none() {
+            if let Err(error) = self.verify_entitlement() {
+                log::warn!(
+                    "[Launch Thread: {}] Entitlement check failed: {}",
+                    self.instance.instanceId,
+                    error.code()
+                );
+                self.emit_error(error.message(), Some(json!({ "errorCode": error.code() })));
+                telemetry::record(TelemetryEvent::LaunchResult {
+                    minecraft_version: self.instance.minecraftVersion.clone(),
+                    modpack_id: self.instance.modpackId.clone(),
+                    success: false,
+                    exit_code: None,
+                    error_code: Some(error.code().to_string()),
+                });
+                return;
+            }
+        }
+
+        // 4. Use the new MinecraftLauncher because it handles launch type, etc
 
         let final_launch_result = {
             // Create a new MinecraftLauncher instance
-            let minecraft_launcher = MinecraftLauncher::new(self.instance.clone());
+            // Copia completa de la salida en disco, por si el launcher se cierra
+            let session_log = SessionLog::create(&self.instance);
+            if let Some(session_log) = &session_log {
+                log::info!(
+                    "[Launch Thread: {}] Session log: {}",
+                    self.instance.instanceId,
+                    session_log.path().display()
+                );
+            }
+            let minecraft_launcher = MinecraftLauncher::new(self.instance.clone())
+                .with_options(self.options.clone())
+                .with_session_log(session_log.clone());
 
             // Call the launch method
             match minecraft_launcher.launch() {
@@ -374,19 +620,46 @@ impl InstanceLauncher {
                         self.instance.instanceId,
                         child_process.id()
                     );
-                    self.emit_status("instance-launched", "Minecraft se está ejecutando.", None);
+                    launch_guard.mark_running(child_process.id());
+                    instance_state::transition(
+                        &self.instance.instanceId,
+                        InstanceState::Running,
+                        None,
+                    );
+                    self.emit_status(
+                        names::INSTANCE_LAUNCHED,
+                        Message::new(keys::LAUNCH_RUNNING),
+                        None,
+                    );
+                    // Post-launch hook en segundo plano para no retrasar el monitor
+                    launch_hooks::run_hook_detached(
+                        self.instance.clone(),
+                        HookPoint::PostLaunch,
+                        vec![("MODPACKSTORE_PID", child_process.id().to_string())],
+                    );
                     // Start monitoring the process in its own background thread.
-                    Self::monitor_process(self.instance.clone(), child_process);
+                    Self::monitor_process(
+                        self.instance.clone(),
+                        self.events.clone(),
+                        child_process,
+                        session_log,
+                        self.options.clone(),
+                    );
                     Ok(()) // Indicate successful initiation of the launch.
                 }
                 None => {
                     // Failure: GameLauncher::launch returned None.
-                    let err_msg =
-                        "Error al iniciar el proceso de Minecraft: No se pudo iniciar el proceso"
-                            .to_string();
+                    let err_msg = Message::new(keys::LAUNCH_START_FAILED);
                     eprintln!("[Launch Thread: {}] {}", self.instance.instanceId, err_msg);
-                    self.emit_error(&err_msg, None);
-                    Err(IoError::new(IoErrorKind::Other, err_msg))
+                    self.emit_error(err_msg.clone(), None);
+                    telemetry::record(TelemetryEvent::LaunchResult {
+                        minecraft_version: self.instance.minecraftVersion.clone(),
+                        modpack_id: self.instance.modpackId.clone(),
+                        success: false,
+                        exit_code: None,
+                        error_code: Some("ProcessStartFailed".to_string()),
+                    });
+                    Err(IoError::new(IoErrorKind::Other, err_msg.text))
                 }
             }
         };
@@ -399,11 +672,8 @@ impl InstanceLauncher {
                 e
             );
         } else {
-            let config_manager = get_config_manager();
-            let close_on_launch = config_manager
-                .lock()
-                .expect("Failed to lock config manager mutex")
-                .get_close_on_launch();
+            let close_on_launch =
+                config::with_config(|config| config.get_close_on_launch()).unwrap_or(false);
 
             if close_on_launch {
                 // Close the main process if configured to do so
@@ -413,22 +683,8 @@ impl InstanceLauncher {
                 );
                 thread::sleep(std::time::Duration::from_secs(5));
 
-                // Use the global app handle to close the main process
-                if let Ok(guard) = GLOBAL_APP_HANDLE.lock() {
-                    if let Some(app_handle) = guard.as_ref() {
-                        app_handle.exit(0);
-                    } else {
-                        eprintln!(
-                            "[Launch Thread: {}] Error: GLOBAL_APP_HANDLE is None when trying to close.",
-                            self.instance.instanceId
-                        );
-                    }
-                } else {
-                    eprintln!(
-                        "[Launch Thread: {}] Error: Could not lock GLOBAL_APP_HANDLE mutex for closing.",
-                        self.instance.instanceId
-                    );
-                }
+                // The emitter carries the app handle used to close the main process
+                self.events.app_handle().exit(0);
             }
 
             log::info!(
@@ -443,15 +699,69 @@ impl InstanceLauncher {
         // Thread finishes here.
     }
 
+    /// Validates the instance account. If it isn't usable and offline play
+    /// is allowed (launch option or `offlineFallback`), switches this launch
+    /// to an offline account instead of failing.
+    fn check_account(&mut self) -> Result<(), AccountIssue> {
+        let issue = match account_validation::validate_instance_account(&self.instance) {
+            Ok(_) => return Ok(()),
+            // La demo usa una cuenta provisional si la instancia no tiene
+            Err(AccountIssue::NotSelected | AccountIssue::NotFound) if self.options.demo => {
+                return Ok(())
+            }
+            Err(issue) => issue,
+        };
+
+        if !self.options.allow_offline_fallback && !account_validation::offline_fallback_enabled() {
+            return Err(issue);
+        }
+
+        let account = account_validation::offline_fallback_account(&self.instance);
+        log::warn!(
+            "[Launch Thread: {}] Account check failed ({}), playing offline as {}",
+            self.instance.instanceId,
+            issue.code(),
+            account.username()
+        );
+        self.emit_status(
+            names::INSTANCE_LAUNCH_START,
+            Message::new(keys::ACCOUNT_OFFLINE_FALLBACK).param("username", account.username()),
+            Some(json!({ "reasonCode": issue.code(), "offlineFallback": true })),
+        );
+        self.options.offline_account = Some(account);
+        Ok(())
+    }
+
+    /// Verifies that the instance's Microsoft account owns the game and has
+    /// a profile. Instances without a known account are left to the launcher.
+    fn verify_entitlement(&self) -> Result<(), EntitlementError> {
+        let Some(account) = self
+            .instance
+            .accountUuid
+            .as_deref()
+            .and_then(|uuid| AccountsManager::new().get_minecraft_account_by_uuid(uuid))
+        else {
+            return Ok(());
+        };
+        entitlement::verify_account(&account)
+    }
+
     // --- Public Asynchronous Launch Method ---
 
     /// Initiates the instance launch process in a separate background thread.
     /// This function returns immediately, allowing the caller (e.g., Tauri command)
     /// to remain responsive. Status updates are sent via events.
     /// Requires `MinecraftInstance` to implement `Clone`.
-    pub fn launch_instance_async(&self) {
+    ///
+    /// Fails immediately if the instance is already launching or running.
+    pub fn launch_instance_async(&self) -> Result<(), String> {
+        // Lock de lanzamiento por instancia; se mueve al hilo
+        let launch_guard = process_registry::begin_launch(&self.instance.instanceId)?;
+
         // Clone the necessary instance data for the new thread.
         let instance_data_clone = self.instance.clone();
+        let options = self.options.clone();
+        let events = self.events.clone();
         let instance_id = instance_data_clone.instanceId.clone(); // For logging before spawn
 
         log::info!(
@@ -462,9 +772,12 @@ impl InstanceLauncher {
         // Spawn the background thread
         thread::spawn(move || {
             // Create a new InstanceLauncher specific to this thread.
-            let mut thread_launcher = InstanceLauncher::new(instance_data_clone);
+            let mut thread_launcher =
+                InstanceLauncher::new(instance_data_clone, events).with_options(options);
             // Execute the sequential, potentially blocking launch steps within this thread.
-            thread_launcher.perform_launch_steps();
+            thread_launcher.perform_launch_steps(launch_guard);
+            // Sin proceso ni error (p. ej. cuenta inválida): vuelve a `ready`
+            instance_state::settle_launch(&thread_launcher.instance.instanceId);
             // The thread will terminate automatically after perform_launch_steps finishes.
         });
 
@@ -473,5 +786,6 @@ impl InstanceLauncher {
             "[Main Thread] Finished spawning thread for {}. Caller continues.",
             instance_id
         );
+        Ok(())
     }
 }
@@ -0,0 +1,100 @@
+//! Instances installed outside the instance roots.
+
+use crate::config::{read_config, write_config};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const LOCATIONS_KEY: &str = "instanceLocations";
+
+/// Checks the folder chosen for a new instance and creates it. The instance
+/// goes into a subfolder, like in the roots.
+pub fn prepare_location(location: &str) -> Result<PathBuf, String> {
+    let location = Path::new(location.trim());
+    if location.as_os_str().is_empty() {
+        return Err("La ubicación de la instancia está vacía".to_string());
+    }
+    if !location.is_absolute() {
+        return Err(format!(
+            "La ubicación {} debe ser una ruta absoluta",
+            location.display()
+        ));
+    }
+    fs::create_dir_all(location)
+        .map_err(|e| format!("No se pudo crear la carpeta {}: {}", location.display(), e))?;
+    Ok(location.to_path_buf())
+}
+
+/// Registers `instance_dir` unless a configured root already lists it.
+pub fn register(instance_dir: &Path) -> Result<(), String> {
+    let mut config_lock = write_config();
+    let config = config_lock.as_mut().map_err(|e| e.clone())?;
+
+    let scanned = instance_dir.parent().is_some_and(|parent| {
+        config
+            .get_instance_roots()
+            .iter()
+            .any(|root| root == parent)
+    });
+    let mut locations = config.get_instance_locations();
+    if scanned || locations.iter().any(|location| location == instance_dir) {
+        return Ok(());
+    }
+
+    locations.push(instance_dir.to_path_buf());
+    let locations: Vec<String> = locations
+        .iter()
+        .map(|location| location.to_string_lossy().to_string())
+        .collect();
+    config
+        .set(LOCATIONS_KEY, &locations)
+        .map_err(|e| format!("Error de validación: {}", e))?;
+    config.save()?;
+    log::info!(
+        "[InstanceLocations] Registered instance outside the roots: {}",
+        instance_dir.display()
+    );
+    Ok(())
+}
+
+/// Forgets `instance_dir` after the instance was removed. Instances in the
+/// roots were never registered and are ignored.
+pub fn unregister(instance_dir: &Path) {
+    let registered = read_config()
+        .as_ref()
+        .map(|config| {
+            config
+                .get_instance_locations()
+                .iter()
+                .any(|l| l == instance_dir)
+        })
+        .unwrap_or(false);
+    if !registered {
+        return;
+    }
+
+    let mut config_lock = write_config();
+    let Ok(config) = config_lock.as_mut() else {
+        return;
+    };
+    let locations: Vec<String> = config
+        .get_instance_locations()
+        .iter()
+        .filter(|location| location.as_path() != instance_dir)
+        .map(|location| location.to_string_lossy().to_string())
+        .collect();
+    let result = config
+        .set(LOCATIONS_KEY, &locations)
+        .map_err(|e| e.to_string())
+        .and_then(|_| config.save());
+    match result {
+        Ok(()) => log::info!(
+            "[InstanceLocations] Unregistered {}",
+            instance_dir.display()
+        ),
+        Err(e) => log::warn!(
+            "[InstanceLocations] Could not unregister {}: {}",
+            instance_dir.display(),
+            e
+        ),
+    }
+}
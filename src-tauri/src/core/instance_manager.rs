@@ -1,19 +1,36 @@
 // src-tauri/src/core/instance_manager.rs
 
-use crate::config::get_config_manager;
+use crate::config::{read_config, ConfigManager};
+use crate::core::events::{self, names, EventEmitter, LauncherEvent};
+use crate::core::i18n::{keys, Message};
 use crate::core::instance_bootstrap::InstanceBootstrap;
+use crate::core::instance_images;
+use crate::core::instance_locations;
+use crate::core::instance_readme;
+use crate::core::instance_registry;
+use crate::core::instance_state::{self, InstanceState};
+use crate::core::launch_queue::{self, LaunchRequest};
+use crate::core::minecraft::environment::validate_instance_overrides;
+use crate::core::minecraft::LaunchOptions;
 use crate::core::minecraft_instance;
 use crate::core::minecraft_instance::MinecraftInstance;
 use crate::core::models::ModpackInfo;
-use crate::core::tasks_manager::{TaskStatus, TasksManager};
-use crate::GLOBAL_APP_HANDLE;
+use crate::core::modpack_updates;
+use crate::core::preflight::free_disk_space_mb;
+use crate::core::prelaunch_appearance;
+use crate::core::system_resources::MIN_INSTANCE_MEMORY_MB;
+use crate::core::tasks_manager::{
+    self, TaskInfo, TaskKind, TaskPriority, TaskStatus, TasksManager,
+};
+use crate::core::telemetry::{self, TelemetryEvent};
 use dirs::config_dir;
-use serde_json::from_str;
+use serde::Serialize;
+use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::Mutex;
-use tauri::Emitter;
+use tauri::State;
 
 // Función auxiliar para normalizar rutas
 fn normalize_path(path: &Path) -> String {
@@ -23,43 +40,81 @@ fn normalize_path(path: &Path) -> String {
 
 #[tauri::command]
 pub fn get_all_instances() -> Result<Vec<MinecraftInstance>, String> {
-    let config_manager = get_config_manager()
-        .lock()
-        .map_err(|_| "Failed to lock config manager mutex".to_string())?;
+    let config_manager = read_config();
 
     let config = config_manager.as_ref().map_err(|e| e.clone())?;
 
-    let instances_dir = config.get_instances_dir();
-    get_instances(instances_dir.to_str().unwrap_or_default())
+    get_instances(config)
 }
 
 #[tauri::command]
 pub fn get_instance_by_name(instance_name: String) -> Result<Option<MinecraftInstance>, String> {
-    let config_manager = get_config_manager()
-        .lock()
-        .map_err(|_| "Failed to lock config manager mutex".to_string())?;
+    let config_manager = read_config();
 
     let config = config_manager.as_ref().map_err(|e| e.clone())?;
 
-    let instances_dir = config.get_instances_dir();
-
-    let instances = get_instances(instances_dir.to_str().unwrap_or_default())?;
+    let instances = get_instances(config)?;
     Ok(instances
         .into_iter()
         .find(|i| i.instanceName == instance_name))
 }
 
+/// Límite de las notas de una instancia, en caracteres
+const MAX_NOTES_LENGTH: usize = 10_000;
+
+/// `None` deja la memoria en manos del ajuste global
+fn validate_instance_memory(memory: Option<u32>) -> Result<(), String> {
+    match memory {
+        Some(memory) if memory < MIN_INSTANCE_MEMORY_MB => Err(format!(
+            "La memoria de la instancia debe ser de al menos {} MB",
+            MIN_INSTANCE_MEMORY_MB
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Acepta "#rgb" o "#rrggbb" y lo guarda como "#rrggbb" en minúsculas
+fn normalize_instance_color(color: Option<String>) -> Result<Option<String>, String> {
+    let Some(color) = color
+        .map(|c| c.trim().to_lowercase())
+        .filter(|c| !c.is_empty())
+    else {
+        return Ok(None);
+    };
+    let hex = color
+        .strip_prefix('#')
+        .filter(|hex| hex.chars().all(|c| c.is_ascii_hexdigit()))
+        .ok_or_else(|| format!("Color no válido: {}", color))?;
+    match hex.len() {
+        6 => Ok(Some(color)),
+        3 => Ok(Some(format!(
+            "#{}",
+            hex.chars().flat_map(|c| [c, c]).collect::<String>()
+        ))),
+        _ => Err(format!("Color no válido: {}", color)),
+    }
+}
+
 #[tauri::command]
 pub fn update_instance(instance: MinecraftInstance) -> Result<(), String> {
-    let config_manager = get_config_manager()
-        .lock()
-        .map_err(|_| "Failed to lock config manager mutex".to_string())?;
+    validate_instance_overrides(&instance)?;
+    validate_instance_memory(instance.memory)?;
+    if instance
+        .notes
+        .as_ref()
+        .is_some_and(|notes| notes.chars().count() > MAX_NOTES_LENGTH)
+    {
+        return Err(format!(
+            "Las notas no pueden superar los {} caracteres",
+            MAX_NOTES_LENGTH
+        ));
+    }
 
-    let config = config_manager.as_ref().map_err(|e| e.clone())?;
+    let config_manager = read_config();
 
-    let instances_dir = config.get_instances_dir();
+    let config = config_manager.as_ref().map_err(|e| e.clone())?;
 
-    let instances = get_instances(instances_dir.to_str().unwrap_or_default())?;
+    let instances = get_instances(config)?;
     let original_instance = instances
         .into_iter()
         .find(|i| i.instanceId == instance.instanceId)
@@ -70,134 +125,409 @@ pub fn update_instance(instance: MinecraftInstance) -> Result<(), String> {
         None => return Err("Instance directory is missing".to_string()),
     };
 
-    let config_file = instance_path.join("instance.json");
-
-    if config_file.exists() {
-        let contents =
-            fs::read_to_string(&config_file).map_err(|e| format!("Error reading JSON: {}", e))?;
-
-        let mut existing_instance: MinecraftInstance =
-            from_str(&contents).map_err(|e| format!("Error parsing JSON: {}", e))?;
+    if !instance_path.join("instance.json").exists() {
+        return Ok(());
+    }
 
+    minecraft_instance::with_locked(instance_path, |existing_instance| {
         existing_instance.instanceName = instance.instanceName;
         existing_instance.accountUuid = instance.accountUuid;
-
-        // Guardar la instancia actualizada
-        existing_instance
-            .save()
-            .map_err(|e| format!("Error saving instance: {}", e))?;
-    }
-
-    Ok(())
+        existing_instance.envVars = instance.envVars;
+        existing_instance.wrapperCommand = instance.wrapperCommand;
+        existing_instance.hooks = instance.hooks;
+        existing_instance.resolution = instance.resolution;
+        existing_instance.isolation = instance.isolation;
+        existing_instance.notes = instance.notes.filter(|notes| !notes.trim().is_empty());
+        existing_instance.color = normalize_instance_color(instance.color)?;
+        existing_instance.appearanceHotReload = instance.appearanceHotReload;
+        existing_instance.memory = instance.memory;
+        existing_instance.gameLanguage = instance
+            .gameLanguage
+            .filter(|language| !language.trim().is_empty());
+        existing_instance.touch();
+
+        Ok(())
+    })
 }
 
 #[tauri::command]
 pub fn get_instance_by_id(instance_id: String) -> Result<Option<MinecraftInstance>, String> {
-    let config_manager = get_config_manager()
-        .lock()
-        .map_err(|_| "Failed to lock config manager mutex".to_string())?;
+    let config_manager = read_config();
 
     let config = config_manager.as_ref().map_err(|e| e.clone())?;
 
-    let instances_dir = config.get_instances_dir();
-
-    let instances: Vec<MinecraftInstance> =
-        get_instances(instances_dir.to_str().unwrap_or_default())?;
+    let instances: Vec<MinecraftInstance> = get_instances(config)?;
     Ok(instances.into_iter().find(|i| i.instanceId == instance_id))
 }
 
+/// `minecraft_instance::with_locked` for the instance with `instance_id`.
+pub fn update_instance_by_id<R>(
+    instance_id: &str,
+    update: impl FnOnce(&mut MinecraftInstance) -> Result<R, String>,
+) -> Result<R, String> {
+    let instance = get_instance_by_id(instance_id.to_string())?
+        .ok_or_else(|| format!("Instance with ID {} not found", instance_id))?;
+    let directory = instance
+        .instanceDirectory
+        .ok_or_else(|| "Instance directory is missing".to_string())?;
+    minecraft_instance::with_locked(Path::new(&directory), update)
+}
+
 #[tauri::command]
 pub fn delete_instance(instance_path: String) -> Result<(), String> {
     let path = Path::new(&instance_path);
     if path.exists() && path.is_dir() {
         fs::remove_dir_all(path).map_err(|e| format!("Failed to delete instance: {}", e))?;
     }
+    instance_locations::unregister(path);
     Ok(())
 }
 
+/// Starts the game, or queues the launch if the instance is still being
+/// installed or updated (see `launch_queue`).
 #[tauri::command]
-pub fn launch_mc_instance(instance_id: String) -> Result<(), String> {
-    let config_manager = get_config_manager()
-        .lock()
-        .map_err(|_| "Failed to lock config manager mutex".to_string())?;
+pub fn launch_mc_instance(
+    events: State<'_, EventEmitter>,
+    instance_id: String,
+    options: Option<LaunchOptions>,
+) -> Result<LaunchRequest, String> {
+    let config_manager = read_config();
 
     let config = config_manager.as_ref().map_err(|e| e.clone())?;
 
-    let instances_dir = config.get_instances_dir();
-
-    let instances = get_instances(instances_dir.to_str().unwrap_or_default())?;
+    let instances = get_instances(config)?;
 
     let instance = instances
         .into_iter()
         .find(|i| i.instanceId == instance_id)
         .ok_or_else(|| format!("Instance with ID {} not found", instance_id))?;
 
-    instance
-        .launch()
-        .map_err(|e| format!("Failed to launch instance: {}", e))?;
+    check_prelaunch_notice(&instance)?;
+
+    let options = options.unwrap_or_default();
+    if let Some(blocker) = launch_queue::blocker(&instance_id) {
+        return Ok(launch_queue::enqueue(
+            events.inner().clone(),
+            instance_id,
+            options,
+            blocker,
+        ));
+    }
 
+    start_launch(events.inner().clone(), &instance, options)?;
+
+    Ok(LaunchRequest::started(instance_id))
+}
+
+// El modpack exige aceptar un aviso (changelog, normas...) antes de jugar
+fn check_prelaunch_notice(instance: &MinecraftInstance) -> Result<(), String> {
+    if let Some(pending) = prelaunch_appearance::pending_notice(instance) {
+        log::info!(
+            "Launch blocked for {}: notice {} not accepted",
+            instance.instanceId,
+            pending.version
+        );
+        events::emit(LauncherEvent::NoticeRequired(pending));
+        return Err("Debes aceptar el aviso del modpack antes de jugar".to_string());
+    }
     Ok(())
 }
 
-fn get_instances(instances_dir: &str) -> Result<Vec<MinecraftInstance>, String> {
-    let path = Path::new(instances_dir);
+/// Starts the launch thread of `instance`, checking the pre-launch notice
+/// again (a queued launch may follow an update that brought a new one).
+pub(crate) fn start_launch(
+    events: EventEmitter,
+    instance: &MinecraftInstance,
+    options: LaunchOptions,
+) -> Result<(), String> {
+    check_prelaunch_notice(instance)?;
 
-    if !path.exists() || !path.is_dir() {
-        return Ok(Vec::new());
+    if options.demo {
+        log::info!("Launching {} in demo mode", instance.instanceId);
     }
 
+    instance
+        .launch_with_options(events, options)
+        .map_err(|e| format!("Failed to launch instance: {}", e))
+}
+
+/// Instances of every configured root, plus the ones registered outside
+/// them (see `instance_locations`). The registry is pruned once with the
+/// files of all roots so one root never evicts another's entries.
+fn get_instances(config: &ConfigManager) -> Result<Vec<MinecraftInstance>, String> {
     let mut instances = Vec::new();
+    let mut seen = HashSet::new();
+
+    for root in config.get_instance_roots() {
+        // Un disco desconectado no debe ocultar las instancias del resto
+        if let Err(e) = scan_root(&root, &mut instances, &mut seen) {
+            log::warn!("Skipping instances root {}: {}", root.display(), e);
+        }
+    }
+    for location in config.get_instance_locations() {
+        if location.join("instance.json").exists() {
+            load_instance_dir(&location, &mut instances, &mut seen);
+        } else {
+            log::warn!("Registered instance not found: {}", location.display());
+        }
+    }
+
+    instance_registry::retain(&seen);
+    Ok(instances)
+}
+
+fn scan_root(
+    path: &Path,
+    instances: &mut Vec<MinecraftInstance>,
+    seen: &mut HashSet<PathBuf>,
+) -> Result<(), String> {
+    if !path.exists() || !path.is_dir() {
+        return Ok(());
+    }
 
     for entry in fs::read_dir(path).map_err(|e| format!("Error reading directory: {}", e))? {
         let entry = entry.map_err(|e| format!("Error reading entry: {}", e))?;
         let instance_path = entry.path();
 
-        if instance_path.is_dir() {
-            let config_file = instance_path.join("instance.json");
+        if instance_path.is_dir() && instance_path.join("instance.json").exists() {
+            load_instance_dir(&instance_path, instances, seen);
+        }
+    }
+
+    Ok(())
+}
+
+fn load_instance_dir(
+    instance_path: &Path,
+    instances: &mut Vec<MinecraftInstance>,
+    seen: &mut HashSet<PathBuf>,
+) {
+    let config_file = instance_path.join("instance.json");
+    // Una carpeta registrada que además está en una raíz se lista una vez
+    if !seen.insert(config_file.clone()) {
+        return;
+    }
+    // Una instancia irreparable no debe ocultar al resto (ver instance_schema)
+    let mut instance = match instance_registry::load(&config_file) {
+        Ok(instance) => instance,
+        Err(e) => {
+            log::warn!("Skipping instance {}: {}", instance_path.display(), e);
+            return;
+        }
+    };
+
+    // Normalizar las rutas; sólo se reescribe el archivo si cambian
+    let instance_directory = normalize_path(instance_path);
+    let minecraft_path = normalize_path(&instance_path.join("minecraft"));
+    let paths_changed = instance.instanceDirectory.as_deref() != Some(instance_directory.as_str())
+        || instance.minecraftPath != minecraft_path;
 
-            if config_file.exists() {
-                let contents = fs::read_to_string(&config_file)
-                    .map_err(|e| format!("Error reading JSON: {}", e))?;
+    if paths_changed {
+        instance.instanceDirectory = Some(instance_directory);
+        instance.minecraftPath = minecraft_path;
 
-                let mut instance: MinecraftInstance =
-                    from_str(&contents).map_err(|e| format!("Error parsing JSON: {}", e))?;
+        // Manejamos los errores al guardar
+        match instance.save() {
+            Ok(()) => instance_registry::remember(&config_file, &instance),
+            Err(e) => println!("Warning: Failed to save instance config: {}", e),
+        }
+    }
 
-                // Normalizar la ruta del directorio de la instancia
-                instance.instanceDirectory = Some(normalize_path(&instance_path));
+    instance_images::resolve_image_urls(&mut instance);
+    instances.push(instance);
+}
 
-                // Normalizar la ruta de Minecraft
-                let minecraft_path = instance_path.join("minecraft");
-                instance.minecraftPath = normalize_path(&minecraft_path);
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceRoot {
+    pub path: String,
+    /// La carpeta `instancesDir`; las nuevas instancias van aquí por defecto
+    pub primary: bool,
+    pub exists: bool,
+    pub free_space_mb: Option<u64>,
+    pub instance_count: usize,
+}
 
-                // Manejamos los errores al guardar
-                if let Err(e) = instance.save() {
-                    println!("Warning: Failed to save instance config: {}", e);
-                }
+fn describe_root(path: &Path, primary: bool) -> InstanceRoot {
+    // Si la carpeta aún no existe medimos el primer ancestro existente
+    let existing = path
+        .ancestors()
+        .find(|p| p.exists())
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| path.to_path_buf());
+    let instance_count = fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.path().join("instance.json").is_file())
+                .count()
+        })
+        .unwrap_or(0);
+
+    InstanceRoot {
+        path: path.to_string_lossy().to_string(),
+        primary,
+        exists: path.is_dir(),
+        free_space_mb: free_disk_space_mb(&existing),
+        instance_count,
+    }
+}
 
-                instances.push(instance);
+/// Configured instance roots with their free space, primary first.
+#[tauri::command]
+pub fn get_instance_roots() -> Result<Vec<InstanceRoot>, String> {
+    let roots = {
+        let config_manager = read_config();
+        let config = config_manager.as_ref().map_err(|e| e.clone())?;
+        config.get_instance_roots()
+    };
+
+    Ok(roots
+        .iter()
+        .enumerate()
+        .map(|(index, root)| describe_root(root, index == 0))
+        .collect())
+}
+
+/// Root a new instance goes into: `root` if given (it must be one of the
+/// configured roots), the primary one otherwise.
+pub(crate) fn resolve_instance_root(root: Option<&str>) -> Result<PathBuf, String> {
+    let roots = {
+        let config_manager = read_config();
+        let config = config_manager.as_ref().map_err(|e| e.clone())?;
+        config.get_instance_roots()
+    };
+
+    let Some(root) = root.map(str::trim).filter(|root| !root.is_empty()) else {
+        return Ok(roots[0].clone());
+    };
+    let requested = Path::new(root);
+    roots
+        .into_iter()
+        .find(|candidate| candidate == requested)
+        .ok_or_else(|| {
+            format!(
+                "La carpeta {} no es una ubicación de instancias configurada",
+                root
+            )
+        })
+}
+
+/// Folder a new instance goes into: `location` if given (any folder, e.g.
+/// on another drive; the instance is registered so it is still listed),
+/// otherwise the configured root from `resolve_instance_root`.
+pub(crate) fn resolve_instance_parent(
+    root: Option<&str>,
+    location: Option<&str>,
+) -> Result<PathBuf, String> {
+    match location.filter(|location| !location.trim().is_empty()) {
+        Some(location) => instance_locations::prepare_location(location),
+        None => resolve_instance_root(root),
+    }
+}
+
+/// Runs the vanilla/Forge bootstrap for an instance reporting on `task_id`.
+/// Blocks until it finishes, so call it from a background thread.
+pub(crate) fn run_instance_bootstrap(
+    events: EventEmitter,
+    instance: &MinecraftInstance,
+    task_id: &str,
+    task_manager: &Arc<Mutex<TasksManager>>,
+) {
+    instance_state::transition(&instance.instanceId, InstanceState::Installing, None);
+
+    // Iniciar el bootstrap de la instancia
+    let mut bootstrap = InstanceBootstrap::new(events);
+
+    // Determinar si es una instancia vanilla o forge
+    let result = if instance.forgeVersion.is_some() {
+        // Si tiene forge version, usar el método para instancias forge
+        bootstrap.bootstrap_forge_instance(
+            instance,
+            Some(task_id.to_string()),
+            Some(Arc::clone(task_manager)),
+        )
+    } else {
+        // Si no tiene forge version, usar el método para instancias vanilla
+        bootstrap.bootstrap_vanilla_instance(
+            instance,
+            Some(task_id.to_string()),
+            Some(Arc::clone(task_manager)),
+        )
+    };
+
+    telemetry::record(TelemetryEvent::ModpackInstall {
+        minecraft_version: instance.minecraftVersion.clone(),
+        modpack_id: instance.modpackId.clone(),
+        loader: instance.forgeVersion.as_ref().map(|_| "forge".to_string()),
+        success: result.is_ok(),
+        error_code: result.as_ref().err().map(|_| "BootstrapFailed".to_string()),
+    });
+
+    match result {
+        Ok(_) => {
+            instance_state::transition(&instance.instanceId, InstanceState::Ready, None);
+
+            // Emit task completion event
+            if let Ok(mut tm) = task_manager.lock() {
+                tm.update_task(
+                    task_id,
+                    TaskStatus::Completed,
+                    100.0,
+                    Message::new(keys::INSTANCE_CREATED)
+                        .param("instanceName", instance.instanceName.clone()),
+                    Some(serde_json::json!({
+                        "instanceName": instance.instanceName.clone(),
+                        "instanceId": instance.instanceId.clone()
+                    })),
+                );
+            }
+
+            println!("Instance creation completed: {:?}", instance);
+        }
+        Err(e) => {
+            eprintln!("Error during bootstrap: {}", e);
+            instance_state::transition(
+                &instance.instanceId,
+                InstanceState::Failed,
+                Some(e.clone()),
+            );
+            // Actualizar el estado de la tarea a fallido
+            if let Ok(mut tm) = task_manager.lock() {
+                tm.update_task(
+                    task_id,
+                    TaskStatus::Failed,
+                    0.0,
+                    Message::new(keys::INSTANCE_BOOTSTRAP_FAILED).param("error", e.clone()),
+                    Some(serde_json::json!({
+                        "instanceName": instance.instanceName.clone(),
+                        "instanceId": instance.instanceId.clone(),
+                        "error": e
+                    })),
+                );
             }
         }
     }
 
-    Ok(instances)
+    std::thread::sleep(std::time::Duration::from_secs(60));
+    if let Ok(mut tm) = task_manager.lock() {
+        tm.remove_task(task_id);
+    }
 }
 
 #[tauri::command]
 pub async fn create_local_instance(
+    events: State<'_, EventEmitter>,
     instance_name: String,
     mc_version: String,
     forge_version: Option<String>,
+    root: Option<String>,
+    location: Option<String>,
+    memory: Option<u32>,
 ) -> Result<String, String> {
-    // Obtener el directorio de instancias
-    let instances_dir = {
-        let config_manager = get_config_manager()
-            .lock()
-            .map_err(|_| "Failed to lock config manager mutex".to_string())?;
-
-        let config = config_manager.as_ref().map_err(|e| e.clone())?;
-
-        config.get_instances_dir()
-    };
+    validate_instance_memory(memory)?;
+    // Obtener el directorio de instancias elegido
+    let instances_dir = resolve_instance_parent(root.as_deref(), location.as_deref())?;
 
     // Creamos una instancia de Minecraft
     let mut instance = MinecraftInstance::new();
@@ -206,6 +536,9 @@ pub async fn create_local_instance(
     instance.forgeVersion = forge_version.clone();
     instance.instanceId = uuid::Uuid::new_v4().to_string();
 
+    // Sin valor propio se usa el ajuste global o la recomendación al lanzar
+    instance.memory = memory;
+
     let is_forge = instance.forgeVersion.is_some();
 
     let DEFAULT_VANILLA_ICON = "/images/default_instances/default_vanilla.webp";
@@ -232,6 +565,7 @@ pub async fn create_local_instance(
 
     // Set the instance directory (normalizado)
     instance.instanceDirectory = Some(normalize_path(&instance_dir));
+    instance_locations::register(&instance_dir)?;
 
     // Guardamos la instancia inicialmente
     instance
@@ -239,11 +573,13 @@ pub async fn create_local_instance(
         .map_err(|e| format!("Failed to save instance: {}", e))?;
 
     // Creamos el task manager y lo envolvemos en Arc<Mutex<>> para compartirlo entre hilos
-    let task_manager = Arc::new(Mutex::new(TasksManager::new()));
+    let task_manager = Arc::new(Mutex::new(TasksManager::new(events.inner().clone())));
     let task_id = {
         let mut tm = task_manager.lock().unwrap();
-        tm.add_task(
+        tm.add_instance_task(
             &format!("Creando instancia {}", instance.instanceName),
+            TaskKind::InstanceBootstrap,
+            &instance.instanceId,
             Some(serde_json::json!({
                 "instanceName": instance.instanceName.clone(),
                 "instanceId": instance.instanceId.clone()
@@ -258,7 +594,7 @@ pub async fn create_local_instance(
             &task_id,
             TaskStatus::Running,
             10.0,
-            "Creando metadatos",
+            Message::new(keys::INSTANCE_CREATING_METADATA),
             Some(serde_json::json!({
                 "instanceName": instance.instanceName.clone(),
                 "instanceId": instance.instanceId.clone()
@@ -272,101 +608,114 @@ pub async fn create_local_instance(
         fs::create_dir_all(&instance_path)
             .map_err(|e| format!("Failed to create instance directory: {}", e))?;
     }
-    let instance_json_path = instance_path.join("instance.json");
-    fs::write(
-        &instance_json_path,
-        serde_json::to_string(&instance).unwrap(),
-    )
-    .map_err(|e| format!("Failed to write instance.json: {}", e))?;
+    instance
+        .save()
+        .map_err(|e| format!("Failed to write instance.json: {}", e))?;
 
     // Clone los datos necesarios para el hilo
     let instance_clone = instance.clone();
     let task_id_clone = task_id.clone();
     let task_manager_clone = Arc::clone(&task_manager);
+    let events = events.inner().clone();
 
     // Lanzar el proceso en segundo plano
     std::thread::spawn(move || {
-        // Iniciar el bootstrap de la instancia
-        let mut bootstrap = InstanceBootstrap::new();
-
-        // Determinar si es una instancia vanilla o forge
-        let result = if instance_clone.forgeVersion.is_some() {
-            // Si tiene forge version, usar el método para instancias forge
-            bootstrap.bootstrap_forge_instance(
-                &instance_clone,
-                Some(task_id_clone.clone()),
-                Some(Arc::clone(&task_manager_clone)),
-            )
-        } else {
-            // Si no tiene forge version, usar el método para instancias vanilla
-            bootstrap.bootstrap_vanilla_instance(
-                &instance_clone,
-                Some(task_id_clone.clone()),
-                Some(Arc::clone(&task_manager_clone)),
-            )
-        };
-
-        match result {
-            Ok(_) => {
-                // Emit task completion event
-                if let Ok(mut tm) = task_manager_clone.lock() {
-                    tm.update_task(
-                        &task_id_clone,
-                        TaskStatus::Completed,
-                        100.0,
-                        &format!("Instancia {} creada", instance_clone.instanceName),
-                        Some(serde_json::json!({
-                            "instanceName": instance_clone.instanceName.clone(),
-                            "instanceId": instance_clone.instanceId.clone()
-                        })),
-                    );
-                }
-
-                println!("Instance creation completed: {:?}", instance_clone);
-            }
-            Err(e) => {
-                eprintln!("Error during bootstrap: {}", e);
-                // Actualizar el estado de la tarea a fallido
-                if let Ok(mut tm) = task_manager_clone.lock() {
-                    tm.update_task(
-                        &task_id_clone,
-                        TaskStatus::Failed,
-                        0.0,
-                        &format!("Error en bootstrap: {}", e),
-                        Some(serde_json::json!({
-                            "instanceName": instance_clone.instanceName.clone(),
-                            "instanceId": instance_clone.instanceId.clone(),
-                            "error": e
-                        })),
-                    );
-                }
-            }
-        }
-
-        std::thread::sleep(std::time::Duration::from_secs(60));
-        if let Ok(mut tm) = task_manager_clone.lock() {
-            tm.remove_task(&task_id_clone);
-        }
+        // Esperar turno si ya hay otras instancias instalándose
+        let _slot = tasks_manager::wait_for_slot(
+            &task_id_clone,
+            TaskPriority::User,
+            true,
+            &task_manager_clone,
+        );
+        run_instance_bootstrap(events, &instance_clone, &task_id_clone, &task_manager_clone);
     });
 
     // Devolvemos inmediatamente una respuesta con el ID de la instancia
     Ok(instance.instanceId)
 }
 
+#[tauri::command]
+pub fn get_interrupted_tasks() -> Vec<TaskInfo> {
+    tasks_manager::interrupted_tasks()
+}
+
+/// Continues the bootstrap of every interrupted task that can be resumed.
+/// The rest stay listed until they are discarded. Returns the resumed ids.
+#[tauri::command]
+pub fn resume_interrupted_tasks(events: State<'_, EventEmitter>) -> Result<Vec<String>, String> {
+    let mut resumed = Vec::new();
+
+    for task in tasks_manager::interrupted_tasks() {
+        if !task.is_resumable() {
+            log::info!("[Tasks] Task {} cannot be resumed, skipping", task.id);
+            continue;
+        }
+
+        let instance_id = task.instance_id.clone().unwrap_or_default();
+        let Some(task) = tasks_manager::take_interrupted_task(&task.id) else {
+            continue;
+        };
+
+        let Some(instance) = get_instance_by_id(instance_id.clone())? else {
+            // La instancia ya no existe: no queda nada que retomar
+            log::warn!(
+                "[Tasks] Instance {} of task {} no longer exists",
+                instance_id,
+                task.id
+            );
+            continue;
+        };
+
+        let task_manager = Arc::new(Mutex::new(TasksManager::new(events.inner().clone())));
+        let task_id = task_manager.lock().unwrap().restore_task(task);
+        resumed.push(task_id.clone());
+        let events = events.inner().clone();
+
+        std::thread::spawn(move || {
+            let _slot =
+                tasks_manager::wait_for_slot(&task_id, TaskPriority::User, true, &task_manager);
+            run_instance_bootstrap(events, &instance, &task_id, &task_manager);
+        });
+    }
+
+    Ok(resumed)
+}
+
+/// Drops an interrupted task and deletes the half-built instance it left.
+#[tauri::command]
+pub fn discard_interrupted_task(task_id: String) -> Result<(), String> {
+    let task = tasks_manager::take_interrupted_task(&task_id)
+        .ok_or_else(|| format!("No hay ninguna tarea interrumpida con ID {}", task_id))?;
+
+    let (Some(_), Some(instance_id)) = (task.kind, task.instance_id) else {
+        return Ok(());
+    };
+
+    if let Some(directory) =
+        get_instance_by_id(instance_id.clone())?.and_then(|instance| instance.instanceDirectory)
+    {
+        log::info!(
+            "[Tasks] Removing partial instance {} at {}",
+            instance_id,
+            directory
+        );
+        fs::remove_dir_all(&directory)
+            .map_err(|e| format!("Failed to delete instance directory: {}", e))?;
+    }
+
+    Ok(())
+}
+
 #[tauri::command]
 // Returns bool
 pub async fn remove_instance(instance_id: String) -> Result<bool, String> {
     // Obtener la información necesaria antes de las operaciones asíncronas
     let instance_directory = {
-        let config_manager = get_config_manager()
-            .lock()
-            .map_err(|_| "Failed to lock config manager mutex".to_string())?;
+        let config_manager = read_config();
 
         let config = config_manager.as_ref().map_err(|e| e.clone())?;
 
-        let instances_dir = config.get_instances_dir();
-
-        let instances = get_instances(instances_dir.to_str().unwrap_or_default())?;
+        let instances = get_instances(config)?;
 
         let instance = instances
             .into_iter()
@@ -380,10 +729,12 @@ pub async fn remove_instance(instance_id: String) -> Result<bool, String> {
     // Delete the instance directory asynchronously
     if let Some(directory) = instance_directory {
         // Usar spawn_blocking para operaciones de I/O intensivas
+        let removed = directory.clone();
         let result = tokio::task::spawn_blocking(move || std::fs::remove_dir_all(&directory))
             .await
             .map_err(|e| format!("Task join error: {}", e))?
             .map_err(|e| format!("Failed to delete instance directory: {}", e))?;
+        instance_locations::unregister(Path::new(&removed));
     }
 
     Ok(true)
@@ -391,24 +742,15 @@ pub async fn remove_instance(instance_id: String) -> Result<bool, String> {
 
 #[tauri::command]
 pub async fn search_instances(query: String) -> Result<Vec<MinecraftInstance>, String> {
-    let config_manager = get_config_manager()
-        .lock()
-        .map_err(|_| "Failed to lock config manager mutex".to_string())?;
+    let config_manager = read_config();
 
     let config = config_manager.as_ref().map_err(|e| e.clone())?;
 
-    let instances_dir = config.get_instances_dir();
-
-    // Obtener la ruta segura como str
-    let dir_path = instances_dir
-        .to_str()
-        .ok_or_else(|| "Invalid instances directory path".to_string())?;
-
     // Convertir la consulta a minúsculas para hacer la búsqueda case-insensitive
     let query_lowercase = query.to_lowercase();
 
     // Buscar instancias
-    let instances = get_instances(dir_path)?;
+    let instances = get_instances(config)?;
 
     // Filtrar instancias de manera más flexible
     let filtered_instances: Vec<MinecraftInstance> = if query.is_empty() {
@@ -440,52 +782,40 @@ pub async fn search_instances(query: String) -> Result<Vec<MinecraftInstance>, S
 
 #[tauri::command]
 pub async fn update_modpack_instance(
+    events: State<'_, EventEmitter>,
     instance_id: String,
     modpack_id: String,
     password: Option<String>,
 ) -> Result<(), String> {
-    use tauri::Emitter;
-    
     log::info!("Starting modpack update for instance {} with modpack {}", instance_id, modpack_id);
     
     // Get the instance first to validate it exists
-    let mut instance = get_instance_by_id(instance_id.clone())?
+    let instance = get_instance_by_id(instance_id.clone())?
         .ok_or_else(|| format!("Instance with ID {} not found", instance_id))?;
     
-    // Check if we need to handle "latest" version
-    let should_update = if let Some(modpack_info) = &instance.modpackInfo {
-        if let Some(version_id) = &modpack_info.modpackVersionId {
-            if version_id == "latest" {
-                log::info!("Instance has 'latest' version, checking for updates...");
-                // TODO: Make API call to check for newer version
-                // For now, we'll always update if version is "latest"
-                true
-            } else {
-                // TODO: Compare current version with available versions
-                // For now, we'll always update for non-latest versions too
-                true
-            }
-        } else {
-            false
-        }
-    } else {
-        false
-    };
-
-    if !should_update {
+    // El canal y la versión fijada deciden a qué versión se actualiza
+    let Some(target) = modpack_updates::check_for_update(&instance).await? else {
         log::info!("No update needed for instance {}", instance_id);
         return Ok(());
-    }
+    };
+    log::info!(
+        "Updating instance {} to modpack version {} ({:?})",
+        instance_id,
+        target.version,
+        target.channel
+    );
+
+    instance_state::transition(&instance_id, InstanceState::Updating, None);
     
     // Emit event to update frontend status
-    if let Ok(guard) = GLOBAL_APP_HANDLE.lock() {
-        if let Some(app_handle) = guard.as_ref() {
-            let _ = app_handle.emit("instance-downloading-modpack-assets", serde_json::json!({
-                "id": instance_id,
-                "message": "Iniciando actualización del modpack..."
-            }));
-        }
-    }
+    let events = events.inner().clone();
+    events.send(LauncherEvent::instance(
+        names::INSTANCE_DOWNLOADING_MODPACK_ASSETS,
+        instance_id.clone(),
+        None,
+        Message::new(keys::MODPACK_UPDATING),
+        None,
+    ));
     
     // TODO: Implement password validation if provided
     if password.is_some() {
@@ -495,32 +825,29 @@ pub async fn update_modpack_instance(
         tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
     }
     
-    // TODO: Implement actual modpack update logic here
-    // This would include:
-    // 1. Download new modpack manifest
-    // 2. Compare with existing files
-    // 3. Download missing/updated files
-    // 4. Update instance configuration
-    
-    // For now, simulate the update process
-    tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
-    
-    // Validate modpack assets after update
-    let bootstrap = crate::core::instance_bootstrap::InstanceBootstrap::new();
-    if let Err(e) = bootstrap.validate_modpack_assets(&instance, None, None) {
-        log::warn!("Failed to validate modpack assets: {}", e);
+    let instance = match modpack_updates::apply_update(events.clone(), &instance, &target).await {
+        Ok(instance) => instance,
+        Err(e) => {
+            log::error!("Failed to update instance {}: {}", instance_id, e);
+            instance_state::transition(&instance_id, InstanceState::Failed, Some(e.clone()));
+            return Err(e);
+        }
+    };
+    // La descripción puede haber cambiado con la nueva versión
+    if let Err(e) = instance_readme::refresh(&instance).await {
+        log::warn!("Failed to refresh modpack description: {}", e);
     }
     
     // Emit completion event
-    if let Ok(guard) = GLOBAL_APP_HANDLE.lock() {
-        if let Some(app_handle) = guard.as_ref() {
-            let _ = app_handle.emit("instance-finish-assets-download", serde_json::json!({
-                "id": instance_id,
-                "message": "Actualización del modpack completada"
-            }));
-        }
-    }
+    events.send(LauncherEvent::instance(
+        names::INSTANCE_FINISH_ASSETS_DOWNLOAD,
+        instance_id.clone(),
+        None,
+        Message::new(keys::MODPACK_UPDATED),
+        None,
+    ));
     
+    instance_state::transition(&instance_id, InstanceState::Ready, None);
     log::info!("Modpack update completed for instance {}", instance_id);
     Ok(())
 }
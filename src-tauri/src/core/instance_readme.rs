@@ -0,0 +1,159 @@
+//! Offline copy of the modpack's description (`modpack_readme.json`).
+
+use crate::core::api_client::ApiClient;
+use crate::core::instance_manager::get_instance_by_id;
+use crate::core::minecraft_instance::MinecraftInstance;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const README_FILE: &str = "modpack_readme.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceReadme {
+    pub modpack_id: String,
+    /// Versión del modpack instalada cuando se guardó
+    pub modpack_version: Option<String>,
+    pub name: Option<String>,
+    /// Markdown
+    pub content: String,
+    /// Notas de instalación del publisher, en markdown
+    pub install_notes: Option<String>,
+    /// RFC 3339
+    pub fetched_at: String,
+}
+
+/// Fields of `/explore/modpack/{id}` used for the readme.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ModpackDetails {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    readme: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    short_description: Option<String>,
+    #[serde(default)]
+    install_notes: Option<String>,
+}
+
+fn readme_path(instance: &MinecraftInstance) -> Option<PathBuf> {
+    let instance_dir = instance.instanceDirectory.as_deref()?;
+    Some(Path::new(instance_dir).join(README_FILE))
+}
+
+fn installed_version(instance: &MinecraftInstance) -> Option<String> {
+    let info = instance.modpackInfo.as_ref()?;
+    info.version
+        .clone()
+        .or_else(|| info.modpackVersionId.clone())
+}
+
+fn read_cached(instance: &MinecraftInstance) -> Option<InstanceReadme> {
+    let content = fs::read(readme_path(instance)?).ok()?;
+    match serde_json::from_slice::<InstanceReadme>(&content) {
+        // Una copia vacía no sirve: se vuelve a pedir
+        Ok(readme) if readme.content.trim().is_empty() => None,
+        Ok(readme) => Some(readme),
+        Err(e) => {
+            log::warn!(
+                "[Readme] Ignoring invalid {} of {}: {}",
+                README_FILE,
+                instance.instanceId,
+                e
+            );
+            None
+        }
+    }
+}
+
+fn non_empty(text: Option<String>) -> Option<String> {
+    text.filter(|text| !text.trim().is_empty())
+}
+
+/// Downloads the modpack's description and saves it in the instance
+/// folder. Returns `None` for instances that don't come from a modpack,
+/// and an error when the API sends no description.
+pub async fn refresh(instance: &MinecraftInstance) -> Result<Option<InstanceReadme>, String> {
+    let (Some(modpack_id), Some(path)) = (&instance.modpackId, readme_path(instance)) else {
+        return Ok(None);
+    };
+
+    let body = ApiClient::from_global()?
+        .get_public(&format!("/explore/modpack/{}", modpack_id))
+        .await?
+        .error_for_status()
+        .map_err(|e| format!("Error al obtener la descripción del modpack: {}", e))?
+        .json::<Value>()
+        .await
+        .map_err(|e| format!("Descripción del modpack inválida: {}", e))?;
+    // La API envuelve el modpack en `data`
+    let details = body.get("data").cloned().unwrap_or(body);
+    let details: ModpackDetails = serde_json::from_value(details)
+        .map_err(|e| format!("Descripción del modpack inválida: {}", e))?;
+
+    let content = non_empty(details.readme)
+        .or_else(|| non_empty(details.description))
+        .or_else(|| non_empty(details.short_description))
+        .ok_or_else(|| "El modpack no tiene descripción".to_string())?;
+    let readme = InstanceReadme {
+        modpack_id: modpack_id.clone(),
+        modpack_version: installed_version(instance),
+        name: details.name,
+        content,
+        install_notes: non_empty(details.install_notes),
+        fetched_at: chrono::Utc::now().to_rfc3339(),
+    };
+
+    let json = serde_json::to_string_pretty(&readme)
+        .map_err(|e| format!("Error al serializar la descripción: {}", e))?;
+    fs::write(&path, json).map_err(|e| format!("Error al guardar {}: {}", path.display(), e))?;
+    log::info!(
+        "[Readme] Saved the description of {} for instance {}",
+        modpack_id,
+        instance.instanceId
+    );
+    Ok(Some(readme))
+}
+
+/// Fetches the description in the background if the instance has none yet
+/// or it belongs to another version of the modpack.
+pub fn ensure_cached(instance: &MinecraftInstance) {
+    if instance.modpackId.is_none() {
+        return;
+    }
+    let current = read_cached(instance)
+        .is_some_and(|readme| readme.modpack_version == installed_version(instance));
+    if current {
+        return;
+    }
+
+    let instance = instance.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = refresh(&instance).await {
+            log::warn!(
+                "[Readme] Could not fetch the description of instance {}: {}",
+                instance.instanceId,
+                e
+            );
+        }
+    });
+}
+
+/// Description of the instance's modpack. The saved copy is returned when
+/// there is one; otherwise it is fetched (and saved) now. `None` for
+/// instances that don't come from a modpack.
+#[tauri::command]
+pub async fn get_instance_readme(instance_id: String) -> Result<Option<InstanceReadme>, String> {
+    let instance = get_instance_by_id(instance_id.clone())?
+        .ok_or_else(|| format!("Instance with ID {} not found", instance_id))?;
+
+    if let Some(readme) = read_cached(&instance) {
+        return Ok(Some(readme));
+    }
+    refresh(&instance).await
+}
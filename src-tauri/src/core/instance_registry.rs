@@ -0,0 +1,121 @@
+//! In-memory cache of parsed `instance.json` files.
+
+use crate::core::instance_schema;
+use crate::core::minecraft::paths;
+use crate::core::minecraft_instance::MinecraftInstance;
+use once_cell::sync::Lazy;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::SystemTime;
+
+struct CachedInstance {
+    modified: SystemTime,
+    len: u64,
+    instance: MinecraftInstance,
+}
+
+static REGISTRY: Lazy<Mutex<HashMap<PathBuf, CachedInstance>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn lock_registry() -> MutexGuard<'static, HashMap<PathBuf, CachedInstance>> {
+    REGISTRY.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn file_stamp(config_file: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = fs::metadata(config_file).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Parsed contents of `config_file`, re-read only if it changed on disk.
+pub fn load(config_file: &Path) -> Result<MinecraftInstance, String> {
+    load_with(config_file, MinecraftInstance::save)
+}
+
+/// `load` for a caller already holding the file's lock (see
+/// `minecraft_instance::with_locked`): a migrated file is written without
+/// locking it again.
+pub fn load_locked(config_file: &Path) -> Result<MinecraftInstance, String> {
+    load_with(config_file, |instance| instance.write_to(config_file))
+}
+
+fn load_with(
+    config_file: &Path,
+    save: impl FnOnce(&MinecraftInstance) -> io::Result<()>,
+) -> Result<MinecraftInstance, String> {
+    let stamp = file_stamp(config_file);
+
+    if let Some((modified, len)) = stamp {
+        if let Some(cached) = lock_registry().get(config_file) {
+            if cached.modified == modified && cached.len == len {
+                return Ok(cached.instance.clone());
+            }
+        }
+    }
+
+    let contents =
+        fs::read_to_string(config_file).map_err(|e| format!("Error reading JSON: {}", e))?;
+    let (instance, migrated, report) = instance_schema::parse(&contents, config_file);
+    let Some(mut instance) = instance else {
+        return Err(format!(
+            "instance.json no válido ({}): {}",
+            config_file.display(),
+            report.errors.join("; ")
+        ));
+    };
+
+    // Se reescribe en el formato actual para no migrarla en cada arranque
+    let mut stamp = stamp;
+    if migrated {
+        if instance.instanceDirectory.is_none() {
+            instance.instanceDirectory = config_file.parent().map(paths::stored_path);
+        }
+        match save(&instance) {
+            Ok(()) => stamp = file_stamp(config_file),
+            Err(e) => log::warn!(
+                "[InstanceRegistry] Could not save migrated {}: {}",
+                config_file.display(),
+                e
+            ),
+        }
+    }
+    if report.needs_attention() {
+        instance.validation = Some(report);
+    }
+
+    if let Some((modified, len)) = stamp {
+        remember_with_stamp(config_file, modified, len, &instance);
+    }
+    Ok(instance)
+}
+
+fn remember_with_stamp(
+    config_file: &Path,
+    modified: SystemTime,
+    len: u64,
+    instance: &MinecraftInstance,
+) {
+    lock_registry().insert(
+        config_file.to_path_buf(),
+        CachedInstance {
+            modified,
+            len,
+            instance: instance.clone(),
+        },
+    );
+}
+
+/// Stores an instance that was just saved to `config_file`, so the write
+/// does not force a re-read on the next listing.
+pub fn remember(config_file: &Path, instance: &MinecraftInstance) {
+    if let Some((modified, len)) = file_stamp(config_file) {
+        remember_with_stamp(config_file, modified, len, instance);
+    }
+}
+
+/// Forgets instances whose `instance.json` was not seen in the last listing.
+pub fn retain(seen: &HashSet<PathBuf>) {
+    lock_registry().retain(|config_file, _| seen.contains(config_file));
+}
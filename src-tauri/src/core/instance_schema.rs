@@ -0,0 +1,255 @@
+//! Versioned `instance.json` schema and the migrations between versions.
+
+use crate::core::minecraft::paths;
+use crate::core::minecraft_instance::MinecraftInstance;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use uuid::Uuid;
+
+/// Versión del formato de instance.json que escribe este launcher
+pub const CURRENT_SCHEMA_VERSION: u32 = 2;
+
+/// Nombres de campos de versiones antiguas y su nombre actual
+const RENAMED_FIELDS: &[(&str, &str)] = &[
+    ("id", "instanceId"),
+    ("name", "instanceName"),
+    ("version", "minecraftVersion"),
+    ("forge", "forgeVersion"),
+];
+
+type Migration = fn(&mut Map<String, Value>, &Path, &mut Vec<String>);
+
+/// `MIGRATIONS[n]` lleva un archivo de la versión n a la n + 1
+const MIGRATIONS: &[Migration] = &[migrate_fields, migrate_types];
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceValidationReport {
+    pub instance_id: Option<String>,
+    pub config_file: String,
+    pub from_version: u32,
+    pub to_version: u32,
+    /// Cambios aplicados para poder cargar la instancia
+    pub repairs: Vec<String>,
+    /// Campos desconocidos, que se descartan
+    pub warnings: Vec<String>,
+    /// Problemas sin arreglo; la instancia no se carga
+    pub errors: Vec<String>,
+}
+
+impl InstanceValidationReport {
+    pub fn needs_attention(&self) -> bool {
+        !self.repairs.is_empty() || !self.warnings.is_empty() || !self.errors.is_empty()
+    }
+}
+
+static REPORTS: Lazy<Mutex<HashMap<PathBuf, InstanceValidationReport>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn reports() -> MutexGuard<'static, HashMap<PathBuf, InstanceValidationReport>> {
+    REPORTS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn record(config_file: &Path, report: &InstanceValidationReport) {
+    if report.needs_attention() {
+        reports().insert(config_file.to_path_buf(), report.clone());
+    }
+}
+
+/// v0 -> v1: old field names and required fields that early versions
+/// didn't always write.
+fn migrate_fields(json: &mut Map<String, Value>, instance_dir: &Path, repairs: &mut Vec<String>) {
+    for (old, new) in RENAMED_FIELDS {
+        if json.contains_key(*new) {
+            continue;
+        }
+        if let Some(value) = json.remove(*old) {
+            json.insert(new.to_string(), value);
+            repairs.push(format!("Campo '{}' renombrado a '{}'", old, new));
+        }
+    }
+
+    let mut fill = |key: &str, value: Value| {
+        let missing = match json.get(key) {
+            None | Some(Value::Null) => true,
+            Some(Value::String(s)) => s.trim().is_empty(),
+            Some(_) => false,
+        };
+        if missing {
+            repairs.push(format!("Campo obligatorio '{}' completado", key));
+            json.insert(key.to_string(), value);
+        }
+    };
+
+    // Determinista: releer el archivo sin guardarlo da el mismo id
+    let instance_id = Uuid::new_v3(
+        &Uuid::NAMESPACE_URL,
+        paths::stored_path(instance_dir).as_bytes(),
+    );
+    fill("instanceId", Value::String(instance_id.to_string()));
+    let folder_name = instance_dir
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    fill("instanceName", Value::String(folder_name));
+    fill(
+        "minecraftPath",
+        Value::String(paths::stored_path(&instance_dir.join("minecraft"))),
+    );
+    fill(
+        "instanceDirectory",
+        Value::String(paths::stored_path(instance_dir)),
+    );
+    fill("usesDefaultIcon", Value::Bool(false));
+}
+
+/// v1 -> v2: values that were saved with another type.
+fn migrate_types(json: &mut Map<String, Value>, _instance_dir: &Path, repairs: &mut Vec<String>) {
+    if let Some(Value::String(memory)) = json.get("memory") {
+        let parsed = memory
+            .trim()
+            .trim_end_matches(['M', 'm'])
+            .parse::<u32>()
+            .ok();
+        repairs.push(format!("Memoria '{}' convertida a número", memory));
+        json.insert(
+            "memory".to_string(),
+            parsed.map_or(Value::Null, Value::from),
+        );
+    }
+
+    if let Some(Value::String(flag)) = json.get("usesDefaultIcon") {
+        let flag = flag.eq_ignore_ascii_case("true");
+        repairs.push("'usesDefaultIcon' convertido a booleano".to_string());
+        json.insert("usesDefaultIcon".to_string(), Value::Bool(flag));
+    }
+
+    // Antes las variables de entorno se guardaban como lista "CLAVE=valor"
+    if let Some(Value::Array(entries)) = json.get("envVars") {
+        let vars: Map<String, Value> = entries
+            .iter()
+            .filter_map(Value::as_str)
+            .filter_map(|entry| entry.split_once('='))
+            .map(|(key, value)| (key.trim().to_string(), Value::String(value.to_string())))
+            .filter(|(key, _)| !key.is_empty())
+            .collect();
+        repairs.push("'envVars' convertido de lista a objeto".to_string());
+        json.insert("envVars".to_string(), Value::Object(vars));
+    }
+}
+
+/// Fields the current `MinecraftInstance` knows about.
+fn known_fields() -> Vec<String> {
+    match serde_json::to_value(MinecraftInstance::new()) {
+        Ok(Value::Object(fields)) => fields.keys().cloned().collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Migrates and parses the contents of `config_file`. Returns the instance
+/// (unless it can't be used), whether the file has to be rewritten and the
+/// report.
+pub fn parse(
+    contents: &str,
+    config_file: &Path,
+) -> (Option<MinecraftInstance>, bool, InstanceValidationReport) {
+    let mut report = InstanceValidationReport {
+        config_file: config_file.display().to_string(),
+        to_version: CURRENT_SCHEMA_VERSION,
+        ..Default::default()
+    };
+    let instance_dir = config_file.parent().unwrap_or(Path::new(""));
+
+    let mut json = match serde_json::from_str::<Value>(contents) {
+        Ok(Value::Object(json)) => json,
+        Ok(_) => {
+            report
+                .errors
+                .push("instance.json no es un objeto".to_string());
+            record(config_file, &report);
+            return (None, false, report);
+        }
+        Err(e) => {
+            report.errors.push(format!("JSON inválido: {}", e));
+            record(config_file, &report);
+            return (None, false, report);
+        }
+    };
+
+    report.from_version = json
+        .get("schemaVersion")
+        .and_then(Value::as_u64)
+        .map_or(0, |version| version as u32);
+    if report.from_version > CURRENT_SCHEMA_VERSION {
+        report.warnings.push(format!(
+            "Creada por un launcher más reciente (esquema v{})",
+            report.from_version
+        ));
+    }
+    for migration in MIGRATIONS.iter().skip(report.from_version as usize) {
+        migration(&mut json, instance_dir, &mut report.repairs);
+    }
+    let migrated = report.from_version < CURRENT_SCHEMA_VERSION;
+    if migrated {
+        json.insert(
+            "schemaVersion".to_string(),
+            Value::from(CURRENT_SCHEMA_VERSION),
+        );
+    }
+
+    let known = known_fields();
+    let mut unknown: Vec<&String> = json.keys().filter(|key| !known.contains(key)).collect();
+    unknown.sort();
+    report.warnings.extend(
+        unknown
+            .into_iter()
+            .map(|key| format!("Campo desconocido '{}'", key)),
+    );
+
+    report.instance_id = json
+        .get("instanceId")
+        .and_then(Value::as_str)
+        .map(String::from);
+    let instance = match serde_json::from_value::<MinecraftInstance>(Value::Object(json)) {
+        Ok(instance) => instance,
+        Err(e) => {
+            report
+                .errors
+                .push(format!("Campo con formato inválido: {}", e));
+            record(config_file, &report);
+            return (None, false, report);
+        }
+    };
+    if instance.minecraftVersion.trim().is_empty() {
+        report
+            .errors
+            .push("La instancia no indica su versión de Minecraft".to_string());
+        record(config_file, &report);
+        return (None, false, report);
+    }
+
+    if !report.repairs.is_empty() {
+        log::info!(
+            "[InstanceSchema] Repaired {} (v{} -> v{}): {}",
+            config_file.display(),
+            report.from_version,
+            report.to_version,
+            report.repairs.join("; ")
+        );
+    }
+    record(config_file, &report);
+    (Some(instance), migrated, report)
+}
+
+/// Instances that needed repairs or couldn't be loaded since the launcher
+/// started.
+#[tauri::command]
+pub fn get_instance_validation_reports() -> Vec<InstanceValidationReport> {
+    let mut reports: Vec<InstanceValidationReport> = reports().values().cloned().collect();
+    reports.sort_by(|a, b| a.config_file.cmp(&b.config_file));
+    reports
+}
@@ -0,0 +1,157 @@
+//! Explicit lifecycle state of each instance.
+
+use crate::core::events::{self, LauncherEvent};
+use crate::core::instance_manager::get_instance_by_id;
+use crate::core::launch_queue;
+use crate::core::minecraft::paths;
+use crate::core::process_registry::{self, ProcessState};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum InstanceState {
+    NotInstalled,
+    Installing,
+    Ready,
+    Updating,
+    Launching,
+    Running,
+    Failed,
+}
+
+impl InstanceState {
+    /// Transitions the launcher expects; anything else is logged but applied,
+    /// the backend is still the source of truth.
+    fn can_move_to(self, next: InstanceState) -> bool {
+        use InstanceState::*;
+        match self {
+            NotInstalled => matches!(next, Installing),
+            Installing => matches!(next, Ready | Failed),
+            Ready => matches!(next, Installing | Updating | Launching),
+            Updating => matches!(next, Ready | Failed),
+            Launching => matches!(next, Running | Ready | Failed),
+            Running => matches!(next, Ready | Failed),
+            Failed => matches!(next, Installing | Updating | Launching | Ready),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceStateInfo {
+    pub instance_id: String,
+    pub state: InstanceState,
+    pub previous: Option<InstanceState>,
+    /// Motivo del fallo cuando `state` es `failed`
+    pub error: Option<String>,
+    pub changed_at: String,
+}
+
+static STATES: Lazy<Mutex<HashMap<String, InstanceStateInfo>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn states() -> MutexGuard<'static, HashMap<String, InstanceStateInfo>> {
+    STATES.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Moves `instance_id` to `state` and notifies the frontend. Repeating the
+/// current state (without a new error) does nothing.
+pub fn transition(instance_id: &str, state: InstanceState, error: Option<String>) {
+    let info = {
+        let mut states = states();
+        let previous = states.get(instance_id);
+        if previous.is_some_and(|previous| previous.state == state && previous.error == error) {
+            return;
+        }
+        let previous = previous.map(|previous| previous.state);
+        if previous.is_some_and(|previous| !previous.can_move_to(state)) {
+            log::warn!(
+                "[InstanceState] Unexpected transition for {}: {:?} -> {:?}",
+                instance_id,
+                previous,
+                state
+            );
+        }
+
+        let info = InstanceStateInfo {
+            instance_id: instance_id.to_string(),
+            state,
+            previous,
+            error,
+            changed_at: chrono::Utc::now().to_rfc3339(),
+        };
+        states.insert(instance_id.to_string(), info.clone());
+        info
+    };
+
+    log::info!(
+        "[InstanceState] {}: {:?} -> {:?}",
+        instance_id,
+        info.previous,
+        info.state
+    );
+    let (state, error) = (info.state, info.error.clone());
+    events::emit(LauncherEvent::InstanceStateChanged(info));
+    launch_queue::on_state_changed(instance_id, state, error.as_deref());
+}
+
+/// Last state reported for the instance, without looking at the disk.
+pub fn current(instance_id: &str) -> Option<InstanceState> {
+    states().get(instance_id).map(|info| info.state)
+}
+
+/// Called when a launch attempt ends: if no process was started (and no
+/// error was reported) the instance goes back to `ready`.
+pub fn settle_launch(instance_id: &str) {
+    let launching = states()
+        .get(instance_id)
+        .is_some_and(|info| info.state == InstanceState::Launching);
+    if launching {
+        transition(instance_id, InstanceState::Ready, None);
+    }
+}
+
+/// State of an instance no code path has reported on yet.
+fn derive(instance_id: &str) -> Result<InstanceStateInfo, String> {
+    let process = process_registry::get_all()
+        .into_iter()
+        .find(|process| process.instance_id == instance_id);
+    let state = match process.map(|process| process.state) {
+        Some(ProcessState::Launching) => InstanceState::Launching,
+        Some(ProcessState::Running) => InstanceState::Running,
+        None => {
+            let instance = get_instance_by_id(instance_id.to_string())?
+                .ok_or_else(|| format!("Instance with ID {} not found", instance_id))?;
+            let client_jar = paths::version_file(
+                &paths::native_path(&instance.minecraftPath),
+                &instance.minecraftVersion,
+                "jar",
+            );
+            if client_jar.is_file() {
+                InstanceState::Ready
+            } else {
+                InstanceState::NotInstalled
+            }
+        }
+    };
+
+    Ok(InstanceStateInfo {
+        instance_id: instance_id.to_string(),
+        state,
+        previous: None,
+        error: None,
+        changed_at: chrono::Utc::now().to_rfc3339(),
+    })
+}
+
+/// Current state of the instance.
+#[tauri::command]
+pub fn get_instance_state(instance_id: String) -> Result<InstanceStateInfo, String> {
+    if let Some(info) = states().get(&instance_id).cloned() {
+        return Ok(info);
+    }
+    derive(&instance_id)
+}
@@ -1,14 +1,19 @@
+use crate::config::with_config;
+use crate::core::http_client;
+use crate::core::instance_manager::get_all_instances;
+use crate::core::process_registry;
+use crate::core::tasks_manager;
+use crate::core::zip_extract::ZipExtractor;
 use anyhow::{anyhow, Context, Result};
 use dirs;
 use flate2::read::GzDecoder;
 use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::fs::{self, create_dir_all, File};
 use std::io::{self, copy, Cursor, Read, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tar::Archive;
-use tauri_plugin_http::reqwest;
-use zip::ZipArchive;
 
 // Estructuras para deserializar la información de java
 #[derive(Debug, Deserialize)]
@@ -120,7 +125,7 @@ impl JavaManager {
         let temp_file = target_dir.join(format!("java_temp_archive.{}", extension));
 
         // Crear un cliente con tiempo de espera personalizado
-        let client = reqwest::Client::builder()
+        let client = http_client::async_builder()
             .timeout(std::time::Duration::from_secs(300)) // 5 minutos
             .build()?;
 
@@ -221,7 +226,9 @@ impl JavaManager {
 
         println!("Consultando API de Adoptium: {}", api_url);
 
-        let response = reqwest::get(&api_url)
+        let response = http_client::async_client()
+            .get(&api_url)
+            .send()
             .await
             .context("Error al consultar la API de Adoptium")?;
 
@@ -287,45 +294,10 @@ impl JavaManager {
 
     /// Extrae un archivo ZIP usando la biblioteca zip-rs
     fn extract_zip(&self, zip_path: &PathBuf, target_dir: &PathBuf) -> Result<()> {
-        let file = File::open(zip_path).context("No se pudo abrir el archivo ZIP")?;
-        let mut archive = ZipArchive::new(file).context("No se pudo leer el archivo ZIP")?;
-
-        for i in 0..archive.len() {
-            let mut file = archive
-                .by_index(i)
-                .context("No se pudo acceder al archivo en el ZIP")?;
-            let outpath = match file.enclosed_name() {
-                Some(path) => target_dir.join(path),
-                None => continue,
-            };
-
-            if file.name().ends_with('/') {
-                fs::create_dir_all(&outpath)
-                    .context("No se pudo crear directorio durante la extracción")?;
-            } else {
-                if let Some(p) = outpath.parent() {
-                    if !p.exists() {
-                        fs::create_dir_all(p)
-                            .context("No se pudo crear directorio padre durante la extracción")?;
-                    }
-                }
-                let mut outfile = File::create(&outpath)
-                    .context("No se pudo crear archivo durante la extracción")?;
-                io::copy(&mut file, &mut outfile)
-                    .context("No se pudo copiar contenido del archivo ZIP")?;
-
-                // Preservar permisos de ejecución en sistemas Unix
-                #[cfg(unix)]
-                {
-                    use std::os::unix::fs::PermissionsExt;
-                    if file.unix_mode().unwrap_or(0) & 0o111 != 0 {
-                        let mut perms = fs::metadata(&outpath)?.permissions();
-                        perms.set_mode(0o755);
-                        fs::set_permissions(&outpath, perms)?;
-                    }
-                }
-            }
-        }
+        ZipExtractor::new(zip_path, target_dir)
+            .extract()
+            .map_err(|e| anyhow!(e))
+            .context("No se pudo extraer el archivo ZIP")?;
 
         Ok(())
     }
@@ -417,6 +389,142 @@ impl JavaManager {
         let version_dir = self.base_path.join(format!("{}", version));
         version_dir.exists()
     }
+
+    /// Nombres de las carpetas de runtimes instalados (`java8`, `java17`...)
+    pub fn installed_runtimes(&self) -> Vec<String> {
+        fs::read_dir(&self.base_path)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter(|entry| entry.path().is_dir())
+                    .map(|entry| entry.file_name().to_string_lossy().to_string())
+                    .filter(|name| name.starts_with("java"))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Runtime que contiene `java_path`, si es uno de los gestionados
+    pub fn runtime_of(&self, java_path: &Path) -> Option<String> {
+        let relative = java_path.strip_prefix(&self.base_path).ok()?;
+        relative
+            .components()
+            .next()
+            .map(|component| component.as_os_str().to_string_lossy().to_string())
+    }
+
+    /// Elimina un runtime y devuelve los bytes liberados
+    pub fn remove_runtime(&self, name: &str) -> Result<u64> {
+        let runtime_dir = self.base_path.join(name);
+        let size = dir_size(&runtime_dir);
+        fs::remove_dir_all(&runtime_dir)
+            .with_context(|| format!("No se pudo eliminar {}", runtime_dir.display()))?;
+        Ok(size)
+    }
+}
+
+fn dir_size(path: &Path) -> u64 {
+    fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| match entry.metadata() {
+                    Ok(metadata) if metadata.is_dir() => dir_size(&entry.path()),
+                    Ok(metadata) => metadata.len(),
+                    Err(_) => 0,
+                })
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JavaCleanupReport {
+    pub removed_versions: Vec<String>,
+    pub reclaimed_bytes: u64,
+}
+
+/// Runtimes que alguna instancia necesita, por versión o por `javaPath`, y
+/// el que tenga configurado `javaDir`
+fn referenced_runtimes(manager: &JavaManager) -> std::result::Result<HashSet<String>, String> {
+    // Si no se pueden leer las instancias no se borra nada
+    let instances = get_all_instances()?;
+    let java_dir = with_config(|config| config.get_java_dir())?;
+
+    let mut referenced = HashSet::new();
+    if let Some(runtime) = java_dir.and_then(|dir| manager.runtime_of(&dir)) {
+        referenced.insert(runtime);
+    }
+    for instance in instances {
+        if let Some(major) = &instance.javaMajorVersion {
+            referenced.insert(format!("java{}", major));
+        }
+        if let Some(runtime) = instance
+            .javaPath
+            .as_deref()
+            .and_then(|java_path| manager.runtime_of(Path::new(java_path)))
+        {
+            referenced.insert(runtime);
+        }
+    }
+    Ok(referenced)
+}
+
+/// Deletes the downloaded Java runtimes no instance references.
+pub fn cleanup_unused_runtimes() -> std::result::Result<JavaCleanupReport, String> {
+    // Un juego abierto puede estar usando un runtime, y un bootstrap en curso
+    // puede haberlo instalado sin haberlo guardado aún en la instancia
+    if !process_registry::get_all().is_empty() || !tasks_manager::get_active_tasks().is_empty() {
+        return Err(
+            "No se puede limpiar Java con instancias abiertas o tareas en curso".to_string(),
+        );
+    }
+    let manager = JavaManager::new().map_err(|e| e.to_string())?;
+    let referenced = referenced_runtimes(&manager)?;
+    let mut report = JavaCleanupReport::default();
+
+    for runtime in manager.installed_runtimes() {
+        if referenced.contains(&runtime) {
+            continue;
+        }
+        match manager.remove_runtime(&runtime) {
+            Ok(bytes) => {
+                log::info!(
+                    "[Java] Removed unused runtime {} ({} bytes)",
+                    runtime,
+                    bytes
+                );
+                report.reclaimed_bytes += bytes;
+                report.removed_versions.push(runtime);
+            }
+            Err(e) => log::warn!("[Java] {:#}", e),
+        }
+    }
+    Ok(report)
+}
+
+#[tauri::command]
+pub async fn cleanup_unused_javas() -> std::result::Result<JavaCleanupReport, String> {
+    tokio::task::spawn_blocking(cleanup_unused_runtimes)
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Runs the cleanup in the background at startup if `autoCleanupJava` is on.
+pub fn spawn_auto_cleanup() {
+    let enabled = with_config(|config| config.get_auto_cleanup_java())
+        .ok()
+        .unwrap_or(false);
+    if !enabled {
+        return;
+    }
+
+    std::thread::spawn(|| {
+        if let Err(e) = cleanup_unused_runtimes() {
+            log::warn!("[Java] Automatic cleanup failed: {}", e);
+        }
+    });
 }
 
 // Ejemplo de uso:
@@ -0,0 +1,175 @@
+//! JVM crash (`hs_err_pid*.log`) detection.
+
+use crate::core::instance_manager::get_instance_by_id;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JvmCrashReport {
+    pub file: String,
+    /// Línea de error fatal, p. ej. "EXCEPTION_ACCESS_VIOLATION (0xc0000005)"
+    pub error: Option<String>,
+    pub problematic_frame: Option<String>,
+    /// Biblioteca nativa del frame, p. ej. "ig9icd64.dll"
+    pub library: Option<String>,
+    pub java_version: Option<String>,
+    /// Sugerencia para el usuario, si se reconoce la causa
+    pub hint: Option<String>,
+}
+
+/// Library name fragments (lowercase) and the hint shown for them.
+const LIBRARY_HINTS: &[(&[&str], &str)] = &[
+    (
+        &["ig4icd", "ig7icd", "ig75icd", "ig8icd", "ig9icd", "igxelpicd", "igd10"],
+        "El controlador gráfico de Intel ha fallado. Actualiza los drivers de Intel desde la web del fabricante.",
+    ),
+    (
+        &["atio6axx", "atioglxx", "atig6pxx", "atiumd", "amdxx"],
+        "El controlador gráfico de AMD ha fallado. Actualiza los drivers de AMD (Adrenalin).",
+    ),
+    (
+        &["nvoglv", "libnvidia-glcore", "libnvidia-eglcore"],
+        "El controlador gráfico de NVIDIA ha fallado. Actualiza los drivers de NVIDIA.",
+    ),
+    (
+        &["libgl.so", "libgallium", "_dri.so", "libvulkan"],
+        "El controlador gráfico (Mesa) ha fallado. Actualiza los paquetes de Mesa de tu distribución.",
+    ),
+    (
+        &["openal", "libopenal"],
+        "El sistema de audio ha fallado. Revisa los drivers de sonido o desconecta dispositivos de audio recientes.",
+    ),
+    (
+        &["lwjgl", "glfw"],
+        "Las bibliotecas nativas del juego han fallado. Repara la instancia para volver a descargarlas.",
+    ),
+    (
+        &["jvm.dll", "libjvm"],
+        "La propia máquina virtual de Java ha fallado. Prueba con otra versión de Java o reduce la memoria asignada.",
+    ),
+];
+
+const OUT_OF_MEMORY_HINT: &str =
+    "Java se ha quedado sin memoria nativa. Cierra otros programas, reduce la memoria asignada o usa Java de 64 bits.";
+
+fn is_crash_log(path: &Path) -> bool {
+    path.file_name()
+        .map(|name| name.to_string_lossy())
+        .is_some_and(|name| name.starts_with("hs_err_pid") && name.ends_with(".log"))
+}
+
+/// Crash logs in `game_dir` written at or after `since`, newest first.
+pub fn find_crash_logs(game_dir: &Path, since: Option<SystemTime>) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(game_dir) else {
+        return Vec::new();
+    };
+
+    let mut logs: Vec<(SystemTime, PathBuf)> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| is_crash_log(path))
+        .filter_map(|path| {
+            let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+            let recent = !since.is_some_and(|since| modified < since);
+            recent.then_some((modified, path))
+        })
+        .collect();
+
+    logs.sort_by(|a, b| b.0.cmp(&a.0));
+    logs.into_iter().map(|(_, path)| path).collect()
+}
+
+/// Native library inside a frame such as `C  [ig9icd64.dll+0x1c2b3]`.
+fn frame_library(frame: &str) -> Option<String> {
+    let start = frame.find('[')? + 1;
+    let end = start + frame[start..].find(']')?;
+    let library = frame[start..end].split('+').next()?.trim();
+    (!library.is_empty()).then(|| library.to_string())
+}
+
+fn hint_for(library: Option<&str>, content: &str) -> Option<String> {
+    if content.contains("There is insufficient memory for the Java Runtime Environment") {
+        return Some(OUT_OF_MEMORY_HINT.to_string());
+    }
+
+    let library = library?.to_lowercase();
+    LIBRARY_HINTS
+        .iter()
+        .find(|(fragments, _)| fragments.iter().any(|fragment| library.contains(fragment)))
+        .map(|(_, hint)| hint.to_string())
+}
+
+/// Extracts the relevant lines from the content of an hs_err log.
+pub fn parse_crash_log(file: &str, content: &str) -> JvmCrashReport {
+    let comment = |line: &str| line.trim_start_matches('#').trim().to_string();
+    let lines: Vec<&str> = content.lines().collect();
+
+    // El primer bloque de comentarios empieza con la señal o excepción
+    let error = lines.iter().map(|line| comment(line)).find(|line| {
+        line.starts_with("EXCEPTION_")
+            || line.starts_with("SIG")
+            || line.starts_with("Internal Error")
+            || line.starts_with("There is insufficient memory")
+    });
+
+    let java_version = lines.iter().find_map(|line| {
+        comment(line)
+            .strip_prefix("JRE version:")
+            .map(str::trim)
+            .map(str::to_string)
+    });
+
+    let problematic_frame = lines
+        .iter()
+        .position(|line| comment(line) == "Problematic frame:")
+        .and_then(|index| lines.get(index + 1))
+        .map(|line| comment(line))
+        .filter(|frame| !frame.is_empty());
+
+    let library = problematic_frame.as_deref().and_then(frame_library);
+    let hint = hint_for(library.as_deref(), content);
+
+    JvmCrashReport {
+        file: file.to_string(),
+        error,
+        problematic_frame,
+        library,
+        java_version,
+        hint,
+    }
+}
+
+pub fn read_crash_log(path: &Path) -> Option<JvmCrashReport> {
+    // Los logs pueden traer bytes no UTF-8 en rutas o nombres de hilos
+    let content = fs::read(path).ok()?;
+    let content = String::from_utf8_lossy(&content);
+    Some(parse_crash_log(&path.to_string_lossy(), &content))
+}
+
+/// Report of the newest crash log written since the game was started.
+pub fn collect_since(game_dir: &Path, since: SystemTime) -> Option<JvmCrashReport> {
+    let report = find_crash_logs(game_dir, Some(since))
+        .first()
+        .and_then(|path| read_crash_log(path))?;
+    log::warn!(
+        "[JvmCrash] JVM crashed in {:?} ({})",
+        report.library,
+        report.file
+    );
+    Some(report)
+}
+
+/// Every JVM crash log of the instance, newest first, for support reports.
+#[tauri::command]
+pub fn get_instance_jvm_crashes(instance_id: String) -> Result<Vec<JvmCrashReport>, String> {
+    let instance = get_instance_by_id(instance_id.clone())?
+        .ok_or_else(|| format!("Instance with ID {} not found", instance_id))?;
+
+    Ok(find_crash_logs(Path::new(&instance.minecraftPath), None)
+        .iter()
+        .filter_map(|path| read_crash_log(path))
+        .collect())
+}
@@ -0,0 +1,234 @@
+// src-tauri/src/core/launch_hooks.rs
+use crate::core::minecraft_instance::MinecraftInstance;
+use crate::core::subprocess;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{BufRead, BufReader, Read};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Tiempo máximo por defecto para un hook (segundos)
+const DEFAULT_HOOK_TIMEOUT_SECS: u64 = 60;
+
+/// User commands run around the lifecycle of an instance.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceHooks {
+    pub pre_launch: Option<String>,
+    pub post_launch: Option<String>,
+    pub post_exit: Option<String>,
+    pub timeout_seconds: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HookPoint {
+    PreLaunch,
+    PostLaunch,
+    PostExit,
+}
+
+impl HookPoint {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HookPoint::PreLaunch => "pre-launch",
+            HookPoint::PostLaunch => "post-launch",
+            HookPoint::PostExit => "post-exit",
+        }
+    }
+}
+
+impl fmt::Display for HookPoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug)]
+pub struct HookOutcome {
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+}
+
+impl HookOutcome {
+    pub fn success(&self) -> bool {
+        !self.timed_out && self.exit_code == Some(0)
+    }
+}
+
+/// Runs the hook configured for `point`, if any.
+///
+/// The command runs through the system shell with the instance context in
+/// `MODPACKSTORE_*` environment variables; `extra_env` adds point-specific
+/// values (PID, exit code...). Output is forwarded to the launcher log and
+/// the process is killed once the timeout expires.
+///
+/// Returns `Ok(None)` when no hook is configured.
+pub fn run_hook(
+    instance: &MinecraftInstance,
+    point: HookPoint,
+    extra_env: &[(&str, String)],
+) -> Result<Option<HookOutcome>, String> {
+    let hooks = match &instance.hooks {
+        Some(hooks) => hooks,
+        None => return Ok(None),
+    };
+
+    let command_line = match point {
+        HookPoint::PreLaunch => hooks.pre_launch.as_deref(),
+        HookPoint::PostLaunch => hooks.post_launch.as_deref(),
+        HookPoint::PostExit => hooks.post_exit.as_deref(),
+    };
+
+    let command_line = match command_line.map(str::trim).filter(|c| !c.is_empty()) {
+        Some(command_line) => command_line,
+        None => return Ok(None),
+    };
+
+    let timeout = Duration::from_secs(hooks.timeout_seconds.unwrap_or(DEFAULT_HOOK_TIMEOUT_SECS));
+    let log_prefix = format!("[Hook:{}:{}]", point, instance.instanceId);
+
+    log::info!("{} Running: {}", log_prefix, command_line);
+
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.arg("/C").arg(command_line);
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(command_line);
+        command
+    };
+
+    let instance_dir = instance
+        .instanceDirectory
+        .as_ref()
+        .map(PathBuf::from)
+        .unwrap_or_default();
+    let game_dir = instance_dir.join("minecraft");
+
+    let mut env: HashMap<&str, String> = HashMap::new();
+    env.insert("MODPACKSTORE_HOOK", point.as_str().to_string());
+    env.insert("MODPACKSTORE_INSTANCE_ID", instance.instanceId.clone());
+    env.insert("MODPACKSTORE_INSTANCE_NAME", instance.instanceName.clone());
+    env.insert(
+        "MODPACKSTORE_INSTANCE_DIR",
+        instance_dir.to_string_lossy().to_string(),
+    );
+    env.insert(
+        "MODPACKSTORE_GAME_DIR",
+        game_dir.to_string_lossy().to_string(),
+    );
+    env.insert(
+        "MODPACKSTORE_MINECRAFT_VERSION",
+        instance.minecraftVersion.clone(),
+    );
+    env.insert(
+        "MODPACKSTORE_FORGE_VERSION",
+        instance.forgeVersion.clone().unwrap_or_default(),
+    );
+    if let Some(modpack_id) = &instance.modpackId {
+        env.insert("MODPACKSTORE_MODPACK_ID", modpack_id.clone());
+    }
+    for (key, value) in extra_env {
+        env.insert(key, value.clone());
+    }
+
+    command
+        .envs(env)
+        .current_dir(if game_dir.exists() {
+            &game_dir
+        } else {
+            &instance_dir
+        })
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    subprocess::hide_console(&mut command);
+    subprocess::own_process_group(&mut command);
+
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("No se pudo ejecutar el hook {}: {}", point, e))?;
+
+    let readers = [
+        child
+            .stdout
+            .take()
+            .map(|out| forward_output(out, log_prefix.clone(), false)),
+        child
+            .stderr
+            .take()
+            .map(|err| forward_output(err, log_prefix.clone(), true)),
+    ];
+
+    let started = Instant::now();
+    let mut timed_out = false;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Some(status),
+            Ok(None) if started.elapsed() >= timeout => {
+                log::warn!(
+                    "{} Timed out after {}s, killing process",
+                    log_prefix,
+                    timeout.as_secs()
+                );
+                timed_out = true;
+                subprocess::kill_tree(&mut child);
+                break child.wait().ok();
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(100)),
+            Err(e) => {
+                log::error!("{} Failed to wait for hook: {}", log_prefix, e);
+                break None;
+            }
+        }
+    };
+
+    subprocess::join_readers(readers.into_iter().flatten().collect());
+
+    let outcome = HookOutcome {
+        exit_code: status.and_then(|s| s.code()),
+        timed_out,
+    };
+
+    log::info!(
+        "{} Finished (exit code: {:?}, timed out: {})",
+        log_prefix,
+        outcome.exit_code,
+        outcome.timed_out
+    );
+
+    Ok(Some(outcome))
+}
+
+/// Runs a hook in a background thread; failures are only logged.
+pub fn run_hook_detached(
+    instance: MinecraftInstance,
+    point: HookPoint,
+    extra_env: Vec<(&'static str, String)>,
+) {
+    thread::spawn(move || {
+        if let Err(e) = run_hook(&instance, point, &extra_env) {
+            log::error!("[Hook:{}:{}] {}", point, instance.instanceId, e);
+        }
+    });
+}
+
+fn forward_output<R: Read + Send + 'static>(
+    stream: R,
+    prefix: String,
+    is_stderr: bool,
+) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        for line in BufReader::new(stream).lines().map_while(Result::ok) {
+            if is_stderr {
+                log::warn!("{} {}", prefix, line);
+            } else {
+                log::info!("{} {}", prefix, line);
+            }
+        }
+    })
+}
@@ -0,0 +1,117 @@
+//! Launch plan of an instance, for the diagnostics panel and bug reports.
+
+use crate::config::read_config;
+use crate::core::instance_manager::get_instance_by_id;
+use crate::core::minecraft::MinecraftLauncher;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const REDACTED: &str = "REDACTED";
+/// Argumentos cuyo valor siguiente es un secreto de la sesión
+const SECRET_ARGS: [&str; 2] = ["--accessToken", "--session"];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClasspathEntry {
+    pub path: String,
+    pub exists: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchPlan {
+    pub java_path: String,
+    pub java_exists: bool,
+    /// Comando que envuelve a Java (gamemoderun, mangohud...), vacío si no hay
+    pub wrapper: Vec<String>,
+    pub working_directory: String,
+    pub main_class: String,
+    pub jvm_args: Vec<String>,
+    pub game_args: Vec<String>,
+    pub classpath: Vec<ClasspathEntry>,
+    pub env_vars: BTreeMap<String, String>,
+    pub account: String,
+    pub memory_mb: u32,
+}
+
+/// Replaces the session token wherever it appears, plus the value of the
+/// arguments that always carry one.
+fn redact(args: &[String], token: Option<&str>) -> Vec<String> {
+    let token = token.filter(|token| !token.is_empty() && *token != "null");
+    let mut redacted = Vec::with_capacity(args.len());
+    let mut secret_next = false;
+    for arg in args {
+        if secret_next {
+            redacted.push(REDACTED.to_string());
+            secret_next = false;
+            continue;
+        }
+        secret_next = SECRET_ARGS.contains(&arg.as_str());
+        redacted.push(match token {
+            Some(token) => arg.replace(token, REDACTED),
+            None => arg.clone(),
+        });
+    }
+    redacted
+}
+
+/// Entries of the `-cp` / `-classpath` argument.
+fn classpath_entries(jvm_args: &[String]) -> Vec<ClasspathEntry> {
+    let separator = if cfg!(windows) { ';' } else { ':' };
+    jvm_args
+        .iter()
+        .position(|arg| arg == "-cp" || arg == "-classpath")
+        .and_then(|index| jvm_args.get(index + 1))
+        .map(|classpath| {
+            classpath
+                .split(separator)
+                .filter(|entry| !entry.is_empty())
+                .map(|entry| ClasspathEntry {
+                    path: entry.to_string(),
+                    exists: Path::new(entry).exists(),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn launch_plan(instance_id: &str) -> Result<LaunchPlan, String> {
+    let instance = get_instance_by_id(instance_id.to_string())?
+        .ok_or_else(|| format!("Instance with ID {} not found", instance_id))?;
+
+    let prepared = {
+        let config_manager = read_config();
+        let config = config_manager.as_ref().map_err(|e| e.clone())?;
+
+        MinecraftLauncher::new(instance).prepare(config)?
+    };
+
+    let token = prepared.account.access_token();
+    Ok(LaunchPlan {
+        java_path: prepared.java_path.to_string_lossy().to_string(),
+        java_exists: prepared.java_path.is_file(),
+        wrapper: prepared.environment.wrapper().to_vec(),
+        working_directory: prepared.game_dir.to_string_lossy().to_string(),
+        main_class: prepared.main_class.clone(),
+        classpath: classpath_entries(&prepared.jvm_args),
+        jvm_args: redact(&prepared.jvm_args, token),
+        game_args: redact(&prepared.game_args, token),
+        env_vars: prepared
+            .environment
+            .env_vars()
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect(),
+        account: prepared.account.username().to_string(),
+        memory_mb: prepared.memory,
+    })
+}
+
+/// Command line the instance would be started with, without starting it.
+#[tauri::command]
+pub async fn get_launch_plan(instance_id: String) -> Result<LaunchPlan, String> {
+    tokio::task::spawn_blocking(move || launch_plan(&instance_id))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,217 @@
+//! Launches requested while the instance is still being set up.
+
+use crate::core::events::{self, EventEmitter, LauncherEvent};
+use crate::core::instance_manager::{self, get_instance_by_id};
+use crate::core::instance_state::{self, InstanceState};
+use crate::core::minecraft::LaunchOptions;
+use crate::core::tasks_manager::{self, TaskInfo, TaskStatus};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+use std::thread;
+
+/// What `launch_mc_instance` did with the request.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum LaunchRequestStatus {
+    Started,
+    /// Espera a que termine la preparación de la instancia
+    Queued,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchRequest {
+    pub instance_id: String,
+    pub status: LaunchRequestStatus,
+    /// Tarea que retiene el lanzamiento, si la hay
+    pub blocking_task_id: Option<String>,
+    /// Estado de la instancia que retiene el lanzamiento (`installing`, `updating`)
+    pub blocking_state: Option<InstanceState>,
+}
+
+impl LaunchRequest {
+    pub fn started(instance_id: String) -> Self {
+        Self {
+            instance_id,
+            status: LaunchRequestStatus::Started,
+            blocking_task_id: None,
+            blocking_state: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DequeueReason {
+    /// La preparación terminó y el juego se está lanzando
+    Started,
+    SetupFailed,
+    /// El lanzamiento se intentó pero no pudo empezar
+    LaunchFailed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchDequeued {
+    pub instance_id: String,
+    pub reason: DequeueReason,
+    pub error: Option<String>,
+}
+
+/// What is still preparing the instance.
+#[derive(Debug, Clone)]
+pub struct SetupBlocker {
+    task: Option<TaskInfo>,
+    state: Option<InstanceState>,
+}
+
+struct QueuedLaunch {
+    events: EventEmitter,
+    options: LaunchOptions,
+}
+
+static QUEUED: Lazy<Mutex<HashMap<String, QueuedLaunch>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn queued() -> MutexGuard<'static, HashMap<String, QueuedLaunch>> {
+    QUEUED.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// The bootstrap, import or update still running (or waiting for its turn)
+/// for the instance, if any.
+pub fn blocker(instance_id: &str) -> Option<SetupBlocker> {
+    // Las subtareas cuentan a través de su padre
+    let task = tasks_manager::get_active_tasks().into_iter().find(|task| {
+        task.parent_id.is_none()
+            && task.instance_id.as_deref() == Some(instance_id)
+            && !task.status.is_terminal()
+    });
+    let state = instance_state::current(instance_id)
+        .filter(|state| matches!(state, InstanceState::Installing | InstanceState::Updating));
+
+    if task.is_none() && state.is_none() {
+        return None;
+    }
+    Some(SetupBlocker { task, state })
+}
+
+/// Queues the launch of `instance_id` until `blocker` is gone, replacing an
+/// earlier queued launch of the same instance.
+pub fn enqueue(
+    events: EventEmitter,
+    instance_id: String,
+    options: LaunchOptions,
+    blocker: SetupBlocker,
+) -> LaunchRequest {
+    let request = LaunchRequest {
+        instance_id: instance_id.clone(),
+        status: LaunchRequestStatus::Queued,
+        blocking_task_id: blocker.task.map(|task| task.id),
+        blocking_state: blocker.state,
+    };
+
+    queued().insert(instance_id.clone(), QueuedLaunch { events, options });
+    log::info!(
+        "[LaunchQueue] Launch of {} queued behind task {:?} / state {:?}",
+        instance_id,
+        request.blocking_task_id,
+        request.blocking_state
+    );
+    events::emit(LauncherEvent::InstanceLaunchQueued(request.clone()));
+
+    // La preparación pudo terminar entre la comprobación y la inserción
+    try_start(&instance_id);
+    request
+}
+
+fn take(instance_id: &str, reason: DequeueReason, error: Option<String>) -> Option<QueuedLaunch> {
+    let launch = queued().remove(instance_id)?;
+    log::info!(
+        "[LaunchQueue] Launch of {} dequeued: {:?}",
+        instance_id,
+        reason
+    );
+    events::emit(LauncherEvent::InstanceLaunchDequeued(LaunchDequeued {
+        instance_id: instance_id.to_string(),
+        reason,
+        error,
+    }));
+    Some(launch)
+}
+
+/// Starts the queued launch of `instance_id` if nothing blocks it anymore.
+fn try_start(instance_id: &str) {
+    if !queued().contains_key(instance_id) || blocker(instance_id).is_some() {
+        return;
+    }
+    let Some(launch) = take(instance_id, DequeueReason::Started, None) else {
+        return;
+    };
+
+    // Fuera del hilo que terminó la preparación (puede tener locks tomados)
+    let instance_id = instance_id.to_string();
+    thread::spawn(move || {
+        let result = get_instance_by_id(instance_id.clone())
+            .and_then(|instance| {
+                instance.ok_or_else(|| format!("Instance with ID {} not found", instance_id))
+            })
+            .and_then(|instance| {
+                instance_manager::start_launch(launch.events, &instance, launch.options)
+            });
+        if let Err(e) = result {
+            log::warn!(
+                "[LaunchQueue] Queued launch of {} failed: {}",
+                instance_id,
+                e
+            );
+            events::emit(LauncherEvent::InstanceLaunchDequeued(LaunchDequeued {
+                instance_id,
+                reason: DequeueReason::LaunchFailed,
+                error: Some(e),
+            }));
+        }
+    });
+}
+
+/// Called on every lifecycle transition: a failed setup drops the queued
+/// launch, anything else may unblock it.
+pub fn on_state_changed(instance_id: &str, state: InstanceState, error: Option<&str>) {
+    match state {
+        InstanceState::Failed => {
+            take(
+                instance_id,
+                DequeueReason::SetupFailed,
+                error.map(String::from),
+            );
+        }
+        InstanceState::Ready => try_start(instance_id),
+        _ => {}
+    }
+}
+
+/// Called when an instance task finishes.
+pub fn on_task_finished(task: &TaskInfo) {
+    let Some(instance_id) = task.instance_id.as_deref() else {
+        return;
+    };
+    match task.status {
+        TaskStatus::Failed | TaskStatus::Cancelled => {
+            let reason = if task.status == TaskStatus::Cancelled {
+                DequeueReason::Cancelled
+            } else {
+                DequeueReason::SetupFailed
+            };
+            take(instance_id, reason, Some(task.message.clone()));
+        }
+        _ => try_start(instance_id),
+    }
+}
+
+/// Drops the queued launch of the instance. Returns whether there was one.
+#[tauri::command]
+pub fn cancel_queued_launch(instance_id: String) -> bool {
+    take(&instance_id, DequeueReason::Cancelled, None).is_some()
+}
@@ -0,0 +1,229 @@
+//! Release notes of the launcher for the "what's new" dialog.
+
+use crate::config::{read_config, write_config};
+use crate::core::http_client;
+use crate::core::minecraft::maven_version;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+const RELEASES_URL: &str =
+    "https://api.github.com/repos/ModpackStore/ModpackStore-Releases/releases?per_page=100";
+/// Antigüedad a partir de la cual la caché se vuelve a descargar
+const CACHE_TTL_HOURS: i64 = 6;
+const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangelogEntry {
+    pub version: String,
+    pub title: String,
+    /// Notas en Markdown tal y como se publicaron
+    pub notes: String,
+    pub published_at: Option<String>,
+    pub prerelease: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CachedChangelog {
+    fetched_at: String,
+    entries: Vec<ChangelogEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LauncherChangelog {
+    pub current_version: String,
+    /// Versión desde la que se muestran cambios (excluida)
+    pub since_version: Option<String>,
+    /// De la más nueva a la más antigua
+    pub entries: Vec<ChangelogEntry>,
+    /// Hubo una actualización cuyas novedades aún no se han marcado como vistas
+    pub unseen: bool,
+}
+
+fn cache_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("dev.alexitoo.modpackstore").join("changelog.json"))
+}
+
+fn load_cache() -> Option<CachedChangelog> {
+    let content = fs::read_to_string(cache_path()?).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn save_cache(cache: &CachedChangelog) {
+    let Some(path) = cache_path() else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    match serde_json::to_string_pretty(cache) {
+        Ok(content) => {
+            if let Err(e) = fs::write(&path, content) {
+                log::warn!("[Changelog] Could not cache release notes: {}", e);
+            }
+        }
+        Err(e) => log::warn!("[Changelog] Could not serialize release notes: {}", e),
+    }
+}
+
+fn is_fresh(cache: &CachedChangelog) -> bool {
+    chrono::DateTime::parse_from_rfc3339(&cache.fetched_at).is_ok_and(|fetched_at| {
+        chrono::Utc::now().signed_duration_since(fetched_at)
+            < chrono::Duration::hours(CACHE_TTL_HOURS)
+    })
+}
+
+/// `v0.0.224` -> `0.0.224`; tags that aren't a version (`canary`) are skipped.
+fn tag_version(tag: &str) -> Option<String> {
+    let version = tag.trim().trim_start_matches(['v', 'V']);
+    version
+        .starts_with(|c: char| c.is_ascii_digit())
+        .then(|| version.to_string())
+}
+
+fn parse_releases(releases: &Value) -> Vec<ChangelogEntry> {
+    releases
+        .as_array()
+        .map(|releases| {
+            releases
+                .iter()
+                .filter(|release| !release["draft"].as_bool().unwrap_or(false))
+                .filter_map(|release| {
+                    let version = tag_version(release["tag_name"].as_str()?)?;
+                    Some(ChangelogEntry {
+                        title: release["name"]
+                            .as_str()
+                            .filter(|name| !name.trim().is_empty())
+                            .unwrap_or(&version)
+                            .to_string(),
+                        notes: release["body"].as_str().unwrap_or_default().to_string(),
+                        published_at: release["published_at"].as_str().map(str::to_string),
+                        prerelease: release["prerelease"].as_bool().unwrap_or(false),
+                        version,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+async fn fetch_entries() -> Result<Vec<ChangelogEntry>, String> {
+    let releases = http_client::async_builder()
+        .timeout(FETCH_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?
+        .get(RELEASES_URL)
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await
+        .map_err(|e| format!("Error al consultar las novedades: {}", e))?
+        .error_for_status()
+        .map_err(|e| format!("Error al consultar las novedades: {}", e))?
+        .json::<Value>()
+        .await
+        .map_err(|e| format!("Respuesta inválida de las novedades: {}", e))?;
+    Ok(parse_releases(&releases))
+}
+
+/// Release notes, from the cache while it is fresh. A stale cache is still
+/// used when GitHub can't be reached.
+async fn load_entries() -> Result<Vec<ChangelogEntry>, String> {
+    let cached = load_cache();
+    if let Some(cache) = cached.as_ref().filter(|cache| is_fresh(cache)) {
+        return Ok(cache.entries.clone());
+    }
+
+    match fetch_entries().await {
+        Ok(entries) => {
+            save_cache(&CachedChangelog {
+                fetched_at: chrono::Utc::now().to_rfc3339(),
+                entries: entries.clone(),
+            });
+            Ok(entries)
+        }
+        Err(e) => match cached {
+            Some(cache) => {
+                log::warn!("[Changelog] {}; using cached release notes", e);
+                Ok(cache.entries)
+            }
+            None => Err(e),
+        },
+    }
+}
+
+/// `lastSeenChangelogVersion`, falling back to the version the launcher was
+/// updated from.
+fn last_seen_version() -> Option<String> {
+    let config_manager = read_config();
+    let config = config_manager.as_ref().ok()?;
+    ["lastSeenChangelogVersion", "updatedFrom"]
+        .iter()
+        .filter_map(|key| config.get_typed::<String>(key))
+        .find(|version| !version.trim().is_empty())
+}
+
+/// Downloads the notes in the background after an update so the dialog
+/// opens instantly (and offline). Call once from the app setup.
+pub fn spawn_prefetch(app_handle: tauri::AppHandle) {
+    let current = app_handle.package_info().version.to_string();
+    if !last_seen_version().is_some_and(|seen| seen != current) {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = load_entries().await {
+            log::warn!("[Changelog] Could not prefetch release notes: {}", e);
+        }
+    });
+}
+
+/// Release notes after `since_version` (the last seen version by default)
+/// up to the running version.
+#[tauri::command]
+pub async fn get_launcher_changelog(
+    app_handle: tauri::AppHandle,
+    since_version: Option<String>,
+) -> Result<LauncherChangelog, String> {
+    let current = app_handle.package_info().version.to_string();
+    let last_seen = last_seen_version();
+    let since = since_version
+        .map(|version| version.trim().trim_start_matches(['v', 'V']).to_string())
+        .filter(|version| !version.is_empty())
+        .or_else(|| last_seen.clone());
+
+    let mut entries: Vec<ChangelogEntry> = load_entries()
+        .await?
+        .into_iter()
+        .filter(|entry| maven_version::compare(&entry.version, &current) != Ordering::Greater)
+        .filter(|entry| {
+            !since.as_deref().is_some_and(|since| {
+                maven_version::compare(&entry.version, since) != Ordering::Greater
+            })
+        })
+        .collect();
+    entries.sort_by(|a, b| maven_version::compare(&b.version, &a.version));
+
+    Ok(LauncherChangelog {
+        unseen: last_seen.is_some_and(|seen| seen != current),
+        current_version: current,
+        since_version: since,
+        entries,
+    })
+}
+
+/// Stores the running version as seen so "what's new" isn't shown again.
+#[tauri::command]
+pub fn mark_changelog_seen(app_handle: tauri::AppHandle) -> Result<(), String> {
+    let current = app_handle.package_info().version.to_string();
+    let mut config_lock = write_config();
+    let config = config_lock.as_mut().map_err(|e| e.clone())?;
+    config
+        .set("lastSeenChangelogVersion", &current)
+        .map_err(|e| format!("Error de validación: {}", e))?;
+    config.save()
+}
@@ -0,0 +1,241 @@
+//! `launcher_profiles.json` of an instance, repaired when it can't be parsed.
+
+use crate::core::instance_manager::get_instance_by_id;
+use crate::core::minecraft::paths;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const FILE_NAME: &str = "launcher_profiles.json";
+/// Versión del formato que escribe el launcher oficial
+const FORMAT_VERSION: u64 = 3;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfilesRepair {
+    pub path: String,
+    /// El archivo ya era válido y sólo se completaron perfiles
+    pub was_valid: bool,
+    /// Copia del archivo dañado, si se hizo
+    pub backup: Option<String>,
+    pub kept: usize,
+    /// Perfiles regenerados a partir de `versions/`
+    pub added: Vec<String>,
+    /// Perfiles descartados por inválidos o por apuntar a versiones que no existen
+    pub removed: Vec<String>,
+}
+
+fn profiles_path(game_dir: &Path) -> PathBuf {
+    game_dir.join(FILE_NAME)
+}
+
+/// Checks the parts of the structure the launcher relies on.
+fn validate(document: &Value) -> Result<(), String> {
+    let profiles = document
+        .as_object()
+        .ok_or("el archivo no es un objeto JSON")?
+        .get("profiles")
+        .and_then(Value::as_object)
+        .ok_or("falta el objeto \"profiles\"")?;
+
+    for (id, profile) in profiles {
+        let valid = profile
+            .as_object()
+            .is_some_and(|profile| !profile.get("lastVersionId").is_some_and(|v| !v.is_string()));
+        if !valid {
+            return Err(format!("perfil \"{}\" inválido", id));
+        }
+    }
+    Ok(())
+}
+
+/// Versions with a `versions/<id>/<id>.json`, sorted.
+fn installed_versions(game_dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(paths::versions_dir(game_dir)) else {
+        return Vec::new();
+    };
+    let mut versions: Vec<String> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|id| paths::version_file(game_dir, id, "json").is_file())
+        .collect();
+    versions.sort();
+    versions
+}
+
+/// Same key and shape as the Forge profile written by the bootstrap.
+fn profile_for(version_id: &str) -> (String, Value) {
+    let now = chrono::Utc::now()
+        .format("%Y-%m-%dT%H:%M:%S.%3fZ")
+        .to_string();
+    let is_forge = version_id.to_lowercase().contains("forge");
+    let key = if is_forge {
+        format!("forge-{}", version_id)
+    } else {
+        version_id.to_string()
+    };
+    let name = if is_forge {
+        format!("{} (Forge)", version_id)
+    } else {
+        version_id.to_string()
+    };
+
+    (
+        key,
+        json!({
+            "created": now,
+            "lastUsed": now,
+            "lastVersionId": version_id,
+            "name": name,
+            "type": "custom"
+        }),
+    )
+}
+
+fn backup_file(path: &Path) -> Result<PathBuf, String> {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(
+        ".corrupt-{}.bak",
+        chrono::Utc::now().format("%Y%m%d%H%M%S")
+    ));
+    let destination = path.with_file_name(name);
+    fs::copy(path, &destination)
+        .map_err(|e| format!("Error al copiar {}: {}", path.display(), e))?;
+    Ok(destination)
+}
+
+/// Rebuilds the profiles of `game_dir`. A valid file keeps everything it
+/// has and only gains profiles for installed versions without one.
+pub fn repair(game_dir: &Path) -> Result<ProfilesRepair, String> {
+    let path = profiles_path(game_dir);
+    let existing = fs::read_to_string(&path).ok();
+    let parsed = existing
+        .as_deref()
+        .map(|content| serde_json::from_str::<Value>(content).map_err(|e| e.to_string()));
+
+    let (document, was_valid) = match parsed {
+        Some(Ok(document)) => {
+            let valid = validate(&document);
+            if let Err(e) = &valid {
+                log::warn!(
+                    "[Profiles] {} has an invalid structure: {}",
+                    path.display(),
+                    e
+                );
+            }
+            (document, valid.is_ok())
+        }
+        Some(Err(e)) => {
+            log::warn!("[Profiles] Could not parse {}: {}", path.display(), e);
+            (Value::Null, false)
+        }
+        None => (Value::Null, true),
+    };
+
+    let backup = match (&existing, was_valid) {
+        (Some(_), false) => Some(backup_file(&path)?.to_string_lossy().to_string()),
+        _ => None,
+    };
+
+    // Se conservan los perfiles válidos que apuntan a una versión instalada
+    // (o a los alias del launcher oficial, p. ej. "latest-release")
+    let versions = installed_versions(game_dir);
+    let mut root = match document {
+        Value::Object(root) => root,
+        _ => Map::new(),
+    };
+    let old_profiles = match root.remove("profiles") {
+        Some(Value::Object(profiles)) => profiles,
+        _ => Map::new(),
+    };
+    let mut profiles = Map::new();
+    let mut removed = Vec::new();
+    for (id, profile) in old_profiles {
+        let version = profile.get("lastVersionId").and_then(Value::as_str);
+        let keep = profile.is_object()
+            && version.is_some_and(|version| {
+                versions.iter().any(|v| v == version) || version.starts_with("latest-")
+            });
+        if keep || (was_valid && profile.is_object() && version.is_none()) {
+            profiles.insert(id, profile);
+        } else {
+            removed.push(id);
+        }
+    }
+    let kept = profiles.len();
+
+    let mut added = Vec::new();
+    for version in &versions {
+        let referenced = profiles
+            .values()
+            .any(|profile| profile.get("lastVersionId").and_then(Value::as_str) == Some(version));
+        if !referenced {
+            let (key, profile) = profile_for(version);
+            added.push(key.clone());
+            profiles.insert(key, profile);
+        }
+    }
+
+    root.insert("profiles".to_string(), Value::Object(profiles));
+    root.entry("settings").or_insert_with(|| json!({}));
+    root.entry("version").or_insert(json!(FORMAT_VERSION));
+
+    // Un archivo válido y completo no se reescribe
+    let unchanged = was_valid && existing.is_some() && added.is_empty() && removed.is_empty();
+    if !unchanged {
+        let content =
+            serde_json::to_string_pretty(&Value::Object(root)).map_err(|e| e.to_string())?;
+        fs::write(&path, content)
+            .map_err(|e| format!("Error al guardar {}: {}", path.display(), e))?;
+        log::info!(
+            "[Profiles] Rebuilt {} ({} kept, {} added, {} removed)",
+            path.display(),
+            kept,
+            added.len(),
+            removed.len()
+        );
+    }
+
+    Ok(ProfilesRepair {
+        path: path.to_string_lossy().to_string(),
+        was_valid,
+        backup,
+        kept,
+        added,
+        removed,
+    })
+}
+
+/// Reads `launcher_profiles.json`, repairing it first if it is damaged.
+/// `None` if it doesn't exist or can't be repaired.
+pub fn load(game_dir: &Path) -> Option<Value> {
+    let path = profiles_path(game_dir);
+    let content = fs::read_to_string(&path).ok()?;
+    if let Ok(document) = serde_json::from_str::<Value>(&content) {
+        if validate(&document).is_ok() {
+            return Some(document);
+        }
+    }
+
+    if let Err(e) = repair(game_dir) {
+        log::error!("[Profiles] Could not repair {}: {}", path.display(), e);
+        return None;
+    }
+    fs::read_to_string(&path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+}
+
+/// Validates the instance's `launcher_profiles.json` and regenerates the
+/// missing profiles from its installed versions.
+#[tauri::command]
+pub fn repair_launcher_profiles(instance_id: String) -> Result<ProfilesRepair, String> {
+    let instance = get_instance_by_id(instance_id.clone())?
+        .ok_or_else(|| format!("Instance with ID {} not found", instance_id))?;
+    let instance_dir = instance
+        .instanceDirectory
+        .as_deref()
+        .ok_or_else(|| "La instancia no tiene directorio".to_string())?;
+    repair(&paths::native_path(instance_dir).join("minecraft"))
+}
@@ -0,0 +1,272 @@
+//! Background updates of the launcher itself.
+
+use crate::config::{read_config, write_config};
+use crate::core::events::{self, LauncherEvent};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::Url;
+use tauri_plugin_updater::{Update, UpdaterExt};
+
+/// Espera antes de la primera comprobación para no competir con el arranque
+const STARTUP_DELAY: Duration = Duration::from_secs(30);
+/// Con comprobaciones periódicas desactivadas se vuelve a mirar la configuración cada hora
+const IDLE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const DEFAULT_DEFER_HOURS: u32 = 24;
+const MAX_DEFER_HOURS: u32 = 24 * 30;
+
+const CHANNELS: [&str; 3] = ["stable", "beta", "canary"];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LauncherUpdateInfo {
+    pub version: String,
+    pub current_version: String,
+    pub channel: String,
+    pub notes: Option<String>,
+    /// Fecha de publicación, si el manifiesto la trae
+    pub date: Option<String>,
+}
+
+/// Update downloaded and verified, waiting for the player to restart.
+struct ReadyUpdate {
+    update: Update,
+    bytes: Vec<u8>,
+    info: LauncherUpdateInfo,
+}
+
+static READY: Lazy<Mutex<Option<ReadyUpdate>>> = Lazy::new(|| Mutex::new(None));
+/// Evita dos descargas a la vez (comprobación periódica y manual)
+static CHECKING: Lazy<tokio::sync::Mutex<()>> = Lazy::new(|| tokio::sync::Mutex::new(()));
+
+struct UpdatePolicy {
+    channel: String,
+    on_startup: bool,
+    interval_hours: u64,
+    deferred_version: String,
+    deferred_until: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl UpdatePolicy {
+    fn load() -> Result<Self, String> {
+        let config_manager = read_config();
+        let config = config_manager.as_ref().map_err(|e| e.clone())?;
+
+        let channel = config.get_release_channel();
+        Ok(Self {
+            channel: if CHANNELS.contains(&channel.as_str()) {
+                channel
+            } else {
+                "canary".to_string()
+            },
+            on_startup: config.check_updates_on_startup(),
+            interval_hours: config.get_update_check_interval_hours(),
+            deferred_version: config
+                .get_typed::<String>("deferredUpdateVersion")
+                .unwrap_or_default(),
+            deferred_until: config
+                .get_typed::<String>("deferredUpdateUntil")
+                .and_then(|until| chrono::DateTime::parse_from_rfc3339(&until).ok())
+                .map(|until| until.with_timezone(&chrono::Utc)),
+        })
+    }
+
+    fn is_deferred(&self, version: &str) -> bool {
+        self.deferred_version == version
+            && self
+                .deferred_until
+                .is_some_and(|until| until > chrono::Utc::now())
+    }
+}
+
+fn endpoints(channel: &str) -> Result<Vec<Url>, String> {
+    [
+        format!(
+            "https://github.com/ModpackStore/ModpackStore-Releases/releases/download/{}/latest.json",
+            channel
+        ),
+        format!(
+            "https://www.alexitoo.dev/api/modpack-store/updates.json?channel={}",
+            channel
+        ),
+    ]
+    .iter()
+    .map(|url| Url::parse(url).map_err(|e| format!("URL de actualización inválida: {}", e)))
+    .collect()
+}
+
+/// Spawns the periodic check. Call once from the app setup.
+pub fn start(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(STARTUP_DELAY).await;
+        let mut first = true;
+
+        loop {
+            let policy = match UpdatePolicy::load() {
+                Ok(policy) => policy,
+                Err(e) => {
+                    log::warn!("[Updater] Could not read the update settings: {}", e);
+                    tokio::time::sleep(IDLE_INTERVAL).await;
+                    continue;
+                }
+            };
+
+            let due = if first {
+                policy.on_startup
+            } else {
+                policy.interval_hours > 0
+            };
+            if due {
+                if let Err(e) = check_and_download(&app_handle, &policy, false).await {
+                    log::warn!("[Updater] Update check failed: {}", e);
+                }
+            }
+            first = false;
+
+            let wait = match policy.interval_hours {
+                0 => IDLE_INTERVAL,
+                hours => Duration::from_secs(hours * 60 * 60),
+            };
+            tokio::time::sleep(wait).await;
+        }
+    });
+}
+
+/// Checks the channel and downloads a newer version. `force` ignores the
+/// player's deferral (manual checks).
+async fn check_and_download(
+    app_handle: &tauri::AppHandle,
+    policy: &UpdatePolicy,
+    force: bool,
+) -> Result<Option<LauncherUpdateInfo>, String> {
+    let _guard = CHECKING.lock().await;
+
+    let updater = app_handle
+        .updater_builder()
+        .endpoints(endpoints(&policy.channel)?)
+        .and_then(|builder| builder.build())
+        .map_err(|e| format!("Error al preparar el actualizador: {}", e))?;
+    let Some(update) = updater
+        .check()
+        .await
+        .map_err(|e| format!("Error al buscar actualizaciones: {}", e))?
+    else {
+        log::info!("[Updater] No updates on channel {}", policy.channel);
+        return Ok(None);
+    };
+
+    let info = LauncherUpdateInfo {
+        version: update.version.clone(),
+        current_version: update.current_version.clone(),
+        channel: policy.channel.clone(),
+        notes: update.body.clone(),
+        date: update.date.map(|date| date.to_string()),
+    };
+
+    // Ya descargada en una comprobación anterior: sólo se vuelve a avisar
+    let downloaded = READY.lock().ok().and_then(|ready| {
+        ready
+            .as_ref()
+            .filter(|ready| ready.info.version == info.version)
+            .map(|ready| ready.info.clone())
+    });
+    if let Some(ready) = downloaded {
+        if force || !policy.is_deferred(&ready.version) {
+            events::emit(LauncherEvent::LauncherUpdateReady(ready.clone()));
+        }
+        return Ok(Some(ready));
+    }
+
+    if !force && policy.is_deferred(&info.version) {
+        log::info!(
+            "[Updater] Update {} deferred by the user, skipping",
+            info.version
+        );
+        return Ok(None);
+    }
+
+    log::info!(
+        "[Updater] Downloading {} (current {}, channel {})",
+        info.version,
+        info.current_version,
+        info.channel
+    );
+    // La firma se comprueba dentro de `download`; si no coincide falla aquí
+    let bytes = update
+        .download(|_, _| {}, || {})
+        .await
+        .map_err(|e| format!("Error al descargar o verificar la actualización: {}", e))?;
+    log::info!("[Updater] Update {} downloaded and verified", info.version);
+
+    if let Ok(mut ready) = READY.lock() {
+        *ready = Some(ReadyUpdate {
+            update,
+            bytes,
+            info: info.clone(),
+        });
+    }
+    events::emit(LauncherEvent::LauncherUpdateReady(info.clone()));
+    Ok(Some(info))
+}
+
+/// Checks for an update now, ignoring any deferral. Returns it once it is
+/// downloaded and ready to install.
+#[tauri::command]
+pub async fn check_launcher_update(
+    app_handle: tauri::AppHandle,
+) -> Result<Option<LauncherUpdateInfo>, String> {
+    let policy = UpdatePolicy::load()?;
+    check_and_download(&app_handle, &policy, true).await
+}
+
+/// Postpones the notice for `version` by `hours` (24 by default).
+#[tauri::command]
+pub fn defer_update(version: String, hours: Option<u32>) -> Result<String, String> {
+    let hours = hours
+        .unwrap_or(DEFAULT_DEFER_HOURS)
+        .clamp(1, MAX_DEFER_HOURS);
+    let until = (chrono::Utc::now() + chrono::Duration::hours(hours as i64)).to_rfc3339();
+
+    let mut config_lock = write_config();
+    let config = config_lock.as_mut().map_err(|e| e.clone())?;
+    config
+        .set("deferredUpdateVersion", &version)
+        .and_then(|_| config.set("deferredUpdateUntil", &until))
+        .map_err(|e| format!("Error de validación: {}", e))?;
+    config.save()?;
+
+    log::info!("[Updater] Update {} deferred until {}", version, until);
+    Ok(until)
+}
+
+/// Installs the downloaded update and restarts the launcher.
+#[tauri::command]
+pub fn install_launcher_update(app_handle: tauri::AppHandle) -> Result<(), String> {
+    let ready = READY
+        .lock()
+        .map_err(|_| "Failed to lock update mutex".to_string())?
+        .take()
+        .ok_or_else(|| "No hay ninguna actualización descargada".to_string())?;
+
+    {
+        let mut config_lock = write_config();
+        let config = config_lock.as_mut().map_err(|e| e.clone())?;
+        let recorded = config
+            .set("lastUpdatedAt", chrono::Utc::now().to_rfc3339())
+            .and_then(|_| config.set("updatedFrom", &ready.info.current_version))
+            .and_then(|_| config.set("deferredUpdateVersion", ""))
+            .and_then(|_| config.set("deferredUpdateUntil", ""));
+        if let Err(e) = recorded {
+            log::warn!("[Updater] Could not record the update: {}", e);
+        }
+        config.save()?;
+    }
+
+    log::info!("[Updater] Installing update {}", ready.info.version);
+    ready
+        .update
+        .install(&ready.bytes)
+        .map_err(|e| format!("Error al instalar la actualización: {}", e))?;
+    app_handle.restart()
+}
@@ -1,15 +1,20 @@
 // src-tauri/src/auth/microsoft.rs
 
-use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+use crate::core::accounts_manager::AccountsManager;
+use crate::core::entitlement::{
+    has_java_license, ACCOUNT_OWNS_MINECRAFT_URL, MINECRAFT_PROFILE_URL,
+};
+use crate::core::events::{self, LauncherEvent};
+use crate::core::http_client;
 use crate::core::minecraft_account::MinecraftAccount;
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use std::thread;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tauri::async_runtime;
-use tauri::{AppHandle, Emitter, Manager};
+use tauri::Manager;
 use tauri_plugin_http::reqwest;
-use crate::core::accounts_manager::AccountsManager;
 
 // Estructuras para respuestas de API
 #[derive(Deserialize, Debug)]
@@ -76,7 +81,7 @@ struct MinecraftSkin {
 
 
 // Estructuras para eventos
-#[derive(Serialize, Clone)]
+#[derive(Debug, Serialize, Clone)]
 pub struct AuthProgressEvent {
     step: String,
     message: String,
@@ -95,8 +100,6 @@ const MICROSOFT_TOKEN_URL: &str = "https://login.microsoftonline.com/consumers/o
 const XBOX_AUTH_URL: &str = "https://user.auth.xboxlive.com/user/authenticate";
 const XSTS_AUTH_URL: &str = "https://xsts.auth.xboxlive.com/xsts/authorize";
 const MINECRAFT_AUTH_URL: &str = "https://api.minecraftservices.com/authentication/login_with_xbox";
-const MINECRAFT_PROFILE_URL: &str = "https://api.minecraftservices.com/minecraft/profile";
-const ACCOUNT_OWNS_MINECRAFT_URL: &str = "https://api.minecraftservices.com/entitlements/license";
 
 // Clase principal para autenticación
 pub struct MicrosoftAuthenticator {
@@ -105,7 +108,7 @@ pub struct MicrosoftAuthenticator {
 
 impl MicrosoftAuthenticator {
     pub fn new() -> Self {
-        let client = reqwest::Client::builder()
+        let client = http_client::async_builder()
             .timeout(Duration::from_secs(30))
             .build()
             .expect("Failed to create HTTP client");
@@ -113,25 +116,22 @@ impl MicrosoftAuthenticator {
         Self { client }
     }
 
-    pub fn start_authentication(&self, app_handle: AppHandle) {
-        // Clonamos el cliente HTTP y el app_handle para uso en el hilo
+    pub fn start_authentication(&self) {
+        // Clonamos el cliente HTTP para uso en el hilo; los eventos van por el EventEmitter
         let client = self.client.clone();
-        let app_handle_clone = app_handle.clone();
 
         // Iniciamos la autenticación en un hilo separado
         thread::spawn(move || {
-            let result = async_runtime::block_on(async {
-                Self::authenticate(&client, &app_handle_clone).await
-            });
+            let result = async_runtime::block_on(async { Self::authenticate(&client).await });
 
             match result {
                 Ok(account) => {
                     // Notificamos éxito con la cuenta
-                    let _ = app_handle_clone.emit("microsoft-auth-success", account);
+                    events::emit(LauncherEvent::MicrosoftAuthSuccess(Some(account)));
                 }
                 Err(err) => {
                     // Notificamos error
-                    let _ = app_handle_clone.emit("microsoft-auth-error", err.to_string());
+                    events::emit(LauncherEvent::MicrosoftAuthError(err.to_string()));
                 }
             }
         });
@@ -139,11 +139,9 @@ impl MicrosoftAuthenticator {
 
     async fn authenticate(
         client: &reqwest::Client,
-        app_handle: &AppHandle,
     ) -> Result<MinecraftAccount, Box<dyn std::error::Error>> {
         // Paso 1: Obtener código de dispositivo
         Self::emit_progress(
-            app_handle,
             "device_code",
             "Solicitando código de dispositivo...",
             0,
@@ -154,7 +152,6 @@ impl MicrosoftAuthenticator {
         let device_code_response = Self::get_device_code(client).await?;
 
         Self::emit_progress(
-            app_handle,
             "waiting_auth",
             "Por favor, visita el sitio web y usa el código para autenticarte",
             10,
@@ -167,12 +164,10 @@ impl MicrosoftAuthenticator {
             client,
             &device_code_response.device_code,
             device_code_response.interval,
-            app_handle,
         )
         .await?;
 
         Self::emit_progress(
-            app_handle,
             "microsoft_token",
             "Autenticación con Microsoft completada",
             30,
@@ -182,7 +177,6 @@ impl MicrosoftAuthenticator {
 
         // Paso 3: Autenticar con Xbox Live
         Self::emit_progress(
-            app_handle,
             "xbox_auth",
             "Autenticando con Xbox Live...",
             40,
@@ -193,7 +187,6 @@ impl MicrosoftAuthenticator {
             Self::authenticate_with_xbox_live(client, &token_response.access_token).await?;
 
         Self::emit_progress(
-            app_handle,
             "xsts_token",
             "Obteniendo token XSTS...",
             50,
@@ -204,7 +197,6 @@ impl MicrosoftAuthenticator {
 
         // Paso 4: Autenticar con Minecraft
         Self::emit_progress(
-            app_handle,
             "minecraft_auth",
             "Autenticando con Minecraft...",
             70,
@@ -220,7 +212,6 @@ impl MicrosoftAuthenticator {
 
         // Paso 5: Obtener perfil de Minecraft
         Self::emit_progress(
-            app_handle,
             "profile",
             "Obteniendo perfil de Minecraft...",
             90,
@@ -230,7 +221,6 @@ impl MicrosoftAuthenticator {
         let profile = Self::get_minecraft_profile(client, &minecraft_token.access_token).await?;
 
         Self::emit_progress(
-            app_handle,
             "complete",
             "Autenticación completada con éxito",
             100,
@@ -243,7 +233,7 @@ impl MicrosoftAuthenticator {
         log::info!("Autenticación exitosa: {:?}", profile);
 
         // Emitir evento de éxito
-        let _ = app_handle.emit("microsoft-auth-success", None::<String>);
+        events::emit(LauncherEvent::MicrosoftAuthSuccess(None));
      
        
                // Usar el método add_microsoft_account para crear y guardar la cuenta
@@ -260,14 +250,13 @@ let account = match AccountsManager::add_microsoft_account(
 
         log::info!("Cuenta guardada: {:?}", account);
         // Emitir evento de cuenta guardada
-        let _ = app_handle.emit("microsoft-auth-account-saved", account.clone());
+        events::emit(LauncherEvent::MicrosoftAuthAccountSaved(account.clone()));
 
         Ok(account)
     }
 
     // Emite un evento de progreso al frontend
     fn emit_progress(
-        app_handle: &AppHandle,
         step: &str,
         message: &str,
         percentage: u8,
@@ -282,7 +271,7 @@ let account = match AccountsManager::add_microsoft_account(
             verification_url,
         };
 
-        let _ = app_handle.emit("microsoft-auth-progress", event);
+        events::emit(LauncherEvent::MicrosoftAuthProgress(event));
     }
 
     // Obtiene un código de dispositivo para iniciar la autenticación
@@ -318,7 +307,6 @@ let account = match AccountsManager::add_microsoft_account(
         client: &reqwest::Client,
         device_code: &str,
         interval: u64,
-        app_handle: &AppHandle,
     ) -> Result<TokenResponse, Box<dyn std::error::Error>> {
         let params = [
             ("grant_type", "urn:ietf:params:oauth:grant-type:device_code"),
@@ -356,7 +344,6 @@ let account = match AccountsManager::add_microsoft_account(
             // Actualizar progreso
             let percentage = 10 + ((elapsed_time as f32 / max_wait_time as f32) * 15.0) as u8;
             Self::emit_progress(
-                app_handle,
                 "waiting_auth",
                 &format!("Esperando autenticación... {}%", percentage),
                 percentage,
@@ -493,20 +480,7 @@ let account = match AccountsManager::add_microsoft_account(
         log::info!("License data: {:?}", license_data);
 
         // Check if user has valid Java Edition license (not trial)
-        let has_valid_license = license_data
-            .get("items")
-            .and_then(|items| items.as_array())
-            .map(|items| {
-                items.iter().any(|item| {
-                    let name = item.get("name").and_then(|n| n.as_str()).unwrap_or("");
-                    let source = item.get("source").and_then(|s| s.as_str()).unwrap_or("");
-
-                    (name == "product_minecraft" || name == "game_minecraft") && source != "TRIAL"
-                })
-            })
-            .unwrap_or(false);
-
-        if !has_valid_license {
+        if !has_java_license(&license_data) {
             return Err("Esta cuenta de Microsoft no tiene una licencia válida de Minecraft Java Edition. Por favor, adquiere el juego antes de continuar.".into());
         }
 
@@ -564,7 +538,7 @@ let account = match AccountsManager::add_microsoft_account(
 }
 
 #[tauri::command]
-pub fn start_microsoft_auth(app_handle: AppHandle) {
+pub fn start_microsoft_auth() {
     let authenticator = MicrosoftAuthenticator::new();
-    authenticator.start_authentication(app_handle);
+    authenticator.start_authentication();
 }
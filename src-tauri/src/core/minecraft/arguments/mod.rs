@@ -1,5 +1,7 @@
 pub mod processor;
 pub mod rules;
+pub mod sanitizer;
 
 pub use processor::ArgumentProcessor;
 pub use rules::RuleEvaluator;
+pub use sanitizer::ArgumentSanitizer;
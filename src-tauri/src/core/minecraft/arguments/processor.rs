@@ -1,5 +1,8 @@
 use super::rules::RuleEvaluator;
+use crate::core::minecraft::legacy;
+use crate::core::minecraft::manifest;
 use crate::core::minecraft::paths::MinecraftPaths;
+use crate::core::minecraft::resolution::GameResolution;
 use crate::core::minecraft_account::MinecraftAccount;
 use serde_json::Value;
 use std::collections::HashMap;
@@ -10,6 +13,8 @@ pub struct ArgumentProcessor<'a> {
     account: &'a MinecraftAccount,
     paths: &'a MinecraftPaths,
     memory: u32,
+    resolution: GameResolution,
+    demo: bool,
 }
 
 impl<'a> ArgumentProcessor<'a> {
@@ -24,15 +29,33 @@ impl<'a> ArgumentProcessor<'a> {
             account,
             paths,
             memory,
+            resolution: GameResolution::default(),
+            demo: false,
         }
     }
 
+    pub fn with_resolution(mut self, resolution: GameResolution) -> Self {
+        self.resolution = resolution;
+        self
+    }
+
+    pub fn with_demo(mut self, demo: bool) -> Self {
+        self.demo = demo;
+        self
+    }
+
     pub fn process_arguments(&self) -> Option<(Vec<String>, Vec<String>)> {
         let placeholders = self.create_placeholders();
         let features = self.create_features_map();
 
         let jvm_args = self.process_jvm_arguments(&placeholders)?;
-        let game_args = self.process_game_arguments(&placeholders, &features)?;
+        let mut game_args = self.process_game_arguments(&placeholders, &features)?;
+        self.append_resolution_arguments(&mut game_args);
+
+        // Antes de 1.13 no existe la regla is_demo_user
+        if self.demo && !game_args.iter().any(|arg| arg == "--demo") {
+            game_args.push("--demo".to_string());
+        }
 
         Some((jvm_args, game_args))
     }
@@ -55,20 +78,29 @@ impl<'a> ArgumentProcessor<'a> {
             "assets_root".to_string(),
             self.paths.assets_dir().to_string_lossy().to_string(),
         );
+        let assets_index_name = self
+            .manifest
+            .get("assets")
+            .and_then(|v| v.as_str())
+            .or_else(|| self.manifest.get("assetIndex")?.get("id")?.as_str())
+            .unwrap_or("legacy")
+            .to_string();
+        // Versiones antiguas (<1.7.2) leen los assets desde un directorio plano
         placeholders.insert(
-            "assets_index_name".to_string(),
-            self.manifest
-                .get("assets")
-                .and_then(|v| v.as_str())
-                .or_else(|| self.manifest.get("assetIndex")?.get("id")?.as_str())
-                .unwrap_or("legacy")
+            "game_assets".to_string(),
+            legacy::game_assets_dir(self.paths, &assets_index_name)
+                .to_string_lossy()
                 .to_string(),
         );
+        placeholders.insert("assets_index_name".to_string(), assets_index_name);
         placeholders.insert("auth_uuid".to_string(), self.account.uuid().to_string());
+        let access_token = self.account.access_token().unwrap_or("null").to_string();
         placeholders.insert(
-            "auth_access_token".to_string(),
-            self.account.access_token().unwrap_or("null").to_string(),
+            "auth_session".to_string(),
+            format!("token:{}:{}", access_token, self.account.uuid()),
         );
+        placeholders.insert("auth_access_token".to_string(), access_token);
+        placeholders.insert("user_properties".to_string(), "{}".to_string());
         placeholders.insert(
             "user_type".to_string(),
             if self.account.user_type() != "offline" {
@@ -78,7 +110,10 @@ impl<'a> ArgumentProcessor<'a> {
             }
             .to_string(),
         );
-        placeholders.insert("version_type".to_string(), "release".to_string());
+        placeholders.insert(
+            "version_type".to_string(),
+            manifest::version_type(self.manifest).to_string(),
+        );
         placeholders.insert(
             "natives_directory".to_string(),
             self.paths.natives_dir().to_string_lossy().to_string(),
@@ -88,14 +123,22 @@ impl<'a> ArgumentProcessor<'a> {
 
         placeholders.insert("classpath".to_string(), self.paths.classpath_str());
 
+        if let Some((width, height)) = self.resolution.custom_size() {
+            placeholders.insert("resolution_width".to_string(), width.to_string());
+            placeholders.insert("resolution_height".to_string(), height.to_string());
+        }
+
         placeholders
     }
 
     fn create_features_map(&self) -> HashMap<String, bool> {
         let mut features = HashMap::new();
-        features.insert("has_custom_resolution".to_string(), false);
+        features.insert(
+            "has_custom_resolution".to_string(),
+            self.resolution.custom_size().is_some(),
+        );
         features.insert("has_quick_plays_support".to_string(), false);
-        features.insert("is_demo_user".to_string(), false);
+        features.insert("is_demo_user".to_string(), self.demo);
         features.insert("is_quick_play_singleplayer".to_string(), false);
         features.insert("is_quick_play_multiplayer".to_string(), false);
         features.insert("is_quick_play_realms".to_string(), false);
@@ -201,6 +244,25 @@ impl<'a> ArgumentProcessor<'a> {
         }
     }
 
+    /// Versions without the `has_custom_resolution` rule (pre-1.13) still
+    /// accept `--width`/`--height`; `--fullscreen` is never in the manifest.
+    fn append_resolution_arguments(&self, game_args: &mut Vec<String>) {
+        if let Some((width, height)) = self.resolution.custom_size() {
+            if !game_args.iter().any(|arg| arg == "--width") {
+                game_args.extend([
+                    "--width".to_string(),
+                    width.to_string(),
+                    "--height".to_string(),
+                    height.to_string(),
+                ]);
+            }
+        }
+
+        if self.resolution.fullscreen && !game_args.iter().any(|arg| arg == "--fullscreen") {
+            game_args.push("--fullscreen".to_string());
+        }
+    }
+
     fn process_arguments_list(
         &self,
         args_obj: &Value,
@@ -214,21 +276,9 @@ impl<'a> ArgumentProcessor<'a> {
                 if let Some(arg_str) = arg.as_str() {
                     processed_args.push(self.replace_placeholders(arg_str, placeholders));
                 } else if arg.is_object() {
-                    if let Some(rules) = arg.get("rules").and_then(|r| r.as_array()) {
-                        let mut should_include = false;
-
-                        for rule in rules {
-                            if RuleEvaluator::should_apply_rule(rule, features) {
-                                should_include = true;
-                                break;
-                            }
-                        }
-
-                        if should_include {
-                            if let Some(value) = arg.get("value") {
-                                processed_args
-                                    .extend(self.process_rule_values(value, placeholders));
-                            }
+                    if RuleEvaluator::applies(arg, features) {
+                        if let Some(value) = arg.get("value") {
+                            processed_args.extend(self.process_rule_values(value, placeholders));
                         }
                     }
                 }
@@ -1,71 +1,340 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
 use serde_json::Value;
 use std::collections::HashMap;
+use sysinfo::System;
 
+/// Evaluates the `rules` of version JSON libraries and arguments.
+///
+/// Same semantics as the official launcher, so bootstrap and launch agree on
+/// what applies: without rules everything applies; with rules nothing does
+/// unless the last rule whose conditions match is an `allow`.
 pub struct RuleEvaluator;
 
+/// `os.version` como lo reporta Java (`System.getProperty("os.version")`)
+static OS_VERSION: Lazy<String> = Lazy::new(java_os_version);
+
+fn java_os_version() -> String {
+    if cfg!(windows) {
+        // sysinfo da la versión comercial ("11 (22631)"); Java da la del kernel NT
+        let version = System::os_version().unwrap_or_default();
+        let release = version.split_whitespace().next().unwrap_or_default();
+        match release {
+            "10" | "11" => "10.0",
+            "8.1" => "6.3",
+            "8" => "6.2",
+            "7" => "6.1",
+            "Vista" => "6.0",
+            "XP" => "5.1",
+            other => other,
+        }
+        .to_string()
+    } else if cfg!(target_os = "macos") {
+        System::os_version().unwrap_or_default()
+    } else {
+        System::kernel_version().unwrap_or_default()
+    }
+}
+
+fn current_os_name() -> &'static str {
+    if cfg!(windows) {
+        "windows"
+    } else if cfg!(target_os = "macos") {
+        "osx"
+    } else {
+        "linux"
+    }
+}
+
+fn is_current_arch(arch: &str) -> bool {
+    match arch {
+        "x86" => cfg!(target_arch = "x86"),
+        "x86_64" => cfg!(target_arch = "x86_64"),
+        "arm" => cfg!(target_arch = "arm"),
+        "arm64" | "aarch64" => cfg!(target_arch = "aarch64"),
+        _ => false,
+    }
+}
+
 impl RuleEvaluator {
-    pub fn should_apply_rule(rule: &Value, features: Option<&HashMap<String, bool>>) -> bool {
-        let action = rule
-            .get("action")
-            .and_then(|a| a.as_str())
-            .unwrap_or("allow");
-        let mut should_apply = action == "allow";
-
-        // Check OS rules
-        if let Some(os_obj) = rule.get("os") {
-            let mut os_match = true;
-
-            if let Some(os_name) = os_obj.get("name").and_then(|n| n.as_str()) {
-                let is_current_os = match os_name {
-                    "windows" => cfg!(windows),
-                    "osx" => cfg!(target_os = "macos"),
-                    "linux" => cfg!(target_os = "linux"),
-                    _ => false,
-                };
-                if !is_current_os {
-                    os_match = false;
+    /// Whether the conditions of `rule` (os name, version and arch, and
+    /// features) hold on this machine. A rule without conditions matches.
+    pub fn rule_matches(rule: &Value, features: Option<&HashMap<String, bool>>) -> bool {
+        if let Some(os) = rule.get("os") {
+            if let Some(name) = os.get("name").and_then(Value::as_str) {
+                // `osx` y `macos` aparecen según la época del manifiesto
+                let name = if name == "macos" { "osx" } else { name };
+                if name != current_os_name() {
+                    return false;
+                }
+            }
+            if let Some(arch) = os.get("arch").and_then(Value::as_str) {
+                if !is_current_arch(arch) {
+                    return false;
                 }
             }
+            if let Some(pattern) = os.get("version").and_then(Value::as_str) {
+                match Regex::new(pattern) {
+                    Ok(regex) if regex.is_match(&OS_VERSION) => {}
+                    Ok(_) => return false,
+                    Err(e) => {
+                        log::warn!("[Rules] Invalid os.version pattern {}: {}", pattern, e);
+                        return false;
+                    }
+                }
+            }
+        }
 
-            if let Some(os_arch) = os_obj.get("arch").and_then(|a| a.as_str()) {
-                let is_current_arch = match os_arch {
-                    "x86" => cfg!(target_arch = "x86"),
-                    "x86_64" => cfg!(target_arch = "x86_64"),
-                    "arm" => cfg!(target_arch = "arm"),
-                    "arm64" => cfg!(target_arch = "aarch64"),
-                    _ => false,
+        if let Some(required) = rule.get("features").and_then(Value::as_object) {
+            for (feature, expected) in required {
+                let Some(expected) = expected.as_bool() else {
+                    continue;
                 };
-                if !is_current_arch {
-                    os_match = false;
+                let actual = features
+                    .and_then(|features| features.get(feature))
+                    .copied()
+                    .unwrap_or(false);
+                if actual != expected {
+                    return false;
                 }
             }
+        }
+
+        true
+    }
+
+    /// Applies `rules` in order: the last matching rule decides.
+    pub fn is_allowed(rules: &[Value], features: Option<&HashMap<String, bool>>) -> bool {
+        if rules.is_empty() {
+            return true;
+        }
+        rules
+            .iter()
+            .rev()
+            .find(|rule| Self::rule_matches(rule, features))
+            .is_some_and(|rule| {
+                rule.get("action")
+                    .and_then(Value::as_str)
+                    .unwrap_or("allow")
+                    == "allow"
+            })
+    }
+
+    /// Rules of a library or argument object; entries without `rules`
+    /// always apply.
+    pub fn applies(entry: &Value, features: Option<&HashMap<String, bool>>) -> bool {
+        match entry.get("rules").and_then(Value::as_array) {
+            Some(rules) => Self::is_allowed(rules, features),
+            None => true,
+        }
+    }
+}
 
-            if action == "allow" {
-                should_apply = os_match;
-            } else {
-                should_apply = !os_match;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn other_os() -> &'static str {
+        if current_os_name() == "linux" {
+            "windows"
+        } else {
+            "linux"
+        }
+    }
+
+    fn current_arch() -> Option<&'static str> {
+        ["x86", "x86_64", "arm", "arm64"]
+            .into_iter()
+            .find(|arch| is_current_arch(arch))
+    }
+
+    fn other_arch() -> &'static str {
+        if cfg!(target_arch = "x86") {
+            "x86_64"
+        } else {
+            "x86"
+        }
+    }
+
+    fn features(enabled: &[&str]) -> HashMap<String, bool> {
+        enabled
+            .iter()
+            .map(|feature| (feature.to_string(), true))
+            .collect()
+    }
+
+    /// `(condiciones de la regla, features activas, si se cumplen)`
+    fn conditions() -> Vec<(Value, HashMap<String, bool>, bool)> {
+        let version = format!("^{}$", regex::escape(&OS_VERSION));
+        let mut conditions = vec![
+            (json!({}), features(&[]), true),
+            (
+                json!({ "os": { "name": current_os_name() } }),
+                features(&[]),
+                true,
+            ),
+            (
+                json!({ "os": { "name": other_os() } }),
+                features(&[]),
+                false,
+            ),
+            (json!({ "os": { "version": version } }), features(&[]), true),
+            (
+                json!({ "os": { "version": "^0\\.0-never$" } }),
+                features(&[]),
+                false,
+            ),
+            // Un patrón inválido nunca coincide
+            (json!({ "os": { "version": "(" } }), features(&[]), false),
+            (
+                json!({ "os": { "arch": other_arch() } }),
+                features(&[]),
+                false,
+            ),
+            (
+                json!({ "os": { "name": current_os_name(), "version": "^0\\.0-never$" } }),
+                features(&[]),
+                false,
+            ),
+            (
+                json!({ "features": { "has_custom_resolution": true } }),
+                features(&["has_custom_resolution"]),
+                true,
+            ),
+            (
+                json!({ "features": { "has_custom_resolution": true } }),
+                features(&[]),
+                false,
+            ),
+            (
+                json!({ "features": { "is_demo_user": false } }),
+                features(&[]),
+                true,
+            ),
+            (
+                json!({ "features": { "is_demo_user": true, "has_custom_resolution": true } }),
+                features(&["is_demo_user"]),
+                false,
+            ),
+            (
+                json!({ "os": { "name": other_os() }, "features": { "is_demo_user": true } }),
+                features(&["is_demo_user"]),
+                false,
+            ),
+        ];
+        if let Some(arch) = current_arch() {
+            conditions.push((json!({ "os": { "arch": arch } }), features(&[]), true));
+            conditions.push((
+                json!({ "os": { "name": current_os_name(), "arch": arch } }),
+                features(&[]),
+                true,
+            ));
+        }
+        conditions
+    }
+
+    fn rule(action: &str, conditions: &Value) -> Value {
+        let mut rule = conditions.clone();
+        rule["action"] = json!(action);
+        rule
+    }
+
+    #[test]
+    fn single_rule_matrix() {
+        for (conditions, features, matches) in conditions() {
+            assert_eq!(
+                RuleEvaluator::rule_matches(&conditions, Some(&features)),
+                matches,
+                "{}",
+                conditions
+            );
+            for action in ["allow", "disallow"] {
+                let rules = [rule(action, &conditions)];
+                assert_eq!(
+                    RuleEvaluator::is_allowed(&rules, Some(&features)),
+                    matches && action == "allow",
+                    "{} {}",
+                    action,
+                    conditions
+                );
             }
         }
+    }
 
-        // Check feature rules
-        if let Some(feature_obj) = rule.get("features") {
-            if let Some(features_map) = features {
-                for (feature_name, feature_value) in
-                    feature_obj.as_object().unwrap_or(&serde_json::Map::new())
-                {
-                    if let Some(expected_value) = feature_value.as_bool() {
-                        let actual_value = *features_map.get(feature_name).unwrap_or(&false);
-                        if actual_value != expected_value {
-                            should_apply = action != "allow";
-                            break;
-                        }
-                    }
-                }
-            } else {
-                should_apply = action != "allow";
+    #[test]
+    fn last_matching_rule_wins() {
+        // Patrón habitual: allow general y una excepción
+        for (conditions, features, matches) in conditions() {
+            for action in ["allow", "disallow"] {
+                let rules = [json!({ "action": "allow" }), rule(action, &conditions)];
+                assert_eq!(
+                    RuleEvaluator::is_allowed(&rules, Some(&features)),
+                    !matches || action == "allow",
+                    "allow, then {} {}",
+                    action,
+                    conditions
+                );
+
+                let rules = [json!({ "action": "disallow" }), rule(action, &conditions)];
+                assert_eq!(
+                    RuleEvaluator::is_allowed(&rules, Some(&features)),
+                    matches && action == "allow",
+                    "disallow, then {} {}",
+                    action,
+                    conditions
+                );
             }
         }
+    }
+
+    #[test]
+    fn os_aliases_match() {
+        if current_os_name() == "osx" {
+            assert!(RuleEvaluator::rule_matches(
+                &json!({ "os": { "name": "macos" } }),
+                None
+            ));
+        }
+        if cfg!(target_arch = "aarch64") {
+            assert!(RuleEvaluator::rule_matches(
+                &json!({ "os": { "arch": "aarch64" } }),
+                None
+            ));
+        }
+        assert!(!RuleEvaluator::rule_matches(
+            &json!({ "os": { "arch": "sparc" } }),
+            None
+        ));
+    }
+
+    #[test]
+    fn missing_features_count_as_disabled() {
+        let required = json!({ "features": { "has_custom_resolution": true } });
+        let disabled = json!({ "features": { "has_custom_resolution": false } });
+        // Los valores que no son booleanos se ignoran
+        let malformed = json!({ "features": { "has_custom_resolution": "yes" } });
+
+        assert!(!RuleEvaluator::rule_matches(&required, None));
+        assert!(RuleEvaluator::rule_matches(&disabled, None));
+        assert!(RuleEvaluator::rule_matches(&malformed, None));
+    }
 
-        should_apply
+    #[test]
+    fn entries_without_rules_apply() {
+        assert!(RuleEvaluator::applies(&json!({ "name": "a:b:1" }), None));
+        assert!(RuleEvaluator::applies(
+            &json!({ "name": "a:b:1", "rules": [] }),
+            None
+        ));
+        assert!(!RuleEvaluator::applies(
+            &json!({ "name": "a:b:1", "rules": [{ "action": "disallow" }] }),
+            None
+        ));
+        // Sin `action` la regla permite
+        assert!(RuleEvaluator::applies(
+            &json!({ "name": "a:b:1", "rules": [{ "os": { "name": current_os_name() } }] }),
+            None
+        ));
     }
 }
@@ -0,0 +1,220 @@
+use crate::core::minecraft::legacy::REPEATABLE_LEGACY_ARGS;
+use std::path::Path;
+
+/// Removes repeated options from the final JVM and game arguments.
+///
+/// Merged manifests (vanilla, loader, OptiFine...) and the launcher itself
+/// can pass the same option more than once. Options are compared by what
+/// they mean, not as strings: `-Xmx2G` and `-Xmx4096M` are the same flag.
+/// Like the JVM, the last value wins, kept at the position of the first
+/// occurrence. The heap size and `java.library.path` belong to the launcher:
+/// they always get the instance's values.
+pub struct ArgumentSanitizer;
+
+/// Opciones de la JVM cuyo valor va en el argumento siguiente
+const JVM_OPTIONS_WITH_VALUE: &[&str] = &[
+    "-cp",
+    "-classpath",
+    "--class-path",
+    "-p",
+    "--module-path",
+    "--add-modules",
+    "--add-opens",
+    "--add-exports",
+    "--add-reads",
+];
+
+/// Se acumulan: sólo se quitan las repeticiones exactas
+const JVM_REPEATABLE: &[&str] = &[
+    "--add-modules",
+    "--add-opens",
+    "--add-exports",
+    "--add-reads",
+];
+
+const SIZED_OPTIONS: &[&str] = &["-Xmx", "-Xms", "-Xss", "-Xmn"];
+
+/// An option and its separate value, if it has one. Arguments without a
+/// `key` are never deduplicated.
+struct Arg {
+    key: Option<String>,
+    tokens: Vec<String>,
+}
+
+fn jvm_key(option: &str, value: Option<&str>) -> String {
+    if let Some(property) = option.strip_prefix("-D") {
+        return format!("-D{}", property.split('=').next().unwrap_or(property));
+    }
+    if let Some(flag) = option.strip_prefix("-XX:") {
+        let flag = flag.trim_start_matches(['+', '-']);
+        return format!("-XX:{}", flag.split('=').next().unwrap_or(flag));
+    }
+    if let Some(prefix) = SIZED_OPTIONS
+        .iter()
+        .find(|prefix| option.starts_with(**prefix))
+    {
+        return prefix.to_string();
+    }
+    match option {
+        "-classpath" | "--class-path" => "-cp".to_string(),
+        "--module-path" => "-p".to_string(),
+        _ if JVM_REPEATABLE.contains(&option) => format!("{} {}", option, value.unwrap_or("")),
+        _ => option.to_string(),
+    }
+}
+
+fn split_jvm(args: Vec<String>) -> Vec<Arg> {
+    let mut result = Vec::new();
+    let mut tokens = args.into_iter();
+    while let Some(option) = tokens.next() {
+        let value = if JVM_OPTIONS_WITH_VALUE.contains(&option.as_str()) {
+            tokens.next()
+        } else {
+            None
+        };
+        let key = Some(jvm_key(&option, value.as_deref()));
+        result.push(Arg {
+            key,
+            tokens: std::iter::once(option).chain(value).collect(),
+        });
+    }
+    result
+}
+
+fn split_game(args: Vec<String>) -> Vec<Arg> {
+    let mut result = Vec::new();
+    let mut tokens = args.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        if !token.starts_with("--") {
+            result.push(Arg {
+                key: None,
+                tokens: vec![token],
+            });
+            continue;
+        }
+
+        let value = tokens.next_if(|next| !next.starts_with("--"));
+        let key = if REPEATABLE_LEGACY_ARGS.contains(&token.as_str()) {
+            format!("{} {}", token, value.as_deref().unwrap_or(""))
+        } else {
+            token.clone()
+        };
+        result.push(Arg {
+            key: Some(key),
+            tokens: std::iter::once(token).chain(value).collect(),
+        });
+    }
+    result
+}
+
+fn dedupe(args: Vec<Arg>, kind: &str) -> Vec<Arg> {
+    let mut result: Vec<Arg> = Vec::new();
+    for arg in args {
+        let kept = arg.key.as_ref().and_then(|key| {
+            result
+                .iter_mut()
+                .find(|kept| kept.key.as_ref() == Some(key))
+        });
+        match kept {
+            Some(kept) => {
+                if kept.tokens == arg.tokens {
+                    log::info!(
+                        "[Arguments] Removed duplicate {} argument: {}",
+                        kind,
+                        arg.tokens.join(" ")
+                    );
+                } else {
+                    log::info!(
+                        "[Arguments] {} argument {} overridden by {}",
+                        kind,
+                        kept.tokens.join(" "),
+                        arg.tokens.join(" ")
+                    );
+                }
+                kept.tokens = arg.tokens;
+            }
+            None => result.push(arg),
+        }
+    }
+    result
+}
+
+fn join(args: Vec<Arg>) -> Vec<String> {
+    args.into_iter().flat_map(|arg| arg.tokens).collect()
+}
+
+/// Size of a `-Xms`/`-Xmx` value (`512M`, `4G`, `2097152k`, bytes) in MB.
+fn size_mb(value: &str) -> Option<u64> {
+    let digits = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(digits);
+    let number: u64 = number.parse().ok()?;
+    match unit.to_ascii_lowercase().as_str() {
+        "" => Some(number / (1024 * 1024)),
+        "k" => Some(number / 1024),
+        "m" => Some(number),
+        "g" => Some(number * 1024),
+        "t" => Some(number * 1024 * 1024),
+        _ => None,
+    }
+}
+
+/// Replaces the value of `key` with `value`, logging it when it changes.
+fn enforce(args: &mut [Arg], key: &str, value: String) {
+    if let Some(arg) = args.iter_mut().find(|arg| arg.key.as_deref() == Some(key)) {
+        if arg.tokens != [value.as_str()] {
+            log::info!(
+                "[Arguments] {} replaced by the launcher's {}",
+                arg.tokens.join(" "),
+                value
+            );
+            arg.tokens = vec![value];
+        }
+    }
+}
+
+impl ArgumentSanitizer {
+    /// JVM arguments without repeated options, with the instance's heap
+    /// size and natives directory.
+    pub fn jvm(args: Vec<String>, memory_mb: u32, natives_dir: &Path) -> Vec<String> {
+        let mut args = dedupe(split_jvm(args), "JVM");
+
+        let xmx = format!("-Xmx{}M", memory_mb);
+        if args.iter().any(|arg| arg.key.as_deref() == Some("-Xmx")) {
+            enforce(&mut args, "-Xmx", xmx);
+        } else {
+            args.insert(
+                0,
+                Arg {
+                    key: Some("-Xmx".to_string()),
+                    tokens: vec![xmx],
+                },
+            );
+        }
+
+        // Un -Xms mayor que -Xmx impide arrancar la JVM
+        let xms_too_big = args
+            .iter()
+            .find(|arg| arg.key.as_deref() == Some("-Xms"))
+            .and_then(|arg| size_mb(arg.tokens[0].trim_start_matches("-Xms")))
+            .is_some_and(|xms| xms > u64::from(memory_mb));
+        if xms_too_big {
+            enforce(&mut args, "-Xms", format!("-Xms{}M", memory_mb));
+        }
+
+        enforce(
+            &mut args,
+            "-Djava.library.path",
+            format!("-Djava.library.path={}", natives_dir.display()),
+        );
+
+        join(args)
+    }
+
+    /// Game arguments without repeated flags; repeatable ones such as
+    /// `--tweakClass` only lose exact repetitions.
+    pub fn game(args: Vec<String>) -> Vec<String> {
+        join(dedupe(split_game(args), "game"))
+    }
+}
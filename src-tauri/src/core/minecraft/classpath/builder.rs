@@ -1,95 +1,271 @@
+use crate::core::minecraft::maven_version;
 use crate::core::minecraft::paths::MinecraftPaths;
+use crate::core::minecraft::MavenCoordinate;
 use crate::core::minecraft::RuleEvaluator;
+use serde::Serialize;
 use serde_json::Value;
-use std::collections::HashSet;
-use std::path::{Path, MAIN_SEPARATOR};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Repositorio de las librerías que sólo declaran `name`
+const LIBRARIES_URL: &str = "https://libraries.minecraft.net/";
+
+const NATIVE_CLASSIFIER: &str = if cfg!(windows) {
+    "natives-windows"
+} else if cfg!(target_os = "linux") {
+    "natives-linux"
+} else {
+    "natives-macos"
+};
 
 pub struct ClasspathBuilder<'a> {
     manifest: &'a Value,
     paths: &'a MinecraftPaths,
 }
 
+/// A library already placed on the classpath.
+struct ClasspathEntry {
+    version: String,
+    path: String,
+}
+
+/// A jar requested by the manifest.
+struct LibraryJar {
+    name: String,
+    classifier: Option<&'static str>,
+    path: PathBuf,
+    url: Option<String>,
+    sha1: Option<String>,
+}
+
+/// A classpath jar that isn't on disk, with where to download it from.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingLibrary {
+    pub name: String,
+    pub path: String,
+    pub url: Option<String>,
+    pub sha1: Option<String>,
+}
+
 impl<'a> ClasspathBuilder<'a> {
     pub fn new(manifest: &'a Value, paths: &'a MinecraftPaths) -> Self {
         Self { manifest, paths }
     }
 
     pub fn build(&self) -> Option<String> {
-        let mut entries = Vec::new();
-        let mut seen = HashSet::new();
+        let mut entries: Vec<ClasspathEntry> = Vec::new();
+        // group:artifact[:classifier] -> posición en `entries`
+        let mut by_identity: HashMap<String, usize> = HashMap::new();
+        let mut seen_paths = HashSet::new();
 
         // Add client JAR
         let client_path = self.paths.client_jar().to_string_lossy().to_string();
-        entries.push(client_path.clone());
-        seen.insert(client_path);
-
-        // Process libraries
-        if let Some(libs) = self.manifest.get("libraries").and_then(|v| v.as_array()) {
-            for lib in libs {
-                if !self.should_include_library(lib) {
-                    continue;
-                }
+        seen_paths.insert(client_path.clone());
+        entries.push(ClasspathEntry {
+            version: String::new(),
+            path: client_path,
+        });
 
-                // Add main artifact
-                if let Some(path_val) = lib
-                    .get("downloads")
-                    .and_then(|d| d.get("artifact"))
-                    .and_then(|a| a.get("path"))
-                    .and_then(Value::as_str)
-                {
-                    let jar = self
-                        .paths
-                        .libraries_dir()
-                        .join(path_val.replace('/', &MAIN_SEPARATOR.to_string()));
-                    self.add_if_new(&jar, &mut entries, &mut seen);
-                }
+        for jar in self.jars() {
+            self.add_library(
+                &jar.name,
+                jar.classifier,
+                &jar.path,
+                &mut entries,
+                &mut by_identity,
+                &mut seen_paths,
+            );
+        }
 
-                // Add native classifiers
-                if let Some(classifiers) = lib
-                    .get("downloads")
-                    .and_then(|d| d.get("classifiers"))
-                    .and_then(Value::as_object)
-                {
-                    let os_classifier = if cfg!(windows) {
-                        "natives-windows"
-                    } else if cfg!(target_os = "linux") {
-                        "natives-linux"
-                    } else {
-                        "natives-macos"
-                    };
-
-                    if let Some(info) = classifiers.get(os_classifier) {
-                        if let Some(path_val) = info.get("path").and_then(Value::as_str) {
-                            let native_jar = self
-                                .paths
-                                .libraries_dir()
-                                .join(path_val.replace('/', &MAIN_SEPARATOR.to_string()));
-                            self.add_if_new(&native_jar, &mut entries, &mut seen);
-                        }
-                    }
-                }
+        Some(
+            entries
+                .into_iter()
+                .map(|entry| entry.path)
+                .collect::<Vec<_>>()
+                .join(self.classpath_separator()),
+        )
+    }
+
+    /// Jars the classpath needs that aren't on disk. A library is only
+    /// missing when no other version of the same artifact is present, since
+    /// `build` would keep that one anyway.
+    pub fn missing(&self) -> Vec<MissingLibrary> {
+        let mut missing = Vec::new();
+
+        let client_jar = self.paths.client_jar();
+        if !client_jar.exists() {
+            let client = self.manifest.get("downloads").and_then(|d| d.get("client"));
+            missing.push(MissingLibrary {
+                name: format!("minecraft:client:{}", self.paths.minecraft_version()),
+                path: client_jar.to_string_lossy().to_string(),
+                url: download_field(client, "url"),
+                sha1: download_field(client, "sha1"),
+            });
+        }
+
+        let jars = self.jars();
+        let present: HashSet<String> = jars
+            .iter()
+            .filter(|jar| jar.path.exists())
+            .filter_map(|jar| library_identity(&jar.name, jar.classifier))
+            .map(|(identity, _)| identity)
+            .collect();
+        let mut seen_paths = HashSet::new();
+
+        for jar in jars {
+            if jar.path.exists() || !seen_paths.insert(jar.path.clone()) {
+                continue;
             }
+            let replaced = library_identity(&jar.name, jar.classifier)
+                .is_some_and(|(identity, _)| present.contains(&identity));
+            if replaced {
+                continue;
+            }
+            missing.push(MissingLibrary {
+                name: jar.name,
+                path: jar.path.to_string_lossy().to_string(),
+                url: jar.url,
+                sha1: jar.sha1,
+            });
         }
+        missing
+    }
+
+    /// Every library jar the manifest asks for on this OS, present or not.
+    fn jars(&self) -> Vec<LibraryJar> {
+        let mut jars = Vec::new();
+        let Some(libs) = self.manifest.get("libraries").and_then(|v| v.as_array()) else {
+            return jars;
+        };
+
+        for lib in libs {
+            if !self.should_include_library(lib) {
+                continue;
+            }
 
-        Some(entries.join(self.classpath_separator()))
+            let name = lib.get("name").and_then(Value::as_str).unwrap_or("");
+            let artifact = lib.get("downloads").and_then(|d| d.get("artifact"));
+
+            // Main artifact (or its maven path for libraries without downloads)
+            let artifact_path = artifact
+                .and_then(|a| a.get("path"))
+                .and_then(Value::as_str)
+                .map(PathBuf::from);
+            let artifact = match artifact_path {
+                Some(relative) => Some((
+                    relative,
+                    download_field(artifact, "url"),
+                    download_field(artifact, "sha1"),
+                )),
+                None if lib.get("downloads").is_none() => {
+                    MavenCoordinate::parse(name).map(|coordinate| {
+                        let repository = lib
+                            .get("url")
+                            .and_then(Value::as_str)
+                            .unwrap_or(LIBRARIES_URL);
+                        let url = format!(
+                            "{}/{}",
+                            repository.trim_end_matches('/'),
+                            coordinate.url_path()
+                        );
+                        (coordinate.relative_path(), Some(url), None)
+                    })
+                }
+                None => None,
+            };
+
+            if let Some((relative, url, sha1)) = artifact {
+                jars.push(LibraryJar {
+                    name: name.to_string(),
+                    classifier: None,
+                    path: self.paths.library(&relative.to_string_lossy()),
+                    url,
+                    sha1,
+                });
+            }
+
+            // Native classifiers
+            let native = lib
+                .get("downloads")
+                .and_then(|d| d.get("classifiers"))
+                .and_then(|c| c.get(NATIVE_CLASSIFIER));
+            if let Some(path_val) = native.and_then(|n| n.get("path")).and_then(Value::as_str) {
+                jars.push(LibraryJar {
+                    name: name.to_string(),
+                    classifier: Some(NATIVE_CLASSIFIER),
+                    path: self.paths.library(path_val),
+                    url: download_field(native, "url"),
+                    sha1: download_field(native, "sha1"),
+                });
+            }
+        }
+        jars
     }
 
     fn should_include_library(&self, lib: &Value) -> bool {
-        lib.get("rules")
-            .and_then(|r| r.as_array())
-            .map(|rules| {
-                rules
-                    .iter()
-                    .any(|rule| RuleEvaluator::should_apply_rule(rule, None))
-            })
-            .unwrap_or(true)
+        RuleEvaluator::applies(lib, None)
     }
 
-    fn add_if_new(&self, path: &Path, entries: &mut Vec<String>, seen: &mut HashSet<String>) {
-        if path.exists() {
-            let s = path.to_string_lossy().to_string();
-            if seen.insert(s.clone()) {
-                entries.push(s);
+    /// Adds a library keyed by `group:artifact[:classifier]`. When the same
+    /// artifact appears twice (vanilla + Forge, different folders...) only
+    /// the highest version is kept; on equal versions the later entry (the
+    /// child manifest) wins.
+    fn add_library(
+        &self,
+        name: &str,
+        native_classifier: Option<&str>,
+        path: &Path,
+        entries: &mut Vec<ClasspathEntry>,
+        by_identity: &mut HashMap<String, usize>,
+        seen_paths: &mut HashSet<String>,
+    ) {
+        if !path.exists() {
+            return;
+        }
+
+        let path_str = path.to_string_lossy().to_string();
+        if !seen_paths.insert(path_str.clone()) {
+            return;
+        }
+
+        let (identity, version) = match library_identity(name, native_classifier) {
+            Some(identity) => identity,
+            None => {
+                // Sin coordenadas maven: sólo deduplicamos por ruta
+                entries.push(ClasspathEntry {
+                    version: String::new(),
+                    path: path_str,
+                });
+                return;
+            }
+        };
+
+        match by_identity.get(&identity) {
+            Some(&index) => {
+                let existing = &mut entries[index];
+                let replace = maven_version::compare(&version, &existing.version) != Ordering::Less;
+
+                log::warn!(
+                    "[ClasspathBuilder] Conflict for {}: {} vs {}, keeping {}",
+                    identity,
+                    existing.version,
+                    version,
+                    if replace { &version } else { &existing.version }
+                );
+
+                if replace {
+                    existing.version = version;
+                    existing.path = path_str;
+                }
+            }
+            None => {
+                by_identity.insert(identity, entries.len());
+                entries.push(ClasspathEntry {
+                    version,
+                    path: path_str,
+                });
             }
         }
     }
@@ -102,3 +278,21 @@ impl<'a> ClasspathBuilder<'a> {
         }
     }
 }
+
+/// Non-empty string field of a `downloads` entry.
+fn download_field(download: Option<&Value>, field: &str) -> Option<String> {
+    download
+        .and_then(|d| d.get(field))
+        .and_then(Value::as_str)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+/// `group:artifact:version[:classifier][@ext]` -> (`group:artifact[:classifier]`, version).
+fn library_identity(name: &str, native_classifier: Option<&str>) -> Option<(String, String)> {
+    let mut coordinate = MavenCoordinate::parse(name)?;
+    if let Some(classifier) = native_classifier {
+        coordinate.classifier = Some(classifier.to_string());
+    }
+    Some((coordinate.identity(), coordinate.version))
+}
@@ -1,3 +1,5 @@
 pub mod builder;
+pub mod repair;
 
-pub use builder::ClasspathBuilder;
+pub use builder::{ClasspathBuilder, MissingLibrary};
+pub use repair::repair_missing_libraries;
@@ -0,0 +1,80 @@
+use super::builder::{ClasspathBuilder, MissingLibrary};
+use crate::core::download_cache::{self, HashAlgo};
+use crate::core::http_client;
+use crate::core::inflight_downloads;
+use crate::core::minecraft::paths::MinecraftPaths;
+use crate::core::mirrors;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+/// Downloads the classpath jars that are missing from disk. Returns how many
+/// were restored, or the ones still missing afterwards.
+pub fn repair_missing_libraries(
+    manifest: &Value,
+    paths: &MinecraftPaths,
+) -> Result<usize, Vec<MissingLibrary>> {
+    let builder = ClasspathBuilder::new(manifest, paths);
+    let missing = builder.missing();
+    if missing.is_empty() {
+        return Ok(0);
+    }
+
+    log::warn!(
+        "[Classpath] {} missing libraries, trying to download them",
+        missing.len()
+    );
+    let client = http_client::blocking_client();
+    for library in &missing {
+        if let Err(e) = restore_library(&client, library) {
+            log::error!("[Classpath] Could not restore {}: {}", library.name, e);
+        }
+    }
+
+    let still_missing = builder.missing();
+    if still_missing.is_empty() {
+        log::info!("[Classpath] Restored {} missing libraries", missing.len());
+        Ok(missing.len())
+    } else {
+        Err(still_missing)
+    }
+}
+
+fn restore_library(
+    client: &reqwest::blocking::Client,
+    library: &MissingLibrary,
+) -> Result<(), String> {
+    let destination = Path::new(&library.path);
+    let hash = library.sha1.as_deref();
+    if hash.is_some_and(|hash| download_cache::restore(hash, destination)) {
+        return Ok(());
+    }
+
+    let url = library
+        .url
+        .as_deref()
+        .ok_or_else(|| "no download URL".to_string())?;
+    // Se escribe aparte y se renombra: un jar a medias no debe contar como presente
+    inflight_downloads::download(destination, hash, |temp| {
+        let bytes = mirrors::get(client, url)
+            .and_then(|response| response.error_for_status())
+            .and_then(|response| response.bytes())
+            .map_err(|e| format!("download error: {}", e))?;
+        fs::write(temp, &bytes).map_err(|e| format!("Error writing file: {}", e))?;
+
+        match hash {
+            Some(hash)
+                if download_cache::file_hash(temp, HashAlgo::Sha1).as_deref()
+                    != Some(hash.to_lowercase().as_str()) =>
+            {
+                Err(format!("hash mismatch for {}", url))
+            }
+            _ => Ok(()),
+        }
+    })?;
+
+    if let Some(hash) = hash {
+        download_cache::store(destination, hash);
+    }
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use crate::config::validation::{parse_env_var, split_command_line, validate_env_var_name};
+use crate::config::ConfigManager;
+use crate::core::game_language::validate_language_code;
+use crate::core::minecraft::paths::MinecraftPaths;
+use crate::core::minecraft::sandbox::InstanceSandbox;
+use crate::core::minecraft_instance::MinecraftInstance;
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+
+/// Environment variables and wrapper command applied to the game process.
+///
+/// The instance sandbox directories are applied first, then the global
+/// values from the config, and the instance's `envVars` / `wrapperCommand`
+/// override them.
+#[derive(Debug, Default)]
+pub struct LaunchEnvironment {
+    env_vars: HashMap<String, String>,
+    wrapper: Vec<String>,
+}
+
+impl LaunchEnvironment {
+    pub fn new(
+        instance: &MinecraftInstance,
+        paths: &MinecraftPaths,
+        config: &ConfigManager,
+    ) -> Result<Self, String> {
+        let placeholders = Self::placeholders(instance, paths);
+        let mut env_vars: HashMap<String, String> = InstanceSandbox::new(instance)
+            .env_vars()
+            .into_iter()
+            .collect();
+
+        for entry in config.get_env_vars() {
+            let (key, value) = parse_env_var(&entry).map_err(|e| e.to_string())?;
+            env_vars.insert(key, replace_placeholders(&value, &placeholders));
+        }
+
+        if let Some(instance_vars) = &instance.envVars {
+            for (key, value) in instance_vars {
+                validate_env_var_name(key).map_err(|e| e.to_string())?;
+                env_vars.insert(key.clone(), replace_placeholders(value, &placeholders));
+            }
+        }
+
+        let wrapper_command = instance
+            .wrapperCommand
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .or_else(|| config.get_wrapper_command());
+
+        let wrapper = match wrapper_command {
+            Some(command) => split_command_line(&command)
+                .map_err(|e| e.to_string())?
+                .iter()
+                .map(|part| replace_placeholders(part, &placeholders))
+                .collect(),
+            None => Vec::new(),
+        };
+
+        Ok(Self { env_vars, wrapper })
+    }
+
+    fn placeholders(
+        instance: &MinecraftInstance,
+        paths: &MinecraftPaths,
+    ) -> HashMap<&'static str, String> {
+        let mut map = HashMap::new();
+        map.insert("instance_id", instance.instanceId.clone());
+        map.insert("instance_name", instance.instanceName.clone());
+        map.insert(
+            "instance_dir",
+            instance.instanceDirectory.clone().unwrap_or_default(),
+        );
+        map.insert("game_dir", paths.game_dir().to_string_lossy().to_string());
+        map.insert("java_path", paths.java_path().to_string_lossy().to_string());
+        map.insert(
+            "natives_dir",
+            paths.natives_dir().to_string_lossy().to_string(),
+        );
+        map.insert("version_name", instance.minecraftVersion.clone());
+        map
+    }
+
+    /// Builds the `Command` for `java_path`, prefixed by the wrapper (if any)
+    /// and with the extra environment variables applied.
+    pub fn command(&self, java_path: &Path) -> Command {
+        let mut command = match self.wrapper.split_first() {
+            Some((program, args)) => {
+                let mut command = Command::new(program);
+                command.args(args).arg(java_path);
+                command
+            }
+            None => Command::new(java_path),
+        };
+
+        command.envs(&self.env_vars);
+        command
+    }
+
+    pub fn env_vars(&self) -> &HashMap<String, String> {
+        &self.env_vars
+    }
+
+    pub fn wrapper(&self) -> &[String] {
+        &self.wrapper
+    }
+}
+
+/// Validates the per-instance launch overrides before they are persisted.
+pub fn validate_instance_overrides(instance: &MinecraftInstance) -> Result<(), String> {
+    if let Some(vars) = &instance.envVars {
+        for key in vars.keys() {
+            validate_env_var_name(key).map_err(|e| e.to_string())?;
+        }
+    }
+
+    if let Some(command) = &instance.wrapperCommand {
+        split_command_line(command).map_err(|e| e.to_string())?;
+    }
+
+    if let Some(language) = instance.gameLanguage.as_deref().filter(|l| !l.is_empty()) {
+        validate_language_code(language)?;
+    }
+
+    if let Some(resolution) = &instance.resolution {
+        resolution.validate()?;
+    }
+
+    Ok(())
+}
+
+/// Replaces `${name}` placeholders, leaving unknown ones untouched.
+fn replace_placeholders(value: &str, placeholders: &HashMap<&'static str, String>) -> String {
+    let mut result = value.to_string();
+    for (key, replacement) in placeholders {
+        result = result.replace(&format!("${{{}}}", key), replacement);
+    }
+    result
+}
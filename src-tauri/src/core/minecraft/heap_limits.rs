@@ -0,0 +1,102 @@
+//! Guardrails for the game's maximum heap (`-Xmx`).
+
+use crate::core::events::{self, LauncherEvent};
+use crate::core::system_resources::SystemMemory;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+/// Heap que una JVM de 32 bits reserva sin fallar; en Windows el espacio
+/// contiguo es aún menor
+const MAX_32BIT_HEAP_MB: u32 = if cfg!(windows) { 1024 } else { 2048 };
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JvmBitness {
+    Bits32,
+    Bits64,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum HeapLimitReason {
+    /// Más memoria de la que tiene el equipo
+    SystemMemory,
+    /// La JVM es de 32 bits
+    Jvm32Bit,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeapAdjustment {
+    pub instance_id: String,
+    pub requested_mb: u32,
+    pub applied_mb: u32,
+    pub reason: HeapLimitReason,
+    pub system_total_mb: u64,
+}
+
+/// Bitness of the JVM at `java_path`, from the `release` file of its
+/// installation (`OS_ARCH`).
+pub fn jvm_bitness(java_path: &Path) -> JvmBitness {
+    // <java home>/bin/java
+    let Some(java_home) = java_path.parent().and_then(Path::parent) else {
+        return JvmBitness::Unknown;
+    };
+    let Ok(release) = fs::read_to_string(java_home.join("release")) else {
+        return JvmBitness::Unknown;
+    };
+    let arch = release.lines().find_map(|line| {
+        line.strip_prefix("OS_ARCH=")
+            .map(|value| value.trim().trim_matches('"').to_ascii_lowercase())
+    });
+
+    match arch.as_deref() {
+        Some("x86" | "i386" | "i486" | "i586" | "i686" | "arm" | "aarch32") => JvmBitness::Bits32,
+        Some(arch) if arch.contains("64") => JvmBitness::Bits64,
+        _ => JvmBitness::Unknown,
+    }
+}
+
+/// Highest heap that can work with this memory and JVM, and what imposes
+/// it. The lowest limit wins.
+pub fn heap_limit(system: &SystemMemory, bitness: JvmBitness) -> (u32, HeapLimitReason) {
+    let system_limit = system.max_heap_mb();
+    if bitness == JvmBitness::Bits32 && MAX_32BIT_HEAP_MB < system_limit {
+        (MAX_32BIT_HEAP_MB, HeapLimitReason::Jvm32Bit)
+    } else {
+        (system_limit, HeapLimitReason::SystemMemory)
+    }
+}
+
+/// Heap to launch the instance with: `requested_mb`, or the safe maximum
+/// when it is above it, in which case the frontend is warned.
+pub fn guard_heap(instance_id: &str, requested_mb: u32, java_path: &Path) -> u32 {
+    let system = SystemMemory::detect();
+    // Sin datos de memoria no hay nada con qué comparar
+    if system.total_mb == 0 {
+        return requested_mb;
+    }
+    let (limit_mb, reason) = heap_limit(&system, jvm_bitness(java_path));
+    if requested_mb <= limit_mb {
+        return requested_mb;
+    }
+
+    log::warn!(
+        "[HeapLimits] {} MB requested for instance {} but the limit is {} MB ({:?}, {} MB of RAM); using {} MB",
+        requested_mb,
+        instance_id,
+        limit_mb,
+        reason,
+        system.total_mb,
+        limit_mb
+    );
+    events::emit(LauncherEvent::InstanceMemoryAdjusted(HeapAdjustment {
+        instance_id: instance_id.to_string(),
+        requested_mb,
+        applied_mb: limit_mb,
+        reason,
+        system_total_mb: system.total_mb,
+    }));
+    limit_mb
+}
@@ -0,0 +1,31 @@
+use crate::core::accounts_manager::AccountsManager;
+use crate::core::minecraft_account::MinecraftAccount;
+use serde::Deserialize;
+
+/// Nombre del jugador en modo demo cuando la instancia no tiene cuenta
+const DEMO_USERNAME: &str = "Player";
+
+/// Per-launch options chosen by the user, not stored in the instance.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchOptions {
+    /// Starts the game in demo mode (`is_demo_user`). The instance account is
+    /// optional: without one an offline placeholder account is used.
+    #[serde(default)]
+    pub demo: bool,
+    /// Plays offline if the instance account is missing or its session has
+    /// expired, instead of stopping (same as the `offlineFallback` setting).
+    #[serde(default)]
+    pub allow_offline_fallback: bool,
+    /// Cuenta offline elegida por `InstanceLauncher` tras aceptar el modo sin conexión
+    #[serde(skip)]
+    pub offline_account: Option<MinecraftAccount>,
+}
+
+impl LaunchOptions {
+    pub fn demo_account() -> MinecraftAccount {
+        let uuid = AccountsManager::get_offline_player_uuid(DEMO_USERNAME)
+            .unwrap_or_else(|_| uuid::Uuid::nil().to_string());
+        MinecraftAccount::new(DEMO_USERNAME.to_string(), uuid, None, "offline".to_string())
+    }
+}
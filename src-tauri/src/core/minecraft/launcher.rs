@@ -1,58 +1,81 @@
-use crate::config::get_config_manager;
+use crate::config::{read_config, ConfigManager};
 use crate::core::accounts_manager::AccountsManager;
 use crate::core::minecraft::{
-    arguments::ArgumentProcessor,
+    arguments::{ArgumentProcessor, ArgumentSanitizer},
     classpath::ClasspathBuilder,
+    environment::LaunchEnvironment,
+    heap_limits,
+    launch_options::LaunchOptions,
+    legacy,
     manifest::{ManifestMerger, ManifestParser},
+    path_encoding,
     paths::MinecraftPaths,
+    resolution::GameResolution,
+    sandbox::InstanceSandbox,
 };
+use crate::core::session_logs::SessionLog;
+use crate::core::subprocess;
+use crate::core::system_resources::effective_memory_mb;
 use crate::core::{minecraft_account::MinecraftAccount, minecraft_instance::MinecraftInstance};
 use crate::interfaces::game_launcher::GameLauncher;
-use std::process::{Child, Command, Stdio};
+use std::path::PathBuf;
+use std::process::{Child, Stdio};
+use std::sync::Arc;
 use uuid::Uuid;
 
 pub struct MinecraftLauncher {
     instance: MinecraftInstance,
+    options: LaunchOptions,
+    session_log: Option<Arc<SessionLog>>,
 }
 
 impl MinecraftLauncher {
     pub fn new(instance: MinecraftInstance) -> Self {
-        Self { instance }
+        Self {
+            instance,
+            options: LaunchOptions::default(),
+            session_log: None,
+        }
     }
-}
 
-impl GameLauncher for MinecraftLauncher {
-    fn launch(&self) -> Option<Child> {
-        let config_manager = match get_config_manager().lock() {
-            Ok(manager) => manager,
-            Err(_) => return None,
-        };
+    pub fn with_options(mut self, options: LaunchOptions) -> Self {
+        self.options = options;
+        self
+    }
 
-        let config = match config_manager.as_ref() {
-            Ok(cfg) => cfg,
-            Err(_) => return None,
-        };
+    /// Has the game write its stdout/stderr straight to the session log
+    /// files instead of pipes, so the output outlives the launcher.
+    pub fn with_session_log(mut self, session_log: Option<Arc<SessionLog>>) -> Self {
+        self.session_log = session_log;
+        self
+    }
 
-        log::info!("[MinecraftLauncher] Config loaded");
+    /// Everything `launch` does before spawning the process: resolves the
+    /// account, memory and paths, prepares natives and the sandbox and
+    /// builds the full command line.
+    pub fn prepare(&self, config: &ConfigManager) -> Result<PreparedLaunch, String> {
         log::info!(
             "[MinecraftLauncher] Starting {} Minecraft instance",
             self.instance.instanceName
         );
 
-        let mc_memory = match config.get_minecraft_memory() {
-            Some(mem) => mem,
-            None => {
-                log::warn!("No Minecraft memory config found, using default 2048MB");
-                2048
-            }
-        };
+        let mc_memory = effective_memory_mb(&self.instance, config.get_minecraft_memory());
 
         log::info!("Minecraft memory: {}MB", mc_memory);
 
         // Get account
         let accounts_manager = AccountsManager::new();
-        let account_uuid = self.instance.accountUuid.as_ref()?;
-        let account = accounts_manager.get_minecraft_account_by_uuid(account_uuid)?;
+        let account = match self.options.offline_account.clone().or_else(|| {
+            self.instance
+                .accountUuid
+                .as_ref()
+                .and_then(|uuid| accounts_manager.get_minecraft_account_by_uuid(uuid))
+        }) {
+            Some(account) => account,
+            // La demo no necesita una cuenta con licencia
+            None if self.options.demo => LaunchOptions::demo_account(),
+            None => return Err("La instancia no tiene una cuenta válida".to_string()),
+        };
 
         log::info!(
             "[MinecraftLauncher] Launching Minecraft using account: {}",
@@ -60,40 +83,170 @@ impl GameLauncher for MinecraftLauncher {
         );
 
         // Setup paths
-        let paths = MinecraftPaths::new(&self.instance, config)?;
+        let paths = MinecraftPaths::new(&self.instance, config)
+            .ok_or_else(|| "No se pudieron resolver las rutas de la instancia".to_string())?;
 
         log::info!("[MinecraftLauncher] Minecraft paths: {:?}", paths);
         log::info!("[MinecraftLauncher] Java path: {:?}", paths.java_path());
+        let mc_memory =
+            heap_limits::guard_heap(&self.instance.instanceId, mc_memory, paths.java_path());
+        path_encoding::warn_non_ascii_paths(&paths);
+
+        // Copia privada de los nativos para esta instancia y versión
+        let natives_dir = paths
+            .prepare_natives()
+            .map_err(|e| format!("No se pudieron preparar los nativos: {}", e))?;
+        log::info!("[MinecraftLauncher] Natives dir: {}", natives_dir.display());
+
         // Load and merge manifests if needed
         let manifest_parser = ManifestParser::new(&paths);
-        let manifest_json = manifest_parser.load_merged_manifest()?;
+        let manifest_json = manifest_parser
+            .load_merged_manifest()
+            .ok_or_else(|| "No se pudo cargar el manifiesto de la versión".to_string())?;
 
         log::info!("[MinecraftLauncher] Manifest loaded");
         log::info!("[MinecraftLauncher] Manifest JSON: {:?}", manifest_json);
 
+        // Índices antiguos (legacy / pre-1.6) necesitan los assets con su nombre real
+        if let Some(asset_index) = manifest_json
+            .get("assets")
+            .and_then(|v| v.as_str())
+            .or_else(|| manifest_json.get("assetIndex")?.get("id")?.as_str())
+        {
+            if let Err(e) = legacy::materialize_legacy_assets(
+                &paths.assets_dir(),
+                paths.game_dir(),
+                asset_index,
+            ) {
+                log::warn!(
+                    "[MinecraftLauncher] Failed to materialize legacy assets: {}",
+                    e
+                );
+            }
+        }
+
         // Build classpath
         let classpath_builder = ClasspathBuilder::new(&manifest_json, &paths);
-        let classpath_str = classpath_builder.build()?;
+        let classpath_str = classpath_builder
+            .build()
+            .ok_or_else(|| "No se pudo construir el classpath".to_string())?;
 
         log::info!("[MinecraftLauncher] Classpath: {}", classpath_str);
 
         // Process arguments
+        let mut resolution = GameResolution::new(&self.instance, config);
+        if let Err(e) = resolution.validate() {
+            // Un tamaño inválido se ignora y el juego abre con su ventana por defecto
+            log::warn!("[MinecraftLauncher] Ignoring invalid resolution: {}", e);
+            resolution = GameResolution {
+                fullscreen: resolution.fullscreen,
+                ..GameResolution::default()
+            };
+        }
         let argument_processor =
-            ArgumentProcessor::new(&manifest_json, &account, &paths, mc_memory);
-        let (jvm_args, game_args) = argument_processor.process_arguments()?;
+            ArgumentProcessor::new(&manifest_json, &account, &paths, mc_memory)
+                .with_resolution(resolution)
+                .with_demo(self.options.demo);
+        let (manifest_jvm_args, game_args) = argument_processor
+            .process_arguments()
+            .ok_or_else(|| "No se pudieron procesar los argumentos de lanzamiento".to_string())?;
+
+        // UTF-8 primero: un valor explícito del manifiesto lo reemplaza
+        let mut jvm_args = path_encoding::encoding_jvm_args();
+        jvm_args.extend(manifest_jvm_args);
+
+        // Aislamiento opcional de home/appdata/temp de la instancia
+        let sandbox = InstanceSandbox::new(&self.instance);
+        sandbox.prepare()?;
+        jvm_args.extend(sandbox.jvm_args());
+        if config.get_relocate_non_ascii_paths() {
+            jvm_args.extend(path_encoding::relocated_temp_arg(&sandbox.java_temp_dir()));
+        }
+
+        // Opciones repetidas por la fusión de manifiestos; memoria y nativos del launcher
+        let jvm_args = ArgumentSanitizer::jvm(jvm_args, mc_memory, &natives_dir);
+        let game_args = ArgumentSanitizer::game(game_args);
 
         // Get main class
-        let main_class = manifest_json.get("mainClass")?.as_str()?;
+        let main_class = manifest_json
+            .get("mainClass")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| "El manifiesto no define mainClass".to_string())?
+            .to_string();
+
+        // Environment variables and wrapper command (gamemoderun, mangohud...)
+        let environment = LaunchEnvironment::new(&self.instance, &paths, config)
+            .map_err(|e| format!("Entorno de lanzamiento no válido: {}", e))?;
+
+        if !environment.wrapper().is_empty() {
+            log::info!(
+                "[MinecraftLauncher] Using wrapper command: {:?}",
+                environment.wrapper()
+            );
+        }
+
+        Ok(PreparedLaunch {
+            java_path: paths.java_path().to_path_buf(),
+            game_dir: paths.game_dir().to_path_buf(),
+            jvm_args,
+            main_class,
+            game_args,
+            environment,
+            account,
+            memory: mc_memory,
+        })
+    }
+}
+
+/// Command line of an instance, ready to spawn.
+pub struct PreparedLaunch {
+    pub java_path: PathBuf,
+    pub game_dir: PathBuf,
+    /// Incluye las propiedades del sandbox
+    pub jvm_args: Vec<String>,
+    pub main_class: String,
+    pub game_args: Vec<String>,
+    pub environment: LaunchEnvironment,
+    pub account: MinecraftAccount,
+    pub memory: u32,
+}
+
+impl GameLauncher for MinecraftLauncher {
+    fn launch(&self) -> Option<Child> {
+        let config_manager = read_config();
+
+        let config = match config_manager.as_ref() {
+            Ok(cfg) => cfg,
+            Err(_) => return None,
+        };
+
+        log::info!("[MinecraftLauncher] Config loaded");
+
+        let prepared = match self.prepare(config) {
+            Ok(prepared) => prepared,
+            Err(e) => {
+                log::error!("[MinecraftLauncher] {}", e);
+                return None;
+            }
+        };
+
+        // Sin log de sesión la salida va por pipes y la vacía el monitor
+        let (stdout, stderr) = self
+            .session_log
+            .as_ref()
+            .and_then(|session_log| session_log.child_stdio())
+            .unwrap_or_else(|| (Stdio::piped(), Stdio::piped()));
 
         // Build and execute command
-        let mut command = Command::new(paths.java_path());
+        let mut command = prepared.environment.command(&prepared.java_path);
         command
-            .args(&jvm_args)
-            .arg(main_class)
-            .args(&game_args)
-            .current_dir(paths.game_dir())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped());
+            .args(&prepared.jvm_args)
+            .arg(&prepared.main_class)
+            .args(&prepared.game_args)
+            .current_dir(&prepared.game_dir)
+            .stdout(stdout)
+            .stderr(stderr);
+        subprocess::game_process(&mut command, config.get_high_priority_game());
 
         log::info!("Launching Minecraft with command: {:?}", command);
 
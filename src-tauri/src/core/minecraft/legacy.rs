@@ -0,0 +1,355 @@
+//! Compatibility helpers for legacy versions (pre-1.13 / LaunchWrapper based
+//! Forge such as 1.7.10 and 1.12.2).
+
+use crate::core::minecraft::paths::MinecraftPaths;
+use serde_json::Value;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// `minecraftArguments` flags that may legitimately appear several times.
+pub const REPEATABLE_LEGACY_ARGS: &[&str] = &["--tweakClass"];
+
+/// Merges two `minecraftArguments` strings keeping the parent's order.
+///
+/// Regular flags from `child` override the parent's value, while repeatable
+/// flags such as `--tweakClass` are appended once per distinct value (FML,
+/// OptiFine and LiteLoader each add their own tweaker).
+pub fn merge_minecraft_arguments(parent: Option<&str>, child: Option<&str>) -> Option<String> {
+    let mut pairs: Vec<(String, String)> = Vec::new();
+
+    for source in [parent, child].into_iter().flatten() {
+        for (key, value) in parse_minecraft_arguments(source) {
+            let repeatable = REPEATABLE_LEGACY_ARGS.contains(&key.as_str());
+            match pairs
+                .iter_mut()
+                .find(|(k, v)| k == &key && (!repeatable || v == &value))
+            {
+                Some(existing) if !repeatable => existing.1 = value,
+                Some(_) => {}
+                None => pairs.push((key, value)),
+            }
+        }
+    }
+
+    if pairs.is_empty() {
+        return None;
+    }
+
+    Some(
+        pairs
+            .into_iter()
+            .map(|(k, v)| {
+                if v.is_empty() {
+                    k
+                } else {
+                    format!("{} {}", k, v)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" "),
+    )
+}
+
+/// Splits `--flag value --flag2 value2` into pairs; flags without a value
+/// (e.g. `--demo`) get an empty one.
+fn parse_minecraft_arguments(arguments: &str) -> Vec<(String, String)> {
+    let tokens: Vec<&str> = arguments.split_whitespace().collect();
+    let mut pairs = Vec::new();
+    let mut i = 0;
+
+    while i < tokens.len() {
+        let key = tokens[i];
+        match tokens.get(i + 1) {
+            Some(value) if !value.starts_with("--") => {
+                pairs.push((key.to_string(), value.to_string()));
+                i += 2;
+            }
+            _ => {
+                pairs.push((key.to_string(), String::new()));
+                i += 1;
+            }
+        }
+    }
+
+    pairs
+}
+
+/// Directory passed as `${game_assets}` for the given asset index.
+///
+/// `pre-1.6` reads loose files from `<game_dir>/resources`, `legacy` from
+/// `assets/virtual/legacy`; newer indexes use the hashed layout directly.
+pub fn game_assets_dir(paths: &MinecraftPaths, asset_index: &str) -> PathBuf {
+    match asset_index {
+        "pre-1.6" => paths.game_dir().join("resources"),
+        "legacy" => paths.assets_dir().join("virtual").join("legacy"),
+        _ => paths.assets_dir(),
+    }
+}
+
+/// Copies hashed objects to their named location for indexes flagged as
+/// `virtual` or `map_to_resources`. Files whose size already matches the
+/// index are kept. Returns how many files were written.
+pub fn materialize_legacy_assets(
+    assets_dir: &Path,
+    game_dir: &Path,
+    asset_index: &str,
+) -> io::Result<usize> {
+    let index_path = assets_dir
+        .join("indexes")
+        .join(format!("{}.json", asset_index));
+    if !index_path.exists() {
+        return Ok(0);
+    }
+
+    let index: Value = serde_json::from_str(&fs::read_to_string(&index_path)?)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    let map_to_resources = index
+        .get("map_to_resources")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    let is_virtual = index
+        .get("virtual")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+
+    let target_root = if map_to_resources {
+        game_dir.join("resources")
+    } else if is_virtual {
+        assets_dir.join("virtual").join(asset_index)
+    } else {
+        return Ok(0);
+    };
+
+    let objects = match index.get("objects").and_then(Value::as_object) {
+        Some(objects) => objects,
+        None => return Ok(0),
+    };
+
+    let mut written = 0;
+    for (name, object) in objects {
+        let hash = match object.get("hash").and_then(Value::as_str) {
+            Some(hash) if hash.len() > 2 => hash,
+            _ => continue,
+        };
+
+        let source = assets_dir.join("objects").join(&hash[0..2]).join(hash);
+        let target = target_root.join(name);
+        if !source.exists() {
+            continue;
+        }
+
+        let expected_size = object.get("size").and_then(Value::as_u64);
+        if let Ok(metadata) = fs::metadata(&target) {
+            if expected_size.map_or(true, |size| size == metadata.len()) {
+                continue;
+            }
+        }
+
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(&source, &target)?;
+        written += 1;
+    }
+
+    if written > 0 {
+        log::info!(
+            "[Legacy] Materialized {} assets for index {} in {}",
+            written,
+            asset_index,
+            target_root.display()
+        );
+    }
+
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::minecraft::manifest::ManifestMerger;
+    use serde_json::json;
+
+    const VANILLA_1_7_10: &str = "--username ${auth_player_name} --version ${version_name} \
+        --gameDir ${game_directory} --assetsDir ${assets_root} \
+        --assetIndex ${assets_index_name} --uuid ${auth_uuid} \
+        --accessToken ${auth_access_token} --userProperties ${user_properties} \
+        --userType ${user_type}";
+    const VANILLA_1_12_2: &str = "--username ${auth_player_name} --version ${version_name} \
+        --gameDir ${game_directory} --assetsDir ${assets_root} \
+        --assetIndex ${assets_index_name} --uuid ${auth_uuid} \
+        --accessToken ${auth_access_token} --userType ${user_type} \
+        --versionType ${version_type}";
+    const FML_1_7_10: &str = "cpw.mods.fml.common.launcher.FMLTweaker";
+    const FML_1_12_2: &str = "net.minecraftforge.fml.common.launcher.FMLTweaker";
+    const OPTIFINE: &str = "optifine.OptiFineForgeTweaker";
+
+    fn values_of<'a>(arguments: &'a str, flag: &str) -> Vec<&'a str> {
+        let tokens: Vec<&str> = arguments.split_whitespace().collect();
+        tokens
+            .windows(2)
+            .filter(|pair| pair[0] == flag)
+            .map(|pair| pair[1])
+            .collect()
+    }
+
+    #[test]
+    fn forge_1_7_10_appends_its_tweaker() {
+        let forge = format!("{} --tweakClass {}", VANILLA_1_7_10, FML_1_7_10);
+        let merged = merge_minecraft_arguments(Some(VANILLA_1_7_10), Some(&forge)).unwrap();
+
+        assert_eq!(merged, forge);
+    }
+
+    #[test]
+    fn child_value_replaces_parent_in_place() {
+        let forge = format!(
+            "{} --tweakClass {}",
+            VANILLA_1_12_2.replace("${version_type}", "Forge"),
+            FML_1_12_2
+        );
+        let merged = merge_minecraft_arguments(Some(VANILLA_1_12_2), Some(&forge)).unwrap();
+
+        assert_eq!(values_of(&merged, "--versionType"), vec!["Forge"]);
+        assert!(merged.starts_with("--username ${auth_player_name} --version"));
+        assert!(merged.ends_with(&format!("--tweakClass {}", FML_1_12_2)));
+    }
+
+    #[test]
+    fn distinct_tweakers_are_kept_once_each() {
+        let parent = format!("{} --tweakClass {}", VANILLA_1_12_2, FML_1_12_2);
+        let child = format!("--tweakClass {} --tweakClass {}", FML_1_12_2, OPTIFINE);
+        let merged = merge_minecraft_arguments(Some(&parent), Some(&child)).unwrap();
+
+        assert_eq!(
+            values_of(&merged, "--tweakClass"),
+            vec![FML_1_12_2, OPTIFINE]
+        );
+    }
+
+    #[test]
+    fn flags_without_value_are_kept() {
+        let merged =
+            merge_minecraft_arguments(Some("--demo --username ${auth_player_name}"), None).unwrap();
+
+        assert_eq!(merged, "--demo --username ${auth_player_name}");
+    }
+
+    #[test]
+    fn no_arguments_merge_to_none() {
+        assert_eq!(merge_minecraft_arguments(None, None), None);
+        assert_eq!(merge_minecraft_arguments(Some("  "), None), None);
+    }
+
+    #[test]
+    fn merged_1_12_2_manifest_keeps_legacy_arguments() {
+        let vanilla = json!({
+            "id": "1.12.2",
+            "mainClass": "net.minecraft.client.main.Main",
+            "minecraftArguments": VANILLA_1_12_2,
+            "assets": "1.12",
+            "libraries": [],
+        });
+        let forge = json!({
+            "id": "1.12.2-forge-14.23.5.2859",
+            "inheritsFrom": "1.12.2",
+            "mainClass": "net.minecraft.launchwrapper.Launch",
+            "minecraftArguments": format!("{} --tweakClass {}", VANILLA_1_12_2, FML_1_12_2),
+            "libraries": [],
+        });
+
+        let merged = ManifestMerger::merge(vanilla, forge);
+        let arguments = merged["minecraftArguments"].as_str().unwrap();
+
+        assert_eq!(merged["mainClass"], "net.minecraft.launchwrapper.Launch");
+        assert_eq!(values_of(arguments, "--tweakClass"), vec![FML_1_12_2]);
+        assert_eq!(
+            values_of(arguments, "--assetIndex"),
+            vec!["${assets_index_name}"]
+        );
+        assert!(merged.get("arguments").is_none());
+    }
+
+    /// Directorio temporal con un índice de assets y su único objeto
+    fn assets_fixture(index: &str, flags: Value) -> (PathBuf, PathBuf) {
+        let root = std::env::temp_dir().join(format!("legacy-assets-{}", uuid::Uuid::new_v4()));
+        let assets_dir = root.join("assets");
+        let hash = "a5c9fdf8f4dc0e07c9e3b8a9c7e6b6fd3c1f1e20";
+
+        fs::create_dir_all(assets_dir.join("indexes")).unwrap();
+        fs::create_dir_all(assets_dir.join("objects").join(&hash[..2])).unwrap();
+        fs::write(
+            assets_dir.join("objects").join(&hash[..2]).join(hash),
+            b"click",
+        )
+        .unwrap();
+
+        let mut index_json = json!({
+            "objects": { "sound/random/click.ogg": { "hash": hash, "size": 5 } },
+        });
+        for (key, value) in flags.as_object().unwrap() {
+            index_json[key] = value.clone();
+        }
+        fs::write(
+            assets_dir.join("indexes").join(format!("{}.json", index)),
+            index_json.to_string(),
+        )
+        .unwrap();
+
+        (root, assets_dir)
+    }
+
+    #[test]
+    fn virtual_index_is_materialized_once() {
+        let (root, assets_dir) = assets_fixture("legacy", json!({ "virtual": true }));
+        let game_dir = root.join("minecraft");
+        let target = assets_dir.join("virtual/legacy/sound/random/click.ogg");
+
+        assert_eq!(
+            materialize_legacy_assets(&assets_dir, &game_dir, "legacy").unwrap(),
+            1
+        );
+        assert_eq!(fs::read(&target).unwrap(), b"click");
+        // Ya tiene el tamaño del índice: no se vuelve a copiar
+        assert_eq!(
+            materialize_legacy_assets(&assets_dir, &game_dir, "legacy").unwrap(),
+            0
+        );
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn pre_1_6_index_is_mapped_to_resources() {
+        let (root, assets_dir) = assets_fixture("pre-1.6", json!({ "map_to_resources": true }));
+        let game_dir = root.join("minecraft");
+
+        assert_eq!(
+            materialize_legacy_assets(&assets_dir, &game_dir, "pre-1.6").unwrap(),
+            1
+        );
+        assert!(game_dir.join("resources/sound/random/click.ogg").is_file());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn modern_or_missing_indexes_are_left_alone() {
+        let (root, assets_dir) = assets_fixture("1.12", json!({}));
+        let game_dir = root.join("minecraft");
+
+        assert_eq!(
+            materialize_legacy_assets(&assets_dir, &game_dir, "1.12").unwrap(),
+            0
+        );
+        assert_eq!(
+            materialize_legacy_assets(&assets_dir, &game_dir, "missing").unwrap(),
+            0
+        );
+        assert!(!assets_dir.join("virtual").exists());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
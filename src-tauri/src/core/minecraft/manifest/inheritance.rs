@@ -0,0 +1,113 @@
+//! `inheritsFrom` chains of version JSONs.
+
+use super::merger::{ManifestMerger, MergeReport};
+use crate::core::minecraft::paths;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+/// Más niveles que esto es casi seguro un perfil roto
+const MAX_DEPTH: usize = 8;
+
+/// Version JSONs of an inheritance chain, root first and the version being
+/// launched last.
+pub struct InheritanceChain {
+    levels: Vec<(String, Value)>,
+}
+
+impl InheritanceChain {
+    /// Follows `inheritsFrom` from `manifest` (the JSON of version `id`)
+    /// through `versions/<parent>/<parent>.json`. Fails on missing or invalid
+    /// parents, cycles and chains deeper than `MAX_DEPTH`.
+    pub fn resolve(game_dir: &Path, id: &str, manifest: Value) -> Result<Self, String> {
+        let mut levels: Vec<(String, Value)> = Vec::new();
+        let mut seen = HashSet::new();
+        let mut id = id.to_string();
+        let mut current = manifest;
+
+        loop {
+            seen.insert(id.clone());
+            let parent = current
+                .get("inheritsFrom")
+                .and_then(Value::as_str)
+                .map(String::from);
+            levels.push((id, current));
+
+            let Some(parent) = parent else {
+                break;
+            };
+            if seen.contains(&parent) {
+                let mut cycle: Vec<&str> = levels.iter().map(|(id, _)| id.as_str()).collect();
+                cycle.push(&parent);
+                return Err(format!(
+                    "Herencia circular entre versiones: {}",
+                    cycle.join(" -> ")
+                ));
+            }
+            if levels.len() >= MAX_DEPTH {
+                return Err(format!(
+                    "La versión {} supera los {} niveles de herencia",
+                    levels[0].0, MAX_DEPTH
+                ));
+            }
+
+            let parent_file = paths::version_file(game_dir, &parent, "json");
+            let data = fs::read_to_string(&parent_file).map_err(|e| {
+                format!(
+                    "No se pudo leer el manifiesto de {} ({}): {}",
+                    parent,
+                    parent_file.display(),
+                    e
+                )
+            })?;
+            current = serde_json::from_str(&data)
+                .map_err(|e| format!("Manifiesto de {} inválido: {}", parent, e))?;
+            id = parent;
+        }
+
+        levels.reverse();
+        log::info!(
+            "Resolved version inheritance: {}",
+            levels
+                .iter()
+                .map(|(id, _)| id.as_str())
+                .collect::<Vec<_>>()
+                .join(" -> ")
+        );
+        Ok(Self { levels })
+    }
+
+    /// Version ids, root first.
+    pub fn ids(&self) -> Vec<String> {
+        self.levels.iter().map(|(id, _)| id.clone()).collect()
+    }
+
+    /// Merges every level on top of its parents with `merge(parent, child)`.
+    pub fn merge_with(self, mut merge: impl FnMut(Value, Value) -> Value) -> Value {
+        let mut levels = self.levels.into_iter().map(|(_, manifest)| manifest);
+        let root = levels.next().unwrap_or_default();
+        levels.fold(root, |parent, child| merge(parent, child))
+    }
+
+    /// Merges the chain with `ManifestMerger`, adding up the reports of every
+    /// level.
+    pub fn merge_with_report(self) -> (Value, MergeReport) {
+        let mut report = MergeReport {
+            inheritance: self.ids(),
+            ..Default::default()
+        };
+        let mut first = true;
+        let merged = self.merge_with(|parent, child| {
+            let (merged, level) = ManifestMerger::merge_with_report(parent, child);
+            if first {
+                report.parent_libraries = level.parent_libraries;
+                first = false;
+            }
+            report.child_libraries += level.child_libraries;
+            report.conflicts.extend(level.conflicts);
+            merged
+        });
+        (merged, report)
+    }
+}
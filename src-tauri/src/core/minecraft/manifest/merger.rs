@@ -1,11 +1,61 @@
+use crate::core::minecraft::{legacy, maven_version, MavenCoordinate};
+use serde::Serialize;
 use serde_json::{Map, Value};
+use std::cmp::Ordering;
 use std::collections::{BTreeMap, HashMap};
 
+/// How a library present in both manifests is resolved.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictPolicy {
+    /// Keep the highest maven version (child wins on ties).
+    Highest,
+    PreferChild,
+    PreferParent,
+}
+
+/// Known exceptions to the "highest version wins" rule (group:artifact prefix).
+const LIBRARY_OVERRIDES: &[(&str, ConflictPolicy)] = &[
+    // Forge y LaunchWrapper dependen de sus propias builds
+    ("net.minecraftforge:", ConflictPolicy::PreferChild),
+    ("cpw.mods:", ConflictPolicy::PreferChild),
+    ("net.minecraft:launchwrapper", ConflictPolicy::PreferChild),
+    // authlib y LWJGL deben coincidir con el cliente vanilla
+    ("com.mojang:authlib", ConflictPolicy::PreferParent),
+    ("org.lwjgl", ConflictPolicy::PreferParent),
+];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryConflict {
+    pub library: String,
+    pub parent_version: String,
+    pub child_version: String,
+    pub chosen_version: String,
+    pub policy: ConflictPolicy,
+}
+
+/// Summary of how the child manifest's libraries were merged.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeReport {
+    /// Versiones de la cadena `inheritsFrom`, de la raíz a la instancia
+    pub inheritance: Vec<String>,
+    pub parent_libraries: usize,
+    pub child_libraries: usize,
+    pub conflicts: Vec<LibraryConflict>,
+}
+
 pub struct ManifestMerger;
 
 impl ManifestMerger {
     pub fn merge(vanilla: Value, forge: Value) -> Value {
+        Self::merge_with_report(vanilla, forge).0
+    }
+
+    pub fn merge_with_report(vanilla: Value, forge: Value) -> (Value, MergeReport) {
         let mut result = vanilla.clone();
+        let mut report = MergeReport::default();
 
         // Merge main class
         if let Some(mc) = forge.get("mainClass") {
@@ -13,7 +63,7 @@ impl ManifestMerger {
         }
 
         // Merge libraries
-        Self::merge_libraries(&mut result, &vanilla, &forge);
+        Self::merge_libraries(&mut result, &vanilla, &forge, &mut report);
 
         // Merge arguments
         Self::merge_arguments(&mut result, &vanilla, &forge);
@@ -21,105 +71,125 @@ impl ManifestMerger {
         // Merge legacy arguments
         Self::merge_legacy_arguments(&mut result, &vanilla, &forge);
 
-        result
+        (result, report)
     }
 
-    fn merge_libraries(result: &mut Value, vanilla: &Value, forge: &Value) {
+    fn merge_libraries(
+        result: &mut Value,
+        vanilla: &Value,
+        forge: &Value,
+        report: &mut MergeReport,
+    ) {
         let mut libs: BTreeMap<String, Value> = BTreeMap::new();
-        let mut duplicates: HashMap<String, Vec<String>> = HashMap::new();
+        // group:artifact[:classifier] -> claves completas de las versiones vanilla
+        let mut vanilla_keys: HashMap<String, Vec<String>> = HashMap::new();
 
         // Primero agregamos todas las bibliotecas vanilla directamente
         if let Some(arr) = vanilla.get("libraries").and_then(Value::as_array) {
+            report.parent_libraries = arr.len();
             for lib in arr {
-                if let Some((name, ga, vver, _, classifier)) = Self::extract_lib_info(lib) {
-                    // Construimos una clave que incluya el clasificador y la versión para garantizar unicidad
+                if let Some((_, ga, vver, _, classifier)) = Self::extract_lib_info(lib) {
+                    // Clave con clasificador y versión: vanilla puede declarar la
+                    // misma librería con versiones distintas según el SO
                     let key = Self::build_complete_lib_key(&ga, &vver, &classifier);
-
-                    // No consideramos duplicados dentro de vanilla, simplemente las agregamos
+                    vanilla_keys
+                        .entry(Self::build_lib_key(&ga, &classifier))
+                        .or_default()
+                        .push(key.clone());
                     libs.insert(key, lib.clone());
-
-                    // Guardamos esta versión para posible referencia de debug
-                    let version_str = format!("vanilla:{}", vver.unwrap_or_default());
-                    duplicates.entry(ga).or_default().push(version_str);
                 }
             }
         }
 
-        // Luego procesamos las bibliotecas de forge con reglas especiales para manejar duplicados
+        // Luego las de forge, resolviendo conflictos por versión
         if let Some(arr) = forge.get("libraries").and_then(Value::as_array) {
+            report.child_libraries = arr.len();
             for lib in arr {
-                if let Some((name, ga, fver, furl, classifier)) = Self::extract_lib_info(lib) {
-                    // Construimos la misma clave que usaríamos para vanilla
-                    let std_key = Self::build_lib_key(&ga, &classifier);
-                    // También construimos una clave única para esta versión específica
-                    let forge_key = Self::build_complete_lib_key(&ga, &fver, &classifier);
-
-                    // Verificamos si hay alguna versión de esta biblioteca en vanilla
-                    let vanilla_versions: Vec<(&String, &Value)> = libs
-                        .iter()
-                        .filter(|(k, _)| k.starts_with(&std_key))
-                        .collect();
-
-                    let should_add_forge = if !vanilla_versions.is_empty() {
-                        // Si hay versiones de vanilla, decidimos si preferimos la de forge
-                        let mut prefer_forge = true;
-
-                        for (_, vanilla_lib) in &vanilla_versions {
-                            let (_, _, vver, vurl, _) =
-                                Self::extract_lib_info(vanilla_lib).unwrap();
-
-                            // Si las URLs son diferentes, mantenemos ambas versiones
-                            if furl != vurl {
-                                continue;
-                            }
-
-                            // Para log4j específicamente, preferimos la versión más alta
-                            if ga.contains("log4j") {
-                                if let (Some(v), Some(f)) = (&vver, &fver) {
-                                    let cmp_v: Vec<i32> =
-                                        v.split('.').filter_map(|p| p.parse().ok()).collect();
-                                    let cmp_f: Vec<i32> =
-                                        f.split('.').filter_map(|p| p.parse().ok()).collect();
-                                    prefer_forge = cmp_f > cmp_v;
-                                }
-                            }
-
-                            // Si decidimos no preferir forge, no necesitamos revisar más versiones de vanilla
-                            if !prefer_forge {
-                                break;
-                            }
-                        }
+                let (_, ga, fver, _, classifier) = match Self::extract_lib_info(lib) {
+                    Some(info) => info,
+                    None => continue,
+                };
+                let std_key = Self::build_lib_key(&ga, &classifier);
+                let forge_key = Self::build_complete_lib_key(&ga, &fver, &classifier);
+                let child_version = fver.unwrap_or_default();
+
+                let existing = vanilla_keys.remove(&std_key).unwrap_or_default();
+                if existing.is_empty() {
+                    libs.insert(forge_key, lib.clone());
+                    continue;
+                }
 
-                        prefer_forge
-                    } else {
-                        // Si no hay versiones en vanilla, siempre agregamos la de forge
-                        true
+                let policy = Self::conflict_policy(&ga);
+                let mut keep_child = true;
+
+                for key in &existing {
+                    let parent_version = libs
+                        .get(key)
+                        .and_then(Self::extract_lib_info)
+                        .and_then(|(_, _, v, _, _)| v)
+                        .unwrap_or_default();
+
+                    let child_wins = match policy {
+                        ConflictPolicy::PreferChild => true,
+                        ConflictPolicy::PreferParent => false,
+                        ConflictPolicy::Highest => {
+                            maven_version::compare(&child_version, &parent_version)
+                                != Ordering::Less
+                        }
                     };
 
-                    if should_add_forge {
-                        // Registramos esta versión para depuración
-                        let forge_version_str =
-                            format!("forge:{}", fver.clone().unwrap_or_default());
-                        duplicates
-                            .entry(ga.clone())
-                            .or_default()
-                            .push(forge_version_str);
-
-                        // Agregamos la biblioteca de forge
-                        libs.insert(forge_key, lib.clone());
+                    if parent_version != child_version {
+                        log::info!(
+                            "Library conflict {}: vanilla {} / forge {} -> {} ({:?})",
+                            std_key,
+                            parent_version,
+                            child_version,
+                            if child_wins {
+                                &child_version
+                            } else {
+                                &parent_version
+                            },
+                            policy
+                        );
+                        report.conflicts.push(LibraryConflict {
+                            library: std_key.clone(),
+                            chosen_version: if child_wins {
+                                child_version.clone()
+                            } else {
+                                parent_version.clone()
+                            },
+                            parent_version,
+                            child_version: child_version.clone(),
+                            policy,
+                        });
                     }
+
+                    keep_child &= child_wins;
                 }
-            }
-        }
 
-        // Registramos duplicados solo para depuración (bibliotecas con múltiples versiones)
-        for (ga, sources) in duplicates.iter().filter(|(_, s)| s.len() > 1) {
-            log::info!("Multiple versions of {}: {}", ga, sources.join(", "));
+                if keep_child {
+                    for key in &existing {
+                        libs.remove(key);
+                    }
+                    libs.insert(forge_key, lib.clone());
+                } else {
+                    // Conservamos la versión vanilla para próximos duplicados
+                    vanilla_keys.insert(std_key, existing);
+                }
+            }
         }
 
         result["libraries"] = Value::Array(libs.into_values().collect());
     }
 
+    fn conflict_policy(ga: &str) -> ConflictPolicy {
+        LIBRARY_OVERRIDES
+            .iter()
+            .find(|(prefix, _)| ga.starts_with(prefix))
+            .map(|(_, policy)| *policy)
+            .unwrap_or(ConflictPolicy::Highest)
+    }
+
     fn merge_arguments(result: &mut Value, vanilla: &Value, forge: &Value) {
         let mut args_map = Map::default();
 
@@ -153,27 +223,11 @@ impl ManifestMerger {
     }
 
     fn merge_legacy_arguments(result: &mut Value, vanilla: &Value, forge: &Value) {
-        let mut kv = HashMap::new();
-
-        for src in [
-            vanilla.get("minecraftArguments"),
-            forge.get("minecraftArguments"),
-        ] {
-            if let Some(Value::String(s)) = src {
-                for pair in s.split_whitespace().collect::<Vec<_>>().chunks(2) {
-                    if let [k, v] = pair {
-                        kv.insert(k.to_string(), v.to_string());
-                    }
-                }
-            }
-        }
-
-        if !kv.is_empty() {
-            let merged_legacy = kv
-                .into_iter()
-                .map(|(k, v)| format!("{} {}", k, v))
-                .collect::<Vec<_>>()
-                .join(" ");
+        // Conserva el orden y los --tweakClass repetidos (FML, OptiFine, LiteLoader...)
+        if let Some(merged_legacy) = legacy::merge_minecraft_arguments(
+            vanilla.get("minecraftArguments").and_then(Value::as_str),
+            forge.get("minecraftArguments").and_then(Value::as_str),
+        ) {
             result["minecraftArguments"] = Value::String(merged_legacy);
         }
     }
@@ -188,20 +242,22 @@ impl ManifestMerger {
         Option<String>,
     )> {
         let name = lib.get("name")?.as_str()?.to_string();
-        let parts: Vec<&str> = name.split(':').collect();
-        let ga = if parts.len() >= 2 {
-            format!("{}:{}", parts[0], parts[1])
-        } else {
-            name.clone()
-        };
-        let version = parts.get(2).map(|s| s.to_string());
+        let coordinate = MavenCoordinate::parse(&name);
+        let ga = coordinate
+            .as_ref()
+            .map(|coordinate| format!("{}:{}", coordinate.group, coordinate.artifact))
+            .unwrap_or_else(|| name.clone());
+        let version = coordinate
+            .as_ref()
+            .map(|coordinate| coordinate.version.clone());
         let classifier = lib
             .get("downloads")
             .and_then(|d| d.get("artifact"))
             .and_then(|a| a.get("classifier"))
             .or_else(|| lib.get("classifier"))
             .and_then(Value::as_str)
-            .map(String::from);
+            .map(String::from)
+            .or_else(|| coordinate.and_then(|coordinate| coordinate.classifier));
         let url = lib.get("url").and_then(Value::as_str).map(String::from);
         Some((name, ga, version, url, classifier))
     }
@@ -228,15 +284,195 @@ impl ManifestMerger {
             format!("{}:{}", ga, ver_part)
         }
     }
+}
 
-    fn prefer_forge(ga: &str, vver: &Option<String>, fver: &Option<String>) -> bool {
-        if ga.contains("log4j") {
-            if let (Some(v), Some(f)) = (vver, fver) {
-                let cmp_v: Vec<i32> = v.split('.').filter_map(|p| p.parse().ok()).collect();
-                let cmp_f: Vec<i32> = f.split('.').filter_map(|p| p.parse().ok()).collect();
-                return cmp_f > cmp_v;
-            }
-        }
-        true
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn library(name: &str) -> Value {
+        json!({ "name": name })
+    }
+
+    fn vanilla() -> Value {
+        json!({
+            "id": "1.16.5",
+            "mainClass": "net.minecraft.client.main.Main",
+            "arguments": {
+                "game": ["--username", "${auth_player_name}", "--version", "${version_name}"],
+                "jvm": ["-Djava.library.path=${natives_directory}", "-cp", "${classpath}"],
+            },
+            "libraries": [
+                library("org.apache.logging.log4j:log4j-api:2.8.1"),
+                library("com.google.guava:guava:21.0"),
+                library("com.mojang:authlib:2.1.28"),
+                library("org.lwjgl:lwjgl:3.2.2"),
+                library("org.lwjgl:lwjgl:3.2.2:natives-linux"),
+            ],
+        })
+    }
+
+    fn forge() -> Value {
+        json!({
+            "id": "1.16.5-forge-36.2.39",
+            "inheritsFrom": "1.16.5",
+            "mainClass": "cpw.mods.modlauncher.Launcher",
+            "arguments": {
+                "game": ["--launchTarget", "fmlclient", "--fml.forgeVersion", "36.2.39"],
+                "jvm": ["-Dforge.logging.console.level=info"],
+            },
+            "libraries": [
+                library("org.apache.logging.log4j:log4j-api:2.11.2"),
+                library("com.google.guava:guava:20.0"),
+                library("com.mojang:authlib:2.2.30"),
+                library("cpw.mods:modlauncher:8.1.3"),
+                library("net.minecraftforge:forge:1.16.5-36.2.39"),
+            ],
+        })
+    }
+
+    fn library_names(manifest: &Value) -> Vec<&str> {
+        let mut names: Vec<&str> = manifest["libraries"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|lib| lib["name"].as_str())
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
+    fn strings(value: &Value) -> Vec<&str> {
+        value
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(Value::as_str)
+            .collect()
+    }
+
+    #[test]
+    fn libraries_resolve_by_identity_and_policy() {
+        let (merged, report) = ManifestMerger::merge_with_report(vanilla(), forge());
+
+        assert_eq!(
+            library_names(&merged),
+            vec![
+                // PreferParent: authlib debe coincidir con el cliente
+                "com.google.guava:guava:21.0",
+                "com.mojang:authlib:2.1.28",
+                "cpw.mods:modlauncher:8.1.3",
+                "net.minecraftforge:forge:1.16.5-36.2.39",
+                // Highest: la de forge es más nueva
+                "org.apache.logging.log4j:log4j-api:2.11.2",
+                "org.lwjgl:lwjgl:3.2.2",
+                "org.lwjgl:lwjgl:3.2.2:natives-linux",
+            ]
+        );
+        assert_eq!(report.parent_libraries, 5);
+        assert_eq!(report.child_libraries, 5);
+    }
+
+    #[test]
+    fn conflicts_are_reported_with_the_chosen_version() {
+        let (_, report) = ManifestMerger::merge_with_report(vanilla(), forge());
+        let conflict = |library: &str| {
+            report
+                .conflicts
+                .iter()
+                .find(|conflict| conflict.library == library)
+                .unwrap()
+        };
+
+        assert_eq!(report.conflicts.len(), 3);
+
+        let log4j = conflict("org.apache.logging.log4j:log4j-api");
+        assert_eq!(log4j.chosen_version, "2.11.2");
+        assert_eq!(log4j.policy, ConflictPolicy::Highest);
+
+        let guava = conflict("com.google.guava:guava");
+        assert_eq!(guava.chosen_version, "21.0");
+        assert_eq!(guava.policy, ConflictPolicy::Highest);
+
+        let authlib = conflict("com.mojang:authlib");
+        assert_eq!(authlib.chosen_version, "2.1.28");
+        assert_eq!(authlib.policy, ConflictPolicy::PreferParent);
+    }
+
+    #[test]
+    fn forge_builds_override_even_when_older() {
+        let vanilla = json!({ "libraries": [library("net.minecraft:launchwrapper:1.12")] });
+        let forge = json!({ "libraries": [library("net.minecraft:launchwrapper:1.11")] });
+
+        let merged = ManifestMerger::merge(vanilla, forge);
+
+        assert_eq!(
+            library_names(&merged),
+            vec!["net.minecraft:launchwrapper:1.11"]
+        );
+    }
+
+    #[test]
+    fn classifiers_are_separate_libraries() {
+        let forge = json!({
+            "libraries": [library("org.lwjgl:lwjgl:3.2.1:natives-windows")],
+        });
+
+        let merged = ManifestMerger::merge(vanilla(), forge);
+        let names = library_names(&merged);
+
+        assert!(names.contains(&"org.lwjgl:lwjgl:3.2.2"));
+        assert!(names.contains(&"org.lwjgl:lwjgl:3.2.2:natives-linux"));
+        assert!(names.contains(&"org.lwjgl:lwjgl:3.2.1:natives-windows"));
+    }
+
+    #[test]
+    fn forge_arguments_are_appended_after_vanilla() {
+        let merged = ManifestMerger::merge(vanilla(), forge());
+
+        assert_eq!(
+            strings(&merged["arguments"]["game"]),
+            vec![
+                "--username",
+                "${auth_player_name}",
+                "--version",
+                "${version_name}",
+                "--launchTarget",
+                "fmlclient",
+                "--fml.forgeVersion",
+                "36.2.39",
+            ]
+        );
+        assert_eq!(
+            strings(&merged["arguments"]["jvm"]),
+            vec![
+                "-Djava.library.path=${natives_directory}",
+                "-cp",
+                "${classpath}",
+                "-Dforge.logging.console.level=info",
+            ]
+        );
+    }
+
+    #[test]
+    fn vanilla_arguments_survive_a_forge_manifest_without_them() {
+        let mut forge = forge();
+        forge.as_object_mut().unwrap().remove("arguments");
+
+        let merged = ManifestMerger::merge(vanilla(), forge);
+
+        assert_eq!(merged["arguments"], vanilla()["arguments"]);
+    }
+
+    #[test]
+    fn forge_main_class_takes_precedence() {
+        let merged = ManifestMerger::merge(vanilla(), forge());
+        assert_eq!(merged["mainClass"], "cpw.mods.modlauncher.Launcher");
+
+        let mut forge = forge();
+        forge.as_object_mut().unwrap().remove("mainClass");
+        let merged = ManifestMerger::merge(vanilla(), forge);
+        assert_eq!(merged["mainClass"], "net.minecraft.client.main.Main");
     }
 }
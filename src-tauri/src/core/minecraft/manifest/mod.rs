@@ -1,5 +1,18 @@
+pub mod inheritance;
 pub mod merger;
 pub mod parser;
 
-pub use merger::ManifestMerger;
+pub use inheritance::InheritanceChain;
+pub use merger::{ManifestMerger, MergeReport};
 pub use parser::ManifestParser;
+
+use serde_json::Value;
+
+/// `type` of a version JSON (`release`, `snapshot`, `old_beta`, `old_alpha`).
+/// Merged manifests keep the vanilla value.
+pub fn version_type(manifest: &Value) -> &str {
+    manifest
+        .get("type")
+        .and_then(Value::as_str)
+        .unwrap_or("release")
+}
@@ -1,4 +1,5 @@
-use super::merger::ManifestMerger;
+use super::inheritance::InheritanceChain;
+use super::merger::MergeReport;
 use crate::core::minecraft::paths::MinecraftPaths;
 use serde_json::Value;
 use std::fs;
@@ -13,23 +14,30 @@ impl<'a> ManifestParser<'a> {
     }
 
     pub fn load_merged_manifest(&self) -> Option<Value> {
+        self.load_merged_manifest_with_report()
+            .map(|(manifest, _)| manifest)
+    }
+
+    /// Same as `load_merged_manifest`, also returning how libraries were merged
+    /// (only the version itself for manifests without `inheritsFrom`).
+    pub fn load_merged_manifest_with_report(&self) -> Option<(Value, MergeReport)> {
         let manifest_file = self.paths.manifest_file();
         log::info!("Loading version manifest from {}", manifest_file.display());
 
         let manifest_data = fs::read_to_string(&manifest_file).ok()?;
         let manifest_json: Value = serde_json::from_str(&manifest_data).ok()?;
 
-        // Check for inheritance
-        if let Some(inherits_from) = manifest_json.get("inheritsFrom").and_then(|v| v.as_str()) {
-            log::info!("Found modded instance inheriting from {}", inherits_from);
-            let vanilla_manifest_file = self.paths.vanilla_manifest_file(inherits_from);
-
-            let vanilla_manifest_data = fs::read_to_string(&vanilla_manifest_file).ok()?;
-            let vanilla_manifest: Value = serde_json::from_str(&vanilla_manifest_data).ok()?;
-
-            return Some(ManifestMerger::merge(vanilla_manifest, manifest_json));
+        // Resolve the whole inheritsFrom chain (OptiFine -> Forge -> vanilla...)
+        let id = manifest_file
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        match InheritanceChain::resolve(self.paths.game_dir(), &id, manifest_json) {
+            Ok(chain) => Some(chain.merge_with_report()),
+            Err(e) => {
+                log::error!("Failed to resolve version inheritance for {}: {}", id, e);
+                None
+            }
         }
-
-        Some(manifest_json)
     }
 }
@@ -0,0 +1,243 @@
+//! Maven coordinates: `group:artifact:version[:classifier][@extension]`.
+
+use std::fmt;
+use std::path::PathBuf;
+
+const DEFAULT_EXTENSION: &str = "jar";
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MavenCoordinate {
+    pub group: String,
+    pub artifact: String,
+    pub version: String,
+    pub classifier: Option<String>,
+    /// `jar` salvo que el nombre traiga `@ext`
+    pub extension: String,
+}
+
+impl MavenCoordinate {
+    /// Parses `group:artifact:version[:classifier][@extension]`. Returns
+    /// `None` for anything else (missing or empty parts, extra parts).
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.trim();
+        let (coord, extension) = match name.rsplit_once('@') {
+            Some((coord, extension)) => (coord, extension),
+            None => (name, DEFAULT_EXTENSION),
+        };
+        if extension.is_empty() || extension.contains(':') {
+            return None;
+        }
+
+        let parts: Vec<&str> = coord.split(':').collect();
+        if !(3..=4).contains(&parts.len()) || parts.iter().any(|part| part.is_empty()) {
+            return None;
+        }
+
+        Some(Self {
+            group: parts[0].to_string(),
+            artifact: parts[1].to_string(),
+            version: parts[2].to_string(),
+            classifier: parts.get(3).map(|classifier| classifier.to_string()),
+            extension: extension.to_string(),
+        })
+    }
+
+    /// `group:artifact[:classifier]`: the same library whatever its version.
+    pub fn identity(&self) -> String {
+        match &self.classifier {
+            Some(classifier) => format!("{}:{}:{}", self.group, self.artifact, classifier),
+            None => format!("{}:{}", self.group, self.artifact),
+        }
+    }
+
+    /// `artifact-version[-classifier].extension`
+    pub fn file_name(&self) -> String {
+        match &self.classifier {
+            Some(classifier) => format!(
+                "{}-{}-{}.{}",
+                self.artifact, self.version, classifier, self.extension
+            ),
+            None => format!("{}-{}.{}", self.artifact, self.version, self.extension),
+        }
+    }
+
+    /// `group/as/dirs/artifact/version`, with `/` separators.
+    pub fn directory(&self) -> String {
+        format!(
+            "{}/{}/{}",
+            self.group.replace('.', "/"),
+            self.artifact,
+            self.version
+        )
+    }
+
+    /// Path inside a maven repository, with `/` separators (for URLs and
+    /// `paths::join_relative`).
+    pub fn url_path(&self) -> String {
+        format!("{}/{}", self.directory(), self.file_name())
+    }
+
+    /// Path inside a libraries folder, with native separators.
+    pub fn relative_path(&self) -> PathBuf {
+        let mut path: PathBuf = self.group.split('.').collect();
+        path.push(&self.artifact);
+        path.push(&self.version);
+        path.push(self.file_name());
+        path
+    }
+}
+
+/// Back to `group:artifact:version[:classifier][@extension]`; `@jar` is
+/// omitted.
+impl fmt::Display for MavenCoordinate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.group, self.artifact, self.version)?;
+        if let Some(classifier) = &self.classifier {
+            write!(f, ":{}", classifier)?;
+        }
+        if self.extension != DEFAULT_EXTENSION {
+            write!(f, "@{}", self.extension)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(name: &str) -> MavenCoordinate {
+        MavenCoordinate::parse(name).unwrap_or_else(|| panic!("{} should parse", name))
+    }
+
+    #[test]
+    fn parses_plain_coordinates() {
+        let coordinate = parse("net.minecraftforge:forge:1.12.2-14.23.5.2859");
+
+        assert_eq!(coordinate.group, "net.minecraftforge");
+        assert_eq!(coordinate.artifact, "forge");
+        assert_eq!(coordinate.version, "1.12.2-14.23.5.2859");
+        assert_eq!(coordinate.classifier, None);
+        assert_eq!(coordinate.extension, "jar");
+    }
+
+    #[test]
+    fn parses_classifiers() {
+        let coordinate = parse("org.lwjgl:lwjgl:3.3.1:natives-windows");
+
+        assert_eq!(coordinate.version, "3.3.1");
+        assert_eq!(coordinate.classifier.as_deref(), Some("natives-windows"));
+        assert_eq!(coordinate.extension, "jar");
+    }
+
+    #[test]
+    fn parses_extensions() {
+        let coordinate = parse("de.oceanlabs.mcp:mcp_config:1.19.2-20220805.130853@zip");
+        assert_eq!(coordinate.version, "1.19.2-20220805.130853");
+        assert_eq!(coordinate.classifier, None);
+        assert_eq!(coordinate.extension, "zip");
+
+        let coordinate = parse("net.minecraft:client:1.19.2-20220805.130853:mappings@txt");
+        assert_eq!(coordinate.classifier.as_deref(), Some("mappings"));
+        assert_eq!(coordinate.extension, "txt");
+
+        // Las versiones con `@` no existen: el último `@` separa la extensión
+        let coordinate = parse("com.example:lib:1.0@tar.gz");
+        assert_eq!(coordinate.extension, "tar.gz");
+    }
+
+    #[test]
+    fn trims_surrounding_whitespace() {
+        assert_eq!(parse("  a.b:c:1.0\n"), parse("a.b:c:1.0"));
+    }
+
+    #[test]
+    fn rejects_missing_parts() {
+        for name in [
+            "",
+            "   ",
+            "forge",
+            "net.minecraftforge:forge",
+            "net.minecraftforge",
+        ] {
+            assert_eq!(MavenCoordinate::parse(name), None, "{:?}", name);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_names() {
+        for name in [
+            // Partes vacías
+            ":forge:1.0",
+            "net.minecraftforge::1.0",
+            "net.minecraftforge:forge:",
+            "net.minecraftforge:forge:1.0:",
+            // Demasiadas partes
+            "net.minecraftforge:forge:1.0:universal:extra",
+            // Extensión vacía o mal colocada
+            "net.minecraftforge:forge:1.0@",
+            "net.minecraftforge:forge@zip:1.0",
+            "@zip",
+        ] {
+            assert_eq!(MavenCoordinate::parse(name), None, "{:?}", name);
+        }
+    }
+
+    #[test]
+    fn identity_ignores_version_and_extension() {
+        assert_eq!(parse("org.lwjgl:lwjgl:3.2.2").identity(), "org.lwjgl:lwjgl");
+        assert_eq!(
+            parse("org.lwjgl:lwjgl:3.3.1:natives-linux@zip").identity(),
+            "org.lwjgl:lwjgl:natives-linux"
+        );
+        assert_eq!(
+            parse("org.lwjgl:lwjgl:3.2.2").identity(),
+            parse("org.lwjgl:lwjgl:3.3.1@zip").identity()
+        );
+    }
+
+    #[test]
+    fn builds_repository_paths() {
+        let coordinate = parse("org.lwjgl:lwjgl:3.3.1:natives-windows");
+        assert_eq!(coordinate.file_name(), "lwjgl-3.3.1-natives-windows.jar");
+        assert_eq!(coordinate.directory(), "org/lwjgl/lwjgl/3.3.1");
+        assert_eq!(
+            coordinate.url_path(),
+            "org/lwjgl/lwjgl/3.3.1/lwjgl-3.3.1-natives-windows.jar"
+        );
+
+        let coordinate = parse("de.oceanlabs.mcp:mcp_config:1.19.2-20220805.130853@zip");
+        assert_eq!(
+            coordinate.url_path(),
+            "de/oceanlabs/mcp/mcp_config/1.19.2-20220805.130853/mcp_config-1.19.2-20220805.130853.zip"
+        );
+        assert_eq!(
+            coordinate.relative_path(),
+            [
+                "de",
+                "oceanlabs",
+                "mcp",
+                "mcp_config",
+                "1.19.2-20220805.130853"
+            ]
+            .iter()
+            .collect::<PathBuf>()
+            .join("mcp_config-1.19.2-20220805.130853.zip")
+        );
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for name in [
+            "net.minecraftforge:forge:1.12.2-14.23.5.2859",
+            "org.lwjgl:lwjgl:3.3.1:natives-windows",
+            "de.oceanlabs.mcp:mcp_config:1.19.2-20220805.130853@zip",
+            "net.minecraft:client:1.19.2:mappings@txt",
+        ] {
+            assert_eq!(parse(name).to_string(), name);
+            assert_eq!(parse(&parse(name).to_string()), parse(name));
+        }
+        // `@jar` es la extensión por defecto y no se escribe
+        assert_eq!(parse("a.b:c:1.0@jar").to_string(), "a.b:c:1.0");
+    }
+}
@@ -0,0 +1,152 @@
+//! Maven version ordering (a simplified `ComparableVersion`).
+
+use std::cmp::Ordering;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Item {
+    Number(u64),
+    Qualifier(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct MavenVersion {
+    raw: String,
+    items: Vec<Item>,
+}
+
+impl MavenVersion {
+    pub fn parse(version: &str) -> Self {
+        let lower = version.trim().to_lowercase();
+        let mut items = Vec::new();
+        let mut current = String::new();
+        let mut current_is_digit = false;
+
+        for c in lower.chars() {
+            if c == '.' || c == '-' || c == '_' || c == '+' {
+                flush(&mut current, &mut items);
+                continue;
+            }
+
+            // Cambio dígito/letra: "rc1" -> "rc", 1
+            let is_digit = c.is_ascii_digit();
+            if !current.is_empty() && is_digit != current_is_digit {
+                flush(&mut current, &mut items);
+            }
+            current_is_digit = is_digit;
+            current.push(c);
+        }
+        flush(&mut current, &mut items);
+
+        // "1.0.0" == "1" y "1.0-final" == "1.0"
+        loop {
+            match items.last() {
+                Some(Item::Number(0)) => items.pop(),
+                Some(Item::Qualifier(q)) if q.is_empty() => items.pop(),
+                _ => break,
+            };
+        }
+
+        Self {
+            raw: version.to_string(),
+            items,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.raw
+    }
+}
+
+fn flush(current: &mut String, items: &mut Vec<Item>) {
+    if current.is_empty() {
+        return;
+    }
+    items.push(match current.parse::<u64>() {
+        Ok(number) => Item::Number(number),
+        Err(_) => Item::Qualifier(normalize_qualifier(current)),
+    });
+    current.clear();
+}
+
+/// Maps aliases to their canonical qualifier; release markers become "".
+fn normalize_qualifier(qualifier: &str) -> String {
+    match qualifier {
+        "a" => "alpha",
+        "b" => "beta",
+        "m" => "milestone",
+        "cr" => "rc",
+        "ga" | "final" | "release" => "",
+        other => other,
+    }
+    .to_string()
+}
+
+fn qualifier_rank(qualifier: &str) -> usize {
+    match qualifier {
+        "alpha" => 0,
+        "beta" => 1,
+        "milestone" => 2,
+        "rc" => 3,
+        "snapshot" => 4,
+        "" => 5,
+        "sp" => 6,
+        // Calificadores desconocidos van después de los conocidos
+        _ => 7,
+    }
+}
+
+fn compare_items(a: Option<&Item>, b: Option<&Item>) -> Ordering {
+    match (a, b) {
+        (Some(Item::Number(x)), Some(Item::Number(y))) => x.cmp(y),
+        (Some(Item::Number(_)), Some(Item::Qualifier(_))) => Ordering::Greater,
+        (Some(Item::Qualifier(_)), Some(Item::Number(_))) => Ordering::Less,
+        (Some(Item::Qualifier(x)), Some(Item::Qualifier(y))) => qualifier_rank(x)
+            .cmp(&qualifier_rank(y))
+            .then_with(|| x.cmp(y)),
+        // Un elemento ausente equivale a 0 o a "release"
+        (Some(Item::Number(x)), None) => x.cmp(&0),
+        (None, Some(Item::Number(y))) => 0.cmp(y),
+        (Some(Item::Qualifier(x)), None) => qualifier_rank(x).cmp(&qualifier_rank("")),
+        (None, Some(Item::Qualifier(y))) => qualifier_rank("").cmp(&qualifier_rank(y)),
+        (None, None) => Ordering::Equal,
+    }
+}
+
+impl Ord for MavenVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let len = self.items.len().max(other.items.len());
+        for i in 0..len {
+            let ordering = compare_items(self.items.get(i), other.items.get(i));
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+        }
+        Ordering::Equal
+    }
+}
+
+impl PartialOrd for MavenVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for MavenVersion {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for MavenVersion {}
+
+impl fmt::Display for MavenVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+/// Compares two raw version strings using maven ordering.
+pub fn compare(a: &str, b: &str) -> Ordering {
+    MavenVersion::parse(a).cmp(&MavenVersion::parse(b))
+}
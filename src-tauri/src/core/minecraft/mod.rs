@@ -1,11 +1,26 @@
 pub mod arguments;
 pub mod classpath;
+pub mod environment;
+pub mod heap_limits;
+pub mod launch_options;
 pub mod launcher;
+pub mod legacy;
 pub mod manifest;
+pub mod maven;
+pub mod maven_version;
+pub mod options;
+pub mod path_encoding;
 pub mod paths;
+pub mod resolution;
+pub mod sandbox;
 
-pub use arguments::{ArgumentProcessor, RuleEvaluator};
+pub use arguments::{ArgumentProcessor, ArgumentSanitizer, RuleEvaluator};
 pub use classpath::ClasspathBuilder;
+pub use environment::LaunchEnvironment;
+pub use launch_options::LaunchOptions;
 pub use launcher::MinecraftLauncher;
 pub use manifest::{ManifestMerger, ManifestParser};
+pub use maven::MavenCoordinate;
 pub use paths::MinecraftPaths;
+pub use resolution::GameResolution;
+pub use sandbox::{InstanceIsolation, InstanceSandbox};
@@ -0,0 +1,40 @@
+//! Minimal reader/writer for the game's `options.txt` (`key:value` lines).
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const OPTIONS_FILE: &str = "options.txt";
+
+pub fn read_option(game_dir: &Path, key: &str) -> Option<String> {
+    let content = fs::read_to_string(game_dir.join(OPTIONS_FILE)).ok()?;
+    content.lines().find_map(|line| {
+        let (line_key, value) = line.split_once(':')?;
+        (line_key == key).then(|| value.to_string())
+    })
+}
+
+/// Sets `key` keeping every other line as the game wrote it. Creates the
+/// file if the game has not been started yet.
+pub fn set_option(game_dir: &Path, key: &str, value: &str) -> io::Result<()> {
+    let path = game_dir.join(OPTIONS_FILE);
+    let content = fs::read_to_string(&path).unwrap_or_default();
+
+    let mut found = false;
+    let mut lines: Vec<String> = content
+        .lines()
+        .map(|line| match line.split_once(':') {
+            Some((line_key, _)) if line_key == key => {
+                found = true;
+                format!("{}:{}", key, value)
+            }
+            _ => line.to_string(),
+        })
+        .collect();
+    if !found {
+        lines.push(format!("{}:{}", key, value));
+    }
+
+    fs::create_dir_all(game_dir)?;
+    fs::write(&path, lines.join("\n") + "\n")
+}
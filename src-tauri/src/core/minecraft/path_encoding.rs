@@ -0,0 +1,106 @@
+//! Non-ASCII paths and the JVM.
+
+use super::MinecraftPaths;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Codificación de texto de la JVM; Java 18+ ya usa UTF-8 salvo en la consola
+const ENCODING_JVM_ARGS: &[&str] = &[
+    "-Dfile.encoding=UTF-8",
+    // Java 8-17
+    "-Dsun.stdout.encoding=UTF-8",
+    "-Dsun.stderr.encoding=UTF-8",
+    // Java 19+
+    "-Dstdout.encoding=UTF-8",
+    "-Dstderr.encoding=UTF-8",
+];
+
+pub fn is_ascii_path(path: &Path) -> bool {
+    path.to_string_lossy().is_ascii()
+}
+
+/// JVM properties that make the game read and write text as UTF-8. They go
+/// before the manifest's arguments so an explicit value there still wins.
+pub fn encoding_jvm_args() -> Vec<String> {
+    ENCODING_JVM_ARGS
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect()
+}
+
+/// ASCII-only folder for what the JVM loads by path, or `None` if none of
+/// the candidates can be created.
+pub fn safe_root() -> Option<PathBuf> {
+    let candidates: Vec<PathBuf> = if cfg!(windows) {
+        ["ProgramData", "SystemDrive"]
+            .iter()
+            .filter_map(|var| std::env::var_os(var))
+            .map(|dir| {
+                let mut dir = PathBuf::from(dir);
+                // `SystemDrive` es "C:", sin barra
+                dir.push("\\");
+                dir.join("ModpackStore")
+            })
+            .collect()
+    } else {
+        vec![PathBuf::from("/tmp/ModpackStore")]
+    };
+
+    candidates
+        .into_iter()
+        .find(|dir| is_ascii_path(dir) && fs::create_dir_all(dir).is_ok())
+}
+
+/// Whether the system temp dir (natives, JVM temp files) has to be
+/// replaced by `safe_root`.
+pub fn temp_needs_relocation() -> bool {
+    !is_ascii_path(&std::env::temp_dir())
+}
+
+/// `-Djava.io.tmpdir` pointing into `safe_root` when the temp dir the game
+/// would use has non-ASCII characters.
+pub fn relocated_temp_arg(temp_dir: &Path) -> Option<String> {
+    if is_ascii_path(temp_dir) {
+        return None;
+    }
+    let tmp = safe_root()?.join("tmp");
+    if let Err(e) = fs::create_dir_all(&tmp) {
+        log::warn!(
+            "[PathEncoding] Could not create temp dir {}: {}",
+            tmp.display(),
+            e
+        );
+        return None;
+    }
+    log::info!(
+        "[PathEncoding] JVM temp dir moved from {} to {}",
+        temp_dir.display(),
+        tmp.display()
+    );
+    Some(format!("-Djava.io.tmpdir={}", tmp.display()))
+}
+
+/// Paths the game needs to load (label, path) that contain non-ASCII
+/// characters.
+pub fn non_ascii_paths(paths: &MinecraftPaths) -> Vec<(&'static str, PathBuf)> {
+    [
+        ("Java", paths.java_path().to_path_buf()),
+        ("Juego", paths.game_dir().to_path_buf()),
+        ("Librerías", paths.libraries_dir()),
+        ("Nativos", paths.natives_dir()),
+    ]
+    .into_iter()
+    .filter(|(_, path)| !is_ascii_path(path))
+    .collect()
+}
+
+/// Logs a warning for every critical path with non-ASCII characters.
+pub fn warn_non_ascii_paths(paths: &MinecraftPaths) {
+    for (label, path) in non_ascii_paths(paths) {
+        log::warn!(
+            "[PathEncoding] {} path has non-ASCII characters, the game may fail to load it: {}",
+            label,
+            path.display()
+        );
+    }
+}
@@ -1,16 +1,21 @@
-use crate::config::get_config_manager;
 use crate::core::minecraft::{classpath::ClasspathBuilder, manifest::ManifestMerger};
 use crate::core::minecraft_instance::MinecraftInstance;
-use std::path::{Path, PathBuf};
+use crate::core::optifine;
+use std::path::{Component, Path, PathBuf};
 
-use super::{launcher, ManifestParser};
+use super::{launcher, path_encoding, ManifestParser};
 
 #[derive(Debug)]
 pub struct MinecraftPaths {
+    instance_id: String,
     game_dir: PathBuf,
     java_path: PathBuf,
     minecraft_version: String,
     forge_version: Option<String>,
+    optifine_version: Option<String>,
+    custom_version: Option<String>,
+    /// Nativos en `path_encoding::safe_root` porque el temporal no es ASCII
+    relocate_natives: bool,
 }
 
 impl MinecraftPaths {
@@ -25,20 +30,8 @@ impl MinecraftPaths {
 
         let java_path = instance
             .javaPath
-            .as_ref()
-            .map(PathBuf::from)
-            .unwrap_or_else(|| {
-                config
-                    .get_java_dir()
-                    .unwrap_or_else(|| PathBuf::from("default_java"))
-            })
-            .join("bin")
-            .join(if cfg!(windows) { "javaw.exe" } else { "java" });
-
-        let java_path = instance
-            .javaPath
-            .as_ref()
-            .map(PathBuf::from)
+            .as_deref()
+            .map(native_path)
             .unwrap_or_else(|| {
                 config
                     .get_java_dir()
@@ -46,22 +39,31 @@ impl MinecraftPaths {
             })
             .join("bin")
             .join(if cfg!(windows) { "javaw.exe" } else { "java" });
+        let java_path = normalize(&java_path);
 
         let game_dir = instance
             .instanceDirectory
-            .as_ref()
-            .map(PathBuf::from)
+            .as_deref()
+            .map(native_path)
             .unwrap_or_else(|| PathBuf::from("default_path"))
             .join("minecraft");
+        let game_dir = normalize(&game_dir);
 
         log::info!("[MinecraftPaths] Game directory: {}", game_dir.display());
         log::info!("[MinecraftPaths] Java path: {}", java_path.display());
 
+        let relocate_natives =
+            config.get_relocate_non_ascii_paths() && path_encoding::temp_needs_relocation();
+
         Some(Self {
+            instance_id: instance.instanceId.clone(),
             game_dir,
             java_path,
             minecraft_version: instance.minecraftVersion.clone(),
             forge_version: instance.forgeVersion.clone(),
+            optifine_version: instance.optifineVersion.clone(),
+            custom_version: instance.customVersion.clone(),
+            relocate_natives,
         })
     }
 
@@ -82,142 +84,162 @@ impl MinecraftPaths {
     }
 
     pub fn manifest_file(&self) -> PathBuf {
-        let version_dir = self.game_dir.join("versions");
+        let version_dir = versions_dir(&self.game_dir);
 
-        // Check if we need to find the Forge version
+        // Un JSON de versión propio (instancias avanzadas) manda sobre el loader
+        if let Some(version_id) = &self.custom_version {
+            let manifest = version_file(&self.game_dir, version_id, "json");
+            if manifest.is_file() {
+                log::info!("[MinecraftPaths] Using custom version {}", version_id);
+                return manifest;
+            }
+            log::warn!("[MinecraftPaths] Custom version {} is missing", version_id);
+        }
+
+        // La versión de Forge sale de los metadatos de la instancia, no de
+        // launcher_profiles.json: con varias instancias Forge el perfil
+        // "forge" apunta a la última instalada
         if let Some(forge_ref) = &self.forge_version {
             log::info!(
                 "[MinecraftPaths] Searching for Forge version manifest {}",
                 forge_ref
             );
 
-            // Try to get Forge version from launcher_profiles.json
-            let launcher_profiles_path = self.game_dir.join("launcher_profiles.json");
-
-            if launcher_profiles_path.exists() {
-                if let Ok(data) = std::fs::read_to_string(&launcher_profiles_path) {
-                    if let Ok(json) = serde_json::from_str::<serde_json::Value>(&data) {
-                        log::debug!("[MinecraftPaths] Found launcher_profiles.json structure");
-
-                        // Buscar en la estructura correcta: profiles -> forge -> lastVersionId
-                        // O buscar cualquier perfil que tenga una versión Forge compatible
-                        if let Some(profiles) = json.get("profiles").and_then(|v| v.as_object()) {
-                            // Primero intentamos buscar un perfil explícitamente llamado "forge"
-                            if let Some(forge_profile) = profiles.get("forge") {
-                                if let Some(version_id) =
-                                    forge_profile.get("lastVersionId").and_then(|v| v.as_str())
-                                {
-                                    if version_id.contains(&self.minecraft_version)
-                                        && version_id.contains("forge")
-                                    {
-                                        log::info!("[MinecraftPaths] Found Forge version in 'forge' profile: {}", version_id);
-                                        return version_dir
-                                            .join(version_id)
-                                            .join(format!("{}.json", version_id));
-                                    }
-                                }
-                            }
-
-                            // Si no encontramos un perfil específico de forge, buscamos en todos los perfiles
-                            // una versión compatible con la versión de Minecraft y Forge solicitada
-                            for (_, profile) in profiles {
-                                if let Some(version_id) =
-                                    profile.get("lastVersionId").and_then(|v| v.as_str())
-                                {
-                                    // Verificar si este perfil contiene la versión de Minecraft y referencia a Forge
-                                    if version_id.contains(&self.minecraft_version)
-                                        && version_id.contains("forge")
-                                    {
-                                        // Verificar si este perfil coincide con la versión específica de forge
-                                        if version_id.contains(forge_ref) {
-                                            log::info!("[MinecraftPaths] Found matching Forge version in profiles: {}", version_id);
-                                            return version_dir
-                                                .join(version_id)
-                                                .join(format!("{}.json", version_id));
-                                        }
-                                    }
-                                }
-                            }
-                        }
-
-                        log::warn!("[MinecraftPaths] No matching Forge version found in launcher_profiles.json");
-                    } else {
-                        log::warn!(
-                            "[MinecraftPaths] Failed to parse launcher_profiles.json as JSON"
-                        );
-                    }
-                } else {
-                    log::warn!("[MinecraftPaths] Failed to read launcher_profiles.json");
-                }
-            } else {
-                log::warn!(
-                    "[MinecraftPaths] launcher_profiles.json not found at {}",
-                    launcher_profiles_path.display()
-                );
+            if let Some(version_id) =
+                find_forge_version_id(&version_dir, &self.minecraft_version, forge_ref)
+            {
+                log::info!("[MinecraftPaths] Using Forge version {}", version_id);
+                return version_file(&self.game_dir, &version_id, "json");
             }
+            log::warn!(
+                "[MinecraftPaths] No installed Forge version matches {} {}",
+                self.minecraft_version,
+                forge_ref
+            );
+        }
 
-            // Fallback: Try to use the provided forge reference directly
-            let forge_dir = format!("{}-forge-{}", self.minecraft_version, forge_ref);
-            let forge_path = version_dir
-                .join(&forge_dir)
-                .join(format!("{}.json", forge_dir));
-
-            if forge_path.exists() {
-                log::info!(
-                    "[MinecraftPaths] Using Forge manifest: {}",
-                    forge_path.display()
-                );
-                return forge_path;
-            }
-
-            // Intentar formato alternativo para el directorio de Forge
-            let alt_forge_dir = format!("{}-{}", self.minecraft_version, forge_ref);
-            let alt_forge_path = version_dir
-                .join(&alt_forge_dir)
-                .join(format!("{}.json", alt_forge_dir));
-
-            if alt_forge_path.exists() {
-                log::info!(
-                    "[MinecraftPaths] Using alternative Forge manifest: {}",
-                    alt_forge_path.display()
-                );
-                return alt_forge_path;
+        // OptiFine en instancias vanilla registra su propia versión; en Forge es un mod
+        if let Some(edition) = self
+            .optifine_version
+            .as_deref()
+            .filter(|_| self.forge_version.is_none())
+        {
+            let version_id = optifine::version_id(&self.minecraft_version, edition);
+            let manifest = version_file(&self.game_dir, &version_id, "json");
+            if manifest.is_file() {
+                log::info!("[MinecraftPaths] Using OptiFine version {}", version_id);
+                return manifest;
             }
+            log::warn!(
+                "[MinecraftPaths] OptiFine version {} is missing",
+                version_id
+            );
         }
 
         // Default to vanilla manifest
         log::info!("[MinecraftPaths] Using vanilla manifest file");
-        version_dir
-            .join(&self.minecraft_version)
-            .join(format!("{}.json", self.minecraft_version))
-    }
-
-    pub fn vanilla_manifest_file(&self, version: &str) -> PathBuf {
-        self.game_dir
-            .join("versions")
-            .join(version)
-            .join(format!("{}.json", version))
+        version_file(&self.game_dir, &self.minecraft_version, "json")
     }
 
     pub fn client_jar(&self) -> PathBuf {
-        // For simplicity, return vanilla client jar
-        // In a real implementation, this would check for Forge client jars
-        self.game_dir
-            .join("versions")
-            .join(&self.minecraft_version)
-            .join(format!("{}.jar", self.minecraft_version))
+        // Las versiones propias pueden traer su jar; si no, heredan el de vanilla
+        if let Some(version_id) = &self.custom_version {
+            let jar = version_file(&self.game_dir, version_id, "jar");
+            if jar.is_file() {
+                return jar;
+            }
+        }
+
+        // Forge and OptiFine run on top of the vanilla client jar
+        version_file(&self.game_dir, &self.minecraft_version, "jar")
     }
 
     pub fn libraries_dir(&self) -> PathBuf {
-        self.game_dir.join("libraries")
+        libraries_dir(&self.game_dir)
+    }
+
+    /// Library jar from its manifest path (`org/lwjgl/.../lwjgl.jar`).
+    pub fn library(&self, relative: &str) -> PathBuf {
+        join_relative(&self.libraries_dir(), relative)
     }
 
     pub fn assets_dir(&self) -> PathBuf {
-        self.game_dir.join("assets")
+        assets_dir(&self.game_dir)
     }
 
+    /// Natives extracted during bootstrap, e.g. `minecraft/natives/1.20.2`.
+    pub fn extracted_natives_dir(&self) -> PathBuf {
+        extracted_natives_dir(&self.game_dir, &self.minecraft_version)
+    }
+
+    /// Natives directory used by the running game. It is scoped to the
+    /// version and the instance so parallel launches never share it.
     pub fn natives_dir(&self) -> PathBuf {
-        self.game_dir.join("natives").join(&self.minecraft_version)
+        let root = if self.relocate_natives {
+            Self::safe_natives_root().unwrap_or_else(Self::scoped_natives_root)
+        } else {
+            Self::scoped_natives_root()
+        };
+        root.join(Self::scoped_natives_name(
+            &self.instance_id,
+            &self.minecraft_version,
+        ))
+    }
+
+    fn scoped_natives_name(instance_id: &str, minecraft_version: &str) -> String {
+        format!("{}-{}", minecraft_version, instance_id)
+    }
+
+    /// Temporary folder holding the scoped natives of every launch.
+    pub fn scoped_natives_root() -> PathBuf {
+        std::env::temp_dir().join("ModpackStore").join("natives")
+    }
+
+    /// Natives folder used instead of `scoped_natives_root` when the temp
+    /// dir has non-ASCII characters.
+    fn safe_natives_root() -> Option<PathBuf> {
+        path_encoding::safe_root().map(|root| root.join("natives"))
+    }
+
+    /// Every folder that may hold scoped natives.
+    pub fn scoped_natives_roots() -> Vec<PathBuf> {
+        let mut roots = vec![Self::scoped_natives_root()];
+        roots.extend(Self::safe_natives_root().filter(|root| !roots.contains(root)));
+        roots
+    }
+
+    /// Recreates the scoped natives directory from the extracted natives.
+    pub fn prepare_natives(&self) -> std::io::Result<PathBuf> {
+        let target = self.natives_dir();
+        if target.exists() {
+            std::fs::remove_dir_all(&target)?;
+        }
+        std::fs::create_dir_all(&target)?;
+
+        let source = self.extracted_natives_dir();
+        if source.exists() {
+            copy_dir_recursive(&source, &target)?;
+        }
+
+        Ok(target)
+    }
+
+    /// Removes the scoped natives directory once the game has exited.
+    pub fn cleanup_natives(instance_id: &str, minecraft_version: &str) {
+        let name = Self::scoped_natives_name(instance_id, minecraft_version);
+        for root in Self::scoped_natives_roots() {
+            let dir = root.join(&name);
+            if !dir.exists() {
+                continue;
+            }
+            if let Err(e) = std::fs::remove_dir_all(&dir) {
+                log::warn!(
+                    "[MinecraftPaths] Failed to remove natives dir {}: {}",
+                    dir.display(),
+                    e
+                );
+            }
+        }
     }
 
     pub fn classpath_str(&self) -> String {
@@ -227,3 +249,325 @@ impl MinecraftPaths {
         classpath_builder.build().unwrap_or_default()
     }
 }
+
+fn copy_dir_recursive(source: &Path, target: &Path) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(source)? {
+        let entry = entry?;
+        let path = entry.path();
+        let destination = target.join(entry.file_name());
+
+        if path.is_dir() {
+            std::fs::create_dir_all(&destination)?;
+            copy_dir_recursive(&path, &destination)?;
+        } else {
+            std::fs::copy(&path, &destination)?;
+        }
+    }
+    Ok(())
+}
+
+// Layout of a game directory (`<instance>/minecraft`). Every module that
+// builds a path inside it goes through these, so separators and names are
+// handled in one place.
+
+pub fn versions_dir(game_dir: &Path) -> PathBuf {
+    game_dir.join("versions")
+}
+
+pub fn version_dir(game_dir: &Path, version_id: &str) -> PathBuf {
+    versions_dir(game_dir).join(version_id)
+}
+
+/// `versions/<id>/<id>.<extension>`, e.g. the manifest (`json`) or the
+/// client (`jar`) of a version.
+pub fn version_file(game_dir: &Path, version_id: &str, extension: &str) -> PathBuf {
+    version_dir(game_dir, version_id).join(format!("{}.{}", version_id, extension))
+}
+
+pub fn libraries_dir(game_dir: &Path) -> PathBuf {
+    game_dir.join("libraries")
+}
+
+pub fn assets_dir(game_dir: &Path) -> PathBuf {
+    game_dir.join("assets")
+}
+
+pub fn extracted_natives_dir(game_dir: &Path, minecraft_version: &str) -> PathBuf {
+    game_dir.join("natives").join(minecraft_version)
+}
+
+/// `relative` as found in manifests and Maven paths (`/` or `\`
+/// separators) joined component by component under `base`. Segments that
+/// would leave `base` (`..`, a root or a drive such as `C:`) are dropped.
+pub fn join_relative(base: &Path, relative: &str) -> PathBuf {
+    relative
+        .split(['/', '\\'])
+        .filter(|segment| !matches!(*segment, "" | "." | "..") && !is_drive(segment))
+        .fold(base.to_path_buf(), |path, segment| path.join(segment))
+}
+
+fn is_drive(segment: &str) -> bool {
+    let mut chars = segment.chars();
+    matches!(
+        (chars.next(), chars.next(), chars.next()),
+        (Some(letter), Some(':'), None) if letter.is_ascii_alphabetic()
+    )
+}
+
+/// A path stored by the launcher (instance metadata, config), written with
+/// the separators of whatever OS saved it.
+pub fn native_path(stored: &str) -> PathBuf {
+    if cfg!(windows) {
+        PathBuf::from(stored.replace('/', "\\"))
+    } else {
+        PathBuf::from(stored.replace('\\', "/"))
+    }
+}
+
+/// Inverse of `native_path`: paths are stored with `/` on every OS.
+pub fn stored_path(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// Absolute, canonical form of `path`. Existing paths are resolved by the
+/// file system and the rest are cleaned lexically (`.` and `..`). On
+/// Windows the `\\?\` prefix that `canonicalize` adds is only kept for paths
+/// longer than `MAX_PATH`, since Java and most tools reject it otherwise.
+pub fn normalize(path: &Path) -> PathBuf {
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()
+            .map(|dir| dir.join(path))
+            .unwrap_or_else(|_| path.to_path_buf())
+    };
+    let resolved = absolute
+        .canonicalize()
+        .unwrap_or_else(|_| clean_path(&absolute));
+    long_path(resolved)
+}
+
+fn clean_path(path: &Path) -> PathBuf {
+    let mut cleaned = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                cleaned.pop();
+            }
+            other => cleaned.push(other.as_os_str()),
+        }
+    }
+    cleaned
+}
+
+#[cfg(windows)]
+fn long_path(path: PathBuf) -> PathBuf {
+    const MAX_PATH: usize = 260;
+
+    let Some(raw) = path.to_str() else {
+        return path;
+    };
+    // (forma con prefijo \\?\, forma normal)
+    let (verbatim, plain) = if let Some(share) = raw.strip_prefix(r"\\?\UNC\") {
+        (raw.to_string(), format!(r"\\{}", share))
+    } else if let Some(local) = raw.strip_prefix(r"\\?\") {
+        (raw.to_string(), local.to_string())
+    } else if let Some(share) = raw.strip_prefix(r"\\") {
+        (format!(r"\\?\UNC\{}", share), raw.to_string())
+    } else {
+        (format!(r"\\?\{}", raw), raw.to_string())
+    };
+
+    PathBuf::from(if plain.chars().count() < MAX_PATH {
+        plain
+    } else {
+        verbatim
+    })
+}
+
+#[cfg(not(windows))]
+fn long_path(path: PathBuf) -> PathBuf {
+    path
+}
+
+/// Id of the installed Forge version of `minecraft_version` + `forge_version`
+/// under `versions_dir`. Tries the names the bootstrap and the Forge
+/// installers use, then scans for a `*-forge-*` folder matching both.
+pub fn find_forge_version_id(
+    versions_dir: &Path,
+    minecraft_version: &str,
+    forge_version: &str,
+) -> Option<String> {
+    let is_installed = |id: &str| versions_dir.join(id).join(format!("{}.json", id)).is_file();
+
+    // Algunos modpacks guardan la versión completa ("1.12.2-14.23.5.2859")
+    let forge_version = forge_version
+        .strip_prefix(&format!("{}-", minecraft_version))
+        .unwrap_or(forge_version);
+
+    let candidates = [
+        format!("{}-forge-{}", minecraft_version, forge_version),
+        format!(
+            "{}-forge{}-{}",
+            minecraft_version, minecraft_version, forge_version
+        ),
+        format!(
+            "{}-Forge{}-{}",
+            minecraft_version, forge_version, minecraft_version
+        ),
+        format!("{}-{}", minecraft_version, forge_version),
+    ];
+    if let Some(id) = candidates.into_iter().find(|id| is_installed(id)) {
+        return Some(id);
+    }
+
+    let mut matches: Vec<String> = std::fs::read_dir(versions_dir)
+        .ok()?
+        .filter_map(Result::ok)
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|id| {
+            let lower = id.to_lowercase();
+            lower.contains("forge")
+                && id.contains(minecraft_version)
+                && id.contains(forge_version)
+                && is_installed(id)
+        })
+        .collect();
+    // Con varias coincidencias, la más corta es la menos decorada
+    matches.sort_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+    matches.into_iter().next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn under(base: &Path, segments: &[&str]) -> PathBuf {
+        segments
+            .iter()
+            .fold(base.to_path_buf(), |path, segment| path.join(segment))
+    }
+
+    #[test]
+    fn join_relative_accepts_both_separators() {
+        let base = Path::new("libraries");
+
+        assert_eq!(
+            join_relative(base, "net/minecraftforge\\forge/1.12.2/forge.jar"),
+            under(
+                base,
+                &["net", "minecraftforge", "forge", "1.12.2", "forge.jar"]
+            )
+        );
+        assert_eq!(
+            join_relative(base, "./org//lwjgl/./lwjgl.jar"),
+            under(base, &["org", "lwjgl", "lwjgl.jar"])
+        );
+    }
+
+    #[test]
+    fn join_relative_rejects_parent_segments() {
+        let base = Path::new("minecraft");
+
+        assert_eq!(
+            join_relative(base, "../../options.txt"),
+            under(base, &["options.txt"])
+        );
+        assert_eq!(
+            join_relative(base, "mods\\..\\..\\evil.jar"),
+            under(base, &["mods", "evil.jar"])
+        );
+        assert_eq!(join_relative(base, ".."), base);
+    }
+
+    #[test]
+    fn join_relative_keeps_absolute_inputs_under_base() {
+        let base = Path::new("minecraft");
+
+        assert_eq!(
+            join_relative(base, "/etc/passwd"),
+            under(base, &["etc", "passwd"])
+        );
+        assert_eq!(
+            join_relative(base, "C:\\Windows\\System32"),
+            under(base, &["Windows", "System32"])
+        );
+        assert_eq!(
+            join_relative(base, "\\\\server\\share\\mods"),
+            under(base, &["server", "share", "mods"])
+        );
+        // Sólo una letra seguida de `:` es una unidad
+        assert_eq!(
+            join_relative(base, "config/a:b"),
+            under(base, &["config", "a:b"])
+        );
+    }
+
+    #[test]
+    fn native_path_uses_the_os_separator() {
+        let stored = "C:/Games\\ModpackStore/instances";
+
+        let expected = if cfg!(windows) {
+            "C:\\Games\\ModpackStore\\instances"
+        } else {
+            "C:/Games/ModpackStore/instances"
+        };
+        assert_eq!(native_path(stored), PathBuf::from(expected));
+    }
+
+    #[test]
+    fn stored_path_round_trips_with_forward_slashes() {
+        let stored = "instances/Mi Pack ñ/minecraft";
+
+        assert_eq!(stored_path(&native_path(stored)), stored);
+        assert_eq!(
+            stored_path(Path::new("instances\\Mi Pack ñ\\minecraft")),
+            stored
+        );
+    }
+
+    #[test]
+    fn normalize_makes_relative_paths_absolute() {
+        let missing = format!("missing-{}", uuid::Uuid::new_v4());
+        let relative = Path::new(&missing).join(".").join("a").join("..").join("b");
+
+        let normalized = normalize(&relative);
+
+        assert!(normalized.is_absolute());
+        assert!(normalized.ends_with(Path::new(&missing).join("b")));
+    }
+
+    #[test]
+    fn normalize_cleans_missing_absolute_paths() {
+        let root = std::env::temp_dir().join(format!("missing-{}", uuid::Uuid::new_v4()));
+
+        assert_eq!(
+            normalize(&root.join("a").join("..").join("b").join(".")),
+            long_path(clean_path(&root.join("b")))
+        );
+    }
+
+    #[test]
+    fn normalize_resolves_existing_paths() {
+        let dir = std::env::temp_dir();
+
+        assert_eq!(normalize(&dir), long_path(dir.canonicalize().unwrap()));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn long_windows_paths_keep_the_verbatim_prefix() {
+        let short = PathBuf::from(r"\\?\C:\Games\instance");
+        assert_eq!(long_path(short), PathBuf::from(r"C:\Games\instance"));
+
+        let long = format!(r"C:\{}", "a".repeat(300));
+        assert_eq!(
+            long_path(PathBuf::from(&long)),
+            PathBuf::from(format!(r"\\?\{}", long))
+        );
+
+        let share = PathBuf::from(r"\\server\share\instance");
+        assert_eq!(long_path(share.clone()), share);
+    }
+}
@@ -0,0 +1,54 @@
+use crate::config::ConfigManager;
+use crate::core::minecraft_instance::MinecraftInstance;
+use serde::{Deserialize, Serialize};
+
+/// Tamaño mínimo de ventana que aceptamos (el propio juego no baja de aquí)
+const MIN_WIDTH: u32 = 320;
+const MIN_HEIGHT: u32 = 240;
+
+/// Window size and fullscreen mode the game is started with.
+///
+/// The instance's `resolution` overrides the global `gameWidth`,
+/// `gameHeight` and `gameFullscreen` settings; a size of `None` (or 0 in
+/// the config) leaves the game's default window.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GameResolution {
+    #[serde(default)]
+    pub width: Option<u32>,
+    #[serde(default)]
+    pub height: Option<u32>,
+    #[serde(default)]
+    pub fullscreen: bool,
+}
+
+impl GameResolution {
+    pub fn new(instance: &MinecraftInstance, config: &ConfigManager) -> Self {
+        instance
+            .resolution
+            .unwrap_or_else(|| config.get_game_resolution())
+    }
+
+    /// `(width, height)` when both are set.
+    pub fn custom_size(&self) -> Option<(u32, u32)> {
+        match (self.width, self.height) {
+            (Some(width), Some(height)) if width > 0 && height > 0 => Some((width, height)),
+            _ => None,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.width.is_some() != self.height.is_some() {
+            return Err("Indica tanto el ancho como el alto de la ventana".to_string());
+        }
+        if let Some((width, height)) = self.custom_size() {
+            if width < MIN_WIDTH || height < MIN_HEIGHT {
+                return Err(format!(
+                    "La resolución mínima es {}x{}",
+                    MIN_WIDTH, MIN_HEIGHT
+                ));
+            }
+        }
+        Ok(())
+    }
+}
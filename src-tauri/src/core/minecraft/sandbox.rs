@@ -0,0 +1,145 @@
+use crate::core::minecraft_instance::MinecraftInstance;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Carpeta dentro de la instancia donde viven los directorios aislados
+const SANDBOX_DIR: &str = "sandbox";
+
+/// Per-instance isolation of the directories the game may write to
+/// outside its game dir. Everything is off by default.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceIsolation {
+    /// `user.home`, `HOME` and `USERPROFILE` point inside the instance
+    #[serde(default)]
+    pub isolate_home: bool,
+    /// `APPDATA`/`LOCALAPPDATA` and the XDG config, data and cache dirs
+    /// (where mods keep a shared `.minecraft` or their own config)
+    #[serde(default)]
+    pub isolate_app_data: bool,
+    /// `java.io.tmpdir` and `TMPDIR`/`TEMP`/`TMP`
+    #[serde(default)]
+    pub isolate_temp: bool,
+}
+
+impl InstanceIsolation {
+    pub fn is_enabled(&self) -> bool {
+        self.isolate_home || self.isolate_app_data || self.isolate_temp
+    }
+}
+
+/// Directories and overrides derived from an instance's `isolation`.
+#[derive(Debug, Default)]
+pub struct InstanceSandbox {
+    isolation: InstanceIsolation,
+    root: PathBuf,
+}
+
+impl InstanceSandbox {
+    pub fn new(instance: &MinecraftInstance) -> Self {
+        let root = instance
+            .instanceDirectory
+            .as_ref()
+            .map(|dir| Path::new(dir).join(SANDBOX_DIR))
+            .unwrap_or_default();
+        Self {
+            isolation: instance.isolation.unwrap_or_default(),
+            root,
+        }
+    }
+
+    fn home_dir(&self) -> PathBuf {
+        self.root.join("home")
+    }
+
+    fn temp_dir(&self) -> PathBuf {
+        self.root.join("tmp")
+    }
+
+    fn dirs(&self) -> Vec<(&'static str, PathBuf)> {
+        let mut vars = Vec::new();
+        if self.isolation.isolate_home {
+            let home = self.home_dir();
+            vars.push(("HOME", home.clone()));
+            if cfg!(windows) {
+                vars.push(("USERPROFILE", home));
+            }
+        }
+        if self.isolation.isolate_app_data {
+            let app_data = self.root.join("appdata");
+            if cfg!(windows) {
+                vars.push(("APPDATA", app_data.join("Roaming")));
+                vars.push(("LOCALAPPDATA", app_data.join("Local")));
+            } else {
+                vars.push(("XDG_CONFIG_HOME", app_data.join("config")));
+                vars.push(("XDG_DATA_HOME", app_data.join("data")));
+                vars.push(("XDG_CACHE_HOME", app_data.join("cache")));
+            }
+        }
+        if self.isolation.isolate_temp {
+            let temp = self.temp_dir();
+            for key in ["TMPDIR", "TEMP", "TMP"] {
+                vars.push((key, temp.clone()));
+            }
+        }
+        vars
+    }
+
+    /// Creates the isolated directories; call before spawning the game.
+    pub fn prepare(&self) -> Result<(), String> {
+        for (_, dir) in self.dirs() {
+            fs::create_dir_all(&dir).map_err(|e| {
+                format!(
+                    "Error al crear el directorio aislado {}: {}",
+                    dir.display(),
+                    e
+                )
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Variables for the child process. The instance's own `envVars` are
+    /// applied afterwards and can still override them.
+    pub fn env_vars(&self) -> Vec<(String, String)> {
+        let mut vars: Vec<(String, String)> = self
+            .dirs()
+            .into_iter()
+            .map(|(key, dir)| (key.to_string(), dir.to_string_lossy().to_string()))
+            .collect();
+
+        // X11 busca la cookie en $HOME/.Xauthority; sin ella no abre la ventana
+        if self.isolation.isolate_home && cfg!(all(unix, not(target_os = "macos"))) {
+            if let (Err(_), Some(home)) = (std::env::var("XAUTHORITY"), dirs::home_dir()) {
+                vars.push((
+                    "XAUTHORITY".to_string(),
+                    home.join(".Xauthority").to_string_lossy().to_string(),
+                ));
+            }
+        }
+        vars
+    }
+
+    /// Temp dir the game will use: the sandbox's one or the system's.
+    pub fn java_temp_dir(&self) -> PathBuf {
+        if self.isolation.isolate_temp {
+            self.temp_dir()
+        } else {
+            std::env::temp_dir()
+        }
+    }
+
+    /// JVM properties: Java ignores `HOME` on Unix and reads the passwd
+    /// entry, so `user.home` has to be set explicitly.
+    pub fn jvm_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if self.isolation.isolate_home {
+            args.push(format!("-Duser.home={}", self.home_dir().display()));
+        }
+        if self.isolation.isolate_temp {
+            args.push(format!("-Djava.io.tmpdir={}", self.temp_dir().display()));
+        }
+        args
+    }
+}
@@ -1,38 +1,105 @@
 // src-tauri/src/minecraft_instance.rs
+use crate::core::events::EventEmitter;
+use crate::core::file_rules::FileRules;
 use crate::core::instance_launcher::InstanceLauncher;
+use crate::core::instance_registry;
+use crate::core::instance_schema::{InstanceValidationReport, CURRENT_SCHEMA_VERSION};
+use crate::core::launch_hooks::InstanceHooks;
+use crate::core::minecraft::{paths, GameResolution, InstanceIsolation, LaunchOptions};
+use crate::core::modpack_updates::UpdateChannel;
+use crate::core::optional_groups::OptionalGroupSelection;
 use crate::core::tasks_manager::{TaskInfo, TaskStatus, TasksManager};
-use crate::utils::config_manager::ConfigManager;
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::io::Result as IoResult;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::{Arc, Mutex};
 use std::thread;
 
+/// Un candado por instance.json: el listado, los comandos y los hilos de
+/// bootstrap pueden guardar la misma instancia a la vez
+static FILE_LOCKS: Lazy<Mutex<HashMap<PathBuf, Arc<Mutex<()>>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn file_lock(config_file: &Path) -> Arc<Mutex<()>> {
+    let mut locks = FILE_LOCKS.lock().unwrap_or_else(|e| e.into_inner());
+    Arc::clone(locks.entry(config_file.to_path_buf()).or_default())
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ModpackInfo {
     pub name: Option<String>,
     pub version: Option<String>,
     pub author: Option<String>,
     pub modpackVersionId: Option<String>, // Can be specific version ID or "latest"
+    #[serde(default)]
+    pub updateChannel: Option<UpdateChannel>, // Canal del que se reciben actualizaciones
+    #[serde(default)]
+    pub pinnedVersionId: Option<String>, // Si está fijada, no se actualiza a otra versión
     // Otros campos según necesites
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct MinecraftInstance {
+    #[serde(default)]
+    pub schemaVersion: u32, // Versión del formato de instance.json (ver instance_schema)
+    #[serde(default)]
     pub instanceId: String,
+    #[serde(default)]
     pub usesDefaultIcon: bool,
     pub iconUrl: Option<String>,
     pub bannerUrl: Option<String>,
+    #[serde(default)]
     pub instanceName: String,
     pub accountUuid: Option<String>,
+    #[serde(default)]
     pub minecraftPath: String,
     pub modpackId: Option<String>,
     pub modpackInfo: Option<ModpackInfo>,
+    #[serde(default)]
     pub minecraftVersion: String,
     pub instanceDirectory: Option<String>,
     pub forgeVersion: Option<String>,
+    #[serde(default)]
+    pub optifineVersion: Option<String>, // Edición instalada, p. ej. "HD_U_I6"
+    #[serde(default)]
+    pub customVersion: Option<String>, // JSON de versión propio en versions/; tiene prioridad
     pub javaPath: Option<String>, // In the future, we automatically download the correct Java version
+    pub javaMajorVersion: Option<String>, // Versión de Java que requiere; evita borrar su runtime
+    pub memory: Option<u32>,      // Xmx en MB; si es None lo decide effective_memory_mb
+    pub envVars: Option<HashMap<String, String>>, // Se suman a las variables globales
+    pub wrapperCommand: Option<String>, // p. ej. "gamemoderun" o "mangohud --dlsym"
+    pub hooks: Option<InstanceHooks>,
+    pub acceptedNoticeVersion: Option<String>, // Versión del aviso obligatorio ya aceptada
+    pub gameLanguage: Option<String>, // p. ej. "es_es"; si es None se usa la configuración global
+    pub iconFile: Option<String>,     // Icono propio, relativo a la carpeta de la instancia
+    pub bannerFile: Option<String>,   // Banner propio, relativo a la carpeta de la instancia
+    pub optionalGroups: Option<OptionalGroupSelection>, // Grupos opcionales del modpack elegidos
+    #[serde(default)]
+    pub fileRules: Option<FileRules>, // Archivos que el modpack no instala o no toca
+    pub resolution: Option<GameResolution>, // Si es None se usa la resolución global
+    pub isolation: Option<InstanceIsolation>, // Directorios aislados (home, appdata, temp)
+    #[serde(default)]
+    pub notes: Option<String>, // Notas libres del usuario
+    #[serde(default)]
+    pub color: Option<String>, // Color para mostrar la instancia, "#rrggbb"
+    #[serde(default)]
+    pub appearanceHotReload: Option<bool>, // Recarga prelaunch_appearance.json al editarlo
+    #[serde(default)]
+    pub createdAt: Option<String>, // RFC 3339; None en instancias anteriores
+    #[serde(default)]
+    pub updatedAt: Option<String>, // RFC 3339; última edición desde update_instance
+    // URLs del protocolo asset de las imágenes propias; sólo para el frontend
+    #[serde(default, skip_deserializing)]
+    pub iconAssetUrl: Option<String>,
+    #[serde(default, skip_deserializing)]
+    pub bannerAssetUrl: Option<String>,
+    // Reparaciones hechas al cargar instance.json; sólo para el frontend
+    #[serde(default, skip_deserializing)]
+    pub validation: Option<InstanceValidationReport>,
 }
 
 impl MinecraftInstance {
@@ -42,6 +109,7 @@ impl MinecraftInstance {
 
     pub fn new() -> Self {
         Self {
+            schemaVersion: CURRENT_SCHEMA_VERSION,
             instanceId: String::new(),
             usesDefaultIcon: false,
             iconUrl: None,
@@ -54,69 +122,53 @@ impl MinecraftInstance {
             minecraftVersion: String::new(),
             instanceDirectory: None,
             forgeVersion: None,
+            optifineVersion: None,
+            customVersion: None,
             javaPath: None,
+            javaMajorVersion: None,
+            memory: None,
+            envVars: None,
+            wrapperCommand: None,
+            hooks: None,
+            acceptedNoticeVersion: None,
+            gameLanguage: None,
+            iconFile: None,
+            bannerFile: None,
+            optionalGroups: None,
+            fileRules: None,
+            resolution: None,
+            isolation: None,
+            notes: None,
+            color: None,
+            appearanceHotReload: None,
+            createdAt: Some(chrono::Utc::now().to_rfc3339()),
+            updatedAt: None,
+            iconAssetUrl: None,
+            bannerAssetUrl: None,
+            validation: None,
         }
     }
 
     pub fn from_instance_id(instance_id: &str) -> Option<Self> {
-        // Get the ConfigManager instance from the singleton
-        let config_manager_mutex = crate::utils::config_manager::get_config_manager();
-
-        // Lock the mutex to access the ConfigManager
-        let config_manager = match config_manager_mutex.lock() {
-            Ok(manager) => manager,
-            Err(e) => {
-                println!("Error locking ConfigManager mutex: {}", e);
-                return None;
-            }
-        };
-
-        // Get the instances directory from ConfigManager
-        // Since get_instances_dir() returns PathBuf directly, not Result<PathBuf, Error>
-        let instances_dir = config_manager.get_instances_dir();
-
         println!(
-            "Searching for instance {} in directory: {}",
-            instance_id,
-            instances_dir.display()
+            "Searching for instance {} in the instance roots",
+            instance_id
         );
 
-        // Try to read the instances directory
-        let dir_entries = match fs::read_dir(&instances_dir) {
-            Ok(entries) => entries,
-            Err(e) => {
-                println!("Error reading instances directory: {}", e);
-                return None;
-            }
-        };
-
         // Iterate through all directories looking for instance.json
-        for entry in dir_entries {
-            if let Ok(entry) = entry {
-                let path = entry.path();
-                if path.is_dir() {
-                    let config_file = path.join("instance.json");
-                    if config_file.exists() {
-                        // Try to read and parse the instance.json file
-                        if let Ok(content) = fs::read_to_string(&config_file) {
-                            if let Ok(mut instance) =
-                                serde_json::from_str::<MinecraftInstance>(&content)
-                            {
-                                // Check if this is the instance we're looking for
-                                if instance.instanceId == instance_id {
-                                    // Make sure instanceDirectory is set
-                                    if instance.instanceDirectory.is_none() {
-                                        let native_path_str = path.to_string_lossy().to_string();
-                                        let normalized_to_forward_slash =
-                                            native_path_str.replace("\\", "/"); // Reemplazar \ con /
-                                        instance.instanceDirectory =
-                                            Some(normalized_to_forward_slash);
-                                    }
-                                    println!("Found instance: {}", instance.instanceName);
-                                    return Some(instance);
-                                }
-                            }
+        for path in instance_dirs() {
+            let config_file = path.join("instance.json");
+            if config_file.exists() {
+                // Try to read and parse the instance.json file
+                if let Ok(mut instance) = instance_registry::load(&config_file) {
+                    // Check if this is the instance we're looking for
+                    if instance.instanceId == instance_id {
+                        // Make sure instanceDirectory is set
+                        if instance.instanceDirectory.is_none() {
+                            instance.instanceDirectory = Some(paths::stored_path(&path));
                         }
+                        println!("Found instance: {}", instance.instanceName);
+                        return Some(instance);
                     }
                 }
             }
@@ -132,36 +184,49 @@ impl MinecraftInstance {
             return None;
         }
 
-        match fs::read_to_string(config_file) {
-            Ok(content) => {
-                match serde_json::from_str::<MinecraftInstance>(&content) {
-                    Ok(mut instance) => {
-                        // Aseguramos que instanceDirectory sea una ruta válida
-                        // y que no esté vacía
-                        if instance.instanceDirectory.is_none() {
-                            let native_path_str = directory.to_string_lossy().to_string();
-                            let normalized_to_forward_slash = native_path_str.replace("\\", "/"); // Reemplazar \ con /
-                            instance.instanceDirectory = Some(normalized_to_forward_slash);
-                        }
-                        // Verificamos si la ruta de la instancia es válida
-                        if instance.instanceDirectory.is_none() {
-                            println!("Instance directory is not set or invalid.");
-                            return None;
-                        }
-                        Some(instance)
-                    }
-                    Err(_) => None,
+        match instance_registry::load(&config_file) {
+            Ok(mut instance) => {
+                // Aseguramos que instanceDirectory sea una ruta válida
+                // y que no esté vacía
+                if instance.instanceDirectory.is_none() {
+                    instance.instanceDirectory = Some(paths::stored_path(directory));
+                }
+                // Verificamos si la ruta de la instancia es válida
+                if instance.instanceDirectory.is_none() {
+                    println!("Instance directory is not set or invalid.");
+                    return None;
                 }
+                Some(instance)
             }
             Err(_) => None,
         }
     }
 
+    /// Marca la instancia como editada ahora
+    pub fn touch(&mut self) {
+        self.updatedAt = Some(chrono::Utc::now().to_rfc3339());
+    }
+
+    /// Writes `instance.json` to a temporary file and renames it over the
+    /// old one, so a crash or a concurrent reader never sees it truncated.
     pub fn save(&self) -> IoResult<()> {
         let config_file = Path::new(&self.instanceDirectory.as_ref().unwrap_or(&String::new()))
             .join("instance.json");
+
+        let lock = file_lock(&config_file);
+        let _guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+
+        self.write_to(&config_file)
+    }
+
+    /// `save` without taking the file's lock, for callers that hold it.
+    pub(crate) fn write_to(&self, config_file: &Path) -> IoResult<()> {
         let content = serde_json::to_string_pretty(self)?;
-        fs::write(config_file, content)
+        let temp_file = config_file.with_extension("json.tmp");
+        fs::write(&temp_file, content)?;
+        fs::rename(&temp_file, &config_file).inspect_err(|_| {
+            let _ = fs::remove_file(&temp_file);
+        })
     }
 
     pub fn delete(&self) -> IoResult<()> {
@@ -172,9 +237,17 @@ impl MinecraftInstance {
         }
     }
 
-    pub fn launch(&self) -> Result<(), String> {
-        let launcher = InstanceLauncher::new(self.clone());
-        launcher.launch_instance_async();
+    pub fn launch(&self, events: EventEmitter) -> Result<(), String> {
+        self.launch_with_options(events, LaunchOptions::default())
+    }
+
+    pub fn launch_with_options(
+        &self,
+        events: EventEmitter,
+        options: LaunchOptions,
+    ) -> Result<(), String> {
+        let launcher = InstanceLauncher::new(self.clone(), events).with_options(options);
+        launcher.launch_instance_async()?;
 
         println!(
             "[Tauri Command] Successfully initiated async launch for {}",
@@ -182,18 +255,34 @@ impl MinecraftInstance {
         );
         Ok(())
     }
+}
 
-    pub fn set_java_path(&mut self, java_path: PathBuf) {
-        self.javaPath = Some(java_path.to_string_lossy().to_string());
+#[tauri::command]
+/// Reads the `instance.json` in `directory`, applies `update` and writes it
+/// back, holding the file's lock from the read to the write so two edits
+/// of the same instance at once cannot overwrite each other. Nothing is
+/// written if `update` fails.
+pub fn with_locked<R>(
+    directory: &Path,
+    update: impl FnOnce(&mut MinecraftInstance) -> Result<R, String>,
+) -> Result<R, String> {
+    let config_file = directory.join("instance.json");
+    let lock = file_lock(&config_file);
+    let _guard = lock.lock().unwrap_or_else(|e| e.into_inner());
 
-        // Guardar la ruta de Java en el archivo de configuración
-        self.save().unwrap_or_else(|e| {
-            println!("Error saving Java path: {}", e);
-        });
+    let mut instance = instance_registry::load_locked(&config_file)?;
+    if instance.instanceDirectory.is_none() {
+        instance.instanceDirectory = Some(paths::stored_path(directory));
     }
+    let result = update(&mut instance)?;
+
+    instance
+        .write_to(&config_file)
+        .map_err(|e| format!("Failed to save instance: {}", e))?;
+    instance_registry::remember(&config_file, &instance);
+    Ok(result)
 }
 
-#[tauri::command]
 pub fn save_minecraft_instance(instance: MinecraftInstance) -> bool {
     instance.save().is_ok()
 }
@@ -208,28 +297,41 @@ pub fn revalidate_assets(instance: MinecraftInstance) -> Result<(), String> {
     Ok(())
 }
 
+/// Subdirectories of every configured instance root, plus the instances
+/// registered outside them.
+fn instance_dirs() -> Vec<PathBuf> {
+    let (roots, locations) = match crate::config::with_config(|config| {
+        (config.get_instance_roots(), config.get_instance_locations())
+    }) {
+        Ok(dirs) => dirs,
+        Err(e) => {
+            println!("Error loading config: {}", e);
+            return Vec::new();
+        }
+    };
+
+    roots
+        .iter()
+        .filter_map(|root| fs::read_dir(root).ok())
+        .flat_map(|entries| entries.flatten())
+        .map(|entry| entry.path())
+        .chain(locations)
+        .filter(|path| path.is_dir())
+        .collect()
+}
+
 #[tauri::command]
 pub fn get_instances_by_modpack_id(modpack_id: String) -> Vec<MinecraftInstance> {
     /*
         Gets all instances that match the given modpack ID
     */
-    let config_manager = crate::utils::config_manager::get_config_manager();
-    let instances_dir = config_manager.lock().unwrap().get_instances_dir();
-
     let mut instances = Vec::new();
-    if let Ok(entries) = fs::read_dir(instances_dir) {
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.is_dir() {
-                let config_file = path.join("instance.json");
-                if config_file.exists() {
-                    if let Ok(content) = fs::read_to_string(&config_file) {
-                        if let Ok(instance) = serde_json::from_str::<MinecraftInstance>(&content) {
-                            if instance.modpackId == Some(modpack_id.clone()) {
-                                instances.push(instance);
-                            }
-                        }
-                    }
+    for path in instance_dirs() {
+        let config_file = path.join("instance.json");
+        if config_file.exists() {
+            if let Ok(instance) = instance_registry::load(&config_file) {
+                if instance.modpackId == Some(modpack_id.clone()) {
+                    instances.push(instance);
                 }
             }
         }
@@ -245,11 +347,7 @@ pub fn open_game_dir(instance_id: String) -> Result<(), String> {
     );
     let instance = MinecraftInstance::from_instance_id(&instance_id);
     if let Some(instance) = instance {
-        let path = if cfg!(target_os = "windows") {
-            PathBuf::from(instance.minecraftPath.replace("/", "\\"))
-        } else {
-            PathBuf::from(instance.minecraftPath.replace("\\", "/"))
-        };
+        let path = native_game_dir(&instance);
         println!("[Tauri Command] Opening game directory: {}", path.display());
         if path.exists() {
             // Abre el directorio del juego con el programa predeterminado del sistema
@@ -264,3 +362,70 @@ pub fn open_game_dir(instance_id: String) -> Result<(), String> {
         Err("Instance not found".to_string())
     }
 }
+
+/// `minecraftPath` con los separadores del sistema actual
+fn native_game_dir(instance: &MinecraftInstance) -> PathBuf {
+    paths::native_path(&instance.minecraftPath)
+}
+
+/// Carpetas del juego que se pueden abrir desde la interfaz
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum InstanceSubfolder {
+    #[serde(rename = "mods")]
+    Mods,
+    #[serde(rename = "config")]
+    Config,
+    #[serde(rename = "saves")]
+    Saves,
+    #[serde(rename = "logs")]
+    Logs,
+    #[serde(rename = "crash-reports")]
+    CrashReports,
+    #[serde(rename = "resourcepacks")]
+    ResourcePacks,
+    #[serde(rename = "shaderpacks")]
+    ShaderPacks,
+    #[serde(rename = "screenshots")]
+    Screenshots,
+}
+
+impl InstanceSubfolder {
+    pub fn dir_name(self) -> &'static str {
+        match self {
+            Self::Mods => "mods",
+            Self::Config => "config",
+            Self::Saves => "saves",
+            Self::Logs => "logs",
+            Self::CrashReports => "crash-reports",
+            Self::ResourcePacks => "resourcepacks",
+            Self::ShaderPacks => "shaderpacks",
+            Self::Screenshots => "screenshots",
+        }
+    }
+}
+
+/// Opens one of the whitelisted folders inside the game directory,
+/// creating it first if the game hasn't yet.
+#[tauri::command]
+pub fn open_instance_subfolder(instance_id: String, kind: InstanceSubfolder) -> Result<(), String> {
+    let instance = MinecraftInstance::from_instance_id(&instance_id)
+        .ok_or_else(|| "Instance not found".to_string())?;
+
+    let game_dir = native_game_dir(&instance);
+    if !game_dir.exists() {
+        return Err("Game directory does not exist".to_string());
+    }
+
+    let path = game_dir.join(kind.dir_name());
+    fs::create_dir_all(&path)
+        .map_err(|e| format!("Error al crear la carpeta {}: {}", kind.dir_name(), e))?;
+
+    log::info!(
+        "[Tauri Command] Opening {} folder for instance {}: {}",
+        kind.dir_name(),
+        instance_id,
+        path.display()
+    );
+    tauri_plugin_opener::open_path(path, None::<&str>)
+        .map_err(|e| format!("Error opening {} folder: {}", kind.dir_name(), e))
+}
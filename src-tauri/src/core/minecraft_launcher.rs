@@ -1,18 +1,20 @@
-use crate::config::get_config_manager;
+use crate::config::read_config;
 use crate::core::accounts_manager::AccountsManager;
+use crate::core::minecraft::heap_limits;
+use crate::core::minecraft::manifest::{version_type, InheritanceChain};
+use crate::core::minecraft::path_encoding;
+use crate::core::minecraft::paths::{self, find_forge_version_id};
+use crate::core::minecraft::{ArgumentSanitizer, MavenCoordinate, RuleEvaluator};
 use crate::core::{minecraft_account::MinecraftAccount, minecraft_instance::MinecraftInstance};
 use crate::interfaces::game_launcher::GameLauncher;
 use serde_json::{Map, Value};
 use std::collections::HashSet;
 use std::collections::{BTreeMap, HashMap};
-use std::path::MAIN_SEPARATOR;
 use std::{
     fs,
-    io::{Read, Write},
     path::{Path, PathBuf},
     process::{Child, Command, Stdio},
 };
-use uuid::Uuid;
 
 pub struct MinecraftLauncher {
     instance: MinecraftInstance,
@@ -23,79 +25,6 @@ impl MinecraftLauncher {
         Self { instance }
     }
 
-    // Checks if a rule should apply based on OS, arch, and feature requirements
-    pub fn should_apply_rule(
-        &self,
-        rule: &Value,
-        features: Option<&HashMap<String, bool>>,
-    ) -> bool {
-        let action = rule
-            .get("action")
-            .and_then(|a| a.as_str())
-            .unwrap_or("allow");
-        let mut should_apply = action == "allow";
-
-        // Check OS rules
-        if let Some(os_obj) = rule.get("os") {
-            let mut os_match = true;
-
-            // Check OS name
-            if let Some(os_name) = os_obj.get("name").and_then(|n| n.as_str()) {
-                let is_current_os = match os_name {
-                    "windows" => cfg!(windows),
-                    "osx" => cfg!(target_os = "macos"),
-                    "linux" => cfg!(target_os = "linux"),
-                    _ => false,
-                };
-                if !is_current_os {
-                    os_match = false;
-                }
-            }
-
-            // Check OS architecture
-            if let Some(os_arch) = os_obj.get("arch").and_then(|a| a.as_str()) {
-                let is_current_arch = match os_arch {
-                    "x86" => cfg!(target_arch = "x86"),
-                    "x86_64" => cfg!(target_arch = "x86_64"),
-                    "arm" => cfg!(target_arch = "arm"),
-                    "arm64" => cfg!(target_arch = "aarch64"),
-                    _ => false,
-                };
-                if !is_current_arch {
-                    os_match = false;
-                }
-            }
-
-            if action == "allow" {
-                should_apply = os_match;
-            } else {
-                should_apply = !os_match;
-            }
-        }
-
-        // Check feature rules
-        if let Some(feature_obj) = rule.get("features") {
-            if let Some(features_map) = features {
-                for (feature_name, feature_value) in
-                    feature_obj.as_object().unwrap_or(&serde_json::Map::new())
-                {
-                    if let Some(expected_value) = feature_value.as_bool() {
-                        let actual_value = *features_map.get(feature_name).unwrap_or(&false);
-                        if actual_value != expected_value {
-                            should_apply = action != "allow";
-                            break;
-                        }
-                    }
-                }
-            } else {
-                // If feature rules exist but no features are provided, rule doesn't apply
-                should_apply = action != "allow";
-            }
-        }
-
-        should_apply
-    }
-
     // Process values from a rule or argument
     fn process_rule_values(
         &self,
@@ -148,21 +77,9 @@ impl MinecraftLauncher {
                 // If it's a complex rule-based argument
                 else if arg.is_object() {
                     // Check if rules allow this argument
-                    if let Some(rules) = arg.get("rules").and_then(|r| r.as_array()) {
-                        let mut should_include = false;
-
-                        for rule in rules {
-                            if self.should_apply_rule(rule, features) {
-                                should_include = true;
-                                break;
-                            }
-                        }
-
-                        if should_include {
-                            if let Some(value) = arg.get("value") {
-                                processed_args
-                                    .extend(self.process_rule_values(value, placeholders));
-                            }
+                    if RuleEvaluator::applies(arg, features) {
+                        if let Some(value) = arg.get("value") {
+                            processed_args.extend(self.process_rule_values(value, placeholders));
                         }
                     }
                 }
@@ -213,7 +130,10 @@ impl MinecraftLauncher {
             }
             .to_string(),
         );
-        placeholders.insert("version_type".to_string(), "release".to_string());
+        placeholders.insert(
+            "version_type".to_string(),
+            version_type(manifest_json).to_string(),
+        );
         placeholders.insert(
             "natives_directory".to_string(),
             natives_dir.to_string_lossy().to_string(),
@@ -298,6 +218,7 @@ impl MinecraftLauncher {
 
         // Base memory settings that should always be included
         let mut jvm_args = vec![format!("-Xms512M"), format!("-Xmx{}M", mc_memory)];
+        jvm_args.extend(path_encoding::encoding_jvm_args());
 
         // Check if there are JVM args in the manifest (modern format)
         if let Some(args_obj) = manifest_json.get("arguments").and_then(|v| v.get("jvm")) {
@@ -383,12 +304,7 @@ impl MinecraftLauncher {
         if let Some(libs) = manifest_json.get("libraries").and_then(|v| v.as_array()) {
             for lib in libs {
                 // Reglas de inclusión opcionales
-                let include = lib
-                    .get("rules")
-                    .and_then(|r| r.as_array())
-                    .map(|rules| rules.iter().any(|rule| self.should_apply_rule(rule, None)))
-                    .unwrap_or(true);
-                if !include {
+                if !RuleEvaluator::applies(lib, None) {
                     continue;
                 }
 
@@ -399,8 +315,7 @@ impl MinecraftLauncher {
                     .and_then(|a| a.get("path"))
                     .and_then(Value::as_str)
                 {
-                    let jar =
-                        libraries_dir.join(path_val.replace('/', &MAIN_SEPARATOR.to_string()));
+                    let jar = paths::join_relative(&libraries_dir, path_val);
                     add_if_new(&jar);
                 }
 
@@ -421,8 +336,7 @@ impl MinecraftLauncher {
                     };
                     if let Some(info) = classifiers.get(os_classifier) {
                         if let Some(path_val) = info.get("path").and_then(Value::as_str) {
-                            let native_jar = libraries_dir
-                                .join(path_val.replace('/', &MAIN_SEPARATOR.to_string()));
+                            let native_jar = paths::join_relative(&libraries_dir, path_val);
                             add_if_new(&native_jar);
                         }
                     }
@@ -436,8 +350,7 @@ impl MinecraftLauncher {
 
     // Load and merge vanilla and forge manifests if needed
     fn load_merged_manifest(&self, game_dir: &Path, minecraft_version: &str) -> Option<Value> {
-        let version_dir = game_dir.join("versions").join(minecraft_version);
-        let manifest_file = version_dir.join(format!("{}.json", minecraft_version));
+        let manifest_file = paths::version_file(game_dir, minecraft_version, "json");
 
         log::info!("Loading version manifest from {}", manifest_file.display());
 
@@ -458,36 +371,16 @@ impl MinecraftLauncher {
             }
         };
 
-        // Check if this is a modded instance that inherits from vanilla
-        if let Some(inherits_from) = manifest_json.get("inheritsFrom").and_then(|v| v.as_str()) {
-            println!("Found modded instance inheriting from {}", inherits_from);
-
-            // Load vanilla manifest
-            let vanilla_version_dir = game_dir.join("versions").join(inherits_from);
-            let vanilla_manifest_file = vanilla_version_dir.join(format!("{}.json", inherits_from));
-
-            let vanilla_manifest_data = match fs::read_to_string(&vanilla_manifest_file) {
-                Ok(content) => content,
-                Err(e) => {
-                    println!("Failed to read vanilla manifest file: {}", e);
-                    return Some(manifest_json); // Return modded manifest only if vanilla can't be found
-                }
-            };
-
-            let vanilla_manifest: Value = match serde_json::from_str(&vanilla_manifest_data) {
-                Ok(json) => json,
-                Err(e) => {
-                    println!("Failed to parse vanilla manifest JSON: {}", e);
-                    return Some(manifest_json); // Return modded manifest only if vanilla can't be parsed
-                }
-            };
-
-            // Merge manifests
-            return Some(self.merge_manifests(vanilla_manifest, manifest_json));
+        // Merge the whole inheritsFrom chain, root first
+        match InheritanceChain::resolve(game_dir, minecraft_version, manifest_json.clone()) {
+            Ok(chain) => {
+                Some(chain.merge_with(|parent, child| self.merge_manifests(parent, child)))
+            }
+            Err(e) => {
+                println!("Failed to resolve version inheritance: {}", e);
+                Some(manifest_json) // Return modded manifest only if the parents can't be loaded
+            }
         }
-
-        // Return the original manifest if it's not modded or doesn't inherit
-        Some(manifest_json)
     }
 
     /// Merge vanilla and forge manifests, ensuring arguments are combined correctly
@@ -503,20 +396,22 @@ impl MinecraftLauncher {
             Option<String>,
         )> {
             let name = lib.get("name")?.as_str()?.to_string();
-            let parts: Vec<&str> = name.split(':').collect();
-            let ga = if parts.len() >= 2 {
-                format!("{}:{}", parts[0], parts[1])
-            } else {
-                name.clone()
-            };
-            let version = parts.get(2).map(|s| s.to_string());
+            let coordinate = MavenCoordinate::parse(&name);
+            let ga = coordinate
+                .as_ref()
+                .map(|coordinate| format!("{}:{}", coordinate.group, coordinate.artifact))
+                .unwrap_or_else(|| name.clone());
+            let version = coordinate
+                .as_ref()
+                .map(|coordinate| coordinate.version.clone());
             let classifier = lib
                 .get("downloads")
                 .and_then(|d| d.get("artifact"))
                 .and_then(|a| a.get("classifier"))
                 .or_else(|| lib.get("classifier"))
                 .and_then(Value::as_str)
-                .map(String::from);
+                .map(String::from)
+                .or_else(|| coordinate.and_then(|coordinate| coordinate.classifier));
             let url = lib.get("url").and_then(Value::as_str).map(String::from);
             Some((name, ga, version, url, classifier))
         }
@@ -662,10 +557,7 @@ impl MinecraftLauncher {
         forge_version: Option<&str>,
     ) -> PathBuf {
         // For test, return vanilla client jar
-        let version_dir = game_dir
-            .join("versions")
-            .join(self.instance.minecraftVersion.clone());
-        let client_jar = version_dir.join(format!("{}.jar", self.instance.minecraftVersion));
+        let client_jar = paths::version_file(game_dir, &self.instance.minecraftVersion, "jar");
 
         return client_jar;
 
@@ -690,21 +582,22 @@ impl MinecraftLauncher {
             return client_jar;
         } else {
             // For vanilla, just use the standard path
-            let version_dir = game_dir.join("versions").join(minecraft_version);
-            return version_dir.join(format!("{}.jar", minecraft_version));
+            return paths::version_file(game_dir, minecraft_version, "jar");
         } */
     }
 }
 
 impl GameLauncher for MinecraftLauncher {
     fn launch(&self) -> Option<Child> {
-        let config_lock = get_config_manager()
-            .lock()
-            .expect("Failed to lock config manager mutex");
+        let config_lock = read_config();
 
-        let config = config_lock
-            .as_ref()
-            .expect("Config manager failed to initialize");
+        let config = match config_lock.as_ref() {
+            Ok(config) => config,
+            Err(e) => {
+                println!("Config manager failed to initialize: {}", e);
+                return None;
+            }
+        };
 
         let mc_memory = config.get_minecraft_memory().unwrap_or(2048); // Default to 2GB if not set
         println!("Minecraft memory: {}MB", mc_memory);
@@ -724,6 +617,7 @@ impl GameLauncher for MinecraftLauncher {
         .join(if cfg!(windows) { "javaw.exe" } else { "java" });
 
         println!("Java path: {}", java_path.display());
+        let mc_memory = heap_limits::guard_heap(&self.instance.instanceId, mc_memory, &java_path);
 
         let accounts_manager = AccountsManager::new();
 
@@ -739,13 +633,10 @@ impl GameLauncher for MinecraftLauncher {
         let account = match accounts_manager.get_minecraft_account_by_uuid(account_uuid) {
             Some(acct) => acct,
             None => {
+                // Sin caer en una cuenta offline aleatoria: InstanceLauncher
+                // valida la cuenta y decide si se juega sin conexión
                 println!("Account not found for UUID: {}", account_uuid);
-                MinecraftAccount::new(
-                    "offline".to_string(),
-                    Uuid::new_v4().to_string(),
-                    None,
-                    "offline".to_string(),
-                )
+                return None;
             }
         };
 
@@ -753,7 +644,7 @@ impl GameLauncher for MinecraftLauncher {
 
         // Get game directory
         let game_dir = match &self.instance.instanceDirectory {
-            Some(dir) => PathBuf::from(dir).join("minecraft"),
+            Some(dir) => paths::normalize(&paths::native_path(dir).join("minecraft")),
             None => PathBuf::from("default_path").join("minecraft"),
         };
 
@@ -765,38 +656,28 @@ impl GameLauncher for MinecraftLauncher {
         let mut minecraft_version = self.instance.minecraftVersion.clone();
 
         // Check if this is a Forge instance
-        let is_forge = self.instance.forgeVersion.is_some();
-        if is_forge {
-            println!("Detected Forge version: {:?}", self.instance.forgeVersion);
-
-            let launcher_profiles_file = game_dir.join("launcher_profiles.json");
-            // Get "forge" version from launcher_profiles.json
-
-            if launcher_profiles_file.exists() {
-                let mut file = fs::File::open(&launcher_profiles_file)
-                    .expect("Failed to open launcher_profiles.json");
-                let mut contents = String::new();
-                file.read_to_string(&mut contents)
-                    .expect("Failed to read launcher_profiles.json");
-
-                // Parse the JSON and extract the Forge version
-                let json: Value = serde_json::from_str(&contents).unwrap();
-                if let Some(profiles) = json.get("profiles") {
-                    if let Some(forge_profile) = profiles.get("forge") {
-                        if let Some(version) = forge_profile.get("lastVersionId") {
-                            minecraft_version = version
-                                .as_str()
-                                .unwrap_or(minecraft_version.as_str())
-                                .to_string();
-                        }
-                    }
-                }
+        if let Some(forge_version) = &self.instance.forgeVersion {
+            println!("Detected Forge version: {:?}", forge_version);
+
+            // Resolve the Forge version id from the instance itself; the
+            // "forge" profile of launcher_profiles.json is shared by every
+            // Forge install in the folder
+            match find_forge_version_id(
+                &paths::versions_dir(&game_dir),
+                &vanilla_mc_version,
+                forge_version,
+            ) {
+                Some(version_id) => minecraft_version = version_id,
+                None => println!(
+                    "No installed Forge version found for {} {}",
+                    vanilla_mc_version, forge_version
+                ),
             }
         }
 
-        let natives_dir = game_dir.join("natives").join(&vanilla_mc_version);
-        let libraries_dir = game_dir.join("libraries");
-        let assets_dir = game_dir.join("assets");
+        let natives_dir = paths::extracted_natives_dir(&game_dir, &vanilla_mc_version);
+        let libraries_dir = paths::libraries_dir(&game_dir);
+        let assets_dir = paths::assets_dir(&game_dir);
 
         println!("Launching Minecraft with the following directories:");
         println!("Libraries directory: {}", libraries_dir.display());
@@ -827,6 +708,13 @@ impl GameLauncher for MinecraftLauncher {
         println!("assets_dir: {}", assets_dir.display());
         println!("game_dir: {}", game_dir.display());
 
+        if !path_encoding::is_ascii_path(&natives_dir) {
+            log::warn!(
+                "[PathEncoding] Natives path has non-ASCII characters, the game may fail to load it: {}",
+                natives_dir.display()
+            );
+        }
+
         // Validate required files and directories
         for (desc, path) in &[
             ("Client JAR", &client_jar),
@@ -877,6 +765,10 @@ impl GameLauncher for MinecraftLauncher {
         let jvm_args =
             self.process_jvm_arguments(&manifest_json, &natives_dir, &classpath_str, mc_memory);
 
+        // Drop options repeated by the merged manifests
+        let jvm_args = ArgumentSanitizer::jvm(jvm_args, mc_memory, &natives_dir);
+        let game_args = ArgumentSanitizer::game(game_args);
+
         // Build command
         let mut command = Command::new(&java_path);
 
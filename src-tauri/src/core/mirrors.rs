@@ -0,0 +1,224 @@
+//! Download mirrors for the Mojang and Forge hosts.
+
+use crate::config::{with_config, ConfigManager};
+use crate::core::http_client;
+use crate::core::network_utilities::{probe_endpoint, EndpointHealth};
+use futures::future::join_all;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::Duration;
+use tauri_plugin_http::reqwest;
+
+/// Ajustes que obligan a recalcular los mirrors
+pub const MIRROR_CONFIG_KEYS: &[&str] = &[
+    "downloadMirror",
+    "mirrorMetaUrl",
+    "mirrorAssetsUrl",
+    "mirrorLibrariesUrl",
+    "mirrorForgeMavenUrl",
+];
+
+const BMCLAPI_ROOT: &str = "https://bmclapi2.bangbang93.com/";
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(8);
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum MirrorKind {
+    /// Manifiestos de versiones y client jar
+    Meta,
+    Assets,
+    Libraries,
+    ForgeMaven,
+}
+
+impl MirrorKind {
+    const ALL: [MirrorKind; 4] = [
+        MirrorKind::Meta,
+        MirrorKind::Assets,
+        MirrorKind::Libraries,
+        MirrorKind::ForgeMaven,
+    ];
+
+    /// Official roots the mirror replaces; the first one is used for health
+    /// checks.
+    fn official_roots(self) -> &'static [&'static str] {
+        match self {
+            MirrorKind::Meta => &[
+                "https://launchermeta.mojang.com/",
+                "https://piston-meta.mojang.com/",
+                "https://launcher.mojang.com/",
+                "https://piston-data.mojang.com/",
+            ],
+            MirrorKind::Assets => &["https://resources.download.minecraft.net/"],
+            MirrorKind::Libraries => &["https://libraries.minecraft.net/"],
+            // `files.minecraftforge.net/maven/` antes que la raíz, que la contiene
+            MirrorKind::ForgeMaven => &[
+                "https://maven.minecraftforge.net/",
+                "https://files.minecraftforge.net/maven/",
+                "https://files.minecraftforge.net/",
+            ],
+        }
+    }
+
+    fn bmclapi_root(self) -> String {
+        match self {
+            MirrorKind::Meta => BMCLAPI_ROOT.to_string(),
+            MirrorKind::Assets => format!("{}assets/", BMCLAPI_ROOT),
+            MirrorKind::Libraries | MirrorKind::ForgeMaven => format!("{}maven/", BMCLAPI_ROOT),
+        }
+    }
+
+    fn config_key(self) -> &'static str {
+        match self {
+            MirrorKind::Meta => "mirrorMetaUrl",
+            MirrorKind::Assets => "mirrorAssetsUrl",
+            MirrorKind::Libraries => "mirrorLibrariesUrl",
+            MirrorKind::ForgeMaven => "mirrorForgeMavenUrl",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            MirrorKind::Meta => "Versiones",
+            MirrorKind::Assets => "Assets",
+            MirrorKind::Libraries => "Librerías",
+            MirrorKind::ForgeMaven => "Forge",
+        }
+    }
+
+    /// Path below the root that answers on every host, for health checks.
+    fn probe_path(self) -> &'static str {
+        match self {
+            MirrorKind::Meta => "mc/game/version_manifest.json",
+            _ => "",
+        }
+    }
+}
+
+/// Raíz del mirror de cada tipo; los que no están usan el host oficial
+static ROOTS: Lazy<RwLock<HashMap<MirrorKind, String>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+
+fn with_trailing_slash(root: String) -> String {
+    if root.ends_with('/') {
+        root
+    } else {
+        format!("{}/", root)
+    }
+}
+
+/// Rebuilds the snapshot from `config`.
+pub fn reload(config: &ConfigManager) {
+    let mode = config.get_download_mirror();
+    let roots: HashMap<MirrorKind, String> = MirrorKind::ALL
+        .into_iter()
+        .filter_map(|kind| {
+            let root = match mode.as_str() {
+                "bmclapi" => Some(kind.bmclapi_root()),
+                "custom" => config.get_mirror_url(kind.config_key()),
+                _ => None,
+            }?;
+            Some((kind, with_trailing_slash(root)))
+        })
+        .collect();
+
+    log::info!(
+        "[Mirrors] Download mirror: {} ({} host(s) replaced)",
+        mode,
+        roots.len()
+    );
+    *ROOTS.write().unwrap_or_else(|e| e.into_inner()) = roots;
+}
+
+/// Loads the snapshot at startup.
+pub fn init() {
+    if let Err(e) = with_config(reload) {
+        log::warn!("[Mirrors] Config unavailable, using official hosts: {}", e);
+    }
+}
+
+/// `url` with its official root replaced by the configured mirror; other
+/// URLs are returned unchanged.
+pub fn rewrite(url: &str) -> String {
+    let roots = ROOTS.read().unwrap_or_else(|e| e.into_inner());
+    for (kind, mirror) in roots.iter() {
+        for official in kind.official_roots() {
+            if let Some(rest) = url.strip_prefix(official) {
+                return format!("{}{}", mirror, rest);
+            }
+        }
+    }
+    url.to_string()
+}
+
+/// GET `url` through its mirror. When the mirror doesn't answer or answers
+/// with an error status, the official URL is requested instead.
+pub fn get(
+    client: &reqwest::blocking::Client,
+    url: &str,
+) -> reqwest::Result<reqwest::blocking::Response> {
+    let mirrored = rewrite(url);
+    if mirrored == url {
+        return client.get(url).send();
+    }
+
+    match client
+        .get(&mirrored)
+        .send()
+        .and_then(|response| response.error_for_status())
+    {
+        Ok(response) => Ok(response),
+        Err(e) => {
+            log::warn!(
+                "[Mirrors] {} failed ({}), using the official host",
+                mirrored,
+                e
+            );
+            client.get(url).send()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MirrorHealth {
+    pub kind: MirrorKind,
+    pub official: EndpointHealth,
+    /// `None` si este tipo usa el host oficial
+    pub mirror: Option<EndpointHealth>,
+}
+
+/// Probes the official host and the configured mirror of every kind in
+/// parallel, so both latencies can be compared.
+#[tauri::command]
+pub async fn check_mirrors() -> Result<Vec<MirrorHealth>, String> {
+    let client = http_client::async_builder()
+        .timeout(PROBE_TIMEOUT)
+        .build()
+        .map_err(|e| format!("No se pudo crear el cliente HTTP: {}", e))?;
+    let roots = ROOTS.read().unwrap_or_else(|e| e.into_inner()).clone();
+
+    let checks = MirrorKind::ALL.into_iter().map(|kind| {
+        let client = &client;
+        let mirror_root = roots.get(&kind).cloned();
+        async move {
+            let official_url = format!("{}{}", kind.official_roots()[0], kind.probe_path());
+            let official = probe_endpoint(client, "official", kind.label(), &official_url).await;
+            let mirror = match mirror_root {
+                Some(root) => {
+                    let url = format!("{}{}", root, kind.probe_path());
+                    Some(probe_endpoint(client, "mirror", kind.label(), &url).await)
+                }
+                None => None,
+            };
+            MirrorHealth {
+                kind,
+                official,
+                mirror,
+            }
+        }
+    });
+    Ok(join_all(checks).await)
+}
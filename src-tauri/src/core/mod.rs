@@ -1,17 +1,73 @@
+pub mod account_avatar;
+pub mod account_validation;
 pub mod accounts_manager;
+pub mod accounts_store;
+pub mod api_client;
 pub mod auth;
+pub mod bandwidth;
+pub mod chunked_download;
+pub mod custom_version;
+pub mod download_cache;
+pub mod entitlement;
+pub mod events;
+pub mod file_rules;
 pub mod forge_launcher;
+pub mod forge_processors;
+pub mod game_language;
+pub mod hardware_info;
+pub mod http_client;
+pub mod i18n;
+pub mod inflight_downloads;
 pub mod instance_bootstrap;
+pub mod instance_disk_usage;
+pub mod instance_images;
+pub mod instance_import;
 pub mod instance_launcher;
+pub mod instance_locations;
 pub mod instance_manager;
+pub mod instance_readme;
+pub mod instance_registry;
+pub mod instance_schema;
+pub mod instance_state;
 pub mod java_manager;
+pub mod jvm_crash;
+pub mod launch_hooks;
+pub mod launch_plan;
+pub mod launch_queue;
+pub mod launcher_changelog;
+pub mod launcher_profiles;
+pub mod launcher_updater;
 pub mod microsoft_auth;
 pub mod minecraft;
 pub mod minecraft_account;
 pub mod minecraft_instance;
 pub mod minecraft_launcher;
+pub mod mirrors;
+pub mod mod_blocklist;
 pub mod models;
+pub mod modpack_updates;
+pub mod network_monitor;
 pub mod network_utilities;
+pub mod onboarding;
+pub mod optifine;
+pub mod optional_groups;
+pub mod parallel_hash;
+pub mod preflight;
 pub mod prelaunch_appearance;
+pub mod process_output;
+pub mod process_registry;
+pub mod server_pack;
+pub mod server_ping;
+pub mod session_keeper;
+pub mod session_logs;
+pub mod startup_watchdog;
+pub mod storage_cleanup;
+pub mod subprocess;
+pub mod system_resources;
 pub mod tasks_manager;
+pub mod telemetry;
+pub mod token_storage;
+pub mod transfer_stats;
 pub mod vanilla_launcher;
+pub mod version_catalog;
+pub mod zip_extract;
@@ -0,0 +1,387 @@
+//! Hash blocklist for mods and other downloaded game files.
+
+use crate::core::download_cache::{self, HashAlgo};
+use crate::core::http_client;
+use crate::core::instance_manager::get_all_instances;
+use crate::core::minecraft_instance::MinecraftInstance;
+use crate::API_ENDPOINT;
+use once_cell::sync::Lazy;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Cada cuánto se vuelve a pedir la lista durante una sesión
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Carpetas del juego que no contienen archivos de terceros
+const SKIPPED_DIRS: &[&str] = &["libraries", "versions", "assets", "natives", "logs"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockedHash {
+    pub hash: String,
+    /// Motivo publicado (p. ej. "fractureiser stage 0")
+    #[serde(default)]
+    pub reason: Option<String>,
+    /// Nombre con el que se distribuyó el archivo, si se conoce
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Blocklist {
+    entries: HashMap<String, BlockedHash>,
+}
+
+impl Blocklist {
+    /// Accepts `{ "hashes": [...] }` or a bare array; every item is either a
+    /// hash string or an object with `hash` (or `sha1`/`sha256`).
+    fn parse(data: &Value) -> Self {
+        let items = data
+            .get("hashes")
+            .or_else(|| data.get("data"))
+            .unwrap_or(data)
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+
+        let entries = items
+            .iter()
+            .filter_map(|item| {
+                let entry = match item {
+                    Value::String(hash) => BlockedHash {
+                        hash: hash.clone(),
+                        reason: None,
+                        name: None,
+                    },
+                    Value::Object(object) => BlockedHash {
+                        hash: ["hash", "sha1", "sha256"]
+                            .iter()
+                            .find_map(|field| object.get(*field).and_then(Value::as_str))?
+                            .to_string(),
+                        reason: object
+                            .get("reason")
+                            .and_then(Value::as_str)
+                            .map(str::to_string),
+                        name: object
+                            .get("name")
+                            .and_then(Value::as_str)
+                            .map(str::to_string),
+                    },
+                    _ => return None,
+                };
+                let hash = entry.hash.trim().to_lowercase();
+                HashAlgo::for_hash(&hash)?;
+                Some((hash.clone(), BlockedHash { hash, ..entry }))
+            })
+            .collect();
+
+        Self { entries }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn lookup(&self, hash: &str) -> Option<&BlockedHash> {
+        self.entries.get(&hash.trim().to_lowercase())
+    }
+
+    /// Hashes the file with the algorithms present in the list.
+    pub fn check_file(&self, path: &Path) -> Option<&BlockedHash> {
+        if self.is_empty() {
+            return None;
+        }
+        [HashAlgo::Sha1, HashAlgo::Sha256]
+            .into_iter()
+            .filter(|algo| {
+                self.entries
+                    .keys()
+                    .any(|hash| HashAlgo::for_hash(hash) == Some(*algo))
+            })
+            .find_map(|algo| self.lookup(&download_cache::file_hash(path, algo)?))
+    }
+
+    /// `check_file` for many files on the rayon pool, in the order of
+    /// `paths`.
+    pub fn check_files(&self, paths: &[PathBuf]) -> Vec<Option<BlockedHash>> {
+        if self.is_empty() {
+            return vec![None; paths.len()];
+        }
+        paths
+            .par_iter()
+            .map(|path| self.check_file(path).cloned())
+            .collect()
+    }
+}
+
+struct CachedBlocklist {
+    fetched_at: Instant,
+    blocklist: Blocklist,
+}
+
+static BLOCKLIST: Lazy<Mutex<Option<CachedBlocklist>>> = Lazy::new(|| Mutex::new(None));
+
+fn launcher_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("dev.alexitoo.modpackstore"))
+}
+
+fn disk_cache_path() -> Option<PathBuf> {
+    launcher_dir().map(|dir| dir.join("blocklist.json"))
+}
+
+pub fn quarantine_dir() -> Option<PathBuf> {
+    launcher_dir().map(|dir| dir.join("quarantine"))
+}
+
+fn fetch_remote() -> Result<Value, String> {
+    let client = http_client::blocking_builder()
+        .timeout(FETCH_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let response = client
+        .get(format!("{}/security/blocklist", API_ENDPOINT))
+        .send()
+        .map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("HTTP {}", response.status()));
+    }
+    response.json::<Value>().map_err(|e| e.to_string())
+}
+
+fn load_from_disk() -> Option<Value> {
+    let content = fs::read_to_string(disk_cache_path()?).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn save_to_disk(data: &Value) {
+    let Some(path) = disk_cache_path() else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    if let Err(e) = fs::write(&path, data.to_string()) {
+        log::warn!("[Blocklist] Could not cache blocklist: {}", e);
+    }
+}
+
+/// Current blocklist; fetched at most once per `REFRESH_INTERVAL`, falling
+/// back to the copy on disk when the API is unreachable.
+pub fn blocklist() -> Blocklist {
+    let mut cached = BLOCKLIST.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(cached) = cached
+        .as_ref()
+        .filter(|cached| cached.fetched_at.elapsed() < REFRESH_INTERVAL)
+    {
+        return cached.blocklist.clone();
+    }
+
+    let blocklist = match fetch_remote() {
+        Ok(data) => {
+            save_to_disk(&data);
+            Blocklist::parse(&data)
+        }
+        Err(e) => {
+            log::warn!("[Blocklist] Fetch failed, using cached copy: {}", e);
+            load_from_disk()
+                .map(|data| Blocklist::parse(&data))
+                .unwrap_or_default()
+        }
+    };
+    log::info!("[Blocklist] {} blocked hashes loaded", blocklist.len());
+
+    *cached = Some(CachedBlocklist {
+        fetched_at: Instant::now(),
+        blocklist: blocklist.clone(),
+    });
+    blocklist
+}
+
+/// Moves a blocked file out of the instance. Returns its new location.
+pub fn quarantine_file(instance_id: &str, path: &Path) -> Result<PathBuf, String> {
+    let dir = quarantine_dir()
+        .ok_or("No se pudo determinar la carpeta de cuarentena")?
+        .join(instance_id);
+    fs::create_dir_all(&dir)
+        .map_err(|e| format!("Error al crear la carpeta de cuarentena: {}", e))?;
+
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "file".to_string());
+    let target = dir.join(format!(
+        "{}-{}.quarantined",
+        chrono::Utc::now().format("%Y%m%d%H%M%S"),
+        file_name
+    ));
+
+    // rename falla entre unidades distintas; copiar y borrar en ese caso
+    if fs::rename(path, &target).is_err() {
+        fs::copy(path, &target)
+            .and_then(|_| fs::remove_file(path))
+            .map_err(|e| format!("Error al mover {} a cuarentena: {}", path.display(), e))?;
+    }
+    log::warn!(
+        "[Blocklist] Quarantined {} -> {}",
+        path.display(),
+        target.display()
+    );
+    Ok(target)
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockedFile {
+    pub instance_id: String,
+    pub instance_name: String,
+    /// Ruta relativa a la carpeta del juego
+    pub path: String,
+    pub hash: String,
+    pub reason: Option<String>,
+    pub name: Option<String>,
+    /// Dónde quedó el archivo si se movió a cuarentena
+    pub quarantined_to: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlocklistScanReport {
+    pub blocklist_size: usize,
+    pub scanned_instances: usize,
+    pub scanned_files: usize,
+    pub blocked: Vec<BlockedFile>,
+    /// Instancias o archivos que no se pudieron revisar
+    pub errors: Vec<String>,
+}
+
+fn collect_files(dir: &Path, root: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            let skipped = dir == root
+                && path
+                    .file_name()
+                    .is_some_and(|name| SKIPPED_DIRS.contains(&name.to_string_lossy().as_ref()));
+            if !skipped {
+                collect_files(&path, root, files);
+            }
+        } else if path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("jar") || ext.eq_ignore_ascii_case("zip"))
+        {
+            files.push(path);
+        }
+    }
+}
+
+fn instance_game_dir(instance: &MinecraftInstance) -> Option<PathBuf> {
+    instance
+        .instanceDirectory
+        .as_ref()
+        .map(|dir| Path::new(dir).join("minecraft"))
+}
+
+fn scan_instance(
+    instance: &MinecraftInstance,
+    blocklist: &Blocklist,
+    quarantine: bool,
+    report: &mut BlocklistScanReport,
+) {
+    let Some(game_dir) = instance_game_dir(instance).filter(|dir| dir.exists()) else {
+        report.errors.push(format!(
+            "{}: no se encontró la carpeta del juego",
+            instance.instanceName
+        ));
+        return;
+    };
+
+    let mut files = Vec::new();
+    collect_files(&game_dir, &game_dir, &mut files);
+    report.scanned_instances += 1;
+    report.scanned_files += files.len();
+
+    for file in files {
+        let Some(entry) = blocklist.check_file(&file) else {
+            continue;
+        };
+        let relative = file
+            .strip_prefix(&game_dir)
+            .unwrap_or(&file)
+            .to_string_lossy()
+            .replace('\\', "/");
+
+        let quarantined_to = if quarantine {
+            match quarantine_file(&instance.instanceId, &file) {
+                Ok(target) => Some(target.to_string_lossy().to_string()),
+                Err(e) => {
+                    report.errors.push(e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        report.blocked.push(BlockedFile {
+            instance_id: instance.instanceId.clone(),
+            instance_name: instance.instanceName.clone(),
+            path: relative,
+            hash: entry.hash.clone(),
+            reason: entry.reason.clone(),
+            name: entry.name.clone(),
+            quarantined_to,
+        });
+    }
+}
+
+/// Checks every `.jar`/`.zip` of every instance (outside libraries,
+/// versions and assets) against the blocklist. With `quarantine` the
+/// blocked files are also moved out of the instances.
+#[tauri::command]
+pub async fn scan_instances_for_blocked_files(
+    quarantine: Option<bool>,
+) -> Result<BlocklistScanReport, String> {
+    let quarantine = quarantine.unwrap_or(false);
+    let instances = get_all_instances()?;
+
+    tokio::task::spawn_blocking(move || {
+        let blocklist = blocklist();
+        let mut report = BlocklistScanReport {
+            blocklist_size: blocklist.len(),
+            ..Default::default()
+        };
+        if blocklist.is_empty() {
+            report.errors.push(
+                "La lista de archivos bloqueados está vacía o no se pudo descargar".to_string(),
+            );
+            return report;
+        }
+
+        for instance in &instances {
+            scan_instance(instance, &blocklist, quarantine, &mut report);
+        }
+        if !report.blocked.is_empty() {
+            log::warn!(
+                "[Blocklist] Scan found {} blocked file(s)",
+                report.blocked.len()
+            );
+        }
+        report
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))
+}
@@ -1,5 +1,11 @@
 // src-tauri/src/core/models.rs
-use serde::{Deserialize, Serialize};
+use crate::core::optional_groups::OptionalGroup;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ModpackInfo {
@@ -23,3 +29,243 @@ pub struct MinecraftInstance {
     pub instanceDirectory: Option<String>,
     pub forgeVersion: Option<String>,
 }
+
+/// Manifiesto del modpack guardado en el directorio de la instancia
+pub const MODPACK_MANIFEST_FILE: &str = "modpack_manifest.json";
+
+/// Newest `schemaVersion` of the manifest this launcher knows. Newer
+/// manifests are still read: unknown fields are kept in `extra` and
+/// malformed entries are skipped instead of rejecting the whole manifest.
+pub const MANIFEST_SCHEMA_VERSION: u32 = 1;
+
+/// Modpack Store manifest of one modpack version.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ModpackManifest {
+    /// Ausente en los manifiestos anteriores a que existiera
+    #[serde(default, deserialize_with = "lenient")]
+    pub schema_version: Option<u32>,
+    #[serde(default, deserialize_with = "lenient_list")]
+    pub files: Vec<ManifestFile>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub loader: Option<ManifestLoader>,
+    #[serde(default, deserialize_with = "lenient_list")]
+    pub optional_groups: Vec<OptionalGroup>,
+    /// Globs (same syntax as `FileRules`) the modpack installs once and
+    /// never overwrites nor deletes afterwards, e.g. `options.txt`
+    #[serde(default, deserialize_with = "lenient_list")]
+    pub protected_paths: Vec<String>,
+    #[serde(default, deserialize_with = "lenient_list")]
+    pub servers: Vec<ManifestServer>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub requirements: Option<ManifestRequirements>,
+    /// Formato antiguo: requisitos en la raíz del manifiesto
+    #[serde(default, deserialize_with = "lenient")]
+    pub min_memory: Option<u32>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub recommended_memory: Option<u32>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub default_language: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub prelaunch_appearance: Option<AppearanceReference>,
+    /// Se valida al leerlo como `RequiredNotice`
+    #[serde(default)]
+    pub required_notice: Option<Value>,
+    /// Campos de versiones más nuevas del formato
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+/// A file the modpack installs, relative to the instance's `minecraft`
+/// folder.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestFile {
+    pub path: String,
+    #[serde(default, deserialize_with = "lenient")]
+    pub hash: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub size: Option<u64>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub url: Option<String>,
+    /// Grupo opcional al que pertenece; sin grupo siempre se instala
+    #[serde(default, deserialize_with = "lenient")]
+    pub group: Option<String>,
+    /// `client`, `server` o `both`
+    #[serde(default, deserialize_with = "lenient")]
+    pub side: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub client_only: Option<bool>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub version: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestLoader {
+    /// `forge`, `neoforge`, `fabric`, `quilt`...
+    #[serde(alias = "type")]
+    pub kind: String,
+    pub version: String,
+    #[serde(default)]
+    pub minecraft_version: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestServer {
+    pub name: String,
+    pub address: String,
+    #[serde(default)]
+    pub icon: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestRequirements {
+    #[serde(default, deserialize_with = "lenient")]
+    pub min_memory: Option<u32>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub recommended_memory: Option<u32>,
+}
+
+/// Where the modpack's `prelaunch_appearance.json` comes from.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AppearanceReference {
+    pub url: String,
+    #[serde(default)]
+    pub hash: Option<String>,
+}
+
+/// Deserializes an optional field, treating a value of the wrong type as
+/// absent.
+fn lenient<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let value = Value::deserialize(deserializer)?;
+    if value.is_null() {
+        return Ok(None);
+    }
+    match serde_json::from_value(value) {
+        Ok(value) => Ok(Some(value)),
+        Err(e) => {
+            log::warn!("[Manifest] Ignoring invalid field: {}", e);
+            Ok(None)
+        }
+    }
+}
+
+/// Deserializes a list, skipping the entries that don't fit `T`.
+fn lenient_list<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let entries = match Value::deserialize(deserializer)? {
+        Value::Array(entries) => entries,
+        Value::Null => return Ok(Vec::new()),
+        _ => {
+            log::warn!("[Manifest] Ignoring a field that should be a list");
+            return Ok(Vec::new());
+        }
+    };
+    Ok(entries
+        .into_iter()
+        .filter_map(|entry| match serde_json::from_value(entry) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                log::warn!("[Manifest] Skipping invalid entry: {}", e);
+                None
+            }
+        })
+        .collect())
+}
+
+impl ModpackManifest {
+    /// Parses a manifest, dropping the file entries that point outside the
+    /// instance.
+    pub fn from_value(value: Value) -> Result<Self, String> {
+        if !value.is_object() {
+            return Err("El manifiesto del modpack no es un objeto".to_string());
+        }
+        let mut manifest: Self = serde_json::from_value(value)
+            .map_err(|e| format!("Failed to parse modpack manifest: {}", e))?;
+
+        if manifest
+            .schema_version
+            .is_some_and(|version| version > MANIFEST_SCHEMA_VERSION)
+        {
+            log::warn!(
+                "[Manifest] Schema version {:?} is newer than {}, unknown fields are ignored",
+                manifest.schema_version,
+                MANIFEST_SCHEMA_VERSION
+            );
+        }
+        manifest.files.retain(|file| match file.validate() {
+            Ok(()) => true,
+            Err(e) => {
+                log::warn!("[Manifest] Skipping file entry: {}", e);
+                false
+            }
+        });
+        Ok(manifest)
+    }
+
+    pub fn from_slice(content: &[u8]) -> Result<Self, String> {
+        let value = serde_json::from_slice(content)
+            .map_err(|e| format!("Failed to parse modpack manifest: {}", e))?;
+        Self::from_value(value)
+    }
+
+    /// Reads `modpack_manifest.json` from the instance directory. `Ok(None)`
+    /// when the instance has no manifest (not a modpack instance).
+    pub fn read(instance_dir: &Path) -> Result<Option<Self>, String> {
+        let path = instance_dir.join(MODPACK_MANIFEST_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content =
+            fs::read(&path).map_err(|e| format!("Failed to read modpack manifest: {}", e))?;
+        Self::from_slice(&content).map(Some)
+    }
+
+    /// `requirements`, or the fields at the root in older manifests.
+    pub fn memory_requirements(&self) -> ManifestRequirements {
+        let section = self.requirements.clone().unwrap_or_default();
+        ManifestRequirements {
+            min_memory: section.min_memory.or(self.min_memory),
+            recommended_memory: section.recommended_memory.or(self.recommended_memory),
+        }
+    }
+}
+
+impl ManifestFile {
+    /// The path must stay inside the `minecraft` folder.
+    fn validate(&self) -> Result<(), String> {
+        let path = self.path.replace('\\', "/");
+        if path.trim().is_empty() {
+            return Err("empty path".to_string());
+        }
+        if path.starts_with('/') || path.split('/').next().is_some_and(|s| s.ends_with(':')) {
+            return Err(format!("absolute path {}", self.path));
+        }
+        if path.split('/').any(|segment| segment == "..") {
+            return Err(format!("path {} leaves the instance", self.path));
+        }
+        Ok(())
+    }
+
+    pub fn is_client_only(&self) -> bool {
+        self.side.as_deref() == Some("client") || self.client_only == Some(true)
+    }
+
+    /// Name of the file, without its folders.
+    pub fn file_name(&self) -> &str {
+        self.path.rsplit(['/', '\\']).next().unwrap_or(&self.path)
+    }
+}
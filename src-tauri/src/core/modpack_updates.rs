@@ -0,0 +1,472 @@
+//! Modpack update selection, preview and installation per instance.
+
+use crate::core::api_client::ApiClient;
+use crate::core::download_cache;
+use crate::core::events::EventEmitter;
+use crate::core::file_rules::FileRules;
+use crate::core::instance_bootstrap::InstanceBootstrap;
+use crate::core::instance_manager::{get_instance_by_id, update_instance_by_id};
+use crate::core::minecraft::paths;
+use crate::core::minecraft_instance::{MinecraftInstance, ModpackInfo};
+use crate::core::models::{ManifestFile, ModpackManifest, MODPACK_MANIFEST_FILE};
+use crate::core::optional_groups;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    Beta,
+    Alpha,
+}
+
+impl UpdateChannel {
+    fn parse(channel: &str) -> Result<Self, String> {
+        match channel {
+            "stable" => Ok(UpdateChannel::Stable),
+            "beta" => Ok(UpdateChannel::Beta),
+            "alpha" => Ok(UpdateChannel::Alpha),
+            other => Err(format!("Canal de actualización desconocido: {}", other)),
+        }
+    }
+
+    /// Beta también recibe estables, y alpha recibe todo
+    fn accepts(self, version_channel: UpdateChannel) -> bool {
+        version_channel <= self
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteVersion {
+    pub id: String,
+    pub version: String,
+    #[serde(default)]
+    pub channel: UpdateChannel,
+    #[serde(default)]
+    pub released_at: Option<String>,
+}
+
+async fn fetch_versions(modpack_id: &str) -> Result<Vec<RemoteVersion>, String> {
+    ApiClient::from_global()?
+        .get_public(&format!("/explore/modpack/{}/versions", modpack_id))
+        .await?
+        .error_for_status()
+        .map_err(|e| format!("Error al consultar las versiones del modpack: {}", e))?
+        .json::<Vec<RemoteVersion>>()
+        .await
+        .map_err(|e| format!("Respuesta inválida de versiones del modpack: {}", e))
+}
+
+fn is_installed(info: &ModpackInfo, version: &RemoteVersion) -> bool {
+    info.modpackVersionId.as_deref() == Some(version.id.as_str())
+        || info.version.as_deref() == Some(version.version.as_str())
+}
+
+/// Version the instance should be on: the pinned one, or the newest one
+/// allowed by its channel.
+fn select_target(info: &ModpackInfo, mut versions: Vec<RemoteVersion>) -> Option<RemoteVersion> {
+    if let Some(pinned) = info.pinnedVersionId.as_deref() {
+        return versions.into_iter().find(|version| version.id == pinned);
+    }
+
+    let channel = info.updateChannel.unwrap_or_default();
+    // Fechas RFC 3339: el orden lexicográfico es el cronológico
+    versions.sort_by(|a, b| b.released_at.cmp(&a.released_at));
+    versions
+        .into_iter()
+        .find(|version| channel.accepts(version.channel))
+}
+
+/// Newest version the instance should move to, or `None` if it is up to
+/// date (or not a modpack instance).
+pub async fn check_for_update(
+    instance: &MinecraftInstance,
+) -> Result<Option<RemoteVersion>, String> {
+    let (Some(modpack_id), Some(info)) = (&instance.modpackId, &instance.modpackInfo) else {
+        return Ok(None);
+    };
+
+    let versions = fetch_versions(modpack_id).await?;
+    let Some(target) = select_target(info, versions) else {
+        if let Some(pinned) = &info.pinnedVersionId {
+            log::warn!(
+                "[Updates] Pinned version {} of {} is no longer published",
+                pinned,
+                modpack_id
+            );
+        }
+        return Ok(None);
+    };
+
+    Ok((!is_installed(info, &target)).then_some(target))
+}
+
+fn update_modpack_info(
+    instance_id: String,
+    change: impl FnOnce(&mut ModpackInfo),
+) -> Result<MinecraftInstance, String> {
+    update_instance_by_id(&instance_id, |instance| {
+        let info = instance
+            .modpackInfo
+            .as_mut()
+            .ok_or_else(|| "La instancia no pertenece a un modpack".to_string())?;
+        change(info);
+        Ok(instance.clone())
+    })
+}
+
+/// Subscribes the instance to `stable`, `beta` or `alpha` releases.
+#[tauri::command]
+pub fn set_instance_update_channel(
+    instance_id: String,
+    channel: String,
+) -> Result<MinecraftInstance, String> {
+    let channel = UpdateChannel::parse(&channel)?;
+    update_modpack_info(instance_id, |info| info.updateChannel = Some(channel))
+}
+
+/// Pins the instance to `version_id`; `None` goes back to following the
+/// channel.
+#[tauri::command]
+pub fn pin_instance_version(
+    instance_id: String,
+    version_id: Option<String>,
+) -> Result<MinecraftInstance, String> {
+    update_modpack_info(instance_id, |info| {
+        info.pinnedVersionId = version_id.filter(|id| !id.trim().is_empty())
+    })
+}
+
+/// Version the instance would update to, if any.
+#[tauri::command]
+pub async fn check_instance_update(instance_id: String) -> Result<Option<RemoteVersion>, String> {
+    let instance = get_instance_by_id(instance_id.clone())?
+        .ok_or_else(|| format!("Instance with ID {} not found", instance_id))?;
+    check_for_update(&instance).await
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FileChangeKind {
+    Add,
+    Replace,
+    Delete,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FileChange {
+    /// Relativa a la carpeta `minecraft` de la instancia
+    pub path: String,
+    pub kind: FileChangeKind,
+    /// Tamaño del archivo nuevo, si el manifiesto lo indica
+    pub size: Option<u64>,
+    /// Se recupera de la caché de descargas en lugar de descargarse
+    pub cached: bool,
+}
+
+/// A mod added (`from_version` empty), removed (`to_version` empty) or
+/// moved to another version.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModChange {
+    pub name: String,
+    pub from_version: Option<String>,
+    pub to_version: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdatePreview {
+    pub current_version: Option<String>,
+    pub target: RemoteVersion,
+    pub files: Vec<FileChange>,
+    /// Bytes a descargar, sin contar lo que ya está en la caché
+    pub download_bytes: u64,
+    /// Archivos a descargar cuyo tamaño no indica el manifiesto
+    pub unknown_size_files: usize,
+    pub mods: Vec<ModChange>,
+}
+
+async fn fetch_manifest(modpack_id: &str, version_id: &str) -> Result<ModpackManifest, String> {
+    ApiClient::from_global()?
+        .get_public(&format!(
+            "/explore/modpack/{}/versions/{}/manifest",
+            modpack_id, version_id
+        ))
+        .await?
+        .error_for_status()
+        .map_err(|e| format!("Error al obtener el manifiesto de la versión: {}", e))?
+        .json::<Value>()
+        .await
+        .map_err(|e| format!("Manifiesto de la versión inválido: {}", e))
+        .and_then(ModpackManifest::from_value)
+}
+
+/// Name and version of a mod entry. Without `name`/`version` in the
+/// manifest they come from the file name (`jei-1.20.1-15.2.0.jar`).
+fn mod_identity(entry: &ManifestFile) -> Option<(String, Option<String>)> {
+    let file_name = entry.path.strip_prefix("mods/")?;
+    let stem = file_name
+        .strip_suffix(".jar")
+        .or_else(|| file_name.strip_suffix(".zip"))
+        .unwrap_or(file_name);
+
+    // La versión empieza en el primer guion seguido de un dígito
+    let split = stem
+        .char_indices()
+        .find(|(index, c)| *c == '-' && stem[index + 1..].starts_with(|c: char| c.is_ascii_digit()))
+        .map(|(index, _)| index);
+    let (file_mod, file_version) = match split {
+        Some(index) => (&stem[..index], Some(stem[index + 1..].to_string())),
+        None => (stem, None),
+    };
+
+    let name = entry.name.as_deref().unwrap_or(file_mod).to_string();
+    let version = entry.version.clone().or(file_version);
+    Some((name, version))
+}
+
+fn mod_changes(old_files: &[&ManifestFile], new_files: &[&ManifestFile]) -> Vec<ModChange> {
+    let index = |files: &[&ManifestFile]| -> HashMap<String, Option<String>> {
+        files
+            .iter()
+            .filter_map(|entry| mod_identity(entry))
+            .collect()
+    };
+    let old_mods = index(old_files);
+    let new_mods = index(new_files);
+
+    let mut changes: Vec<ModChange> = new_mods
+        .iter()
+        .filter(|(name, version)| old_mods.get(*name) != Some(*version))
+        .map(|(name, version)| ModChange {
+            name: name.clone(),
+            from_version: old_mods.get(name).cloned().flatten(),
+            to_version: version.clone(),
+        })
+        .chain(
+            old_mods
+                .iter()
+                .filter(|(name, _)| !new_mods.contains_key(*name))
+                .map(|(name, version)| ModChange {
+                    name: name.clone(),
+                    from_version: version.clone(),
+                    to_version: None,
+                }),
+        )
+        .collect();
+    changes.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+    changes
+}
+
+/// Files of `manifest` the instance installs: neither in an optional group
+/// the player didn't pick nor excluded by their rules.
+fn installed_files<'a>(
+    instance: &MinecraftInstance,
+    manifest: &'a ModpackManifest,
+    rules: &FileRules,
+) -> Vec<&'a ManifestFile> {
+    let selection =
+        optional_groups::reconcile(instance.optionalGroups.as_ref(), &manifest.optional_groups);
+    manifest
+        .files
+        .iter()
+        .filter(|entry| optional_groups::is_file_selected(entry, &selection))
+        .filter(|entry| !rules.is_excluded(&entry.path))
+        .collect()
+}
+
+/// Files to add, replace or delete in `minecraft_dir` to go from
+/// `old_files` to `new_files`. Only sizes are checked on disk: the update
+/// itself verifies hashes. Preserved files are never replaced or deleted.
+fn file_changes(
+    minecraft_dir: &Path,
+    rules: &FileRules,
+    old_files: &[&ManifestFile],
+    new_files: &[&ManifestFile],
+) -> Vec<FileChange> {
+    let old_by_path: HashMap<&str, &ManifestFile> = old_files
+        .iter()
+        .map(|entry| (entry.path.as_str(), *entry))
+        .collect();
+    let mut changes = Vec::new();
+
+    for entry in new_files {
+        let path = entry.path.as_str();
+        let hash = entry.hash.as_deref();
+        let size = entry.size;
+
+        let kind = match fs::metadata(minecraft_dir.join(path)) {
+            Err(_) => Some(FileChangeKind::Add),
+            Ok(_) if rules.is_preserved(path) => None,
+            Ok(metadata) => {
+                let size_changed = size.is_some_and(|size| size != metadata.len());
+                let hash_changed = old_by_path
+                    .get(path)
+                    .is_some_and(|old| old.hash.as_deref() != hash);
+                (size_changed || hash_changed).then_some(FileChangeKind::Replace)
+            }
+        };
+        if let Some(kind) = kind {
+            changes.push(FileChange {
+                path: path.to_string(),
+                kind,
+                size,
+                cached: hash.is_some_and(download_cache::contains),
+            });
+        }
+    }
+
+    let new_paths: HashSet<&str> = new_files.iter().map(|entry| entry.path.as_str()).collect();
+    for (path, entry) in &old_by_path {
+        if !new_paths.contains(path)
+            && !rules.is_preserved(path)
+            && minecraft_dir.join(path).exists()
+        {
+            changes.push(FileChange {
+                path: path.to_string(),
+                kind: FileChangeKind::Delete,
+                size: entry.size,
+                cached: false,
+            });
+        }
+    }
+
+    changes.sort_by(|a, b| a.path.cmp(&b.path));
+    changes
+}
+
+/// What updating the instance would change, or `None` if it is up to date.
+/// Nothing is downloaded to disk nor modified.
+#[tauri::command]
+pub async fn preview_modpack_update(instance_id: String) -> Result<Option<UpdatePreview>, String> {
+    let instance = get_instance_by_id(instance_id.clone())?
+        .ok_or_else(|| format!("Instance with ID {} not found", instance_id))?;
+    let Some(target) = check_for_update(&instance).await? else {
+        return Ok(None);
+    };
+    let (Some(modpack_id), Some(info)) = (&instance.modpackId, &instance.modpackInfo) else {
+        return Ok(None);
+    };
+
+    let new_manifest = fetch_manifest(modpack_id, &target.id).await?;
+    let instance_dir = Path::new(instance.instanceDirectory.as_deref().unwrap_or(""));
+    // Sin manifiesto local se compara sólo con lo que hay en disco
+    let old_manifest = ModpackManifest::read(instance_dir)
+        .ok()
+        .flatten()
+        .unwrap_or_default();
+
+    let rules = FileRules::for_manifest(&instance, &new_manifest);
+    let new_files = installed_files(&instance, &new_manifest, &rules);
+    let old_files: Vec<&ManifestFile> = old_manifest.files.iter().collect();
+
+    let files = file_changes(
+        &instance_dir.join("minecraft"),
+        &rules,
+        &old_files,
+        &new_files,
+    );
+    let downloads = files
+        .iter()
+        .filter(|change| change.kind != FileChangeKind::Delete && !change.cached);
+    let download_bytes = downloads.clone().filter_map(|change| change.size).sum();
+    let unknown_size_files = downloads.filter(|change| change.size.is_none()).count();
+
+    Ok(Some(UpdatePreview {
+        current_version: info.version.clone(),
+        target,
+        files,
+        download_bytes,
+        unknown_size_files,
+        mods: mod_changes(&old_files, &new_files),
+    }))
+}
+
+fn write_manifest(instance_dir: &Path, manifest: &ModpackManifest) -> Result<(), String> {
+    let path = instance_dir.join(MODPACK_MANIFEST_FILE);
+    let content = serde_json::to_string_pretty(manifest)
+        .map_err(|e| format!("Error al serializar el manifiesto: {}", e))?;
+    let temp_file = path.with_extension("json.tmp");
+    fs::write(&temp_file, content)
+        .and_then(|_| fs::rename(&temp_file, &path))
+        .map_err(|e| {
+            let _ = fs::remove_file(&temp_file);
+            format!("Error al guardar {}: {}", path.display(), e)
+        })
+}
+
+/// Moves the instance to `target`: saves the version's manifest, deletes
+/// the files it dropped, downloads the new and changed ones and records the
+/// version in instance.json. The version is only recorded when every file
+/// was applied, so a failed update is offered again.
+pub async fn apply_update(
+    events: EventEmitter,
+    instance: &MinecraftInstance,
+    target: &RemoteVersion,
+) -> Result<MinecraftInstance, String> {
+    let modpack_id = instance
+        .modpackId
+        .as_deref()
+        .ok_or_else(|| "La instancia no pertenece a un modpack".to_string())?;
+    let instance_dir = Path::new(
+        instance
+            .instanceDirectory
+            .as_deref()
+            .ok_or_else(|| "La instancia no tiene directorio".to_string())?,
+    );
+
+    let new_manifest = fetch_manifest(modpack_id, &target.id).await?;
+    let old_manifest = ModpackManifest::read(instance_dir)
+        .ok()
+        .flatten()
+        .unwrap_or_default();
+
+    // Lo que la nueva versión ya no incluye se borra (salvo lo que se conserva)
+    let minecraft_dir = instance_dir.join("minecraft");
+    let rules = FileRules::for_manifest(instance, &new_manifest);
+    let old_files: Vec<&ManifestFile> = old_manifest.files.iter().collect();
+    // Los mismos archivos que cuenta la vista previa
+    let new_files = installed_files(instance, &new_manifest, &rules);
+    for change in file_changes(&minecraft_dir, &rules, &old_files, &new_files) {
+        if change.kind != FileChangeKind::Delete {
+            continue;
+        }
+        let path = paths::join_relative(&minecraft_dir, &change.path);
+        fs::remove_file(&path)
+            .map_err(|e| format!("No se pudo borrar {}: {}", path.display(), e))?;
+        log::info!(
+            "[Updates] Removed {} (dropped by the new version)",
+            change.path
+        );
+    }
+
+    write_manifest(instance_dir, &new_manifest)?;
+
+    let missing = InstanceBootstrap::new(events).sync_modpack_files(instance, None, None)?;
+    if missing > 0 {
+        return Err(format!(
+            "No se pudieron aplicar {} archivo(s) de la versión {}",
+            missing, target.version
+        ));
+    }
+
+    let updated = update_modpack_info(instance.instanceId.clone(), |info| {
+        info.version = Some(target.version.clone());
+        info.modpackVersionId = Some(target.id.clone());
+    })?;
+    log::info!(
+        "[Updates] Instance {} is now on version {} of {}",
+        instance.instanceId,
+        target.version,
+        modpack_id
+    );
+    Ok(updated)
+}
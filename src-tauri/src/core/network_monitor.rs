@@ -0,0 +1,151 @@
+//! Background connectivity monitor.
+
+use crate::core::events::{self, LauncherEvent};
+use crate::core::http_client;
+use crate::core::tasks_manager::{self, CancellationToken};
+use crate::API_ENDPOINT;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::time::Duration;
+
+const ONLINE_INTERVAL: Duration = Duration::from_secs(30);
+const OFFLINE_INTERVAL: Duration = Duration::from_secs(5);
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+/// Fallos seguidos antes de considerar que no hay conexión (evita parpadeos)
+const FAILURES_BEFORE_OFFLINE: u32 = 2;
+/// Cada cuánto mira una descarga en pausa si su tarea se canceló
+const CANCEL_CHECK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// URLs externas que se prueban además de la API
+const PROBE_URLS: &[&str] = &["https://launchermeta.mojang.com/mc/game/version_manifest.json"];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkStatus {
+    pub online: bool,
+    /// RFC 3339 del último cambio de estado
+    pub changed_at: Option<String>,
+    /// RFC 3339 de la última comprobación
+    pub last_checked_at: Option<String>,
+}
+
+// Se asume conexión hasta la primera comprobación para no frenar el arranque
+static ONLINE: AtomicBool = AtomicBool::new(true);
+
+static STATUS: Lazy<Mutex<NetworkStatus>> = Lazy::new(|| {
+    Mutex::new(NetworkStatus {
+        online: true,
+        changed_at: None,
+        last_checked_at: None,
+    })
+});
+
+/// Despierta a las descargas en pausa al volver la conexión
+static RECONNECTED: Lazy<(Mutex<()>, Condvar)> = Lazy::new(|| (Mutex::new(()), Condvar::new()));
+
+pub fn is_online() -> bool {
+    ONLINE.load(Ordering::SeqCst)
+}
+
+/// Blocks the calling thread while the launcher is offline, so a download
+/// in progress pauses instead of failing. Returns `false` if `cancel` fires
+/// before the connection comes back.
+pub fn wait_until_online(cancel: Option<&CancellationToken>) -> bool {
+    let (lock, cvar) = &*RECONNECTED;
+    let mut guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+    while !is_online() {
+        if cancel.is_some_and(|token| token.is_cancelled()) {
+            return false;
+        }
+        guard = cvar
+            .wait_timeout(guard, CANCEL_CHECK_INTERVAL)
+            .unwrap_or_else(|e| e.into_inner())
+            .0;
+    }
+    true
+}
+
+/// Any HTTP response counts: a 5xx still means the network works.
+async fn probe() -> bool {
+    let client = match http_client::async_builder().timeout(PROBE_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => {
+            log::warn!("[NetworkMonitor] Could not build HTTP client: {}", e);
+            return true;
+        }
+    };
+
+    let api_ping = format!("{}/ping", API_ENDPOINT);
+    for url in std::iter::once(api_ping.as_str()).chain(PROBE_URLS.iter().copied()) {
+        if client.head(url).send().await.is_ok() {
+            return true;
+        }
+    }
+    false
+}
+
+fn record(online: bool) {
+    let now = chrono::Utc::now().to_rfc3339();
+    let changed = {
+        let mut status = STATUS.lock().unwrap_or_else(|e| e.into_inner());
+        status.last_checked_at = Some(now.clone());
+        if status.online == online {
+            None
+        } else {
+            status.online = online;
+            status.changed_at = Some(now);
+            Some(status.clone())
+        }
+    };
+
+    let Some(status) = changed else {
+        return;
+    };
+    {
+        // Con el candado tomado ninguna descarga queda entre comprobar y esperar
+        let (lock, cvar) = &*RECONNECTED;
+        let _guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+        ONLINE.store(online, Ordering::SeqCst);
+        cvar.notify_all();
+    }
+    if online {
+        log::info!("[NetworkMonitor] Connection restored, resuming downloads and queued tasks");
+    } else {
+        log::warn!("[NetworkMonitor] Connection lost, pausing downloads and queued tasks");
+    }
+
+    events::emit(LauncherEvent::NetworkStatusChanged(status));
+    tasks_manager::wake_queue();
+}
+
+/// Spawns the monitor loop. Call once from the app setup.
+pub fn start() {
+    tauri::async_runtime::spawn(async move {
+        let mut failures = 0;
+        loop {
+            if probe().await {
+                failures = 0;
+                record(true);
+            } else {
+                failures += 1;
+                if failures >= FAILURES_BEFORE_OFFLINE {
+                    record(false);
+                }
+            }
+
+            let interval = if failures > 0 {
+                OFFLINE_INTERVAL
+            } else {
+                ONLINE_INTERVAL
+            };
+            tokio::time::sleep(interval).await;
+        }
+    });
+}
+
+#[tauri::command]
+pub fn get_network_status() -> NetworkStatus {
+    STATUS.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
@@ -1,7 +1,134 @@
 // src/core/NetworkUtilities.rs
+use crate::core::http_client;
+use crate::API_ENDPOINT;
+use futures::future::join_all;
+use serde::Serialize;
+use std::time::{Duration, Instant};
 use tauri_plugin_http::reqwest;
 
-use crate::API_ENDPOINT;
+const ENDPOINT_TIMEOUT: Duration = Duration::from_secs(8);
+
+/// Hosts the launcher depends on: (id, label, url)
+const HEALTH_ENDPOINTS: &[(&str, &str, &str)] = &[
+    (
+        "mojangMeta",
+        "Metadatos de Mojang",
+        "https://launchermeta.mojang.com/mc/game/version_manifest.json",
+    ),
+    (
+        "minecraftLibraries",
+        "Librerías de Minecraft",
+        "https://libraries.minecraft.net/",
+    ),
+    (
+        "minecraftResources",
+        "Assets de Minecraft",
+        "https://resources.download.minecraft.net/",
+    ),
+    (
+        "forgeMaven",
+        "Maven de Forge",
+        "https://maven.minecraftforge.net/",
+    ),
+    // Referencia para distinguir un host bloqueado de no tener Internet
+    ("internet", "Internet", "https://www.google.com"),
+];
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum EndpointFailure {
+    Timeout,
+    /// DNS, conexión rechazada o cortada
+    Connect,
+    Tls,
+    Other,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EndpointHealth {
+    pub id: String,
+    pub label: String,
+    pub url: String,
+    /// Cualquier respuesta HTTP cuenta, incluso un 4xx/5xx
+    pub reachable: bool,
+    pub status_code: Option<u16>,
+    pub latency_ms: u64,
+    pub failure: Option<EndpointFailure>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ConnectionDiagnosis {
+    Healthy,
+    /// Ningún host responde: no hay conexión
+    NoInternet,
+    /// Hay conexión pero algunos hosts no responden (firewall, DNS, caída)
+    SomeUnreachable,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionReport {
+    pub diagnosis: ConnectionDiagnosis,
+    pub endpoints: Vec<EndpointHealth>,
+    pub checked_at: String,
+}
+
+fn classify(error: &reqwest::Error) -> EndpointFailure {
+    if error.is_timeout() {
+        return EndpointFailure::Timeout;
+    }
+    // reqwest no expone los errores TLS por separado
+    let mut source = std::error::Error::source(error);
+    while let Some(cause) = source {
+        let text = cause.to_string().to_lowercase();
+        if text.contains("certificate") || text.contains("tls") || text.contains("ssl") {
+            return EndpointFailure::Tls;
+        }
+        source = cause.source();
+    }
+    if error.is_connect() {
+        EndpointFailure::Connect
+    } else {
+        EndpointFailure::Other
+    }
+}
+
+pub(crate) async fn probe_endpoint(
+    client: &reqwest::Client,
+    id: &str,
+    label: &str,
+    url: &str,
+) -> EndpointHealth {
+    let started = Instant::now();
+    let result = client.head(url).send().await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    let mut health = EndpointHealth {
+        id: id.to_string(),
+        label: label.to_string(),
+        url: url.to_string(),
+        reachable: false,
+        status_code: None,
+        latency_ms,
+        failure: None,
+        error: None,
+    };
+    match result {
+        Ok(response) => {
+            health.reachable = true;
+            health.status_code = Some(response.status().as_u16());
+        }
+        Err(e) => {
+            log::warn!("[Network] {} unreachable: {}", url, e);
+            health.failure = Some(classify(&e));
+            health.error = Some(e.to_string());
+        }
+    }
+    health
+}
 #[tauri::command]
 pub async fn check_connection() -> bool {
     // Usando tokio para el retardo asíncrono
@@ -10,7 +137,7 @@ pub async fn check_connection() -> bool {
     // Attempt to ping the API endpoint using async reqwest
     let api_url = format!("{}/ping", API_ENDPOINT);
 
-    match reqwest::get(&api_url).await {
+    match http_client::async_client().get(&api_url).send().await {
         Ok(resp) => resp.status().is_success(),
         Err(_) => false,
     }
@@ -25,7 +152,7 @@ pub fn check_real_connection() -> bool {
     // This is used internally, for example at the moment of
     // downloading assets (This uses official Minecraft Servers)
 
-    let client = reqwest::blocking::Client::builder()
+    let client = http_client::blocking_builder()
         .timeout(std::time::Duration::from_secs(5))
         .build();
 
@@ -39,3 +166,40 @@ pub fn check_real_connection() -> bool {
     }
     false
 }
+
+/// Probes the API and every host the launcher downloads from in parallel,
+/// with the latency and the kind of failure of each one.
+#[tauri::command]
+pub async fn check_connection_details() -> Result<ConnectionReport, String> {
+    let client = http_client::async_builder()
+        .timeout(ENDPOINT_TIMEOUT)
+        .build()
+        .map_err(|e| format!("No se pudo crear el cliente HTTP: {}", e))?;
+
+    let api_ping = format!("{}/ping", API_ENDPOINT);
+    let endpoints = std::iter::once(("api", "API de Modpack Store", api_ping.as_str()))
+        .chain(HEALTH_ENDPOINTS.iter().copied());
+    let endpoints =
+        join_all(endpoints.map(|(id, label, url)| probe_endpoint(&client, id, label, url))).await;
+
+    let reachable = endpoints.iter().filter(|e| e.reachable).count();
+    let diagnosis = if reachable == endpoints.len() {
+        ConnectionDiagnosis::Healthy
+    } else if reachable == 0 {
+        ConnectionDiagnosis::NoInternet
+    } else {
+        ConnectionDiagnosis::SomeUnreachable
+    };
+    log::info!(
+        "[Network] Connection check: {:?} ({}/{} endpoints reachable)",
+        diagnosis,
+        reachable,
+        endpoints.len()
+    );
+
+    Ok(ConnectionReport {
+        diagnosis,
+        endpoints,
+        checked_at: chrono::Utc::now().to_rfc3339(),
+    })
+}
@@ -0,0 +1,240 @@
+//! First-run setup flow.
+
+use crate::config::{read_config, write_config, ConfigManager};
+use crate::core::instance_import::{detect_instances, ExternalInstance};
+use crate::core::instance_manager::get_all_instances;
+use crate::core::preflight::free_disk_space_mb;
+use crate::core::system_resources::{recommend_memory, ModpackMemoryRequirements, SystemMemory};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Clave interna donde se guardan los pasos completados
+const COMPLETED_STEPS_KEY: &str = "onboardingCompletedSteps";
+/// Espacio libre a partir del cual no avisamos (MB); un modpack mediano ocupa 1-3 GB
+const RECOMMENDED_FREE_SPACE_MB: u64 = 10 * 1024;
+/// Tamaño de modpack que se asume para la memoria por defecto
+const TYPICAL_MODPACK_MODS: usize = 100;
+/// Paso del ajuste `memory` en el esquema
+const MEMORY_STEP_MB: u32 = 256;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum OnboardingStep {
+    InstancesDirectory,
+    ImportInstances,
+    Memory,
+}
+
+impl OnboardingStep {
+    pub const ALL: [OnboardingStep; 3] = [
+        OnboardingStep::InstancesDirectory,
+        OnboardingStep::ImportInstances,
+        OnboardingStep::Memory,
+    ];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::InstancesDirectory => "instancesDirectory",
+            Self::ImportInstances => "importInstances",
+            Self::Memory => "memory",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|step| step.as_str() == value)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstancesDirSuggestion {
+    pub path: String,
+    pub exists: bool,
+    /// `None` si no se pudo determinar el disco
+    pub free_space_mb: Option<u64>,
+    pub low_space: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemorySuggestion {
+    pub recommended_mb: u32,
+    pub max_safe_mb: u32,
+    pub system: SystemMemory,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OnboardingState {
+    /// Primera ejecución: nada completado y sin instancias
+    pub is_first_run: bool,
+    pub completed: bool,
+    /// Paso que la interfaz debe mostrar; `None` al terminar
+    pub current_step: Option<OnboardingStep>,
+    pub completed_steps: Vec<OnboardingStep>,
+    pub instances_dir: InstancesDirSuggestion,
+    /// Sólo se buscan mientras el paso de importación está pendiente
+    pub external_instances: Vec<ExternalInstance>,
+    pub memory: MemorySuggestion,
+}
+
+fn completed_steps(config: &ConfigManager) -> Vec<OnboardingStep> {
+    config
+        .get(COMPLETED_STEPS_KEY)
+        .and_then(Value::as_array)
+        .map(|steps| {
+            steps
+                .iter()
+                .filter_map(Value::as_str)
+                .filter_map(OnboardingStep::parse)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn next_step(completed: &[OnboardingStep]) -> Option<OnboardingStep> {
+    OnboardingStep::ALL
+        .into_iter()
+        .find(|step| !completed.contains(step))
+}
+
+fn suggest_instances_dir(path: PathBuf) -> InstancesDirSuggestion {
+    // Si la carpeta aún no existe medimos el primer ancestro existente
+    let existing = path
+        .ancestors()
+        .find(|p| p.exists())
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| path.clone());
+    let free_space_mb = free_disk_space_mb(&existing);
+
+    InstancesDirSuggestion {
+        path: path.to_string_lossy().to_string(),
+        exists: path.is_dir(),
+        free_space_mb,
+        low_space: free_space_mb.is_some_and(|free| free < RECOMMENDED_FREE_SPACE_MB),
+    }
+}
+
+fn suggest_memory() -> MemorySuggestion {
+    let requirements = ModpackMemoryRequirements {
+        mod_count: TYPICAL_MODPACK_MODS,
+        ..Default::default()
+    };
+    let recommendation = recommend_memory(&SystemMemory::detect(), &requirements);
+
+    MemorySuggestion {
+        recommended_mb: recommendation.recommended_mb / MEMORY_STEP_MB * MEMORY_STEP_MB,
+        max_safe_mb: recommendation.max_safe_mb,
+        system: recommendation.system,
+    }
+}
+
+fn build_state() -> Result<OnboardingState, String> {
+    let (completed_steps, instances_dir) = {
+        let config_lock = read_config();
+        let config = config_lock.as_ref().map_err(|e| e.clone())?;
+        (completed_steps(config), config.get_instances_dir())
+    };
+
+    let current_step = next_step(&completed_steps);
+    let is_first_run = completed_steps.is_empty() && get_all_instances()?.is_empty();
+
+    // Quien actualiza con instancias ya creadas no tiene que pasar por el asistente
+    let completed = current_step.is_none() || (completed_steps.is_empty() && !is_first_run);
+    let current_step = if completed { None } else { current_step };
+
+    let external_instances =
+        if current_step.is_some() && !completed_steps.contains(&OnboardingStep::ImportInstances) {
+            detect_instances()
+        } else {
+            Vec::new()
+        };
+
+    Ok(OnboardingState {
+        is_first_run,
+        completed,
+        current_step,
+        completed_steps,
+        instances_dir: suggest_instances_dir(instances_dir),
+        external_instances,
+        memory: suggest_memory(),
+    })
+}
+
+#[tauri::command]
+pub fn get_onboarding_state() -> Result<OnboardingState, String> {
+    build_state()
+}
+
+/// Applies the result of a step and returns the new state. Steps must be
+/// completed in order; `value` depends on the step:
+///
+/// - `instancesDirectory`: the chosen path (string), or null for the
+///   suggested one.
+/// - `importInstances`: ignored; the imports themselves go through
+///   `import_external_instance`.
+/// - `memory`: `{ "autoMemory": bool, "memory": number }`, both optional,
+///   or null to accept the recommendation.
+#[tauri::command]
+pub fn complete_onboarding_step(
+    step: OnboardingStep,
+    value: Option<Value>,
+) -> Result<OnboardingState, String> {
+    let memory = suggest_memory();
+
+    {
+        let mut config_lock = write_config();
+        let config = config_lock.as_mut().map_err(|e| e.clone())?;
+
+        let mut completed = completed_steps(config);
+        if next_step(&completed) != Some(step) {
+            return Err(format!(
+                "El paso {} no es el paso actual del asistente",
+                step.as_str()
+            ));
+        }
+
+        match step {
+            OnboardingStep::InstancesDirectory => {
+                let path = match value.as_ref().and_then(Value::as_str).map(str::trim) {
+                    Some(path) if !path.is_empty() => path.to_string(),
+                    _ => config.get_instances_dir().to_string_lossy().to_string(),
+                };
+                config
+                    .set("instancesDir", &path)
+                    .map_err(|e| format!("Error de validación: {}", e))?;
+                fs::create_dir_all(config.get_instances_dir())
+                    .map_err(|e| format!("Error al crear la carpeta de instancias: {}", e))?;
+            }
+            OnboardingStep::ImportInstances => {}
+            OnboardingStep::Memory => {
+                let value = value.unwrap_or(Value::Null);
+                let auto_memory = value
+                    .get("autoMemory")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false);
+                let memory_mb = value
+                    .get("memory")
+                    .and_then(Value::as_u64)
+                    .map(|v| v as u32)
+                    .unwrap_or(memory.recommended_mb);
+                config
+                    .set("autoMemory", auto_memory)
+                    .and_then(|_| config.set("memory", memory_mb))
+                    .map_err(|e| format!("Error de validación: {}", e))?;
+            }
+        }
+
+        completed.push(step);
+        let completed: Vec<&str> = completed.iter().map(|step| step.as_str()).collect();
+        config
+            .set(COMPLETED_STEPS_KEY, completed)
+            .map_err(|e| format!("Error de validación: {}", e))?;
+        config.save()?;
+    }
+
+    log::info!("[Onboarding] Completed step {}", step.as_str());
+    build_state()
+}
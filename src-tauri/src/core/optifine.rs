@@ -0,0 +1,326 @@
+//! OptiFine on vanilla and Forge instances.
+
+use crate::config::read_config;
+use crate::core::http_client;
+use crate::core::instance_manager::{get_instance_by_id, update_instance_by_id};
+use crate::core::minecraft::paths::{self, MinecraftPaths};
+use crate::core::minecraft::MavenCoordinate;
+use crate::core::minecraft_instance::MinecraftInstance;
+use crate::core::mirrors;
+use crate::core::subprocess;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const TWEAK_CLASS: &str = "optifine.OptiFineTweaker";
+const LAUNCH_CLASS: &str = "net.minecraft.launchwrapper.Launch";
+/// LaunchWrapper de Mojang, para builds que no traen la suya
+const LEGACY_LAUNCHWRAPPER: &str = "net.minecraft:launchwrapper:1.12";
+const MOJANG_LIBRARIES: &str = "https://libraries.minecraft.net/";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OptiFineInstall {
+    pub instance_id: String,
+    pub minecraft_version: String,
+    /// p. ej. `HD_U_I6`
+    pub edition: String,
+    /// Versión registrada en `versions/` (instancias vanilla)
+    pub version_id: Option<String>,
+    /// Jar copiado a `mods/` (instancias Forge)
+    pub mod_file: Option<String>,
+}
+
+/// Minecraft version and edition from the installer's file name
+/// (`OptiFine_1.20.1_HD_U_I6.jar`, `preview_OptiFine_1.20.4_HD_U_I7_pre3.jar`).
+fn parse_installer_name(installer: &Path) -> Option<(String, String)> {
+    let stem = installer.file_stem()?.to_str()?;
+    let stem = stem.strip_prefix("preview_").unwrap_or(stem);
+    let rest = stem.strip_prefix("OptiFine_")?;
+    let (minecraft_version, edition) = rest.split_once("_HD_")?;
+    Some((minecraft_version.to_string(), format!("HD_{}", edition)))
+}
+
+/// Id of the version JSON OptiFine registers for `minecraft_version`.
+pub fn version_id(minecraft_version: &str, edition: &str) -> String {
+    format!("{}-OptiFine_{}", minecraft_version, edition)
+}
+
+fn open_installer(installer: &Path) -> Result<zip::ZipArchive<fs::File>, String> {
+    let file = fs::File::open(installer)
+        .map_err(|e| format!("No se pudo abrir el instalador de OptiFine: {}", e))?;
+    zip::ZipArchive::new(file).map_err(|e| format!("Instalador de OptiFine inválido: {}", e))
+}
+
+fn read_entry(installer: &Path, entry: &str) -> Option<Vec<u8>> {
+    let mut archive = open_installer(installer).ok()?;
+    let mut file = archive.by_name(entry).ok()?;
+    let mut content = Vec::new();
+    file.read_to_end(&mut content).ok()?;
+    Some(content)
+}
+
+fn write_file(target: &Path, content: &[u8]) -> Result<(), String> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Error al crear directorio: {}", e))?;
+    }
+    fs::write(target, content).map_err(|e| format!("Error al escribir {}: {}", target.display(), e))
+}
+
+fn library_path(libraries_dir: &Path, coordinate: &MavenCoordinate) -> PathBuf {
+    paths::join_relative(libraries_dir, &coordinate.url_path())
+}
+
+/// Builds the OptiFine library from the client jar. Old builds don't ship
+/// `optifine.Patcher`; for those the installer jar itself is the library.
+fn build_library(
+    java_path: &Path,
+    installer: &Path,
+    client_jar: &Path,
+    target: &Path,
+) -> Result<(), String> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Error al crear directorio: {}", e))?;
+    }
+
+    if read_entry(installer, "optifine/Patcher.class").is_none() {
+        log::info!("[OptiFine] No Patcher in installer, copying it as the library");
+        fs::copy(installer, target).map_err(|e| format!("Error al copiar OptiFine: {}", e))?;
+        return Ok(());
+    }
+
+    log::info!(
+        "[OptiFine] Patching {} into {}",
+        client_jar.display(),
+        target.display()
+    );
+    let output = subprocess::background_process(
+        Command::new(java_path)
+            .arg("-cp")
+            .arg(installer)
+            .arg("optifine.Patcher")
+            .arg(client_jar)
+            .arg(installer)
+            .arg(target),
+    )
+    .output()
+    .map_err(|e| format!("Error al ejecutar el instalador de OptiFine: {}", e))?;
+
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        log::info!("[OptiFine] {}", line);
+    }
+    if !output.status.success() || !target.is_file() {
+        let _ = fs::remove_file(target);
+        return Err(format!(
+            "El instalador de OptiFine falló: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+/// Installs the LaunchWrapper OptiFine needs and returns its library entry:
+/// the `launchwrapper-of` bundled in recent installers, or Mojang's.
+fn install_launchwrapper(installer: &Path, libraries_dir: &Path) -> Result<Value, String> {
+    let bundled = read_entry(installer, "launchwrapper-of.txt")
+        .map(|content| String::from_utf8_lossy(&content).trim().to_string())
+        .filter(|version| !version.is_empty());
+
+    if let Some(version) = bundled {
+        let coordinate = MavenCoordinate::parse(&format!("optifine:launchwrapper-of:{}", version))
+            .ok_or_else(|| format!("Versión de launchwrapper-of inválida: {}", version))?;
+        let jar = read_entry(installer, &format!("launchwrapper-of-{}.jar", version))
+            .ok_or_else(|| format!("launchwrapper-of-{}.jar no encontrado", version))?;
+        write_file(&library_path(libraries_dir, &coordinate), &jar)?;
+        return Ok(json!({ "name": coordinate.to_string() }));
+    }
+
+    let coordinate = MavenCoordinate::parse(LEGACY_LAUNCHWRAPPER)
+        .ok_or_else(|| "Coordenada de LaunchWrapper inválida".to_string())?;
+    let target = library_path(libraries_dir, &coordinate);
+    if !target.is_file() {
+        let url = format!("{}{}", MOJANG_LIBRARIES, coordinate.url_path());
+        log::info!("[OptiFine] Downloading LaunchWrapper from {}", url);
+        let mut response = mirrors::get(&http_client::blocking_client(), &url)
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| format!("Error al descargar LaunchWrapper: {}", e))?;
+        let mut content = Vec::new();
+        io::copy(&mut response, &mut content)
+            .map_err(|e| format!("Error al descargar LaunchWrapper: {}", e))?;
+        write_file(&target, &content)?;
+    }
+    Ok(json!({ "name": coordinate.to_string(), "url": MOJANG_LIBRARIES }))
+}
+
+/// Version JSON inheriting from vanilla. Legacy versions get the tweaker in
+/// `minecraftArguments`, modern ones in `arguments.game`.
+fn version_json(
+    id: &str,
+    minecraft_version: &str,
+    vanilla: &Value,
+    libraries: Vec<Value>,
+) -> Value {
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut manifest = json!({
+        "id": id,
+        "inheritsFrom": minecraft_version,
+        "type": "release",
+        "time": now,
+        "releaseTime": now,
+        "mainClass": LAUNCH_CLASS,
+        "libraries": libraries,
+    });
+
+    match vanilla.get("minecraftArguments").and_then(Value::as_str) {
+        Some(arguments) => {
+            manifest["minecraftArguments"] =
+                Value::String(format!("{} --tweakClass {}", arguments, TWEAK_CLASS));
+        }
+        None => {
+            manifest["arguments"] = json!({ "game": ["--tweakClass", TWEAK_CLASS] });
+        }
+    }
+    manifest
+}
+
+fn install_as_version(
+    instance: &MinecraftInstance,
+    installer: &Path,
+    edition: &str,
+) -> Result<String, String> {
+    let paths = {
+        let config_manager = read_config();
+        let config = config_manager.as_ref().map_err(|e| e.clone())?;
+        MinecraftPaths::new(instance, config)
+            .ok_or_else(|| "No se pudieron resolver las rutas de la instancia".to_string())?
+    };
+
+    let client_jar = paths.client_jar();
+    let vanilla_file = paths::version_file(paths.game_dir(), &instance.minecraftVersion, "json");
+    if !client_jar.is_file() || !vanilla_file.is_file() {
+        return Err("Instala la instancia antes de añadir OptiFine".to_string());
+    }
+    let vanilla: Value = fs::read_to_string(&vanilla_file)
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .ok_or_else(|| "No se pudo leer el manifiesto de la versión".to_string())?;
+
+    let libraries_dir = paths.libraries_dir();
+    let optifine = MavenCoordinate::parse(&format!(
+        "optifine:OptiFine:{}_{}",
+        instance.minecraftVersion, edition
+    ))
+    .ok_or_else(|| format!("Versión de OptiFine inválida: {}", edition))?;
+    build_library(
+        paths.java_path(),
+        installer,
+        &client_jar,
+        &library_path(&libraries_dir, &optifine),
+    )?;
+    let launchwrapper = install_launchwrapper(installer, &libraries_dir)?;
+
+    let id = version_id(&instance.minecraftVersion, edition);
+    let manifest = version_json(
+        &id,
+        &instance.minecraftVersion,
+        &vanilla,
+        vec![json!({ "name": optifine.to_string() }), launchwrapper],
+    );
+    let content = serde_json::to_vec_pretty(&manifest)
+        .map_err(|e| format!("Error al serializar el manifiesto: {}", e))?;
+    write_file(
+        &paths::version_file(paths.game_dir(), &id, "json"),
+        &content,
+    )?;
+
+    log::info!("[OptiFine] Registered version {}", id);
+    Ok(id)
+}
+
+/// Copies the jar to `mods/`, replacing any other OptiFine build.
+fn install_as_mod(instance: &MinecraftInstance, installer: &Path) -> Result<String, String> {
+    let mods_dir = paths::native_path(&instance.minecraftPath).join("mods");
+    fs::create_dir_all(&mods_dir).map_err(|e| format!("Error al crear directorio: {}", e))?;
+
+    let file_name = installer
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| "Nombre de archivo de OptiFine inválido".to_string())?;
+    if let Ok(entries) = fs::read_dir(&mods_dir) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let stale = name != file_name
+                && name.ends_with(".jar")
+                && (name.starts_with("OptiFine_") || name.starts_with("preview_OptiFine_"));
+            if stale {
+                log::info!("[OptiFine] Removing previous build {}", name);
+                let _ = fs::remove_file(entry.path());
+            }
+        }
+    }
+
+    fs::copy(installer, mods_dir.join(&file_name))
+        .map_err(|e| format!("Error al copiar OptiFine: {}", e))?;
+    Ok(file_name)
+}
+
+fn install(instance_id: &str, installer: &Path) -> Result<OptiFineInstall, String> {
+    let instance = get_instance_by_id(instance_id.to_string())?
+        .ok_or_else(|| format!("Instance with ID {} not found", instance_id))?;
+
+    let (minecraft_version, edition) = parse_installer_name(installer).ok_or_else(|| {
+        "El archivo no parece un instalador de OptiFine (OptiFine_<versión>_HD_...jar)".to_string()
+    })?;
+    if minecraft_version != instance.minecraftVersion {
+        return Err(format!(
+            "Este OptiFine es para Minecraft {}, la instancia usa {}",
+            minecraft_version, instance.minecraftVersion
+        ));
+    }
+    if read_entry(installer, "optifine/Installer.class").is_none() {
+        return Err("El archivo no es un instalador de OptiFine".to_string());
+    }
+
+    log::info!(
+        "[OptiFine] Installing {} {} on {}",
+        minecraft_version,
+        edition,
+        instance.instanceName
+    );
+    let (version_id, mod_file) = if instance.is_forge_instance() {
+        (None, Some(install_as_mod(&instance, installer)?))
+    } else {
+        (
+            Some(install_as_version(&instance, installer, &edition)?),
+            None,
+        )
+    };
+
+    update_instance_by_id(instance_id, |instance| {
+        instance.optifineVersion = Some(edition.clone());
+        Ok(())
+    })?;
+
+    Ok(OptiFineInstall {
+        instance_id: instance.instanceId,
+        minecraft_version,
+        edition,
+        version_id,
+        mod_file,
+    })
+}
+
+/// Installs the OptiFine build in `installer_path` (the jar downloaded from
+/// optifine.net) on the instance.
+#[tauri::command]
+pub async fn install_optifine(
+    instance_id: String,
+    installer_path: String,
+) -> Result<OptiFineInstall, String> {
+    tokio::task::spawn_blocking(move || install(&instance_id, Path::new(&installer_path)))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
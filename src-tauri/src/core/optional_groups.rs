@@ -0,0 +1,157 @@
+//! Optional mod groups ("optional features") declared by a modpack.
+
+use crate::core::file_rules::FileRules;
+use crate::core::instance_manager::{get_instance_by_id, update_instance_by_id};
+use crate::core::minecraft_instance::MinecraftInstance;
+use crate::core::models::{ManifestFile, ModpackManifest};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OptionalGroupSelection {
+    /// Grupos activados
+    pub selected: Vec<String>,
+    /// Grupos que el usuario ya ha visto, activados o no
+    pub known: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OptionalGroup {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Activado por defecto en instalaciones nuevas
+    #[serde(default, rename = "default")]
+    pub enabled_by_default: bool,
+    /// Estado para la instancia; no viene del manifiesto
+    #[serde(default)]
+    pub selected: bool,
+}
+
+/// Brings the selection in line with the manifest's groups: drops groups
+/// that no longer exist and applies the default to groups never seen.
+pub fn reconcile(
+    selection: Option<&OptionalGroupSelection>,
+    groups: &[OptionalGroup],
+) -> OptionalGroupSelection {
+    let previous = selection.cloned().unwrap_or_default();
+    let exists = |id: &String| groups.iter().any(|group| &group.id == id);
+
+    let mut selected: Vec<String> = previous.selected.into_iter().filter(exists).collect();
+    for group in groups {
+        let is_new = !previous.known.contains(&group.id);
+        if is_new && group.enabled_by_default && !selected.contains(&group.id) {
+            selected.push(group.id.clone());
+        }
+    }
+
+    OptionalGroupSelection {
+        selected,
+        known: groups.iter().map(|group| group.id.clone()).collect(),
+    }
+}
+
+/// Whether a manifest file entry belongs to the selection.
+pub fn is_file_selected(file_entry: &ManifestFile, selection: &OptionalGroupSelection) -> bool {
+    match file_entry.group.as_deref() {
+        Some(group) => selection.selected.iter().any(|id| id == group),
+        None => true,
+    }
+}
+
+fn read_manifest(instance: &MinecraftInstance) -> Result<ModpackManifest, String> {
+    let directory = instance
+        .instanceDirectory
+        .as_deref()
+        .ok_or_else(|| "La instancia no tiene directorio".to_string())?;
+    ModpackManifest::read(Path::new(directory))?
+        .ok_or_else(|| "La instancia no tiene manifiesto de modpack".to_string())
+}
+
+/// Removes the installed files of groups that are not selected, except
+/// the ones the player preserves.
+pub fn remove_unselected_files(
+    instance: &MinecraftInstance,
+    manifest: &ModpackManifest,
+    selection: &OptionalGroupSelection,
+) {
+    let minecraft_dir = Path::new(&instance.minecraftPath);
+    let rules = FileRules::for_manifest(instance, manifest);
+
+    for file_entry in &manifest.files {
+        if is_file_selected(file_entry, selection) {
+            continue;
+        }
+        let path = file_entry.path.as_str();
+        if rules.is_preserved(path) {
+            continue;
+        }
+        let full_path = minecraft_dir.join(path);
+        if full_path.is_file() {
+            log::info!("[OptionalGroups] Removing deselected file {}", path);
+            if let Err(e) = fs::remove_file(&full_path) {
+                log::warn!("[OptionalGroups] Could not remove {}: {}", path, e);
+            }
+        }
+    }
+}
+
+fn load_instance(instance_id: String) -> Result<MinecraftInstance, String> {
+    get_instance_by_id(instance_id.clone())?
+        .ok_or_else(|| format!("Instance with ID {} not found", instance_id))
+}
+
+/// Optional groups of the instance's modpack with their current state.
+#[tauri::command]
+pub fn get_modpack_optional_groups(instance_id: String) -> Result<Vec<OptionalGroup>, String> {
+    let instance = load_instance(instance_id)?;
+    let manifest = read_manifest(&instance)?;
+    let mut groups = manifest.optional_groups;
+    let selection = reconcile(instance.optionalGroups.as_ref(), &groups);
+
+    for group in &mut groups {
+        group.selected = selection.selected.contains(&group.id);
+    }
+    Ok(groups)
+}
+
+/// Replaces the selected groups. Files of deselected groups are removed
+/// now; newly selected ones are downloaded by the next validation.
+#[tauri::command]
+pub fn set_instance_optional_groups(
+    instance_id: String,
+    group_ids: Vec<String>,
+) -> Result<MinecraftInstance, String> {
+    let instance = load_instance(instance_id.clone())?;
+    let manifest = read_manifest(&instance)?;
+    let groups = &manifest.optional_groups;
+
+    let selected: HashSet<String> = group_ids.into_iter().collect();
+    if let Some(unknown) = selected
+        .iter()
+        .find(|id| !groups.iter().any(|group| &group.id == *id))
+    {
+        return Err(format!("Grupo opcional desconocido: {}", unknown));
+    }
+
+    let selection = OptionalGroupSelection {
+        selected: groups
+            .iter()
+            .filter(|group| selected.contains(&group.id))
+            .map(|group| group.id.clone())
+            .collect(),
+        known: groups.iter().map(|group| group.id.clone()).collect(),
+    };
+    let instance = update_instance_by_id(&instance_id, |instance| {
+        instance.optionalGroups = Some(selection.clone());
+        Ok(instance.clone())
+    })?;
+
+    remove_unselected_files(&instance, &manifest, &selection);
+    Ok(instance)
+}
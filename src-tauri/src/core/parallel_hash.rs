@@ -0,0 +1,83 @@
+//! Parallel hash verification.
+
+use crate::core::download_cache::{file_hash, HashAlgo};
+use crate::core::tasks_manager::CancellationToken;
+use rayon::prelude::*;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Cada cuántos archivos se informa del progreso
+const PROGRESS_CHUNK: usize = 32;
+
+#[derive(Debug, Clone)]
+pub struct HashJob {
+    pub path: PathBuf,
+    /// SHA-1 o SHA-256 en hexadecimal; el algoritmo se deduce de la longitud
+    pub expected: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HashOutcome {
+    Valid,
+    Missing,
+    Mismatch,
+    /// El hash esperado no tiene un formato conocido
+    Unsupported,
+}
+
+fn check(job: &HashJob) -> HashOutcome {
+    let Some(algo) = HashAlgo::for_hash(&job.expected) else {
+        return HashOutcome::Unsupported;
+    };
+    if !job.path.exists() {
+        return HashOutcome::Missing;
+    }
+    match file_hash(&job.path, algo) {
+        Some(actual) if actual.eq_ignore_ascii_case(&job.expected) => HashOutcome::Valid,
+        Some(_) => HashOutcome::Mismatch,
+        None => HashOutcome::Missing,
+    }
+}
+
+/// Hashes every job in parallel. The outcomes keep the order of `jobs`.
+///
+/// `on_progress(done, total)` is called from worker threads, at most once
+/// per chunk plus once at the end. Returns an error if `cancel` fires.
+pub fn verify_files<F>(
+    jobs: &[HashJob],
+    cancel: Option<&CancellationToken>,
+    on_progress: F,
+) -> Result<Vec<HashOutcome>, String>
+where
+    F: Fn(usize, usize) + Sync,
+{
+    let total = jobs.len();
+    let done = AtomicUsize::new(0);
+    let is_cancelled = || cancel.is_some_and(|token| token.is_cancelled());
+
+    let outcomes: Vec<Option<HashOutcome>> = jobs
+        .par_iter()
+        .map(|job| {
+            // Los trabajos pendientes se saltan en cuanto se cancela
+            if is_cancelled() {
+                return None;
+            }
+            let outcome = check(job);
+
+            let finished = done.fetch_add(1, Ordering::Relaxed) + 1;
+            if finished % PROGRESS_CHUNK == 0 || finished == total {
+                on_progress(finished, total);
+            }
+            Some(outcome)
+        })
+        .collect();
+
+    if is_cancelled() {
+        return Err("Operación cancelada".to_string());
+    }
+
+    Ok(outcomes
+        .into_iter()
+        .map(|outcome| outcome.unwrap_or(HashOutcome::Missing))
+        .collect())
+}
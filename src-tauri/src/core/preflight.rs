@@ -0,0 +1,527 @@
+// src-tauri/src/core/preflight.rs
+use crate::config::read_config;
+use crate::core::hardware_info;
+use crate::core::instance_manager::get_instance_by_id;
+use crate::core::minecraft::heap_limits::{heap_limit, jvm_bitness, HeapLimitReason};
+use crate::core::minecraft::manifest::{ManifestParser, MergeReport};
+use crate::core::minecraft::path_encoding;
+use crate::core::minecraft::paths::MinecraftPaths;
+use crate::core::minecraft_instance::MinecraftInstance;
+use crate::core::system_resources::{effective_memory_mb, SystemMemory};
+use serde::Serialize;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use sysinfo::{Disks, System};
+
+/// Espacio libre mínimo (MB) por debajo del cual no dejamos lanzar
+const MIN_FREE_DISK_MB: u64 = 512;
+/// Espacio libre (MB) por debajo del cual sólo avisamos
+const LOW_FREE_DISK_MB: u64 = 2048;
+
+/// Procesos conocidos que suelen romper Minecraft/LWJGL (ejecutables en
+/// minúsculas y sin `.exe`, motivo)
+const CONFLICTING_PROCESSES: &[(&[&str], &str)] = &[
+    (
+        &["nahimicsvc64", "nahimicsvc32", "nahimic3"],
+        "Nahimic inyecta un overlay de audio que puede cerrar el juego al iniciar",
+    ),
+    (
+        &["sonicstudio3"],
+        "Sonic Studio puede provocar cierres al inicializar el audio",
+    ),
+    (
+        &["rtss"],
+        "El overlay de RivaTuner puede causar pantallas negras con OpenGL",
+    ),
+    (
+        &["lghub_agent"],
+        "Logitech G HUB es conocido por provocar tirones al iniciar Minecraft",
+    ),
+    (
+        &["fences"],
+        "Stardock Fences puede interferir con la creación de la ventana del juego",
+    ),
+];
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Passed,
+    Warning,
+    Error,
+}
+
+/// A single entry of the checklist shown before launching.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightCheck {
+    pub id: String,
+    pub label: String,
+    pub status: CheckStatus,
+    pub message: String,
+}
+
+impl PreflightCheck {
+    fn new(id: &str, label: &str, status: CheckStatus, message: impl Into<String>) -> Self {
+        Self {
+            id: id.to_string(),
+            label: label.to_string(),
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightReport {
+    pub instance_id: String,
+    /// `false` when at least one check is blocking
+    pub can_launch: bool,
+    pub checks: Vec<PreflightCheck>,
+}
+
+impl PreflightReport {
+    pub fn errors(&self) -> impl Iterator<Item = &PreflightCheck> {
+        self.checks
+            .iter()
+            .filter(|c| c.status == CheckStatus::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &PreflightCheck> {
+        self.checks
+            .iter()
+            .filter(|c| c.status == CheckStatus::Warning)
+    }
+}
+
+/// Runs every pre-launch check against an instance.
+pub fn run_preflight(instance: &MinecraftInstance) -> Result<PreflightReport, String> {
+    let (paths, configured_memory) = {
+        let config_manager = read_config();
+        let config = config_manager.as_ref().map_err(|e| e.clone())?;
+
+        let paths = MinecraftPaths::new(instance, config)
+            .ok_or_else(|| "No se pudieron resolver las rutas de la instancia".to_string())?;
+        (paths, config.get_minecraft_memory())
+    };
+
+    let memory_mb = effective_memory_mb(instance, configured_memory);
+
+    let checks = vec![
+        check_operating_system(),
+        check_java(&paths),
+        check_memory(memory_mb, paths.java_path()),
+        check_disk_space(paths.game_dir()),
+        check_natives(&paths.extracted_natives_dir()),
+        check_path_encoding(&paths),
+        check_conflicting_processes(),
+        check_gpu(&instance.minecraftVersion, paths.game_dir()),
+    ];
+
+    let can_launch = !checks.iter().any(|c| c.status == CheckStatus::Error);
+
+    Ok(PreflightReport {
+        instance_id: instance.instanceId.clone(),
+        can_launch,
+        checks,
+    })
+}
+
+fn check_operating_system() -> PreflightCheck {
+    let os = std::env::consts::OS;
+    let arch = std::env::consts::ARCH;
+
+    match (os, arch) {
+        ("windows" | "macos" | "linux", "x86_64" | "aarch64") => PreflightCheck::new(
+            "os",
+            "Sistema operativo",
+            CheckStatus::Passed,
+            format!("{} ({})", os, arch),
+        ),
+        ("windows" | "linux", "x86") => PreflightCheck::new(
+            "os",
+            "Sistema operativo",
+            CheckStatus::Warning,
+            "Sistema de 32 bits: la memoria asignable estará limitada",
+        ),
+        _ => PreflightCheck::new(
+            "os",
+            "Sistema operativo",
+            CheckStatus::Error,
+            format!("Plataforma no soportada: {} ({})", os, arch),
+        ),
+    }
+}
+
+fn check_java(paths: &MinecraftPaths) -> PreflightCheck {
+    let java_path = paths.java_path();
+    if java_path.exists() {
+        PreflightCheck::new(
+            "java",
+            "Java",
+            CheckStatus::Passed,
+            java_path.display().to_string(),
+        )
+    } else {
+        PreflightCheck::new(
+            "java",
+            "Java",
+            CheckStatus::Error,
+            format!("No se encontró Java en {}", java_path.display()),
+        )
+    }
+}
+
+fn check_memory(memory_mb: u32, java_path: &Path) -> PreflightCheck {
+    let system = SystemMemory::detect();
+    let requested = memory_mb as u64;
+    let (limit_mb, reason) = heap_limit(&system, jvm_bitness(java_path));
+
+    // Al lanzar se usa el límite (ver `heap_limits::guard_heap`)
+    if system.total_mb > 0 && memory_mb > limit_mb {
+        let cause = match reason {
+            HeapLimitReason::SystemMemory => {
+                format!("el equipo sólo tiene {} MB", system.total_mb)
+            }
+            HeapLimitReason::Jvm32Bit => "Java es de 32 bits".to_string(),
+        };
+        PreflightCheck::new(
+            "memory",
+            "Memoria",
+            CheckStatus::Warning,
+            format!(
+                "Se asignaron {} MB pero {}; se usarán {} MB",
+                requested, cause, limit_mb
+            ),
+        )
+    } else if requested > system.available_mb {
+        PreflightCheck::new(
+            "memory",
+            "Memoria",
+            CheckStatus::Warning,
+            format!(
+                "Sólo hay {} MB libres de los {} MB asignados; cierra otras aplicaciones",
+                system.available_mb, requested
+            ),
+        )
+    } else {
+        PreflightCheck::new(
+            "memory",
+            "Memoria",
+            CheckStatus::Passed,
+            format!(
+                "{} MB asignados, {} MB libres",
+                requested, system.available_mb
+            ),
+        )
+    }
+}
+
+/// Free space (MB) of the disk that contains `path`, picking the longest
+/// matching mount point.
+pub fn free_disk_space_mb(path: &Path) -> Option<u64> {
+    let target = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let disks = Disks::new_with_refreshed_list();
+
+    disks
+        .list()
+        .iter()
+        .filter(|disk| target.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| disk.available_space() / 1024 / 1024)
+}
+
+fn check_disk_space(game_dir: &Path) -> PreflightCheck {
+    // Si la carpeta aún no existe medimos el primer ancestro existente
+    let existing: PathBuf = game_dir
+        .ancestors()
+        .find(|p| p.exists())
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| game_dir.to_path_buf());
+
+    match free_disk_space_mb(&existing) {
+        Some(free) if free < MIN_FREE_DISK_MB => PreflightCheck::new(
+            "disk",
+            "Espacio en disco",
+            CheckStatus::Error,
+            format!("Sólo quedan {} MB libres en el disco de la instancia", free),
+        ),
+        Some(free) if free < LOW_FREE_DISK_MB => PreflightCheck::new(
+            "disk",
+            "Espacio en disco",
+            CheckStatus::Warning,
+            format!("Poco espacio libre: {} MB", free),
+        ),
+        Some(free) => PreflightCheck::new(
+            "disk",
+            "Espacio en disco",
+            CheckStatus::Passed,
+            format!("{} MB libres", free),
+        ),
+        None => PreflightCheck::new(
+            "disk",
+            "Espacio en disco",
+            CheckStatus::Warning,
+            "No se pudo determinar el espacio libre",
+        ),
+    }
+}
+
+/// Architecture of a native library, read from the machine field of its
+/// PE, ELF or Mach-O header, with the names of `std::env::consts::ARCH`.
+/// `None` if it can't be read or is a universal (fat) Mach-O.
+fn binary_arch(path: &Path) -> Option<&'static str> {
+    let mut header = [0u8; 1024];
+    let read = fs::File::open(path)
+        .and_then(|mut file| file.read(&mut header))
+        .ok()?;
+    let header = &header[..read];
+    let u16_le = |at: usize| Some(u16::from_le_bytes(header.get(at..at + 2)?.try_into().ok()?));
+    let u32_le = |at: usize| Some(u32::from_le_bytes(header.get(at..at + 4)?.try_into().ok()?));
+
+    match header.get(..4)? {
+        // ELF: e_machine en el offset 18, con el orden de bytes de EI_DATA
+        [0x7f, b'E', b'L', b'F'] => {
+            let bytes: [u8; 2] = header.get(18..20)?.try_into().ok()?;
+            let machine = if header.get(5) == Some(&2) {
+                u16::from_be_bytes(bytes)
+            } else {
+                u16::from_le_bytes(bytes)
+            };
+            match machine {
+                0x03 => Some("x86"),
+                0x3e => Some("x86_64"),
+                0x28 => Some("arm"),
+                0xb7 => Some("aarch64"),
+                _ => None,
+            }
+        }
+        // PE: la cabecera "PE\0\0" está en e_lfanew y le sigue Machine
+        [b'M', b'Z', ..] => {
+            let pe = u32_le(0x3c)? as usize;
+            if header.get(pe..pe + 4)? != b"PE\0\0" {
+                return None;
+            }
+            match u16_le(pe + 4)? {
+                0x014c => Some("x86"),
+                0x8664 => Some("x86_64"),
+                0xaa64 => Some("aarch64"),
+                _ => None,
+            }
+        }
+        // Mach-O de 32 o 64 bits (little endian): cputype tras el magic
+        [0xce, 0xfa, 0xed, 0xfe] | [0xcf, 0xfa, 0xed, 0xfe] => match u32_le(4)? {
+            0x0000_0007 => Some("x86"),
+            0x0100_0007 => Some("x86_64"),
+            0x0100_000c => Some("aarch64"),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn check_natives(natives_dir: &Path) -> PreflightCheck {
+    let expected_ext: &[&str] = if cfg!(windows) {
+        &["dll"]
+    } else if cfg!(target_os = "macos") {
+        &["dylib", "jnilib"]
+    } else {
+        &["so"]
+    };
+
+    let entries: Vec<PathBuf> = match fs::read_dir(natives_dir) {
+        Ok(entries) => entries.flatten().map(|e| e.path()).collect(),
+        Err(_) => {
+            // Las versiones modernas extraen los nativos al vuelo, no es bloqueante
+            return PreflightCheck::new(
+                "natives",
+                "Bibliotecas nativas",
+                CheckStatus::Warning,
+                "La carpeta de nativos no existe todavía",
+            );
+        }
+    };
+
+    let libraries: Vec<&PathBuf> = entries
+        .iter()
+        .filter(|p| {
+            p.extension()
+                .and_then(|e| e.to_str())
+                .map(|e| ["dll", "so", "dylib", "jnilib"].contains(&e))
+                .unwrap_or(false)
+        })
+        .collect();
+
+    if libraries.is_empty() {
+        return PreflightCheck::new(
+            "natives",
+            "Bibliotecas nativas",
+            CheckStatus::Passed,
+            "Sin nativos extraídos (se resuelven en tiempo de ejecución)",
+        );
+    }
+
+    let matching = libraries.iter().any(|p| {
+        p.extension()
+            .and_then(|e| e.to_str())
+            .map(|e| expected_ext.contains(&e))
+            .unwrap_or(false)
+    });
+
+    if !matching {
+        return PreflightCheck::new(
+            "natives",
+            "Bibliotecas nativas",
+            CheckStatus::Error,
+            "Los nativos extraídos no corresponden a este sistema operativo",
+        );
+    }
+
+    // Las versiones antiguas traen nativos de 32 y 64 bits juntos: basta
+    // con que alguno sea de la arquitectura del equipo
+    let host = std::env::consts::ARCH;
+    let archs: Vec<&str> = libraries.iter().filter_map(|p| binary_arch(p)).collect();
+    if !archs.is_empty() && !archs.contains(&host) {
+        let (status, message) = match host {
+            // En ARM, LWJGL 2 no trae nativos arm64
+            "aarch64" if archs.contains(&"x86_64") => (
+                CheckStatus::Warning,
+                "Los nativos son x86_64; en ARM requieren Java x86_64 con emulación".to_string(),
+            ),
+            "x86_64" if archs.contains(&"x86") => (
+                CheckStatus::Warning,
+                "Los nativos son de 32 bits; sólo funcionan con un Java de 32 bits".to_string(),
+            ),
+            _ => (
+                CheckStatus::Error,
+                format!(
+                    "Los nativos extraídos ({}) no corresponden a la arquitectura del equipo ({})",
+                    archs[0], host
+                ),
+            ),
+        };
+        return PreflightCheck::new("natives", "Bibliotecas nativas", status, message);
+    }
+
+    PreflightCheck::new(
+        "natives",
+        "Bibliotecas nativas",
+        CheckStatus::Passed,
+        format!("{} bibliotecas nativas", libraries.len()),
+    )
+}
+
+fn check_path_encoding(paths: &MinecraftPaths) -> PreflightCheck {
+    let non_ascii = path_encoding::non_ascii_paths(paths);
+    if non_ascii.is_empty() {
+        return PreflightCheck::new(
+            "paths",
+            "Rutas",
+            CheckStatus::Passed,
+            "Sin caracteres especiales",
+        );
+    }
+
+    let list = non_ascii
+        .iter()
+        .map(|(label, path)| format!("{}: {}", label, path.display()))
+        .collect::<Vec<_>>()
+        .join(", ");
+    PreflightCheck::new(
+        "paths",
+        "Rutas",
+        CheckStatus::Warning,
+        format!(
+            "Rutas con acentos u otros caracteres no ASCII; algunas versiones de Java no pueden cargarlas ({})",
+            list
+        ),
+    )
+}
+
+fn check_conflicting_processes() -> PreflightCheck {
+    let mut system = System::new();
+    system.refresh_processes();
+
+    let mut found: Vec<String> = Vec::new();
+    for process in system.processes().values() {
+        let name = process.name().to_lowercase();
+        let executable = name.strip_suffix(".exe").unwrap_or(&name);
+        for (executables, reason) in CONFLICTING_PROCESSES {
+            if executables.contains(&executable)
+                && !found.iter().any(|f| f.starts_with(process.name()))
+            {
+                found.push(format!("{}: {}", process.name(), reason));
+            }
+        }
+    }
+
+    if found.is_empty() {
+        PreflightCheck::new(
+            "processes",
+            "Software en conflicto",
+            CheckStatus::Passed,
+            "No se detectó software en conflicto",
+        )
+    } else {
+        PreflightCheck::new(
+            "processes",
+            "Software en conflicto",
+            CheckStatus::Warning,
+            found.join("\n"),
+        )
+    }
+}
+
+fn check_gpu(minecraft_version: &str, game_dir: &Path) -> PreflightCheck {
+    let advisories = hardware_info::gpu_advisories(minecraft_version, game_dir);
+    if !advisories.is_empty() {
+        return PreflightCheck::new(
+            "gpu",
+            "Tarjeta gráfica",
+            CheckStatus::Warning,
+            advisories.join("\n"),
+        );
+    }
+
+    let gpus = hardware_info::gpus();
+    let message = if gpus.is_empty() {
+        "No se pudo detectar la tarjeta gráfica".to_string()
+    } else {
+        gpus.iter()
+            .map(|gpu| gpu.model.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    PreflightCheck::new("gpu", "Tarjeta gráfica", CheckStatus::Passed, message)
+}
+
+#[tauri::command]
+pub fn preflight_check(instance_id: String) -> Result<PreflightReport, String> {
+    let instance = get_instance_by_id(instance_id.clone())?
+        .ok_or_else(|| format!("Instance with ID {} not found", instance_id))?;
+
+    run_preflight(&instance)
+}
+
+/// Library conflicts resolved while merging the instance's version manifest
+/// with its parent, for the diagnostics view.
+#[tauri::command]
+pub fn get_manifest_merge_report(instance_id: String) -> Result<MergeReport, String> {
+    let instance = get_instance_by_id(instance_id.clone())?
+        .ok_or_else(|| format!("Instance with ID {} not found", instance_id))?;
+
+    let paths = {
+        let config_manager = read_config();
+        let config = config_manager.as_ref().map_err(|e| e.clone())?;
+
+        MinecraftPaths::new(&instance, config)
+            .ok_or_else(|| "No se pudieron resolver las rutas de la instancia".to_string())?
+    };
+
+    ManifestParser::new(&paths)
+        .load_merged_manifest_with_report()
+        .map(|(_, report)| report)
+        .ok_or_else(|| "No se pudo cargar el manifiesto de la versión".to_string())
+}
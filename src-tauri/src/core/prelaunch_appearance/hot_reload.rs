@@ -0,0 +1,151 @@
+//! Hot reload of `prelaunch_appearance.json` for publishers.
+
+use super::{parse_appearance, PreLaunchAppearance};
+use crate::core::events::{self, LauncherEvent};
+use crate::core::instance_manager::get_instance_by_id;
+use crate::core::minecraft_instance::MinecraftInstance;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppearanceUpdate {
+    pub instance_id: String,
+    /// `None` si el archivo se borró o no es válido
+    pub appearance: Option<PreLaunchAppearance>,
+    /// Error de parseo, para que el publisher lo vea sin abrir los logs
+    pub error: Option<String>,
+}
+
+struct Watch {
+    stop: Arc<AtomicBool>,
+    /// Ventanas que lo están usando
+    windows: usize,
+}
+
+static WATCHES: Lazy<Mutex<HashMap<String, Watch>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn watches() -> MutexGuard<'static, HashMap<String, Watch>> {
+    WATCHES.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Modification time and size; `None` while the file doesn't exist.
+type Signature = Option<(SystemTime, u64)>;
+
+fn signature(path: &Path) -> Signature {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+fn hot_reload_enabled(instance: &MinecraftInstance) -> bool {
+    cfg!(debug_assertions) || instance.appearanceHotReload == Some(true)
+}
+
+fn reload(instance_id: &str, path: &Path) {
+    let update = match fs::read(path) {
+        Err(_) => AppearanceUpdate {
+            instance_id: instance_id.to_string(),
+            appearance: None,
+            error: None,
+        },
+        Ok(contents) => match parse_appearance(&contents) {
+            Ok(appearance) => AppearanceUpdate {
+                instance_id: instance_id.to_string(),
+                appearance: Some(appearance),
+                error: None,
+            },
+            Err(e) => {
+                log::warn!("[Appearance] Invalid {}: {}", path.display(), e);
+                AppearanceUpdate {
+                    instance_id: instance_id.to_string(),
+                    appearance: None,
+                    error: Some(e.to_string()),
+                }
+            }
+        },
+    };
+    log::info!(
+        "[Appearance] Reloaded appearance of instance {}",
+        instance_id
+    );
+    events::emit(LauncherEvent::PrelaunchAppearanceUpdated(update));
+}
+
+fn spawn_watcher(instance_id: String, path: PathBuf, stop: Arc<AtomicBool>) -> Result<(), String> {
+    thread::Builder::new()
+        .name(format!("appearance-watch-{}", instance_id))
+        .spawn(move || {
+            let mut seen = signature(&path);
+            // Cambio detectado que espera a que el archivo deje de cambiar
+            let mut pending: Option<Signature> = None;
+            while !stop.load(Ordering::Relaxed) {
+                thread::sleep(POLL_INTERVAL);
+                let current = signature(&path);
+                match pending {
+                    // Los editores guardan en varios pasos: se recarga cuando se estabiliza
+                    Some(previous) if previous == current => {
+                        pending = None;
+                        seen = current;
+                        reload(&instance_id, &path);
+                    }
+                    _ if current != seen => pending = Some(current),
+                    _ => pending = None,
+                }
+            }
+            log::debug!("[Appearance] Stopped watching {}", path.display());
+        })
+        .map(|_| ())
+        .map_err(|e| format!("No se pudo vigilar la apariencia: {}", e))
+}
+
+/// Starts sending `prelaunch-appearance-updated` when the instance's
+/// `prelaunch_appearance.json` changes. Returns `false` when hot reload is
+/// disabled for the instance.
+#[tauri::command]
+pub fn watch_prelaunch_appearance(instance_id: String) -> Result<bool, String> {
+    let instance = get_instance_by_id(instance_id.clone())?
+        .ok_or_else(|| format!("Instance with ID {} not found", instance_id))?;
+    if !hot_reload_enabled(&instance) {
+        return Ok(false);
+    }
+    let instance_dir = instance
+        .instanceDirectory
+        .as_deref()
+        .ok_or_else(|| "La instancia no tiene directorio".to_string())?;
+
+    let mut watches = watches();
+    if let Some(watch) = watches.get_mut(&instance_id) {
+        watch.windows += 1;
+        return Ok(true);
+    }
+
+    let path = Path::new(instance_dir).join("prelaunch_appearance.json");
+    let stop = Arc::new(AtomicBool::new(false));
+    spawn_watcher(instance_id.clone(), path.clone(), Arc::clone(&stop))?;
+    log::info!("[Appearance] Watching {}", path.display());
+    watches.insert(instance_id, Watch { stop, windows: 1 });
+    Ok(true)
+}
+
+/// Stops the watch of `watch_prelaunch_appearance` once no window uses it.
+#[tauri::command]
+pub fn unwatch_prelaunch_appearance(instance_id: String) {
+    let mut watches = watches();
+    let Some(watch) = watches.get_mut(&instance_id) else {
+        return;
+    };
+    watch.windows -= 1;
+    if watch.windows == 0 {
+        watch.stop.store(true, Ordering::Relaxed);
+        watches.remove(&instance_id);
+    }
+}
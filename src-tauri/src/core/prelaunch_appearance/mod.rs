@@ -1,13 +1,16 @@
-use crate::core::instance_manager::get_instance_by_id;
+pub mod hot_reload;
+
+use crate::core::instance_manager::{get_instance_by_id, update_instance_by_id};
 use crate::core::minecraft_instance::MinecraftInstance;
+use crate::core::models::{ModpackManifest, MODPACK_MANIFEST_FILE};
 use anyhow::Context;
 use serde::{Deserialize, Serialize};
 use serde_json::from_slice;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tokio::io::AsyncReadExt;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct LogoPosition {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -27,7 +30,7 @@ pub struct LogoPosition {
     pub unknown_fields: HashMap<String, serde_json::Value>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Logo {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -47,7 +50,7 @@ pub struct Logo {
     pub unknown_fields: HashMap<String, serde_json::Value>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PlayButtonPosition {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -67,7 +70,7 @@ pub struct PlayButtonPosition {
     pub unknown_fields: HashMap<String, serde_json::Value>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PlayButton {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -93,7 +96,7 @@ pub struct PlayButton {
     pub unknown_fields: HashMap<String, serde_json::Value>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Background {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -107,7 +110,7 @@ pub struct Background {
     pub unknown_fields: HashMap<String, serde_json::Value>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Audio {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -121,7 +124,7 @@ pub struct Audio {
     pub unknown_fields: HashMap<String, serde_json::Value>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct NewsPosition {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -135,7 +138,7 @@ pub struct NewsPosition {
     pub unknown_fields: HashMap<String, serde_json::Value>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct NewsStyle {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -157,7 +160,7 @@ pub struct NewsStyle {
     pub unknown_fields: HashMap<String, serde_json::Value>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Entry {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -171,7 +174,7 @@ pub struct Entry {
     pub unknown_fields: HashMap<String, serde_json::Value>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct News {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -187,7 +190,7 @@ pub struct News {
     pub unknown_fields: HashMap<String, serde_json::Value>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FooterStyle {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -209,7 +212,27 @@ pub struct FooterStyle {
     pub unknown_fields: HashMap<String, serde_json::Value>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Changelog/rules screen that must be accepted before the first launch of a
+/// modpack version.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequiredNotice {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    pub content: String,
+    /// Versión del aviso; si falta se usa la versión del modpack
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accept_label: Option<String>,
+
+    // Captura campos desconocidos
+    #[serde(flatten)]
+    #[serde(skip_serializing)]
+    pub unknown_fields: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PreLaunchAppearance {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -230,6 +253,8 @@ pub struct PreLaunchAppearance {
     pub footer_style: Option<FooterStyle>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub footer_text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub required_notice: Option<RequiredNotice>,
 
     // Captura campos desconocidos
     #[serde(flatten)]
@@ -273,58 +298,144 @@ pub async fn get_prelaunch_appearance(instance_id: String) -> Option<PreLaunchAp
     let mut contents = Vec::new();
     file.read_to_end(&mut contents).await.ok()?;
 
-    match serde_json::from_slice::<PreLaunchAppearance>(&contents) {
-        Ok(data) => {
-            // Registrar los campos desconocidos
-            log_unknown_fields("prelaunch_appearance", &data.unknown_fields);
-
-            // Registrar campos desconocidos de los componentes anidados
-            if let Some(logo) = &data.logo {
-                log_unknown_fields("logo", &logo.unknown_fields);
-                if let Some(position) = &logo.position {
-                    log_unknown_fields("logo.position", &position.unknown_fields);
-                }
-            }
+    match parse_appearance(&contents) {
+        Ok(data) => Some(data),
+        Err(e) => {
+            log::error!("Failed to parse prelaunch_appearance.json: {:?}", e);
+            None
+        }
+    }
+}
 
-            if let Some(play_button) = &data.play_button {
-                log_unknown_fields("play_button", &play_button.unknown_fields);
-                if let Some(position) = &play_button.position {
-                    log_unknown_fields("play_button.position", &position.unknown_fields);
-                }
-            }
+/// Parses `prelaunch_appearance.json`, logging the attributes this version
+/// doesn't support.
+fn parse_appearance(contents: &[u8]) -> serde_json::Result<PreLaunchAppearance> {
+    let data = serde_json::from_slice::<PreLaunchAppearance>(contents)?;
 
-            if let Some(background) = &data.background {
-                log_unknown_fields("background", &background.unknown_fields);
-            }
+    // Registrar los campos desconocidos
+    log_unknown_fields("prelaunch_appearance", &data.unknown_fields);
 
-            if let Some(audio) = &data.audio {
-                log_unknown_fields("audio", &audio.unknown_fields);
-            }
+    // Registrar campos desconocidos de los componentes anidados
+    if let Some(logo) = &data.logo {
+        log_unknown_fields("logo", &logo.unknown_fields);
+        if let Some(position) = &logo.position {
+            log_unknown_fields("logo.position", &position.unknown_fields);
+        }
+    }
 
-            if let Some(news) = &data.news {
-                log_unknown_fields("news", &news.unknown_fields);
-                if let Some(position) = &news.position {
-                    log_unknown_fields("news.position", &position.unknown_fields);
-                }
-                if let Some(style) = &news.style {
-                    log_unknown_fields("news.style", &style.unknown_fields);
-                }
-                if let Some(entries) = &news.entries {
-                    for (i, entry) in entries.iter().enumerate() {
-                        log_unknown_fields(&format!("news.entries[{}]", i), &entry.unknown_fields);
-                    }
-                }
-            }
+    if let Some(play_button) = &data.play_button {
+        log_unknown_fields("play_button", &play_button.unknown_fields);
+        if let Some(position) = &play_button.position {
+            log_unknown_fields("play_button.position", &position.unknown_fields);
+        }
+    }
 
-            if let Some(footer_style) = &data.footer_style {
-                log_unknown_fields("footer_style", &footer_style.unknown_fields);
-            }
+    if let Some(background) = &data.background {
+        log_unknown_fields("background", &background.unknown_fields);
+    }
+
+    if let Some(audio) = &data.audio {
+        log_unknown_fields("audio", &audio.unknown_fields);
+    }
 
-            Some(data)
+    if let Some(news) = &data.news {
+        log_unknown_fields("news", &news.unknown_fields);
+        if let Some(position) = &news.position {
+            log_unknown_fields("news.position", &position.unknown_fields);
         }
-        Err(e) => {
-            log::error!("Failed to parse prelaunch_appearance.json: {:?}", e);
-            None
+        if let Some(style) = &news.style {
+            log_unknown_fields("news.style", &style.unknown_fields);
         }
+        if let Some(entries) = &news.entries {
+            for (i, entry) in entries.iter().enumerate() {
+                log_unknown_fields(&format!("news.entries[{}]", i), &entry.unknown_fields);
+            }
+        }
+    }
+
+    if let Some(footer_style) = &data.footer_style {
+        log_unknown_fields("footer_style", &footer_style.unknown_fields);
     }
+
+    if let Some(notice) = &data.required_notice {
+        log_unknown_fields("required_notice", &notice.unknown_fields);
+    }
+
+    Ok(data)
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingNotice {
+    pub instance_id: String,
+    /// Versión que hay que pasar a `accept_instance_notice`
+    pub version: String,
+    pub notice: RequiredNotice,
+}
+
+// El aviso puede venir en prelaunch_appearance.json o en el manifiesto del modpack
+fn read_required_notice(instance_dir: &Path) -> Option<RequiredNotice> {
+    let appearance_notice = std::fs::read(instance_dir.join("prelaunch_appearance.json"))
+        .ok()
+        .and_then(|contents| from_slice::<serde_json::Value>(&contents).ok())
+        .and_then(|json| json.get("requiredNotice").cloned());
+    let manifest_notice = ModpackManifest::read(instance_dir)
+        .ok()
+        .flatten()
+        .and_then(|manifest| manifest.required_notice);
+
+    for (file_name, value) in [
+        ("prelaunch_appearance.json", appearance_notice),
+        (MODPACK_MANIFEST_FILE, manifest_notice),
+    ] {
+        let Some(value) = value else {
+            continue;
+        };
+
+        match serde_json::from_value::<RequiredNotice>(value) {
+            Ok(notice) => return Some(notice),
+            Err(e) => log::error!("Invalid requiredNotice in {}: {}", file_name, e),
+        }
+    }
+    None
+}
+
+/// Notice the user still has to accept before launching `instance`, if any.
+pub fn pending_notice(instance: &MinecraftInstance) -> Option<PendingNotice> {
+    let instance_dir = PathBuf::from(instance.instanceDirectory.as_deref()?);
+    let notice = read_required_notice(&instance_dir)?;
+
+    let version = notice
+        .version
+        .clone()
+        .or_else(|| {
+            let info = instance.modpackInfo.as_ref()?;
+            info.version
+                .clone()
+                .or_else(|| info.modpackVersionId.clone())
+        })
+        .unwrap_or_default();
+
+    if instance.acceptedNoticeVersion.as_deref() == Some(version.as_str()) {
+        return None;
+    }
+
+    Some(PendingNotice {
+        instance_id: instance.instanceId.clone(),
+        version,
+        notice,
+    })
+}
+
+#[tauri::command]
+pub fn accept_instance_notice(instance_id: String, version: String) -> Result<(), String> {
+    log::info!(
+        "Notice version {} accepted for instance {}",
+        version,
+        instance_id
+    );
+    update_instance_by_id(&instance_id, |instance| {
+        instance.acceptedNoticeVersion = Some(version);
+        Ok(())
+    })
 }
@@ -0,0 +1,318 @@
+//! Streaming capture of the game's stdout/stderr.
+
+use crate::core::session_logs::SessionLog;
+use crate::core::subprocess;
+use std::collections::{HashSet, VecDeque};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::PathBuf;
+use std::process::Child;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Bytes retenidos por stream (stdout y stderr por separado)
+const DEFAULT_CAPACITY: usize = 256 * 1024;
+/// Cada cuánto se mira si el juego escribió más en los archivos de sesión
+const FOLLOW_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Last lines of a stream, bounded by size, plus which of the watched
+/// patterns ever showed up (they may have scrolled out of the tail).
+#[derive(Debug, Default)]
+pub struct OutputTail {
+    lines: VecDeque<String>,
+    bytes: usize,
+    capacity: usize,
+    dropped_lines: usize,
+    /// Líneas recibidas desde el inicio, incluidas las descartadas
+    received_lines: usize,
+    watched: &'static [&'static str],
+    matched: HashSet<&'static str>,
+}
+
+impl OutputTail {
+    pub fn new(capacity: usize, watched: &'static [&'static str]) -> Self {
+        Self {
+            capacity,
+            watched,
+            ..Default::default()
+        }
+    }
+
+    pub fn push(&mut self, line: String) {
+        for pattern in self.watched {
+            if !self.matched.contains(pattern) && line.contains(pattern) {
+                self.matched.insert(pattern);
+            }
+        }
+
+        self.received_lines += 1;
+        self.bytes += line.len() + 1;
+        self.lines.push_back(line);
+        while self.bytes > self.capacity && self.lines.len() > 1 {
+            if let Some(old) = self.lines.pop_front() {
+                self.bytes -= old.len() + 1;
+                self.dropped_lines += 1;
+            }
+        }
+    }
+
+    /// Whether a watched pattern appeared at any point of the stream.
+    pub fn has_seen(&self, pattern: &str) -> bool {
+        self.matched.contains(pattern)
+    }
+
+    pub fn received_lines(&self) -> usize {
+        self.received_lines
+    }
+
+    /// The last `count` lines kept.
+    pub fn last_lines(&self, count: usize) -> Vec<String> {
+        let skip = self.lines.len().saturating_sub(count);
+        self.lines.iter().skip(skip).cloned().collect()
+    }
+
+    pub fn text(&self) -> String {
+        let mut text = String::with_capacity(self.bytes);
+        if self.dropped_lines > 0 {
+            text.push_str(&format!(
+                "[... {} líneas anteriores omitidas]\n",
+                self.dropped_lines
+            ));
+        }
+        for line in &self.lines {
+            text.push_str(line);
+            text.push('\n');
+        }
+        text
+    }
+}
+
+fn push_line(buffer: &[u8], tail: &Mutex<OutputTail>, label: &str, is_stderr: bool) {
+    // Los juegos no siempre escriben UTF-8 válido (Windows, mods antiguos)
+    let line = String::from_utf8_lossy(buffer)
+        .trim_end_matches(['\r', '\n'])
+        .to_string();
+    if is_stderr {
+        log::warn!("[{} stderr] {}", label, line);
+    } else {
+        log::info!("[{} stdout] {}", label, line);
+    }
+    tail.lock().unwrap_or_else(|e| e.into_inner()).push(line);
+}
+
+fn drain<R: Read + Send + 'static>(
+    stream: R,
+    tail: Arc<Mutex<OutputTail>>,
+    label: String,
+    is_stderr: bool,
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        let mut reader = BufReader::new(stream);
+        let mut buffer = Vec::new();
+        loop {
+            buffer.clear();
+            match reader.read_until(b'\n', &mut buffer) {
+                Ok(0) => break,
+                Ok(_) => push_line(&buffer, &tail, &label, is_stderr),
+                Err(e) => {
+                    log::warn!("[{}] Stopped reading output: {}", label, e);
+                    break;
+                }
+            }
+        }
+    })
+}
+
+/// Reads a file the game is writing, from `offset`, like `tail -f`. Ends
+/// at the end of the file once `stop` is set.
+fn follow(
+    path: PathBuf,
+    offset: u64,
+    tail: Arc<Mutex<OutputTail>>,
+    label: String,
+    is_stderr: bool,
+    stop: Arc<AtomicBool>,
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        let mut file = match File::open(&path) {
+            Ok(file) => file,
+            Err(e) => {
+                log::warn!("[{}] Could not follow {}: {}", label, path.display(), e);
+                return;
+            }
+        };
+        if let Err(e) = file.seek(SeekFrom::Start(offset)) {
+            log::warn!("[{}] Could not follow {}: {}", label, path.display(), e);
+            return;
+        }
+        let mut reader = BufReader::new(file);
+        let mut buffer = Vec::new();
+        loop {
+            // Se mira antes de leer: lo escrito hasta ese momento se lee entero
+            let stopping = stop.load(Ordering::Acquire);
+            match reader.read_until(b'\n', &mut buffer) {
+                Ok(_) if buffer.ends_with(b"\n") => {
+                    push_line(&buffer, &tail, &label, is_stderr);
+                    buffer.clear();
+                }
+                // Fin del archivo, quizá con media línea que el juego aún no terminó
+                Ok(_) if stopping => {
+                    if !buffer.is_empty() {
+                        push_line(&buffer, &tail, &label, is_stderr);
+                    }
+                    break;
+                }
+                Ok(_) => thread::sleep(FOLLOW_INTERVAL),
+                Err(e) => {
+                    log::warn!("[{}] Stopped reading output: {}", label, e);
+                    break;
+                }
+            }
+        }
+    })
+}
+
+/// Output captured from a finished process.
+pub struct CapturedOutput {
+    pub stdout: OutputTail,
+    pub stderr: OutputTail,
+}
+
+/// Live view of a capture for code that watches the game while it runs.
+#[derive(Clone)]
+pub struct OutputWatch {
+    stdout: Arc<Mutex<OutputTail>>,
+    stderr: Arc<Mutex<OutputTail>>,
+}
+
+fn lock_tail(tail: &Mutex<OutputTail>) -> MutexGuard<'_, OutputTail> {
+    tail.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+impl OutputWatch {
+    /// Lines received so far on both streams.
+    pub fn received_lines(&self) -> usize {
+        lock_tail(&self.stdout).received_lines() + lock_tail(&self.stderr).received_lines()
+    }
+
+    /// Whether a watched pattern appeared on either stream.
+    pub fn has_seen(&self, pattern: &str) -> bool {
+        lock_tail(&self.stdout).has_seen(pattern) || lock_tail(&self.stderr).has_seen(pattern)
+    }
+
+    /// The last `count` lines of each stream, stdout first.
+    pub fn excerpt(&self, count: usize) -> String {
+        let mut lines = lock_tail(&self.stdout).last_lines(count);
+        lines.extend(lock_tail(&self.stderr).last_lines(count));
+        lines.join("\n")
+    }
+}
+
+/// Reader threads attached to a child's stdout and stderr.
+pub struct OutputCapture {
+    stdout: Arc<Mutex<OutputTail>>,
+    stderr: Arc<Mutex<OutputTail>>,
+    readers: Vec<JoinHandle<()>>,
+    /// Avisa a los lectores de archivos de que el juego terminó
+    stop: Arc<AtomicBool>,
+}
+
+impl OutputCapture {
+    fn new(
+        stdout_watched: &'static [&'static str],
+        stderr_watched: &'static [&'static str],
+    ) -> Self {
+        Self {
+            stdout: Arc::new(Mutex::new(OutputTail::new(
+                DEFAULT_CAPACITY,
+                stdout_watched,
+            ))),
+            stderr: Arc::new(Mutex::new(OutputTail::new(
+                DEFAULT_CAPACITY,
+                stderr_watched,
+            ))),
+            readers: Vec::new(),
+            stop: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Starts draining the child's pipes. The watched patterns of each
+    /// stream are tracked for the whole session.
+    pub fn attach(
+        child: &mut Child,
+        label: &str,
+        stdout_watched: &'static [&'static str],
+        stderr_watched: &'static [&'static str],
+    ) -> Self {
+        let mut capture = Self::new(stdout_watched, stderr_watched);
+        if let Some(stream) = child.stdout.take() {
+            capture.readers.push(drain(
+                stream,
+                Arc::clone(&capture.stdout),
+                label.to_string(),
+                false,
+            ));
+        }
+        if let Some(stream) = child.stderr.take() {
+            capture.readers.push(drain(
+                stream,
+                Arc::clone(&capture.stderr),
+                label.to_string(),
+                true,
+            ));
+        }
+        capture
+    }
+
+    /// Starts following the session log files the game writes its output
+    /// to (see `SessionLog::child_stdio`).
+    pub fn follow_session(
+        session_log: &SessionLog,
+        label: &str,
+        stdout_watched: &'static [&'static str],
+        stderr_watched: &'static [&'static str],
+    ) -> Self {
+        let mut capture = Self::new(stdout_watched, stderr_watched);
+        capture.readers.push(follow(
+            session_log.path().to_path_buf(),
+            session_log.output_start(),
+            Arc::clone(&capture.stdout),
+            label.to_string(),
+            false,
+            Arc::clone(&capture.stop),
+        ));
+        capture.readers.push(follow(
+            session_log.stderr_path().to_path_buf(),
+            0,
+            Arc::clone(&capture.stderr),
+            label.to_string(),
+            true,
+            Arc::clone(&capture.stop),
+        ));
+        capture
+    }
+
+    pub fn watch(&self) -> OutputWatch {
+        OutputWatch {
+            stdout: Arc::clone(&self.stdout),
+            stderr: Arc::clone(&self.stderr),
+        }
+    }
+
+    /// Waits (briefly) for both streams to reach EOF and returns what was
+    /// kept. A process the game left running may keep the pipes open; its
+    /// output is not waited for.
+    pub fn finish(self) -> CapturedOutput {
+        self.stop.store(true, Ordering::Release);
+        subprocess::join_readers(self.readers);
+        let take = |tail: Arc<Mutex<OutputTail>>| {
+            std::mem::take(&mut *tail.lock().unwrap_or_else(|e| e.into_inner()))
+        };
+        CapturedOutput {
+            stdout: take(self.stdout),
+            stderr: take(self.stderr),
+        }
+    }
+}
@@ -0,0 +1,112 @@
+// src-tauri/src/core/process_registry.rs
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub enum ProcessState {
+    Launching,
+    Running,
+}
+
+/// Entry for an instance that is being launched or is running.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceProcess {
+    pub instance_id: String,
+    pub state: ProcessState,
+    pub pid: Option<u32>,
+    pub started_at: String,
+}
+
+// Una sola entrada por instancia: actúa como lock de lanzamiento
+static REGISTRY: Lazy<Mutex<HashMap<String, InstanceProcess>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn registry() -> MutexGuard<'static, HashMap<String, InstanceProcess>> {
+    // Un hilo que entra en pánico no debe bloquear los lanzamientos siguientes
+    REGISTRY.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Per-instance launch lock. Dropping it before `mark_running` releases the
+/// instance again (e.g. when the launch fails).
+#[derive(Debug)]
+pub struct LaunchGuard {
+    instance_id: String,
+    armed: bool,
+}
+
+impl LaunchGuard {
+    pub fn instance_id(&self) -> &str {
+        &self.instance_id
+    }
+
+    /// Promotes the entry to `Running`; it stays registered until
+    /// `mark_exited` is called by the process monitor.
+    pub fn mark_running(mut self, pid: u32) {
+        if let Some(entry) = registry().get_mut(&self.instance_id) {
+            entry.state = ProcessState::Running;
+            entry.pid = Some(pid);
+        }
+        self.armed = false;
+    }
+}
+
+impl Drop for LaunchGuard {
+    fn drop(&mut self) {
+        if self.armed {
+            registry().remove(&self.instance_id);
+            log::info!(
+                "[ProcessRegistry] Released launch lock for {}",
+                self.instance_id
+            );
+        }
+    }
+}
+
+/// Registers an instance as launching. Fails if it is already launching or
+/// running, so the same instance can't be started twice.
+pub fn begin_launch(instance_id: &str) -> Result<LaunchGuard, String> {
+    let mut registry = registry();
+
+    if let Some(existing) = registry.get(instance_id) {
+        return Err(match existing.state {
+            ProcessState::Launching => "La instancia ya se está iniciando".to_string(),
+            ProcessState::Running => "La instancia ya está en ejecución".to_string(),
+        });
+    }
+
+    registry.insert(
+        instance_id.to_string(),
+        InstanceProcess {
+            instance_id: instance_id.to_string(),
+            state: ProcessState::Launching,
+            pid: None,
+            started_at: chrono::Utc::now().to_rfc3339(),
+        },
+    );
+
+    Ok(LaunchGuard {
+        instance_id: instance_id.to_string(),
+        armed: true,
+    })
+}
+
+/// Removes an instance from the registry once its process has exited.
+pub fn mark_exited(instance_id: &str) {
+    registry().remove(instance_id);
+}
+
+pub fn is_active(instance_id: &str) -> bool {
+    registry().contains_key(instance_id)
+}
+
+pub fn get_all() -> Vec<InstanceProcess> {
+    registry().values().cloned().collect()
+}
+
+#[tauri::command]
+pub fn get_running_instances() -> Vec<InstanceProcess> {
+    get_all()
+}
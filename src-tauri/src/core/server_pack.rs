@@ -0,0 +1,252 @@
+//! Server pack export.
+
+use crate::core::events::EventEmitter;
+use crate::core::instance_bootstrap::InstanceBootstrap;
+use crate::core::instance_manager::get_instance_by_id;
+use crate::core::minecraft_instance::MinecraftInstance;
+use crate::core::models::ModpackManifest;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+/// Carpetas de la instancia que también usa el servidor
+const SERVER_DIRS: &[&str] = &[
+    "config",
+    "defaultconfigs",
+    "kubejs",
+    "scripts",
+    "serverconfig",
+    "global_packs",
+];
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerPackReport {
+    pub path: String,
+    pub included_mods: usize,
+    /// Nombres de archivo de los mods excluidos por ser sólo de cliente
+    pub excluded_mods: Vec<String>,
+    pub server_file: String,
+}
+
+/// Mods flagged client-only in the modpack manifest, by file name.
+fn manifest_client_mods(instance_dir: &Path) -> HashSet<String> {
+    let Ok(Some(manifest)) = ModpackManifest::read(instance_dir) else {
+        return HashSet::new();
+    };
+
+    manifest
+        .files
+        .iter()
+        .filter(|entry| entry.is_client_only())
+        .map(|entry| entry.file_name().to_string())
+        .collect()
+}
+
+fn read_zip_entry(jar: &Path, name: &str) -> Option<String> {
+    let file = fs::File::open(jar).ok()?;
+    let mut archive = zip::ZipArchive::new(file).ok()?;
+    let mut entry = archive.by_name(name).ok()?;
+    let mut content = String::new();
+    entry.read_to_string(&mut content).ok()?;
+    Some(content)
+}
+
+/// Whether the mod declares itself client-only in its own metadata
+/// (`fabric.mod.json` environment or NeoForge's `clientSideOnly`).
+fn is_client_only_jar(jar: &Path) -> bool {
+    if let Some(content) = read_zip_entry(jar, "fabric.mod.json") {
+        let environment = serde_json::from_str::<Value>(&content)
+            .ok()
+            .and_then(|meta| meta.get("environment")?.as_str().map(str::to_string));
+        if environment.as_deref() == Some("client") {
+            return true;
+        }
+    }
+
+    ["META-INF/neoforge.mods.toml", "META-INF/mods.toml"]
+        .iter()
+        .filter_map(|name| read_zip_entry(jar, name))
+        .any(|toml| {
+            toml.lines().any(|line| {
+                let line = line.split('#').next().unwrap_or_default().replace(' ', "");
+                line == "clientSideOnly=true"
+            })
+        })
+}
+
+fn copy_dir(source: &Path, destination: &Path) -> Result<(), String> {
+    fs::create_dir_all(destination)
+        .map_err(|e| format!("Error al crear {}: {}", destination.display(), e))?;
+
+    for entry in fs::read_dir(source).map_err(|e| format!("Error al leer directorio: {}", e))? {
+        let entry = entry.map_err(|e| format!("Error al leer directorio: {}", e))?;
+        let target = destination.join(entry.file_name());
+        if entry.path().is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target)
+                .map_err(|e| format!("Error al copiar {}: {}", entry.path().display(), e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Copies the mods that also run on the server. Returns the number copied
+/// and the names of the excluded ones.
+fn copy_server_mods(
+    minecraft_dir: &Path,
+    server_dir: &Path,
+    client_mods: &HashSet<String>,
+) -> Result<(usize, Vec<String>), String> {
+    let mods_dir = minecraft_dir.join("mods");
+    let mut included = 0;
+    let mut excluded = Vec::new();
+    if !mods_dir.is_dir() {
+        return Ok((included, excluded));
+    }
+
+    let server_mods = server_dir.join("mods");
+    fs::create_dir_all(&server_mods)
+        .map_err(|e| format!("Error al crear {}: {}", server_mods.display(), e))?;
+
+    for entry in fs::read_dir(&mods_dir).map_err(|e| format!("Error al leer mods: {}", e))? {
+        let path = entry
+            .map_err(|e| format!("Error al leer mods: {}", e))?
+            .path();
+        let Some(name) = path.file_name().map(|n| n.to_string_lossy().to_string()) else {
+            continue;
+        };
+        if !path.is_file() || !name.ends_with(".jar") {
+            continue;
+        }
+
+        if client_mods.contains(&name) || is_client_only_jar(&path) {
+            log::info!("[ServerPack] Skipping client-only mod {}", name);
+            excluded.push(name);
+            continue;
+        }
+
+        fs::copy(&path, server_mods.join(&name))
+            .map_err(|e| format!("Error al copiar {}: {}", name, e))?;
+        included += 1;
+    }
+
+    excluded.sort();
+    Ok((included, excluded))
+}
+
+fn write_start_scripts(
+    instance: &MinecraftInstance,
+    server_dir: &Path,
+    server_file: &str,
+) -> Result<(), String> {
+    let memory = instance.memory.unwrap_or(4096);
+    // Con Forge el instalador prepara el servidor y deja sus propios scripts
+    let (script_name, command) = if instance.forgeVersion.is_some() {
+        (
+            "install-server",
+            format!("java -jar {} --installServer", server_file),
+        )
+    } else {
+        (
+            "start",
+            format!("java -Xmx{}M -jar {} nogui", memory, server_file),
+        )
+    };
+
+    let scripts = [
+        (
+            format!("{}.sh", script_name),
+            format!("#!/bin/sh\ncd \"$(dirname \"$0\")\"\n{}\n", command),
+        ),
+        (
+            format!("{}.bat", script_name),
+            format!("@echo off\r\ncd /d \"%~dp0\"\r\n{}\r\npause\r\n", command),
+        ),
+    ];
+    for (name, content) in scripts {
+        let path = server_dir.join(&name);
+        fs::write(&path, content).map_err(|e| format!("Error al escribir {}: {}", name, e))?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let _ = fs::set_permissions(&path, fs::Permissions::from_mode(0o755));
+        }
+    }
+    Ok(())
+}
+
+fn build_server_pack(
+    events: EventEmitter,
+    instance: &MinecraftInstance,
+    server_dir: &Path,
+) -> Result<ServerPackReport, String> {
+    let instance_dir = PathBuf::from(
+        instance
+            .instanceDirectory
+            .as_deref()
+            .ok_or_else(|| "La instancia no tiene directorio".to_string())?,
+    );
+    let minecraft_dir = instance_dir.join("minecraft");
+
+    if server_dir.exists()
+        && fs::read_dir(server_dir)
+            .map(|mut entries| entries.next().is_some())
+            .unwrap_or(false)
+    {
+        return Err("La carpeta de destino no está vacía".to_string());
+    }
+    fs::create_dir_all(server_dir)
+        .map_err(|e| format!("Error al crear {}: {}", server_dir.display(), e))?;
+
+    let client_mods = manifest_client_mods(&instance_dir);
+    let (included_mods, excluded_mods) =
+        copy_server_mods(&minecraft_dir, server_dir, &client_mods)?;
+
+    for dir in SERVER_DIRS {
+        let source = minecraft_dir.join(dir);
+        if source.is_dir() {
+            copy_dir(&source, &server_dir.join(dir))?;
+        }
+    }
+
+    let server_file = InstanceBootstrap::new(events).download_server_files(instance, server_dir)?;
+    write_start_scripts(instance, server_dir, &server_file)?;
+
+    Ok(ServerPackReport {
+        path: server_dir.to_string_lossy().to_string(),
+        included_mods,
+        excluded_mods,
+        server_file,
+    })
+}
+
+/// Exports a ready-to-run server folder for the instance into
+/// `destination`, which must be empty or not exist yet.
+#[tauri::command]
+pub async fn export_server_pack(
+    events: State<'_, EventEmitter>,
+    instance_id: String,
+    destination: String,
+) -> Result<ServerPackReport, String> {
+    let instance = get_instance_by_id(instance_id.clone())?
+        .ok_or_else(|| format!("Instance with ID {} not found", instance_id))?;
+    let events = events.inner().clone();
+
+    tokio::task::spawn_blocking(move || {
+        let server_dir = PathBuf::from(destination);
+        let result = build_server_pack(events, &instance, &server_dir);
+        if let Err(e) = &result {
+            log::error!("[ServerPack] Export failed: {}", e);
+        }
+        result
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,387 @@
+//! Minecraft Server List Ping.
+
+use hickory_resolver::Resolver;
+use serde::Serialize;
+use serde_json::Value;
+use std::cmp::Reverse;
+use std::io::{self, Read, Write};
+use std::net::{IpAddr, SocketAddr, TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+const DEFAULT_PORT: u16 = 25565;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const IO_TIMEOUT: Duration = Duration::from_secs(5);
+/// El favicon viaja en base64 dentro del JSON; nada legítimo se acerca a esto
+const MAX_PACKET_BYTES: usize = 4 * 1024 * 1024;
+/// Versión de protocolo anunciada; el servidor responde igual con la suya
+const HANDSHAKE_PROTOCOL: i32 = -1;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerSample {
+    pub name: String,
+    pub id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerStatus {
+    /// Dirección normalizada `host:puerto`
+    pub address: String,
+    pub latency_ms: u64,
+    pub version_name: Option<String>,
+    pub protocol: Option<i64>,
+    pub players_online: Option<i64>,
+    pub players_max: Option<i64>,
+    pub player_sample: Vec<PlayerSample>,
+    /// MOTD en texto plano, sin códigos de formato
+    pub motd: String,
+    /// MOTD original (texto o componente de chat) para mostrarlo con formato
+    pub motd_raw: Value,
+    /// `data:image/png;base64,...`
+    pub favicon: Option<String>,
+    /// Respondió sólo al ping antiguo (servidores anteriores a 1.7)
+    pub legacy: bool,
+}
+
+/// Splits `host[:port]`, including bracketed IPv6 (`[::1]:25565`). The port
+/// is `None` when the address doesn't give one.
+fn parse_address(address: &str) -> Result<(String, Option<u16>), String> {
+    let address = address.trim();
+    if address.is_empty() {
+        return Err("La dirección del servidor está vacía".to_string());
+    }
+
+    let (host, port) = if let Some(rest) = address.strip_prefix('[') {
+        let (host, rest) = rest
+            .split_once(']')
+            .ok_or_else(|| format!("Dirección no válida: {}", address))?;
+        (host, rest.strip_prefix(':'))
+    } else {
+        match address.rsplit_once(':') {
+            // Más de un ':' sin corchetes es una IPv6 sin puerto
+            Some((host, port)) if !host.contains(':') => (host, Some(port)),
+            _ => (address, None),
+        }
+    };
+
+    let port = port
+        .map(|port| {
+            port.parse::<u16>()
+                .map_err(|_| format!("Puerto no válido: {}", port))
+        })
+        .transpose()?;
+    Ok((host.to_string(), port))
+}
+
+/// Host and port of the `_minecraft._tcp` SRV record of `host`, if it has
+/// one. Picks the lowest priority and, among those, the highest weight.
+fn resolve_srv(host: &str) -> Option<(String, u16)> {
+    // Una IP no tiene registros SRV
+    if host.parse::<IpAddr>().is_ok() {
+        return None;
+    }
+    let resolver = Resolver::from_system_conf()
+        .map_err(|e| log::warn!("[ServerPing] DNS resolver unavailable: {}", e))
+        .ok()?;
+    let lookup = resolver
+        .srv_lookup(format!("_minecraft._tcp.{}", host))
+        .ok()?;
+    let record = lookup
+        .iter()
+        .min_by_key(|record| (record.priority(), Reverse(record.weight())))?;
+    let target = record.target().to_utf8();
+    let target = target.trim_end_matches('.');
+    log::info!(
+        "[ServerPing] SRV record for {}: {}:{}",
+        host,
+        target,
+        record.port()
+    );
+    Some((target.to_string(), record.port()))
+}
+
+fn connect(host: &str, port: u16) -> Result<TcpStream, String> {
+    let addresses: Vec<SocketAddr> = (host, port)
+        .to_socket_addrs()
+        .map_err(|e| format!("No se pudo resolver {}: {}", host, e))?
+        .collect();
+
+    let mut last_error = format!("No se pudo resolver {}", host);
+    for address in addresses {
+        match TcpStream::connect_timeout(&address, CONNECT_TIMEOUT) {
+            Ok(stream) => {
+                stream
+                    .set_read_timeout(Some(IO_TIMEOUT))
+                    .and_then(|_| stream.set_write_timeout(Some(IO_TIMEOUT)))
+                    .map_err(|e| e.to_string())?;
+                let _ = stream.set_nodelay(true);
+                return Ok(stream);
+            }
+            Err(e) => last_error = format!("No se pudo conectar a {}:{}: {}", host, port, e),
+        }
+    }
+    Err(last_error)
+}
+
+fn write_varint(buf: &mut Vec<u8>, value: i32) {
+    let mut value = value as u32;
+    loop {
+        if value & !0x7F == 0 {
+            buf.push(value as u8);
+            return;
+        }
+        buf.push(((value & 0x7F) | 0x80) as u8);
+        value >>= 7;
+    }
+}
+
+fn read_varint(reader: &mut impl Read) -> io::Result<i32> {
+    let mut result = 0u32;
+    for position in 0..5 {
+        let mut byte = [0u8; 1];
+        reader.read_exact(&mut byte)?;
+        result |= ((byte[0] & 0x7F) as u32) << (7 * position);
+        if byte[0] & 0x80 == 0 {
+            return Ok(result as i32);
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        "VarInt too long",
+    ))
+}
+
+fn write_string(buf: &mut Vec<u8>, value: &str) {
+    write_varint(buf, value.len() as i32);
+    buf.extend_from_slice(value.as_bytes());
+}
+
+fn send_packet(stream: &mut TcpStream, id: i32, payload: &[u8]) -> io::Result<()> {
+    let mut body = Vec::with_capacity(payload.len() + 5);
+    write_varint(&mut body, id);
+    body.extend_from_slice(payload);
+
+    let mut packet = Vec::with_capacity(body.len() + 5);
+    write_varint(&mut packet, body.len() as i32);
+    packet.extend_from_slice(&body);
+    stream.write_all(&packet)
+}
+
+/// Reads one packet and returns `(id, payload)`.
+fn read_packet(stream: &mut TcpStream) -> io::Result<(i32, Vec<u8>)> {
+    let length = read_varint(stream)?;
+    if length <= 0 || length as usize > MAX_PACKET_BYTES {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Invalid packet length {}", length),
+        ));
+    }
+    let mut body = vec![0u8; length as usize];
+    stream.read_exact(&mut body)?;
+
+    let mut cursor = io::Cursor::new(body);
+    let id = read_varint(&mut cursor)?;
+    let offset = cursor.position() as usize;
+    let mut body = cursor.into_inner();
+    body.drain(..offset);
+    Ok((id, body))
+}
+
+/// Text of a chat component (or plain string) without `§` format codes.
+fn plain_text(component: &Value) -> String {
+    fn collect(component: &Value, out: &mut String) {
+        match component {
+            Value::String(text) => out.push_str(text),
+            Value::Array(parts) => parts.iter().for_each(|part| collect(part, out)),
+            Value::Object(object) => {
+                if let Some(text) = object.get("text") {
+                    collect(text, out);
+                }
+                if let Some(extra) = object.get("extra") {
+                    collect(extra, out);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut text = String::new();
+    collect(component, &mut text);
+    strip_format_codes(&text)
+}
+
+fn strip_format_codes(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '§' {
+            chars.next();
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn status_from_json(address: String, latency_ms: u64, json: &Value) -> ServerStatus {
+    let players = json.get("players");
+    let motd_raw = json.get("description").cloned().unwrap_or(Value::Null);
+
+    ServerStatus {
+        address,
+        latency_ms,
+        version_name: json["version"]["name"].as_str().map(strip_format_codes),
+        protocol: json["version"]["protocol"].as_i64(),
+        players_online: players.and_then(|p| p["online"].as_i64()),
+        players_max: players.and_then(|p| p["max"].as_i64()),
+        player_sample: players
+            .and_then(|p| p["sample"].as_array())
+            .map(|sample| {
+                sample
+                    .iter()
+                    .filter_map(|player| {
+                        Some(PlayerSample {
+                            name: strip_format_codes(player["name"].as_str()?),
+                            id: player["id"].as_str().map(str::to_string),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        motd: plain_text(&motd_raw),
+        motd_raw,
+        favicon: json["favicon"].as_str().map(str::to_string),
+        legacy: false,
+    }
+}
+
+/// 1.7+ status: handshake, status request, then ping/pong for latency.
+fn modern_ping(host: &str, port: u16) -> Result<ServerStatus, String> {
+    let mut stream = connect(host, port)?;
+    let io_error = |e: io::Error| format!("Error de comunicación con el servidor: {}", e);
+
+    let mut handshake = Vec::new();
+    write_varint(&mut handshake, HANDSHAKE_PROTOCOL);
+    write_string(&mut handshake, host);
+    handshake.extend_from_slice(&port.to_be_bytes());
+    write_varint(&mut handshake, 1); // siguiente estado: status
+    send_packet(&mut stream, 0x00, &handshake).map_err(io_error)?;
+
+    let request_sent = Instant::now();
+    send_packet(&mut stream, 0x00, &[]).map_err(io_error)?;
+    let (id, payload) = read_packet(&mut stream).map_err(io_error)?;
+    let status_rtt = request_sent.elapsed();
+    if id != 0x00 {
+        return Err(format!(
+            "Respuesta inesperada del servidor (paquete {})",
+            id
+        ));
+    }
+
+    let mut cursor = io::Cursor::new(payload);
+    let length = read_varint(&mut cursor).map_err(io_error)? as usize;
+    let start = cursor.position() as usize;
+    let payload = cursor.into_inner();
+    let raw = payload
+        .get(start..start + length)
+        .ok_or_else(|| "Respuesta del servidor incompleta".to_string())?;
+    let json: Value = serde_json::from_slice(raw)
+        .map_err(|e| format!("Respuesta del servidor no válida: {}", e))?;
+
+    // Algunos proxies cierran la conexión sin contestar al ping; en ese caso
+    // vale el tiempo de la petición de estado
+    let ping_sent = Instant::now();
+    let timestamp = chrono::Utc::now().timestamp_millis();
+    let latency = match send_packet(&mut stream, 0x01, &timestamp.to_be_bytes())
+        .and_then(|_| read_packet(&mut stream))
+    {
+        Ok((0x01, _)) => ping_sent.elapsed(),
+        _ => status_rtt,
+    };
+
+    Ok(status_from_json(
+        format!("{}:{}", host, port),
+        latency.as_millis() as u64,
+        &json,
+    ))
+}
+
+/// Pre-1.7 ping (`0xFE 0x01`); answers `§1\0protocol\0version\0motd\0online\0max`.
+fn legacy_ping(host: &str, port: u16) -> Result<ServerStatus, String> {
+    let mut stream = connect(host, port)?;
+    let io_error = |e: io::Error| format!("Error de comunicación con el servidor: {}", e);
+
+    let sent = Instant::now();
+    stream.write_all(&[0xFE, 0x01]).map_err(io_error)?;
+
+    let mut header = [0u8; 3];
+    stream.read_exact(&mut header).map_err(io_error)?;
+    let latency = sent.elapsed();
+    if header[0] != 0xFF {
+        return Err("El servidor no respondió al ping".to_string());
+    }
+
+    let length = u16::from_be_bytes([header[1], header[2]]) as usize;
+    let mut raw = vec![0u8; length * 2];
+    stream.read_exact(&mut raw).map_err(io_error)?;
+    let units: Vec<u16> = raw
+        .chunks_exact(2)
+        .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+        .collect();
+    let text = String::from_utf16_lossy(&units);
+
+    let fields: Vec<&str> = text.split('\0').collect();
+    // Servidores beta: sólo `motd§online§max`
+    let (protocol, version, motd, online, max) = match fields.as_slice() {
+        ["§1", protocol, version, motd, online, max, ..] => {
+            (protocol.parse().ok(), Some(*version), *motd, *online, *max)
+        }
+        _ => {
+            let mut parts = text.rsplitn(3, '§');
+            let max = parts.next().unwrap_or_default();
+            let online = parts.next().unwrap_or_default();
+            (None, None, parts.next().unwrap_or_default(), online, max)
+        }
+    };
+
+    Ok(ServerStatus {
+        address: format!("{}:{}", host, port),
+        latency_ms: latency.as_millis() as u64,
+        version_name: version.map(str::to_string),
+        protocol,
+        players_online: online.parse().ok(),
+        players_max: max.parse().ok(),
+        player_sample: Vec::new(),
+        motd: strip_format_codes(motd),
+        motd_raw: Value::String(motd.to_string()),
+        favicon: None,
+        legacy: true,
+    })
+}
+
+pub fn ping_server(address: &str) -> Result<ServerStatus, String> {
+    let (host, port) = match parse_address(address)? {
+        (host, Some(port)) => (host, port),
+        (host, None) => resolve_srv(&host).unwrap_or((host, DEFAULT_PORT)),
+    };
+    modern_ping(&host, port).or_else(|e| {
+        log::info!(
+            "[ServerPing] Modern ping to {}:{} failed ({}), trying legacy ping",
+            host,
+            port,
+            e
+        );
+        // Si el ping antiguo también falla, el error útil es el primero
+        legacy_ping(&host, port).map_err(|_| e)
+    })
+}
+
+/// Status of a Minecraft server (`host` or `host:port`) for the prelaunch
+/// screen.
+#[tauri::command]
+pub async fn ping_minecraft_server(address: String) -> Result<ServerStatus, String> {
+    tokio::task::spawn_blocking(move || ping_server(&address))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,153 @@
+//! Background keeper for the Modpack Store session.
+
+use crate::core::api_client::ApiClient;
+use crate::core::auth::{self, AuthState, UserSession};
+use crate::core::events::{self, LauncherEvent};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::Manager;
+
+const TICK_INTERVAL: Duration = Duration::from_secs(60);
+/// Con la sesión sana sólo se valida contra la API cada N ticks
+const VALIDATE_EVERY_TICKS: u32 = 5;
+/// Renovar el token si caduca en menos de este margen
+const REFRESH_MARGIN_SECS: i64 = 5 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SessionHealth {
+    /// No session stored (or not checked yet).
+    Unknown,
+    Active,
+    /// The API could not be reached; the session may still be valid.
+    Offline,
+    Expired,
+}
+
+struct SessionKeeper {
+    app_handle: tauri::AppHandle,
+    health: SessionHealth,
+    ticks_since_validation: u32,
+}
+
+/// Spawns the keeper loop. Call once from the app setup.
+pub fn start(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut keeper = SessionKeeper {
+            app_handle,
+            health: SessionHealth::Unknown,
+            ticks_since_validation: 0,
+        };
+
+        loop {
+            tokio::time::sleep(TICK_INTERVAL).await;
+            keeper.tick().await;
+        }
+    });
+}
+
+/// `exp` claim of a JWT access token, if it is one.
+fn token_expiry(access_token: &str) -> Option<i64> {
+    let payload = access_token.split('.').nth(1)?;
+    let decoded = URL_SAFE_NO_PAD.decode(payload.trim_end_matches('=')).ok()?;
+    let claims: serde_json::Value = serde_json::from_slice(&decoded).ok()?;
+    claims.get("exp").and_then(|exp| exp.as_i64())
+}
+
+impl SessionKeeper {
+    async fn tick(&mut self) {
+        let tokens = match auth::load_tokens_from_store(&self.app_handle).await {
+            Ok(Some(tokens)) => tokens,
+            Ok(None) => {
+                // Logout o sesión nunca iniciada: nada que vigilar
+                if self.health != SessionHealth::Expired {
+                    self.health = SessionHealth::Unknown;
+                }
+                return;
+            }
+            Err(e) => {
+                log::warn!("[SessionKeeper] Could not load tokens: {}", e);
+                return;
+            }
+        };
+
+        if let Some(expires_at) = token_expiry(&tokens.access_token) {
+            if expires_at - chrono::Utc::now().timestamp() < REFRESH_MARGIN_SECS {
+                log::info!("[SessionKeeper] Access token about to expire, refreshing");
+                if let Err(e) =
+                    auth::refresh_session_tokens(&self.app_handle, &tokens.access_token).await
+                {
+                    log::warn!("[SessionKeeper] Proactive refresh failed: {}", e);
+                }
+            }
+        }
+
+        self.ticks_since_validation += 1;
+        let due = self.ticks_since_validation >= VALIDATE_EVERY_TICKS;
+        // Sin conexión se reintenta en cada tick para detectar la recuperación
+        if due || self.health != SessionHealth::Active {
+            self.ticks_since_validation = 0;
+            self.validate().await;
+        }
+    }
+
+    async fn validate(&mut self) {
+        let response = ApiClient::new(self.app_handle.clone())
+            .get("/auth/me")
+            .await;
+
+        let response = match response {
+            Ok(response) => response,
+            Err(e) => {
+                // Si la renovación fue rechazada, los tokens ya no existen
+                let tokens = auth::load_tokens_from_store(&self.app_handle).await;
+                if matches!(tokens, Ok(None)) {
+                    self.set_expired();
+                } else {
+                    log::warn!("[SessionKeeper] API unreachable: {}", e);
+                    self.health = SessionHealth::Offline;
+                }
+                return;
+            }
+        };
+
+        let status = response.status();
+        if status.is_success() {
+            let user = match response.json::<UserSession>().await {
+                Ok(user) => user,
+                Err(e) => {
+                    log::warn!("[SessionKeeper] Invalid session payload: {}", e);
+                    return;
+                }
+            };
+
+            let previous = self.health;
+            self.health = SessionHealth::Active;
+
+            let auth_state = self.app_handle.state::<Arc<AuthState>>();
+            *auth_state.session.lock().await = Some(user.clone());
+
+            if matches!(previous, SessionHealth::Offline | SessionHealth::Expired) {
+                log::info!("[SessionKeeper] Session restored");
+                events::emit(LauncherEvent::SessionRestored(user));
+            }
+        } else if status.is_server_error() {
+            log::warn!("[SessionKeeper] API error {}, will retry", status);
+            self.health = SessionHealth::Offline;
+        } else {
+            // 401 incluso tras renovar, o 403: la sesión ya no vale
+            auth::expire_session(&self.app_handle).await;
+            self.set_expired();
+        }
+    }
+
+    fn set_expired(&mut self) {
+        if self.health == SessionHealth::Expired {
+            return;
+        }
+        log::info!("[SessionKeeper] Session expired");
+        self.health = SessionHealth::Expired;
+        events::emit(LauncherEvent::SessionExpired);
+    }
+}
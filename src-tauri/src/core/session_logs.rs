@@ -0,0 +1,285 @@
+//! Per-session copies of the game's stdout/stderr.
+
+use crate::core::instance_manager::get_instance_by_id;
+use crate::core::minecraft_instance::MinecraftInstance;
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+
+/// Sesiones que se conservan por instancia
+const MAX_SESSIONS: usize = 20;
+
+/// Nombre de cada archivo, p. ej. `2024-05-01_18-30-00.log`
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d_%H-%M-%S";
+const TIMESTAMP_LEN: usize = "2024-05-01_18-30-00".len();
+/// Sufijo del archivo de stderr de cada sesión
+const STDERR_SUFFIX: &str = ".stderr.log";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionLogInfo {
+    pub file_name: String,
+    pub path: String,
+    /// stderr de la sesión, si existe
+    pub stderr_path: Option<String>,
+    /// RFC 3339, de la fecha del nombre del archivo
+    pub started_at: Option<String>,
+    pub size_bytes: u64,
+    /// Código de salida, si la sesión terminó con el launcher abierto
+    pub exit_code: Option<i32>,
+}
+
+/// Log files of one session. The game writes to them directly; the
+/// launcher only adds a header and the exit code to the stdout file.
+pub struct SessionLog {
+    path: PathBuf,
+    stderr_path: PathBuf,
+    /// Donde empieza la salida del juego (después de la cabecera)
+    output_start: u64,
+    /// Abierto en modo append: nunca pisa lo que escribió el juego
+    file: Mutex<File>,
+}
+
+fn sessions_dir(instance: &MinecraftInstance) -> Option<PathBuf> {
+    let instance_dir = instance.instanceDirectory.as_deref()?;
+    // El id acaba en el nombre de la carpeta
+    let folder: String = instance
+        .instanceId
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    Some(
+        Path::new(instance_dir)
+            .join("logs")
+            .join("launcher-sessions")
+            .join(folder),
+    )
+}
+
+fn stderr_path_of(path: &Path) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    path.with_file_name(format!("{}{}", stem, STDERR_SUFFIX))
+}
+
+/// Start time and counter of a session file: `<stamp>.log` is 0 and
+/// `<stamp>-2.log` is 2, so sessions of the same second keep their order.
+fn session_key(path: &Path) -> (String, u32) {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let stamp = stem.get(..TIMESTAMP_LEN).unwrap_or(&stem).to_string();
+    let counter = stem
+        .get(TIMESTAMP_LEN..)
+        .and_then(|rest| rest.strip_prefix('-'))
+        .and_then(|counter| counter.parse().ok())
+        .unwrap_or(0);
+    (stamp, counter)
+}
+
+/// Session logs (stdout files) of the directory, newest first.
+fn list_logs(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut logs: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "log"))
+        .filter(|path| {
+            !path
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().ends_with(STDERR_SUFFIX))
+        })
+        .collect();
+    logs.sort_by_key(|path| Reverse(session_key(path)));
+    logs
+}
+
+/// Removes the oldest sessions so that, with the one about to start,
+/// `MAX_SESSIONS` are kept.
+fn rotate(dir: &Path) {
+    for old in list_logs(dir).into_iter().skip(MAX_SESSIONS - 1) {
+        for path in [stderr_path_of(&old), old] {
+            match fs::remove_file(&path) {
+                Err(e) if e.kind() != ErrorKind::NotFound => log::warn!(
+                    "[SessionLogs] Could not remove old session log {}: {}",
+                    path.display(),
+                    e
+                ),
+                _ => {}
+            }
+        }
+    }
+}
+
+fn create_new(path: &Path) -> io::Result<File> {
+    OpenOptions::new().append(true).create_new(true).open(path)
+}
+
+impl SessionLog {
+    /// Creates the log files of a new session of the instance. Failing to
+    /// create them never stops the launch.
+    pub fn create(instance: &MinecraftInstance) -> Option<Arc<Self>> {
+        let dir = sessions_dir(instance)?;
+        if let Err(e) = fs::create_dir_all(&dir) {
+            log::warn!("[SessionLogs] Could not create {}: {}", dir.display(), e);
+            return None;
+        }
+        rotate(&dir);
+
+        let now = chrono::Local::now();
+        let stamp = now.format(TIMESTAMP_FORMAT).to_string();
+        // Dos lanzamientos en el mismo segundo no se pisan
+        let mut path = dir.join(format!("{}.log", stamp));
+        let mut suffix = 1;
+        let mut file = loop {
+            match create_new(&path) {
+                Ok(file) => break file,
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    path = dir.join(format!("{}-{}.log", stamp, suffix));
+                    suffix += 1;
+                }
+                Err(e) => {
+                    log::warn!("[SessionLogs] Could not create {}: {}", path.display(), e);
+                    return None;
+                }
+            }
+        };
+        let stderr_path = stderr_path_of(&path);
+        if let Err(e) = File::create(&stderr_path) {
+            log::warn!(
+                "[SessionLogs] Could not create {}: {}",
+                stderr_path.display(),
+                e
+            );
+            return None;
+        }
+
+        let header = format!(
+            "[launcher] {} ({}) - Minecraft {} - {}\n",
+            instance.instanceName,
+            instance.instanceId,
+            instance.minecraftVersion,
+            now.to_rfc3339()
+        );
+        if let Err(e) = file.write_all(header.as_bytes()) {
+            log::warn!("[SessionLogs] Could not write {}: {}", path.display(), e);
+            return None;
+        }
+
+        Some(Arc::new(Self {
+            path,
+            stderr_path,
+            output_start: header.len() as u64,
+            file: Mutex::new(file),
+        }))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn stderr_path(&self) -> &Path {
+        &self.stderr_path
+    }
+
+    /// Offset of the stdout file where the game's output begins.
+    pub fn output_start(&self) -> u64 {
+        self.output_start
+    }
+
+    /// Handles for the game's stdout and stderr, appending to the session
+    /// files. `None` if they can't be opened; the game then uses pipes.
+    pub fn child_stdio(&self) -> Option<(Stdio, Stdio)> {
+        let open = |path: &Path| match OpenOptions::new().append(true).open(path) {
+            Ok(file) => Some(Stdio::from(file)),
+            Err(e) => {
+                log::warn!("[SessionLogs] Could not open {}: {}", path.display(), e);
+                None
+            }
+        };
+        Some((open(&self.path)?, open(&self.stderr_path)?))
+    }
+
+    /// Marks the end of the session with the game's exit code.
+    pub fn finish(&self, exit_code: Option<i32>) {
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        let _ = match exit_code {
+            Some(code) => writeln!(file, "[launcher] Exit code: {}", code),
+            None => writeln!(file, "[launcher] Exit code: unknown"),
+        };
+        let _ = file.flush();
+    }
+}
+
+/// Exit code written by `SessionLog::finish`, looking only at the end of
+/// the file.
+fn read_exit_code(path: &Path) -> Option<i32> {
+    let mut file = File::open(path).ok()?;
+    let len = file.metadata().ok()?.len();
+    file.seek(SeekFrom::Start(len.saturating_sub(256))).ok()?;
+    let mut tail = Vec::new();
+    file.read_to_end(&mut tail).ok()?;
+    String::from_utf8_lossy(&tail)
+        .lines()
+        .rev()
+        .find_map(|line| line.strip_prefix("[launcher] Exit code: "))
+        .and_then(|code| code.trim().parse().ok())
+}
+
+fn session_info(path: &Path) -> SessionLogInfo {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let started_at = path
+        .file_stem()
+        .and_then(|stem| {
+            let stem = stem.to_string_lossy();
+            let stamp = stem.get(..TIMESTAMP_LEN)?;
+            chrono::NaiveDateTime::parse_from_str(stamp, TIMESTAMP_FORMAT).ok()
+        })
+        .and_then(|date| date.and_local_timezone(chrono::Local).single())
+        .map(|date| date.to_rfc3339());
+
+    let stderr_path = stderr_path_of(path);
+    SessionLogInfo {
+        file_name,
+        path: path.display().to_string(),
+        stderr_path: stderr_path
+            .exists()
+            .then(|| stderr_path.display().to_string()),
+        started_at,
+        size_bytes: fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+        exit_code: read_exit_code(path),
+    }
+}
+
+/// Recent game sessions of the instance, newest first.
+#[tauri::command]
+pub fn get_instance_session_logs(instance_id: String) -> Result<Vec<SessionLogInfo>, String> {
+    let instance = get_instance_by_id(instance_id.clone())?
+        .ok_or_else(|| format!("Instance with ID {} not found", instance_id))?;
+
+    let Some(dir) = sessions_dir(&instance) else {
+        return Ok(Vec::new());
+    };
+    Ok(list_logs(&dir)
+        .iter()
+        .map(|path| session_info(path))
+        .collect())
+}
@@ -0,0 +1,185 @@
+//! Watchdog for game startups that hang before the window opens.
+
+use crate::config::with_config;
+use crate::core::events::{self, EventEmitter, LauncherEvent};
+use crate::core::instance_manager::{self, get_instance_by_id};
+use crate::core::minecraft::LaunchOptions;
+use crate::core::process_output::OutputWatch;
+use crate::core::process_registry;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, System};
+use tauri::State;
+
+/// Líneas que el juego escribe al crear la ventana o arrancar el sonido
+pub const READY_MARKERS: &[&str] = &[
+    // 1.13+
+    "Backend library: LWJGL version",
+    // 1.12 y anteriores
+    "LWJGL Version:",
+    "Sound engine started",
+    "OpenAL initialized",
+];
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// Líneas de cada stream que se envían con el aviso
+const EXCERPT_LINES: usize = 40;
+/// Lo que se espera a que el monitor vea salir el proceso tras matarlo
+const EXIT_WAIT: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchStalled {
+    pub instance_id: String,
+    pub pid: u32,
+    pub timeout_secs: u64,
+    /// Desde que se inició el proceso
+    pub elapsed_secs: u64,
+    /// Últimas líneas de stdout y stderr
+    pub excerpt: String,
+}
+
+/// Arranque bloqueado a la espera de que el usuario decida reiniciarlo
+struct StalledLaunch {
+    pid: u32,
+    options: LaunchOptions,
+}
+
+/// Bloqueos detectados por instancia; cada lanzamiento borra el anterior
+static STALLED: Lazy<Mutex<HashMap<String, StalledLaunch>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Whether `pid` is still the registered process of the instance (it may
+/// have exited, or the instance may have been launched again since).
+fn is_current(instance_id: &str, pid: u32) -> bool {
+    process_registry::get_all()
+        .iter()
+        .any(|process| process.instance_id == instance_id && process.pid == Some(pid))
+}
+
+/// Watches the startup of process `pid` of the instance on its own thread.
+/// `options` are the ones it was launched with, reused if it has to be
+/// restarted. Does nothing when the watchdog is disabled.
+pub fn spawn(instance_id: String, pid: u32, options: LaunchOptions, output: OutputWatch) {
+    STALLED
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&instance_id);
+
+    let Some(timeout) = with_config(|config| config.get_startup_watchdog_timeout())
+        .ok()
+        .flatten()
+    else {
+        return;
+    };
+
+    thread::spawn(move || {
+        let started = Instant::now();
+        let mut last_progress = started;
+        let mut received = output.received_lines();
+
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            if !is_current(&instance_id, pid) {
+                return;
+            }
+            if READY_MARKERS.iter().any(|marker| output.has_seen(marker)) {
+                log::info!(
+                    "[Watchdog: {}] Game ready after {}s",
+                    instance_id,
+                    started.elapsed().as_secs()
+                );
+                return;
+            }
+
+            let now_received = output.received_lines();
+            if now_received != received {
+                received = now_received;
+                last_progress = Instant::now();
+                continue;
+            }
+            if last_progress.elapsed() < timeout {
+                continue;
+            }
+
+            log::warn!(
+                "[Watchdog: {}] No output for {}s before the game window opened",
+                instance_id,
+                timeout.as_secs()
+            );
+            STALLED
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(instance_id.clone(), StalledLaunch { pid, options });
+            events::emit(LauncherEvent::InstanceLaunchStalled(LaunchStalled {
+                instance_id,
+                pid,
+                timeout_secs: timeout.as_secs(),
+                elapsed_secs: started.elapsed().as_secs(),
+                excerpt: output.excerpt(EXCERPT_LINES),
+            }));
+            return;
+        }
+    });
+}
+
+fn kill_process(pid: u32) -> Result<(), String> {
+    let pid = Pid::from_u32(pid);
+    let mut system = System::new();
+    if !system.refresh_process(pid) {
+        // Ya había terminado
+        return Ok(());
+    }
+    match system.process(pid) {
+        Some(process) if !process.kill() => {
+            Err("No se pudo detener el proceso del juego".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+fn restart(events: EventEmitter, instance_id: &str) -> Result<(), String> {
+    let stalled = STALLED
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(instance_id)
+        .ok_or_else(|| "No se detectó ningún bloqueo en el arranque de la instancia".to_string())?;
+
+    if is_current(instance_id, stalled.pid) {
+        log::info!(
+            "[Watchdog: {}] Killing stalled process {}",
+            instance_id,
+            stalled.pid
+        );
+        kill_process(stalled.pid)?;
+
+        // El monitor libera la instancia cuando ve salir el proceso
+        let deadline = Instant::now() + EXIT_WAIT;
+        while process_registry::is_active(instance_id) {
+            if Instant::now() > deadline {
+                return Err("El juego no terminó a tiempo tras detenerlo".to_string());
+            }
+            thread::sleep(Duration::from_millis(200));
+        }
+    }
+
+    let instance = get_instance_by_id(instance_id.to_string())?
+        .ok_or_else(|| format!("Instance with ID {} not found", instance_id))?;
+    instance_manager::start_launch(events, &instance, stalled.options)
+}
+
+/// Kills the (stalled) game process of the instance and launches it again.
+#[tauri::command]
+pub async fn restart_stalled_instance(
+    events: State<'_, EventEmitter>,
+    instance_id: String,
+) -> Result<(), String> {
+    let events = events.inner().clone();
+    tokio::task::spawn_blocking(move || restart(events, &instance_id))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
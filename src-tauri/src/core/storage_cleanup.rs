@@ -0,0 +1,577 @@
+//! Launcher-wide storage cleanup.
+
+use crate::core::download_cache;
+use crate::core::instance_manager::get_all_instances;
+use crate::core::minecraft::paths::{self, MinecraftPaths};
+use crate::core::minecraft::{ManifestParser, MavenCoordinate};
+use crate::core::minecraft_instance::MinecraftInstance;
+use crate::core::process_registry;
+use crate::core::tasks_manager;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Los logs más antiguos que esto se consideran prescindibles
+const STALE_LOG_AGE: Duration = Duration::from_secs(14 * 24 * 60 * 60);
+/// Logs que el juego o el launcher siguen usando
+const ACTIVE_LOGS: &[&str] = &["latest.log", "debug.log"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StorageCategory {
+    OrphanedLibraries,
+    OrphanedAssets,
+    ForgeInstallers,
+    OldNatives,
+    StaleLogs,
+    DownloadCache,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageItem {
+    pub path: String,
+    pub bytes: u64,
+    pub files: u64,
+    pub instance_id: Option<String>,
+    pub instance_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageCategoryReport {
+    pub category: StorageCategory,
+    pub bytes: u64,
+    pub files: u64,
+    pub items: Vec<StorageItem>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageReport {
+    pub total_bytes: u64,
+    pub categories: Vec<StorageCategoryReport>,
+    /// Instancias en ejecución o con tareas activas, que no se analizaron
+    pub skipped_instances: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanStorageOptions {
+    pub categories: Vec<StorageCategory>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanStorageReport {
+    pub removed_files: u64,
+    pub reclaimed_bytes: u64,
+    /// Rutas que no se pudieron borrar, con el motivo
+    pub failed: Vec<String>,
+}
+
+/// Something that can be deleted, plus the item shown for it in the report.
+struct Candidate {
+    category: StorageCategory,
+    item: StorageItem,
+    /// Archivos o carpetas a borrar
+    targets: Vec<PathBuf>,
+}
+
+struct Analysis {
+    candidates: Vec<Candidate>,
+    skipped_instances: Vec<String>,
+}
+
+/// Bytes and files under `path` (a file or a folder), without following links.
+fn measure(path: &Path) -> (u64, u64) {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return (0, 0);
+    };
+    if !metadata.is_dir() {
+        return (metadata.len(), 1);
+    }
+
+    let (mut bytes, mut files) = (0, 0);
+    let mut pending = vec![path.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                pending.push(entry.path());
+            } else if metadata.is_file() {
+                bytes += metadata.len();
+                files += 1;
+            }
+        }
+    }
+    (bytes, files)
+}
+
+fn candidate(
+    category: StorageCategory,
+    path: &Path,
+    targets: Vec<PathBuf>,
+    instance: Option<&MinecraftInstance>,
+) -> Candidate {
+    let (bytes, files) = targets
+        .iter()
+        .map(|target| measure(target))
+        .fold((0, 0), |(b, f), (bytes, files)| (b + bytes, f + files));
+    Candidate {
+        category,
+        item: StorageItem {
+            path: path.to_string_lossy().to_string(),
+            bytes,
+            files,
+            instance_id: instance.map(|i| i.instanceId.clone()),
+            instance_name: instance.map(|i| i.instanceName.clone()),
+        },
+        targets,
+    }
+}
+
+/// Files directly inside `dir`.
+fn files_in(dir: &Path) -> Vec<(PathBuf, fs::Metadata)> {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|entry| Some((entry.path(), entry.metadata().ok()?)))
+                .filter(|(_, metadata)| metadata.is_file())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn subdirs(dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.metadata().is_ok_and(|metadata| metadata.is_dir()))
+                .map(|entry| entry.path())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// `group/artifact/version` folder of every library in the manifest,
+/// whatever its rules say.
+fn referenced_library_dirs(manifest: &Value) -> HashSet<String> {
+    let Some(libraries) = manifest.get("libraries").and_then(Value::as_array) else {
+        return HashSet::new();
+    };
+
+    let mut dirs = HashSet::new();
+    for library in libraries {
+        if let Some(name) = library.get("name").and_then(Value::as_str) {
+            if let Some(coordinate) = MavenCoordinate::parse(name) {
+                dirs.insert(coordinate.directory());
+            }
+        }
+
+        let downloads = library.get("downloads");
+        let artifact = downloads.and_then(|d| d.get("artifact"));
+        let classifiers = downloads
+            .and_then(|d| d.get("classifiers"))
+            .and_then(Value::as_object)
+            .into_iter()
+            .flat_map(|classifiers| classifiers.values());
+        for download in artifact.into_iter().chain(classifiers) {
+            let Some(path) = download.get("path").and_then(Value::as_str) else {
+                continue;
+            };
+            let path = path.replace('\\', "/");
+            if let Some((dir, _)) = path.rsplit_once('/') {
+                dirs.insert(dir.to_string());
+            }
+        }
+    }
+    dirs
+}
+
+/// Version folders of artifacts the manifest references in another version.
+/// Artifacts it doesn't mention at all (Forge processor output) are kept.
+fn orphaned_libraries(
+    instance: &MinecraftInstance,
+    paths: &MinecraftPaths,
+    manifest: &Value,
+    candidates: &mut Vec<Candidate>,
+) {
+    let referenced = referenced_library_dirs(manifest);
+    let artifacts: HashSet<&str> = referenced
+        .iter()
+        .filter_map(|dir| dir.rsplit_once('/').map(|(artifact, _)| artifact))
+        .collect();
+    let libraries_dir = paths.libraries_dir();
+
+    for artifact in artifacts {
+        let artifact_dir = paths::join_relative(&libraries_dir, artifact);
+        for version_dir in subdirs(&artifact_dir) {
+            let Some(version) = version_dir.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            if referenced.contains(&format!("{}/{}", artifact, version)) {
+                continue;
+            }
+            candidates.push(candidate(
+                StorageCategory::OrphanedLibraries,
+                &version_dir,
+                vec![version_dir.clone()],
+                Some(instance),
+            ));
+        }
+    }
+}
+
+fn orphaned_assets(
+    instance: &MinecraftInstance,
+    paths: &MinecraftPaths,
+    manifest: &Value,
+    candidates: &mut Vec<Candidate>,
+) {
+    let Some(index_id) = manifest
+        .get("assetIndex")
+        .and_then(|index| index.get("id"))
+        .or_else(|| manifest.get("assets"))
+        .and_then(Value::as_str)
+    else {
+        return;
+    };
+    let assets_dir = paths.assets_dir();
+    // Sin el índice no se sabe qué objetos se usan: no se toca nada
+    let Some(index) = fs::read_to_string(
+        assets_dir
+            .join("indexes")
+            .join(format!("{}.json", index_id)),
+    )
+    .ok()
+    .and_then(|content| serde_json::from_str::<Value>(&content).ok()) else {
+        return;
+    };
+    let Some(objects) = index.get("objects").and_then(Value::as_object) else {
+        return;
+    };
+    let used: HashSet<&str> = objects
+        .values()
+        .filter_map(|object| object.get("hash").and_then(Value::as_str))
+        .collect();
+
+    let objects_dir = assets_dir.join("objects");
+    let targets: Vec<PathBuf> = subdirs(&objects_dir)
+        .iter()
+        .flat_map(|prefix| files_in(prefix))
+        .map(|(path, _)| path)
+        .filter(|path| {
+            !path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|hash| used.contains(hash))
+        })
+        .collect();
+    if !targets.is_empty() {
+        candidates.push(candidate(
+            StorageCategory::OrphanedAssets,
+            &objects_dir,
+            targets,
+            Some(instance),
+        ));
+    }
+}
+
+fn instance_leftovers(
+    instance: &MinecraftInstance,
+    game_dir: &Path,
+    candidates: &mut Vec<Candidate>,
+) {
+    // El bootstrap lo borra al terminar; si sigue ahí, la instalación se cortó
+    let installer = game_dir.join("forge-installer.jar");
+    if installer.is_file() {
+        candidates.push(candidate(
+            StorageCategory::ForgeInstallers,
+            &installer,
+            vec![installer.clone()],
+            Some(instance),
+        ));
+    }
+
+    let current_natives = paths::extracted_natives_dir(game_dir, &instance.minecraftVersion);
+    for natives in subdirs(&game_dir.join("natives")) {
+        if natives != current_natives {
+            candidates.push(candidate(
+                StorageCategory::OldNatives,
+                &natives,
+                vec![natives.clone()],
+                Some(instance),
+            ));
+        }
+    }
+
+    for dir in [game_dir.join("logs"), game_dir.join("crash-reports")] {
+        let stale = stale_logs(&dir);
+        if !stale.is_empty() {
+            candidates.push(candidate(
+                StorageCategory::StaleLogs,
+                &dir,
+                stale,
+                Some(instance),
+            ));
+        }
+    }
+}
+
+fn stale_logs(dir: &Path) -> Vec<PathBuf> {
+    let now = SystemTime::now();
+    files_in(dir)
+        .into_iter()
+        .filter(|(path, _)| {
+            !path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| ACTIVE_LOGS.contains(&name))
+        })
+        .filter(|(_, metadata)| {
+            metadata
+                .modified()
+                .ok()
+                .and_then(|modified| now.duration_since(modified).ok())
+                .is_some_and(|age| age > STALE_LOG_AGE)
+        })
+        .map(|(path, _)| path)
+        .collect()
+}
+
+fn launcher_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("dev.alexitoo.modpackstore"))
+}
+
+/// Whether `game_dir` is a symlink or resolves outside the instance folder.
+fn is_linked_game_dir(instance: &MinecraftInstance, game_dir: &Path) -> bool {
+    if fs::symlink_metadata(game_dir).is_ok_and(|metadata| metadata.file_type().is_symlink()) {
+        return true;
+    }
+    let Some(instance_dir) = instance.instanceDirectory.as_deref() else {
+        return false;
+    };
+    match (fs::canonicalize(game_dir), fs::canonicalize(instance_dir)) {
+        (Ok(game_dir), Ok(instance_dir)) => !game_dir.starts_with(instance_dir),
+        _ => false,
+    }
+}
+
+fn analyze() -> Result<Analysis, String> {
+    let instances = get_all_instances()?;
+    let busy: HashSet<String> = tasks_manager::get_active_tasks()
+        .into_iter()
+        .filter_map(|task| task.instance_id)
+        .collect();
+
+    let mut candidates = Vec::new();
+    let mut skipped_instances = Vec::new();
+    let mut running = Vec::new();
+
+    for instance in &instances {
+        if process_registry::is_active(&instance.instanceId) || busy.contains(&instance.instanceId)
+        {
+            running.push(instance.instanceId.clone());
+            skipped_instances.push(instance.instanceName.clone());
+            continue;
+        }
+
+        let game_dir = paths::native_path(&instance.minecraftPath);
+        // Importada en modo enlace: la carpeta es de otro launcher y puede
+        // compartirse con otras instancias, así que no se toca nada
+        if is_linked_game_dir(instance, &game_dir) {
+            log::info!(
+                "[Storage] Skipping {}: its game directory is linked outside the instance",
+                instance.instanceName
+            );
+            skipped_instances.push(instance.instanceName.clone());
+            continue;
+        }
+        instance_leftovers(instance, &game_dir, &mut candidates);
+
+        let paths = {
+            let config_manager = crate::config::read_config();
+            let config = config_manager.as_ref().map_err(|e| e.clone())?;
+            MinecraftPaths::new(instance, config)
+        };
+        // Sin manifiesto no se sabe qué usa la instancia: sus librerías y assets se quedan
+        let Some(paths) = paths else {
+            continue;
+        };
+        let Some(manifest) = ManifestParser::new(&paths).load_merged_manifest() else {
+            log::warn!(
+                "[Storage] No manifest for {}, keeping its libraries and assets",
+                instance.instanceName
+            );
+            continue;
+        };
+        orphaned_libraries(instance, &paths, &manifest, &mut candidates);
+        orphaned_assets(instance, &paths, &manifest, &mut candidates);
+    }
+
+    // Copias temporales de natives de partidas que ya terminaron
+    let natives_dirs = MinecraftPaths::scoped_natives_roots()
+        .iter()
+        .flat_map(|root| subdirs(root))
+        .collect::<Vec<_>>();
+    for natives in natives_dirs {
+        let name = natives
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if !running.iter().any(|id| name.ends_with(id.as_str())) {
+            candidates.push(candidate(
+                StorageCategory::OldNatives,
+                &natives,
+                vec![natives.clone()],
+                None,
+            ));
+        }
+    }
+
+    if let Some(logs_dir) = launcher_dir().map(|dir| dir.join("logs")) {
+        let stale = stale_logs(&logs_dir);
+        if !stale.is_empty() {
+            candidates.push(candidate(
+                StorageCategory::StaleLogs,
+                &logs_dir,
+                stale,
+                None,
+            ));
+        }
+    }
+
+    let (files, bytes) = download_cache::usage();
+    if files > 0 {
+        let cache_dir = launcher_dir()
+            .map(|dir| dir.join("cache").join("downloads"))
+            .unwrap_or_default();
+        candidates.push(Candidate {
+            category: StorageCategory::DownloadCache,
+            item: StorageItem {
+                path: cache_dir.to_string_lossy().to_string(),
+                bytes,
+                files,
+                instance_id: None,
+                instance_name: None,
+            },
+            targets: Vec::new(),
+        });
+    }
+
+    Ok(Analysis {
+        candidates,
+        skipped_instances,
+    })
+}
+
+fn report(analysis: Analysis) -> StorageReport {
+    let mut categories: Vec<StorageCategoryReport> = Vec::new();
+    for candidate in analysis.candidates {
+        let index = match categories
+            .iter()
+            .position(|report| report.category == candidate.category)
+        {
+            Some(index) => index,
+            None => {
+                categories.push(StorageCategoryReport {
+                    category: candidate.category,
+                    bytes: 0,
+                    files: 0,
+                    items: Vec::new(),
+                });
+                categories.len() - 1
+            }
+        };
+        let report = &mut categories[index];
+        report.bytes += candidate.item.bytes;
+        report.files += candidate.item.files;
+        report.items.push(candidate.item);
+    }
+    for report in &mut categories {
+        report.items.sort_by(|a, b| b.bytes.cmp(&a.bytes));
+    }
+    categories.sort_by(|a, b| b.bytes.cmp(&a.bytes));
+
+    StorageReport {
+        total_bytes: categories.iter().map(|report| report.bytes).sum(),
+        categories,
+        skipped_instances: analysis.skipped_instances,
+    }
+}
+
+fn remove(target: &Path) -> std::io::Result<()> {
+    if fs::symlink_metadata(target)?.is_dir() {
+        fs::remove_dir_all(target)
+    } else {
+        fs::remove_file(target)
+    }
+}
+
+fn clean(categories: &[StorageCategory]) -> Result<CleanStorageReport, String> {
+    let mut report = CleanStorageReport::default();
+
+    for candidate in analyze()?.candidates {
+        if !categories.contains(&candidate.category) {
+            continue;
+        }
+        if candidate.category == StorageCategory::DownloadCache {
+            let cleared = download_cache::clear_download_cache()?;
+            report.removed_files += cleared.removed_files;
+            report.reclaimed_bytes += cleared.reclaimed_bytes;
+            continue;
+        }
+
+        for target in &candidate.targets {
+            let (bytes, files) = measure(target);
+            match remove(target) {
+                Ok(()) => {
+                    report.removed_files += files;
+                    report.reclaimed_bytes += bytes;
+                }
+                Err(e) => {
+                    log::warn!("[Storage] Could not remove {}: {}", target.display(), e);
+                    report.failed.push(format!("{}: {}", target.display(), e));
+                }
+            }
+        }
+    }
+
+    log::info!(
+        "[Storage] Cleanup removed {} files ({} bytes), {} failures",
+        report.removed_files,
+        report.reclaimed_bytes,
+        report.failed.len()
+    );
+    Ok(report)
+}
+
+/// Space that can be reclaimed, itemized by category. Deletes nothing.
+#[tauri::command]
+pub async fn analyze_storage() -> Result<StorageReport, String> {
+    tokio::task::spawn_blocking(|| analyze().map(report))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Deletes the reclaimable files of the selected categories.
+#[tauri::command]
+pub async fn clean_storage(options: CleanStorageOptions) -> Result<CleanStorageReport, String> {
+    if options.categories.is_empty() {
+        return Err("No se seleccionó ninguna categoría".to_string());
+    }
+    tokio::task::spawn_blocking(move || clean(&options.categories))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
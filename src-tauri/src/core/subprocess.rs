@@ -0,0 +1,128 @@
+//! Creation flags for the processes the launcher spawns.
+
+use crate::config::with_config;
+use std::process::{Child, Command, Stdio};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+#[cfg(windows)]
+const BELOW_NORMAL_PRIORITY_CLASS: u32 = 0x0000_4000;
+#[cfg(windows)]
+const HIGH_PRIORITY_CLASS: u32 = 0x0000_0080;
+
+/// Cuánto se espera a que los lectores de stdout/stderr lleguen a EOF una
+/// vez terminado el proceso
+const READER_GRACE: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProcessPriority {
+    BelowNormal,
+    Normal,
+    High,
+}
+
+#[cfg(windows)]
+fn apply(command: &mut Command, priority: ProcessPriority) {
+    use std::os::windows::process::CommandExt;
+
+    let priority_class = match priority {
+        ProcessPriority::BelowNormal => BELOW_NORMAL_PRIORITY_CLASS,
+        ProcessPriority::Normal => 0,
+        ProcessPriority::High => HIGH_PRIORITY_CLASS,
+    };
+    command.creation_flags(CREATE_NO_WINDOW | priority_class);
+}
+
+#[cfg(not(windows))]
+fn apply(_command: &mut Command, _priority: ProcessPriority) {}
+
+/// Short-lived helper processes (hooks, system queries): no console window.
+pub fn hide_console(command: &mut Command) -> &mut Command {
+    apply(command, ProcessPriority::Normal);
+    command
+}
+
+/// Installers and verification work: no console window, and below normal
+/// priority when `lowPriorityBackgroundTasks` is enabled.
+///
+/// Reads the config, so it must not be called while holding its lock.
+pub fn background_process(command: &mut Command) -> &mut Command {
+    let low_priority = with_config(|config| config.get_low_priority_background_tasks())
+        .ok()
+        .unwrap_or(false);
+
+    let priority = if low_priority {
+        ProcessPriority::BelowNormal
+    } else {
+        ProcessPriority::Normal
+    };
+    apply(command, priority);
+    command
+}
+
+/// The game process. `high_priority` comes from `highPriorityGame`; it is
+/// passed in because the launcher already holds the config lock.
+pub fn game_process(command: &mut Command, high_priority: bool) -> &mut Command {
+    let priority = if high_priority {
+        ProcessPriority::High
+    } else {
+        ProcessPriority::Normal
+    };
+    apply(command, priority);
+    command
+}
+
+/// Starts the process in its own process group (Unix), so `kill_tree` also
+/// reaches whatever it spawns. On Windows the tree is found by parent PID.
+pub fn own_process_group(command: &mut Command) -> &mut Command {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    command
+}
+
+/// Kills `child` and the processes it started. A grandchild that survives
+/// keeps the stdout/stderr pipes open, so killing only the shell is not
+/// enough for commands run through `sh -c` / `cmd /C`.
+pub fn kill_tree(child: &mut Child) {
+    let pid = child.id().to_string();
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("taskkill");
+        command.args(["/T", "/F", "/PID", &pid]);
+        command
+    } else {
+        // Con own_process_group el id del grupo es el del proceso
+        let mut command = Command::new("kill");
+        command.args(["-KILL", "--", &format!("-{}", pid)]);
+        command
+    };
+    hide_console(&mut command)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    if !command.status().is_ok_and(|status| status.success()) {
+        log::warn!("[Subprocess] Could not kill the process tree of {}", pid);
+    }
+    let _ = child.kill();
+}
+
+/// Joins the threads draining a finished child's pipes, waiting at most
+/// `READER_GRACE`. A process the child left behind may hold the pipes open
+/// indefinitely; those readers are detached and end when it exits.
+pub fn join_readers(readers: Vec<JoinHandle<()>>) {
+    let deadline = Instant::now() + READER_GRACE;
+    for reader in readers {
+        while !reader.is_finished() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(20));
+        }
+        if reader.is_finished() {
+            let _ = reader.join();
+        } else {
+            log::warn!("[Subprocess] Output still open after the process exited, detaching reader");
+        }
+    }
+}
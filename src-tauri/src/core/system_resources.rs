@@ -0,0 +1,169 @@
+// src-tauri/src/core/system_resources.rs
+use crate::core::instance_manager::get_instance_by_id;
+use crate::core::minecraft_instance::MinecraftInstance;
+use crate::core::models::ModpackManifest;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use sysinfo::System;
+
+/// Memoria mínima que asignamos a cualquier instancia (MB)
+pub const MIN_INSTANCE_MEMORY_MB: u32 = 1024;
+/// Memoria máxima recomendada; por encima de esto el GC suele empeorar (MB)
+pub const MAX_RECOMMENDED_MEMORY_MB: u32 = 12288;
+/// Memoria que se reserva siempre para el sistema operativo y el launcher (MB)
+const SYSTEM_RESERVED_MEMORY_MB: u64 = 2048;
+
+/// Snapshot of the machine memory, in megabytes.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemMemory {
+    pub total_mb: u64,
+    pub available_mb: u64,
+}
+
+impl SystemMemory {
+    /// Reads total/available memory from the OS using sysinfo.
+    pub fn detect() -> Self {
+        let mut system = System::new();
+        system.refresh_memory();
+
+        Self {
+            total_mb: system.total_memory() / 1024 / 1024,
+            available_mb: system.available_memory() / 1024 / 1024,
+        }
+    }
+
+    /// Largest heap the game can get without starving the OS. On small
+    /// machines the reserve gives way so at least half of the RAM is usable.
+    pub fn max_heap_mb(&self) -> u32 {
+        self.total_mb
+            .saturating_sub(SYSTEM_RESERVED_MEMORY_MB)
+            .max(self.total_mb / 2)
+            .min(u32::MAX as u64) as u32
+    }
+}
+
+/// Memory requirements declared by the modpack (if any).
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModpackMemoryRequirements {
+    pub min_memory_mb: Option<u32>,
+    pub recommended_memory_mb: Option<u32>,
+    pub mod_count: usize,
+}
+
+impl ModpackMemoryRequirements {
+    /// Reads the requirements from the instance's `modpack_manifest.json` and
+    /// counts the installed mods as a size heuristic.
+    pub fn from_instance(instance: &MinecraftInstance) -> Self {
+        let instance_dir = Path::new(instance.instanceDirectory.as_deref().unwrap_or(""));
+        let mut requirements = Self::default();
+
+        if let Ok(Some(manifest)) = ModpackManifest::read(instance_dir) {
+            let memory = manifest.memory_requirements();
+            requirements.min_memory_mb = memory.min_memory;
+            requirements.recommended_memory_mb = memory.recommended_memory;
+        }
+
+        let mods_dir = instance_dir.join("minecraft").join("mods");
+        if let Ok(entries) = fs::read_dir(&mods_dir) {
+            requirements.mod_count = entries
+                .flatten()
+                .filter(|e| {
+                    e.path()
+                        .extension()
+                        .map(|ext| ext == "jar")
+                        .unwrap_or(false)
+                })
+                .count();
+        }
+
+        requirements
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryRecommendation {
+    pub recommended_mb: u32,
+    pub max_safe_mb: u32,
+    pub system: SystemMemory,
+    pub requirements: ModpackMemoryRequirements,
+}
+
+/// Computes a recommended Xmx value (MB) for the given system and modpack.
+///
+/// The baseline scales with the number of mods, is raised to whatever the
+/// publisher declares, and is finally capped so the OS always keeps some
+/// headroom.
+pub fn recommend_memory(
+    system: &SystemMemory,
+    requirements: &ModpackMemoryRequirements,
+) -> MemoryRecommendation {
+    let max_safe_mb = system
+        .total_mb
+        .saturating_sub(SYSTEM_RESERVED_MEMORY_MB)
+        .max(MIN_INSTANCE_MEMORY_MB as u64)
+        .min(MAX_RECOMMENDED_MEMORY_MB as u64) as u32;
+
+    // Vanilla va bien con 2 GB; cada ~50 mods suman 1 GB más
+    let baseline = match requirements.mod_count {
+        0 => 2048,
+        1..=49 => 3072,
+        50..=149 => 4096,
+        150..=249 => 6144,
+        _ => 8192,
+    };
+
+    let mut recommended = baseline;
+    if let Some(declared) = requirements.recommended_memory_mb {
+        recommended = declared;
+    }
+    if let Some(min) = requirements.min_memory_mb {
+        recommended = recommended.max(min);
+    }
+
+    let recommended_mb = recommended.clamp(MIN_INSTANCE_MEMORY_MB, max_safe_mb);
+
+    MemoryRecommendation {
+        recommended_mb,
+        max_safe_mb,
+        system: system.clone(),
+        requirements: requirements.clone(),
+    }
+}
+
+/// Recommendation for an instance using the live system memory.
+pub fn recommend_memory_for_instance(instance: &MinecraftInstance) -> MemoryRecommendation {
+    let system = SystemMemory::detect();
+    let requirements = ModpackMemoryRequirements::from_instance(instance);
+    recommend_memory(&system, &requirements)
+}
+
+/// Heap the instance launches with: the `memory` the user chose for it,
+/// then the recommendation if its modpack declares requirements, then the
+/// global setting (`configured_mb`, `None` with automatic memory) and
+/// finally the recommendation.
+pub fn effective_memory_mb(instance: &MinecraftInstance, configured_mb: Option<u32>) -> u32 {
+    if let Some(memory) = instance.memory {
+        return memory;
+    }
+    let recommendation = recommend_memory_for_instance(instance);
+    let requirements = &recommendation.requirements;
+    // El ajuste global no se eligió pensando en este modpack
+    let modpack_declares =
+        requirements.min_memory_mb.is_some() || requirements.recommended_memory_mb.is_some();
+    match configured_mb {
+        Some(configured) if !modpack_declares => configured,
+        _ => recommendation.recommended_mb,
+    }
+}
+
+#[tauri::command]
+pub fn get_recommended_memory(instance_id: String) -> Result<MemoryRecommendation, String> {
+    let instance = get_instance_by_id(instance_id.clone())?
+        .ok_or_else(|| format!("Instance with ID {} not found", instance_id))?;
+
+    Ok(recommend_memory_for_instance(&instance))
+}
@@ -1,12 +1,23 @@
+use crate::config::with_config;
+use crate::core::events::{EventEmitter, LauncherEvent};
+use crate::core::i18n::{keys, Message};
+use crate::core::launch_queue;
+use crate::core::network_monitor;
+use crate::core::transfer_stats::TransferStats;
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::sync::Mutex;
-use tauri::{AppHandle, Emitter, Wry};
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Wry};
 use uuid::Uuid; // Asegúrate de importar Wry si no lo estaba
 
 // --- TaskStatus y TaskInfo permanecen iguales ---
 
-#[derive(Clone, Serialize, Deserialize, Debug)]
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
 pub enum TaskStatus {
     Pending,
     Running,
@@ -15,6 +26,25 @@ pub enum TaskStatus {
     Cancelled,
 }
 
+impl TaskStatus {
+    /// Completed, Failed y Cancelled no admiten más cambios
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            TaskStatus::Completed | TaskStatus::Failed | TaskStatus::Cancelled
+        )
+    }
+}
+
+/// Qué hace una tarea, para saber cómo retomarla tras un cierre inesperado
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+pub enum TaskKind {
+    /// Descarga/instalación de los archivos de una instancia; se puede repetir
+    InstanceBootstrap,
+    /// Copia de una instancia externa; sin el origen no se puede retomar
+    InstanceImport,
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct TaskInfo {
     pub id: String,
@@ -22,158 +52,879 @@ pub struct TaskInfo {
     pub status: TaskStatus,
     pub progress: f32,
     pub message: String,
+    /// Clave y parámetros de `message` para traducirlo en el frontend
+    #[serde(default)]
+    pub message_key: Option<String>,
+    #[serde(default)]
+    pub message_params: Option<serde_json::Value>,
     pub data: Option<serde_json::Value>,
     pub created_at: String,
+    #[serde(default)]
+    pub kind: Option<TaskKind>,
+    #[serde(default)]
+    pub instance_id: Option<String>,
+    /// Pasos ya terminados, en orden
+    #[serde(default)]
+    pub completed_steps: Vec<String>,
+    /// El launcher se cerró con la tarea a medias
+    #[serde(default)]
+    pub interrupted: bool,
+    /// Posición (desde 1) mientras espera en la cola de tareas pesadas
+    #[serde(default)]
+    pub queue_position: Option<usize>,
+    /// Velocidad, ETA y bytes mientras la tarea descarga archivos
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transfer: Option<TransferStats>,
+    /// Primera vez que pasó a `Running`
+    #[serde(default)]
+    pub started_at: Option<String>,
+    #[serde(default)]
+    pub finished_at: Option<String>,
+    /// Desde que empezó (o se creó, si nunca llegó a empezar) hasta que terminó
+    #[serde(default)]
+    pub duration_ms: Option<u64>,
+    /// Tarea de la que forma parte
+    #[serde(default)]
+    pub parent_id: Option<String>,
+    /// Subtareas, en orden de creación
+    #[serde(default)]
+    pub subtasks: Vec<String>,
+    /// Peso en el progreso del padre
+    #[serde(default = "default_weight")]
+    pub weight: f32,
+}
+
+fn default_weight() -> f32 {
+    1.0
+}
+
+impl TaskInfo {
+    fn new(
+        label: &str,
+        kind: Option<TaskKind>,
+        instance_id: Option<String>,
+        data: Option<serde_json::Value>,
+    ) -> Self {
+        let mut task = TaskInfo {
+            id: Uuid::new_v4().to_string(),
+            label: label.to_string(),
+            status: TaskStatus::Pending,
+            progress: 0.0,
+            message: String::new(),
+            message_key: None,
+            message_params: None,
+            data,
+            created_at: chrono::Utc::now().to_rfc3339(), // Asegúrate de tener chrono
+            kind,
+            instance_id,
+            completed_steps: Vec::new(),
+            interrupted: false,
+            queue_position: None,
+            transfer: None,
+            started_at: None,
+            finished_at: None,
+            duration_ms: None,
+            parent_id: None,
+            subtasks: Vec::new(),
+            weight: default_weight(),
+        };
+        task.set_message(Message::new(keys::TASK_WAITING));
+        task
+    }
+
+    fn set_message(&mut self, message: Message) {
+        self.message_params = message.params_value();
+        self.message_key = message.key;
+        self.message = message.text;
+    }
+
+    /// Se puede continuar desde donde quedó en lugar de descartarla
+    pub fn is_resumable(&self) -> bool {
+        self.kind == Some(TaskKind::InstanceBootstrap) && self.instance_id.is_some()
+    }
+
+    // Un nuevo mensaje en una tarea en curso cierra el paso anterior. Con
+    // clave, el paso es la clave: "(3/120)" y "(4/120)" son el mismo paso
+    fn close_step(&mut self, next_key: Option<&str>, next_message: &str) {
+        let same_step = match (self.message_key.as_deref(), next_key) {
+            (Some(key), Some(next_key)) => key == next_key,
+            _ => self.message == next_message,
+        };
+        if self.status == TaskStatus::Running
+            && !same_step
+            && !self.completed_steps.contains(&self.message)
+        {
+            if self.completed_steps.len() >= MAX_COMPLETED_STEPS {
+                self.completed_steps.remove(0);
+            }
+            self.completed_steps.push(self.message.clone());
+        }
+    }
+
+    fn mark_started(&mut self) {
+        if self.started_at.is_none() {
+            self.started_at = Some(chrono::Utc::now().to_rfc3339());
+        }
+    }
+
+    fn mark_finished(&mut self) {
+        let now = chrono::Utc::now();
+        let started = self.started_at.as_deref().unwrap_or(&self.created_at);
+        self.duration_ms = chrono::DateTime::parse_from_rfc3339(started)
+            .ok()
+            .and_then(|started| {
+                (now - started.with_timezone(&chrono::Utc))
+                    .num_milliseconds()
+                    .try_into()
+                    .ok()
+            });
+        self.finished_at = Some(now.to_rfc3339());
+    }
+}
+
+// --- Tareas en curso e historial ---
+// Las tareas viven repartidas en un TasksManager por operación; este registro
+// las reúne para poder consultarlas (p. ej. tras recargar la ventana). Las
+// terminadas pasan a un historial acotado que se guarda en disco.
+
+/// Tareas terminadas que se conservan en el historial
+const MAX_HISTORY: usize = 100;
+
+/// Pasos completados que se recuerdan por tarea; se descartan los más viejos
+const MAX_COMPLETED_STEPS: usize = 50;
+
+static ACTIVE_TASKS: Lazy<Mutex<HashMap<String, TaskInfo>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+static HISTORY: Lazy<Mutex<VecDeque<TaskInfo>>> = Lazy::new(|| Mutex::new(load_history()));
+
+fn lock_active_tasks() -> MutexGuard<'static, HashMap<String, TaskInfo>> {
+    ACTIVE_TASKS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn lock_history() -> MutexGuard<'static, VecDeque<TaskInfo>> {
+    HISTORY.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn history_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| {
+        dir.join("dev.alexitoo.modpackstore")
+            .join("task_history.json")
+    })
+}
+
+fn load_history() -> VecDeque<TaskInfo> {
+    history_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn persist_history(history: &VecDeque<TaskInfo>) {
+    let Some(path) = history_path() else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    match serde_json::to_string_pretty(history) {
+        Ok(content) => {
+            if let Err(e) = fs::write(&path, content) {
+                log::warn!("[Tasks] Could not persist task history: {}", e);
+            }
+        }
+        Err(e) => log::warn!("[Tasks] Could not serialize task history: {}", e),
+    }
+}
+
+/// Refleja el último estado de la tarea; al terminar pasa al historial
+fn track_task(task: &TaskInfo) {
+    if !task.status.is_terminal() {
+        lock_active_tasks().insert(task.id.clone(), task.clone());
+        return;
+    }
+
+    lock_active_tasks().remove(&task.id);
+    // Las subtareas se consultan a través de su padre
+    if task.parent_id.is_some() {
+        return;
+    }
+    let mut history = lock_history();
+    history.retain(|entry| entry.id != task.id);
+    history.push_front(task.clone());
+    history.truncate(MAX_HISTORY);
+    persist_history(&history);
+    drop(history);
+
+    launch_queue::on_task_finished(task);
+}
+
+fn untrack_task(id: &str) {
+    let removed = lock_active_tasks().remove(id);
+    // Una tarea quitada sin terminar tampoco debe retener un lanzamiento
+    if let Some(task) = removed.filter(|task| task.parent_id.is_none()) {
+        launch_queue::on_task_finished(&task);
+    }
+}
+
+#[tauri::command]
+pub fn get_active_tasks() -> Vec<TaskInfo> {
+    let mut tasks: Vec<TaskInfo> = lock_active_tasks().values().cloned().collect();
+    tasks.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+    tasks
+}
+
+/// Tarea en curso, terminada (si sigue en el historial) o interrumpida
+#[tauri::command]
+pub fn get_task(task_id: String) -> Result<TaskInfo, String> {
+    if let Some(task) = lock_active_tasks().get(&task_id) {
+        return Ok(task.clone());
+    }
+    if let Some(task) = lock_history().iter().find(|task| task.id == task_id) {
+        return Ok(task.clone());
+    }
+    lock_journal()
+        .get(&task_id)
+        .cloned()
+        .ok_or_else(|| "La tarea no existe".to_string())
+}
+
+/// Últimas tareas terminadas, de la más reciente a la más antigua
+#[tauri::command]
+pub fn get_recent_task_history(limit: Option<usize>) -> Vec<TaskInfo> {
+    let limit = limit.unwrap_or(MAX_HISTORY).min(MAX_HISTORY);
+    lock_history().iter().take(limit).cloned().collect()
+}
+
+// --- Registro en disco de las tareas sin terminar ---
+// Cada TasksManager vive lo que dura una operación, así que el registro es
+// global y sobrevive a reinicios del launcher.
+
+static JOURNAL: Lazy<Mutex<HashMap<String, TaskInfo>>> = Lazy::new(|| Mutex::new(load_journal()));
+
+/// Cada cuánto se guarda como mucho una tarea a la que sólo le cambió el
+/// progreso; los cambios de estado y de paso se guardan al momento
+const JOURNAL_INTERVAL: Duration = Duration::from_secs(5);
+
+static JOURNAL_WRITTEN_AT: Lazy<Mutex<Option<Instant>>> = Lazy::new(|| Mutex::new(None));
+
+fn journal_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("dev.alexitoo.modpackstore").join("tasks.json"))
+}
+
+fn load_journal() -> HashMap<String, TaskInfo> {
+    journal_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn lock_journal() -> MutexGuard<'static, HashMap<String, TaskInfo>> {
+    JOURNAL.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn persist_journal(journal: &HashMap<String, TaskInfo>) {
+    let Some(path) = journal_path() else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    *JOURNAL_WRITTEN_AT.lock().unwrap_or_else(|e| e.into_inner()) = Some(Instant::now());
+    match serde_json::to_string_pretty(journal) {
+        Ok(content) => {
+            if let Err(e) = fs::write(&path, content) {
+                log::warn!("[Tasks] Could not persist task journal: {}", e);
+            }
+        }
+        Err(e) => log::warn!("[Tasks] Could not serialize task journal: {}", e),
+    }
+}
+
+/// Guarda el estado de la tarea; las terminadas ya no hace falta recordarlas.
+/// Las subtareas se rehacen con su padre, así que no se guardan
+fn journal_task(task: &TaskInfo) {
+    if task.parent_id.is_some() {
+        return;
+    }
+    let mut journal = lock_journal();
+    if task.status.is_terminal() {
+        if journal.remove(&task.id).is_none() {
+            return;
+        }
+    } else {
+        let changed = journal.get(&task.id).is_none_or(|saved| {
+            saved.status != task.status
+                || saved.kind != task.kind
+                || saved.completed_steps != task.completed_steps
+        });
+        journal.insert(task.id.clone(), task.clone());
+        let write_due = JOURNAL_WRITTEN_AT
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_none_or(|written_at| written_at.elapsed() >= JOURNAL_INTERVAL);
+        if !changed && !write_due {
+            return;
+        }
+    }
+    persist_journal(&journal);
+}
+
+fn forget_task(id: &str) {
+    lock_cancellations().remove(id);
+    let mut journal = lock_journal();
+    if journal.remove(id).is_some() {
+        persist_journal(&journal);
+    }
+}
+
+/// Marca como interrumpidas las tareas que quedaron a medias en la ejecución
+/// anterior. Llamar una vez al arrancar, antes de crear tareas nuevas.
+pub fn mark_interrupted_tasks() -> usize {
+    let mut journal = lock_journal();
+    let mut marked = 0;
+    for task in journal.values_mut().filter(|task| !task.interrupted) {
+        task.interrupted = true;
+        task.set_message(Message::new(keys::TASK_INTERRUPTED));
+        marked += 1;
+    }
+    if marked > 0 {
+        log::info!("[Tasks] {} interrupted task(s) from a previous run", marked);
+        persist_journal(&journal);
+    }
+    marked
+}
+
+pub fn interrupted_tasks() -> Vec<TaskInfo> {
+    let mut tasks: Vec<TaskInfo> = lock_journal()
+        .values()
+        .filter(|task| task.interrupted)
+        .cloned()
+        .collect();
+    tasks.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+    tasks
+}
+
+/// Quita una tarea interrumpida del registro para retomarla o descartarla
+pub fn take_interrupted_task(id: &str) -> Option<TaskInfo> {
+    let mut journal = lock_journal();
+    if !journal.get(id).is_some_and(|task| task.interrupted) {
+        return None;
+    }
+    let task = journal.remove(id);
+    persist_journal(&journal);
+    task
+}
+
+// --- Cancelación ---
+// El trabajo largo (hashing, extracción...) consulta el token de su tarea
+// entre archivo y archivo y se detiene en cuanto se cancela.
+
+#[derive(Clone, Default, Debug)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+static CANCELLATIONS: Lazy<Mutex<HashMap<String, CancellationToken>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn lock_cancellations() -> MutexGuard<'static, HashMap<String, CancellationToken>> {
+    CANCELLATIONS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Token de cancelación de la tarea; se crea la primera vez que se pide
+pub fn cancellation_token(task_id: &str) -> CancellationToken {
+    lock_cancellations()
+        .entry(task_id.to_string())
+        .or_default()
+        .clone()
+}
+
+#[tauri::command]
+pub fn cancel_task(task_id: String) -> Result<(), String> {
+    let token = lock_cancellations().get(&task_id).cloned();
+    match token {
+        Some(token) => {
+            log::info!("[Tasks] Cancellation requested for {}", task_id);
+            token.cancel();
+            Ok(())
+        }
+        None => Err("La tarea no existe o no se puede cancelar".to_string()),
+    }
+}
+
+// --- Cola de tareas pesadas ---
+// Crear o importar varias instancias a la vez no debe lanzar todas las
+// descargas en paralelo: sólo `maxConcurrentTasks` corren y el resto espera.
+
+/// Prioridad en la cola; a igual prioridad se respeta el orden de llegada
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TaskPriority {
+    /// Trabajo que el usuario no ha pedido (p. ej. buscar actualizaciones)
+    Background,
+    /// Iniciada por el usuario
+    User,
+}
+
+struct QueuedTask {
+    id: String,
+    priority: TaskPriority,
+    seq: u64,
+    /// Sin conexión no puede empezar
+    needs_network: bool,
+    manager: Arc<Mutex<TasksManager>>,
+}
+
+#[derive(Default)]
+struct TaskQueue {
+    running: usize,
+    waiting: Vec<QueuedTask>,
+    next_seq: u64,
+}
+
+static QUEUE: Lazy<(Mutex<TaskQueue>, Condvar)> =
+    Lazy::new(|| (Mutex::new(TaskQueue::default()), Condvar::new()));
+
+fn max_concurrent_tasks() -> usize {
+    with_config(|config| config.get_max_concurrent_tasks())
+        .ok()
+        .unwrap_or(2)
 }
 
-// --- Importa tu variable estática ---
-// Asumiendo que main.rs está en la raíz del crate (src/main.rs)
-// Si TasksManager está en otro módulo, ajusta la ruta (ej: `crate::main::GLOBAL_APP_HANDLE`)
-use crate::GLOBAL_APP_HANDLE;
+impl TaskQueue {
+    /// Actualiza la posición que ve el frontend de cada tarea en espera
+    fn publish_positions(&self, online: bool) {
+        for (index, queued) in self.waiting.iter().enumerate() {
+            if let Ok(tm) = queued.manager.lock() {
+                let offline = !online && queued.needs_network;
+                tm.set_queue_position(&queued.id, Some(index + 1), offline);
+            }
+        }
+    }
+}
+
+/// Re-evaluates the waiting tasks, e.g. after the network comes back.
+pub fn wake_queue() {
+    let (lock, cvar) = &*QUEUE;
+    // Con el candado tomado ninguna tarea puede estar entre comprobar y esperar
+    let _queue = lock.lock().unwrap_or_else(|e| e.into_inner());
+    cvar.notify_all();
+}
+
+/// Mientras existe, la tarea ocupa uno de los huecos de la cola
+pub struct TaskSlot;
+
+impl Drop for TaskSlot {
+    fn drop(&mut self) {
+        let (lock, cvar) = &*QUEUE;
+        let mut queue = lock.lock().unwrap_or_else(|e| e.into_inner());
+        queue.running = queue.running.saturating_sub(1);
+        cvar.notify_all();
+    }
+}
+
+/// Bloquea hasta que la tarea pueda empezar. Llamar desde el hilo que hace
+/// el trabajo y mantener el `TaskSlot` hasta terminar. Sin conexión no
+/// empiezan las tareas con `needs_network`; las demás pueden adelantarlas.
+pub fn wait_for_slot(
+    task_id: &str,
+    priority: TaskPriority,
+    needs_network: bool,
+    manager: &Arc<Mutex<TasksManager>>,
+) -> TaskSlot {
+    let (lock, cvar) = &*QUEUE;
+    let mut queue = lock.lock().unwrap_or_else(|e| e.into_inner());
+
+    let seq = queue.next_seq;
+    queue.next_seq += 1;
+    queue.waiting.push(QueuedTask {
+        id: task_id.to_string(),
+        priority,
+        seq,
+        needs_network,
+        manager: Arc::clone(manager),
+    });
+    queue
+        .waiting
+        .sort_by(|a, b| b.priority.cmp(&a.priority).then(a.seq.cmp(&b.seq)));
+
+    loop {
+        let online = network_monitor::is_online();
+        let is_next = queue
+            .waiting
+            .iter()
+            .find(|queued| online || !queued.needs_network)
+            .is_some_and(|next| next.id == task_id);
+        if is_next && queue.running < max_concurrent_tasks() {
+            queue.waiting.retain(|queued| queued.id != task_id);
+            queue.running += 1;
+            queue.publish_positions(online);
+            // Puede haber más huecos libres para la siguiente en la cola
+            cvar.notify_all();
+            break;
+        }
+
+        queue.publish_positions(online);
+        queue = cvar.wait(queue).unwrap_or_else(|e| e.into_inner());
+    }
+    drop(queue);
+
+    if let Ok(tm) = manager.lock() {
+        tm.set_queue_position(task_id, None, false);
+    }
+    TaskSlot
+}
+
+/// Media de las subtareas ponderada por su peso, de 0 a 100
+fn subtasks_progress(tasks: &HashMap<String, TaskInfo>, ids: &[String]) -> f32 {
+    let (mut done, mut total) = (0.0, 0.0);
+    for task in ids.iter().filter_map(|id| tasks.get(id)) {
+        let progress = if task.status == TaskStatus::Completed {
+            100.0
+        } else {
+            task.progress.clamp(0.0, 100.0)
+        };
+        done += progress * task.weight;
+        total += task.weight;
+    }
+    if total > 0.0 {
+        done / total
+    } else {
+        0.0
+    }
+}
+
+/// Las subtareas que no llegaron a terminar acaban con su padre: completadas
+/// si él se completó y canceladas en otro caso
+fn close_subtasks(tasks: &mut HashMap<String, TaskInfo>, parent_id: &str) -> Vec<TaskInfo> {
+    let Some(parent) = tasks.get(parent_id) else {
+        return Vec::new();
+    };
+    let completed = parent.status == TaskStatus::Completed;
+    let mut closed = Vec::new();
+    for id in parent.subtasks.clone() {
+        let Some(task) = tasks.get_mut(&id) else {
+            continue;
+        };
+        if task.status.is_terminal() {
+            continue;
+        }
+        if completed {
+            task.status = TaskStatus::Completed;
+            task.progress = 100.0;
+        } else {
+            task.status = TaskStatus::Cancelled;
+        }
+        task.transfer = None;
+        task.mark_finished();
+        track_task(task);
+        closed.push(task.clone());
+        closed.extend(close_subtasks(tasks, &id));
+    }
+    closed
+}
+
+/// Recalcula el progreso de los antecesores de `id`. Mientras una subtarea
+/// está en curso su padre muestra su mensaje.
+fn refresh_parents(tasks: &mut HashMap<String, TaskInfo>, id: &str) -> Vec<TaskInfo> {
+    let mut refreshed = Vec::new();
+    let mut child_id = id.to_string();
+    while let Some(child) = tasks.get(&child_id).cloned() {
+        let Some(parent_id) = child.parent_id else {
+            break;
+        };
+        let progress = match tasks.get(&parent_id) {
+            Some(parent) => subtasks_progress(tasks, &parent.subtasks),
+            None => break,
+        };
+        let Some(parent) = tasks.get_mut(&parent_id) else {
+            break;
+        };
+        if parent.status.is_terminal() {
+            break;
+        }
+
+        parent.progress = progress;
+        if child.status == TaskStatus::Running {
+            parent.close_step(child.message_key.as_deref(), &child.message);
+            parent.status = TaskStatus::Running;
+            parent.mark_started();
+            parent.message = child.message;
+            parent.message_key = child.message_key;
+            parent.message_params = child.message_params;
+        }
+        journal_task(parent);
+        track_task(parent);
+        refreshed.push(parent.clone());
+        child_id = parent_id;
+    }
+    refreshed
+}
 
 pub struct TasksManager {
     pub tasks: Mutex<HashMap<String, TaskInfo>>,
+    events: EventEmitter,
 }
 
 impl TasksManager {
-    pub fn new() -> Self {
+    /// `events` comes from Tauri managed state (`State<EventEmitter>`).
+    pub fn new(events: EventEmitter) -> Self {
         Self {
             tasks: Mutex::new(HashMap::new()),
+            events,
         }
     }
 
+    // Con varios lanzamientos en paralelo un pánico en un hilo no debe
+    // envenenar el mapa de tareas para el resto
+    fn lock_tasks(&self) -> MutexGuard<'_, HashMap<String, TaskInfo>> {
+        self.tasks.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
     // Ya no necesita app_handle como parámetro
     pub fn add_task(&self, label: &str, data: Option<serde_json::Value>) -> String {
-        let id = Uuid::new_v4().to_string();
-        let task = TaskInfo {
-            id: id.clone(),
-            label: label.to_string(),
-            status: TaskStatus::Pending,
-            progress: 0.0,
-            message: "En espera...".into(),
-            data,
-            created_at: chrono::Utc::now().to_rfc3339(), // Asegúrate de tener chrono
-        };
+        self.insert_task(label, None, None, data)
+    }
+
+    /// Tarea ligada a una instancia que se puede retomar o limpiar si el
+    /// launcher se cierra antes de que termine
+    pub fn add_instance_task(
+        &self,
+        label: &str,
+        kind: TaskKind,
+        instance_id: &str,
+        data: Option<serde_json::Value>,
+    ) -> String {
+        self.insert_task(label, Some(kind), Some(instance_id.to_string()), data)
+    }
+
+    fn insert_task(
+        &self,
+        label: &str,
+        kind: Option<TaskKind>,
+        instance_id: Option<String>,
+        data: Option<serde_json::Value>,
+    ) -> String {
+        let task = TaskInfo::new(label, kind, instance_id, data);
+        let id = task.id.clone();
 
         println!("Task created: {}", task.id);
 
-        self.tasks
-            .lock()
-            .expect("Failed to lock tasks mutex for add")
-            .insert(id.clone(), task.clone());
-
-        // Emitir evento usando el AppHandle global
-        println!(
-            "Attempting to emit task-created event for task: {}",
-            task.id
-        );
-        // Bloquea el Mutex para acceder al Option<AppHandle> global
-        if let Ok(guard) = GLOBAL_APP_HANDLE.lock() {
-            // Verifica si el AppHandle ya fue inicializado en setup
-            if let Some(app_handle) = guard.as_ref() {
-                // Usa app_handle para emitir el evento
-                if let Err(e) = app_handle.emit("task-created", task.clone()) {
-                    // Clonar task aquí
-                    eprintln!("Failed to emit task-created event: {}", e);
-                } else {
-                    println!("Successfully emitted task-created event.");
-                }
-            } else {
-                eprintln!("Error: GLOBAL_APP_HANDLE is None when trying to emit task-created.");
+        self.lock_tasks().insert(id.clone(), task.clone());
+        journal_task(&task);
+        track_task(&task);
+
+        self.events.send(LauncherEvent::TaskCreated(task));
+
+        id
+    }
+
+    /// Subtarea de `parent_id`. Mientras un padre tiene subtareas su
+    /// progreso es la media de ellas ponderada por `weight`
+    pub fn add_subtask(&self, parent_id: &str, label: &str, weight: f32) -> String {
+        let mut task = TaskInfo::new(label, None, None, None);
+        task.weight = weight.max(0.0);
+        let id = task.id.clone();
+
+        {
+            let mut tasks = self.lock_tasks();
+            if let Some(parent) = tasks.get_mut(parent_id) {
+                parent.subtasks.push(id.clone());
+                task.parent_id = Some(parent_id.to_string());
             }
-        } else {
-            eprintln!("Error: Could not lock GLOBAL_APP_HANDLE mutex for task-created.");
+            tasks.insert(id.clone(), task.clone());
         }
+        track_task(&task);
+
+        self.events.send(LauncherEvent::TaskCreated(task));
+
+        id
+    }
+
+    /// Vuelve a poner en marcha una tarea interrumpida conservando su id y
+    /// los pasos que ya había completado
+    pub fn restore_task(&self, mut task: TaskInfo) -> String {
+        task.status = TaskStatus::Pending;
+        task.set_message(Message::new(keys::TASK_RESUMING));
+        task.interrupted = false;
+        task.transfer = None;
+        task.started_at = None;
+
+        let id = task.id.clone();
+        self.lock_tasks().insert(id.clone(), task.clone());
+        journal_task(&task);
+        track_task(&task);
+
+        self.events.send(LauncherEvent::TaskCreated(task));
 
         id
     }
 
+    /// Cambia lo que hace la tarea a partir de este punto (p. ej. una
+    /// importación que ya copió los archivos y sólo le falta el bootstrap)
+    pub fn set_task_kind(&self, id: &str, kind: TaskKind) {
+        let mut tasks = self.lock_tasks();
+        if let Some(task) = tasks.get_mut(id) {
+            task.kind = Some(kind);
+            journal_task(task);
+            track_task(task);
+        }
+    }
+
     // Ya no necesita app_handle como parámetro
     pub fn update_task(
         &self,
         id: &str,
         status: TaskStatus,
         progress: f32,
-        message: &str,
+        message: impl Into<Message>,
         data: Option<serde_json::Value>,
     ) {
-        let mut updated_task_clone = None;
+        let message = message.into();
 
         // Alcance del bloqueo para las tareas
-        {
-            let mut tasks = self
-                .tasks
-                .lock()
-                .expect("Failed to lock tasks mutex for update");
-            if let Some(task) = tasks.get_mut(id) {
-                task.status = status;
-                task.progress = progress;
-                task.message = message.to_string();
-                task.data = data;
-                updated_task_clone = Some(task.clone()); // Clonar dentro del bloqueo
-            }
-        } // Bloqueo de `tasks` se libera aquí
-
-        // Si la tarea fue actualizada, intenta emitir el evento
-        if let Some(task_to_emit) = updated_task_clone {
-            println!(
-                "Attempting to emit task-updated event for task: {}",
-                task_to_emit.id
-            );
-            // Bloquea el Mutex para acceder al Option<AppHandle> global
-            if let Ok(guard) = GLOBAL_APP_HANDLE.lock() {
-                if let Some(app_handle) = guard.as_ref() {
-                    if let Err(e) = app_handle.emit("task-updated", task_to_emit) {
-                        // Usar el clon
-                        eprintln!("Failed to emit task-updated event: {}", e);
-                    } else {
-                        println!("Successfully emitted task-updated event.");
-                    }
-                } else {
-                    eprintln!("Error: GLOBAL_APP_HANDLE is None when trying to emit task-updated.");
+        let updated = {
+            let mut tasks = self.lock_tasks();
+            // Con subtareas el progreso sale de ellas, no del que se indica
+            let progress = match tasks.get(id) {
+                Some(task) if !task.subtasks.is_empty() && !status.is_terminal() => {
+                    subtasks_progress(&tasks, &task.subtasks)
                 }
+                _ => progress,
+            };
+            let Some(task) = tasks.get_mut(id) else {
+                return;
+            };
+            // Una tarea ya terminada no vuelve a cambiar de estado
+            if task.status.is_terminal() {
+                log::warn!(
+                    "Ignoring update for finished task {} ({:?})",
+                    id,
+                    task.status
+                );
+                return;
+            }
+            task.close_step(message.key.as_deref(), &message.text);
+            task.status = status;
+            task.progress = progress;
+            task.set_message(message);
+            task.data = data;
+            if task.status == TaskStatus::Running {
+                task.mark_started();
+            }
+            let finished = task.status.is_terminal();
+            if finished {
+                task.transfer = None;
+                task.mark_finished();
+            }
+            journal_task(task);
+            track_task(task);
+
+            let mut updated = vec![task.clone()];
+            if finished {
+                updated.extend(close_subtasks(&mut tasks, id));
+            }
+            updated.extend(refresh_parents(&mut tasks, id));
+            updated
+        }; // Bloqueo de `tasks` se libera aquí
+
+        // El progreso se agrupa; el estado final sale al momento
+        for task in updated {
+            let id = task.id.clone();
+            if task.status.is_terminal() {
+                self.events
+                    .send_final(&id, LauncherEvent::TaskUpdated(task));
             } else {
-                eprintln!("Error: Could not lock GLOBAL_APP_HANDLE mutex for task-updated.");
+                self.events
+                    .send_throttled(&id, LauncherEvent::TaskUpdated(task));
             }
         }
     }
 
+    /// Posición en la cola; `offline` cambia el mensaje a "esperando conexión"
+    pub fn set_queue_position(&self, id: &str, position: Option<usize>, offline: bool) {
+        let message = position.map(|position| {
+            if offline {
+                Message::new(keys::TASK_WAITING_NETWORK)
+            } else {
+                Message::new(keys::TASK_QUEUED).param("position", position)
+            }
+        });
+
+        let updated = {
+            let mut tasks = self.lock_tasks();
+            let Some(task) = tasks.get_mut(id) else {
+                return;
+            };
+            let same_message = !message.as_ref().is_some_and(|message| {
+                message.key != task.message_key || message.params_value() != task.message_params
+            });
+            if task.queue_position == position && same_message {
+                return;
+            }
+            task.queue_position = position;
+            if let Some(message) = message {
+                task.set_message(message);
+            }
+            track_task(task);
+            task.clone()
+        };
+
+        self.events
+            .send_throttled(id, LauncherEvent::TaskUpdated(updated));
+    }
+
+    /// Publica las estadísticas de descarga. No se guardan en el diario:
+    /// cambian varias veces por segundo y no sirven para reanudar la tarea
+    pub fn update_transfer(&self, id: &str, stats: TransferStats) {
+        let updated = {
+            let mut tasks = self.lock_tasks();
+            let Some(task) = tasks.get_mut(id) else {
+                return;
+            };
+            if task.status.is_terminal() {
+                return;
+            }
+            task.transfer = Some(stats);
+            track_task(task);
+            task.clone()
+        };
+
+        self.events
+            .send_throttled(id, LauncherEvent::TaskUpdated(updated));
+    }
+
     pub fn get_all_tasks(&self) -> Vec<TaskInfo> {
-        self.tasks
-            .lock()
-            .expect("Failed to lock tasks mutex for get")
-            .values()
-            .cloned()
-            .collect()
+        self.lock_tasks().values().cloned().collect()
     }
 
     pub fn remove_task(&self, id: &str) {
-        self.tasks
-            .lock()
-            .expect("Failed to lock tasks mutex for remove")
-            .remove(id);
+        let subtasks = self
+            .lock_tasks()
+            .get(id)
+            .map(|task| task.subtasks.clone())
+            .unwrap_or_default();
+        for subtask in subtasks {
+            self.remove_task(&subtask);
+        }
 
-        // Emitir evento de eliminación
-        println!("Task removed: {}", id);
-        // Bloquea el Mutex para acceder al Option<AppHandle> global
-        if let Ok(guard) = GLOBAL_APP_HANDLE.lock() {
-            if let Some(app_handle) = guard.as_ref() {
-                if let Err(e) = app_handle.emit("task-removed", id) {
-                    eprintln!("Failed to emit task-removed event: {}", e);
-                } else {
-                    println!("Successfully emitted task-removed event.");
-                }
-            } else {
-                eprintln!("Error: GLOBAL_APP_HANDLE is None when trying to emit task-removed.");
-            }
-        } else {
-            eprintln!("Error: Could not lock GLOBAL_APP_HANDLE mutex for task-removed.");
+        forget_task(id);
+        untrack_task(id);
+        if self.lock_tasks().remove(id).is_none() {
+            // Ya eliminada (p. ej. por otro hilo); no emitir duplicados
+            return;
         }
-    }
-}
 
-impl Default for TasksManager {
-    fn default() -> Self {
-        Self::new()
+        // Emitir evento de eliminación
+        println!("Task removed: {}", id);
+        self.events
+            .send_final(id, LauncherEvent::TaskRemoved(id.to_string()));
     }
 }
@@ -0,0 +1,220 @@
+//! Anonymous, opt-out telemetry.
+
+use crate::config::with_config;
+use crate::core::http_client;
+use crate::API_ENDPOINT;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri_plugin_http::reqwest::Client;
+
+const FLUSH_INTERVAL: Duration = Duration::from_secs(5 * 60);
+/// Con esta cantidad de eventos pendientes se envía sin esperar al intervalo
+const BATCH_SIZE: usize = 20;
+/// Límite de la cola local; se descartan los eventos más antiguos
+const MAX_QUEUED_EVENTS: usize = 500;
+const MAX_SEND_ATTEMPTS: u32 = 3;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum TelemetryEvent {
+    #[serde(rename_all = "camelCase")]
+    LaunchResult {
+        minecraft_version: String,
+        modpack_id: Option<String>,
+        success: bool,
+        exit_code: Option<i32>,
+        error_code: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    ModpackInstall {
+        minecraft_version: String,
+        modpack_id: Option<String>,
+        loader: Option<String>,
+        success: bool,
+        error_code: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct QueuedEvent {
+    recorded_at: String,
+    #[serde(flatten)]
+    event: TelemetryEvent,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TelemetryState {
+    /// Identificador aleatorio de la instalación, no ligado a ninguna cuenta
+    installation_id: String,
+    #[serde(default)]
+    queue: Vec<QueuedEvent>,
+}
+
+static STATE: Lazy<Mutex<TelemetryState>> = Lazy::new(|| Mutex::new(load_state()));
+
+// Evita dos envíos simultáneos del mismo lote
+static FLUSHING: Lazy<tokio::sync::Mutex<()>> = Lazy::new(|| tokio::sync::Mutex::new(()));
+
+fn state_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("dev.alexitoo.modpackstore").join("telemetry.json"))
+}
+
+fn load_state() -> TelemetryState {
+    let mut state = state_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str::<TelemetryState>(&content).ok())
+        .unwrap_or_default();
+
+    if state.installation_id.is_empty() {
+        state.installation_id = uuid::Uuid::new_v4().to_string();
+    }
+    state
+}
+
+fn persist(state: &TelemetryState) {
+    let Some(path) = state_path() else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    match serde_json::to_string(state) {
+        Ok(content) => {
+            if let Err(e) = fs::write(&path, content) {
+                log::warn!("[Telemetry] Could not persist queue: {}", e);
+            }
+        }
+        Err(e) => log::warn!("[Telemetry] Could not serialize queue: {}", e),
+    }
+}
+
+fn is_enabled() -> bool {
+    with_config(|config| config.is_telemetry_enabled())
+        .ok()
+        .unwrap_or(false)
+}
+
+/// Queues an event. Cheap and non-blocking on the network; safe to call
+/// from launcher threads.
+pub fn record(event: TelemetryEvent) {
+    if !is_enabled() {
+        return;
+    }
+
+    let should_flush = {
+        let Ok(mut state) = STATE.lock() else {
+            return;
+        };
+        state.queue.push(QueuedEvent {
+            recorded_at: chrono::Utc::now().to_rfc3339(),
+            event,
+        });
+        if state.queue.len() > MAX_QUEUED_EVENTS {
+            let overflow = state.queue.len() - MAX_QUEUED_EVENTS;
+            state.queue.drain(..overflow);
+        }
+        persist(&state);
+        state.queue.len() >= BATCH_SIZE
+    };
+
+    if should_flush {
+        tauri::async_runtime::spawn(flush());
+    }
+}
+
+/// Starts the periodic flush loop. Call once from the app setup.
+pub fn start() {
+    tauri::async_runtime::spawn(async {
+        loop {
+            flush().await;
+            tokio::time::sleep(FLUSH_INTERVAL).await;
+        }
+    });
+}
+
+/// Sends queued events in batches, retrying with backoff. Events stay
+/// queued (and on disk) until the API accepts or rejects them.
+pub async fn flush() {
+    let _flushing = FLUSHING.lock().await;
+
+    if !is_enabled() {
+        if let Ok(mut state) = STATE.lock() {
+            if !state.queue.is_empty() {
+                state.queue.clear();
+                persist(&state);
+            }
+        }
+        return;
+    }
+
+    let client = http_client::async_client();
+    loop {
+        let (installation_id, batch) = match STATE.lock() {
+            Ok(state) if !state.queue.is_empty() => {
+                let batch: Vec<QueuedEvent> =
+                    state.queue.iter().take(BATCH_SIZE).cloned().collect();
+                (state.installation_id.clone(), batch)
+            }
+            _ => return,
+        };
+
+        let payload = json!({
+            "installationId": installation_id,
+            "launcherVersion": env!("CARGO_PKG_VERSION"),
+            "os": std::env::consts::OS,
+            "arch": std::env::consts::ARCH,
+            "events": batch,
+        });
+
+        if cfg!(debug_assertions) {
+            log::info!("[Telemetry] Sending payload: {}", payload);
+        }
+
+        match send_with_retries(&client, &payload).await {
+            // Un lote rechazado se descarta para no bloquear la cola
+            SendOutcome::Sent | SendOutcome::Rejected => {}
+            SendOutcome::Failed => return,
+        }
+
+        if let Ok(mut state) = STATE.lock() {
+            let sent = batch.len().min(state.queue.len());
+            state.queue.drain(..sent);
+            persist(&state);
+        }
+    }
+}
+
+enum SendOutcome {
+    Sent,
+    Rejected,
+    Failed,
+}
+
+async fn send_with_retries(client: &Client, payload: &serde_json::Value) -> SendOutcome {
+    let endpoint = format!("{}/telemetry/events", API_ENDPOINT);
+
+    for attempt in 0..MAX_SEND_ATTEMPTS {
+        if attempt > 0 {
+            tokio::time::sleep(Duration::from_secs(2u64.pow(attempt))).await;
+        }
+
+        match client.post(&endpoint).json(payload).send().await {
+            Ok(resp) if resp.status().is_success() => return SendOutcome::Sent,
+            Ok(resp) if resp.status().is_client_error() => {
+                // El servidor no acepta el lote: reintentar no sirve de nada
+                log::warn!("[Telemetry] Batch rejected: {}", resp.status());
+                return SendOutcome::Rejected;
+            }
+            Ok(resp) => log::warn!("[Telemetry] Send failed: {}", resp.status()),
+            Err(e) => log::warn!("[Telemetry] Send failed: {}", e),
+        }
+    }
+    SendOutcome::Failed
+}
@@ -0,0 +1,136 @@
+//! Secure storage for auth tokens.
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use tauri_plugin_store::StoreExt;
+
+const KEYRING_SERVICE: &str = "dev.alexitoo.modpackstore";
+/// Sufijo de la clave del store donde se guarda la copia cifrada
+const ENCRYPTED_SUFFIX: &str = "_encrypted";
+const NONCE_LEN: usize = 12;
+
+fn keyring_entry(key: &str) -> Result<keyring::Entry, keyring::Error> {
+    keyring::Entry::new(KEYRING_SERVICE, key)
+}
+
+/// AES-256 key derived from the machine id, so a copied store is useless
+/// on another device.
+fn device_key() -> Result<[u8; 32], String> {
+    let machine_id = machine_uid::get()
+        .map_err(|e| format!("No se pudo obtener el identificador del equipo: {}", e))?;
+
+    let mut hasher = Sha256::new();
+    hasher.update(KEYRING_SERVICE.as_bytes());
+    hasher.update(machine_id.trim().as_bytes());
+    Ok(hasher.finalize().into())
+}
+
+/// Encrypts with the device key (nonce + ciphertext, base64).
+pub fn encrypt(plaintext: &str) -> Result<String, String> {
+    let cipher = Aes256Gcm::new_from_slice(&device_key()?).map_err(|e| e.to_string())?;
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext.as_bytes())
+        .map_err(|_| "Error al cifrar el secreto".to_string())?;
+
+    let mut blob = nonce.to_vec();
+    blob.extend_from_slice(&ciphertext);
+    Ok(BASE64.encode(blob))
+}
+
+pub fn decrypt(encoded: &str) -> Result<String, String> {
+    let blob = BASE64
+        .decode(encoded)
+        .map_err(|e| format!("Secreto cifrado corrupto: {}", e))?;
+    if blob.len() <= NONCE_LEN {
+        return Err("Secreto cifrado corrupto".to_string());
+    }
+
+    let (nonce, ciphertext) = blob.split_at(NONCE_LEN);
+    let cipher = Aes256Gcm::new_from_slice(&device_key()?).map_err(|e| e.to_string())?;
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "No se pudo descifrar el secreto (¿otro equipo?)".to_string())?;
+
+    String::from_utf8(plaintext).map_err(|e| e.to_string())
+}
+
+fn encrypted_key(key: &str) -> String {
+    format!("{}{}", key, ENCRYPTED_SUFFIX)
+}
+
+/// Stores `value` under `key`, preferring the OS keychain.
+pub fn save_secret(
+    app_handle: &tauri::AppHandle,
+    store_path: &str,
+    key: &str,
+    value: &str,
+) -> Result<(), String> {
+    let store = app_handle.store(store_path).map_err(|e| e.to_string())?;
+
+    match keyring_entry(key).and_then(|entry| entry.set_password(value)) {
+        Ok(()) => {
+            // Si antes usábamos el fallback, borrar la copia cifrada
+            if store.delete(encrypted_key(key)) {
+                store.save().map_err(|e| e.to_string())?;
+            }
+        }
+        Err(e) => {
+            log::warn!(
+                "[TokenStorage] Keychain unavailable ({}), using device-bound encryption",
+                e
+            );
+            store.set(encrypted_key(key), json!(encrypt(value)?));
+            store.save().map_err(|e| e.to_string())?;
+        }
+    }
+
+    store.close_resource();
+    Ok(())
+}
+
+/// Loads the secret stored under `key`, from the keychain or the encrypted
+/// fallback.
+pub fn load_secret(
+    app_handle: &tauri::AppHandle,
+    store_path: &str,
+    key: &str,
+) -> Result<Option<String>, String> {
+    match keyring_entry(key).and_then(|entry| entry.get_password()) {
+        Ok(value) => return Ok(Some(value)),
+        Err(keyring::Error::NoEntry) => {}
+        Err(e) => log::warn!("[TokenStorage] Could not read from keychain: {}", e),
+    }
+
+    let store = app_handle.store(store_path).map_err(|e| e.to_string())?;
+    let encrypted = store
+        .get(encrypted_key(key))
+        .and_then(|value| value.as_str().map(str::to_string));
+    store.close_resource();
+
+    encrypted.map(|value| decrypt(&value)).transpose()
+}
+
+/// Removes the secret from both the keychain and the encrypted fallback.
+pub fn delete_secret(
+    app_handle: &tauri::AppHandle,
+    store_path: &str,
+    key: &str,
+) -> Result<(), String> {
+    match keyring_entry(key).and_then(|entry| entry.delete_credential()) {
+        Ok(()) | Err(keyring::Error::NoEntry) => {}
+        Err(e) => log::warn!("[TokenStorage] Could not delete from keychain: {}", e),
+    }
+
+    let store = app_handle.store(store_path).map_err(|e| e.to_string())?;
+    if store.delete(encrypted_key(key)) {
+        store.save().map_err(|e| e.to_string())?;
+    }
+    store.close_resource();
+
+    Ok(())
+}
@@ -0,0 +1,324 @@
+//! Byte-level progress of the downloads of a task.
+
+use crate::core::bandwidth;
+use crate::core::events::{self, LauncherEvent};
+use crate::core::tasks_manager::TasksManager;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(500);
+/// Cada cuánto se emite `transfer-stats` mientras hay descargas
+const STATS_EVENT_INTERVAL: Duration = Duration::from_secs(1);
+/// Sin recibir bytes durante este tiempo la descarga se marca como atascada
+const STALLED_AFTER: Duration = Duration::from_secs(30);
+/// Peso de la última muestra en la media móvil de la velocidad
+const SPEED_SMOOTHING: f64 = 0.3;
+
+/// Progress of the file being downloaded right now.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileTransfer {
+    pub name: String,
+    pub downloaded_bytes: u64,
+    pub total_bytes: Option<u64>,
+}
+
+/// Snapshot published in `TaskInfo::transfer`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferStats {
+    /// Velocidad suavizada en bytes/s
+    pub bytes_per_second: u64,
+    /// Segundos restantes; `None` si no se conoce el total
+    pub eta_seconds: Option<u64>,
+    pub downloaded_bytes: u64,
+    /// Total esperado de la tarea; `None` si no se conoce
+    pub total_bytes: Option<u64>,
+    pub files_done: usize,
+    pub files_total: Option<usize>,
+    pub current_file: Option<FileTransfer>,
+}
+
+#[derive(Debug)]
+struct TrackerState {
+    stats: TransferStats,
+    speed: f64,
+    last_sample: Instant,
+    bytes_at_last_sample: u64,
+    last_activity: Instant,
+}
+
+/// Shared between the download threads of a task.
+pub struct TransferTracker {
+    task_id: String,
+    started_at: Instant,
+    task_manager: Arc<Mutex<TasksManager>>,
+    state: Mutex<TrackerState>,
+}
+
+impl TransferTracker {
+    /// Creates the tracker of `task_id` and registers it as active until
+    /// the last reference is dropped.
+    pub fn new(task_id: &str, task_manager: &Arc<Mutex<TasksManager>>) -> Arc<Self> {
+        let tracker = Arc::new(Self {
+            task_id: task_id.to_string(),
+            started_at: Instant::now(),
+            task_manager: Arc::clone(task_manager),
+            state: Mutex::new(TrackerState {
+                stats: TransferStats::default(),
+                speed: 0.0,
+                last_sample: Instant::now(),
+                bytes_at_last_sample: 0,
+                last_activity: Instant::now(),
+            }),
+        });
+        lock_active().insert(task_id.to_string(), Arc::downgrade(&tracker));
+        tracker
+    }
+
+    pub fn task_id(&self) -> &str {
+        &self.task_id
+    }
+
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, TrackerState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Declares the work ahead: file count and, if the manifest lists
+    /// sizes, the bytes still to download.
+    pub fn expect(&self, files: usize, bytes: Option<u64>) {
+        let mut state = self.lock_state();
+        let stats = &mut state.stats;
+        stats.files_total = Some(stats.files_done + files);
+        stats.total_bytes = bytes.map(|bytes| stats.downloaded_bytes + bytes);
+    }
+
+    pub fn start_file(&self, name: &str, total_bytes: Option<u64>) {
+        self.lock_state().stats.current_file = Some(FileTransfer {
+            name: name.to_string(),
+            downloaded_bytes: 0,
+            total_bytes,
+        });
+    }
+
+    /// Removes from the totals a file that didn't need downloading (e.g.
+    /// restored from the download cache).
+    pub fn skip_file(&self, bytes: u64) {
+        let mut state = self.lock_state();
+        let stats = &mut state.stats;
+        stats.files_total = stats.files_total.map(|total| total.saturating_sub(1));
+        stats.total_bytes = stats.total_bytes.map(|total| total.saturating_sub(bytes));
+    }
+
+    pub fn add_bytes(&self, bytes: u64) {
+        let snapshot = {
+            let mut state = self.lock_state();
+            state.last_activity = Instant::now();
+            state.stats.downloaded_bytes += bytes;
+            if let Some(file) = state.stats.current_file.as_mut() {
+                file.downloaded_bytes += bytes;
+            }
+            Self::sample(&mut state, false)
+        };
+        self.publish(snapshot);
+    }
+
+    pub fn finish_file(&self) {
+        let snapshot = {
+            let mut state = self.lock_state();
+            state.stats.files_done += 1;
+            state.stats.current_file = None;
+            Self::sample(&mut state, false)
+        };
+        self.publish(snapshot);
+    }
+
+    /// Recomputes speed and ETA; `None` if the last sample is too recent.
+    fn sample(state: &mut TrackerState, force: bool) -> Option<TransferStats> {
+        let elapsed = state.last_sample.elapsed();
+        if !force && elapsed < SAMPLE_INTERVAL {
+            return None;
+        }
+
+        let bytes = state.stats.downloaded_bytes - state.bytes_at_last_sample;
+        let instant_speed = bytes as f64 / elapsed.as_secs_f64().max(0.001);
+        state.speed = if state.speed == 0.0 {
+            instant_speed
+        } else {
+            SPEED_SMOOTHING * instant_speed + (1.0 - SPEED_SMOOTHING) * state.speed
+        };
+        state.last_sample = Instant::now();
+        state.bytes_at_last_sample = state.stats.downloaded_bytes;
+
+        let stats = &mut state.stats;
+        stats.bytes_per_second = state.speed.round() as u64;
+        let remaining = stats
+            .total_bytes
+            .map(|total| total.saturating_sub(stats.downloaded_bytes))
+            .or_else(|| {
+                let file = stats.current_file.as_ref()?;
+                Some(file.total_bytes?.saturating_sub(file.downloaded_bytes))
+            });
+        stats.eta_seconds = remaining
+            .filter(|_| stats.bytes_per_second > 0)
+            .map(|remaining| remaining.div_ceil(stats.bytes_per_second));
+
+        Some(stats.clone())
+    }
+
+    fn active_transfer(&self) -> ActiveTransfer {
+        let state = self.lock_state();
+        let idle = state.last_activity.elapsed();
+        let stats = &state.stats;
+        let current_file = stats.current_file.as_ref();
+
+        ActiveTransfer {
+            task_id: self.task_id.clone(),
+            file_name: current_file.map(|file| file.name.clone()),
+            file_downloaded_bytes: current_file.map_or(0, |file| file.downloaded_bytes),
+            file_total_bytes: current_file.and_then(|file| file.total_bytes),
+            // Sin archivo en curso no hay nada que pueda estar atascado
+            stalled: current_file.is_some() && idle >= STALLED_AFTER,
+            idle_seconds: idle.as_secs(),
+            elapsed_seconds: self.started_at.elapsed().as_secs(),
+            stats: stats.clone(),
+        }
+    }
+
+    fn publish(&self, snapshot: Option<TransferStats>) {
+        let Some(snapshot) = snapshot else {
+            return;
+        };
+        if let Ok(tm) = self.task_manager.lock() {
+            tm.update_transfer(&self.task_id, snapshot);
+        }
+    }
+
+    /// Publishes the final numbers once the downloads are over.
+    pub fn flush(&self) {
+        let snapshot = Self::sample(&mut self.lock_state(), true);
+        self.publish(snapshot);
+    }
+}
+
+/// Writer that reports every write to an optional tracker and keeps the
+/// downloads within the bandwidth limit.
+pub struct TrackedWriter<'a, W: Write> {
+    inner: W,
+    tracker: Option<&'a TransferTracker>,
+}
+
+impl<'a, W: Write> TrackedWriter<'a, W> {
+    pub fn new(inner: W, tracker: Option<&'a TransferTracker>) -> Self {
+        Self { inner, tracker }
+    }
+}
+
+impl<W: Write> Write for TrackedWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        if let Some(tracker) = self.tracker {
+            tracker.add_bytes(written as u64);
+        }
+        bandwidth::throttle(written);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl Drop for TransferTracker {
+    fn drop(&mut self) {
+        let mut active = lock_active();
+        // Un tracker nuevo de la misma tarea puede haber ocupado ya la entrada
+        if active
+            .get(&self.task_id)
+            .is_some_and(|tracker| tracker.strong_count() == 0)
+        {
+            active.remove(&self.task_id);
+        }
+    }
+}
+
+/// One in-flight download, as shown in the download manager.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveTransfer {
+    pub task_id: String,
+    /// Archivo en curso; `None` entre dos archivos
+    pub file_name: Option<String>,
+    pub file_downloaded_bytes: u64,
+    pub file_total_bytes: Option<u64>,
+    /// Lleva `STALLED_AFTER` sin recibir datos
+    pub stalled: bool,
+    /// Segundos desde el último byte recibido
+    pub idle_seconds: u64,
+    pub elapsed_seconds: u64,
+    /// Totales de la tarea
+    pub stats: TransferStats,
+}
+
+/// Payload of `get_active_transfers` and the `transfer-stats` event.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferSummary {
+    pub transfers: Vec<ActiveTransfer>,
+    /// Suma de la velocidad de todas las descargas
+    pub bytes_per_second: u64,
+    pub downloaded_bytes: u64,
+    pub stalled_count: usize,
+}
+
+static ACTIVE: Lazy<Mutex<HashMap<String, Weak<TransferTracker>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn lock_active() -> std::sync::MutexGuard<'static, HashMap<String, Weak<TransferTracker>>> {
+    ACTIVE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+pub fn active_transfers() -> TransferSummary {
+    let trackers: Vec<Arc<TransferTracker>> =
+        lock_active().values().filter_map(Weak::upgrade).collect();
+
+    let mut transfers: Vec<ActiveTransfer> = trackers
+        .iter()
+        .map(|tracker| tracker.active_transfer())
+        .collect();
+    transfers.sort_by_key(|transfer| std::cmp::Reverse(transfer.elapsed_seconds));
+
+    TransferSummary {
+        bytes_per_second: transfers.iter().map(|t| t.stats.bytes_per_second).sum(),
+        downloaded_bytes: transfers.iter().map(|t| t.stats.downloaded_bytes).sum(),
+        stalled_count: transfers.iter().filter(|t| t.stalled).count(),
+        transfers,
+    }
+}
+
+/// Spawns the loop behind `transfer-stats`: one event per second while
+/// something downloads, plus a final empty one when everything is done.
+pub fn start_stats_emitter() {
+    tauri::async_runtime::spawn(async move {
+        let mut was_active = false;
+        loop {
+            tokio::time::sleep(STATS_EVENT_INTERVAL).await;
+
+            let summary = active_transfers();
+            let active = !summary.transfers.is_empty();
+            if active || was_active {
+                events::emit(LauncherEvent::TransferStats(summary));
+            }
+            was_active = active;
+        }
+    });
+}
+
+#[tauri::command]
+pub fn get_active_transfers() -> TransferSummary {
+    active_transfers()
+}
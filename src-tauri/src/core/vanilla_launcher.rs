@@ -6,13 +6,14 @@ use std::{
     process::{Child, Command, Stdio},
 };
 
-use crate::config::get_config_manager;
+use crate::config::read_config;
 use crate::core::accounts_manager::AccountsManager;
+use crate::core::minecraft::heap_limits;
+use crate::core::minecraft::manifest::version_type;
+use crate::core::minecraft::paths;
+use crate::core::minecraft::{MavenCoordinate, RuleEvaluator};
 use crate::core::{minecraft_account::MinecraftAccount, minecraft_instance::MinecraftInstance};
 use crate::interfaces::game_launcher::GameLauncher;
-use uuid::Uuid;
-
-const CREATE_NO_WINDOW: u32 = 0x08000000;
 
 pub struct VanillaLauncher {
     instance: MinecraftInstance,
@@ -23,79 +24,6 @@ impl VanillaLauncher {
         Self { instance }
     }
 
-    // Checks if a rule should apply based on OS, arch, and feature requirements
-    pub fn should_apply_rule(
-        &self,
-        rule: &Value,
-        features: Option<&HashMap<String, bool>>,
-    ) -> bool {
-        let action = rule
-            .get("action")
-            .and_then(|a| a.as_str())
-            .unwrap_or("allow");
-        let mut should_apply = action == "allow";
-
-        // Check OS rules
-        if let Some(os_obj) = rule.get("os") {
-            let mut os_match = true;
-
-            // Check OS name
-            if let Some(os_name) = os_obj.get("name").and_then(|n| n.as_str()) {
-                let is_current_os = match os_name {
-                    "windows" => cfg!(windows),
-                    "osx" => cfg!(target_os = "macos"),
-                    "linux" => cfg!(target_os = "linux"),
-                    _ => false,
-                };
-                if !is_current_os {
-                    os_match = false;
-                }
-            }
-
-            // Check OS architecture
-            if let Some(os_arch) = os_obj.get("arch").and_then(|a| a.as_str()) {
-                let is_current_arch = match os_arch {
-                    "x86" => cfg!(target_arch = "x86"),
-                    "x86_64" => cfg!(target_arch = "x86_64"),
-                    "arm" => cfg!(target_arch = "arm"),
-                    "arm64" => cfg!(target_arch = "aarch64"),
-                    _ => false,
-                };
-                if !is_current_arch {
-                    os_match = false;
-                }
-            }
-
-            if action == "allow" {
-                should_apply = os_match;
-            } else {
-                should_apply = !os_match;
-            }
-        }
-
-        // Check feature rules
-        if let Some(feature_obj) = rule.get("features") {
-            if let Some(features_map) = features {
-                for (feature_name, feature_value) in
-                    feature_obj.as_object().unwrap_or(&serde_json::Map::new())
-                {
-                    if let Some(expected_value) = feature_value.as_bool() {
-                        let actual_value = *features_map.get(feature_name).unwrap_or(&false);
-                        if actual_value != expected_value {
-                            should_apply = action != "allow";
-                            break;
-                        }
-                    }
-                }
-            } else {
-                // If feature rules exist but no features are provided, rule doesn't apply
-                should_apply = action != "allow";
-            }
-        }
-
-        should_apply
-    }
-
     // Process values from a rule or argument
     fn process_rule_values(
         &self,
@@ -148,21 +76,9 @@ impl VanillaLauncher {
                 // If it's a complex rule-based argument
                 else if arg.is_object() {
                     // Check if rules allow this argument
-                    if let Some(rules) = arg.get("rules").and_then(|r| r.as_array()) {
-                        let mut should_include = false;
-
-                        for rule in rules {
-                            if self.should_apply_rule(rule, features) {
-                                should_include = true;
-                                break;
-                            }
-                        }
-
-                        if should_include {
-                            if let Some(value) = arg.get("value") {
-                                processed_args
-                                    .extend(self.process_rule_values(value, placeholders));
-                            }
+                    if RuleEvaluator::applies(arg, features) {
+                        if let Some(value) = arg.get("value") {
+                            processed_args.extend(self.process_rule_values(value, placeholders));
                         }
                     }
                 }
@@ -213,7 +129,10 @@ impl VanillaLauncher {
             }
             .to_string(),
         );
-        placeholders.insert("version_type".to_string(), "release".to_string());
+        placeholders.insert(
+            "version_type".to_string(),
+            version_type(manifest_json).to_string(),
+        );
         placeholders.insert(
             "natives_directory".to_string(),
             natives_dir.to_string_lossy().to_string(),
@@ -363,17 +282,7 @@ impl VanillaLauncher {
         if let Some(libs) = manifest_json.get("libraries").and_then(|v| v.as_array()) {
             for lib in libs {
                 // Check if this library has rules that might exclude it
-                let should_include = lib
-                    .get("rules")
-                    .and_then(|rules| rules.as_array())
-                    .map(|rules_arr| {
-                        rules_arr
-                            .iter()
-                            .any(|rule| self.should_apply_rule(rule, None))
-                    })
-                    .unwrap_or(true); // Default to include if no rules
-
-                if !should_include {
+                if !RuleEvaluator::applies(lib, None) {
                     continue;
                 }
 
@@ -384,8 +293,7 @@ impl VanillaLauncher {
                     .and_then(|artifact| artifact.get("path"))
                     .and_then(|path| path.as_str())
                 {
-                    let lib_path = libraries_dir
-                        .join(path.replace('/', &std::path::MAIN_SEPARATOR.to_string()));
+                    let lib_path = paths::join_relative(&libraries_dir, path);
                     if lib_path.exists() {
                         classpath.push(lib_path.to_string_lossy().to_string());
                     } else {
@@ -395,18 +303,8 @@ impl VanillaLauncher {
                 // Legacy format - construct path from name
                 else if let Some(name) = lib.get("name").and_then(|name| name.as_str()) {
                     // Parse Maven coordinates
-                    let parts: Vec<&str> = name.split(':').collect();
-                    if parts.len() >= 3 {
-                        let group_id =
-                            parts[0].replace('.', &std::path::MAIN_SEPARATOR.to_string());
-                        let artifact_id = parts[1];
-                        let version = parts[2];
-
-                        let lib_path = libraries_dir
-                            .join(group_id)
-                            .join(artifact_id)
-                            .join(version)
-                            .join(format!("{}-{}.jar", artifact_id, version));
+                    if let Some(coordinate) = MavenCoordinate::parse(name) {
+                        let lib_path = paths::join_relative(&libraries_dir, &coordinate.url_path());
 
                         if lib_path.exists() {
                             classpath.push(lib_path.to_string_lossy().to_string());
@@ -424,13 +322,15 @@ impl VanillaLauncher {
 
 impl GameLauncher for VanillaLauncher {
     fn launch(&self) -> Option<Child> {
-        let config_lock = get_config_manager()
-            .lock()
-            .expect("Failed to lock config manager mutex");
+        let config_lock = read_config();
 
-        let config = config_lock
-            .as_ref()
-            .expect("Config manager failed to initialize");
+        let config = match config_lock.as_ref() {
+            Ok(config) => config,
+            Err(e) => {
+                println!("Config manager failed to initialize: {}", e);
+                return None;
+            }
+        };
 
         let mc_memory = config.get_minecraft_memory().unwrap_or(2048); // Default to 2GB if not set
         println!("Minecraft memory: {}MB", mc_memory);
@@ -450,6 +350,7 @@ impl GameLauncher for VanillaLauncher {
         .join(if cfg!(windows) { "java.exe" } else { "java" });
 
         println!("Java path: {}", java_path.display());
+        let mc_memory = heap_limits::guard_heap(&self.instance.instanceId, mc_memory, &java_path);
 
         let accounts_manager = AccountsManager::new();
 
@@ -465,13 +366,10 @@ impl GameLauncher for VanillaLauncher {
         let account = match accounts_manager.get_minecraft_account_by_uuid(account_uuid) {
             Some(acct) => acct,
             None => {
+                // Sin caer en una cuenta offline aleatoria: InstanceLauncher
+                // valida la cuenta y decide si se juega sin conexión
                 println!("Account not found for UUID: {}", account_uuid);
-                MinecraftAccount::new(
-                    "offline".to_string(),
-                    Uuid::new_v4().to_string(),
-                    None,
-                    "offline".to_string(),
-                )
+                return None;
             }
         };
 
@@ -479,7 +377,7 @@ impl GameLauncher for VanillaLauncher {
 
         // Get game directory
         let game_dir = match &self.instance.instanceDirectory {
-            Some(dir) => PathBuf::from(dir).join("minecraft"),
+            Some(dir) => paths::normalize(&paths::native_path(dir).join("minecraft")),
             None => PathBuf::from("default_path").join("minecraft"),
         };
 
@@ -489,12 +387,12 @@ impl GameLauncher for VanillaLauncher {
 
         let minecraft_version = self.instance.minecraftVersion.clone();
 
-        let version_dir = game_dir.join("versions").join(&minecraft_version);
-        let client_jar = version_dir.join(format!("{}.jar", minecraft_version));
-        let natives_dir = game_dir.join("natives").join(&minecraft_version);
-        let libraries_dir = game_dir.join("libraries");
-        let assets_dir = game_dir.join("assets");
-        let manifest_file = version_dir.join(format!("{}.json", minecraft_version));
+        let version_dir = paths::version_dir(&game_dir, &minecraft_version);
+        let client_jar = paths::version_file(&game_dir, &minecraft_version, "jar");
+        let natives_dir = paths::extracted_natives_dir(&game_dir, &minecraft_version);
+        let libraries_dir = paths::libraries_dir(&game_dir);
+        let assets_dir = paths::assets_dir(&game_dir);
+        let manifest_file = paths::version_file(&game_dir, &minecraft_version, "json");
 
         //  Extracted from siglauncher code, maybe we can handle all vanilla and forge in a single way
 
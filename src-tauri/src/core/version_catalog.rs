@@ -0,0 +1,324 @@
+//! Minecraft and mod loader version lists for the instance creation form.
+
+use crate::core::http_client;
+use crate::core::mirrors;
+use crate::API_ENDPOINT;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const MOJANG_VERSION_MANIFEST_URL: &str =
+    "https://launchermeta.mojang.com/mc/game/version_manifest.json";
+const FORGE_PROMOTIONS_URL: &str =
+    "https://files.minecraftforge.net/net/minecraftforge/forge/promotions_slim.json";
+const FORGE_MAVEN_METADATA_URL: &str =
+    "https://files.minecraftforge.net/net/minecraftforge/forge/maven-metadata.json";
+const CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+static CACHE: Lazy<Mutex<HashMap<&'static str, (Value, Instant)>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MinecraftVersion {
+    pub id: String,
+    /// `release`, `snapshot`, `old_beta` u `old_alpha`
+    pub version_type: String,
+    pub release_time: String,
+    /// Última release o snapshot según el manifiesto
+    pub latest: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoaderVersion {
+    /// Versión tal y como se guarda en la instancia (p. ej. `47.3.0`)
+    pub version: String,
+    pub minecraft_version: String,
+    pub recommended: bool,
+    pub latest: bool,
+    /// Publicada como beta
+    pub beta: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoaderRecommendation {
+    pub loader: String,
+    pub minecraft_version: String,
+    /// Versión aconsejada para una instancia nueva
+    pub recommended: Option<String>,
+    /// De dónde sale `recommended`: `modpackStore`, `promotions`, `latest` o `beta`
+    pub source: Option<String>,
+    /// Última versión estable
+    pub latest: Option<String>,
+    /// Última beta, sólo si es más nueva que `latest`
+    pub latest_beta: Option<String>,
+    /// Aviso de Modpack Store para esta combinación (fallos conocidos...)
+    pub note: Option<String>,
+}
+
+/// Recommendation published by the Modpack Store API.
+#[derive(Debug, Deserialize)]
+struct LoaderHint {
+    version: Option<String>,
+    note: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Loader {
+    Forge,
+}
+
+impl Loader {
+    fn parse(loader: &str) -> Result<Self, String> {
+        match loader.to_lowercase().as_str() {
+            "forge" => Ok(Loader::Forge),
+            // Se listará cuando el launcher pueda instalarlo
+            "neoforge" => {
+                Err("NeoForge todavía no se puede instalar desde el launcher".to_string())
+            }
+            other => Err(format!("Loader no soportado: {}", other)),
+        }
+    }
+
+    fn id(self) -> &'static str {
+        match self {
+            Loader::Forge => "forge",
+        }
+    }
+}
+
+async fn fetch_json(url: &str) -> Result<Value, String> {
+    http_client::async_client()
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Error al consultar {}: {}", url, e))?
+        .error_for_status()
+        .map_err(|e| format!("Error al consultar {}: {}", url, e))?
+        .json::<Value>()
+        .await
+        .map_err(|e| format!("Respuesta inválida de {}: {}", url, e))
+}
+
+/// Downloads `url` as JSON, reusing the cached copy while it is fresh.
+/// A stale copy is still returned if the refresh fails.
+async fn fetch_cached(url: &'static str) -> Result<Value, String> {
+    let cached = CACHE.lock().ok().and_then(|cache| cache.get(url).cloned());
+
+    if let Some((value, fetched_at)) = &cached {
+        if fetched_at.elapsed() < CACHE_TTL {
+            return Ok(value.clone());
+        }
+    }
+
+    // Primero el mirror configurado, si lo hay; después el host oficial
+    let mirrored = mirrors::rewrite(url);
+    let fetched = match fetch_json(&mirrored).await {
+        Err(e) if mirrored != url => {
+            log::warn!("[Versions] {}; using the official host", e);
+            fetch_json(url).await
+        }
+        fetched => fetched,
+    };
+
+    match fetched {
+        Ok(value) => {
+            if let Ok(mut cache) = CACHE.lock() {
+                cache.insert(url, (value.clone(), Instant::now()));
+            }
+            Ok(value)
+        }
+        Err(e) => match cached {
+            Some((value, _)) => {
+                log::warn!("[Versions] {}; using cached copy", e);
+                Ok(value)
+            }
+            None => Err(e),
+        },
+    }
+}
+
+#[tauri::command]
+pub async fn get_minecraft_versions(
+    include_releases: Option<bool>,
+    include_snapshots: Option<bool>,
+    include_historical: Option<bool>,
+) -> Result<Vec<MinecraftVersion>, String> {
+    let include_releases = include_releases.unwrap_or(true);
+    let include_snapshots = include_snapshots.unwrap_or(false);
+    let include_historical = include_historical.unwrap_or(false);
+
+    let manifest = fetch_cached(MOJANG_VERSION_MANIFEST_URL).await?;
+    let latest_release = manifest["latest"]["release"].as_str();
+    let latest_snapshot = manifest["latest"]["snapshot"].as_str();
+
+    let versions = manifest["versions"]
+        .as_array()
+        .ok_or_else(|| "Invalid version manifest format".to_string())?;
+
+    Ok(versions
+        .iter()
+        .filter_map(|version| {
+            let id = version["id"].as_str()?;
+            let version_type = version["type"].as_str()?;
+            let wanted = match version_type {
+                "release" => include_releases,
+                "snapshot" => include_snapshots,
+                "old_beta" | "old_alpha" => include_historical,
+                _ => false,
+            };
+            if !wanted {
+                return None;
+            }
+
+            Some(MinecraftVersion {
+                id: id.to_string(),
+                version_type: version_type.to_string(),
+                release_time: version["releaseTime"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+                latest: Some(id) == latest_release || Some(id) == latest_snapshot,
+            })
+        })
+        .collect())
+}
+
+/// Loader versions for `mc_version`, newest first.
+#[tauri::command]
+pub async fn get_loader_versions(
+    mc_version: String,
+    loader: String,
+) -> Result<Vec<LoaderVersion>, String> {
+    loader_versions(Loader::parse(&loader)?, &mc_version).await
+}
+
+async fn loader_versions(loader: Loader, mc_version: &str) -> Result<Vec<LoaderVersion>, String> {
+    match loader {
+        Loader::Forge => get_forge_versions(mc_version).await,
+    }
+}
+
+async fn get_forge_versions(mc_version: &str) -> Result<Vec<LoaderVersion>, String> {
+    let metadata = fetch_cached(FORGE_MAVEN_METADATA_URL).await?;
+    // Las promociones son sólo marcadores; sin ellas la lista sigue siendo útil
+    let promotions = fetch_cached(FORGE_PROMOTIONS_URL)
+        .await
+        .unwrap_or_else(|e| {
+            log::warn!("[Versions] Forge promotions unavailable: {}", e);
+            Value::Null
+        });
+
+    let recommended = promotions["promos"][format!("{}-recommended", mc_version)].as_str();
+    let latest = promotions["promos"][format!("{}-latest", mc_version)].as_str();
+
+    let Some(builds) = metadata[mc_version].as_array() else {
+        return Ok(Vec::new());
+    };
+
+    // El metadata usa `<mc>-<forge>` (algunas versiones antiguas añaden `-<mc>`)
+    let prefix = format!("{}-", mc_version);
+    let mut versions: Vec<LoaderVersion> = builds
+        .iter()
+        .filter_map(Value::as_str)
+        .map(|full| {
+            let version = full.strip_prefix(&prefix).unwrap_or(full);
+            let version = version
+                .strip_suffix(&format!("-{}", mc_version))
+                .unwrap_or(version);
+            LoaderVersion {
+                version: version.to_string(),
+                minecraft_version: mc_version.to_string(),
+                recommended: Some(version) == recommended,
+                latest: Some(version) == latest,
+                beta: false,
+            }
+        })
+        .collect();
+
+    versions.reverse();
+    Ok(versions)
+}
+
+/// Modpack Store's pick for the loader, if it has one. Optional: any
+/// error just means there is no hint.
+async fn fetch_loader_hint(loader: Loader, mc_version: &str) -> Option<LoaderHint> {
+    // Endpoint público: no necesita sesión
+    let url = format!(
+        "{}/explore/loaders/{}/recommended?minecraftVersion={}",
+        API_ENDPOINT,
+        loader.id(),
+        mc_version
+    );
+    let response = http_client::async_client()
+        .get(&url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status());
+    match response {
+        Ok(response) => response.json::<LoaderHint>().await.ok(),
+        Err(e) => {
+            log::debug!(
+                "[Versions] No loader hint for {} {}: {}",
+                loader.id(),
+                mc_version,
+                e
+            );
+            None
+        }
+    }
+}
+
+/// Loader version to preselect when creating an instance: Modpack Store's
+/// hint, then the loader's own recommended build, then its latest stable.
+#[tauri::command]
+pub async fn get_recommended_loader_version(
+    mc_version: String,
+    loader: String,
+) -> Result<LoaderRecommendation, String> {
+    let loader = Loader::parse(&loader)?;
+    let versions = loader_versions(loader, &mc_version).await?;
+    let hint = fetch_loader_hint(loader, &mc_version).await;
+
+    let latest = versions
+        .iter()
+        .find(|version| version.latest)
+        .or_else(|| versions.iter().find(|version| !version.beta));
+    // La lista va de la más nueva a la más antigua
+    let latest_beta = versions
+        .iter()
+        .take_while(|version| !latest.is_some_and(|latest| latest.version == version.version))
+        .find(|version| version.beta);
+
+    let hinted = hint
+        .as_ref()
+        .and_then(|hint| hint.version.as_deref())
+        .filter(|hinted| versions.iter().any(|version| version.version == *hinted));
+    let (recommended, source) = if let Some(hinted) = hinted {
+        (Some(hinted.to_string()), Some("modpackStore"))
+    } else if let Some(promoted) = versions.iter().find(|version| version.recommended) {
+        (Some(promoted.version.clone()), Some("promotions"))
+    } else if let Some(latest) = latest {
+        (Some(latest.version.clone()), Some("latest"))
+    } else if let Some(beta) = latest_beta {
+        // Versión de Minecraft tan nueva que sólo hay betas
+        (Some(beta.version.clone()), Some("beta"))
+    } else {
+        (None, None)
+    };
+
+    Ok(LoaderRecommendation {
+        loader: loader.id().to_string(),
+        minecraft_version: mc_version,
+        recommended,
+        source: source.map(str::to_string),
+        latest: latest.map(|version| version.version.clone()),
+        latest_beta: latest_beta.map(|version| version.version.clone()),
+        note: hint.and_then(|hint| hint.note),
+    })
+}
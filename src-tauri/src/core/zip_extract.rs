@@ -0,0 +1,189 @@
+//! Shared zip extraction.
+
+use crate::core::i18n::Message;
+use crate::core::tasks_manager::{CancellationToken, TaskStatus, TasksManager};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+type EntryMapper<'a> = Box<dyn FnMut(&Path) -> Option<PathBuf> + 'a>;
+
+struct TaskProgress {
+    task_id: String,
+    manager: Arc<Mutex<TasksManager>>,
+    message: Message,
+    data: Option<serde_json::Value>,
+}
+
+pub struct ZipExtractor<'a> {
+    archive_path: PathBuf,
+    target_dir: PathBuf,
+    mapper: Option<EntryMapper<'a>>,
+    cancel: Option<CancellationToken>,
+    progress: Option<TaskProgress>,
+    span: (f32, f32),
+}
+
+impl<'a> ZipExtractor<'a> {
+    pub fn new(archive_path: &Path, target_dir: &Path) -> Self {
+        Self {
+            archive_path: archive_path.to_path_buf(),
+            target_dir: target_dir.to_path_buf(),
+            mapper: None,
+            cancel: None,
+            progress: None,
+            span: (0.0, 100.0),
+        }
+    }
+
+    /// Chooses where each entry goes, relative to the target directory.
+    /// Receives the entry's sanitized path; `None` skips the entry.
+    pub fn map_entries(mut self, mapper: impl FnMut(&Path) -> Option<PathBuf> + 'a) -> Self {
+        self.mapper = Some(Box::new(mapper));
+        self
+    }
+
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    /// Reports progress to the task while extracting. A keyed `message`
+    /// gets the `done`, `total` and `percent` params; plain text gets
+    /// ` (n/total)` appended.
+    pub fn report_to(
+        mut self,
+        task_id: &str,
+        manager: &Arc<Mutex<TasksManager>>,
+        message: impl Into<Message>,
+        data: Option<serde_json::Value>,
+    ) -> Self {
+        self.progress = Some(TaskProgress {
+            task_id: task_id.to_string(),
+            manager: Arc::clone(manager),
+            message: message.into(),
+            data,
+        });
+        self
+    }
+
+    /// Maps the extraction onto `from..to` of the task's progress, for
+    /// when it is one step of a longer task.
+    pub fn progress_span(mut self, from: f32, to: f32) -> Self {
+        self.span = (from, to);
+        self
+    }
+
+    fn report(&self, done: usize, total: usize, last_percent: &mut Option<u32>) {
+        let Some(progress) = &self.progress else {
+            return;
+        };
+        // Un archivo con miles de entradas no debe emitir miles de eventos
+        let percent = (done * 100 / total.max(1)) as u32;
+        if *last_percent == Some(percent) {
+            return;
+        }
+        *last_percent = Some(percent);
+
+        let mut message = progress.message.clone().progress(done, total);
+        if message.key.is_none() {
+            message.text = format!("{} ({}/{})", message.text, done, total);
+        }
+
+        let (from, to) = self.span;
+        if let Ok(tm) = progress.manager.lock() {
+            tm.update_task(
+                &progress.task_id,
+                TaskStatus::Running,
+                from + (to - from) * percent as f32 / 100.0,
+                message,
+                progress.data.clone(),
+            );
+        }
+    }
+
+    /// Extracts the archive. Returns the number of files written.
+    pub fn extract(mut self) -> Result<usize, String> {
+        let file = fs::File::open(&self.archive_path)
+            .map_err(|e| format!("Error al abrir {}: {}", self.archive_path.display(), e))?;
+        let mut archive = zip::ZipArchive::new(io::BufReader::new(file)).map_err(|e| {
+            format!(
+                "Archivo ZIP inválido {}: {}",
+                self.archive_path.display(),
+                e
+            )
+        })?;
+
+        let total = archive.len();
+        let mut written = 0;
+        let mut last_percent = None;
+
+        for i in 0..total {
+            if self
+                .cancel
+                .as_ref()
+                .is_some_and(|token| token.is_cancelled())
+            {
+                return Err("Operación cancelada".to_string());
+            }
+
+            let mut entry = archive
+                .by_index(i)
+                .map_err(|e| format!("Error al leer entrada ZIP: {}", e))?;
+
+            let Some(name) = entry.enclosed_name() else {
+                log::warn!(
+                    "[Zip] Skipping unsafe entry {} in {}",
+                    entry.name(),
+                    self.archive_path.display()
+                );
+                continue;
+            };
+
+            let relative = match self.mapper.as_mut() {
+                Some(mapper) => mapper(&name),
+                None => Some(name),
+            };
+            // Lo que devuelve el mapper tampoco puede salir del destino
+            let Some(relative) = relative.filter(|path| is_safe_relative(path)) else {
+                self.report(i + 1, total, &mut last_percent);
+                continue;
+            };
+            let output_path = self.target_dir.join(relative);
+
+            if entry.is_dir() {
+                fs::create_dir_all(&output_path)
+                    .map_err(|e| format!("Error al crear directorio: {}", e))?;
+            } else {
+                if let Some(parent) = output_path.parent() {
+                    fs::create_dir_all(parent)
+                        .map_err(|e| format!("Error al crear directorio: {}", e))?;
+                }
+                let mut output = fs::File::create(&output_path)
+                    .map_err(|e| format!("Error al extraer {}: {}", output_path.display(), e))?;
+                io::copy(&mut entry, &mut output)
+                    .map_err(|e| format!("Error al extraer {}: {}", output_path.display(), e))?;
+
+                // Sólo el bit de ejecución: los ZIP creados en Windows no
+                // traen permisos y no deben acabar como 000
+                #[cfg(unix)]
+                if entry.unix_mode().unwrap_or(0) & 0o111 != 0 {
+                    use std::os::unix::fs::PermissionsExt;
+                    let _ = fs::set_permissions(&output_path, fs::Permissions::from_mode(0o755));
+                }
+
+                written += 1;
+            }
+
+            self.report(i + 1, total, &mut last_percent);
+        }
+
+        Ok(written)
+    }
+}
+
+fn is_safe_relative(path: &Path) -> bool {
+    path.components()
+        .all(|component| matches!(component, std::path::Component::Normal(_)))
+}
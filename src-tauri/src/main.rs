@@ -9,20 +9,15 @@
 mod config;
 mod core;
 mod interfaces;
-mod utils;
 
 use core::auth::*;
 use serde_json::json;
 use std::sync::Arc;
-use tauri::Emitter;
 use tauri::Manager; // Necesario para get_window y emit
 use tauri::Wry;
 use tauri_plugin_log::{Target, TargetKind};
 use tauri_plugin_store::StoreExt;
 
-static GLOBAL_APP_HANDLE: once_cell::sync::Lazy<std::sync::Mutex<Option<tauri::AppHandle>>> =
-    once_cell::sync::Lazy::new(|| std::sync::Mutex::new(None));
-
 static API_ENDPOINT: &str = "https://api-modpackstore.alexitoo.dev/v1";
 
 pub fn main() {
@@ -73,44 +68,130 @@ pub fn main() {
                 std::env::consts::ARCH
             );
 
-            // Store the AppHandle in the static variable
-            let mut app_handle = GLOBAL_APP_HANDLE.lock().unwrap();
-            *app_handle = Some(app.handle().clone());
+            core::events::EventEmitter::init(app.handle().clone());
+            core::http_client::init();
+            core::mirrors::init();
+            core::bandwidth::init();
+            core::tasks_manager::mark_interrupted_tasks();
+            core::accounts_manager::migrate_offline_accounts();
+
+            // Vigilar la sesión de Modpack Store en segundo plano
+            core::session_keeper::start(app.handle().clone());
+            core::launcher_updater::start(app.handle().clone());
+            core::launcher_changelog::spawn_prefetch(app.handle().clone());
+            core::telemetry::start();
+            core::java_manager::spawn_auto_cleanup();
+            core::hardware_info::spawn_detection();
+            core::network_monitor::start();
+            core::transfer_stats::start_stats_emitter();
+
             // Emit an event to the main window
-            main_window.emit("app-ready", ()).unwrap();
+            core::events::emit(core::events::LauncherEvent::AppReady);
 
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             config::get_config,
             config::get_schema,
+            config::get_settings_layout,
+            config::bundle::export_settings_bundle,
+            config::bundle::import_settings_bundle,
             config::set_config,
+            core::launcher_updater::check_launcher_update,
+            core::launcher_updater::defer_update,
+            core::launcher_updater::install_launcher_update,
+            core::launcher_changelog::get_launcher_changelog,
+            core::launcher_changelog::mark_changelog_seen,
+            core::game_language::get_ui_locale,
+            core::download_cache::clear_download_cache,
+            core::storage_cleanup::analyze_storage,
+            core::storage_cleanup::clean_storage,
+            core::tasks_manager::cancel_task,
+            core::tasks_manager::get_active_tasks,
+            core::tasks_manager::get_task,
+            core::tasks_manager::get_recent_task_history,
+            core::transfer_stats::get_active_transfers,
             core::network_utilities::check_connection,
             core::network_utilities::check_real_connection,
+            core::network_utilities::check_connection_details,
+            core::mirrors::check_mirrors,
+            core::network_monitor::get_network_status,
             core::instance_manager::get_all_instances,
+            core::instance_schema::get_instance_validation_reports,
             core::instance_manager::get_instance_by_id,
+            core::instance_state::get_instance_state,
             core::instance_manager::delete_instance,
-            //utils::config_manager::get_config,
             core::instance_manager::launch_mc_instance,
+            core::launch_queue::cancel_queued_launch,
+            core::startup_watchdog::restart_stalled_instance,
             core::minecraft_instance::open_game_dir,
+            core::minecraft_instance::open_instance_subfolder,
+            core::instance_disk_usage::get_instance_disk_usage,
             core::instance_manager::update_instance,
             core::instance_manager::update_modpack_instance,
+            core::modpack_updates::set_instance_update_channel,
+            core::modpack_updates::pin_instance_version,
+            core::modpack_updates::check_instance_update,
+            core::modpack_updates::preview_modpack_update,
+            core::instance_readme::get_instance_readme,
+            core::optional_groups::get_modpack_optional_groups,
+            core::optional_groups::set_instance_optional_groups,
+            core::optifine::install_optifine,
+            core::custom_version::import_custom_version,
+            core::custom_version::clear_custom_version,
+            core::launcher_profiles::repair_launcher_profiles,
+            core::file_rules::get_instance_file_rules,
+            core::file_rules::add_instance_file_rule,
+            core::file_rules::remove_instance_file_rule,
+            core::server_pack::export_server_pack,
+            core::java_manager::cleanup_unused_javas,
+            core::jvm_crash::get_instance_jvm_crashes,
+            core::session_logs::get_instance_session_logs,
             core::instance_manager::create_local_instance,
+            core::instance_manager::get_instance_roots,
+            core::instance_import::detect_external_instances,
+            core::instance_import::inspect_external_instance,
+            core::instance_import::import_external_instance,
+            core::onboarding::get_onboarding_state,
+            core::onboarding::complete_onboarding_step,
             core::instance_manager::search_instances,
             core::instance_manager::remove_instance,
+            core::version_catalog::get_minecraft_versions,
+            core::version_catalog::get_loader_versions,
+            core::version_catalog::get_recommended_loader_version,
+            core::instance_images::set_instance_image,
+            core::instance_images::clear_instance_image,
+            core::instance_manager::get_interrupted_tasks,
+            core::instance_manager::resume_interrupted_tasks,
+            core::instance_manager::discard_interrupted_task,
             core::instance_bootstrap::check_vanilla_integrity,
             core::instance_bootstrap::validate_modpack_assets,
             core::accounts_manager::get_all_accounts,
             core::accounts_manager::add_offline_account,
             core::accounts_manager::ensure_account_exists,
             core::accounts_manager::remove_account,
+            core::account_avatar::get_account_avatar,
             core::minecraft_instance::get_instances_by_modpack_id,
             core::auth::start_discord_auth,
             core::auth::get_current_session,
             core::auth::logout,
             core::auth::init_session,
+            core::auth::list_sessions,
+            core::auth::switch_session,
             core::microsoft_auth::start_microsoft_auth,
             core::prelaunch_appearance::get_prelaunch_appearance,
+            core::prelaunch_appearance::hot_reload::watch_prelaunch_appearance,
+            core::prelaunch_appearance::hot_reload::unwatch_prelaunch_appearance,
+            core::prelaunch_appearance::accept_instance_notice,
+            core::server_ping::ping_minecraft_server,
+            core::system_resources::get_recommended_memory,
+            core::preflight::preflight_check,
+            core::preflight::get_manifest_merge_report,
+            core::launch_plan::get_launch_plan,
+            core::hardware_info::get_hardware_info,
+            core::i18n::get_message_catalog,
+            core::mod_blocklist::scan_instances_for_blocked_files,
+            core::process_registry::get_running_instances,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -1 +0,0 @@
-pub mod config_manager;